@@ -38,7 +38,7 @@ async function setupMocks(page: Page, opts?: { withAudioPool?: boolean }) {
           case 'load_project_banks':
             return []
           case 'load_project_metadata':
-            return {
+            return { data: {
               name: 'TestProject',
               tempo: 120.0,
               time_signature: '4/4',
@@ -78,7 +78,7 @@ async function setupMocks(page: Page, opts?: { withAudioPool?: boolean }) {
                   file_exists: false, compatibility: null, file_format: null, bit_depth: null, sample_rate: null,
                 })),
               },
-            }
+            }, warnings: [] }
           case 'reset_slot_attributes':
           case 'clear_sample_slots':
           case 'clear_sample_keep_attributes':
@@ -30,7 +30,7 @@ async function setupMocks(page: Page, options: { withAudioPool?: boolean; flexSl
       invoke: async (cmd: string, args?: any) => {
         switch (cmd) {
           case 'load_project_metadata':
-            return {
+            return { data: {
               name: 'TestProject',
               tempo: 120.0,
               time_signature: '4/4',
@@ -80,7 +80,7 @@ async function setupMocks(page: Page, options: { withAudioPool?: boolean; flexSl
                   bit_depth: null, sample_rate: null,
                 }),
               },
-            }
+            }, warnings: [] }
 
           case 'load_project_banks':
             return Array(16).fill(null).map((_, i) => ({
@@ -98,7 +98,7 @@ async function setupTauriMocks(page: Page, options?: MockOptions) {
         invokeCalls.push({ cmd, args })
         switch (cmd) {
           case 'load_project_metadata':
-            return {
+            return { data: {
               name: 'TestProject',
               tempo: 120.0,
               time_signature: '4/4',
@@ -118,7 +118,7 @@ async function setupTauriMocks(page: Page, options?: MockOptions) {
                 flex_slots: Array(128).fill(null).map((_, i) => ({ slot_id: i, slot_type: 'Flex', path: null, gain: null, loop_mode: null, timestretch_mode: null, source_location: null, file_exists: false, compatibility: null, file_format: null, bit_depth: null, sample_rate: null })),
                 static_slots: Array(128).fill(null).map((_, i) => ({ slot_id: i, slot_type: 'Static', path: null, gain: null, loop_mode: null, timestretch_mode: null, source_location: null, file_exists: false, compatibility: null, file_format: null, bit_depth: null, sample_rate: null })),
               },
-            }
+            }, warnings: [] }
 
           case 'get_existing_banks':
             return [0, 1]
@@ -16,7 +16,7 @@ async function setupMocks(page: Page) {
       invoke: async (cmd: string) => {
         switch (cmd) {
           case 'load_project_metadata':
-            return {
+            return { data: {
               name: 'TestProject',
               tempo: 120.0,
               time_signature: '4/4',
@@ -46,7 +46,7 @@ async function setupMocks(page: Page) {
                   compatibility: null, file_format: null, bit_depth: null, sample_rate: null,
                 })),
               },
-            }
+            }, warnings: [] }
           case 'compute_sample_usage': {
             ;(window as any).__usageComputedAt__ = Date.now()
             const flex = emptyUsage()
@@ -20,7 +20,7 @@ async function setupTauriMocks(page: Page, overrides?: { sameSet?: boolean; with
 
         switch (cmd) {
           case 'load_project_metadata':
-            return {
+            return { data: {
               name: 'TestProject',
               tempo: 120.0,
               time_signature: '4/4',
@@ -111,7 +111,7 @@ async function setupTauriMocks(page: Page, overrides?: { sameSet?: boolean; with
                   sample_rate: null,
                 })),
               },
-            }
+            }, warnings: [] }
 
           case 'load_project_banks':
             return Array(16).fill(null).map((_, i) => ({
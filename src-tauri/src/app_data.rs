@@ -0,0 +1,90 @@
+// Files the app keeps for itself, outside any project: where they live, how
+// JSON stores are saved and the temp folders operations stage files in.
+//
+// Stores sit in `<data dir>/octatrack-manager/` (e.g. ~/.local/share on
+// Linux, ~/Library/Application Support on macOS, %APPDATA% on Windows) and
+// are replaced atomically, so a crash mid-save keeps the previous version.
+
+use crate::atomic_write::write_atomic;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// `<data dir>/octatrack-manager/<name>`.
+pub fn data_path(name: &str) -> Result<PathBuf, String> {
+    dirs::data_dir()
+        .map(|d| d.join("octatrack-manager").join(name))
+        .ok_or_else(|| "Could not determine data directory".to_string())
+}
+
+/// Write `value` as pretty JSON to `path`, creating its directory. `what`
+/// names the store in errors ("notes", "presets"...).
+pub fn save_json<T: Serialize>(path: &Path, value: &T, what: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {} directory: {}", what, e))?;
+    }
+    let data = serde_json::to_string_pretty(value)
+        .map_err(|e| format!("Failed to serialize {}: {}", what, e))?;
+    write_atomic(path, data.as_bytes()).map_err(|e| format!("Failed to write {}: {}", what, e))
+}
+
+/// Temp folder removed on drop.
+pub(crate) struct StagingDir(pub(crate) PathBuf);
+
+impl Drop for StagingDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// A new, empty staging folder under `root`, named by process and time so
+/// concurrent operations never share one.
+pub(crate) fn staging_dir(root: &Path) -> Result<StagingDir, String> {
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S-%f");
+    let dir = root.join(format!("{}-{}", std::process::id(), stamp));
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create staging directory: {}", e))?;
+    Ok(StagingDir(dir))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_save_json_creates_directory_and_replaces_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("nested").join("store.json");
+        let mut store = BTreeMap::from([("a", 1)]);
+        save_json(&path, &store, "store").unwrap();
+        store.insert("b", 2);
+        save_json(&path, &store, "store").unwrap();
+
+        let saved: BTreeMap<String, i32> =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved.len(), 2);
+        assert_eq!(fs::read_dir(path.parent().unwrap()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_data_path_is_under_the_app_folder() {
+        if let Ok(path) = data_path("notes.json") {
+            assert!(path.ends_with("octatrack-manager/notes.json"));
+        }
+    }
+
+    #[test]
+    fn test_staging_dirs_are_distinct_and_removed_on_drop() {
+        let root = TempDir::new().unwrap();
+        let a = staging_dir(root.path()).unwrap();
+        let b = staging_dir(root.path()).unwrap();
+        assert_ne!(a.0, b.0);
+        assert!(a.0.is_dir());
+        let path = a.0.clone();
+        drop(a);
+        assert!(!path.exists());
+        assert!(b.0.is_dir());
+    }
+}
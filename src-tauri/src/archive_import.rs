@@ -0,0 +1,471 @@
+// Import of sample packs shipped as archives (.zip, .tar, .tar.gz, .7z).
+// Audio entries are unpacked into a temporary staging folder, then go
+// through the batch conversion pipeline into the target pool folder, keeping
+// the archive's folder layout. Everything else in the archive is left out and
+// listed in the report.
+
+use crate::app_data::staging_dir;
+use crate::audio_pool::{
+    is_audio_file, is_cancelled, register_cancellation_token, remove_cancellation_token,
+    ConversionOptions,
+};
+use crate::batch_convert::{convert_directory_sync, BatchConvertFileResult, BatchConvertOptions};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{BufReader, Read};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::Instant;
+use tauri::{AppHandle, Emitter};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SamplePackImportOptions {
+    #[serde(default)]
+    pub overwrite: bool, // replace files already present in dest (otherwise skipped)
+    #[serde(default)]
+    pub transfer_id: Option<String>, // cancellable via cancel_audio_transfer
+    #[serde(default)]
+    pub conversion: ConversionOptions,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SamplePackImportProgress {
+    pub transfer_id: Option<String>,
+    pub stage: String, // "extracting" or "converting"
+    pub files_done: usize,
+    pub files_total: Option<usize>, // unknown while extracting
+    pub bytes_done: u64,            // source bytes converted so far (0 while extracting)
+    pub bytes_total: u64,
+    pub current_file: Option<String>, // path inside the archive
+    pub eta_seconds: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SamplePackImportReport {
+    pub archive: String,
+    pub dest: String,
+    pub files: Vec<BatchConvertFileResult>, // source is the path inside the archive
+    pub skipped_non_audio: Vec<String>,
+    pub converted: usize,
+    pub copied: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    pub cancelled: bool,
+    pub elapsed_seconds: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ArchiveFormat {
+    Zip,
+    Tar,
+    TarGz,
+    SevenZip,
+}
+
+fn archive_format(path: &Path) -> Option<ArchiveFormat> {
+    let name = path.file_name()?.to_string_lossy().to_lowercase();
+    if name.ends_with(".zip") {
+        Some(ArchiveFormat::Zip)
+    } else if name.ends_with(".tar") {
+        Some(ArchiveFormat::Tar)
+    } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        Some(ArchiveFormat::TarGz)
+    } else if name.ends_with(".7z") {
+        Some(ArchiveFormat::SevenZip)
+    } else {
+        None
+    }
+}
+
+/// `name` as a relative path inside the staging folder, or None when it would
+/// escape it (../, absolute or drive paths).
+fn safe_relative(name: &str) -> Option<PathBuf> {
+    let mut out = PathBuf::new();
+    for component in Path::new(&name.replace('\\', "/")).components() {
+        match component {
+            Component::Normal(part) => out.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    (!out.as_os_str().is_empty()).then_some(out)
+}
+
+/// Finder and resource-fork leftovers that macOS adds to archives.
+fn is_os_metadata(path: &Path) -> bool {
+    path.components().any(|c| c.as_os_str() == "__MACOSX")
+        || path.file_name().is_some_and(|n| {
+            let n = n.to_string_lossy();
+            n.starts_with("._") || n == ".DS_Store"
+        })
+}
+
+/// Parent of the staging folders; maintenance clears what a crash left there.
+pub(crate) fn staging_root() -> PathBuf {
+    std::env::temp_dir().join("octatrack-manager-import")
+}
+
+/// Writes the audio entries of an archive into the staging folder.
+struct Extractor<'a> {
+    staging: &'a Path,
+    cancel_token: Option<Arc<AtomicBool>>,
+    skipped_non_audio: Vec<String>,
+    extracted: usize,
+    on_entry: &'a dyn Fn(usize, &str),
+}
+
+impl Extractor<'_> {
+    fn cancelled(&self) -> bool {
+        self.cancel_token.as_ref().is_some_and(is_cancelled)
+    }
+
+    /// Stage one entry; `size` is its unpacked size as the archive declares it.
+    fn entry(
+        &mut self,
+        name: &str,
+        is_dir: bool,
+        size: u64,
+        reader: &mut dyn Read,
+    ) -> Result<(), String> {
+        if is_dir {
+            return Ok(());
+        }
+        let relative =
+            safe_relative(name).ok_or_else(|| format!("Unsafe path in archive: {}", name))?;
+        if is_os_metadata(&relative) {
+            return Ok(());
+        }
+        // .ot files are staged too: they are carried along with their sample
+        let is_ot = relative
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("ot"));
+        if !is_audio_file(name) && !is_ot {
+            self.skipped_non_audio.push(name.to_string());
+            return Ok(());
+        }
+        (self.on_entry)(self.extracted, name);
+        let target = self.staging.join(&relative);
+        // Staging lives in the temp dir, often on a smaller system volume
+        crate::disk_space::ensure_free_space(self.staging, size)?;
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let mut out = File::create(&target)
+            .map_err(|e| format!("Failed to create {}: {}", target.display(), e))?;
+        std::io::copy(reader, &mut out)
+            .map_err(|e| format!("Failed to extract {}: {}", name, e))?;
+        self.extracted += 1;
+        Ok(())
+    }
+}
+
+fn extract_zip(archive: &Path, extractor: &mut Extractor) -> Result<(), String> {
+    let file =
+        File::open(archive).map_err(|e| format!("Failed to open {}: {}", archive.display(), e))?;
+    let mut zip = zip::ZipArchive::new(BufReader::new(file))
+        .map_err(|e| format!("Not a zip archive: {}", e))?;
+    for i in 0..zip.len() {
+        if extractor.cancelled() {
+            return Ok(());
+        }
+        let mut entry = zip
+            .by_index(i)
+            .map_err(|e| format!("Failed to read archive: {}", e))?;
+        let name = entry.name().to_string();
+        let is_dir = entry.is_dir();
+        let size = entry.size();
+        extractor.entry(&name, is_dir, size, &mut entry)?;
+    }
+    Ok(())
+}
+
+fn extract_tar<R: Read>(reader: R, extractor: &mut Extractor) -> Result<(), String> {
+    let mut tar = tar::Archive::new(reader);
+    let entries = tar
+        .entries()
+        .map_err(|e| format!("Not a tar archive: {}", e))?;
+    for entry in entries {
+        if extractor.cancelled() {
+            return Ok(());
+        }
+        let mut entry = entry.map_err(|e| format!("Failed to read archive: {}", e))?;
+        let entry_type = entry.header().entry_type();
+        // Links and special files are never audio content
+        if !entry_type.is_file() && !entry_type.is_dir() {
+            continue;
+        }
+        let name = entry
+            .path()
+            .map_err(|e| format!("Invalid path in archive: {}", e))?
+            .to_string_lossy()
+            .to_string();
+        let size = entry.size();
+        extractor.entry(&name, entry_type.is_dir(), size, &mut entry)?;
+    }
+    Ok(())
+}
+
+fn extract_7z(archive: &Path, extractor: &mut Extractor) -> Result<(), String> {
+    let mut reader = sevenz_rust::SevenZReader::open(archive, sevenz_rust::Password::empty())
+        .map_err(|e| format!("Not a 7z archive: {}", e))?;
+    let mut failure = None;
+    reader
+        .for_each_entries(|entry, data| {
+            if extractor.cancelled() {
+                return Ok(false);
+            }
+            if let Err(e) = extractor.entry(entry.name(), entry.is_directory(), entry.size(), data)
+            {
+                failure = Some(e);
+                return Ok(false);
+            }
+            Ok(true)
+        })
+        .map_err(|e| format!("Failed to read archive: {}", e))?;
+    failure.map_or(Ok(()), Err)
+}
+
+fn source_in_archive(staged: &str, staging: &Path) -> String {
+    Path::new(staged)
+        .strip_prefix(staging)
+        .map(|p| p.to_string_lossy().replace('\\', "/"))
+        .unwrap_or_else(|_| staged.to_string())
+}
+
+/// Unpack the audio in `archive` into `dest`, converting it for the Octatrack.
+pub fn import_archive_sync(
+    archive: &Path,
+    dest: &Path,
+    options: &SamplePackImportOptions,
+    cancel_token: Option<Arc<AtomicBool>>,
+    on_progress: &dyn Fn(&SamplePackImportProgress),
+) -> Result<SamplePackImportReport, String> {
+    let format = archive_format(archive)
+        .ok_or_else(|| format!("Unsupported archive: {}", archive.display()))?;
+    if !archive.is_file() {
+        return Err(format!("Archive not found: {}", archive.display()));
+    }
+    let started = Instant::now();
+
+    let staging = staging_dir(&staging_root())?;
+    // The staging path is resolved so batch conversion results can be mapped back
+    let staging_path = staging
+        .0
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve staging directory: {}", e))?;
+    let on_entry = |done: usize, name: &str| {
+        on_progress(&SamplePackImportProgress {
+            transfer_id: options.transfer_id.clone(),
+            stage: "extracting".to_string(),
+            files_done: done,
+            files_total: None,
+            bytes_done: 0,
+            bytes_total: 0,
+            current_file: Some(name.to_string()),
+            eta_seconds: None,
+        });
+    };
+    let mut extractor = Extractor {
+        staging: &staging_path,
+        cancel_token: cancel_token.clone(),
+        skipped_non_audio: Vec::new(),
+        extracted: 0,
+        on_entry: &on_entry,
+    };
+    match format {
+        ArchiveFormat::Zip => extract_zip(archive, &mut extractor)?,
+        ArchiveFormat::Tar => {
+            let file = File::open(archive)
+                .map_err(|e| format!("Failed to open {}: {}", archive.display(), e))?;
+            extract_tar(BufReader::new(file), &mut extractor)?
+        }
+        ArchiveFormat::TarGz => {
+            let file = File::open(archive)
+                .map_err(|e| format!("Failed to open {}: {}", archive.display(), e))?;
+            extract_tar(
+                flate2::read::GzDecoder::new(BufReader::new(file)),
+                &mut extractor,
+            )?
+        }
+        ArchiveFormat::SevenZip => extract_7z(archive, &mut extractor)?,
+    }
+    let skipped_non_audio = extractor.skipped_non_audio;
+
+    if cancel_token.as_ref().is_some_and(is_cancelled) {
+        return Ok(SamplePackImportReport {
+            archive: archive.to_string_lossy().to_string(),
+            dest: dest.to_string_lossy().to_string(),
+            files: Vec::new(),
+            skipped_non_audio,
+            converted: 0,
+            copied: 0,
+            skipped: 0,
+            failed: 0,
+            cancelled: true,
+            elapsed_seconds: started.elapsed().as_secs_f64(),
+        });
+    }
+
+    let batch_options = BatchConvertOptions {
+        overwrite: options.overwrite,
+        transfer_id: options.transfer_id.clone(),
+        conversion: options.conversion.clone(),
+    };
+    let batch = convert_directory_sync(&staging_path, dest, &batch_options, cancel_token, &|p| {
+        on_progress(&SamplePackImportProgress {
+            transfer_id: p.transfer_id.clone(),
+            stage: "converting".to_string(),
+            files_done: p.files_done,
+            files_total: Some(p.files_total),
+            bytes_done: p.bytes_done,
+            bytes_total: p.bytes_total,
+            current_file: p
+                .current_file
+                .as_deref()
+                .map(|f| source_in_archive(f, &staging_path)),
+            eta_seconds: p.eta_seconds,
+        })
+    })?;
+
+    let files = batch
+        .files
+        .into_iter()
+        .map(|f| BatchConvertFileResult {
+            source: source_in_archive(&f.source, &staging_path),
+            ..f
+        })
+        .collect();
+    Ok(SamplePackImportReport {
+        archive: archive.to_string_lossy().to_string(),
+        dest: batch.dest,
+        files,
+        skipped_non_audio,
+        converted: batch.converted,
+        copied: batch.copied,
+        skipped: batch.skipped,
+        failed: batch.failed,
+        cancelled: batch.cancelled,
+        elapsed_seconds: started.elapsed().as_secs_f64(),
+    })
+}
+
+/// Import a sample pack archive into a pool folder. Emits
+/// "archive-import-progress" events; cancellable via cancel_audio_transfer
+/// when a transfer_id is given.
+#[tauri::command]
+pub async fn import_sample_archive(
+    app: AppHandle,
+    archive: String,
+    dest: String,
+    options: Option<SamplePackImportOptions>,
+) -> Result<SamplePackImportReport, String> {
+    crate::fs_scope::ensure_allowed(&dest)?;
+    let options = options.unwrap_or_default();
+    let cancel_token = options
+        .transfer_id
+        .as_deref()
+        .map(register_cancellation_token);
+    let transfer_id = options.transfer_id.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        import_archive_sync(
+            Path::new(&archive),
+            Path::new(&dest),
+            &options,
+            cancel_token,
+            &|progress| {
+                let _ = app.emit("archive-import-progress", progress);
+            },
+        )
+    })
+    .await
+    .unwrap();
+    if let Some(id) = transfer_id {
+        remove_cancellation_token(&id);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+    use zip::write::SimpleFileOptions;
+
+    fn wav_bytes(sample_rate: u32) -> Vec<u8> {
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut cursor = std::io::Cursor::new(Vec::new());
+        let mut writer = hound::WavWriter::new(&mut cursor, spec).unwrap();
+        for i in 0..4800 {
+            writer.write_sample(((i % 100) * 100) as i16).unwrap();
+        }
+        writer.finalize().unwrap();
+        cursor.into_inner()
+    }
+
+    #[test]
+    fn test_safe_relative_rejects_escapes() {
+        assert_eq!(
+            safe_relative("Pack/kick.wav"),
+            Some(PathBuf::from("Pack/kick.wav"))
+        );
+        assert_eq!(
+            safe_relative("./Pack\\hat.wav"),
+            Some(PathBuf::from("Pack/hat.wav"))
+        );
+        assert_eq!(safe_relative("../evil.wav"), None);
+        assert_eq!(safe_relative("/etc/evil.wav"), None);
+        assert!(is_os_metadata(Path::new("__MACOSX/Pack/._kick.wav")));
+    }
+
+    #[test]
+    fn test_import_zip_converts_and_reports_non_audio() {
+        let tmp = TempDir::new().unwrap();
+        let archive = tmp.path().join("pack.zip");
+        let mut zip = zip::ZipWriter::new(File::create(&archive).unwrap());
+        let opts = SimpleFileOptions::default();
+        zip.start_file("Pack/kick.wav", opts).unwrap();
+        zip.write_all(&wav_bytes(44100)).unwrap();
+        zip.start_file("Pack/loops/hat.wav", opts).unwrap();
+        zip.write_all(&wav_bytes(48000)).unwrap();
+        zip.start_file("Pack/license.txt", opts).unwrap();
+        zip.write_all(b"free").unwrap();
+        zip.start_file("__MACOSX/Pack/._kick.wav", opts).unwrap();
+        zip.write_all(b"junk").unwrap();
+        zip.finish().unwrap();
+
+        let dest = tmp.path().join("AUDIO");
+        let report =
+            import_archive_sync(&archive, &dest, &Default::default(), None, &|_| {}).unwrap();
+
+        assert_eq!((report.converted, report.copied, report.failed), (1, 1, 0));
+        assert_eq!(
+            report.skipped_non_audio,
+            vec!["Pack/license.txt".to_string()]
+        );
+        assert!(report
+            .files
+            .iter()
+            .any(|f| f.source == "Pack/loops/hat.wav"));
+        assert!(dest.join("Pack").join("kick.wav").exists());
+        let converted = hound::WavReader::open(dest.join("Pack/loops/hat.wav")).unwrap();
+        assert_eq!(converted.spec().sample_rate, 44100);
+        assert!(!dest.join("__MACOSX").exists());
+
+        assert!(import_archive_sync(
+            &tmp.path().join("pack.rar"),
+            &dest,
+            &Default::default(),
+            None,
+            &|_| {}
+        )
+        .is_err());
+    }
+}
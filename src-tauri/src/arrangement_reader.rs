@@ -0,0 +1,249 @@
+// Arrangement reader: parses arr01.work – arr08.work.
+//
+// Layout (same as ot-tools-io's ArrangementFile, 11336 bytes):
+//   0   header (22 bytes) + 2 unknown bytes
+//   24  current arrangement block (5650 bytes)
+//   5674  2 unknown bytes
+//   5676  previous (saved) arrangement block
+//   11326 8 unknown bytes, then the 2-byte checksum
+// Block: name[15], 2 unknown bytes, row count, then 256 rows of 22 bytes.
+//
+// Files are read as raw bytes: the hardware's own arrangement files are the
+// reference here (ArrangementFile::default() does not round-trip, see
+// project_manager::create_project_sync).
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+const FILE_SIZE: usize = 11336;
+const CURRENT_BLOCK_OFFSET: usize = 24;
+const NAME_LEN: usize = 15;
+const ROW_COUNT_OFFSET: usize = NAME_LEN + 2;
+const ROWS_OFFSET: usize = ROW_COUNT_OFFSET + 1;
+const ROW_SIZE: usize = 22;
+const MAX_ROWS: usize = 256;
+
+/// One arranger row. Fields that do not apply to the row's kind are None.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ArrangementRow {
+    pub index: u16,           // 0-based row number
+    pub kind: String,         // "pattern", "loop", "jump", "halt", "reminder"
+    pub bank: Option<String>, // "A"-"P"
+    pub pattern: Option<u8>,  // 1-16
+    pub repeats: Option<u8>,
+    pub offset: Option<u8>,
+    pub length: Option<u8>,
+    pub tempo: Option<f32>,    // BPM; None = keep current tempo
+    pub muted_tracks: Vec<u8>, // audio tracks 1-8
+    pub scene_a: Option<u8>,
+    pub scene_b: Option<u8>,
+    pub loop_count: Option<u8>,
+    pub target_row: Option<u8>,
+    pub reminder: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Arrangement {
+    pub arrangement_id: u8, // 1-8
+    pub name: String,
+    pub rows: Vec<ArrangementRow>,
+}
+
+fn ascii_string(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end])
+        .trim_end()
+        .to_string()
+}
+
+fn empty_row(index: u16, kind: &str) -> ArrangementRow {
+    ArrangementRow {
+        index,
+        kind: kind.to_string(),
+        bank: None,
+        pattern: None,
+        repeats: None,
+        offset: None,
+        length: None,
+        tempo: None,
+        muted_tracks: Vec::new(),
+        scene_a: None,
+        scene_b: None,
+        loop_count: None,
+        target_row: None,
+        reminder: None,
+    }
+}
+
+/// Decode one 22-byte row. Byte 0 is the row type: 0 = pattern,
+/// 1 = loop/jump/halt, 2 = reminder text.
+fn parse_row(index: u16, row: &[u8], row_count: usize) -> ArrangementRow {
+    match row[0] {
+        0 => {
+            let pattern_id = row[1] as usize;
+            // Tempo is stored as BPM * 24 over two bytes (like the project tempo)
+            let raw_tempo = u16::from_be_bytes([row[6], row[7]]);
+            ArrangementRow {
+                bank: crate::project_reader::BANK_LETTERS
+                    .get(pattern_id / 16)
+                    .map(|b| b.to_string()),
+                pattern: Some((pattern_id % 16) as u8 + 1),
+                repeats: Some(row[2]),
+                muted_tracks: (0..8u8)
+                    .filter(|t| row[4] & (1 << t) != 0)
+                    .map(|t| t + 1)
+                    .collect(),
+                tempo: (raw_tempo != 0).then(|| raw_tempo as f32 / 24.0),
+                scene_a: Some(row[8]),
+                scene_b: Some(row[9]),
+                offset: Some(row[11]),
+                length: Some(row[13]),
+                ..empty_row(index, "pattern")
+            }
+        }
+        1 => {
+            let (loop_count, target) = (row[2], row[3]);
+            let kind = if loop_count > 0 {
+                "loop"
+            } else if (target as usize) < row_count {
+                "jump"
+            } else {
+                "halt"
+            };
+            ArrangementRow {
+                loop_count: Some(loop_count),
+                target_row: Some(target),
+                ..empty_row(index, kind)
+            }
+        }
+        _ => ArrangementRow {
+            reminder: Some(ascii_string(&row[1..])),
+            ..empty_row(index, "reminder")
+        },
+    }
+}
+
+/// Parse the working (current) state of one arrangement file.
+pub fn read_arrangement_file(path: &Path, arrangement_id: u8) -> Result<Arrangement, String> {
+    let data = fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    if data.len() != FILE_SIZE || &data[0..4] != b"FORM" {
+        return Err(format!(
+            "Not an arrangement file: {} ({} bytes)",
+            path.display(),
+            data.len()
+        ));
+    }
+
+    let block = &data[CURRENT_BLOCK_OFFSET..];
+    let name = ascii_string(&block[..NAME_LEN]);
+    let row_count = (block[ROW_COUNT_OFFSET] as usize).min(MAX_ROWS);
+    let rows = (0..row_count)
+        .map(|i| {
+            let start = ROWS_OFFSET + i * ROW_SIZE;
+            parse_row(i as u16, &block[start..start + ROW_SIZE], row_count)
+        })
+        .collect();
+
+    Ok(Arrangement {
+        arrangement_id,
+        name,
+        rows,
+    })
+}
+
+/// All arrangements of a project (arrNN.work, falling back to arrNN.strd).
+/// Missing files are skipped; an unreadable one is an error.
+pub fn read_arrangements(project_path: &str) -> Result<Vec<Arrangement>, String> {
+    let path = Path::new(project_path);
+    if !path.is_dir() {
+        return Err(format!("Project path does not exist: {}", project_path));
+    }
+    let mut arrangements = Vec::new();
+    for id in 1u8..=8 {
+        let work = path.join(format!("arr{:02}.work", id));
+        let strd = path.join(format!("arr{:02}.strd", id));
+        let file = if work.exists() {
+            work
+        } else if strd.exists() {
+            strd
+        } else {
+            continue;
+        };
+        arrangements.push(read_arrangement_file(&file, id)?);
+    }
+    Ok(arrangements)
+}
+
+#[tauri::command]
+pub async fn load_arrangements(path: String) -> Result<Vec<Arrangement>, String> {
+    tauri::async_runtime::spawn_blocking(move || read_arrangements(&path))
+        .await
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    static BLANK: &[u8] = include_bytes!("templates/blank_arrangement.work");
+
+    #[test]
+    fn test_blank_arrangement_has_no_rows() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("arr01.work");
+        fs::write(&path, BLANK).unwrap();
+
+        let arrangement = read_arrangement_file(&path, 1).unwrap();
+        assert_eq!(arrangement.name, "");
+        assert!(arrangement.rows.is_empty());
+    }
+
+    #[test]
+    fn test_pattern_and_loop_rows() {
+        let mut data = BLANK.to_vec();
+        let block = CURRENT_BLOCK_OFFSET;
+        data[block..block + 4].copy_from_slice(b"SONG");
+        data[block + ROW_COUNT_OFFSET] = 2;
+        let row0 = block + ROWS_OFFSET;
+        data[row0] = 0;
+        data[row0 + 1] = 2 * 16 + 4; // C05
+        data[row0 + 2] = 3; // repeats
+        data[row0 + 4] = 0b0000_0101; // T1 + T3 muted
+        data[row0 + 6..row0 + 8].copy_from_slice(&(120u16 * 24).to_be_bytes());
+        data[row0 + 13] = 64; // length
+        let row1 = row0 + ROW_SIZE;
+        data[row1] = 1;
+        data[row1 + 2] = 4; // loop 4 times
+        data[row1 + 3] = 0; // back to row 0
+
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("arr02.work"), &data).unwrap();
+        let arrangements = read_arrangements(&dir.path().to_string_lossy()).unwrap();
+
+        assert_eq!(arrangements.len(), 1);
+        let arrangement = &arrangements[0];
+        assert_eq!(arrangement.arrangement_id, 2);
+        assert_eq!(arrangement.name, "SONG");
+        let row = &arrangement.rows[0];
+        assert_eq!(row.kind, "pattern");
+        assert_eq!(row.bank.as_deref(), Some("C"));
+        assert_eq!(row.pattern, Some(5));
+        assert_eq!(row.repeats, Some(3));
+        assert_eq!(row.muted_tracks, vec![1, 3]);
+        assert_eq!(row.tempo, Some(120.0));
+        assert_eq!(row.length, Some(64));
+        let row = &arrangement.rows[1];
+        assert_eq!(row.kind, "loop");
+        assert_eq!((row.loop_count, row.target_row), (Some(4), Some(0)));
+    }
+
+    #[test]
+    fn test_wrong_size_is_rejected() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("arr01.work");
+        fs::write(&path, b"FORM").unwrap();
+        assert!(read_arrangement_file(&path, 1).is_err());
+    }
+}
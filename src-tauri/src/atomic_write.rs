@@ -0,0 +1,127 @@
+// Crash-safe file writes for everything the device reads (banks, project,
+// markers, .ot sidecars).
+//
+// Data is written to `<file>.otm-tmp` next to the target, flushed to disk,
+// then renamed over the target; the rename is atomic on the same filesystem,
+// so a crash or a card pulled mid-write leaves either the old file or the new
+// one, never a truncated mix. A leftover temp file is harmless and is
+// overwritten by the next write.
+
+use ot_tools_io::OctatrackFileIO;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const TMP_SUFFIX: &str = ".otm-tmp";
+
+fn tmp_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(TMP_SUFFIX);
+    path.with_file_name(name)
+}
+
+/// Flush the directory entry of a rename to disk. Windows has no directory
+/// handles to sync; its rename is already durable once it returns.
+fn sync_dir(path: &Path) {
+    #[cfg(unix)]
+    if let Some(dir) = path.parent() {
+        let dir = if dir.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            dir
+        };
+        if let Ok(handle) = File::open(dir) {
+            let _ = handle.sync_all();
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+}
+
+/// fsync `tmp` and rename it over `path`; removes `tmp` on failure.
+fn commit(tmp: &Path, path: &Path) -> Result<(), String> {
+    let result = OpenOptions::new()
+        .write(true)
+        .open(tmp)
+        .and_then(|f| f.sync_all())
+        .and_then(|_| fs::rename(tmp, path));
+    if let Err(e) = result {
+        let _ = fs::remove_file(tmp);
+        return Err(format!("{}", e));
+    }
+    sync_dir(path);
+    crate::metadata_cache::invalidate_written(path);
+    Ok(())
+}
+
+/// Atomically replace `path` with `data`.
+pub fn write_atomic<P: AsRef<Path>>(path: P, data: &[u8]) -> Result<(), String> {
+    let path = path.as_ref();
+    let tmp = tmp_path(path);
+    let written = File::create(&tmp).and_then(|mut f| f.write_all(data));
+    if let Err(e) = written {
+        let _ = fs::remove_file(&tmp);
+        return Err(format!("{}", e));
+    }
+    commit(&tmp, path)
+}
+
+/// Atomically replace `path` with the encoded Octatrack file `value`.
+pub fn write_data_file<T: OctatrackFileIO, P: AsRef<Path>>(
+    value: &T,
+    path: P,
+) -> Result<(), String> {
+    let path = path.as_ref();
+    let tmp = tmp_path(path);
+    if let Err(e) = value.to_data_file(&tmp) {
+        let _ = fs::remove_file(&tmp);
+        return Err(format!("{:?}", e));
+    }
+    commit(&tmp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ot_tools_io::BankFile;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_write_atomic_replaces_target() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("bank01.work");
+        fs::write(&path, b"old contents that are longer").unwrap();
+
+        write_atomic(&path, b"new").unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"new");
+        assert!(!tmp_path(&path).exists());
+    }
+
+    #[test]
+    fn test_failed_write_keeps_original() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("missing").join("bank01.work");
+        assert!(write_atomic(&path, b"new").is_err());
+
+        // A target that can't be renamed over (a directory) stays untouched.
+        let target = dir.path().join("bank02.work");
+        fs::create_dir(&target).unwrap();
+        fs::write(target.join("keep"), b"x").unwrap();
+        assert!(write_atomic(&target, b"new").is_err());
+        assert!(target.join("keep").exists());
+        assert!(!tmp_path(&target).exists());
+    }
+
+    #[test]
+    fn test_write_data_file_round_trips() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("bank01.work");
+        let bank = BankFile::default();
+        write_data_file(&bank, &path).unwrap();
+        assert!(!tmp_path(&path).exists());
+
+        let direct = dir.path().join("bank02.work");
+        bank.to_data_file(&direct).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), fs::read(&direct).unwrap());
+    }
+}
@@ -0,0 +1,365 @@
+// Rhythm analysis of samples: an onset-strength envelope (how sharply the
+// level rises, frame by frame), and tempo estimation and transient detection
+// from it.
+//
+// Tempo comes from autocorrelating the envelope over the lags of 60-200 BPM,
+// weighted towards ~120 BPM so a loop is not reported at half or double
+// speed. Loops are usually cut to whole bars, so an estimate close to a
+// whole number of bars over the file's length is snapped to it.
+//
+// Detected tempos are remembered per file (keyed by mtime and size) so
+// directory listings can show them without analysing anything.
+
+use crate::audio_pool::decode_audio_file;
+use crate::sample_attributes::{write_ot_attributes, OtAttributesUpdate};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+/// Envelope hop size in sample frames (~11.6 ms at 44.1kHz).
+pub(crate) const HOP: usize = 512;
+const MIN_BPM: f32 = 60.0;
+const MAX_BPM: f32 = 200.0;
+/// Shortest audio worth analysing for tempo, in seconds.
+const MIN_TEMPO_SECONDS: f64 = 1.5;
+/// Relative distance within which an estimate is snapped to whole bars.
+const BAR_SNAP_TOLERANCE: f32 = 0.04;
+/// Envelope frames on each side averaged for the transient threshold.
+const ONSET_WINDOW: usize = 10;
+
+type Stamp = (Option<SystemTime>, u64);
+static DETECTED_BPM: Lazy<Mutex<HashMap<String, (Stamp, Option<f32>)>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn stamp(path: &Path) -> Option<Stamp> {
+    let meta = std::fs::metadata(path).ok()?;
+    Some((meta.modified().ok(), meta.len()))
+}
+
+/// Mean of all channels.
+pub(crate) fn mono_mix(channels: &[Vec<f32>]) -> Vec<f32> {
+    let Some(first) = channels.first() else {
+        return Vec::new();
+    };
+    (0..first.len())
+        .map(|i| {
+            channels
+                .iter()
+                .map(|ch| ch.get(i).copied().unwrap_or(0.0))
+                .sum::<f32>()
+                / channels.len() as f32
+        })
+        .collect()
+}
+
+/// Half-wave rectified rise in log energy between consecutive `HOP` frames.
+/// Element `i` describes the frame starting at sample `i * HOP`.
+pub(crate) fn onset_envelope(mono: &[f32]) -> Vec<f32> {
+    let energies: Vec<f32> = mono
+        .chunks(HOP)
+        .map(|frame| (frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32 + 1e-10).ln())
+        .collect();
+    let mut envelope = Vec::with_capacity(energies.len());
+    envelope.push(0.0);
+    envelope.extend(energies.windows(2).map(|w| (w[1] - w[0]).max(0.0)));
+    envelope
+}
+
+fn autocorrelation(envelope: &[f32], lag: usize) -> f32 {
+    if lag >= envelope.len() {
+        return 0.0;
+    }
+    let n = envelope.len() - lag;
+    envelope[..n]
+        .iter()
+        .zip(&envelope[lag..])
+        .map(|(a, b)| a * b)
+        .sum::<f32>()
+        / n as f32
+}
+
+/// Estimate the tempo of mono audio at `sample_rate`. None when it is too
+/// short or has no periodic onsets.
+pub fn estimate_bpm(mono: &[f32], sample_rate: u32) -> Option<f32> {
+    let duration = mono.len() as f64 / sample_rate.max(1) as f64;
+    if duration < MIN_TEMPO_SECONDS {
+        return None;
+    }
+    let frame_rate = sample_rate as f32 / HOP as f32;
+    let mut envelope = onset_envelope(mono);
+    let mean = envelope.iter().sum::<f32>() / envelope.len() as f32;
+    envelope.iter_mut().for_each(|v| *v -= mean);
+
+    let min_lag = (60.0 * frame_rate / MAX_BPM).floor().max(1.0) as usize;
+    let max_lag = (60.0 * frame_rate / MIN_BPM).ceil() as usize;
+    let score = |lag: usize| {
+        let bpm = 60.0 * frame_rate / lag as f32;
+        let prior = (-0.5 * (bpm / 120.0).log2().powi(2)).exp();
+        (autocorrelation(&envelope, lag) + 0.5 * autocorrelation(&envelope, lag * 2)) * prior
+    };
+    let (best_lag, best_score) = (min_lag..=max_lag)
+        .map(|lag| (lag, score(lag)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))?;
+    if best_score <= 0.0 {
+        return None;
+    }
+
+    // Parabolic interpolation around the peak for a sub-frame lag
+    let (prev, next) = (score(best_lag - 1), score(best_lag + 1));
+    let denominator = prev - 2.0 * best_score + next;
+    let offset = if denominator.abs() > f32::EPSILON {
+        (0.5 * (prev - next) / denominator).clamp(-0.5, 0.5)
+    } else {
+        0.0
+    };
+    let bpm = 60.0 * frame_rate / (best_lag as f32 + offset);
+
+    // Snap to a whole number of 4/4 bars over the file's length
+    let bars = (duration as f32 * bpm / 60.0 / 4.0).round().max(1.0);
+    let bar_bpm = bars * 4.0 * 60.0 / duration as f32;
+    if ((bar_bpm - bpm) / bpm).abs() <= BAR_SNAP_TOLERANCE {
+        Some((bar_bpm * 100.0).round() / 100.0)
+    } else {
+        Some((bpm * 10.0).round() / 10.0)
+    }
+}
+
+/// A detected transient: where it starts and how sharp it is.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Onset {
+    pub position: usize, // sample frames
+    pub strength: f32,
+}
+
+/// Transients of mono audio, in time order. `sensitivity` (0-1) lowers the
+/// threshold above the local average so quieter hits count; peaks closer
+/// than `min_gap` frames are merged into the stronger one.
+pub fn detect_onsets(mono: &[f32], sensitivity: f32, min_gap: usize) -> Vec<Onset> {
+    let envelope = onset_envelope(mono);
+    let peak = envelope.iter().copied().fold(0.0f32, f32::max);
+    if peak <= 0.0 {
+        return Vec::new();
+    }
+    let delta = (1.0 - sensitivity.clamp(0.0, 1.0)) * 0.5 * peak;
+    let gap = (min_gap / HOP).max(1);
+    let around = |i: usize, radius: usize| {
+        &envelope[i.saturating_sub(radius)..(i + radius + 1).min(envelope.len())]
+    };
+
+    let mut frames: Vec<usize> = Vec::new();
+    for (i, &value) in envelope.iter().enumerate() {
+        let local = around(i, ONSET_WINDOW);
+        let mean = local.iter().sum::<f32>() / local.len() as f32;
+        let is_peak = around(i, gap).iter().all(|&v| v <= value);
+        // Equal neighbours on a plateau: keep the first
+        let merged = frames.last().is_some_and(|&last| i - last <= gap);
+        if value > 0.0 && is_peak && value >= mean + delta && !merged {
+            frames.push(i);
+        }
+    }
+    frames
+        .into_iter()
+        .map(|i| Onset {
+            position: refine_onset(mono, i),
+            strength: envelope[i],
+        })
+        .collect()
+}
+
+/// Sample-accurate start of the transient found in envelope frame `frame`:
+/// the first sample reaching half the local peak, moved back to the
+/// preceding zero crossing so a slice starting there doesn't click.
+fn refine_onset(mono: &[f32], frame: usize) -> usize {
+    let start = (frame.saturating_sub(1) * HOP).min(mono.len());
+    let end = ((frame + 1) * HOP).min(mono.len());
+    let window = &mono[start..end];
+    let loudest = window.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+    let mut position = start
+        + window
+            .iter()
+            .position(|s| s.abs() >= loudest * 0.5)
+            .unwrap_or(0);
+    while position > start
+        && mono[position - 1] != 0.0
+        && mono[position - 1].signum() == mono[position].signum()
+    {
+        position -= 1;
+    }
+    position
+}
+
+/// Detect the tempo of an audio file and remember it for `cached_bpm`.
+pub fn detect_bpm(path: &Path) -> Result<Option<f32>, String> {
+    let decoded = decode_audio_file(path)?;
+    let bpm = estimate_bpm(&mono_mix(&decoded.channels), decoded.sample_rate);
+    if let Some(stamp) = stamp(path) {
+        DETECTED_BPM
+            .lock()
+            .unwrap()
+            .insert(path.to_string_lossy().to_string(), (stamp, bpm));
+    }
+    Ok(bpm)
+}
+
+/// Tempo found by an earlier `detect_bpm`, if the file hasn't changed since.
+pub fn cached_bpm(path: &Path) -> Option<f32> {
+    let cache = DETECTED_BPM.lock().unwrap();
+    let (cached_stamp, bpm) = cache.get(path.to_string_lossy().as_ref())?;
+    (stamp(path).as_ref() == Some(cached_stamp))
+        .then_some(*bpm)
+        .flatten()
+}
+
+/// Detect the tempo of `path` and store it as the .ot tempo so timestretch
+/// works on the device straight away. Best effort: returns the tempo written.
+pub fn detect_and_write_bpm(path: &Path) -> Result<Option<f32>, String> {
+    let Some(bpm) = detect_bpm(path)? else {
+        return Ok(None);
+    };
+    let update = OtAttributesUpdate {
+        bpm: Some(bpm),
+        ..Default::default()
+    };
+    write_ot_attributes(path, &update)?;
+    Ok(Some(bpm))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BpmDetection {
+    pub path: String,
+    pub bpm: Option<f32>,
+    pub written_to_ot: bool,
+    pub error: Option<String>,
+}
+
+/// Detect the tempo of each file, optionally writing it into its .ot file.
+#[tauri::command]
+pub async fn detect_sample_bpm(
+    paths: Vec<String>,
+    write_ot: Option<bool>,
+) -> Result<Vec<BpmDetection>, String> {
+    let write_ot = write_ot.unwrap_or(false);
+    if write_ot {
+        for path in &paths {
+            crate::fs_scope::ensure_allowed(path)?;
+        }
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        Ok(paths
+            .into_iter()
+            .map(|path| {
+                let file = Path::new(&path);
+                let result = if write_ot {
+                    detect_and_write_bpm(file)
+                } else {
+                    detect_bpm(file)
+                };
+                match result {
+                    Ok(bpm) => BpmDetection {
+                        written_to_ot: write_ot && bpm.is_some(),
+                        path,
+                        bpm,
+                        error: None,
+                    },
+                    Err(e) => BpmDetection {
+                        path,
+                        bpm: None,
+                        written_to_ot: false,
+                        error: Some(e),
+                    },
+                }
+            })
+            .collect())
+    })
+    .await
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Short decaying noise bursts on every beat.
+    fn click_track(bpm: f32, beats: usize, sample_rate: u32) -> Vec<f32> {
+        let beat_len = (sample_rate as f32 * 60.0 / bpm) as usize;
+        let mut out = vec![0.0f32; beat_len * beats];
+        for beat in 0..beats {
+            for i in 0..400 {
+                let noise = if (i * 7919 + beat) % 3 == 0 {
+                    0.8
+                } else {
+                    -0.8
+                };
+                out[beat * beat_len + i] = noise * (1.0 - i as f32 / 400.0);
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn test_estimate_bpm_of_click_loops() {
+        let bpm = estimate_bpm(&click_track(120.0, 16, 44100), 44100).unwrap();
+        assert!((bpm - 120.0).abs() < 0.5, "got {}", bpm);
+        let bpm = estimate_bpm(&click_track(95.0, 16, 44100), 44100).unwrap();
+        assert!((bpm - 95.0).abs() < 1.0, "got {}", bpm);
+
+        assert_eq!(estimate_bpm(&vec![0.0; 44100 * 4], 44100), None);
+        assert_eq!(estimate_bpm(&click_track(120.0, 2, 44100), 44100), None);
+    }
+
+    /// Four hits on a quiet noise floor; deterministic pseudo-random noise.
+    fn hits(positions: &[(usize, f32)], len: usize) -> Vec<f32> {
+        let mut seed = 12345u32;
+        let mut noise = move || {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            (seed >> 16) as f32 / 32768.0 - 1.0
+        };
+        let mut out: Vec<f32> = (0..len).map(|_| noise() * 0.01).collect();
+        for &(start, level) in positions {
+            for i in 0..3000 {
+                out[start + i] += noise() * level * (1.0 - i as f32 / 3000.0);
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn test_detect_onsets_finds_hits() {
+        let positions = [(0, 0.9), (11025, 0.6), (22050, 0.9), (33075, 0.7)];
+        let onsets = detect_onsets(&hits(&positions, 44100), 0.5, 2205);
+        let found: Vec<usize> = onsets.iter().map(|o| o.position).collect();
+        // The hit at 0 has no rise before it; callers always slice from the start
+        assert_eq!(found.len(), 3, "{:?}", found);
+        for (onset, &(expected, _)) in found.iter().zip(&positions[1..]) {
+            assert!(onset.abs_diff(expected) < 64, "{} vs {}", onset, expected);
+        }
+        assert!(detect_onsets(&vec![0.0; 44100], 1.0, 2205).is_empty());
+    }
+
+    #[test]
+    fn test_detect_and_write_bpm_sets_ot_tempo() {
+        let dir = TempDir::new().unwrap();
+        let wav = dir.path().join("loop.wav");
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 44100,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&wav, spec).unwrap();
+        for s in click_track(120.0, 8, 44100) {
+            writer.write_sample((s * i16::MAX as f32) as i16).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        assert_eq!(cached_bpm(&wav), None);
+        let bpm = detect_and_write_bpm(&wav).unwrap().unwrap();
+        assert_eq!(cached_bpm(&wav), Some(bpm));
+        let attributes = crate::sample_attributes::sidecar_attributes(&wav).unwrap();
+        assert!((attributes.bpm - 120.0).abs() < 0.5);
+    }
+}
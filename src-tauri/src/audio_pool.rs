@@ -2,6 +2,10 @@
 #![allow(clippy::needless_range_loop)] // indexed loop pattern is clearer for audio buffer operations
 #![allow(clippy::collapsible_if)] // separate if statements are sometimes clearer
 
+use crate::sample_attributes::{
+    has_sample_extension, is_sidecar_with_sample, move_sidecar, ot_path_for, sidecar_attributes,
+    OtSampleAttributes,
+};
 use once_cell::sync::Lazy;
 use rubato::{
     Resampler, SincFixedIn, SincInterpolationParameters, SincInterpolationType, WindowFunction,
@@ -64,6 +68,8 @@ pub struct AudioFileInfo {
     pub sample_rate: Option<u32>,
     pub is_directory: bool,
     pub path: String,
+    pub ot_attributes: Option<OtSampleAttributes>, // from the sibling .ot file, if any
+    pub detected_bpm: Option<f32>,                 // from an earlier tempo detection, if any
 }
 
 /// List files in a directory with audio metadata
@@ -113,6 +119,11 @@ pub fn list_directory(path: &str) -> Result<Vec<AudioFileInfo>, String> {
         } else {
             (None, None, None)
         };
+        let ot_attributes = if !is_directory && is_audio_file(&file_name) {
+            sidecar_attributes(&file_path)
+        } else {
+            None
+        };
 
         files.push(AudioFileInfo {
             name: file_name,
@@ -121,7 +132,9 @@ pub fn list_directory(path: &str) -> Result<Vec<AudioFileInfo>, String> {
             bit_rate,
             sample_rate,
             is_directory,
+            detected_bpm: crate::audio_analysis::cached_bpm(&file_path),
             path: file_path.to_string_lossy().to_string(),
+            ot_attributes,
         });
     }
 
@@ -160,6 +173,8 @@ pub fn files_info(paths: &[String]) -> Vec<AudioFileInfo> {
                 sample_rate,
                 is_directory: false,
                 path: p.clone(),
+                ot_attributes: sidecar_attributes(path),
+                detected_bpm: crate::audio_analysis::cached_bpm(path),
             }
         })
         .collect()
@@ -242,7 +257,7 @@ fn collect_audio_files_inner(dir: &Path, out: &mut Vec<String>) -> Result<(), St
 }
 
 /// Check if a file is an audio file based on extension
-fn is_audio_file(filename: &str) -> bool {
+pub(crate) fn is_audio_file(filename: &str) -> bool {
     let lower = filename.to_lowercase();
     lower.ends_with(".wav")
         || lower.ends_with(".aif")
@@ -251,10 +266,12 @@ fn is_audio_file(filename: &str) -> bool {
         || lower.ends_with(".flac")
         || lower.ends_with(".ogg")
         || lower.ends_with(".m4a")
+        || (crate::external_decoder::needs_external_decoder(Path::new(&lower))
+            && crate::external_decoder::ffmpeg_available())
 }
 
 /// Extract audio metadata from a file
-fn extract_audio_metadata(path: &PathBuf) -> (Option<u32>, Option<u32>, Option<u32>) {
+pub(crate) fn extract_audio_metadata(path: &PathBuf) -> (Option<u32>, Option<u32>, Option<u32>) {
     let ext = path
         .extension()
         .and_then(|e| e.to_str())
@@ -379,10 +396,15 @@ fn extract_symphonia_metadata(path: &PathBuf) -> (Option<u32>, Option<u32>, Opti
 }
 
 /// Target sample rate for Octatrack compatibility
-const OCTATRACK_SAMPLE_RATE: u32 = 44100;
+pub(crate) const OCTATRACK_SAMPLE_RATE: u32 = 44100;
 
 /// Check if audio file needs conversion for Octatrack compatibility
-fn needs_conversion(path: &Path) -> bool {
+pub(crate) fn needs_conversion(path: &Path) -> bool {
+    needs_conversion_to(path, SampleRateTarget::Octatrack)
+}
+
+/// Check if audio file needs conversion to be written at `target`
+fn needs_conversion_to(path: &Path, target: SampleRateTarget) -> bool {
     let ext = path
         .extension()
         .and_then(|e| e.to_str())
@@ -393,8 +415,8 @@ fn needs_conversion(path: &Path) -> bool {
         Some("wav") => {
             if let Ok(reader) = hound::WavReader::open(path) {
                 let spec = reader.spec();
-                // Needs conversion if sample rate isn't 44.1kHz or bit depth is not 16/24
-                spec.sample_rate != OCTATRACK_SAMPLE_RATE
+                // Needs conversion if sample rate isn't the target or bit depth is not 16/24
+                target.rate_for(spec.sample_rate) != spec.sample_rate
                     || spec.bits_per_sample < 16
                     || spec.bits_per_sample > 24
             } else {
@@ -412,8 +434,8 @@ fn needs_conversion(path: &Path) -> bool {
                         aifc::SampleFormat::I32 => 32,
                         _ => 0,
                     };
-                    // Needs conversion if sample rate isn't 44.1kHz or bit depth is not 16/24
-                    (info.sample_rate as u32) != OCTATRACK_SAMPLE_RATE
+                    // Needs conversion if sample rate isn't the target or bit depth is not 16/24
+                    target.rate_for(info.sample_rate as u32) != info.sample_rate as u32
                         || !(16..=24).contains(&bit_depth)
                 } else {
                     true
@@ -424,17 +446,264 @@ fn needs_conversion(path: &Path) -> bool {
         }
         // All other formats definitely need conversion
         Some("mp3") | Some("flac") | Some("ogg") | Some("m4a") | Some("aac") => true,
+        // Decoded through ffmpeg (see `external_decoder`)
+        Some("opus") | Some("wv") | Some("wma") => true,
         _ => false, // Not an audio file we handle
     }
 }
 
+/// Optional processing applied while converting for the Octatrack.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConversionOptions {
+    /// Sum multichannel sources to mono (halves Flex RAM use for stereo samples)
+    #[serde(default)]
+    pub downmix_to_mono: bool,
+    /// Gain applied to the channel sum, in dB. Defaults to -3 (pan law
+    /// compensation); -6 averages the channels, 0 is a plain sum.
+    #[serde(default)]
+    pub downmix_law_db: Option<f32>,
+    /// Linear fade applied at the start of the sample, in milliseconds
+    #[serde(default)]
+    pub fade_in_ms: Option<u32>,
+    /// Linear fade applied at the end of the sample, in milliseconds
+    #[serde(default)]
+    pub fade_out_ms: Option<u32>,
+    /// Detect the tempo of imported audio and store it in the sample's .ot file
+    #[serde(default)]
+    pub detect_bpm: bool,
+    /// Dither applied when higher-resolution audio is reduced to 16-bit
+    #[serde(default)]
+    pub dither: Dither,
+    /// Read each file back after writing and fail the transfer if it is damaged
+    #[serde(default)]
+    pub verify: bool,
+    /// Sample rate of converted files
+    #[serde(default)]
+    pub sample_rate: SampleRateTarget,
+    /// Gain applied before quantization, in dB (negative to tame hot packs)
+    #[serde(default)]
+    pub gain_db: Option<f32>,
+    /// Write copies under FAT32-safe names (see `file_names`)
+    #[serde(default)]
+    pub sanitize_names: bool,
+}
+
+/// Sample rate that converted files are written at.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SampleRateTarget {
+    /// 44.1kHz, the rate the Octatrack plays back at
+    #[default]
+    Octatrack,
+    /// Keep each file's own rate, for destinations other than the Octatrack
+    Source,
+    /// A fixed rate in Hz, clamped to 8-192kHz
+    Fixed(u32),
+}
+
+impl SampleRateTarget {
+    /// Rate a file recorded at `source_rate` is written at.
+    pub(crate) fn rate_for(self, source_rate: u32) -> u32 {
+        match self {
+            SampleRateTarget::Octatrack => OCTATRACK_SAMPLE_RATE,
+            SampleRateTarget::Source => source_rate,
+            SampleRateTarget::Fixed(rate) => rate.clamp(8000, 192000),
+        }
+    }
+}
+
+/// How samples are quantized when written at 16-bit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Dither {
+    /// Plain truncation
+    #[default]
+    None,
+    /// Triangular (TPDF) dither at +/-1 LSB, flat noise spectrum
+    Tpdf,
+    /// TPDF dither with first-order noise shaping, moving the noise towards
+    /// high frequencies where it is less audible
+    Shaped,
+}
+
+/// Per-channel 16-bit quantizer state for `Dither`.
+struct Quantizer {
+    mode: Dither,
+    rng: u32,
+    error: f32, // previous quantization error, fed back when shaping
+}
+
+impl Quantizer {
+    fn new(mode: Dither, channel: usize) -> Self {
+        Quantizer {
+            mode,
+            rng: 0x9E37_79B9 ^ (channel as u32 + 1).wrapping_mul(0x85EB_CA6B),
+            error: 0.0,
+        }
+    }
+
+    /// Uniform value in [0, 1) (xorshift32; deterministic so output is reproducible).
+    fn uniform(&mut self) -> f32 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 17;
+        self.rng ^= self.rng << 5;
+        (self.rng >> 8) as f32 / (1u32 << 24) as f32
+    }
+
+    fn quantize(&mut self, sample: f32) -> i16 {
+        let scaled = sample * i16::MAX as f32;
+        match self.mode {
+            Dither::None => scaled as i16,
+            Dither::Tpdf | Dither::Shaped => {
+                let target = if self.mode == Dither::Shaped {
+                    scaled - self.error
+                } else {
+                    scaled
+                };
+                let noise = self.uniform() - self.uniform();
+                let out = (target + noise)
+                    .round()
+                    .clamp(i16::MIN as f32, i16::MAX as f32);
+                self.error = out - target;
+                out as i16
+            }
+        }
+    }
+}
+
+impl ConversionOptions {
+    /// True if these options change `path` even when its format is already compatible.
+    fn alters(&self, path: &Path) -> bool {
+        let fades = self.fade_in_ms.unwrap_or(0) > 0 || self.fade_out_ms.unwrap_or(0) > 0;
+        let gain = self.gain_db.is_some_and(|db| db != 0.0);
+        fades
+            || gain
+            || self.downmix_to_mono
+                && extract_audio_metadata(&path.to_path_buf())
+                    .0
+                    .is_some_and(|channels| channels > 1)
+    }
+}
+
+/// `needs_conversion`, also counting processing requested through `options`.
+pub(crate) fn needs_conversion_with(path: &Path, options: &ConversionOptions) -> bool {
+    needs_conversion_to(path, options.sample_rate) || options.alters(path)
+}
+
+/// Sum all channels into one, scaled by `law_db`.
+fn downmix_to_mono(samples: Vec<Vec<f32>>, law_db: f32) -> Vec<Vec<f32>> {
+    if samples.len() < 2 {
+        return samples;
+    }
+    let gain = 10f32.powf(law_db / 20.0);
+    let mono = (0..samples[0].len())
+        .map(|i| {
+            samples
+                .iter()
+                .map(|ch| ch.get(i).copied().unwrap_or(0.0))
+                .sum::<f32>()
+                * gain
+        })
+        .collect();
+    vec![mono]
+}
+
+/// Ramp the first `fade_in_ms` and last `fade_out_ms` of every channel linearly
+/// from/to silence. Fades longer than the sample are clamped to its length.
+fn apply_fades(samples: &mut [Vec<f32>], sample_rate: u32, fade_in_ms: u32, fade_out_ms: u32) {
+    let to_frames = |ms: u32| (sample_rate as u64 * ms as u64 / 1000) as usize;
+    for channel in samples.iter_mut() {
+        let len = channel.len();
+        let fade_in = to_frames(fade_in_ms).min(len);
+        for (i, sample) in channel.iter_mut().take(fade_in).enumerate() {
+            *sample *= i as f32 / fade_in as f32;
+        }
+        let fade_out = to_frames(fade_out_ms).min(len);
+        for (i, sample) in channel.iter_mut().rev().take(fade_out).enumerate() {
+            *sample *= i as f32 / fade_out as f32;
+        }
+    }
+}
+
+/// Scale every channel by `gain_db`. Samples pushed past full scale are
+/// clipped when written.
+fn apply_gain(samples: &mut [Vec<f32>], gain_db: f32) {
+    if gain_db == 0.0 {
+        return;
+    }
+    let gain = 10f32.powf(gain_db / 20.0);
+    for sample in samples.iter_mut().flatten() {
+        *sample *= gain;
+    }
+}
+
+/// Approximate size of the WAV written when converting `source_path`: decoded
+/// length at the `target` rate with the bit depth clamped to 16-24 bits. Falls
+/// back to the source size when the container does not report its frame count.
+fn estimate_converted_size(source_path: &Path, target: SampleRateTarget) -> u64 {
+    let source_size = fs::metadata(source_path).map(|m| m.len()).unwrap_or(0);
+    let Ok(file) = fs::File::open(source_path) else {
+        return source_size;
+    };
+    let mss = MediaSourceStream::new(Box::new(file), Default::default());
+    let mut hint = Hint::new();
+    if let Some(ext) = source_path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(ext);
+    }
+    let Ok(probed) = symphonia::default::get_probe().format(
+        &hint,
+        mss,
+        &FormatOptions::default(),
+        &MetadataOptions::default(),
+    ) else {
+        return source_size;
+    };
+    let Some(params) = probed
+        .format
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+        .map(|t| t.codec_params.clone())
+    else {
+        return source_size;
+    };
+    let (Some(frames), Some(rate)) = (params.n_frames, params.sample_rate) else {
+        return source_size;
+    };
+    let channels = params.channels.map(|c| c.count() as u64).unwrap_or(2);
+    let bytes_per_sample = (params.bits_per_sample.unwrap_or(16).clamp(16, 24) / 8) as u64;
+    let target_frames = frames * target.rate_for(rate) as u64 / rate.max(1) as u64;
+    // 44-byte RIFF header
+    target_frames * channels * bytes_per_sample + 44
+}
+
 /// Convert an audio file to Octatrack-compatible WAV format with progress reporting
+pub(crate) fn convert_to_octatrack_format_with_progress<F>(
+    source_path: &Path,
+    dest_path: &Path,
+    progress_callback: &F,
+    cancel_token: &Option<Arc<AtomicBool>>,
+) -> Result<(), String>
+where
+    F: Fn(&str, f32),
+{
+    convert_to_octatrack_format_with_options(
+        source_path,
+        dest_path,
+        &ConversionOptions::default(),
+        progress_callback,
+        cancel_token,
+    )
+}
+
+/// Convert an audio file to Octatrack-compatible WAV format, applying `options`.
 /// Progress is dynamically computed based on required steps:
 /// - If resampling needed: decoding (0-50%), resampling (50-80%), writing (80-100%)
 /// - If no resampling: decoding (0-60%), writing (60-100%)
-fn convert_to_octatrack_format_with_progress<F>(
+pub(crate) fn convert_to_octatrack_format_with_options<F>(
     source_path: &Path,
     dest_path: &Path,
+    options: &ConversionOptions,
     progress_callback: &F,
     cancel_token: &Option<Arc<AtomicBool>>,
 ) -> Result<(), String>
@@ -452,6 +721,11 @@ where
     };
 
     check_cancelled()?;
+    progress_callback("decoding", 0.0);
+    // Formats symphonia can't decode are transcoded to a temporary WAV first
+    let decodable = crate::external_decoder::decodable(source_path, cancel_token.as_ref())?;
+    let source_path = decodable.path();
+
     // Open the source file
     let file =
         fs::File::open(source_path).map_err(|e| format!("Failed to open source file: {}", e))?;
@@ -512,7 +786,8 @@ where
     };
 
     // Determine if resampling is needed to compute progress ranges dynamically
-    let needs_resampling = source_sample_rate != OCTATRACK_SAMPLE_RATE;
+    let target_sample_rate = options.sample_rate.rate_for(source_sample_rate);
+    let needs_resampling = source_sample_rate != target_sample_rate;
 
     // Dynamic progress ranges based on required steps
     // Weights approximate relative processing time for each step
@@ -566,71 +841,7 @@ where
             .decode(&packet)
             .map_err(|e| format!("Decode error: {}", e))?;
 
-        // Convert to f32 samples per channel
-        match decoded {
-            AudioBufferRef::F32(buf) => {
-                for ch in 0..channels {
-                    all_samples[ch].extend(buf.chan(ch).iter().cloned());
-                }
-            }
-            AudioBufferRef::S32(buf) => {
-                for ch in 0..channels {
-                    all_samples[ch]
-                        .extend(buf.chan(ch).iter().map(|&s| s as f32 / i32::MAX as f32));
-                }
-            }
-            AudioBufferRef::S16(buf) => {
-                for ch in 0..channels {
-                    all_samples[ch]
-                        .extend(buf.chan(ch).iter().map(|&s| s as f32 / i16::MAX as f32));
-                }
-            }
-            AudioBufferRef::U8(buf) => {
-                for ch in 0..channels {
-                    all_samples[ch]
-                        .extend(buf.chan(ch).iter().map(|&s| (s as f32 - 128.0) / 128.0));
-                }
-            }
-            AudioBufferRef::S24(buf) => {
-                for ch in 0..channels {
-                    all_samples[ch].extend(buf.chan(ch).iter().map(|s| s.0 as f32 / 8388607.0));
-                }
-            }
-            AudioBufferRef::F64(buf) => {
-                for ch in 0..channels {
-                    all_samples[ch].extend(buf.chan(ch).iter().map(|&s| s as f32));
-                }
-            }
-            AudioBufferRef::U16(buf) => {
-                for ch in 0..channels {
-                    all_samples[ch]
-                        .extend(buf.chan(ch).iter().map(|&s| (s as f32 - 32768.0) / 32768.0));
-                }
-            }
-            AudioBufferRef::U24(buf) => {
-                for ch in 0..channels {
-                    all_samples[ch].extend(
-                        buf.chan(ch)
-                            .iter()
-                            .map(|s| (s.0 as f32 - 8388608.0) / 8388608.0),
-                    );
-                }
-            }
-            AudioBufferRef::U32(buf) => {
-                for ch in 0..channels {
-                    all_samples[ch].extend(
-                        buf.chan(ch)
-                            .iter()
-                            .map(|&s| (s as f32 - 2147483648.0) / 2147483648.0),
-                    );
-                }
-            }
-            AudioBufferRef::S8(buf) => {
-                for ch in 0..channels {
-                    all_samples[ch].extend(buf.chan(ch).iter().map(|&s| s as f32 / i8::MAX as f32));
-                }
-            }
-        }
+        append_decoded(&mut all_samples, decoded);
     }
 
     // Check if we got any samples
@@ -640,16 +851,23 @@ where
 
     progress_callback("decoding", decode_end);
 
+    // Downmix before resampling so only one channel goes through the resampler
+    let all_samples = if options.downmix_to_mono {
+        downmix_to_mono(all_samples, options.downmix_law_db.unwrap_or(-3.0))
+    } else {
+        all_samples
+    };
+
     // Check cancellation before resampling
     check_cancelled()?;
 
     // Resample if necessary
-    let resampled: Vec<Vec<f32>> = if needs_resampling {
+    let mut resampled: Vec<Vec<f32>> = if needs_resampling {
         progress_callback("resampling", decode_end);
         resample_audio_with_progress(
             &all_samples,
             source_sample_rate,
-            OCTATRACK_SAMPLE_RATE,
+            target_sample_rate,
             cancel_token,
             |p| {
                 // Map resampling progress (0-1) to overall progress (decode_end to resample_end)
@@ -659,17 +877,34 @@ where
     } else {
         all_samples
     };
+    apply_fades(
+        &mut resampled,
+        target_sample_rate,
+        options.fade_in_ms.unwrap_or(0),
+        options.fade_out_ms.unwrap_or(0),
+    );
+    apply_gain(&mut resampled, options.gain_db.unwrap_or(0.0));
 
     // Check cancellation before writing
     check_cancelled()?;
 
+    // Dither only when precision is actually lost: integer sources above
+    // 16-bit, and float or lossy sources that report no bit depth
+    let dither = if target_bits == 16 && !matches!(codec_params.bits_per_sample, Some(b) if b <= 16)
+    {
+        options.dither
+    } else {
+        Dither::None
+    };
+
     // Write to WAV file (resample_end to 1.0)
     progress_callback("writing", resample_end);
     write_wav_file_with_progress(
         dest_path,
         &resampled,
-        OCTATRACK_SAMPLE_RATE,
+        target_sample_rate,
         target_bits,
+        dither,
         cancel_token,
         |p| {
             // Map writing progress (0-1) to overall progress (resample_end to 1.0)
@@ -681,8 +916,161 @@ where
     Ok(())
 }
 
+/// Append a decoded packet to per-channel f32 buffers (one per channel).
+fn append_decoded(all_samples: &mut [Vec<f32>], decoded: AudioBufferRef) {
+    match decoded {
+        AudioBufferRef::F32(buf) => {
+            for (ch, out) in all_samples.iter_mut().enumerate() {
+                out.extend(buf.chan(ch).iter().cloned());
+            }
+        }
+        AudioBufferRef::S32(buf) => {
+            for (ch, out) in all_samples.iter_mut().enumerate() {
+                out.extend(buf.chan(ch).iter().map(|&s| s as f32 / i32::MAX as f32));
+            }
+        }
+        AudioBufferRef::S16(buf) => {
+            for (ch, out) in all_samples.iter_mut().enumerate() {
+                out.extend(buf.chan(ch).iter().map(|&s| s as f32 / i16::MAX as f32));
+            }
+        }
+        AudioBufferRef::U8(buf) => {
+            for (ch, out) in all_samples.iter_mut().enumerate() {
+                out.extend(buf.chan(ch).iter().map(|&s| (s as f32 - 128.0) / 128.0));
+            }
+        }
+        AudioBufferRef::S24(buf) => {
+            for (ch, out) in all_samples.iter_mut().enumerate() {
+                out.extend(buf.chan(ch).iter().map(|s| s.0 as f32 / 8388607.0));
+            }
+        }
+        AudioBufferRef::F64(buf) => {
+            for (ch, out) in all_samples.iter_mut().enumerate() {
+                out.extend(buf.chan(ch).iter().map(|&s| s as f32));
+            }
+        }
+        AudioBufferRef::U16(buf) => {
+            for (ch, out) in all_samples.iter_mut().enumerate() {
+                out.extend(buf.chan(ch).iter().map(|&s| (s as f32 - 32768.0) / 32768.0));
+            }
+        }
+        AudioBufferRef::U24(buf) => {
+            for (ch, out) in all_samples.iter_mut().enumerate() {
+                out.extend(
+                    buf.chan(ch)
+                        .iter()
+                        .map(|s| (s.0 as f32 - 8388608.0) / 8388608.0),
+                );
+            }
+        }
+        AudioBufferRef::U32(buf) => {
+            for (ch, out) in all_samples.iter_mut().enumerate() {
+                out.extend(
+                    buf.chan(ch)
+                        .iter()
+                        .map(|&s| (s as f32 - 2147483648.0) / 2147483648.0),
+                );
+            }
+        }
+        AudioBufferRef::S8(buf) => {
+            for (ch, out) in all_samples.iter_mut().enumerate() {
+                out.extend(buf.chan(ch).iter().map(|&s| s as f32 / i8::MAX as f32));
+            }
+        }
+    }
+}
+
+/// A whole audio file decoded to f32, one buffer per channel.
+pub(crate) struct DecodedAudio {
+    pub channels: Vec<Vec<f32>>,
+    pub sample_rate: u32,
+}
+
+/// Decode any format symphonia reads (WAV, AIFF, FLAC, MP3...) to f32.
+pub(crate) fn decode_audio_file(path: &Path) -> Result<DecodedAudio, String> {
+    let mut all_samples: Vec<Vec<f32>> = Vec::new();
+    let (sample_rate, channels) = decode_audio_blocks(path, |_, block| {
+        all_samples.resize(block.len(), Vec::new());
+        for (out, samples) in all_samples.iter_mut().zip(block) {
+            out.extend_from_slice(samples);
+        }
+    })?;
+    all_samples.resize(channels, Vec::new());
+    Ok(DecodedAudio {
+        channels: all_samples,
+        sample_rate,
+    })
+}
+
+/// Decode like `decode_audio_file`, handing each decoded packet to
+/// `on_block(sample_rate, channels)` instead of keeping the whole file in
+/// memory. Returns the sample rate and channel count.
+pub(crate) fn decode_audio_blocks(
+    path: &Path,
+    mut on_block: impl FnMut(u32, &[Vec<f32>]),
+) -> Result<(u32, usize), String> {
+    let decodable = crate::external_decoder::decodable(path, None)?;
+    let path = decodable.path();
+    let file =
+        fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mss = MediaSourceStream::new(Box::new(file), Default::default());
+    let mut hint = Hint::new();
+    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(ext);
+    }
+    let probed = symphonia::default::get_probe()
+        .format(
+            &hint,
+            mss,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(|_| "Unsupported or unrecognized audio format".to_string())?;
+    let mut format = probed.format;
+    let track = format
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| "No audio track found".to_string())?;
+    let track_id = track.id;
+    let codec_params = track.codec_params.clone();
+    let sample_rate = codec_params
+        .sample_rate
+        .ok_or_else(|| "Could not determine sample rate".to_string())?;
+    let channels = codec_params
+        .channels
+        .ok_or_else(|| "Could not determine channel count".to_string())?
+        .count();
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&codec_params, &DecoderOptions::default())
+        .map_err(|e| format!("Failed to create decoder: {}", e))?;
+
+    let mut block: Vec<Vec<f32>> = vec![Vec::new(); channels];
+    loop {
+        let packet = match format.next_packet() {
+            Ok(p) => p,
+            Err(symphonia::core::errors::Error::IoError(ref e))
+                if e.kind() == std::io::ErrorKind::UnexpectedEof =>
+            {
+                break
+            }
+            Err(e) => return Err(format!("Error reading packet: {}", e)),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let decoded = decoder
+            .decode(&packet)
+            .map_err(|e| format!("Decode error: {}", e))?;
+        block.iter_mut().for_each(Vec::clear);
+        append_decoded(&mut block, decoded);
+        on_block(sample_rate, &block);
+    }
+    Ok((sample_rate, channels))
+}
+
 /// Resample audio with progress reporting and cancellation support
-fn resample_audio_with_progress<F>(
+pub(crate) fn resample_audio_with_progress<F>(
     samples: &[Vec<f32>],
     source_rate: u32,
     target_rate: u32,
@@ -766,11 +1154,12 @@ where
 }
 
 /// Write samples to a WAV file with progress reporting and cancellation support
-fn write_wav_file_with_progress<F>(
+pub(crate) fn write_wav_file_with_progress<F>(
     path: &Path,
     samples: &[Vec<f32>],
     sample_rate: u32,
     bits_per_sample: u16,
+    dither: Dither,
     cancel_token: &Option<Arc<AtomicBool>>,
     progress_callback: F,
 ) -> Result<(), String>
@@ -778,6 +1167,9 @@ where
     F: Fn(f32),
 {
     let channels = samples.len() as u16;
+    let mut quantizers: Vec<Quantizer> = (0..channels as usize)
+        .map(|ch| Quantizer::new(dither, ch))
+        .collect();
 
     let spec = hound::WavSpec {
         channels,
@@ -820,7 +1212,7 @@ where
 
             match bits_per_sample {
                 16 => {
-                    let s = (clamped * i16::MAX as f32) as i16;
+                    let s = quantizers[ch].quantize(clamped);
                     writer
                         .write_sample(s)
                         .map_err(|e| format!("Write error: {}", e))?;
@@ -832,7 +1224,7 @@ where
                         .map_err(|e| format!("Write error: {}", e))?;
                 }
                 _ => {
-                    let s = (clamped * i16::MAX as f32) as i16;
+                    let s = quantizers[ch].quantize(clamped);
                     writer
                         .write_sample(s)
                         .map_err(|e| format!("Write error: {}", e))?;
@@ -919,7 +1311,7 @@ where
 }
 
 /// Copy and convert audio file to Octatrack-compatible format if needed
-fn copy_and_convert_audio(
+pub(crate) fn copy_and_convert_audio(
     source_path: &Path,
     dest_dir: &Path,
     overwrite: bool,
@@ -928,13 +1320,35 @@ fn copy_and_convert_audio(
 }
 
 /// Copy and convert audio file with progress reporting and optional cancellation
-fn copy_and_convert_audio_with_progress<F>(
+pub(crate) fn copy_and_convert_audio_with_progress<F>(
     source_path: &Path,
     dest_dir: &Path,
     overwrite: bool,
     progress_callback: F,
     cancel_token: Option<Arc<AtomicBool>>,
 ) -> Result<PathBuf, String>
+where
+    F: Fn(&str, f32),
+{
+    copy_and_convert_audio_with_options(
+        source_path,
+        dest_dir,
+        overwrite,
+        &ConversionOptions::default(),
+        progress_callback,
+        cancel_token,
+    )
+}
+
+/// Copy and convert audio file, applying the conversion `options`
+pub(crate) fn copy_and_convert_audio_with_options<F>(
+    source_path: &Path,
+    dest_dir: &Path,
+    overwrite: bool,
+    options: &ConversionOptions,
+    progress_callback: F,
+    cancel_token: Option<Arc<AtomicBool>>,
+) -> Result<PathBuf, String>
 where
     F: Fn(&str, f32),
 {
@@ -963,7 +1377,7 @@ where
         // Not an audio file, just copy it directly
         check_cancelled()?;
         progress_callback("copying", 0.0);
-        let dest_file = dest_dir.join(file_name);
+        let dest_file = dest_dir.join(dest_filename_with_options(source_path, options));
         if dest_file.exists() && !overwrite {
             return Err(format!(
                 "File already exists: {}",
@@ -976,24 +1390,16 @@ where
         }
         check_cancelled()?;
         fs::copy(source_path, &dest_file).map_err(|e| format!("Failed to copy file: {}", e))?;
+        if options.verify {
+            crate::transfer_verify::verify_transfer(source_path, &dest_file, None)?;
+        }
         progress_callback("complete", 1.0);
         return Ok(dest_file);
     }
 
     // Determine destination file name (always .wav for converted files)
-    let needs_conv = needs_conversion(source_path);
-    let dest_file_name = if needs_conv {
-        // Change extension to .wav for converted files
-        let stem = source_path
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("audio");
-        format!("{}.wav", stem)
-    } else {
-        file_name_str.to_string()
-    };
-
-    let dest_file = dest_dir.join(&dest_file_name);
+    let needs_conv = needs_conversion_with(source_path, options);
+    let dest_file = dest_dir.join(dest_filename_with_options(source_path, options));
 
     // Check if destination exists
     if dest_file.exists() && !overwrite {
@@ -1009,22 +1415,65 @@ where
             .map_err(|e| format!("Failed to remove existing file: {}", e))?;
     }
 
+    write_converted(
+        source_path,
+        &dest_file,
+        needs_conv,
+        options,
+        progress_callback,
+        &cancel_token,
+    )?;
+
+    Ok(dest_file)
+}
+
+/// Write `source_path` to `dest_file`, converted when `needs_conv`, applying
+/// the conversion `options`. A partial file is removed if conversion fails.
+fn write_converted<F>(
+    source_path: &Path,
+    dest_file: &Path,
+    needs_conv: bool,
+    options: &ConversionOptions,
+    progress_callback: F,
+    cancel_token: &Option<Arc<AtomicBool>>,
+) -> Result<(), String>
+where
+    F: Fn(&str, f32),
+{
+    let check_cancelled = || -> Result<(), String> {
+        if let Some(ref token) = cancel_token {
+            if is_cancelled(token) {
+                return Err("Transfer cancelled".to_string());
+            }
+        }
+        Ok(())
+    };
+
     check_cancelled()?;
 
+    let required = if needs_conv {
+        estimate_converted_size(source_path, options.sample_rate)
+    } else {
+        fs::metadata(source_path).map(|m| m.len()).unwrap_or(0)
+    };
+    let dest_dir = dest_file.parent().unwrap_or(Path::new("."));
+    crate::disk_space::ensure_free_space(dest_dir, required)?;
+
     // Convert or copy based on needs_conversion
     if needs_conv {
         progress_callback("converting", 0.0);
-        let result = convert_to_octatrack_format_with_progress(
+        let result = convert_to_octatrack_format_with_options(
             source_path,
-            &dest_file,
+            dest_file,
+            options,
             &progress_callback,
-            &cancel_token,
+            cancel_token,
         );
 
         // If cancelled or errored, clean up partial file
         if result.is_err() {
             if dest_file.exists() {
-                let _ = fs::remove_file(&dest_file);
+                let _ = fs::remove_file(dest_file);
             }
         }
         result?;
@@ -1032,18 +1481,33 @@ where
         // File is already compatible, just copy
         progress_callback("copying", 0.0);
         check_cancelled()?;
-        fs::copy(source_path, &dest_file).map_err(|e| format!("Failed to copy file: {}", e))?;
+        fs::copy(source_path, dest_file).map_err(|e| format!("Failed to copy file: {}", e))?;
         progress_callback("complete", 1.0);
     }
 
-    Ok(dest_file)
+    if options.verify {
+        let conversion = needs_conv.then_some(options.sample_rate);
+        crate::transfer_verify::verify_transfer(source_path, dest_file, conversion)?;
+    }
+
+    // Slices and attributes travel with the sample
+    crate::sample_attributes::copy_sidecar(source_path, dest_file)?;
+
+    if options.detect_bpm {
+        // Best effort: an undetectable tempo or unwritable .ot doesn't fail the import
+        let _ = crate::audio_analysis::detect_and_write_bpm(dest_file);
+    }
+
+    Ok(())
 }
 
-/// Public function to copy a single file with progress callback and optional cancellation token
-pub fn copy_single_file_with_progress<F>(
+/// Public function to copy a single file with progress callback and optional cancellation token.
+/// Audio files are converted for the Octatrack, applying the conversion `options`.
+pub fn copy_single_file_with_options<F>(
     source_path: &str,
     destination_dir: &str,
     overwrite: bool,
+    options: &ConversionOptions,
     progress_callback: F,
     cancel_token: Option<Arc<AtomicBool>>,
 ) -> Result<String, String>
@@ -1073,16 +1537,19 @@ where
             .file_name()
             .ok_or_else(|| format!("Invalid directory name: {}", source_path))?;
         let dst = dest_dir.join(dir_name);
+        let required = crate::project_manager::dir_size(source).unwrap_or(0);
+        crate::disk_space::ensure_free_space(dest_dir, required)?;
         progress_callback("copying", 0.0);
         copy_dir_recursive_with_conversion(source, &dst)?;
         progress_callback("complete", 1.0);
         return Ok(dst.to_string_lossy().to_string());
     }
 
-    let result = copy_and_convert_audio_with_progress(
+    let result = copy_and_convert_audio_with_options(
         source,
         dest_dir,
         overwrite,
+        options,
         progress_callback,
         cancel_token,
     )?;
@@ -1130,6 +1597,8 @@ fn copy_dir_recursive_with_conversion(src: &Path, dst: &Path) -> Result<(), Stri
         if src_path.is_dir() {
             let dst_path = dst.join(entry.file_name());
             copy_dir_recursive_with_conversion(&src_path, &dst_path)?;
+        } else if is_sidecar_with_sample(&src_path) {
+            continue; // copied with its sample
         } else {
             // Use audio conversion for files (overwrite = true since we already handled removal at top level)
             copy_and_convert_audio(&src_path, dst, true)?;
@@ -1139,13 +1608,112 @@ fn copy_dir_recursive_with_conversion(src: &Path, dst: &Path) -> Result<(), Stri
     Ok(())
 }
 
-/// Copy files from source to destination with optional overwrite
-/// Audio files are automatically converted to Octatrack-compatible format
-pub fn copy_files_with_overwrite(
-    source_paths: Vec<String>,
+/// What to do when a copied file already exists at the destination.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictPolicy {
+    /// Stop the batch with an error (the frontend then asks the user)
+    #[default]
+    Error,
+    /// Leave the existing file alone and report it as skipped
+    Skip,
+    /// Copy under the first free `<stem>_N` name
+    Rename,
+    /// Replace the existing file
+    Overwrite,
+}
+
+/// Result of copying one file under a `ConflictPolicy`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CopyOutcome {
+    pub source: String,
+    pub dest: String,   // the existing file when skipped
+    pub action: String, // "copied", "overwritten", "renamed" or "skipped"
+}
+
+/// Copy one file into `dest_dir`, converting audio, resolving a name
+/// collision with `policy`.
+fn copy_file_with_policy(
+    source: &Path,
+    dest_dir: &Path,
+    policy: ConflictPolicy,
+) -> Result<CopyOutcome, String> {
+    let target = dest_dir.join(dest_filename_for(source));
+    let outcome = |dest: &Path, action: &str| CopyOutcome {
+        source: source.to_string_lossy().to_string(),
+        dest: dest.to_string_lossy().to_string(),
+        action: action.to_string(),
+    };
+    if !target.exists() {
+        let written = copy_and_convert_audio(source, dest_dir, false)?;
+        return Ok(outcome(&written, "copied"));
+    }
+    match policy {
+        ConflictPolicy::Error => Err(format!("File already exists: {}", target.to_string_lossy())),
+        ConflictPolicy::Skip => Ok(outcome(&target, "skipped")),
+        ConflictPolicy::Overwrite => {
+            let written = copy_and_convert_audio(source, dest_dir, true)?;
+            Ok(outcome(&written, "overwritten"))
+        }
+        ConflictPolicy::Rename => {
+            let renamed = next_free_file_name(&target)?;
+            if is_audio_file(&source.to_string_lossy()) {
+                let needs_conv = needs_conversion(source);
+                write_converted(
+                    source,
+                    &renamed,
+                    needs_conv,
+                    &ConversionOptions::default(),
+                    |_: &str, _: f32| {},
+                    &None,
+                )?;
+            } else {
+                fs::copy(source, &renamed).map_err(|e| format!("Failed to copy file: {}", e))?;
+            }
+            Ok(outcome(&renamed, "renamed"))
+        }
+    }
+}
+
+/// Copy the tree under `src` into `dst`, merging with an existing folder and
+/// applying `policy` to every file that collides.
+fn copy_dir_with_policy(
+    src: &Path,
+    dst: &Path,
+    policy: ConflictPolicy,
+    outcomes: &mut Vec<CopyOutcome>,
+) -> Result<(), String> {
+    if !dst.exists() {
+        fs::create_dir(dst)
+            .map_err(|e| format!("Failed to create directory {}: {}", dst.display(), e))?;
+    }
+    let mut entries: Vec<PathBuf> = fs::read_dir(src)
+        .map_err(|e| format!("Failed to read directory {}: {}", src.display(), e))?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .collect();
+    entries.sort();
+    for src_path in entries {
+        if src_path.is_dir() {
+            let dst_path = dst.join(src_path.file_name().unwrap_or_default());
+            copy_dir_with_policy(&src_path, &dst_path, policy, outcomes)?;
+        } else if is_sidecar_with_sample(&src_path) {
+            continue; // copied with its sample
+        } else {
+            outcomes.push(copy_file_with_policy(&src_path, dst, policy)?);
+        }
+    }
+    Ok(())
+}
+
+/// Copy files and folders into `destination_dir`, converting audio for the
+/// Octatrack. Collisions are resolved with `policy`, the same way for files
+/// dropped directly and for files inside dropped folders (which are merged
+/// into a same-named folder). Returns one outcome per file copied.
+pub fn copy_files_with_policy(
+    source_paths: &[String],
     destination_dir: &str,
-    overwrite: bool,
-) -> Result<Vec<String>, String> {
+    policy: ConflictPolicy,
+) -> Result<Vec<CopyOutcome>, String> {
     let dest_path = Path::new(destination_dir);
 
     if !dest_path.exists() {
@@ -1162,61 +1730,84 @@ pub fn copy_files_with_overwrite(
         ));
     }
 
-    let mut copied_files = Vec::new();
-
-    for source in source_paths.iter() {
-        let source_path = Path::new(&source);
+    let mut outcomes = Vec::new();
+    for source in source_paths {
+        let source_path = Path::new(source);
 
         if !source_path.exists() {
             return Err(format!("Source file does not exist: {}", source));
         }
+        if is_selected_sidecar(source_path, source_paths) {
+            continue;
+        }
 
-        // Handle directory vs file copy
         if source_path.is_dir() {
             let file_name = source_path
                 .file_name()
                 .ok_or_else(|| format!("Invalid file name: {}", source))?;
-            let dest_file = dest_path.join(file_name);
-
-            // Check if destination directory already exists
-            if dest_file.exists() && !overwrite {
+            let dest_dir = dest_path.join(file_name);
+            if dest_dir.exists() && policy == ConflictPolicy::Error {
                 return Err(format!(
                     "Directory already exists: {}",
-                    dest_file.to_string_lossy()
+                    dest_dir.to_string_lossy()
                 ));
             }
-
-            // If overwriting, remove existing directory first
-            if dest_file.exists() && overwrite {
-                fs::remove_dir_all(&dest_file)
-                    .map_err(|e| format!("Failed to remove existing directory: {}", e))?;
-            }
-
-            copy_dir_recursive_with_conversion(source_path, &dest_file)?;
-            copied_files.push(dest_file.to_string_lossy().to_string());
+            copy_dir_with_policy(source_path, &dest_dir, policy, &mut outcomes)?;
         } else {
-            // Use audio conversion for files
-            let result_path = copy_and_convert_audio(source_path, dest_path, overwrite)?;
-            copied_files.push(result_path.to_string_lossy().to_string());
+            outcomes.push(copy_file_with_policy(source_path, dest_path, policy)?);
         }
     }
 
-    Ok(copied_files)
+    Ok(outcomes)
+}
+
+/// Copy files from source to destination with optional overwrite
+/// Audio files are automatically converted to Octatrack-compatible format
+/// Returns the path created for each source (folders as a whole).
+pub fn copy_files_with_overwrite(
+    source_paths: Vec<String>,
+    destination_dir: &str,
+    overwrite: bool,
+) -> Result<Vec<String>, String> {
+    let policy = if overwrite {
+        ConflictPolicy::Overwrite
+    } else {
+        ConflictPolicy::Error
+    };
+    copy_files_with_policy(&source_paths, destination_dir, policy)?;
+    Ok(source_paths
+        .iter()
+        .map(|source| {
+            let source = Path::new(source);
+            let dest = Path::new(destination_dir);
+            if source.is_dir() {
+                dest.join(source.file_name().unwrap_or_default())
+            } else {
+                dest.join(dest_filename_for(source))
+            }
+            .to_string_lossy()
+            .to_string()
+        })
+        .collect())
 }
 
 /// Compute the destination filename for a source file (accounting for audio conversion).
 /// Mirrors the logic in `copy_and_convert_audio_with_progress`.
-fn dest_filename_for(source_path: &Path) -> String {
+pub(crate) fn dest_filename_for(source_path: &Path) -> String {
+    dest_filename_with_options(source_path, &ConversionOptions::default())
+}
+
+/// `dest_filename_for`, for a copy made with the conversion `options`.
+pub(crate) fn dest_filename_with_options(
+    source_path: &Path,
+    options: &ConversionOptions,
+) -> String {
     let file_name = source_path
         .file_name()
         .map(|n| n.to_string_lossy().to_string())
         .unwrap_or_default();
 
-    if !is_audio_file(&file_name) {
-        return file_name;
-    }
-
-    if needs_conversion(source_path) {
+    let file_name = if is_audio_file(&file_name) && needs_conversion_with(source_path, options) {
         let stem = source_path
             .file_stem()
             .and_then(|s| s.to_str())
@@ -1224,6 +1815,12 @@ fn dest_filename_for(source_path: &Path) -> String {
         format!("{}.wav", stem)
     } else {
         file_name
+    };
+
+    if options.sanitize_names {
+        crate::file_names::sanitize_file_name(&file_name, crate::file_names::FAT32_MAX_NAME_LEN)
+    } else {
+        file_name
     }
 }
 
@@ -1273,6 +1870,27 @@ pub fn copy_audio_files_or_use_existing(
     Ok(result_paths)
 }
 
+/// True when `path` is an .ot whose sample is also in `selection`, so it is
+/// handled together with the sample.
+fn is_selected_sidecar(path: &Path, selection: &[String]) -> bool {
+    path.extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("ot"))
+        && selection.iter().any(|other| {
+            let other = Path::new(other);
+            has_sample_extension(other) && ot_path_for(other) == path
+        })
+}
+
+/// True when the sample `old` has an .ot that would land on an existing file
+/// if the sample became `new`.
+fn has_sidecar_conflict(old: &Path, new: &Path) -> bool {
+    old.is_file()
+        && has_sample_extension(old)
+        && ot_path_for(old).is_file()
+        && ot_path_for(old) != ot_path_for(new)
+        && ot_path_for(new).exists()
+}
+
 /// Move files from source to destination
 pub fn move_files(source_paths: Vec<String>, destination_dir: &str) -> Result<Vec<String>, String> {
     let dest_path = Path::new(destination_dir);
@@ -1293,9 +1911,12 @@ pub fn move_files(source_paths: Vec<String>, destination_dir: &str) -> Result<Ve
 
     let mut moved_files = Vec::new();
 
-    for source in source_paths {
-        let source_path = Path::new(&source);
+    for source in &source_paths {
+        let source_path = Path::new(source);
 
+        if is_selected_sidecar(source_path, &source_paths) {
+            continue; // moved with its sample
+        }
         if !source_path.exists() {
             return Err(format!("Source file does not exist: {}", source));
         }
@@ -1306,8 +1927,8 @@ pub fn move_files(source_paths: Vec<String>, destination_dir: &str) -> Result<Ve
 
         let dest_file = dest_path.join(file_name);
 
-        // Check if destination file already exists
-        if dest_file.exists() {
+        // Check if destination file (or its .ot) already exists
+        if dest_file.exists() || has_sidecar_conflict(source_path, &dest_file) {
             return Err(format!(
                 "File already exists: {}",
                 dest_file.to_string_lossy()
@@ -1315,6 +1936,9 @@ pub fn move_files(source_paths: Vec<String>, destination_dir: &str) -> Result<Ve
         }
 
         fs::rename(source_path, &dest_file).map_err(|e| format!("Failed to move file: {}", e))?;
+        if dest_file.is_file() {
+            move_sidecar(source_path, &dest_file)?;
+        }
 
         moved_files.push(dest_file.to_string_lossy().to_string());
     }
@@ -1360,7 +1984,7 @@ pub fn rename_file(old_path: &str, new_name: &str) -> Result<String, String> {
 
     let new_path = parent.join(new_name);
 
-    if new_path.exists() {
+    if new_path.exists() || has_sidecar_conflict(old_path, &new_path) {
         return Err(format!(
             "A file or folder with the name '{}' already exists",
             new_name
@@ -1368,15 +1992,207 @@ pub fn rename_file(old_path: &str, new_name: &str) -> Result<String, String> {
     }
 
     fs::rename(old_path, &new_path).map_err(|e| format!("Failed to rename: {}", e))?;
+    if new_path.is_file() {
+        move_sidecar(old_path, &new_path)?;
+    }
 
     Ok(new_path.to_string_lossy().to_string())
 }
 
+/// Streaming 64-bit FNV-1a hash of a file's contents. Stable across runs and
+/// platforms, so it can be stored and compared later (unlike `DefaultHasher`).
+pub fn file_content_hash(path: &Path) -> Result<u64, String> {
+    let file =
+        fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    content_hash(BufReader::new(file))
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+}
+
+/// `file_content_hash` of anything readable, such as a zip entry.
+pub(crate) fn content_hash(mut reader: impl std::io::Read) -> std::io::Result<u64> {
+    let mut buf = [0u8; 64 * 1024];
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    loop {
+        let n = reader.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        for &b in &buf[..n] {
+            hash ^= b as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    Ok(hash)
+}
+
+/// True when two files have identical contents (size check first, then hash).
+pub(crate) fn same_file_contents(a: &Path, b: &Path) -> Result<bool, String> {
+    let len_a = fs::metadata(a).map_err(|e| e.to_string())?.len();
+    let len_b = fs::metadata(b).map_err(|e| e.to_string())?.len();
+    if len_a != len_b {
+        return Ok(false);
+    }
+    Ok(file_content_hash(a)? == file_content_hash(b)?)
+}
+
+/// First free `{stem}_{n}.{ext}` name (n = 2, 3, …) next to `path`.
+pub(crate) fn next_free_file_name(path: &Path) -> Result<PathBuf, String> {
+    let parent = path
+        .parent()
+        .ok_or_else(|| "Cannot determine parent directory".to_string())?;
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let ext = path
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+    for n in 2u32..=999 {
+        let candidate = parent.join(format!("{}_{}{}", stem, n, ext));
+        if !candidate.exists() {
+            return Ok(candidate);
+        }
+    }
+    Err(format!(
+        "Could not find an available name for {} (tried up to _999)",
+        path.display()
+    ))
+}
+
+/// One file handled by `merge_pools`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PoolMergeEntry {
+    pub source_path: String,
+    pub dest_path: String,
+    pub relative_path: String, // path inside the pool, '/'-separated
+    pub status: String,        // "copied", "identical" (already present) or "renamed"
+}
+
+/// Outcome of a pool merge. `entries` is the mapping report: every entry with
+/// status "renamed" must be applied to projects that referenced the source file
+/// (see `apply_pool_merge_mapping`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PoolMergeReport {
+    pub entries: Vec<PoolMergeEntry>,
+    pub copied: u32,
+    pub identical: u32,
+    pub renamed: u32,
+}
+
+/// Merge the AUDIO pool at `src_root` into the pool at `dst_root`, keeping the
+/// sub-folder layout. A file already present in the destination with the same
+/// content is reused; one with the same name but different content is copied
+/// under a free `_N` suffix and reported as "renamed". Files are copied as-is
+/// (no conversion) and hidden files (`.DS_Store`, `._*`) are skipped.
+pub fn merge_pools(src_root: &str, dst_root: &str) -> Result<PoolMergeReport, String> {
+    let src = Path::new(src_root);
+    let dst = Path::new(dst_root);
+    if !src.is_dir() {
+        return Err(format!("Source pool does not exist: {}", src_root));
+    }
+    if !dst.is_dir() {
+        return Err(format!("Destination pool does not exist: {}", dst_root));
+    }
+    if fs::canonicalize(src).ok() == fs::canonicalize(dst).ok() {
+        return Err("Source and destination pools are the same directory".to_string());
+    }
+
+    let mut report = PoolMergeReport {
+        entries: Vec::new(),
+        copied: 0,
+        identical: 0,
+        renamed: 0,
+    };
+
+    let walker = walkdir::WalkDir::new(src)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.'));
+    for entry in walker {
+        let entry = entry.map_err(|e| format!("Failed to scan source pool: {}", e))?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let rel = entry
+            .path()
+            .strip_prefix(src)
+            .map_err(|e| e.to_string())?
+            .to_path_buf();
+        let mut target = dst.join(&rel);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+
+        let status = if !target.exists() {
+            "copied"
+        } else if same_file_contents(entry.path(), &target)? {
+            "identical"
+        } else {
+            target = next_free_file_name(&target)?;
+            "renamed"
+        };
+
+        if status != "identical" {
+            fs::copy(entry.path(), &target)
+                .map_err(|e| format!("Failed to copy {}: {}", entry.path().display(), e))?;
+        }
+        match status {
+            "copied" => report.copied += 1,
+            "identical" => report.identical += 1,
+            _ => report.renamed += 1,
+        }
+        report.entries.push(PoolMergeEntry {
+            source_path: entry.path().to_string_lossy().to_string(),
+            dest_path: target.to_string_lossy().to_string(),
+            relative_path: rel.to_string_lossy().replace('\\', "/"),
+            status: status.to_string(),
+        });
+    }
+
+    Ok(report)
+}
+
+/// (old, new) absolute path pairs, inside `dst_root`, for every renamed entry of
+/// a merge report: projects moved next to the destination pool still point at
+/// the original name and must be repointed to the renamed copy.
+pub fn pool_merge_renames(dst_root: &str, entries: &[PoolMergeEntry]) -> Vec<(String, String)> {
+    entries
+        .iter()
+        .filter(|e| e.status == "renamed")
+        .map(|e| {
+            let original = Path::new(dst_root).join(&e.relative_path);
+            (original.to_string_lossy().to_string(), e.dest_path.clone())
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use tempfile::TempDir;
 
+    fn copy_single_file_with_progress<F>(
+        source_path: &str,
+        destination_dir: &str,
+        overwrite: bool,
+        progress_callback: F,
+        cancel_token: Option<Arc<AtomicBool>>,
+    ) -> Result<String, String>
+    where
+        F: Fn(&str, f32) + Send + 'static,
+    {
+        copy_single_file_with_options(
+            source_path,
+            destination_dir,
+            overwrite,
+            &ConversionOptions::default(),
+            progress_callback,
+            cancel_token,
+        )
+    }
+
     #[test]
     fn test_collect_audio_files_recursive_walks_subdirs_and_skips_non_audio() {
         let tmp = TempDir::new().unwrap();
@@ -1829,6 +2645,51 @@ mod tests {
         assert!(dest_dir.path().join("subdir/file.txt").exists());
     }
 
+    #[test]
+    fn test_copy_files_with_conflict_policies() {
+        let source_dir = TempDir::new().unwrap();
+        let dest_dir = TempDir::new().unwrap();
+        let dest = dest_dir.path().to_string_lossy().to_string();
+        let kit = source_dir.path().join("kit");
+        fs::create_dir(&kit).unwrap();
+        fs::write(kit.join("notes.txt"), "new").unwrap();
+        fs::write(kit.join("extra.txt"), "extra").unwrap();
+        create_test_wav(&kit.join("kick.wav"), 44100, 16, 100);
+        let sources = vec![kit.to_string_lossy().to_string()];
+        let existing = dest_dir.path().join("kit");
+        fs::create_dir(&existing).unwrap();
+        fs::write(existing.join("notes.txt"), "old").unwrap();
+        fs::write(existing.join("kick.wav"), "old").unwrap();
+
+        assert!(copy_files_with_policy(&sources, &dest, ConflictPolicy::Error).is_err());
+
+        let skipped = copy_files_with_policy(&sources, &dest, ConflictPolicy::Skip).unwrap();
+        let actions: Vec<&str> = skipped.iter().map(|o| o.action.as_str()).collect();
+        assert_eq!(actions, vec!["copied", "skipped", "skipped"]); // extra, kick, notes
+        assert_eq!(
+            fs::read_to_string(existing.join("notes.txt")).unwrap(),
+            "old"
+        );
+
+        let renamed = copy_files_with_policy(&sources, &dest, ConflictPolicy::Rename).unwrap();
+        assert!(renamed.iter().all(|o| o.action == "renamed"));
+        assert!(existing.join("notes_2.txt").exists());
+        assert!(existing.join("extra_2.txt").exists());
+        assert!(hound::WavReader::open(existing.join("kick_2.wav")).is_ok());
+        assert_eq!(
+            fs::read_to_string(existing.join("notes.txt")).unwrap(),
+            "old"
+        );
+
+        let replaced = copy_files_with_policy(&sources, &dest, ConflictPolicy::Overwrite).unwrap();
+        assert!(replaced.iter().all(|o| o.action == "overwritten"));
+        assert_eq!(
+            fs::read_to_string(existing.join("notes.txt")).unwrap(),
+            "new"
+        );
+        assert!(existing.join("notes_2.txt").exists()); // merged, not replaced wholesale
+    }
+
     // ==================== MOVE FILES TESTS ====================
 
     #[test]
@@ -2645,6 +3506,183 @@ mod tests {
         assert_eq!(reader.spec().sample_rate, 44100);
     }
 
+    #[test]
+    fn test_copy_with_downmix_writes_mono() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_path = temp_dir.path().join("stereo.wav");
+        let dest_dir = temp_dir.path().join("dest");
+        fs::create_dir(&dest_dir).unwrap();
+
+        // Already compatible, so it is only rewritten because of the downmix
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 44100,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&source_path, spec).unwrap();
+        for _ in 0..1000 {
+            writer.write_sample(16384i16).unwrap();
+            writer.write_sample(16384i16).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let options = ConversionOptions {
+            downmix_to_mono: true,
+            downmix_law_db: Some(-6.0),
+            ..Default::default()
+        };
+        assert!(!needs_conversion(&source_path));
+        assert!(needs_conversion_with(&source_path, &options));
+
+        let dest = copy_single_file_with_options(
+            source_path.to_str().unwrap(),
+            dest_dir.to_str().unwrap(),
+            false,
+            &options,
+            |_, _| {},
+            None,
+        )
+        .unwrap();
+
+        let mut reader = hound::WavReader::open(&dest).unwrap();
+        assert_eq!(reader.spec().channels, 1);
+        assert_eq!(reader.spec().sample_rate, 44100);
+        let first = reader.samples::<i16>().next().unwrap().unwrap();
+        assert!((first as i32 - 16384).abs() < 200, "got {}", first);
+
+        // Default law is -3 dB: equal channels come out ~1.41x louder than each input
+        let louder = downmix_to_mono(vec![vec![0.25; 4], vec![0.25; 4]], -3.0);
+        assert_eq!(louder.len(), 1);
+        assert!((louder[0][0] - 0.354).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_apply_fades_ramps_both_ends() {
+        // 10 ms at 1 kHz = 10 frames each side
+        let mut samples = vec![vec![1.0f32; 100]];
+        apply_fades(&mut samples, 1000, 10, 10);
+        let ch = &samples[0];
+        assert_eq!(ch[0], 0.0);
+        assert!((ch[5] - 0.5).abs() < 1e-6);
+        assert_eq!(ch[10], 1.0);
+        assert_eq!(ch[89], 1.0);
+        assert!((ch[94] - 0.5).abs() < 1e-6);
+        assert_eq!(ch[99], 0.0);
+
+        // Fades longer than the sample are clamped instead of panicking
+        let mut short = vec![vec![1.0f32; 4]];
+        apply_fades(&mut short, 44100, 500, 0);
+        assert_eq!(short[0][0], 0.0);
+
+        let tmp = TempDir::new().unwrap();
+        let wav = tmp.path().join("ok.wav");
+        create_test_wav(&wav, 44100, 16, 100);
+        let fade = ConversionOptions {
+            fade_out_ms: Some(5),
+            ..Default::default()
+        };
+        assert!(needs_conversion_with(&wav, &fade));
+        assert!(!needs_conversion_with(&wav, &ConversionOptions::default()));
+    }
+
+    #[test]
+    fn test_conversion_respects_target_sample_rate() {
+        let tmp = TempDir::new().unwrap();
+        let wav = tmp.path().join("48k.wav");
+        create_test_wav(&wav, 48000, 16, 4800);
+
+        let keep = ConversionOptions {
+            sample_rate: SampleRateTarget::Source,
+            ..Default::default()
+        };
+        assert!(needs_conversion_with(&wav, &ConversionOptions::default()));
+        assert!(!needs_conversion_with(&wav, &keep));
+
+        let fixed = ConversionOptions {
+            sample_rate: SampleRateTarget::Fixed(22050),
+            verify: true,
+            ..Default::default()
+        };
+        assert!(needs_conversion_with(&wav, &fixed));
+        let dest = tmp.path().join("22k.wav");
+        write_converted(&wav, &dest, true, &fixed, |_: &str, _: f32| {}, &None).unwrap();
+        let reader = hound::WavReader::open(&dest).unwrap();
+        assert_eq!(reader.spec().sample_rate, 22050);
+        assert!((reader.duration() as i64 - 2205).abs() <= 2);
+    }
+
+    #[test]
+    fn test_conversion_applies_gain() {
+        let tmp = TempDir::new().unwrap();
+        let wav = tmp.path().join("hot.wav");
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 44100,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&wav, spec).unwrap();
+        for i in 0..100 {
+            writer
+                .write_sample(if i % 2 == 0 { 16000i16 } else { -16000 })
+                .unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let convert = |gain_db: f32| {
+            let options = ConversionOptions {
+                gain_db: Some(gain_db),
+                ..Default::default()
+            };
+            assert!(needs_conversion_with(&wav, &options));
+            let dest = tmp.path().join(format!("{}.wav", gain_db));
+            write_converted(&wav, &dest, true, &options, |_: &str, _: f32| {}, &None).unwrap();
+            hound::WavReader::open(&dest)
+                .unwrap()
+                .into_samples::<i16>()
+                .map(|s| s.unwrap())
+                .collect::<Vec<_>>()
+        };
+        let quieter = convert(-6.0206);
+        assert!((quieter[0] - 8000).abs() <= 2, "{}", quieter[0]);
+        assert!((quieter[1] + 8000).abs() <= 2, "{}", quieter[1]);
+        // Pushed past full scale: clipped, not wrapped around
+        let louder = convert(12.0);
+        assert_eq!(louder[0], i16::MAX);
+        assert!(louder[1] <= -i16::MAX);
+    }
+
+    #[test]
+    fn test_dither_keeps_signal_below_one_lsb() {
+        // A 1 kHz tone at 0.4 LSB truncates to silence without dither
+        let lsb = 1.0 / i16::MAX as f32;
+        let tone: Vec<f32> = (0..4410)
+            .map(|i| 0.4 * lsb * (i as f32 * 2.0 * std::f32::consts::PI / 44.1).sin())
+            .collect();
+        let tmp = TempDir::new().unwrap();
+        let read = |dither: Dither| {
+            let path = tmp.path().join(format!("{:?}.wav", dither));
+            write_wav_file_with_progress(&path, &[tone.clone()], 44100, 16, dither, &None, |_| {})
+                .unwrap();
+            hound::WavReader::open(&path)
+                .unwrap()
+                .into_samples::<i16>()
+                .map(|s| s.unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        assert!(read(Dither::None).iter().all(|&s| s == 0));
+        for mode in [Dither::Tpdf, Dither::Shaped] {
+            let out = read(mode);
+            assert!(out.iter().all(|s| s.abs() <= 3));
+            // The tone survives as a bias in the dithered output
+            let correlation: f32 = out.iter().zip(&tone).map(|(&o, &t)| o as f32 * t).sum();
+            assert!(correlation > 0.0, "{:?}", mode);
+        }
+        assert_eq!(read(Dither::Tpdf), read(Dither::Tpdf));
+    }
+
     #[test]
     fn test_copy_22050hz_wav() {
         let temp_dir = TempDir::new().unwrap();
@@ -2994,4 +4032,56 @@ mod tests {
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("does not exist"));
     }
+
+    #[test]
+    fn test_merge_pools_copies_dedups_and_renames() {
+        let src = TempDir::new().unwrap();
+        let dst = TempDir::new().unwrap();
+        fs::create_dir_all(src.path().join("drums")).unwrap();
+        fs::write(src.path().join("drums/kick.wav"), b"kick-a").unwrap();
+        fs::write(src.path().join("pad.wav"), b"same").unwrap();
+        fs::write(src.path().join("new.wav"), b"new").unwrap();
+        fs::write(src.path().join(".DS_Store"), b"junk").unwrap();
+        fs::create_dir_all(dst.path().join("drums")).unwrap();
+        fs::write(dst.path().join("drums/kick.wav"), b"kick-b").unwrap();
+        fs::write(dst.path().join("pad.wav"), b"same").unwrap();
+
+        let report =
+            merge_pools(&src.path().to_string_lossy(), &dst.path().to_string_lossy()).unwrap();
+
+        assert_eq!((report.copied, report.identical, report.renamed), (1, 1, 1));
+        assert_eq!(
+            fs::read(dst.path().join("drums/kick.wav")).unwrap(),
+            b"kick-b"
+        );
+        assert_eq!(
+            fs::read(dst.path().join("drums/kick_2.wav")).unwrap(),
+            b"kick-a"
+        );
+        assert_eq!(fs::read(dst.path().join("new.wav")).unwrap(), b"new");
+        assert!(!dst.path().join(".DS_Store").exists());
+
+        let renames = pool_merge_renames(&dst.path().to_string_lossy(), &report.entries);
+        assert_eq!(renames.len(), 1);
+        assert!(renames[0].0.ends_with("kick.wav"));
+        assert!(renames[0].1.ends_with("kick_2.wav"));
+    }
+
+    #[test]
+    fn test_merge_pools_rejects_same_directory() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().to_string_lossy().to_string();
+        assert!(merge_pools(&path, &path).is_err());
+    }
+
+    #[test]
+    fn test_file_content_hash_is_content_based() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("a"), b"abc").unwrap();
+        fs::write(dir.path().join("b"), b"abc").unwrap();
+        fs::write(dir.path().join("c"), b"abd").unwrap();
+        let h = |n: &str| file_content_hash(&dir.path().join(n)).unwrap();
+        assert_eq!(h("a"), h("b"));
+        assert_ne!(h("a"), h("c"));
+    }
 }
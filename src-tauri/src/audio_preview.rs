@@ -0,0 +1,422 @@
+// Native sample preview: plays pool files and slot samples through the
+// system's default output with rodio, independent of the webview's <audio>
+// element (see `audio_stream`), which some Linux GStreamer setups can't play.
+//
+// rodio's output stream can't leave the thread that opened it, so one player
+// thread owns it and takes commands over a channel. Files are decoded with the
+// same decoder as conversions, so anything the app can import can be
+// auditioned. While playing, the thread emits "preview-position" events for
+// the playhead, and a last one with `playing: false` when playback ends.
+
+use crate::audio_pool::decode_audio_file;
+use once_cell::sync::Lazy;
+use rodio::buffer::SamplesBuffer;
+use rodio::{OutputStream, Sink};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+const POSITION_INTERVAL: Duration = Duration::from_millis(50);
+const MAX_VOLUME: f32 = 2.0;
+
+static PLAYER: Lazy<Mutex<Option<Sender<PlayerCommand>>>> = Lazy::new(|| Mutex::new(None));
+/// Current volume (f32 bits), kept across plays and before the first one.
+static VOLUME: AtomicU32 = AtomicU32::new(0x3F80_0000); // 1.0
+
+/// A decoded file ready to play: interleaved samples.
+#[derive(Clone)]
+struct PreviewAudio {
+    path: String,
+    samples: std::sync::Arc<Vec<f32>>,
+    channels: u16,
+    sample_rate: u32,
+}
+
+impl PreviewAudio {
+    fn duration(&self) -> f64 {
+        let frames = self.samples.len() / self.channels.max(1) as usize;
+        frames as f64 / self.sample_rate as f64
+    }
+}
+
+enum PlayerCommand {
+    Play(PreviewAudio, f64, Sender<Result<(), String>>),
+    Stop,
+    Pause,
+    Resume,
+    Seek(f64),
+    Volume(f32),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreviewInfo {
+    pub path: String,
+    pub duration_seconds: f64,
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreviewPosition {
+    pub path: String,
+    pub position_seconds: f64,
+    pub duration_seconds: f64,
+    pub playing: bool,
+}
+
+/// Interleave per-channel buffers (as decoded) into rodio's frame order.
+fn interleave(channels: &[Vec<f32>]) -> Vec<f32> {
+    let frames = channels.iter().map(Vec::len).min().unwrap_or(0);
+    let mut out = Vec::with_capacity(frames * channels.len());
+    for frame in 0..frames {
+        out.extend(channels.iter().map(|ch| ch[frame]));
+    }
+    out
+}
+
+/// Playhead of the current sound: where playback (re)started and for how
+/// long it has been running since, pauses excluded.
+struct Playhead {
+    start: f64,
+    resumed_at: Option<Instant>,
+    played: Duration,
+}
+
+impl Playhead {
+    fn new(start: f64) -> Self {
+        Playhead {
+            start,
+            resumed_at: Some(Instant::now()),
+            played: Duration::ZERO,
+        }
+    }
+
+    fn position(&self) -> f64 {
+        let running = self.resumed_at.map(|t| t.elapsed()).unwrap_or_default();
+        self.start + (self.played + running).as_secs_f64()
+    }
+
+    fn pause(&mut self) {
+        if let Some(t) = self.resumed_at.take() {
+            self.played += t.elapsed();
+        }
+    }
+
+    fn resume(&mut self) {
+        self.resumed_at.get_or_insert_with(Instant::now);
+    }
+}
+
+/// Samples of `audio` from `start` seconds on.
+fn source_from(audio: &PreviewAudio, start: f64) -> SamplesBuffer<f32> {
+    let channels = audio.channels.max(1) as usize;
+    let frame = (start.max(0.0) * audio.sample_rate as f64) as usize;
+    let offset = (frame * channels).min(audio.samples.len());
+    SamplesBuffer::new(
+        audio.channels,
+        audio.sample_rate,
+        audio.samples[offset..].to_vec(),
+    )
+}
+
+fn run_player(app: AppHandle, commands: Receiver<PlayerCommand>) {
+    // Opened on the first Play, so a machine without audio output only fails
+    // when something is actually played.
+    let mut output: Option<(OutputStream, Sink)> = None;
+    let mut current: Option<(PreviewAudio, Playhead)> = None;
+
+    let emit = |audio: &PreviewAudio, position: f64, playing: bool| {
+        let _ = app.emit(
+            "preview-position",
+            PreviewPosition {
+                path: audio.path.clone(),
+                position_seconds: position.min(audio.duration()),
+                duration_seconds: audio.duration(),
+                playing,
+            },
+        );
+    };
+
+    loop {
+        match commands.recv_timeout(POSITION_INTERVAL) {
+            Ok(PlayerCommand::Play(audio, start, reply)) => {
+                if output.is_none() {
+                    let opened = OutputStream::try_default()
+                        .map_err(|e| format!("No audio output available: {}", e))
+                        .and_then(|(stream, handle)| {
+                            Sink::try_new(&handle)
+                                .map(|sink| (stream, sink))
+                                .map_err(|e| format!("Failed to open audio output: {}", e))
+                        });
+                    match opened {
+                        Ok(o) => output = Some(o),
+                        Err(e) => {
+                            let _ = reply.send(Err(e));
+                            continue;
+                        }
+                    }
+                }
+                let (_, sink) = output.as_ref().unwrap();
+                sink.stop();
+                sink.set_volume(f32::from_bits(VOLUME.load(Ordering::Relaxed)));
+                sink.append(source_from(&audio, start));
+                sink.play();
+                current = Some((audio, Playhead::new(start)));
+                let _ = reply.send(Ok(()));
+            }
+            Ok(PlayerCommand::Stop) => {
+                if let Some((_, sink)) = &output {
+                    sink.stop();
+                }
+                if let Some((audio, playhead)) = current.take() {
+                    emit(&audio, playhead.position(), false);
+                }
+            }
+            Ok(PlayerCommand::Pause) => {
+                if let (Some((_, sink)), Some((_, playhead))) = (&output, &mut current) {
+                    sink.pause();
+                    playhead.pause();
+                }
+            }
+            Ok(PlayerCommand::Resume) => {
+                if let (Some((_, sink)), Some((_, playhead))) = (&output, &mut current) {
+                    sink.play();
+                    playhead.resume();
+                }
+            }
+            Ok(PlayerCommand::Seek(position)) => {
+                if let (Some((_, sink)), Some((audio, playhead))) = (&output, &mut current) {
+                    let position = position.clamp(0.0, audio.duration());
+                    let paused = sink.is_paused();
+                    sink.stop();
+                    sink.append(source_from(audio, position));
+                    *playhead = Playhead::new(position);
+                    if paused {
+                        sink.pause();
+                        playhead.pause();
+                    } else {
+                        sink.play();
+                    }
+                }
+            }
+            Ok(PlayerCommand::Volume(v)) => {
+                if let Some((_, sink)) = &output {
+                    sink.set_volume(v);
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+
+        // Playhead updates, and the end of playback.
+        let mut finished = false;
+        if let (Some((_, sink)), Some((audio, playhead))) = (&output, &current) {
+            if sink.empty() {
+                emit(audio, audio.duration(), false);
+                finished = true;
+            } else if !sink.is_paused() {
+                emit(audio, playhead.position(), true);
+            }
+        }
+        if finished {
+            current = None;
+        }
+    }
+}
+
+fn send(command: PlayerCommand) -> Result<(), String> {
+    let player = PLAYER.lock().unwrap_or_else(|e| e.into_inner());
+    match player.as_ref() {
+        Some(sender) => sender
+            .send(command)
+            .map_err(|_| "Audio preview stopped unexpectedly".to_string()),
+        None => Ok(()), // nothing has been played yet
+    }
+}
+
+fn player(app: &AppHandle) -> Sender<PlayerCommand> {
+    let mut player = PLAYER.lock().unwrap_or_else(|e| e.into_inner());
+    player
+        .get_or_insert_with(|| {
+            let (sender, receiver) = mpsc::channel();
+            let app = app.clone();
+            std::thread::spawn(move || run_player(app, receiver));
+            sender
+        })
+        .clone()
+}
+
+fn load(path: &Path) -> Result<PreviewAudio, String> {
+    let decoded = decode_audio_file(path)?;
+    if decoded.channels.is_empty() || decoded.sample_rate == 0 {
+        return Err(format!("{} holds no audio", path.display()));
+    }
+    Ok(PreviewAudio {
+        path: path.to_string_lossy().to_string(),
+        channels: decoded.channels.len() as u16,
+        sample_rate: decoded.sample_rate,
+        samples: std::sync::Arc::new(interleave(&decoded.channels)),
+    })
+}
+
+/// File loaded in a sample slot (`slot_type` "static" or "flex", 1-based).
+fn slot_file(project_path: &str, slot_type: &str, slot_index: u16) -> Result<PathBuf, String> {
+    let project_dir = Path::new(project_path);
+    let project_file = ["project.work", "project.strd"]
+        .iter()
+        .map(|name| project_dir.join(name))
+        .find(|p| p.exists())
+        .ok_or_else(|| "No project file found".to_string())?;
+    let fields = crate::project_reader::read_raw_sample_fields(&project_file)?;
+    let path = fields
+        .get(&(slot_type.to_uppercase(), slot_index))
+        .and_then(|f| f.get("PATH"))
+        .filter(|p| !p.is_empty())
+        .ok_or_else(|| format!("{} slot {} holds no sample", slot_type, slot_index))?;
+    Ok(crate::project_reader::normalize_path_lexically(
+        &project_dir.join(path.replace('\\', "/")),
+    ))
+}
+
+fn play(app: AppHandle, path: PathBuf, start_seconds: Option<f64>) -> Result<PreviewInfo, String> {
+    crate::fs_scope::ensure_allowed(&path.to_string_lossy())?;
+    let audio = load(&path)?;
+    let info = PreviewInfo {
+        path: audio.path.clone(),
+        duration_seconds: audio.duration(),
+        sample_rate: audio.sample_rate,
+        channels: audio.channels,
+    };
+    let (reply, result) = mpsc::channel();
+    player(&app)
+        .send(PlayerCommand::Play(
+            audio,
+            start_seconds.unwrap_or(0.0),
+            reply,
+        ))
+        .map_err(|_| "Audio preview stopped unexpectedly".to_string())?;
+    result
+        .recv()
+        .map_err(|_| "Audio preview stopped unexpectedly".to_string())??;
+    Ok(info)
+}
+
+/// Play an audio file, replacing whatever is playing.
+#[tauri::command]
+pub async fn preview_play(
+    app: AppHandle,
+    path: String,
+    start_seconds: Option<f64>,
+) -> Result<PreviewInfo, String> {
+    tauri::async_runtime::spawn_blocking(move || play(app, PathBuf::from(path), start_seconds))
+        .await
+        .unwrap()
+}
+
+/// Play the sample loaded in a project slot.
+#[tauri::command]
+pub async fn preview_play_slot(
+    app: AppHandle,
+    project_path: String,
+    slot_type: String,
+    slot_index: u16,
+) -> Result<PreviewInfo, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let path = slot_file(&project_path, &slot_type, slot_index)?;
+        play(app, path, None)
+    })
+    .await
+    .unwrap()
+}
+
+#[tauri::command]
+pub fn preview_stop() -> Result<(), String> {
+    send(PlayerCommand::Stop)
+}
+
+#[tauri::command]
+pub fn preview_pause() -> Result<(), String> {
+    send(PlayerCommand::Pause)
+}
+
+#[tauri::command]
+pub fn preview_resume() -> Result<(), String> {
+    send(PlayerCommand::Resume)
+}
+
+#[tauri::command]
+pub fn preview_seek(position_seconds: f64) -> Result<(), String> {
+    if !position_seconds.is_finite() {
+        return Err("Invalid position".to_string());
+    }
+    send(PlayerCommand::Seek(position_seconds))
+}
+
+/// `volume` is linear gain: 0.0 (silent) to 2.0, 1.0 = unchanged.
+#[tauri::command]
+pub fn preview_set_volume(volume: f32) -> Result<(), String> {
+    if !(0.0..=MAX_VOLUME).contains(&volume) {
+        return Err(format!("Volume must be between 0 and {}", MAX_VOLUME));
+    }
+    VOLUME.store(volume.to_bits(), Ordering::Relaxed);
+    send(PlayerCommand::Volume(volume))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ot_tools_io::projects::SlotAttributes;
+    use ot_tools_io::settings::SlotType;
+    use ot_tools_io::{OctatrackFileIO, ProjectFile};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_interleave_and_offset() {
+        let samples = interleave(&[vec![0.1, 0.2, 0.3], vec![-0.1, -0.2, -0.3]]);
+        assert_eq!(samples, vec![0.1, -0.1, 0.2, -0.2, 0.3, -0.3]);
+
+        let audio = PreviewAudio {
+            path: "x.wav".to_string(),
+            samples: std::sync::Arc::new(samples),
+            channels: 2,
+            sample_rate: 2,
+        };
+        assert_eq!(audio.duration(), 1.5);
+        // One second in = frame 2, past the end = nothing left.
+        assert_eq!(
+            rodio::Source::total_duration(&source_from(&audio, 1.0)),
+            Some(Duration::from_millis(500))
+        );
+        assert_eq!(source_from(&audio, 9.0).count(), 0);
+    }
+
+    #[test]
+    fn test_slot_file_resolves_project_relative_path() {
+        let dir = TempDir::new().unwrap();
+        let project = dir.path().join("SONG");
+        std::fs::create_dir_all(&project).unwrap();
+        let mut file = ProjectFile::default();
+        file.slots.flex_slots[2] = Some(
+            SlotAttributes::new(
+                SlotType::Flex,
+                3,
+                Some(PathBuf::from("../AUDIO/kick.wav")),
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap(),
+        );
+        file.to_data_file(&project.join("project.work")).unwrap();
+
+        let path = slot_file(&project.to_string_lossy(), "flex", 3).unwrap();
+        assert_eq!(path, dir.path().join("AUDIO/kick.wav"));
+        assert!(slot_file(&project.to_string_lossy(), "flex", 4).is_err());
+    }
+}
@@ -0,0 +1,497 @@
+// Sample streaming for in-browser playback: a small HTTP server on 127.0.0.1
+// that serves audio files with Range support, so the frontend's <audio> element
+// can seek through long files without loading them fully. A plain localhost URL
+// also works where custom schemes don't (webkit2gtk hands <audio> to GStreamer,
+// which bypasses Tauri's scheme handlers; see `read_audio_file`).
+//
+// Only files registered through `get_stream_url` (which applies the filesystem
+// scope) are served, under a random per-session token, and only the app's own
+// webview origin is allowed to read them cross-origin. WAV files are served
+// as-is; other formats are decoded once into a WAV in the temp dir.
+
+use once_cell::sync::Lazy;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, UNIX_EPOCH};
+
+static SERVER: Lazy<Mutex<Option<StreamServer>>> = Lazy::new(|| Mutex::new(None));
+
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// A client that stalls longer than this (mid-headers or not reading the
+/// body) is dropped, so idle connections don't pin a thread each.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Connections served at once; more are answered 503 right away. A player
+/// opens one or two per file, so this only caps runaway clients.
+const MAX_CONNECTIONS: usize = 16;
+
+/// Largest request line plus headers read from a client; a request with more
+/// is answered 431.
+const MAX_HEAD_BYTES: u64 = 8 * 1024;
+
+/// Origins of the app's webview: the bundled frontend on macOS/Linux and on
+/// Windows, plus the dev server (tauri.conf.json `devUrl`) in debug builds.
+fn is_app_origin(origin: &str) -> bool {
+    matches!(origin, "tauri://localhost" | "http://tauri.localhost")
+        || (cfg!(debug_assertions) && origin == "http://localhost:1420")
+}
+
+/// Running server: its port, URL token and the files it may serve (id -> path).
+#[derive(Clone)]
+struct StreamServer {
+    port: u16,
+    token: String,
+    files: Arc<Mutex<HashMap<String, PathBuf>>>,
+}
+
+fn random_hex() -> String {
+    // RandomState is seeded from the OS RNG.
+    let a = RandomState::new().build_hasher().finish();
+    let b = RandomState::new().build_hasher().finish();
+    format!("{:016x}{:016x}", a, b)
+}
+
+/// Stable id of a file version: path, size and modification time.
+fn file_id(path: &Path) -> Result<String, String> {
+    let meta =
+        fs::metadata(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let modified = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    path.hash(&mut hasher);
+    meta.len().hash(&mut hasher);
+    modified.hash(&mut hasher);
+    Ok(format!("{:016x}", hasher.finish()))
+}
+
+fn start_server() -> Result<StreamServer, String> {
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .map_err(|e| format!("Failed to start audio stream server: {}", e))?;
+    let port = listener
+        .local_addr()
+        .map_err(|e| format!("Failed to start audio stream server: {}", e))?
+        .port();
+    let server = StreamServer {
+        port,
+        token: random_hex(),
+        files: Arc::new(Mutex::new(HashMap::new())),
+    };
+    let handler = server.clone();
+    let active = Arc::new(AtomicUsize::new(0));
+    std::thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            if active.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+                active.fetch_sub(1, Ordering::SeqCst);
+                let _ = stream.set_write_timeout(Some(Duration::from_secs(1)));
+                let _ = respond_status(&mut stream, "503 Service Unavailable");
+                continue;
+            }
+            let handler = handler.clone();
+            let active = active.clone();
+            std::thread::spawn(move || {
+                if let Err(e) = handler.handle(stream) {
+                    eprintln!("Audio stream request failed: {}", e);
+                }
+                active.fetch_sub(1, Ordering::SeqCst);
+            });
+        }
+    });
+    Ok(server)
+}
+
+fn server() -> Result<StreamServer, String> {
+    let mut server = SERVER.lock().unwrap_or_else(|e| e.into_inner());
+    if server.is_none() {
+        *server = Some(start_server()?);
+    }
+    Ok(server.clone().unwrap())
+}
+
+/// Parse a `Range: bytes=...` value against a file of `len` bytes into an
+/// inclusive (start, end). Ok(None) = no usable range, serve the whole file;
+/// Err = unsatisfiable (416).
+fn parse_range(value: &str, len: u64) -> Result<Option<(u64, u64)>, ()> {
+    let Some(spec) = value.trim().strip_prefix("bytes=") else {
+        return Ok(None);
+    };
+    // Multiple ranges are not supported; answer them with the whole file.
+    if spec.contains(',') {
+        return Ok(None);
+    }
+    let Some((start, end)) = spec.split_once('-') else {
+        return Ok(None);
+    };
+    let (start, end) = match (start.trim(), end.trim()) {
+        ("", "") => return Ok(None),
+        ("", suffix) => {
+            let n: u64 = suffix.parse().map_err(|_| ())?;
+            if n == 0 {
+                return Err(());
+            }
+            (len.saturating_sub(n), len.saturating_sub(1))
+        }
+        (start, "") => (start.parse().map_err(|_| ())?, len.saturating_sub(1)),
+        (start, end) => {
+            let end: u64 = end.parse().map_err(|_| ())?;
+            (
+                start.parse().map_err(|_| ())?,
+                end.min(len.saturating_sub(1)),
+            )
+        }
+    };
+    if len == 0 || start > end || start >= len {
+        return Err(());
+    }
+    Ok(Some((start, end)))
+}
+
+fn content_type(path: &Path) -> &'static str {
+    match path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .as_deref()
+    {
+        Some("wav") => "audio/wav",
+        Some("aif") | Some("aiff") => "audio/aiff",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Request line and the headers the server looks at.
+struct RequestHead {
+    request_line: String,
+    range: Option<String>,
+    origin: Option<String>,
+}
+
+/// Read a request's head, at most `MAX_HEAD_BYTES` of it. None when the head
+/// doesn't end within that.
+fn read_head(stream: impl Read) -> Result<Option<RequestHead>, String> {
+    let mut reader = BufReader::new(stream.take(MAX_HEAD_BYTES));
+    let mut head = RequestHead {
+        request_line: String::new(),
+        range: None,
+        origin: None,
+    };
+    reader
+        .read_line(&mut head.request_line)
+        .map_err(|e| e.to_string())?;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).map_err(|e| e.to_string())? == 0 {
+            // Out of budget, or the client closed before the blank line
+            if reader.get_ref().limit() == 0 {
+                return Ok(None);
+            }
+            break;
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("range") {
+                head.range = Some(value.trim().to_string());
+            } else if name.eq_ignore_ascii_case("origin") {
+                head.origin = Some(value.trim().to_string());
+            }
+        }
+    }
+    Ok(Some(head))
+}
+
+impl StreamServer {
+    fn handle(&self, mut stream: TcpStream) -> Result<(), String> {
+        stream
+            .set_read_timeout(Some(CLIENT_TIMEOUT))
+            .and_then(|_| stream.set_write_timeout(Some(CLIENT_TIMEOUT)))
+            .map_err(|e| e.to_string())?;
+        let Some(RequestHead {
+            request_line,
+            range,
+            origin,
+        }) = read_head(stream.try_clone().map_err(|e| e.to_string())?)?
+        else {
+            return respond_status(&mut stream, "431 Request Header Fields Too Large");
+        };
+
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next().unwrap_or("");
+        let target = parts.next().unwrap_or("");
+        if method != "GET" && method != "HEAD" {
+            return respond_status(&mut stream, "405 Method Not Allowed");
+        }
+        let path = target
+            .strip_prefix('/')
+            .and_then(|t| t.split_once('/'))
+            .filter(|(token, _)| *token == self.token)
+            .and_then(|(_, id)| {
+                let files = self.files.lock().unwrap_or_else(|e| e.into_inner());
+                files.get(id.split('?').next().unwrap_or(id)).cloned()
+            });
+        let Some(path) = path else {
+            return respond_status(&mut stream, "404 Not Found");
+        };
+
+        let mut file = fs::File::open(&path).map_err(|e| e.to_string())?;
+        let len = file.metadata().map_err(|e| e.to_string())?.len();
+        let (status, start, end) = match range.as_deref().map(|r| parse_range(r, len)) {
+            Some(Err(())) => {
+                let header = format!(
+                    "HTTP/1.1 416 Range Not Satisfiable\r\nContent-Range: bytes */{}\r\n\
+                     Content-Length: 0\r\nConnection: close\r\n\r\n",
+                    len
+                );
+                return stream
+                    .write_all(header.as_bytes())
+                    .map_err(|e| e.to_string());
+            }
+            Some(Ok(Some((start, end)))) => ("206 Partial Content", start, end),
+            _ => ("200 OK", 0, len.saturating_sub(1)),
+        };
+        let body_len = if len == 0 { 0 } else { end - start + 1 };
+
+        let mut header = format!(
+            "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nAccept-Ranges: bytes\r\n\
+             Cache-Control: no-cache\r\nConnection: close\r\n",
+            status,
+            content_type(&path),
+            body_len
+        );
+        if let Some(origin) = origin.filter(|o| is_app_origin(o)) {
+            header.push_str(&format!(
+                "Access-Control-Allow-Origin: {}\r\nVary: Origin\r\n",
+                origin
+            ));
+        }
+        if status.starts_with("206") {
+            header.push_str(&format!(
+                "Content-Range: bytes {}-{}/{}\r\n",
+                start, end, len
+            ));
+        }
+        header.push_str("\r\n");
+        stream
+            .write_all(header.as_bytes())
+            .map_err(|e| e.to_string())?;
+        if method == "HEAD" {
+            return Ok(());
+        }
+
+        file.seek(SeekFrom::Start(start))
+            .map_err(|e| e.to_string())?;
+        let mut remaining = body_len;
+        let mut buf = vec![0u8; CHUNK_SIZE];
+        while remaining > 0 {
+            let n = file
+                .read(&mut buf[..(remaining as usize).min(CHUNK_SIZE)])
+                .map_err(|e| e.to_string())?;
+            if n == 0 {
+                break;
+            }
+            // The player closing the connection mid-file (seek, pause) is normal.
+            if stream.write_all(&buf[..n]).is_err() {
+                return Ok(());
+            }
+            remaining -= n as u64;
+        }
+        Ok(())
+    }
+
+    fn url(&self, id: &str) -> String {
+        format!("http://127.0.0.1:{}/{}/{}", self.port, self.token, id)
+    }
+}
+
+fn respond_status(stream: &mut TcpStream, status: &str) -> Result<(), String> {
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        status
+    );
+    stream
+        .write_all(response.as_bytes())
+        .map_err(|e| e.to_string())
+}
+
+/// Where non-WAV files are decoded for playback; maintenance caps its size.
+pub(crate) fn transcode_dir() -> PathBuf {
+    std::env::temp_dir().join("octatrack-manager-stream")
+}
+
+/// The file to serve for `path`: the file itself when the webview can play it
+/// (WAV), otherwise a WAV decoded into `cache_dir` (reused while the source
+/// is unchanged).
+fn playable_file(path: &Path, id: &str, cache_dir: &Path) -> Result<PathBuf, String> {
+    let is_wav = path
+        .extension()
+        .is_some_and(|e| e.to_string_lossy().eq_ignore_ascii_case("wav"));
+    if is_wav && hound::WavReader::open(path).is_ok() {
+        return Ok(path.to_path_buf());
+    }
+    let decoded = cache_dir.join(format!("{}.wav", id));
+    if !decoded.exists() {
+        fs::create_dir_all(cache_dir)
+            .map_err(|e| format!("Failed to create {}: {}", cache_dir.display(), e))?;
+        let tmp = cache_dir.join(format!("{}.tmp.wav", id));
+        crate::audio_pool::convert_to_octatrack_format_with_progress(
+            path,
+            &tmp,
+            &|_: &str, _: f32| {},
+            &None,
+        )?;
+        fs::rename(&tmp, &decoded)
+            .map_err(|e| format!("Failed to write {}: {}", decoded.display(), e))?;
+    }
+    Ok(decoded)
+}
+
+fn register(server: &StreamServer, path: &str, cache_dir: &Path) -> Result<String, String> {
+    let source = fs::canonicalize(path).map_err(|e| format!("Failed to open {}: {}", path, e))?;
+    let name = source
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    if !crate::audio_pool::is_audio_file(&name) {
+        return Err(format!("Not an audio file: {}", path));
+    }
+    let id = file_id(&source)?;
+    let playable = playable_file(&source, &id, cache_dir)?;
+    server
+        .files
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(id.clone(), playable);
+    Ok(server.url(&id))
+}
+
+/// Streaming URL for an audio file, starting the server on first use.
+#[tauri::command]
+pub async fn get_stream_url(path: String) -> Result<String, String> {
+    crate::fs_scope::ensure_allowed(&path)?;
+    tauri::async_runtime::spawn_blocking(move || register(&server()?, &path, &transcode_dir()))
+        .await
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{ramp, write_wav};
+    use tempfile::TempDir;
+
+    fn request(url: &str, range: Option<&str>) -> (String, Vec<u8>) {
+        request_from(url, range, None)
+    }
+
+    fn request_from(url: &str, range: Option<&str>, origin: Option<&str>) -> (String, Vec<u8>) {
+        let rest = url.strip_prefix("http://").unwrap();
+        let (host, target) = rest.split_once('/').unwrap();
+        let mut stream = TcpStream::connect(host).unwrap();
+        let mut req = format!("GET /{} HTTP/1.1\r\nHost: {}\r\n", target, host);
+        if let Some(range) = range {
+            req.push_str(&format!("Range: {}\r\n", range));
+        }
+        if let Some(origin) = origin {
+            req.push_str(&format!("Origin: {}\r\n", origin));
+        }
+        req.push_str("\r\n");
+        stream.write_all(req.as_bytes()).unwrap();
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).unwrap();
+        let split = response.windows(4).position(|w| w == b"\r\n\r\n").unwrap();
+        (
+            String::from_utf8_lossy(&response[..split]).to_string(),
+            response[split + 4..].to_vec(),
+        )
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range("bytes=0-99", 1000), Ok(Some((0, 99))));
+        assert_eq!(parse_range("bytes=900-", 1000), Ok(Some((900, 999))));
+        assert_eq!(parse_range("bytes=-100", 1000), Ok(Some((900, 999))));
+        assert_eq!(parse_range("bytes=990-2000", 1000), Ok(Some((990, 999))));
+        assert_eq!(parse_range("bytes=0-1,5-9", 1000), Ok(None));
+        assert_eq!(parse_range("bytes=1000-", 1000), Err(()));
+        assert_eq!(parse_range("bytes=5-1", 1000), Err(()));
+    }
+
+    #[test]
+    fn test_serves_registered_wav_with_ranges() {
+        let dir = TempDir::new().unwrap();
+        let wav = dir.path().join("loop.wav");
+        write_wav(&wav, 1, 44100, &ramp(10_000));
+        let bytes = fs::read(&wav).unwrap();
+
+        let server = start_server().unwrap();
+        let url = register(&server, &wav.to_string_lossy(), &dir.path().join("cache")).unwrap();
+
+        let (head, body) = request(&url, None);
+        assert!(head.starts_with("HTTP/1.1 200"), "{}", head);
+        assert_eq!(body, bytes);
+
+        let (head, body) = request(&url, Some("bytes=100-199"));
+        assert!(head.starts_with("HTTP/1.1 206"), "{}", head);
+        assert!(head.contains(&format!("Content-Range: bytes 100-199/{}", bytes.len())));
+        assert_eq!(body, &bytes[100..200]);
+
+        let (head, _) = request(&url, Some(&format!("bytes={}-", bytes.len())));
+        assert!(head.starts_with("HTTP/1.1 416"), "{}", head);
+
+        // Unknown ids and a wrong token are not served.
+        let (head, _) = request(&url.replace(&server.token, "nope"), None);
+        assert!(head.starts_with("HTTP/1.1 404"), "{}", head);
+        assert!(!dir.path().join("cache").exists(), "WAV is served in place");
+    }
+
+    #[test]
+    fn test_cross_origin_reads_limited_to_the_app() {
+        let dir = TempDir::new().unwrap();
+        let wav = dir.path().join("loop.wav");
+        write_wav(&wav, 1, 44100, &ramp(100));
+        let server = start_server().unwrap();
+        let url = register(&server, &wav.to_string_lossy(), &dir.path().join("cache")).unwrap();
+
+        let (head, _) = request_from(&url, None, Some("tauri://localhost"));
+        assert!(head.contains("Access-Control-Allow-Origin: tauri://localhost"));
+        let (head, _) = request_from(&url, None, Some("https://example.com"));
+        assert!(head.starts_with("HTTP/1.1 200"), "{}", head);
+        assert!(!head.contains("Access-Control-Allow-Origin"), "{}", head);
+        let (head, _) = request(&url, None);
+        assert!(!head.contains("Access-Control-Allow-Origin"), "{}", head);
+    }
+
+    #[test]
+    fn test_request_head_is_bounded() {
+        let head = read_head(&b"GET /t/id HTTP/1.1\r\nRange: bytes=0-9\r\n\r\n"[..])
+            .unwrap()
+            .unwrap();
+        assert_eq!(head.request_line.trim_end(), "GET /t/id HTTP/1.1");
+        assert_eq!(head.range.as_deref(), Some("bytes=0-9"));
+
+        // One endless header line never ends the head within the budget
+        let endless = format!("GET / HTTP/1.1\r\nX: {}", "a".repeat(20 * 1024));
+        assert!(read_head(endless.as_bytes()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_non_audio_files_are_refused() {
+        let dir = TempDir::new().unwrap();
+        let notes = dir.path().join("notes.txt");
+        fs::write(&notes, b"hello").unwrap();
+        let server = start_server().unwrap();
+        assert!(register(&server, &notes.to_string_lossy(), dir.path()).is_err());
+    }
+}
@@ -0,0 +1,991 @@
+// JSON export/import of decoded bank data: a whole bank (patterns + parts) or a
+// single pattern with the part it plays, written to a standalone file so it can
+// be archived, shared and diffed outside the app, and written back into any
+// project.
+//
+// Import re-encodes the decoded structures. FX1/FX2 parameter locks, which the
+// decoded schema does not carry, travel as raw bytes in `fx_plocks`; exports
+// written before it existed come back with FX locks cleared, with a warning
+// per affected track. Micro-timing offsets shown as "+μ"/"-μ" come back at
+// their default value. Recorder trigs from exports without `recorder_sources`
+// come back armed on INAB.
+
+use crate::project_reader::{
+    apply_parts_data, bank_file_path, bank_index, decode_trig_condition, edit_bank_file,
+    encode_pattern_tempo, encode_trig_masks, read_parts_data, read_single_bank, set_part_name,
+    Bank, PartData, Pattern, TrackInfo, DEFAULT_PATTERN_TEMPO, PATTERN_SCALES, RECORDER_SOURCES,
+};
+use ot_tools_io::{BankFile, OctatrackFileIO};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Identifies an exported document; bump `FORMAT_VERSION` on breaking changes.
+pub const FORMAT_NAME: &str = "octatrack-manager";
+pub const FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportHeader {
+    pub format: String,         // always FORMAT_NAME
+    pub version: u32,           // FORMAT_VERSION at export time
+    pub kind: String,           // "bank" or "pattern"
+    pub exported_at: String,    // RFC 3339, local time
+    pub source_project: String, // project folder name
+    pub bank_id: String,        // "A".."P"
+}
+
+/// FX1/FX2 parameter locks of one audio trig, as stored (255 = not locked).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FxPlocks {
+    pub pattern: u8, // 0-15
+    pub track: u8,   // 0-7
+    pub step: u8,    // 0-63
+    pub fx1: [u8; 6],
+    pub fx2: [u8; 6],
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BankExport {
+    pub header: ExportHeader,
+    pub bank: Bank,
+    pub parts: Vec<PartData>,
+    pub parts_edited_bitmask: u8,
+    pub parts_saved_state: [u8; 4],
+    #[serde(default)]
+    pub fx_plocks: Option<Vec<FxPlocks>>, // None in exports that predate it
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatternExport {
+    pub header: ExportHeader,
+    pub pattern: Pattern,
+    pub part: PartData, // the part the pattern is assigned to
+    #[serde(default)]
+    pub fx_plocks: Option<Vec<FxPlocks>>,
+}
+
+fn header(project_path: &str, kind: &str, bank_id: &str) -> ExportHeader {
+    ExportHeader {
+        format: FORMAT_NAME.to_string(),
+        version: FORMAT_VERSION,
+        kind: kind.to_string(),
+        exported_at: chrono::Local::now().to_rfc3339(),
+        source_project: Path::new(project_path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        bank_id: bank_id.to_string(),
+    }
+}
+
+fn load_bank(project_path: &str, bank_id: &str) -> Result<Bank, String> {
+    read_single_bank(project_path, bank_index(bank_id)?)?
+        .ok_or_else(|| format!("Bank {} does not exist in this project", bank_id))
+}
+
+/// Every audio trig of `patterns` with an FX1 or FX2 parameter lock.
+fn read_fx_plocks(
+    project_path: &str,
+    bank_id: &str,
+    patterns: &[u8],
+) -> Result<Vec<FxPlocks>, String> {
+    let file = bank_file_path(Path::new(project_path), bank_index(bank_id)?)?;
+    let bank = crate::os_compat::read_bank_file(&file)
+        .map_err(|e| format!("Failed to read bank file: {}", e))?;
+    let mut locks = Vec::new();
+    for &pattern in patterns {
+        let tracks = &bank.patterns.0[pattern as usize].audio_track_trigs.0;
+        for (track, trigs) in tracks.iter().enumerate() {
+            for (step, p) in trigs.plocks.0.iter().enumerate() {
+                let fx1 = [
+                    p.fx1.param_1,
+                    p.fx1.param_2,
+                    p.fx1.param_3,
+                    p.fx1.param_4,
+                    p.fx1.param_5,
+                    p.fx1.param_6,
+                ];
+                let fx2 = [
+                    p.fx2.param_1,
+                    p.fx2.param_2,
+                    p.fx2.param_3,
+                    p.fx2.param_4,
+                    p.fx2.param_5,
+                    p.fx2.param_6,
+                ];
+                if fx1.iter().chain(&fx2).any(|&v| v != 255) {
+                    locks.push(FxPlocks {
+                        pattern,
+                        track: track as u8,
+                        step: step as u8,
+                        fx1,
+                        fx2,
+                    });
+                }
+            }
+        }
+    }
+    Ok(locks)
+}
+
+pub fn bank_export(project_path: &str, bank_id: &str) -> Result<BankExport, String> {
+    let bank = load_bank(project_path, bank_id)?;
+    let parts = read_parts_data(project_path, bank_id)?;
+    let fx_plocks = read_fx_plocks(project_path, bank_id, &(0..16).collect::<Vec<_>>())?;
+    Ok(BankExport {
+        header: header(project_path, "bank", bank_id),
+        bank,
+        parts: parts.parts,
+        parts_edited_bitmask: parts.parts_edited_bitmask,
+        parts_saved_state: parts.parts_saved_state,
+        fx_plocks: Some(fx_plocks),
+    })
+}
+
+pub fn pattern_export(
+    project_path: &str,
+    bank_id: &str,
+    pattern_index: u8,
+) -> Result<PatternExport, String> {
+    if pattern_index > 15 {
+        return Err(format!(
+            "Invalid pattern index: {} (must be 0-15)",
+            pattern_index
+        ));
+    }
+    let bank = load_bank(project_path, bank_id)?;
+    let pattern = bank
+        .parts
+        .into_iter()
+        .flat_map(|part| part.patterns)
+        .find(|p| p.id == pattern_index)
+        .ok_or_else(|| {
+            format!(
+                "Pattern {} not found in bank {}",
+                pattern_index + 1,
+                bank_id
+            )
+        })?;
+    let part = read_parts_data(project_path, bank_id)?
+        .parts
+        .into_iter()
+        .find(|p| p.part_id == pattern.part_assignment)
+        .ok_or_else(|| format!("Part {} not found", pattern.part_assignment + 1))?;
+    let fx_plocks = read_fx_plocks(project_path, bank_id, &[pattern_index])?;
+    Ok(PatternExport {
+        header: header(project_path, "pattern", bank_id),
+        pattern,
+        part,
+        fx_plocks: Some(fx_plocks),
+    })
+}
+
+/// Pretty-printed JSON to `out_file`, through a temp file + rename.
+fn write_json<T: Serialize>(value: &T, out_file: &str) -> Result<(), String> {
+    let out = Path::new(out_file);
+    if let Some(parent) = out.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let data = serde_json::to_string_pretty(value)
+        .map_err(|e| format!("Failed to serialize export: {}", e))?;
+    crate::atomic_write::write_atomic(out, data.as_bytes())
+        .map_err(|e| format!("Failed to write {}: {}", out_file, e))
+}
+
+// ============================================================================
+// Import
+// ============================================================================
+
+const TRIG_MODES: [&str; 3] = ["ONE", "ONE2", "HOLD"];
+const TRIG_QUANTS: [(&str, u8); 18] = [
+    ("TR.LEN", 0),
+    ("1/16", 1),
+    ("2/16", 2),
+    ("3/16", 3),
+    ("4/16", 4),
+    ("6/16", 5),
+    ("8/16", 6),
+    ("12/16", 7),
+    ("16/16", 8),
+    ("24/16", 9),
+    ("32/16", 10),
+    ("48/16", 11),
+    ("64/16", 12),
+    ("96/16", 13),
+    ("128/16", 14),
+    ("192/16", 15),
+    ("256/16", 16),
+    ("DIRECT", 255),
+];
+/// Micro-timing labels produced by the reader, with their (offset bits, high
+/// bit of the condition byte) encoding.
+const MICRO_TIMINGS: [(&str, u8, bool); 8] = [
+    ("+1/128", 1, true),
+    ("+1/64", 3, false),
+    ("+1/32", 6, false),
+    ("+23/384", 11, true),
+    ("-23/384", 20, true),
+    ("-1/32", 26, false),
+    ("-1/64", 29, false),
+    ("-1/128", 30, true),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportResult {
+    pub bank_id: String,
+    pub patterns: Vec<u8>, // 0-based pattern indices written
+    pub parts: Vec<u8>,    // 0-based part ids written
+    pub backup: String,
+    pub warnings: Vec<String>,
+}
+
+fn check_header(header: &ExportHeader, kind: &str) -> Result<(), String> {
+    if header.format != FORMAT_NAME {
+        return Err(format!("Not an {} export: {}", FORMAT_NAME, header.format));
+    }
+    if header.version > FORMAT_VERSION {
+        return Err(format!(
+            "Export version {} is newer than supported version {}",
+            header.version, FORMAT_VERSION
+        ));
+    }
+    if header.kind != kind {
+        return Err(format!(
+            "Expected a {} export, got a {} export",
+            kind, header.kind
+        ));
+    }
+    Ok(())
+}
+
+fn code_of(table: &[&str], value: &str, what: &str) -> Result<u8, String> {
+    table
+        .iter()
+        .position(|&v| v == value)
+        .map(|i| i as u8)
+        .ok_or_else(|| format!("Unknown {}: {}", what, value))
+}
+
+fn condition_code(name: &str) -> Result<u8, String> {
+    (1..=64u8)
+        .find(|&code| decode_trig_condition(code).as_deref() == Some(name))
+        .ok_or_else(|| format!("Unknown trig condition: {}", name))
+}
+
+/// Pattern tempo text ("126.5 BPM") back to its two bytes.
+fn encode_tempo(tempo_info: Option<&str>) -> Result<(u8, u8), String> {
+    let Some(info) = tempo_info else {
+        return Ok(DEFAULT_PATTERN_TEMPO);
+    };
+    let bpm: f32 = info
+        .trim_end_matches("BPM")
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid pattern tempo: {}", info))?;
+    encode_pattern_tempo(bpm)
+}
+
+/// Per-track mode master length ("2".."1024" or "INF") as (length, range multiplier).
+fn encode_master_len(len: &str) -> Result<(u8, u8), String> {
+    match len {
+        "INF" => Ok((0, 255)),
+        "1024" => Ok((0, 4)),
+        _ => {
+            let n: u16 = len
+                .parse()
+                .ok()
+                .filter(|n| (2..1024).contains(n))
+                .ok_or_else(|| format!("Invalid master length: {}", len))?;
+            Ok(((n % 256) as u8, (n / 256) as u8))
+        }
+    }
+}
+
+fn lock(value: Option<u8>, what: &str) -> Result<u8, String> {
+    match value {
+        Some(v) if v > 127 => Err(format!("{} lock out of range (0-127): {}", what, v)),
+        Some(v) => Ok(v),
+        None => Ok(255),
+    }
+}
+
+/// Trig masks, offsets/conditions and locks of one track, from its decoded steps.
+struct EncodedSteps {
+    trigger: [bool; 64],
+    trigless: [bool; 64],
+    plock: [bool; 64],
+    oneshot: [bool; 64],
+    swing: [bool; 64],
+    slide: [bool; 64],
+    recorder_sources: [[bool; 64]; 3], // INAB, INCD, SRC3
+    recorder_oneshot: [bool; 64],
+    offsets: [[u8; 2]; 64],
+}
+
+fn encode_steps(track: &TrackInfo, warnings: &mut Vec<String>) -> Result<EncodedSteps, String> {
+    let mut encoded = EncodedSteps {
+        trigger: [false; 64],
+        trigless: [false; 64],
+        plock: [false; 64],
+        oneshot: [false; 64],
+        swing: [false; 64],
+        slide: [false; 64],
+        recorder_sources: [[false; 64]; 3],
+        recorder_oneshot: [false; 64],
+        offsets: [[0, 0]; 64],
+    };
+    let mut approximated = 0;
+    for step in &track.steps {
+        let s = step.step as usize;
+        if s >= 64 {
+            return Err(format!(
+                "Track {}: invalid step {}",
+                track.track_id + 1,
+                step.step
+            ));
+        }
+        if step.trig_repeats > 7 {
+            return Err(format!(
+                "Track {} step {}: trig repeats out of range (0-7)",
+                track.track_id + 1,
+                s + 1
+            ));
+        }
+        encoded.trigger[s] = step.trigger;
+        encoded.trigless[s] = step.trigless;
+        encoded.plock[s] = step.plock;
+        encoded.oneshot[s] = step.oneshot;
+        encoded.swing[s] = step.swing;
+        encoded.slide[s] = step.slide;
+        if step.recorder {
+            if step.recorder_sources.is_empty() {
+                encoded.recorder_sources[0][s] = true;
+            }
+            for name in &step.recorder_sources {
+                let source = RECORDER_SOURCES
+                    .iter()
+                    .position(|known| known == name)
+                    .ok_or_else(|| {
+                        format!(
+                            "Track {} step {}: unknown recorder source {}",
+                            track.track_id + 1,
+                            s + 1,
+                            name
+                        )
+                    })?;
+                encoded.recorder_sources[source][s] = true;
+            }
+        }
+        encoded.recorder_oneshot[s] = step.recorder_oneshot;
+
+        let condition = match &step.trig_condition {
+            Some(name) => condition_code(name)?,
+            None => 0,
+        };
+        let (offset, high_bit) = match step.micro_timing.as_deref() {
+            None => (0, false),
+            Some(label) => match MICRO_TIMINGS.iter().find(|(l, _, _)| *l == label) {
+                Some(&(_, offset, high_bit)) => (offset, high_bit),
+                None => {
+                    approximated += 1;
+                    (0, false)
+                }
+            },
+        };
+        encoded.offsets[s] = [
+            step.trig_repeats * 32 + offset,
+            condition | if high_bit { 0x80 } else { 0 },
+        ];
+    }
+    if approximated > 0 {
+        warnings.push(format!(
+            "Track {}: {} micro-timing offset(s) not exactly described by the export were reset",
+            track.track_id + 1,
+            approximated
+        ));
+    }
+    Ok(encoded)
+}
+
+/// Overwrite `target` with the decoded `pattern`.
+fn encode_pattern(
+    target: &mut ot_tools_io::patterns::Pattern,
+    pattern: &Pattern,
+    warnings: &mut Vec<String>,
+) -> Result<(), String> {
+    if !(1..=64).contains(&pattern.length) {
+        return Err(format!(
+            "Pattern length out of range (1-64): {}",
+            pattern.length
+        ));
+    }
+    if pattern.part_assignment > 3 {
+        return Err(format!(
+            "Invalid part assignment: {}",
+            pattern.part_assignment
+        ));
+    }
+    let per_track = match pattern.scale_mode.as_str() {
+        "Normal" => false,
+        "Per Track" => true,
+        other => return Err(format!("Unknown scale mode: {}", other)),
+    };
+
+    target.part_assignment = pattern.part_assignment;
+    target.scale.scale_mode = per_track as u8;
+    target.scale.master_len = pattern.length as u8;
+    target.scale.master_scale = code_of(&PATTERN_SCALES, &pattern.master_scale, "scale")?;
+    if let Some(settings) = &pattern.per_track_settings {
+        let (len, multiplier) = encode_master_len(&settings.master_len)?;
+        target.scale.master_len_per_track = len;
+        target.scale.master_len_per_track_multiplier = multiplier;
+        target.scale.master_scale_per_track =
+            code_of(&PATTERN_SCALES, &settings.master_scale, "scale")?;
+    }
+    target.chain_behaviour.use_project_setting = (pattern.chain_mode == "Project") as u8;
+    (target.tempo_1, target.tempo_2) = encode_tempo(pattern.tempo_info.as_deref())?;
+
+    for track in &pattern.tracks {
+        let t = track.track_id as usize;
+        if t >= 16 {
+            return Err(format!("Invalid track id: {}", track.track_id));
+        }
+        if track.swing_amount > 30 {
+            return Err(format!("Track {}: swing amount out of range (0-30)", t + 1));
+        }
+        let per_track_len = match track.per_track_len {
+            Some(len) if !(1..=64).contains(&len) => {
+                return Err(format!("Track {}: length out of range (1-64)", t + 1))
+            }
+            Some(len) => Some(len),
+            None => None,
+        };
+        let per_track_scale = match &track.per_track_scale {
+            Some(scale) => Some(code_of(&PATTERN_SCALES, scale, "scale")?),
+            None => None,
+        };
+        let settings = &track.pattern_settings;
+        let trig_mode = code_of(&TRIG_MODES, &settings.trig_mode, "trig mode")?;
+        let trig_quant = TRIG_QUANTS
+            .iter()
+            .find(|(name, _)| *name == settings.trig_quant)
+            .map(|(_, code)| *code)
+            .ok_or_else(|| format!("Unknown trig quantization: {}", settings.trig_quant))?;
+        let steps = encode_steps(track, warnings)?;
+
+        if t < 8 {
+            let dst = &mut target.audio_track_trigs.0[t];
+            dst.swing_amount = track.swing_amount;
+            if let Some(len) = per_track_len {
+                dst.scale_per_track_mode.per_track_len = len;
+            }
+            if let Some(scale) = per_track_scale {
+                dst.scale_per_track_mode.per_track_scale = scale;
+            }
+            dst.pattern_settings.start_silent = if settings.start_silent { 0 } else { 255 };
+            dst.pattern_settings.plays_free = settings.plays_free as u8;
+            dst.pattern_settings.trig_mode = trig_mode;
+            dst.pattern_settings.trig_quant = trig_quant;
+            dst.pattern_settings.oneshot_trk = settings.oneshot_trk as u8;
+
+            let masks = &mut dst.trig_masks;
+            masks.trigger = encode_trig_masks(&steps.trigger);
+            masks.trigless = encode_trig_masks(&steps.trigless);
+            masks.plock = encode_trig_masks(&steps.plock);
+            masks.oneshot = encode_trig_masks(&steps.oneshot);
+            masks.swing = encode_trig_masks(&steps.swing);
+            masks.slide = encode_trig_masks(&steps.slide);
+            masks.recorder = [0; 32];
+            for (i, sources) in steps.recorder_sources.iter().enumerate() {
+                masks.recorder[i * 8..i * 8 + 8].copy_from_slice(&encode_trig_masks(sources));
+            }
+            masks.recorder[24..].copy_from_slice(&encode_trig_masks(&steps.recorder_oneshot));
+            dst.trig_offsets_repeats_conditions = steps.offsets;
+
+            for step in &track.steps {
+                let Some(locks) = &step.audio_plocks else {
+                    continue;
+                };
+                let p = &mut dst.plocks.0[step.step as usize];
+                p.machine.param1 = lock(locks.machine.param1, "Machine")?;
+                p.machine.param2 = lock(locks.machine.param2, "Machine")?;
+                p.machine.param3 = lock(locks.machine.param3, "Machine")?;
+                p.machine.param4 = lock(locks.machine.param4, "Machine")?;
+                p.machine.param5 = lock(locks.machine.param5, "Machine")?;
+                p.machine.param6 = lock(locks.machine.param6, "Machine")?;
+                p.lfo.spd1 = lock(locks.lfo.spd1, "LFO")?;
+                p.lfo.spd2 = lock(locks.lfo.spd2, "LFO")?;
+                p.lfo.spd3 = lock(locks.lfo.spd3, "LFO")?;
+                p.lfo.dep1 = lock(locks.lfo.dep1, "LFO")?;
+                p.lfo.dep2 = lock(locks.lfo.dep2, "LFO")?;
+                p.lfo.dep3 = lock(locks.lfo.dep3, "LFO")?;
+                p.amp.atk = lock(locks.amp.atk, "Amp")?;
+                p.amp.hold = lock(locks.amp.hold, "Amp")?;
+                p.amp.rel = lock(locks.amp.rel, "Amp")?;
+                p.amp.vol = lock(locks.amp.vol, "Amp")?;
+                p.amp.bal = lock(locks.amp.bal, "Amp")?;
+                p.amp.f = lock(locks.amp.f, "Amp")?;
+                p.static_slot_id = locks.static_slot_id.unwrap_or(255);
+                p.flex_slot_id = locks.flex_slot_id.unwrap_or(255);
+            }
+        } else {
+            let dst = &mut target.midi_track_trigs.0[t - 8];
+            dst.swing_amount = track.swing_amount;
+            if let Some(len) = per_track_len {
+                dst.scale_per_track_mode.per_track_len = len;
+            }
+            if let Some(scale) = per_track_scale {
+                dst.scale_per_track_mode.per_track_scale = scale;
+            }
+            dst.pattern_settings.start_silent = if settings.start_silent { 0 } else { 255 };
+            dst.pattern_settings.plays_free = settings.plays_free as u8;
+            dst.pattern_settings.trig_mode = trig_mode;
+            dst.pattern_settings.trig_quant = trig_quant;
+            dst.pattern_settings.oneshot_trk = settings.oneshot_trk as u8;
+
+            let masks = &mut dst.trig_masks;
+            masks.trigger = encode_trig_masks(&steps.trigger);
+            masks.trigless = encode_trig_masks(&steps.trigless);
+            masks.plock = encode_trig_masks(&steps.plock);
+            masks.swing = encode_trig_masks(&steps.swing);
+            dst.trig_offsets_repeats_conditions = steps.offsets;
+
+            for step in &track.steps {
+                let Some(locks) = &step.midi_plocks else {
+                    continue;
+                };
+                let p = &mut dst.plocks.0[step.step as usize];
+                p.midi.note = lock(locks.midi.note, "Note")?;
+                p.midi.vel = lock(locks.midi.vel, "Velocity")?;
+                p.midi.len = lock(locks.midi.len, "Length")?;
+                p.midi.not2 = lock(locks.midi.not2, "Note")?;
+                p.midi.not3 = lock(locks.midi.not3, "Note")?;
+                p.midi.not4 = lock(locks.midi.not4, "Note")?;
+                p.lfo.spd1 = lock(locks.lfo.spd1, "LFO")?;
+                p.lfo.spd2 = lock(locks.lfo.spd2, "LFO")?;
+                p.lfo.spd3 = lock(locks.lfo.spd3, "LFO")?;
+                p.lfo.dep1 = lock(locks.lfo.dep1, "LFO")?;
+                p.lfo.dep2 = lock(locks.lfo.dep2, "LFO")?;
+                p.lfo.dep3 = lock(locks.lfo.dep3, "LFO")?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Back up the bank file about to be overwritten, creating a blank bank when
+/// the project does not have it yet.
+fn prepare_bank(project_path: &str, bank_index: u8) -> Result<String, String> {
+    let work = format!("bank{:02}.work", bank_index + 1);
+    let strd = format!("bank{:02}.strd", bank_index + 1);
+    let path = Path::new(project_path);
+    if !path.join(&work).exists() && !path.join(&strd).exists() {
+        crate::atomic_write::write_data_file(&BankFile::default(), &path.join(&work))
+            .map_err(|e| format!("Failed to create bank file: {}", e))?;
+        return Ok("No files to back up".to_string());
+    }
+    crate::backup_project_files_impl(project_path, &[work, strd], "json_import")
+}
+
+fn write_part_names(target: &mut BankFile, bank: &Bank) -> Result<(), String> {
+    for part in &bank.parts {
+        let default_name = format!("Part {}", part.id + 1);
+        let name = if part.name == default_name {
+            ""
+        } else {
+            &part.name
+        };
+        set_part_name(target, part.id, name)?;
+    }
+    Ok(())
+}
+
+/// Write the FX locks of `source_pattern` into `target`, a pattern encoded by
+/// `encode_pattern`. Without them (older exports), warn for every audio track
+/// whose p-locked steps lose their FX locks.
+fn encode_fx_plocks(
+    target: &mut ot_tools_io::patterns::Pattern,
+    source_pattern: &Pattern,
+    fx_plocks: Option<&[FxPlocks]>,
+    warnings: &mut Vec<String>,
+) -> Result<(), String> {
+    let Some(fx_plocks) = fx_plocks else {
+        for track in source_pattern.tracks.iter().filter(|t| t.track_id < 8) {
+            let locked = track
+                .steps
+                .iter()
+                .filter(|s| s.audio_plocks.is_some())
+                .count();
+            if locked > 0 {
+                warnings.push(format!(
+                    "Track {}: the export has no FX locks; FX1/FX2 locks on its {} p-locked step(s) were cleared",
+                    track.track_id + 1,
+                    locked
+                ));
+            }
+        }
+        return Ok(());
+    };
+    for lock in fx_plocks.iter().filter(|l| l.pattern == source_pattern.id) {
+        if lock.track >= 8 || lock.step >= 64 {
+            return Err(format!(
+                "Invalid FX lock position: track {}, step {}",
+                lock.track + 1,
+                lock.step + 1
+            ));
+        }
+        let p = &mut target.audio_track_trigs.0[lock.track as usize].plocks.0[lock.step as usize];
+        [
+            p.fx1.param_1,
+            p.fx1.param_2,
+            p.fx1.param_3,
+            p.fx1.param_4,
+            p.fx1.param_5,
+            p.fx1.param_6,
+        ] = lock.fx1;
+        [
+            p.fx2.param_1,
+            p.fx2.param_2,
+            p.fx2.param_3,
+            p.fx2.param_4,
+            p.fx2.param_5,
+            p.fx2.param_6,
+        ] = lock.fx2;
+    }
+    Ok(())
+}
+
+fn read_export<T: serde::de::DeserializeOwned>(in_file: &str) -> Result<T, String> {
+    let data =
+        fs::read_to_string(in_file).map_err(|e| format!("Failed to read {}: {}", in_file, e))?;
+    serde_json::from_str(&data).map_err(|e| format!("Invalid export file: {}", e))
+}
+
+/// Write a whole-bank export into bank `bank_id` of the project (patterns,
+/// parts and part names).
+pub fn import_bank(
+    project_path: &str,
+    bank_id: &str,
+    export: &BankExport,
+) -> Result<ImportResult, String> {
+    check_header(&export.header, "bank")?;
+    let bank_index = bank_index(bank_id)?;
+    let mut patterns: Vec<&Pattern> = Vec::new();
+    for pattern in export.bank.parts.iter().flat_map(|p| &p.patterns) {
+        if !patterns.iter().any(|p| p.id == pattern.id) {
+            patterns.push(pattern);
+        }
+    }
+    patterns.sort_by_key(|p| p.id);
+    if patterns.iter().map(|p| p.id).ne(0..16) {
+        return Err("A bank export must hold patterns 1-16".to_string());
+    }
+    if export.parts.iter().any(|p| p.part_id > 3) {
+        return Err("Invalid part id in export".to_string());
+    }
+    if export.bank.parts.iter().any(|p| p.id > 3) {
+        return Err("Invalid part id in export".to_string());
+    }
+
+    // Encoded onto a blank bank so nothing of the patterns they replace leaks
+    // through, and fully validated before anything is written.
+    let mut warnings = Vec::new();
+    let mut blank = BankFile::default();
+    for pattern in &patterns {
+        let target = &mut blank.patterns.0[pattern.id as usize];
+        encode_pattern(target, pattern, &mut warnings)?;
+        encode_fx_plocks(target, pattern, export.fx_plocks.as_deref(), &mut warnings)?;
+    }
+    write_part_names(&mut blank, &export.bank)?;
+
+    // Patterns, parts and part names land in one write of the bank file
+    let backup = prepare_bank(project_path, bank_index)?;
+    edit_bank_file(project_path, bank_index, |bank| {
+        bank.patterns = blank.patterns;
+        apply_parts_data(bank, &export.parts);
+        write_part_names(bank, &export.bank)
+    })?;
+
+    Ok(ImportResult {
+        bank_id: bank_id.to_string(),
+        patterns: (0..16).collect(),
+        parts: export.parts.iter().map(|p| p.part_id).collect(),
+        backup,
+        warnings,
+    })
+}
+
+/// Insert a pattern export as pattern `pattern_index` of bank `bank_id`.
+/// With `include_part`, the exported part also replaces the part the pattern
+/// is assigned to.
+pub fn import_pattern(
+    project_path: &str,
+    bank_id: &str,
+    pattern_index: u8,
+    export: &PatternExport,
+    include_part: bool,
+) -> Result<ImportResult, String> {
+    check_header(&export.header, "pattern")?;
+    let bank_index = bank_index(bank_id)?;
+    if pattern_index > 15 {
+        return Err(format!(
+            "Invalid pattern index: {} (must be 0-15)",
+            pattern_index
+        ));
+    }
+    let mut warnings = Vec::new();
+    let mut blank = BankFile::default();
+    let encoded = &mut blank.patterns.0[pattern_index as usize];
+    encode_pattern(encoded, &export.pattern, &mut warnings)?;
+    encode_fx_plocks(
+        encoded,
+        &export.pattern,
+        export.fx_plocks.as_deref(),
+        &mut warnings,
+    )?;
+    let part = include_part.then(|| PartData {
+        part_id: export.pattern.part_assignment,
+        ..export.part.clone()
+    });
+
+    let backup = prepare_bank(project_path, bank_index)?;
+    edit_bank_file(project_path, bank_index, |bank| {
+        bank.patterns.0[pattern_index as usize] = encoded.clone();
+        apply_parts_data(bank, part.as_slice());
+        Ok(())
+    })?;
+    let parts = part.iter().map(|p| p.part_id).collect();
+
+    Ok(ImportResult {
+        bank_id: bank_id.to_string(),
+        patterns: vec![pattern_index],
+        parts,
+        backup,
+        warnings,
+    })
+}
+
+#[tauri::command]
+pub async fn export_bank_json(
+    path: String,
+    bank_id: String,
+    out_file: String,
+) -> Result<(), String> {
+    crate::fs_scope::ensure_allowed(&out_file)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        write_json(&bank_export(&path, &bank_id)?, &out_file)
+    })
+    .await
+    .unwrap()
+}
+
+#[tauri::command]
+pub async fn export_pattern_json(
+    path: String,
+    bank_id: String,
+    pattern_index: u8,
+    out_file: String,
+) -> Result<(), String> {
+    crate::fs_scope::ensure_allowed(&out_file)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        write_json(&pattern_export(&path, &bank_id, pattern_index)?, &out_file)
+    })
+    .await
+    .unwrap()
+}
+
+#[tauri::command]
+pub async fn import_bank_json(
+    path: String,
+    bank_id: String,
+    in_file: String,
+) -> Result<ImportResult, String> {
+    crate::fs_scope::ensure_allowed(&path)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let export = read_export(&in_file)?;
+        crate::edit_journal::record_edit(
+            &path,
+            "import_bank_json",
+            &crate::edit_journal::bank_files_for_id(&bank_id),
+            || import_bank(&path, &bank_id, &export),
+        )
+    })
+    .await
+    .unwrap()
+}
+
+#[tauri::command]
+pub async fn import_pattern_json(
+    path: String,
+    bank_id: String,
+    pattern_index: u8,
+    in_file: String,
+    include_part: bool,
+) -> Result<ImportResult, String> {
+    crate::fs_scope::ensure_allowed(&path)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let export = read_export(&in_file)?;
+        crate::edit_journal::record_edit(
+            &path,
+            "import_pattern_json",
+            &crate::edit_journal::bank_files_for_id(&bank_id),
+            || import_pattern(&path, &bank_id, pattern_index, &export, include_part),
+        )
+    })
+    .await
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ot_tools_io::ProjectFile;
+    use tempfile::TempDir;
+
+    fn project() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        ProjectFile::default()
+            .to_data_file(&dir.path().join("project.work"))
+            .unwrap();
+        let mut bank = BankFile::default();
+        let pattern = &mut bank.patterns.0[5];
+        pattern.part_assignment = 2;
+        pattern.scale.master_len = 32;
+        let track = &mut pattern.audio_track_trigs.0[3];
+        track.trig_masks.trigger = encode_trig_masks(&std::array::from_fn(|s| s % 4 == 0));
+        track.trig_offsets_repeats_conditions[4] = [2 * 32 + 6, 1]; // 2 repeats, +1/32, Fill
+        track.plocks.0[8].amp.vol = 90;
+        track.plocks.0[8].flex_slot_id = 3;
+        track.plocks.0[8].fx1.param_2 = 40;
+        track.plocks.0[8].fx2.param_6 = 127;
+        // Rec trig on step 7 armed on INCD only
+        track.trig_masks.recorder[8..16]
+            .copy_from_slice(&encode_trig_masks(&std::array::from_fn(|s| s == 6)));
+        let midi = &mut pattern.midi_track_trigs.0[1];
+        midi.trig_masks.trigger = encode_trig_masks(&std::array::from_fn(|s| s == 2));
+        midi.plocks.0[2].midi.note = 60;
+        midi.plocks.0[2].midi.not2 = 67;
+        bank.to_data_file(&dir.path().join("bank02.work")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_export_bank_round_trips_through_json() {
+        let dir = project();
+        let path = dir.path().to_string_lossy().to_string();
+        let out = dir.path().join("out").join("bank_b.json");
+        write_json(&bank_export(&path, "B").unwrap(), &out.to_string_lossy()).unwrap();
+
+        let parsed: BankExport = serde_json::from_str(&fs::read_to_string(&out).unwrap()).unwrap();
+        assert_eq!(parsed.header.kind, "bank");
+        assert_eq!(parsed.header.bank_id, "B");
+        assert_eq!(parsed.header.version, FORMAT_VERSION);
+        assert_eq!(parsed.parts.len(), 4);
+        assert_eq!(parsed.bank.parts[0].patterns.len(), 16);
+    }
+
+    #[test]
+    fn test_export_pattern_includes_assigned_part() {
+        let dir = project();
+        let path = dir.path().to_string_lossy().to_string();
+        let export = pattern_export(&path, "B", 5).unwrap();
+        assert_eq!(export.header.kind, "pattern");
+        assert_eq!(export.pattern.id, 5);
+        assert_eq!(export.part.part_id, 2);
+
+        assert!(
+            pattern_export(&path, "A", 0).is_err(),
+            "bank A does not exist"
+        );
+        assert!(pattern_export(&path, "B", 16).is_err());
+        assert!(bank_export(&path, "Q").is_err());
+    }
+
+    fn steps_json(pattern: &Pattern) -> String {
+        serde_json::to_string(&pattern.tracks.iter().map(|t| &t.steps).collect::<Vec<_>>()).unwrap()
+    }
+
+    #[test]
+    fn test_pattern_import_round_trips() {
+        let dir = project();
+        let path = dir.path().to_string_lossy().to_string();
+        let export = pattern_export(&path, "B", 5).unwrap();
+
+        let result = import_pattern(&path, "C", 9, &export, true).unwrap();
+        assert_eq!(result.patterns, vec![9]);
+        assert_eq!(result.parts, vec![2]);
+        assert!(result.warnings.is_empty(), "{:?}", result.warnings);
+
+        let imported = pattern_export(&path, "C", 9).unwrap();
+        assert_eq!(imported.pattern.length, 32);
+        assert_eq!(imported.pattern.part_assignment, 2);
+        assert_eq!(steps_json(&imported.pattern), steps_json(&export.pattern));
+        let step = &imported.pattern.tracks[3].steps[4];
+        assert_eq!(step.trig_condition.as_deref(), Some("Fill"));
+        assert_eq!(step.micro_timing.as_deref(), Some("+1/32"));
+        assert_eq!(step.trig_repeats, 2);
+        assert_eq!(
+            imported.pattern.tracks[3].steps[6].recorder_sources,
+            ["INCD"]
+        );
+        let fx = imported.fx_plocks.unwrap();
+        assert_eq!(fx.len(), 1);
+        assert_eq!((fx[0].pattern, fx[0].track, fx[0].step), (9, 3, 8));
+        assert_eq!(fx[0].fx1, [255, 40, 255, 255, 255, 255]);
+        assert_eq!(fx[0].fx2, [255, 255, 255, 255, 255, 127]);
+    }
+
+    #[test]
+    fn test_import_without_fx_plocks_warns() {
+        let dir = project();
+        let path = dir.path().to_string_lossy().to_string();
+        let mut export = pattern_export(&path, "B", 5).unwrap();
+        export.fx_plocks = None;
+
+        let result = import_pattern(&path, "C", 0, &export, false).unwrap();
+        assert_eq!(result.warnings.len(), 1, "{:?}", result.warnings);
+        assert!(result.warnings[0].starts_with("Track 4:"));
+        let imported = pattern_export(&path, "C", 0).unwrap();
+        assert_eq!(imported.fx_plocks, Some(Vec::new()));
+    }
+
+    #[test]
+    fn test_bank_import_round_trips_and_backs_up() {
+        let dir = project();
+        let path = dir.path().to_string_lossy().to_string();
+        let export = bank_export(&path, "B").unwrap();
+        BankFile::default()
+            .to_data_file(&dir.path().join("bank01.work"))
+            .unwrap();
+
+        let result = import_bank(&path, "A", &export).unwrap();
+        assert_eq!(result.patterns.len(), 16);
+        assert!(dir.path().join("backups").exists());
+        let imported = bank_export(&path, "A").unwrap();
+        for (a, b) in imported.bank.parts[0]
+            .patterns
+            .iter()
+            .zip(&export.bank.parts[0].patterns)
+        {
+            assert_eq!(steps_json(a), steps_json(b), "pattern {}", a.id + 1);
+            assert_eq!(a.part_assignment, b.part_assignment);
+        }
+    }
+
+    #[test]
+    fn test_import_rejects_invalid_documents() {
+        let dir = project();
+        let path = dir.path().to_string_lossy().to_string();
+        let mut export = pattern_export(&path, "B", 5).unwrap();
+        export.pattern.tracks[3].steps[4].trig_condition = Some("Sometimes".to_string());
+        assert!(import_pattern(&path, "B", 0, &export, false).is_err());
+
+        let mut export = pattern_export(&path, "B", 5).unwrap();
+        export.header.kind = "bank".to_string();
+        assert!(import_pattern(&path, "B", 0, &export, false).is_err());
+
+        // Nothing was written by the rejected imports.
+        assert!(!dir.path().join("backups").exists());
+    }
+}
@@ -0,0 +1,339 @@
+// Batch conversion of a whole folder: every audio file under `source` goes
+// through the regular copy/convert pipeline into the same relative location
+// under `dest`. Progress is reported for the batch as a whole (files, bytes,
+// ETA) rather than per file, and the run ends with a per-file report.
+
+use crate::audio_pool::{
+    collect_audio_files_recursive, copy_and_convert_audio_with_options, dest_filename_with_options,
+    is_cancelled, needs_conversion_with, register_cancellation_token, remove_cancellation_token,
+    ConversionOptions,
+};
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::Instant;
+use tauri::{AppHandle, Emitter};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BatchConvertOptions {
+    #[serde(default)]
+    pub overwrite: bool, // replace files already present in dest (otherwise skipped)
+    #[serde(default)]
+    pub transfer_id: Option<String>, // cancellable via cancel_audio_transfer
+    #[serde(default)]
+    pub conversion: ConversionOptions,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchConvertProgress {
+    pub transfer_id: Option<String>,
+    pub files_done: usize,
+    pub files_total: usize,
+    pub bytes_done: u64, // source bytes, including the current file's share
+    pub bytes_total: u64,
+    pub current_file: Option<String>,
+    pub eta_seconds: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BatchConvertFileResult {
+    pub source: String,
+    pub dest: Option<String>,
+    pub status: String, // "converted", "copied", "skipped", "failed", "cancelled"
+    pub error: Option<String>,
+    #[serde(default)]
+    pub renamed_from: Option<String>, // source name, when sanitize_names changed it
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchConvertReport {
+    pub source: String,
+    pub dest: String,
+    pub files: Vec<BatchConvertFileResult>,
+    pub converted: usize,
+    pub copied: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    pub cancelled: bool,
+    pub elapsed_seconds: f64,
+}
+
+fn eta(started: Instant, done: u64, total: u64) -> Option<f64> {
+    if done == 0 || done >= total {
+        return None;
+    }
+    let elapsed = started.elapsed().as_secs_f64();
+    Some(elapsed / done as f64 * (total - done) as f64)
+}
+
+/// Convert every audio file under `source` into `dest`, keeping sub-folders.
+pub fn convert_directory_sync(
+    source: &Path,
+    dest: &Path,
+    options: &BatchConvertOptions,
+    cancel_token: Option<Arc<AtomicBool>>,
+    on_progress: &dyn Fn(&BatchConvertProgress),
+) -> Result<BatchConvertReport, String> {
+    if !source.is_dir() {
+        return Err(format!("Source is not a directory: {}", source.display()));
+    }
+    let source = source
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve {}: {}", source.display(), e))?;
+    fs::create_dir_all(dest).map_err(|e| format!("Failed to create {}: {}", dest.display(), e))?;
+    let dest = dest
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve {}: {}", dest.display(), e))?;
+    if dest.starts_with(&source) {
+        return Err("Destination must not be inside the source folder".to_string());
+    }
+
+    let sources: Vec<(PathBuf, u64)> = collect_audio_files_recursive(&source.to_string_lossy())?
+        .into_iter()
+        .map(|p| {
+            let size = fs::metadata(&p).map(|m| m.len()).unwrap_or(0);
+            (PathBuf::from(p), size)
+        })
+        .collect();
+    let files_total = sources.len();
+    let bytes_total: u64 = sources.iter().map(|(_, size)| size).sum();
+
+    let started = Instant::now();
+    let bytes_finished = Cell::new(0u64);
+    let progress = |files_done: usize, bytes_done: u64, current: Option<&Path>| {
+        on_progress(&BatchConvertProgress {
+            transfer_id: options.transfer_id.clone(),
+            files_done,
+            files_total,
+            bytes_done,
+            bytes_total,
+            current_file: current.map(|p| p.to_string_lossy().to_string()),
+            eta_seconds: eta(started, bytes_done, bytes_total),
+        });
+    };
+    progress(0, 0, None);
+
+    let mut files = Vec::with_capacity(files_total);
+    let mut cancelled = false;
+    for (index, (path, size)) in sources.iter().enumerate() {
+        let source_str = path.to_string_lossy().to_string();
+        if cancelled || cancel_token.as_ref().is_some_and(is_cancelled) {
+            cancelled = true;
+            files.push(BatchConvertFileResult {
+                source: source_str,
+                dest: None,
+                status: "cancelled".to_string(),
+                error: None,
+                renamed_from: None,
+            });
+            continue;
+        }
+
+        let relative_dir = path
+            .parent()
+            .and_then(|p| p.strip_prefix(&source).ok())
+            .unwrap_or(Path::new(""));
+        let dest_dir = dest.join(relative_dir);
+        let dest_name = dest_filename_with_options(path, &options.conversion);
+        let renamed_from = options
+            .conversion
+            .sanitize_names
+            .then(|| {
+                let plain = ConversionOptions {
+                    sanitize_names: false,
+                    ..options.conversion.clone()
+                };
+                dest_filename_with_options(path, &plain)
+            })
+            .filter(|plain| *plain != dest_name)
+            .and_then(|_| path.file_name())
+            .map(|name| name.to_string_lossy().to_string());
+        let dest_file = dest_dir.join(&dest_name);
+
+        let result = if dest_file.exists() && !options.overwrite {
+            BatchConvertFileResult {
+                source: source_str,
+                dest: Some(dest_file.to_string_lossy().to_string()),
+                status: "skipped".to_string(),
+                error: Some("File already exists".to_string()),
+                renamed_from,
+            }
+        } else {
+            let converting = needs_conversion_with(path, &options.conversion);
+            let outcome = fs::create_dir_all(&dest_dir)
+                .map_err(|e| format!("Failed to create {}: {}", dest_dir.display(), e))
+                .and_then(|_| {
+                    copy_and_convert_audio_with_options(
+                        path,
+                        &dest_dir,
+                        options.overwrite,
+                        &options.conversion,
+                        |_stage: &str, fraction: f32| {
+                            let share = (*size as f64 * fraction.clamp(0.0, 1.0) as f64) as u64;
+                            progress(index, bytes_finished.get() + share, Some(path));
+                        },
+                        cancel_token.clone(),
+                    )
+                });
+            match outcome {
+                Ok(written) => BatchConvertFileResult {
+                    source: source_str,
+                    dest: Some(written.to_string_lossy().to_string()),
+                    status: if converting { "converted" } else { "copied" }.to_string(),
+                    error: None,
+                    renamed_from,
+                },
+                Err(e) if cancel_token.as_ref().is_some_and(is_cancelled) => {
+                    cancelled = true;
+                    BatchConvertFileResult {
+                        source: source_str,
+                        dest: None,
+                        status: "cancelled".to_string(),
+                        error: Some(e),
+                        renamed_from,
+                    }
+                }
+                Err(e) => BatchConvertFileResult {
+                    source: source_str,
+                    dest: None,
+                    status: "failed".to_string(),
+                    error: Some(e),
+                    renamed_from,
+                },
+            }
+        };
+        files.push(result);
+        bytes_finished.set(bytes_finished.get() + size);
+        progress(index + 1, bytes_finished.get(), None);
+    }
+
+    let count = |status: &str| files.iter().filter(|f| f.status == status).count();
+    Ok(BatchConvertReport {
+        source: source.to_string_lossy().to_string(),
+        dest: dest.to_string_lossy().to_string(),
+        converted: count("converted"),
+        copied: count("copied"),
+        skipped: count("skipped"),
+        failed: count("failed"),
+        cancelled,
+        elapsed_seconds: started.elapsed().as_secs_f64(),
+        files,
+    })
+}
+
+/// Convert a whole folder for the Octatrack. Emits "batch-convert-progress"
+/// events; cancellable via cancel_audio_transfer when a transfer_id is given.
+#[tauri::command]
+pub async fn convert_directory(
+    app: AppHandle,
+    source: String,
+    dest: String,
+    options: Option<BatchConvertOptions>,
+) -> Result<BatchConvertReport, String> {
+    crate::fs_scope::ensure_allowed(&dest)?;
+    let options = options.unwrap_or_default();
+    let cancel_token = options
+        .transfer_id
+        .as_deref()
+        .map(register_cancellation_token);
+    let transfer_id = options.transfer_id.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        convert_directory_sync(
+            Path::new(&source),
+            Path::new(&dest),
+            &options,
+            cancel_token,
+            &|progress| {
+                let _ = app.emit("batch-convert-progress", progress);
+            },
+        )
+    })
+    .await
+    .unwrap();
+    if let Some(id) = transfer_id {
+        remove_cancellation_token(&id);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{ramp, write_wav};
+    use std::cell::RefCell;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_convert_directory_keeps_layout_and_reports_each_file() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("src");
+        let dest = tmp.path().join("out");
+        fs::create_dir_all(src.join("kit")).unwrap();
+        write_wav(&src.join("ok.wav"), 1, 44100, &ramp(4800));
+        write_wav(&src.join("kit").join("hi.wav"), 1, 48000, &ramp(4800));
+        fs::write(src.join("broken.mp3"), b"not audio").unwrap();
+        fs::write(src.join("readme.txt"), b"ignored").unwrap();
+
+        let events = RefCell::new(Vec::new());
+        let report =
+            convert_directory_sync(&src, &dest, &BatchConvertOptions::default(), None, &|p| {
+                events.borrow_mut().push(p.clone())
+            })
+            .unwrap();
+
+        assert_eq!(report.files.len(), 3);
+        assert_eq!((report.converted, report.copied, report.failed), (1, 1, 1));
+        assert!(dest.join("ok.wav").exists());
+        let converted = hound::WavReader::open(dest.join("kit").join("hi.wav")).unwrap();
+        assert_eq!(converted.spec().sample_rate, 44100);
+        assert!(!dest.join("readme.txt").exists());
+
+        let events = events.into_inner();
+        let last = events.last().unwrap();
+        assert_eq!((last.files_done, last.files_total), (3, 3));
+        assert_eq!(last.bytes_done, last.bytes_total);
+        assert!(events
+            .windows(2)
+            .all(|w| w[0].bytes_done <= w[1].bytes_done));
+
+        // A second run leaves existing output alone unless asked to overwrite.
+        let again =
+            convert_directory_sync(&src, &dest, &BatchConvertOptions::default(), None, &|_| {})
+                .unwrap();
+        assert_eq!(again.skipped, 2);
+        assert!(
+            convert_directory_sync(&src, &src.join("kit"), &Default::default(), None, &|_| {})
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_convert_directory_reports_sanitized_names() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("src");
+        let dest = tmp.path().join("out");
+        fs::create_dir_all(&src).unwrap();
+        write_wav(&src.join("Café: take 1.wav"), 1, 48000, &ramp(4800));
+        write_wav(&src.join("plain.wav"), 1, 44100, &ramp(4800));
+
+        let options = BatchConvertOptions {
+            conversion: ConversionOptions {
+                sanitize_names: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let report = convert_directory_sync(&src, &dest, &options, None, &|_| {}).unwrap();
+
+        assert!(dest.join("Cafe_ take 1.wav").exists());
+        let renamed: Vec<_> = report
+            .files
+            .iter()
+            .filter_map(|f| f.renamed_from.as_deref())
+            .collect();
+        assert_eq!(renamed, vec!["Café: take 1.wav"]);
+    }
+}
@@ -0,0 +1,105 @@
+// One watched directory at a time, with filesystem event bursts coalesced:
+// shared by the project watcher and the Audio Pool watcher.
+//
+// notify reports every write separately (a save touches a file several
+// times), so paths are collected until no event arrived for `DEBOUNCE` and
+// handed over as one set. Watching is non-recursive; access events are
+// ignored.
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Quiet period after the last filesystem event before changes are handed over.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// The directory a watcher is on, if any: a later `watch` replaces it.
+pub type WatchSlot = Mutex<Option<(String, RecommendedWatcher)>>;
+
+/// Watch `dir` in `slot`, replacing what it watched before. `on_changes` runs
+/// on a background thread with the paths changed in each burst.
+pub fn watch(
+    slot: &WatchSlot,
+    dir: &Path,
+    mut on_changes: impl FnMut(BTreeSet<PathBuf>) + Send + 'static,
+) -> Result<(), String> {
+    let (tx, rx) = mpsc::channel::<PathBuf>();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        let Ok(event) = res else { return };
+        if matches!(event.kind, EventKind::Access(_)) {
+            return;
+        }
+        for path in event.paths {
+            let _ = tx.send(path);
+        }
+    })
+    .map_err(|e| format!("Failed to create file watcher: {}", e))?;
+    watcher
+        .watch(dir, RecursiveMode::NonRecursive)
+        .map_err(|e| format!("Failed to watch {}: {}", dir.display(), e))?;
+
+    // The sender lives in the watcher's handler, so this thread ends once the
+    // watcher is dropped by unwatch() or a later watch().
+    std::thread::spawn(move || {
+        while let Ok(first) = rx.recv() {
+            let mut changed = BTreeSet::from([first]);
+            while let Ok(path) = rx.recv_timeout(DEBOUNCE) {
+                changed.insert(path);
+            }
+            on_changes(changed);
+        }
+    });
+
+    *slot.lock().unwrap() = Some((dir.to_string_lossy().to_string(), watcher));
+    Ok(())
+}
+
+/// Stop the watcher in `slot`. Returns the directory it was watching, if any.
+pub fn unwatch(slot: &WatchSlot) -> Option<String> {
+    slot.lock().unwrap().take().map(|(path, _)| path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_bursts_are_coalesced_until_unwatched() {
+        let dir = TempDir::new().unwrap();
+        let slot = WatchSlot::default();
+        let (tx, rx) = mpsc::channel();
+        watch(&slot, dir.path(), move |changed| {
+            let _ = tx.send(changed);
+        })
+        .unwrap();
+
+        for _ in 0..3 {
+            std::fs::write(dir.path().join("bank01.work"), b"x").unwrap();
+        }
+        std::fs::write(dir.path().join("bank02.work"), b"x").unwrap();
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let mut seen = BTreeSet::new();
+        while seen.len() < 2 && Instant::now() < deadline {
+            if let Ok(changed) = rx.recv_timeout(Duration::from_millis(100)) {
+                seen.extend(
+                    changed
+                        .into_iter()
+                        .filter_map(|p| p.file_name().map(|n| n.to_owned())),
+                );
+            }
+        }
+        assert!(seen.contains(std::ffi::OsStr::new("bank01.work")));
+        assert!(seen.contains(std::ffi::OsStr::new("bank02.work")));
+
+        assert_eq!(
+            unwatch(&slot).as_deref(),
+            Some(dir.path().to_string_lossy().as_ref())
+        );
+        assert!(unwatch(&slot).is_none());
+    }
+}
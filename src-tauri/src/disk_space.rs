@@ -0,0 +1,430 @@
+// Low-disk-space guard, cleanup suggestions and disk usage breakdown.
+//
+// Large writes (backups, conversions, chain builds) call `ensure_free_space`
+// before touching the disk. When a volume is nearly full the frontend can ask
+// for `cleanup_suggestions`, built from the existing analyzers: content hashes
+// (duplicates), the pool-merge junk filter, and Audio Pool usage.
+// `analyze_disk_usage` shows where the space goes instead: per folder, per
+// format/sample rate/bit depth, and against the card's capacity.
+
+use crate::audio_pool::{extract_audio_metadata, file_content_hash, is_audio_file};
+use crate::project_manager::check_free_space;
+use crate::project_reader::{compute_pool_usage, normalize_path_lexically, pool_usage_key};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Files listed in `DiskUsage::largest_files`.
+const LARGEST_FILES: usize = 20;
+
+/// Fail early when the volume holding `target` cannot take `required_bytes`.
+/// The error points the user at the cleanup suggestions.
+pub fn ensure_free_space(target: &Path, required_bytes: u64) -> Result<(), String> {
+    // The target file/directory may not exist yet: check the nearest existing ancestor.
+    let existing = target.ancestors().find(|p| p.exists()).unwrap_or(target);
+    check_free_space(existing, required_bytes).map_err(|e| {
+        format!(
+            "{}. Free up space (duplicates, junk files, unused samples) and try again.",
+            e
+        )
+    })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CleanupFile {
+    pub path: String,
+    pub size: u64,
+}
+
+/// Files with identical contents. Keeping the first one frees `size * (paths.len() - 1)`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateGroup {
+    pub size: u64,
+    pub paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CleanupSuggestions {
+    pub duplicates: Vec<DuplicateGroup>,
+    pub junk_files: Vec<CleanupFile>,
+    /// Audio Pool files no project of the Set references (empty when the
+    /// directory is not an Audio Pool or usage could not be computed).
+    pub unused_samples: Vec<CleanupFile>,
+    pub reclaimable_bytes: u64,
+}
+
+/// OS/tool leftovers that are never audio the Octatrack can use.
+fn is_junk_file(name: &str) -> bool {
+    let lower = name.to_lowercase();
+    lower == ".ds_store"
+        || lower == "thumbs.db"
+        || lower == "desktop.ini"
+        || lower.starts_with("._")
+        || lower.ends_with(".tmp")
+}
+
+/// Scan `dir` and suggest what could be removed to reclaim space.
+pub fn cleanup_suggestions(dir: &str) -> Result<CleanupSuggestions, String> {
+    let root = Path::new(dir);
+    if !root.is_dir() {
+        return Err(format!("Directory not found: {}", dir));
+    }
+
+    // Usage is only meaningful for a Set's Audio Pool ("AUDIO" next to the projects).
+    let is_audio_pool = root
+        .file_name()
+        .is_some_and(|n| n.to_string_lossy().eq_ignore_ascii_case("AUDIO"));
+    let usage = if is_audio_pool {
+        compute_pool_usage(dir).ok()
+    } else {
+        None
+    };
+    let mut junk_files = Vec::new();
+    let mut unused_samples = Vec::new();
+    let mut by_size: HashMap<u64, Vec<String>> = HashMap::new();
+
+    for entry in WalkDir::new(root).into_iter().filter_map(|e| e.ok()) {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let path = entry.path();
+        let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+        let path_str = path.to_string_lossy().to_string();
+        let name = entry.file_name().to_string_lossy();
+
+        if is_junk_file(&name) {
+            junk_files.push(CleanupFile {
+                path: path_str,
+                size,
+            });
+            continue;
+        }
+        if size > 0 {
+            by_size.entry(size).or_default().push(path_str.clone());
+        }
+        if let Some(usage) = &usage {
+            let key = pool_usage_key(&normalize_path_lexically(path));
+            let is_audio = path
+                .extension()
+                .map(|e| {
+                    let e = e.to_string_lossy().to_lowercase();
+                    e == "wav" || e == "aif" || e == "aiff"
+                })
+                .unwrap_or(false);
+            if is_audio && !usage.contains_key(&key) {
+                unused_samples.push(CleanupFile {
+                    path: path_str,
+                    size,
+                });
+            }
+        }
+    }
+
+    // Only same-size files can be duplicates; hash just those.
+    let mut duplicates = Vec::new();
+    for (size, paths) in by_size.into_iter().filter(|(_, p)| p.len() > 1) {
+        let mut by_hash: HashMap<u64, Vec<String>> = HashMap::new();
+        for p in paths {
+            if let Ok(hash) = file_content_hash(Path::new(&p)) {
+                by_hash.entry(hash).or_default().push(p);
+            }
+        }
+        for (_, mut group) in by_hash.into_iter().filter(|(_, g)| g.len() > 1) {
+            group.sort();
+            duplicates.push(DuplicateGroup { size, paths: group });
+        }
+    }
+    duplicates.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.paths.cmp(&b.paths)));
+    junk_files.sort_by(|a, b| a.path.cmp(&b.path));
+    unused_samples.sort_by(|a, b| a.path.cmp(&b.path));
+
+    // A file can be both unused and a duplicate: count each path once. A
+    // duplicate group frees all copies but one of those not removed already.
+    let mut removable: HashMap<&str, u64> = junk_files
+        .iter()
+        .chain(&unused_samples)
+        .map(|f| (f.path.as_str(), f.size))
+        .collect();
+    for group in &duplicates {
+        let remaining: Vec<&str> = group
+            .paths
+            .iter()
+            .map(String::as_str)
+            .filter(|p| !removable.contains_key(p))
+            .collect();
+        for path in remaining.into_iter().skip(1) {
+            removable.insert(path, group.size);
+        }
+    }
+    let reclaimable_bytes = removable.values().sum();
+
+    Ok(CleanupSuggestions {
+        duplicates,
+        junk_files,
+        unused_samples,
+        reclaimable_bytes,
+    })
+}
+
+/// Total on-disk size of the given files (missing files count as 0).
+pub fn files_size(paths: &[std::path::PathBuf]) -> u64 {
+    paths
+        .iter()
+        .filter_map(|p| fs::metadata(p).ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+/// Total size of one folder, sub-folders included.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectoryUsage {
+    pub path: String,
+    pub relative: String, // "" for the analysed folder itself
+    pub bytes: u64,
+    pub files: usize,
+}
+
+/// Files and bytes sharing one format, sample rate or bit depth.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UsageBucket {
+    pub key: String, // "WAV", "44100", "24"... or "unknown"
+    pub files: usize,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskUsage {
+    pub path: String,
+    pub total_bytes: u64,
+    pub file_count: usize,
+    pub directories: Vec<DirectoryUsage>, // largest first
+    pub largest_files: Vec<CleanupFile>,
+    pub by_format: Vec<UsageBucket>,
+    pub by_sample_rate: Vec<UsageBucket>, // audio files only
+    pub by_bit_depth: Vec<UsageBucket>,   // audio files only
+    pub card_total_bytes: Option<u64>,
+    pub card_free_bytes: Option<u64>,
+}
+
+fn add_to_bucket(buckets: &mut HashMap<String, UsageBucket>, key: String, bytes: u64) {
+    let bucket = buckets.entry(key.clone()).or_insert(UsageBucket {
+        key,
+        files: 0,
+        bytes: 0,
+    });
+    bucket.files += 1;
+    bucket.bytes += bytes;
+}
+
+fn sorted_buckets(buckets: HashMap<String, UsageBucket>) -> Vec<UsageBucket> {
+    let mut out: Vec<UsageBucket> = buckets.into_values().collect();
+    out.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.key.cmp(&b.key)));
+    out
+}
+
+/// Break down what the files under `dir` use on disk.
+pub fn analyze_disk_usage(dir: &str) -> Result<DiskUsage, String> {
+    let root = Path::new(dir);
+    if !root.is_dir() {
+        return Err(format!("Directory not found: {}", dir));
+    }
+
+    let mut directories: HashMap<PathBuf, (u64, usize)> = HashMap::new();
+    let mut files = Vec::new();
+    let mut by_format = HashMap::new();
+    let mut by_sample_rate = HashMap::new();
+    let mut by_bit_depth = HashMap::new();
+
+    for entry in WalkDir::new(root).into_iter().filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if entry.file_type().is_dir() {
+            directories.entry(path.to_path_buf()).or_default();
+            continue;
+        }
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+        // Count the file in every folder from its own up to the analysed one
+        for ancestor in path.ancestors().skip(1) {
+            let totals = directories.entry(ancestor.to_path_buf()).or_default();
+            totals.0 += size;
+            totals.1 += 1;
+            if ancestor == root {
+                break;
+            }
+        }
+
+        let name = entry.file_name().to_string_lossy();
+        let format = path
+            .extension()
+            .map(|e| e.to_string_lossy().to_uppercase())
+            .unwrap_or_else(|| "unknown".to_string());
+        add_to_bucket(&mut by_format, format, size);
+        if is_audio_file(&name) {
+            let (_, bit_depth, sample_rate) = extract_audio_metadata(&path.to_path_buf());
+            let key = |v: Option<u32>| v.map_or("unknown".to_string(), |v| v.to_string());
+            add_to_bucket(&mut by_sample_rate, key(sample_rate), size);
+            add_to_bucket(&mut by_bit_depth, key(bit_depth), size);
+        }
+        files.push(CleanupFile {
+            path: path.to_string_lossy().to_string(),
+            size,
+        });
+    }
+
+    let total_bytes = files.iter().map(|f| f.size).sum();
+    let file_count = files.len();
+    files.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+    files.truncate(LARGEST_FILES);
+
+    let mut directories: Vec<DirectoryUsage> = directories
+        .into_iter()
+        .map(|(path, (bytes, files))| DirectoryUsage {
+            relative: path
+                .strip_prefix(root)
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            path: path.to_string_lossy().to_string(),
+            bytes,
+            files,
+        })
+        .collect();
+    directories.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.path.cmp(&b.path)));
+
+    Ok(DiskUsage {
+        path: dir.to_string(),
+        total_bytes,
+        file_count,
+        directories,
+        largest_files: files,
+        by_format: sorted_buckets(by_format),
+        by_sample_rate: sorted_buckets(by_sample_rate),
+        by_bit_depth: sorted_buckets(by_bit_depth),
+        card_total_bytes: fs2::total_space(root).ok(),
+        card_free_bytes: fs2::available_space(root).ok(),
+    })
+}
+
+#[tauri::command]
+pub async fn analyze_pool_usage(path: String) -> Result<DiskUsage, String> {
+    tauri::async_runtime::spawn_blocking(move || analyze_disk_usage(&path))
+        .await
+        .unwrap()
+}
+
+#[tauri::command]
+pub async fn get_cleanup_suggestions(path: String) -> Result<CleanupSuggestions, String> {
+    tauri::async_runtime::spawn_blocking(move || cleanup_suggestions(&path))
+        .await
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ot_tools_io::{OctatrackFileIO, ProjectFile};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_ensure_free_space_accepts_missing_target() {
+        let dir = TempDir::new().unwrap();
+        let target = dir.path().join("not/yet/created.wav");
+        assert!(ensure_free_space(&target, 0).is_ok());
+    }
+
+    #[test]
+    fn test_ensure_free_space_error_mentions_cleanup() {
+        let dir = TempDir::new().unwrap();
+        let err = ensure_free_space(dir.path(), u64::MAX).unwrap_err();
+        assert!(err.contains("Not enough free space"), "got: {}", err);
+        assert!(err.contains("unused samples"), "got: {}", err);
+    }
+
+    #[test]
+    fn test_cleanup_suggestions_finds_duplicates_and_junk() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("drums")).unwrap();
+        fs::write(dir.path().join("kick.wav"), b"same-bytes").unwrap();
+        fs::write(dir.path().join("drums/kick copy.wav"), b"same-bytes").unwrap();
+        fs::write(dir.path().join("snare.wav"), b"other-byte").unwrap();
+        fs::write(dir.path().join(".DS_Store"), b"junk").unwrap();
+        fs::write(dir.path().join("._kick.wav"), b"resource").unwrap();
+
+        let suggestions = cleanup_suggestions(&dir.path().to_string_lossy()).unwrap();
+
+        assert_eq!(suggestions.duplicates.len(), 1);
+        assert_eq!(suggestions.duplicates[0].paths.len(), 2);
+        assert_eq!(suggestions.junk_files.len(), 2);
+        assert!(suggestions.unused_samples.is_empty(), "not an Audio Pool");
+        assert_eq!(suggestions.reclaimable_bytes, 10 + 4 + 8);
+    }
+
+    #[test]
+    fn test_analyze_disk_usage_groups_by_folder_and_format() {
+        let dir = TempDir::new().unwrap();
+        let drums = dir.path().join("drums");
+        fs::create_dir_all(&drums).unwrap();
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 48000,
+            bits_per_sample: 24,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(drums.join("kick.wav"), spec).unwrap();
+        for _ in 0..1000 {
+            writer.write_sample(0i32).unwrap();
+        }
+        writer.finalize().unwrap();
+        fs::write(dir.path().join("notes.txt"), vec![0u8; 100]).unwrap();
+        let kick_size = fs::metadata(drums.join("kick.wav")).unwrap().len();
+
+        let usage = analyze_disk_usage(&dir.path().to_string_lossy()).unwrap();
+
+        assert_eq!(usage.file_count, 2);
+        assert_eq!(usage.total_bytes, kick_size + 100);
+        assert_eq!(usage.directories[0].relative, "");
+        assert_eq!(usage.directories[0].bytes, kick_size + 100);
+        assert_eq!(usage.directories[1].relative, "drums");
+        assert_eq!(usage.directories[1].bytes, kick_size);
+        assert!(usage.largest_files[0].path.ends_with("kick.wav"));
+        assert_eq!(usage.by_format.len(), 2);
+        assert_eq!(
+            usage.by_sample_rate,
+            vec![UsageBucket {
+                key: "48000".to_string(),
+                files: 1,
+                bytes: kick_size,
+            }]
+        );
+        assert_eq!(usage.by_bit_depth[0].key, "24");
+        assert!(usage.card_total_bytes.is_some());
+    }
+
+    #[test]
+    fn test_cleanup_suggestions_lists_unused_pool_samples() {
+        let set = TempDir::new().unwrap();
+        let pool = set.path().join("AUDIO");
+        let project = set.path().join("PROJECT");
+        fs::create_dir_all(&pool).unwrap();
+        fs::create_dir_all(&project).unwrap();
+        ProjectFile::default()
+            .to_data_file(&project.join("project.work"))
+            .unwrap();
+        fs::write(pool.join("orphan.wav"), b"RIFF").unwrap();
+
+        let suggestions = cleanup_suggestions(&pool.to_string_lossy()).unwrap();
+
+        assert_eq!(suggestions.unused_samples.len(), 1);
+        assert!(suggestions.unused_samples[0].path.ends_with("orphan.wav"));
+
+        // Unused duplicates are suggested twice but freed once
+        fs::write(pool.join("orphan copy.wav"), b"RIFF").unwrap();
+        let suggestions = cleanup_suggestions(&pool.to_string_lossy()).unwrap();
+        assert_eq!(suggestions.unused_samples.len(), 2);
+        assert_eq!(suggestions.duplicates.len(), 1);
+        assert_eq!(suggestions.reclaimable_bytes, 8);
+    }
+}
@@ -0,0 +1,587 @@
+// Edit journal: undo/redo for the app's write commands.
+//
+// Each journaled command snapshots the project files it may touch before and
+// after running. The "before" copy is the inverse operation: undo writes it
+// back, redo writes the "after" copy again. Undo/redo refuse to run when the
+// file on disk no longer matches what the journal expects (e.g. the card was
+// edited on the Octatrack in between), so they never silently discard work.
+//
+// Like project notes, the journal lives in the app's data directory keyed by
+// project fingerprint; nothing is written into the project folder. Sample
+// files and `.ot` sidecars are not journaled, only project/bank/markers files.
+
+use crate::project_notes::project_fingerprint;
+use crate::project_reader::{bank_file_lock, bank_index};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+static JOURNAL_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+static PROJECT_LOCKS: Lazy<Mutex<HashMap<PathBuf, Arc<Mutex<()>>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Oldest entries are dropped beyond this many per project...
+const MAX_ENTRIES: usize = 50;
+/// ...or once a project's snapshots take more than this many bytes (a bank
+/// file is about 600 KB, and bulk copies snapshot several of them).
+const MAX_SNAPSHOT_BYTES: u64 = 200 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct JournalFile {
+    pub name: String, // relative to the project, e.g. "bank03.work"
+    pub existed_before: bool,
+    pub existed_after: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct JournalEntry {
+    pub id: u64,
+    pub label: String,     // command name, e.g. "save_parts"
+    pub timestamp: String, // RFC 3339, local time
+    pub files: Vec<JournalFile>,
+    #[serde(default)]
+    pub bytes: u64, // size of the entry's snapshots
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EditJournal {
+    pub entries: Vec<JournalEntry>,
+    /// Entries `..applied` are in effect (undoable), `applied..` were undone (redoable).
+    pub applied: usize,
+    #[serde(default)]
+    next_id: u64,
+}
+
+/// Default journal location: `<data dir>/octatrack-manager/edit_journal`.
+pub fn default_journal_root() -> Result<PathBuf, String> {
+    crate::app_data::data_path("edit_journal")
+}
+
+/// Run `f` while no journaled edit, undo or redo is in progress (maintenance
+/// prunes journals under it).
+pub(crate) fn with_journal_lock<T>(f: impl FnOnce() -> T) -> T {
+    let _guard = JOURNAL_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    f()
+}
+
+/// Locks serializing the journaled edits, undos and redos of `project_dirs`,
+/// in a fixed order so edits spanning several projects can't deadlock. Hold
+/// the guards of every returned lock for the whole operation.
+fn project_edit_locks<'a>(project_dirs: impl IntoIterator<Item = &'a Path>) -> Vec<Arc<Mutex<()>>> {
+    let mut dirs: Vec<PathBuf> = project_dirs
+        .into_iter()
+        .map(|d| d.canonicalize().unwrap_or_else(|_| d.to_path_buf()))
+        .collect();
+    dirs.sort();
+    dirs.dedup();
+    let mut locks = PROJECT_LOCKS.lock().unwrap_or_else(|e| e.into_inner());
+    dirs.into_iter()
+        .map(|d| locks.entry(d).or_default().clone())
+        .collect()
+}
+
+/// Ids of the entries recorded in a project journal directory.
+pub(crate) fn journal_entry_ids(dir: &Path) -> Result<Vec<u64>, String> {
+    Ok(load_journal(dir)?.entries.iter().map(|e| e.id).collect())
+}
+
+/// Journal directory of one project: its folder name and a CRC of its
+/// fingerprint (the full path, which can't be a folder name as is).
+fn project_journal_dir(root: &Path, project_path: &Path) -> PathBuf {
+    let key = project_fingerprint(project_path);
+    let mut crc = flate2::Crc::new();
+    crc.update(key.as_bytes());
+    let name: String = Path::new(&key)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    root.join(format!("{}-{:08x}", name, crc.sum()))
+}
+
+fn load_journal(dir: &Path) -> Result<EditJournal, String> {
+    let path = dir.join("journal.json");
+    if !path.exists() {
+        return Ok(EditJournal::default());
+    }
+    let data = fs::read_to_string(&path).map_err(|e| format!("Failed to read journal: {}", e))?;
+    serde_json::from_str(&data).map_err(|e| format!("Failed to parse journal: {}", e))
+}
+
+fn save_journal(dir: &Path, journal: &EditJournal) -> Result<(), String> {
+    crate::app_data::save_json(&dir.join("journal.json"), journal, "journal")
+}
+
+/// Snapshot file of one side ("before"/"after") of an entry.
+fn snapshot_path(dir: &Path, id: u64, side: &str, name: &str) -> PathBuf {
+    dir.join(id.to_string()).join(side).join(name)
+}
+
+fn read_optional(path: &Path) -> Result<Option<Vec<u8>>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    fs::read(path)
+        .map(Some)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+}
+
+/// Bank files a bank edit may write (the reader falls back to .strd).
+pub fn bank_files(bank_index: u8) -> Vec<String> {
+    let num = bank_index as usize + 1;
+    vec![
+        format!("bank{:02}.work", num),
+        format!("bank{:02}.strd", num),
+    ]
+}
+
+/// [`bank_files`] for a bank letter ("A".."P"); empty for an invalid letter,
+/// which the journaled command itself will reject.
+pub fn bank_files_for_id(bank_id: &str) -> Vec<String> {
+    bank_index(bank_id).map(bank_files).unwrap_or_default()
+}
+
+/// [`bank_files`] of several banks.
+pub fn banks_files(bank_indices: &[u8]) -> Vec<String> {
+    bank_indices.iter().flat_map(|&i| bank_files(i)).collect()
+}
+
+/// Bank files of all 16 banks.
+pub fn all_bank_files() -> Vec<String> {
+    banks_files(&(0..16).collect::<Vec<u8>>())
+}
+
+/// Files written by sample slot edits.
+pub fn project_files() -> Vec<String> {
+    vec!["project.work".to_string(), "markers.work".to_string()]
+}
+
+/// True for the project files the journal snapshots (project, banks,
+/// markers), given relative to the project.
+pub fn is_journaled_file(name: &str) -> bool {
+    !name.contains(['/', '\\']) && (name.ends_with(".work") || name.ends_with(".strd"))
+}
+
+/// Run `edit` and journal the changes it made to `files`. The edit's result is
+/// returned even if journaling fails: the write already happened.
+pub fn record_edit_in<T, E: From<String>>(
+    root: &Path,
+    project_path: &str,
+    label: &str,
+    files: &[String],
+    edit: impl FnOnce() -> Result<T, E>,
+) -> Result<T, E> {
+    record_edits_in(root, &[(project_path, files.to_vec())], label, edit)
+}
+
+/// [`record_edit_in`] for an edit that writes to several projects (a bank
+/// moved between projects): each project gets its own entry.
+pub fn record_edits_in<T, E: From<String>>(
+    root: &Path,
+    projects: &[(&str, Vec<String>)],
+    label: &str,
+    edit: impl FnOnce() -> Result<T, E>,
+) -> Result<T, E> {
+    // Edits of the same project run one after another, so an entry's
+    // snapshots only ever bracket its own writes. The journal itself is
+    // locked only while snapshotting and appending; other projects, undo and
+    // maintenance aren't held up while `edit` runs.
+    let locks = project_edit_locks(projects.iter().map(|(p, _)| Path::new(*p)));
+    let _project_guards: Vec<_> = locks
+        .iter()
+        .map(|l| l.lock().unwrap_or_else(|e| e.into_inner()))
+        .collect();
+
+    let mut before = Vec::new();
+    {
+        let _guard = JOURNAL_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        for (project_path, files) in projects {
+            let project_dir = Path::new(project_path);
+            crate::project_lock::ensure_unlocked(project_dir)?;
+            let snapshot: Vec<Option<Vec<u8>>> = files
+                .iter()
+                .map(|f| read_optional(&project_dir.join(f)))
+                .collect::<Result<_, _>>()?;
+            before.push(snapshot);
+        }
+    }
+
+    let result = edit()?;
+
+    let _guard = JOURNAL_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    for ((project_path, files), before) in projects.iter().zip(before) {
+        if let Err(e) = append_entry(root, Path::new(project_path), label, files, before) {
+            eprintln!("[JOURNAL] Failed to record {}: {}", label, e);
+        }
+    }
+    Ok(result)
+}
+
+fn append_entry(
+    root: &Path,
+    project_dir: &Path,
+    label: &str,
+    files: &[String],
+    before: Vec<Option<Vec<u8>>>,
+) -> Result<(), String> {
+    let mut changed = Vec::new();
+    for (name, before) in files.iter().zip(before) {
+        let after = read_optional(&project_dir.join(name))?;
+        if before != after {
+            changed.push((name, before, after));
+        }
+    }
+    if changed.is_empty() {
+        return Ok(());
+    }
+
+    let dir = project_journal_dir(root, project_dir);
+    let mut journal = load_journal(&dir)?;
+
+    // A new edit invalidates everything that was undone.
+    for entry in journal.entries.drain(journal.applied..) {
+        let _ = fs::remove_dir_all(dir.join(entry.id.to_string()));
+    }
+
+    let id = journal.next_id;
+    journal.next_id += 1;
+    let mut entry = JournalEntry {
+        id,
+        label: label.to_string(),
+        timestamp: chrono::Local::now().to_rfc3339(),
+        files: Vec::new(),
+        bytes: 0,
+    };
+    for (name, before, after) in changed {
+        for (side, data) in [("before", &before), ("after", &after)] {
+            if let Some(data) = data {
+                entry.bytes += data.len() as u64;
+                let path = snapshot_path(&dir, id, side, name);
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)
+                        .map_err(|e| format!("Failed to create journal directory: {}", e))?;
+                }
+                crate::atomic_write::write_atomic(&path, data)
+                    .map_err(|e| format!("Failed to write journal snapshot: {}", e))?;
+            }
+        }
+        entry.files.push(JournalFile {
+            name: name.clone(),
+            existed_before: before.is_some(),
+            existed_after: after.is_some(),
+        });
+    }
+    journal.entries.push(entry);
+
+    prune_entries(&dir, &mut journal, MAX_SNAPSHOT_BYTES);
+    journal.applied = journal.entries.len();
+    save_journal(&dir, &journal)
+}
+
+/// Drop the oldest entries (and their snapshots) beyond `MAX_ENTRIES` or
+/// `max_bytes` of snapshots. The newest entry is kept however large it is.
+fn prune_entries(dir: &Path, journal: &mut EditJournal, max_bytes: u64) {
+    let mut total: u64 = journal.entries.iter().map(|e| e.bytes).sum();
+    while journal.entries.len() > MAX_ENTRIES || (journal.entries.len() > 1 && total > max_bytes) {
+        let oldest = journal.entries.remove(0);
+        total -= oldest.bytes;
+        let _ = fs::remove_dir_all(dir.join(oldest.id.to_string()));
+    }
+}
+
+/// Replace the project files of `entry` with its `to` side, after checking they
+/// still hold its `from` side.
+fn apply_side(
+    dir: &Path,
+    project_dir: &Path,
+    entry: &JournalEntry,
+    from: &str,
+    to: &str,
+) -> Result<(), String> {
+    let side_exists = |file: &JournalFile, side: &str| {
+        if side == "before" {
+            file.existed_before
+        } else {
+            file.existed_after
+        }
+    };
+    let side_data = |file: &JournalFile, side: &str| -> Result<Option<Vec<u8>>, String> {
+        if !side_exists(file, side) {
+            return Ok(None);
+        }
+        read_optional(&snapshot_path(dir, entry.id, side, &file.name))?
+            .map(Some)
+            .ok_or_else(|| format!("Journal snapshot of {} is missing", file.name))
+    };
+
+    // Check every file first so a conflict leaves the project untouched.
+    let mut targets = Vec::new();
+    for file in &entry.files {
+        let current = read_optional(&project_dir.join(&file.name))?;
+        if current != side_data(file, from)? {
+            return Err(format!(
+                "{} was modified after \"{}\"; refusing to overwrite those changes",
+                file.name, entry.label
+            ));
+        }
+        targets.push((file, side_data(file, to)?));
+    }
+
+    for (file, data) in targets {
+        let path = project_dir.join(&file.name);
+        let lock = bank_file_lock(&path);
+        let _guard = lock.lock().unwrap_or_else(|e| e.into_inner());
+        match data {
+            Some(data) => crate::atomic_write::write_atomic(&path, &data)
+                .map_err(|e| format!("Failed to restore {}: {}", file.name, e))?,
+            None => fs::remove_file(&path)
+                .map_err(|e| format!("Failed to remove {}: {}", file.name, e))?,
+        }
+    }
+    Ok(())
+}
+
+/// Revert the most recent applied edit. Returns the entry that was undone.
+pub fn undo_in(root: &Path, project_path: &str) -> Result<JournalEntry, String> {
+    let project_dir = Path::new(project_path);
+    let locks = project_edit_locks([project_dir]);
+    let _project_guard = locks[0].lock().unwrap_or_else(|e| e.into_inner());
+    let _guard = JOURNAL_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    crate::project_lock::ensure_unlocked(project_dir)?;
+    let dir = project_journal_dir(root, project_dir);
+    let mut journal = load_journal(&dir)?;
+    if journal.applied == 0 {
+        return Err("Nothing to undo".to_string());
+    }
+    let entry = journal.entries[journal.applied - 1].clone();
+    apply_side(&dir, project_dir, &entry, "after", "before")?;
+    journal.applied -= 1;
+    save_journal(&dir, &journal)?;
+    Ok(entry)
+}
+
+/// Re-apply the most recently undone edit. Returns the entry that was redone.
+pub fn redo_in(root: &Path, project_path: &str) -> Result<JournalEntry, String> {
+    let project_dir = Path::new(project_path);
+    let locks = project_edit_locks([project_dir]);
+    let _project_guard = locks[0].lock().unwrap_or_else(|e| e.into_inner());
+    let _guard = JOURNAL_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    crate::project_lock::ensure_unlocked(project_dir)?;
+    let dir = project_journal_dir(root, project_dir);
+    let mut journal = load_journal(&dir)?;
+    if journal.applied >= journal.entries.len() {
+        return Err("Nothing to redo".to_string());
+    }
+    let entry = journal.entries[journal.applied].clone();
+    apply_side(&dir, project_dir, &entry, "before", "after")?;
+    journal.applied += 1;
+    save_journal(&dir, &journal)?;
+    Ok(entry)
+}
+
+pub fn journal_in(root: &Path, project_path: &str) -> Result<EditJournal, String> {
+    load_journal(&project_journal_dir(root, Path::new(project_path)))
+}
+
+/// [`record_edit_in`] against the default journal. Without a data directory
+/// the edit still runs, unjournaled.
+pub fn record_edit<T, E: From<String>>(
+    project_path: &str,
+    label: &str,
+    files: &[String],
+    edit: impl FnOnce() -> Result<T, E>,
+) -> Result<T, E> {
+    match default_journal_root() {
+        Ok(root) => record_edit_in(&root, project_path, label, files, edit),
+        Err(_) => edit(),
+    }
+}
+
+/// [`record_edits_in`] against the default journal.
+pub fn record_edits<T, E: From<String>>(
+    projects: &[(&str, Vec<String>)],
+    label: &str,
+    edit: impl FnOnce() -> Result<T, E>,
+) -> Result<T, E> {
+    match default_journal_root() {
+        Ok(root) => record_edits_in(&root, projects, label, edit),
+        Err(_) => edit(),
+    }
+}
+
+#[tauri::command]
+pub async fn undo_last_edit(project_path: String) -> Result<JournalEntry, String> {
+    crate::fs_scope::ensure_allowed(&project_path)?;
+    tauri::async_runtime::spawn_blocking(move || undo_in(&default_journal_root()?, &project_path))
+        .await
+        .unwrap()
+}
+
+#[tauri::command]
+pub async fn redo_edit(project_path: String) -> Result<JournalEntry, String> {
+    crate::fs_scope::ensure_allowed(&project_path)?;
+    tauri::async_runtime::spawn_blocking(move || redo_in(&default_journal_root()?, &project_path))
+        .await
+        .unwrap()
+}
+
+#[tauri::command]
+pub fn get_edit_journal(project_path: String) -> Result<EditJournal, String> {
+    journal_in(&default_journal_root()?, &project_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, TempDir, String) {
+        let root = TempDir::new().unwrap();
+        let project = TempDir::new().unwrap();
+        fs::write(project.path().join("bank01.work"), b"v1").unwrap();
+        let path = project.path().to_string_lossy().to_string();
+        (root, project, path)
+    }
+
+    fn write(path: &str, data: &'static [u8]) -> impl FnOnce() -> Result<(), String> {
+        let file = Path::new(path).join("bank01.work");
+        move || fs::write(file, data).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn test_undo_and_redo_restore_file_contents() {
+        let (root, project, path) = setup();
+        let bank = project.path().join("bank01.work");
+        record_edit_in(
+            root.path(),
+            &path,
+            "save_parts",
+            &bank_files(0),
+            write(&path, b"v2"),
+        )
+        .unwrap();
+
+        let undone = undo_in(root.path(), &path).unwrap();
+        assert_eq!(undone.label, "save_parts");
+        assert_eq!(fs::read(&bank).unwrap(), b"v1");
+
+        redo_in(root.path(), &path).unwrap();
+        assert_eq!(fs::read(&bank).unwrap(), b"v2");
+        assert!(redo_in(root.path(), &path).is_err());
+    }
+
+    #[test]
+    fn test_new_edit_discards_redo_history() {
+        let (root, _project, path) = setup();
+        let files = bank_files(0);
+        record_edit_in(root.path(), &path, "a", &files, write(&path, b"v2")).unwrap();
+        undo_in(root.path(), &path).unwrap();
+        record_edit_in(root.path(), &path, "b", &files, write(&path, b"v3")).unwrap();
+
+        let journal = journal_in(root.path(), &path).unwrap();
+        assert_eq!(journal.entries.len(), 1);
+        assert_eq!(journal.entries[0].label, "b");
+        assert!(redo_in(root.path(), &path).is_err());
+    }
+
+    #[test]
+    fn test_undo_refuses_when_file_changed_externally() {
+        let (root, project, path) = setup();
+        record_edit_in(root.path(), &path, "a", &bank_files(0), write(&path, b"v2")).unwrap();
+        fs::write(project.path().join("bank01.work"), b"device edit").unwrap();
+
+        assert!(undo_in(root.path(), &path).is_err());
+        assert_eq!(
+            fs::read(project.path().join("bank01.work")).unwrap(),
+            b"device edit"
+        );
+    }
+
+    #[test]
+    fn test_failed_or_noop_edits_are_not_journaled() {
+        let (root, _project, path) = setup();
+        let files = bank_files(0);
+        let failed: Result<(), String> =
+            record_edit_in(root.path(), &path, "a", &files, || Err("boom".to_string()));
+        assert!(failed.is_err());
+        record_edit_in(root.path(), &path, "b", &files, || Ok::<_, String>(())).unwrap();
+
+        assert!(journal_in(root.path(), &path).unwrap().entries.is_empty());
+        assert!(undo_in(root.path(), &path).is_err());
+    }
+
+    #[test]
+    fn test_snapshots_are_pruned_by_size() {
+        let (root, project, path) = setup();
+        let files = bank_files(0);
+        for data in [b"v2", b"v3", b"v4"] {
+            record_edit_in(root.path(), &path, "a", &files, write(&path, data)).unwrap();
+        }
+        let dir = project_journal_dir(root.path(), project.path());
+        let mut journal = journal_in(root.path(), &path).unwrap();
+        assert_eq!(journal.entries[0].bytes, 4);
+        let oldest = journal.entries[0].id;
+
+        prune_entries(&dir, &mut journal, 8);
+        assert_eq!(journal.entries.len(), 2);
+        assert!(!dir.join(oldest.to_string()).exists());
+        prune_entries(&dir, &mut journal, 0);
+        assert_eq!(journal.entries.len(), 1, "the newest entry is kept");
+    }
+
+    #[test]
+    fn test_journal_is_not_held_during_an_edit() {
+        let (root, _a, a) = setup();
+        let (_, _b, b) = setup();
+        let files = bank_files(0);
+        // Another project's edit can be journaled while this one runs
+        record_edit_in(root.path(), &a, "outer", &files, || {
+            write(&a, b"v2")()?;
+            record_edit_in(root.path(), &b, "inner", &files, write(&b, b"v2"))
+        })
+        .unwrap();
+
+        assert_eq!(
+            journal_in(root.path(), &a).unwrap().entries[0].label,
+            "outer"
+        );
+        assert_eq!(
+            journal_in(root.path(), &b).unwrap().entries[0].label,
+            "inner"
+        );
+    }
+
+    #[test]
+    fn test_edit_across_projects_journals_each() {
+        let (root, _a, a) = setup();
+        let (_, _b, b) = setup();
+        let files = bank_files(0);
+        let (write_a, write_b) = (write(&a, b"moved"), write(&b, b"reset"));
+        record_edits_in(
+            root.path(),
+            &[(&a, files.clone()), (&b, files)],
+            "transfer_bank",
+            || write_a().and_then(|_| write_b()),
+        )
+        .unwrap();
+
+        undo_in(root.path(), &b).unwrap();
+        assert_eq!(fs::read(Path::new(&b).join("bank01.work")).unwrap(), b"v1");
+        assert_eq!(
+            fs::read(Path::new(&a).join("bank01.work")).unwrap(),
+            b"moved"
+        );
+        assert_eq!(journal_in(root.path(), &a).unwrap().applied, 1);
+    }
+}
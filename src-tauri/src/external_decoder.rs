@@ -0,0 +1,238 @@
+// Decoding of formats symphonia has no decoder for: Opus (.opus), WavPack
+// (.wv) and Windows Media Audio (.wma). These go through an `ffmpeg` binary
+// found on the system, which writes a temporary 32-bit float WAV; the
+// regular pipeline then decodes that file as it would any other WAV, so
+// resampling, dithering, fades and gain apply unchanged.
+//
+// ffmpeg is looked up in `OCTATRACK_MANAGER_FFMPEG`, then on PATH, then in the
+// usual Homebrew/MacPorts locations (apps started from the macOS Finder don't
+// get the shell's PATH), once per run. Without it these formats are not
+// listed as audio at all, and a file opened anyway fails with a message
+// saying ffmpeg is needed rather than as an unknown format. A transcode in
+// progress is stopped when its transfer is cancelled.
+
+use crate::audio_pool::is_cancelled;
+use once_cell::sync::Lazy;
+use std::env;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Extensions decoded through ffmpeg.
+pub(crate) const EXTERNAL_EXTENSIONS: [&str; 3] = ["opus", "wv", "wma"];
+
+const FALLBACK_DIRS: [&str; 3] = ["/opt/homebrew/bin", "/usr/local/bin", "/opt/local/bin"];
+
+/// How often a running transcode checks for cancellation.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+static NEXT_TEMP: AtomicU64 = AtomicU64::new(0);
+
+static FFMPEG: Lazy<Option<PathBuf>> = Lazy::new(find_ffmpeg);
+
+pub(crate) fn needs_external_decoder(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| EXTERNAL_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+}
+
+/// Whether files needing ffmpeg can be decoded on this system.
+pub(crate) fn ffmpeg_available() -> bool {
+    FFMPEG.is_some()
+}
+
+fn find_ffmpeg() -> Option<PathBuf> {
+    if let Some(path) = env::var_os("OCTATRACK_MANAGER_FFMPEG").map(PathBuf::from) {
+        return path.is_file().then_some(path);
+    }
+    let name = if cfg!(windows) {
+        "ffmpeg.exe"
+    } else {
+        "ffmpeg"
+    };
+    env::var_os("PATH")
+        .map(|paths| env::split_paths(&paths).collect::<Vec<_>>())
+        .unwrap_or_default()
+        .into_iter()
+        .chain(FALLBACK_DIRS.iter().map(PathBuf::from))
+        .map(|dir| dir.join(name))
+        .find(|candidate| candidate.is_file())
+}
+
+/// A file the symphonia pipeline can open: the source itself, or a temporary
+/// WAV transcoded from it, removed on drop.
+pub(crate) enum Decodable<'a> {
+    Direct(&'a Path),
+    Transcoded(PathBuf),
+}
+
+impl Decodable<'_> {
+    pub(crate) fn path(&self) -> &Path {
+        match self {
+            Decodable::Direct(path) => path,
+            Decodable::Transcoded(path) => path,
+        }
+    }
+}
+
+impl Drop for Decodable<'_> {
+    fn drop(&mut self) {
+        if let Decodable::Transcoded(path) = self {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+fn ffmpeg_args(source: &Path, dest: &Path) -> Vec<std::ffi::OsString> {
+    let mut args: Vec<std::ffi::OsString> = ["-nostdin", "-hide_banner", "-v", "error", "-y", "-i"]
+        .into_iter()
+        .map(Into::into)
+        .collect();
+    args.push(source.into());
+    // First audio stream only (WMA files can carry several), as float WAV so
+    // no precision is lost before the pipeline's own quantization
+    for arg in ["-map", "0:a:0", "-vn", "-c:a", "pcm_f32le", "-f", "wav"] {
+        args.push(arg.into());
+    }
+    args.push(dest.into());
+    args
+}
+
+fn transcode(
+    ffmpeg: &Path,
+    source: &Path,
+    cancel_token: Option<&Arc<AtomicBool>>,
+) -> Result<PathBuf, String> {
+    let dest = env::temp_dir().join(format!(
+        "octatrack-manager-decode-{}-{}.wav",
+        std::process::id(),
+        NEXT_TEMP.fetch_add(1, Ordering::Relaxed)
+    ));
+    let mut command = Command::new(ffmpeg);
+    command
+        .args(ffmpeg_args(source, &dest))
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped());
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+    // Drained on its own thread so a chatty ffmpeg can't fill the pipe and stall
+    let stderr = child.stderr.take().map(|mut pipe| {
+        std::thread::spawn(move || {
+            let mut text = String::new();
+            let _ = pipe.read_to_string(&mut text);
+            text
+        })
+    });
+    let status = loop {
+        if cancel_token.is_some_and(is_cancelled) {
+            let _ = child.kill();
+            let _ = child.wait();
+            let _ = fs::remove_file(&dest);
+            return Err("Transfer cancelled".to_string());
+        }
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) => std::thread::sleep(POLL_INTERVAL),
+            Err(e) => {
+                let _ = child.kill();
+                let _ = fs::remove_file(&dest);
+                return Err(format!("Failed to run ffmpeg: {}", e));
+            }
+        }
+    };
+    if !status.success() || !dest.is_file() {
+        let _ = fs::remove_file(&dest);
+        let stderr = stderr
+            .and_then(|reader| reader.join().ok())
+            .unwrap_or_default();
+        let reason = stderr.lines().last().unwrap_or("unknown error").trim();
+        return Err(format!("Failed to decode {}: {}", source.display(), reason));
+    }
+    Ok(dest)
+}
+
+/// `source` in a form the symphonia pipeline can decode. A transcode stops
+/// early when `cancel_token` is set.
+pub(crate) fn decodable<'a>(
+    source: &'a Path,
+    cancel_token: Option<&Arc<AtomicBool>>,
+) -> Result<Decodable<'a>, String> {
+    if !needs_external_decoder(source) {
+        return Ok(Decodable::Direct(source));
+    }
+    let ffmpeg = FFMPEG.as_deref().ok_or_else(|| {
+        format!(
+            "Decoding {} requires ffmpeg, which was not found (install it or set OCTATRACK_MANAGER_FFMPEG)",
+            source.display()
+        )
+    })?;
+    transcode(ffmpeg, source, cancel_token).map(Decodable::Transcoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_external_formats_are_routed_through_ffmpeg() {
+        assert!(needs_external_decoder(Path::new("/x/loop.OPUS")));
+        assert!(needs_external_decoder(Path::new("/x/loop.wv")));
+        assert!(needs_external_decoder(Path::new("/x/loop.wma")));
+        assert!(!needs_external_decoder(Path::new("/x/loop.flac")));
+        assert_eq!(
+            crate::audio_pool::is_audio_file("loop.opus"),
+            ffmpeg_available()
+        );
+        assert!(crate::audio_pool::needs_conversion(Path::new(
+            "/x/loop.wma"
+        )));
+
+        let wav = Path::new("/x/loop.wav");
+        assert_eq!(decodable(wav, None).unwrap().path(), wav);
+
+        let args = ffmpeg_args(Path::new("in.wv"), Path::new("out.wav"));
+        let args: Vec<_> = args.iter().map(|a| a.to_string_lossy()).collect();
+        assert_eq!(args[6], "in.wv");
+        assert!(args
+            .windows(2)
+            .any(|w| w[0] == "-c:a" && w[1] == "pcm_f32le"));
+        assert_eq!(args.last().unwrap(), "out.wav");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_cancelled_transcode_stops_ffmpeg() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempfile::TempDir::new().unwrap();
+        // Stand-in for an ffmpeg stuck on a long file
+        let slow = dir.path().join("ffmpeg");
+        fs::write(&slow, "#!/bin/sh\nsleep 30\n").unwrap();
+        fs::set_permissions(&slow, fs::Permissions::from_mode(0o755)).unwrap();
+        let token = Arc::new(AtomicBool::new(false));
+        let canceller = {
+            let token = token.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(200));
+                token.store(true, Ordering::SeqCst);
+            })
+        };
+
+        let started = std::time::Instant::now();
+        let result = transcode(&slow, Path::new("/x/loop.opus"), Some(&token));
+        canceller.join().unwrap();
+        assert_eq!(result.unwrap_err(), "Transfer cancelled");
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+}
@@ -0,0 +1,652 @@
+// Feature export: one CSV row per indexed audio file under a root, for
+// clustering/curation in external tools, plus reading a curated list back.
+//
+// Rows come from the pool index database (run a library rebuild first to
+// cover a folder), with the provenance the library index keeps there. Every
+// file is decoded block by block (never held in memory whole) and analysed
+// in one pass:
+//   - duration, peak/RMS level and zero-crossing rate
+//   - spectral centroid, 85% rolloff and flatness, averaged over the
+//     non-silent 2048-sample frames of the mono mix
+//   - musical key, from the frames' chroma correlated with the
+//     Krumhansl-Kessler major/minor profiles
+//   - an acoustic fingerprint: per frame, 16 bits telling whether the
+//     energy difference between neighbouring bands (300-2000 Hz) rose since
+//     the previous frame, for the first `FINGERPRINT_FRAMES` frames
+// BPM comes from the .ot sidecar, or the tempo detected earlier, and the
+// content hash identifies exact duplicates.
+//
+// Parquet is out of scope: it needs a columnar writer this app doesn't ship,
+// so only .csv destinations are accepted (notebooks read both).
+
+use crate::library_index::{self, FileProvenance};
+use crate::param_decode::NOTE_NAMES;
+use crate::pool_index::{self, IndexedPoolFile, PoolQuery};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const COLUMNS: &[&str] = &[
+    "path",
+    "format",
+    "size",
+    "modified",
+    "sample_rate",
+    "channels",
+    "bit_depth",
+    "duration_secs",
+    "bpm",
+    "key",
+    "peak_dbfs",
+    "rms_dbfs",
+    "zero_crossing_rate",
+    "spectral_centroid_hz",
+    "spectral_rolloff_hz",
+    "spectral_flatness",
+    "fingerprint",
+    "content_hash",
+    "original_path",
+    "original_format",
+];
+
+/// Spectral frame length in samples (a power of two).
+const FRAME: usize = 2048;
+/// Fingerprint bands between these frequencies, log-spaced.
+const FINGERPRINT_BANDS: usize = 17;
+const FINGERPRINT_LOW_HZ: f64 = 300.0;
+const FINGERPRINT_HIGH_HZ: f64 = 2000.0;
+/// Frames in a fingerprint (about 3 s at 44.1 kHz).
+const FINGERPRINT_FRAMES: usize = 64;
+/// Chroma only counts bins in this range, where pitch is well defined.
+const CHROMA_LOW_HZ: f64 = 55.0;
+const CHROMA_HIGH_HZ: f64 = 5000.0;
+
+/// Krumhansl-Kessler key profiles, starting at the tonic.
+const MAJOR_PROFILE: [f64; 12] = [
+    6.35, 2.23, 3.48, 2.33, 4.38, 4.09, 2.52, 5.19, 2.39, 3.66, 2.29, 2.88,
+];
+const MINOR_PROFILE: [f64; 12] = [
+    6.33, 2.68, 3.52, 5.38, 2.60, 3.53, 2.54, 4.75, 3.98, 2.69, 3.34, 3.17,
+];
+
+/// Analysis of a decoded file.
+#[derive(Debug, Clone, PartialEq)]
+struct SignalStats {
+    duration_secs: f64,
+    peak_dbfs: f64,
+    rms_dbfs: f64,
+    zero_crossing_rate: f64, // crossings per second, first channel
+    spectral_centroid_hz: Option<f64>,
+    spectral_rolloff_hz: Option<f64>,
+    spectral_flatness: Option<f64>,
+    key: Option<String>, // "A minor", "C# major"...
+    fingerprint: String, // 4 hex digits per frame
+}
+
+fn to_dbfs(value: f64) -> f64 {
+    if value <= 0.0 {
+        f64::NEG_INFINITY
+    } else {
+        20.0 * value.log10()
+    }
+}
+
+/// In-place radix-2 FFT; the length must be a power of two.
+fn fft(re: &mut [f64], im: &mut [f64]) {
+    let n = re.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * std::f64::consts::PI / len as f64;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (sin, cos) = (angle * k as f64).sin_cos();
+                let (a, b) = (start + k, start + k + len / 2);
+                let tr = re[b] * cos - im[b] * sin;
+                let ti = re[b] * sin + im[b] * cos;
+                re[b] = re[a] - tr;
+                im[b] = im[a] - ti;
+                re[a] += tr;
+                im[a] += ti;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+fn correlation(a: &[f64; 12], b: &[f64]) -> f64 {
+    let mean_a = a.iter().sum::<f64>() / 12.0;
+    let mean_b = b.iter().sum::<f64>() / 12.0;
+    let (mut cov, mut var_a, mut var_b) = (0.0, 0.0, 0.0);
+    for (x, y) in a.iter().zip(b) {
+        cov += (x - mean_a) * (y - mean_b);
+        var_a += (x - mean_a).powi(2);
+        var_b += (y - mean_b).powi(2);
+    }
+    if var_a <= 0.0 || var_b <= 0.0 {
+        0.0
+    } else {
+        cov / (var_a * var_b).sqrt()
+    }
+}
+
+/// Best-matching key for a chroma vector (index 0 = C).
+fn estimate_key(chroma: &[f64; 12]) -> Option<String> {
+    if chroma.iter().sum::<f64>() <= 0.0 {
+        return None;
+    }
+    let mut best: Option<(f64, String)> = None;
+    for tonic in 0..12 {
+        for (profile, mode) in [(&MAJOR_PROFILE, "major"), (&MINOR_PROFILE, "minor")] {
+            // The profile as seen from C: entry `pc` is the weight of pitch class pc
+            let rotated: Vec<f64> = (0..12).map(|pc| profile[(pc + 12 - tonic) % 12]).collect();
+            let score = correlation(chroma, &rotated);
+            if best.as_ref().is_none_or(|(s, _)| score > *s) {
+                best = Some((score, format!("{} {}", NOTE_NAMES[tonic], mode)));
+            }
+        }
+    }
+    best.map(|(_, key)| key)
+}
+
+/// One-pass analysis fed with decoded blocks.
+struct Analyzer {
+    sample_rate: u32,
+    frames: u64,
+    samples: u64,
+    peak: f64,
+    sum_sq: f64,
+    crossings: u64,
+    last: Option<f32>, // previous sample of the first channel
+    pending: Vec<f64>, // mono samples of the next spectral frame
+    window: Vec<f64>,
+    spectral_frames: usize,
+    centroid_sum: f64,
+    rolloff_sum: f64,
+    flatness_sum: f64,
+    chroma: [f64; 12],
+    previous_bands: Option<Vec<f64>>,
+    fingerprint: String,
+}
+
+impl Analyzer {
+    fn new(sample_rate: u32) -> Self {
+        let window = (0..FRAME)
+            .map(|i| 0.5 - 0.5 * (2.0 * std::f64::consts::PI * i as f64 / FRAME as f64).cos())
+            .collect();
+        Analyzer {
+            sample_rate: sample_rate.max(1),
+            frames: 0,
+            samples: 0,
+            peak: 0.0,
+            sum_sq: 0.0,
+            crossings: 0,
+            last: None,
+            pending: Vec::with_capacity(FRAME),
+            window,
+            spectral_frames: 0,
+            centroid_sum: 0.0,
+            rolloff_sum: 0.0,
+            flatness_sum: 0.0,
+            chroma: [0.0; 12],
+            previous_bands: None,
+            fingerprint: String::new(),
+        }
+    }
+
+    fn push(&mut self, block: &[Vec<f32>]) {
+        let Some(first) = block.first() else {
+            return;
+        };
+        for channel in block {
+            for &s in channel {
+                let s = s as f64;
+                self.peak = self.peak.max(s.abs());
+                self.sum_sq += s * s;
+            }
+            self.samples += channel.len() as u64;
+        }
+        for &s in first {
+            if self.last.is_some_and(|last| (last < 0.0) != (s < 0.0)) {
+                self.crossings += 1;
+            }
+            self.last = Some(s);
+        }
+        self.frames += first.len() as u64;
+        for i in 0..first.len() {
+            let mono = block
+                .iter()
+                .map(|ch| ch.get(i).copied().unwrap_or(0.0) as f64)
+                .sum::<f64>()
+                / block.len() as f64;
+            self.pending.push(mono);
+            if self.pending.len() == FRAME {
+                self.spectral_frame();
+            }
+        }
+    }
+
+    fn spectral_frame(&mut self) {
+        let mut re: Vec<f64> = self
+            .pending
+            .drain(..)
+            .zip(&self.window)
+            .map(|(s, w)| s * w)
+            .collect();
+        re.resize(FRAME, 0.0);
+        let mut im = vec![0.0; FRAME];
+        fft(&mut re, &mut im);
+        let bin_hz = self.sample_rate as f64 / FRAME as f64;
+        let power: Vec<f64> = (1..FRAME / 2)
+            .map(|k| re[k] * re[k] + im[k] * im[k])
+            .collect();
+        let total: f64 = power.iter().sum();
+        if total < 1e-12 {
+            return; // silence says nothing about the spectrum
+        }
+        let freq = |i: usize| (i + 1) as f64 * bin_hz;
+
+        let magnitude: Vec<f64> = power.iter().map(|p| p.sqrt()).collect();
+        let magnitude_sum: f64 = magnitude.iter().sum();
+        self.centroid_sum += magnitude
+            .iter()
+            .enumerate()
+            .map(|(i, m)| freq(i) * m)
+            .sum::<f64>()
+            / magnitude_sum;
+        let mut cumulative = 0.0;
+        let rolloff = power
+            .iter()
+            .position(|p| {
+                cumulative += p;
+                cumulative >= 0.85 * total
+            })
+            .unwrap_or(power.len() - 1);
+        self.rolloff_sum += freq(rolloff);
+        let log_mean = power.iter().map(|p| (p + 1e-12).ln()).sum::<f64>() / power.len() as f64;
+        self.flatness_sum += log_mean.exp() / (total / power.len() as f64);
+        self.spectral_frames += 1;
+
+        for (i, p) in power.iter().enumerate() {
+            let f = freq(i);
+            if (CHROMA_LOW_HZ..=CHROMA_HIGH_HZ).contains(&f) {
+                let midi = (12.0 * (f / 440.0).log2() + 69.0).round() as i64;
+                self.chroma[midi.rem_euclid(12) as usize] += p;
+            }
+        }
+
+        if self.fingerprint.len() < FINGERPRINT_FRAMES * 4 {
+            let edge = |b: usize| {
+                FINGERPRINT_LOW_HZ
+                    * (FINGERPRINT_HIGH_HZ / FINGERPRINT_LOW_HZ)
+                        .powf(b as f64 / FINGERPRINT_BANDS as f64)
+            };
+            let bands: Vec<f64> = (0..FINGERPRINT_BANDS)
+                .map(|b| {
+                    let (low, high) = (edge(b), edge(b + 1));
+                    power
+                        .iter()
+                        .enumerate()
+                        .filter(|(i, _)| (low..high).contains(&freq(*i)))
+                        .map(|(_, p)| p)
+                        .sum()
+                })
+                .collect();
+            if let Some(previous) = &self.previous_bands {
+                let bits = (0..FINGERPRINT_BANDS - 1).fold(0u16, |bits, b| {
+                    let rise = (bands[b] - bands[b + 1]) - (previous[b] - previous[b + 1]) > 0.0;
+                    bits | (u16::from(rise) << b)
+                });
+                self.fingerprint.push_str(&format!("{:04x}", bits));
+            }
+            self.previous_bands = Some(bands);
+        }
+    }
+
+    fn finish(mut self) -> SignalStats {
+        // A last partial frame counts when it holds a meaningful share
+        if self.pending.len() >= FRAME / 4 {
+            self.spectral_frame();
+        }
+        let duration_secs = self.frames as f64 / self.sample_rate as f64;
+        let rms = if self.samples == 0 {
+            0.0
+        } else {
+            (self.sum_sq / self.samples as f64).sqrt()
+        };
+        let average =
+            |sum: f64| (self.spectral_frames > 0).then(|| sum / self.spectral_frames as f64);
+        SignalStats {
+            duration_secs,
+            peak_dbfs: to_dbfs(self.peak),
+            rms_dbfs: to_dbfs(rms),
+            zero_crossing_rate: if duration_secs > 0.0 {
+                self.crossings as f64 / duration_secs
+            } else {
+                0.0
+            },
+            spectral_centroid_hz: average(self.centroid_sum),
+            spectral_rolloff_hz: average(self.rolloff_sum),
+            spectral_flatness: average(self.flatness_sum),
+            key: estimate_key(&self.chroma),
+            fingerprint: self.fingerprint.clone(),
+        }
+    }
+}
+
+/// Analyse any decodable file, streaming it through the decoder.
+fn signal_stats(path: &Path) -> Option<SignalStats> {
+    let mut analyzer: Option<Analyzer> = None;
+    crate::audio_pool::decode_audio_blocks(path, |sample_rate, block| {
+        analyzer
+            .get_or_insert_with(|| Analyzer::new(sample_rate))
+            .push(block)
+    })
+    .ok()?;
+    analyzer.map(Analyzer::finish)
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn opt<T: ToString>(value: Option<T>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+fn db(value: f64) -> String {
+    if value.is_finite() {
+        format!("{:.2}", value)
+    } else {
+        "-inf".to_string()
+    }
+}
+
+fn feature_row(file: &IndexedPoolFile, provenance: Option<&FileProvenance>) -> Vec<String> {
+    let path = Path::new(&file.path);
+    let format = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_uppercase())
+        .unwrap_or_default();
+    let stats = signal_stats(path);
+    let bpm = crate::sample_attributes::sidecar_attributes(path)
+        .map(|a| a.bpm)
+        .or_else(|| crate::audio_analysis::cached_bpm(path));
+    vec![
+        file.path.clone(),
+        format,
+        file.size.to_string(),
+        file.modified.to_string(),
+        opt(file.sample_rate),
+        opt(file.channels),
+        opt(file.bit_depth),
+        opt(stats
+            .as_ref()
+            .map(|s| s.duration_secs)
+            .or(file.duration)
+            .map(|d| format!("{:.3}", d))),
+        opt(bpm),
+        opt(stats.as_ref().and_then(|s| s.key.clone())),
+        opt(stats.as_ref().map(|s| db(s.peak_dbfs))),
+        opt(stats.as_ref().map(|s| db(s.rms_dbfs))),
+        opt(stats
+            .as_ref()
+            .map(|s| format!("{:.1}", s.zero_crossing_rate))),
+        opt(stats
+            .as_ref()
+            .and_then(|s| s.spectral_centroid_hz)
+            .map(|v| format!("{:.1}", v))),
+        opt(stats
+            .as_ref()
+            .and_then(|s| s.spectral_rolloff_hz)
+            .map(|v| format!("{:.1}", v))),
+        opt(stats
+            .as_ref()
+            .and_then(|s| s.spectral_flatness)
+            .map(|v| format!("{:.4}", v))),
+        opt(stats.as_ref().map(|s| s.fingerprint.clone())),
+        opt(file.hash.clone()),
+        opt(provenance.map(|p| p.original_path.clone())),
+        opt(provenance.map(|p| p.original_format.clone())),
+    ]
+}
+
+/// Write the features of every indexed file under `root` that still exists to
+/// `dest` (CSV). Returns the number of rows.
+pub fn export_features_in(db_path: &Path, root: &Path, dest: &Path) -> Result<usize, String> {
+    match dest
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .as_deref()
+    {
+        Some("csv") => {}
+        Some("parquet") => {
+            return Err("Parquet export is not supported, use a .csv file".to_string())
+        }
+        _ => return Err(format!("Unsupported export format: {}", dest.display())),
+    }
+    let root =
+        fs::canonicalize(root).map_err(|e| format!("Failed to open {}: {}", root.display(), e))?;
+    let conn = library_index::open_library(db_path)?;
+    let files = pool_index::search(
+        &conn,
+        &PoolQuery {
+            root: Some(root.to_string_lossy().to_string()),
+            limit: Some(u32::MAX),
+            ..Default::default()
+        },
+    )?;
+
+    let mut out = COLUMNS.join(",");
+    out.push('\n');
+    let mut rows = 0;
+    for file in &files {
+        if !Path::new(&file.path).is_file() {
+            continue;
+        }
+        let provenance = library_index::provenance_of(&conn, &file.path)?;
+        let row: Vec<String> = feature_row(file, provenance.as_ref())
+            .iter()
+            .map(|v| csv_field(v))
+            .collect();
+        out.push_str(&row.join(","));
+        out.push('\n');
+        rows += 1;
+    }
+
+    crate::atomic_write::write_atomic(dest, out.as_bytes())
+        .map_err(|e| format!("Failed to write {}: {}", dest.display(), e))?;
+    Ok(rows)
+}
+
+/// Split one CSV line into fields (quoted fields may contain commas and "").
+fn parse_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', _) => quoted = !quoted,
+            (',', false) => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// Read a curated list back: the `path` column of a CSV (a file without a
+/// header row is read as one path per line). Only paths that still exist
+/// are returned.
+pub fn read_feature_list(file: &Path) -> Result<Vec<String>, String> {
+    let data = fs::read_to_string(file)
+        .map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+    let mut lines = data.lines().filter(|l| !l.trim().is_empty()).peekable();
+    let column = match lines.peek() {
+        Some(header) => {
+            let fields = parse_csv_line(header);
+            match fields.iter().position(|f| f.trim() == "path") {
+                Some(i) => {
+                    lines.next();
+                    i
+                }
+                None => 0,
+            }
+        }
+        None => 0,
+    };
+    Ok(lines
+        .filter_map(|line| parse_csv_line(line).into_iter().nth(column))
+        .map(|p| p.trim().to_string())
+        .filter(|p| PathBuf::from(p).is_file())
+        .collect())
+}
+
+/// Export analysis features for the indexed files under `root` to `dest`.
+#[tauri::command]
+pub async fn export_features(root: String, dest: String) -> Result<usize, String> {
+    crate::fs_scope::ensure_allowed(&dest)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        export_features_in(
+            &pool_index::default_db_path()?,
+            Path::new(&root),
+            Path::new(&dest),
+        )
+    })
+    .await
+    .unwrap()
+}
+
+/// Paths listed in a curated CSV, e.g. one filtered from `export_features`.
+#[tauri::command]
+pub async fn import_feature_list(file: String) -> Result<Vec<String>, String> {
+    tauri::async_runtime::spawn_blocking(move || read_feature_list(Path::new(&file)))
+        .await
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::write_wav;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_signal_stats() {
+        let dir = TempDir::new().unwrap();
+        let wav = dir.path().join("square.wav");
+        // 1000 frames at 1 kHz alternating +-half scale: 999 crossings in 1 s
+        let samples: Vec<i16> = (0..1000)
+            .map(|i| if i % 2 == 0 { 16384 } else { -16384 })
+            .collect();
+        write_wav(&wav, 1, 1000, &samples);
+
+        let stats = signal_stats(&wav).unwrap();
+        assert!((stats.duration_secs - 1.0).abs() < 1e-9);
+        assert!((stats.peak_dbfs + 6.02).abs() < 0.01);
+        assert!((stats.rms_dbfs + 6.02).abs() < 0.01);
+        assert!((stats.zero_crossing_rate - 999.0).abs() < 1e-9);
+        // All the energy sits at Nyquist (500 Hz)
+        assert!(stats.spectral_centroid_hz.unwrap() > 450.0);
+    }
+
+    fn chord(freqs: &[f64]) -> Vec<i16> {
+        (0..44100)
+            .map(|i| {
+                let t = i as f64 / 44100.0;
+                let s: f64 = freqs
+                    .iter()
+                    .map(|f| (2.0 * std::f64::consts::PI * f * t).sin())
+                    .sum();
+                (s / freqs.len() as f64 * 16000.0) as i16
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_key_of_a_triad() {
+        let dir = TempDir::new().unwrap();
+        let wav = dir.path().join("c_major.wav");
+        write_wav(&wav, 1, 44100, &chord(&[261.63, 329.63, 392.0]));
+
+        let stats = signal_stats(&wav).unwrap();
+        assert_eq!(stats.key.as_deref(), Some("C major"));
+        assert!(stats.spectral_flatness.unwrap() < 0.1); // tonal, not noisy
+    }
+
+    #[test]
+    fn test_fingerprint_matches_identical_audio_only() {
+        let dir = TempDir::new().unwrap();
+        let a = dir.path().join("a.wav");
+        let b = dir.path().join("b.wav");
+        let c = dir.path().join("c.wav");
+        // A sweep moves energy between bands, so frames differ
+        let sweep: Vec<i16> = (0..44100)
+            .map(|i| {
+                let t = i as f64 / 44100.0;
+                ((2.0 * std::f64::consts::PI * (300.0 + 800.0 * t) * t).sin() * 16000.0) as i16
+            })
+            .collect();
+        write_wav(&a, 1, 44100, &sweep);
+        write_wav(&b, 1, 44100, &sweep);
+        write_wav(&c, 1, 44100, &chord(&[440.0, 1250.0]));
+
+        let fa = signal_stats(&a).unwrap().fingerprint;
+        // 22 frames in 1 s at 44.1 kHz (the last one partial): 21 changes
+        assert_eq!(fa.len(), 21 * 4);
+        assert_eq!(fa, signal_stats(&b).unwrap().fingerprint);
+        assert_ne!(fa, signal_stats(&c).unwrap().fingerprint);
+    }
+
+    #[test]
+    fn test_export_and_read_back() {
+        let dir = TempDir::new().unwrap();
+        let db = dir.path().join("index.sqlite");
+        let library = dir.path().join("library, old");
+        fs::create_dir_all(&library).unwrap();
+        write_wav(&library.join("kick.wav"), 1, 1000, &[0, 1000, -1000, 0]);
+        let outside = dir.path().join("other");
+        fs::create_dir_all(&outside).unwrap();
+        write_wav(&outside.join("other.wav"), 1, 1000, &[0]);
+        let mut conn = pool_index::open_index(&db).unwrap();
+        for folder in [&library, &outside] {
+            let folder = fs::canonicalize(folder).unwrap();
+            pool_index::sync_directory(&mut conn, &folder).unwrap();
+        }
+
+        let dest = dir.path().join("features.csv");
+        assert_eq!(export_features_in(&db, &library, &dest).unwrap(), 1);
+        let csv = fs::read_to_string(&dest).unwrap();
+        assert!(csv.starts_with("path,format,size"));
+        assert!(csv.contains("\"")); // the comma in the folder name is quoted
+
+        let paths = read_feature_list(&dest).unwrap();
+        assert_eq!(
+            paths,
+            vec![library_index::index_key(&library.join("kick.wav"))]
+        );
+
+        let parquet = dir.path().join("features.parquet");
+        assert!(export_features_in(&db, &library, &parquet).is_err());
+    }
+}
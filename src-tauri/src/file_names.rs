@@ -0,0 +1,136 @@
+// Destination file names that FAT32 cards and the Octatrack accept. With the
+// `sanitize_names` conversion option, copies are written under a cleaned-up
+// name instead of the source's:
+//
+// - accented Latin letters are transliterated ("Café" becomes "Cafe"); other
+//   non-ASCII characters, control characters and the characters FAT32 forbids
+//   (`"*/:<>?\|`) become "_";
+// - trailing dots and spaces, which FAT32 drops without telling, are removed;
+// - reserved DOS device names (CON, NUL, COM1, ...) get a "_" suffix, as
+//   Windows refuses to open them on the card;
+// - names longer than the limit are shortened, keeping the extension.
+
+/// Longest file name FAT32 stores, in characters.
+pub(crate) const FAT32_MAX_NAME_LEN: usize = 255;
+
+const FORBIDDEN: &str = "\"*/:<>?\\|";
+
+const RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// ASCII spelling of common accented letters and typographic punctuation.
+fn transliterate(c: char) -> Option<&'static str> {
+    Some(match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ą' => "a",
+        'À' | 'Á' | 'Â' | 'Ã' | 'Ä' | 'Å' | 'Ā' | 'Ą' => "A",
+        'ç' | 'ć' | 'č' => "c",
+        'Ç' | 'Ć' | 'Č' => "C",
+        'ď' | 'đ' | 'ð' => "d",
+        'Ď' | 'Đ' | 'Ð' => "D",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ę' | 'ě' => "e",
+        'È' | 'É' | 'Ê' | 'Ë' | 'Ē' | 'Ę' | 'Ě' => "E",
+        'ì' | 'í' | 'î' | 'ï' | 'ī' => "i",
+        'Ì' | 'Í' | 'Î' | 'Ï' | 'Ī' => "I",
+        'ł' => "l",
+        'Ł' => "L",
+        'ñ' | 'ń' | 'ň' => "n",
+        'Ñ' | 'Ń' | 'Ň' => "N",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ő' => "o",
+        'Ò' | 'Ó' | 'Ô' | 'Õ' | 'Ö' | 'Ø' | 'Ō' | 'Ő' => "O",
+        'ř' => "r",
+        'Ř' => "R",
+        'ś' | 'š' => "s",
+        'Ś' | 'Š' => "S",
+        'ť' => "t",
+        'Ť' => "T",
+        'ù' | 'ú' | 'û' | 'ü' | 'ū' | 'ů' | 'ű' => "u",
+        'Ù' | 'Ú' | 'Û' | 'Ü' | 'Ū' | 'Ů' | 'Ű' => "U",
+        'ý' | 'ÿ' => "y",
+        'Ý' | 'Ÿ' => "Y",
+        'ź' | 'ż' | 'ž' => "z",
+        'Ź' | 'Ż' | 'Ž' => "Z",
+        'ß' => "ss",
+        'æ' => "ae",
+        'Æ' => "AE",
+        'œ' => "oe",
+        'Œ' => "OE",
+        'þ' => "th",
+        'Þ' => "TH",
+        '‘' | '’' | '“' | '”' => "'",
+        '–' | '—' => "-",
+        _ => return None,
+    })
+}
+
+fn clean(part: &str) -> String {
+    let mut out = String::with_capacity(part.len());
+    for c in part.chars() {
+        if let Some(ascii) = transliterate(c) {
+            out.push_str(ascii);
+        } else if !c.is_ascii() || c.is_ascii_control() || FORBIDDEN.contains(c) {
+            out.push('_');
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn trim_end(name: &str) -> &str {
+    name.trim_end_matches(['.', ' '])
+}
+
+/// `name` made safe for FAT32 and the Octatrack, at most `max_len` characters.
+pub(crate) fn sanitize_file_name(name: &str, max_len: usize) -> String {
+    let (stem, extension) = match name.rfind('.') {
+        Some(i) if i > 0 => (&name[..i], &name[i..]),
+        _ => (name, ""),
+    };
+    let extension = trim_end(&clean(extension)).to_string();
+    let mut stem = trim_end(clean(stem).trim_start()).to_string();
+    if stem.is_empty() {
+        stem.push('_');
+    }
+    // "CON.wav" is as reserved as "CON"
+    let device = stem.split('.').next().unwrap_or("").to_ascii_uppercase();
+    if RESERVED_NAMES.contains(&device.as_str()) {
+        stem.push('_');
+    }
+    if stem.len() + extension.len() > max_len {
+        stem.truncate(max_len.saturating_sub(extension.len()).max(1));
+        stem = trim_end(&stem).to_string();
+        if stem.is_empty() {
+            stem.push('_');
+        }
+    }
+    stem + &extension
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_file_name() {
+        let fat = |name: &str| sanitize_file_name(name, FAT32_MAX_NAME_LEN);
+        assert_eq!(fat("Kick 01.wav"), "Kick 01.wav");
+        assert_eq!(fat("Café Señor.wav"), "Cafe Senor.wav");
+        assert_eq!(fat("Straße – “Live”.aif"), "Strasse - 'Live'.aif");
+        assert_eq!(fat("a:b*c?.wav"), "a_b_c_.wav");
+        assert_eq!(fat("鼓.wav"), "_.wav");
+        assert_eq!(fat("loop... .wav"), "loop.wav");
+        assert_eq!(fat("take 1. "), "take 1");
+        assert_eq!(fat("con.wav"), "con_.wav");
+        assert_eq!(fat("LPT1"), "LPT1_");
+        assert_eq!(fat("console.wav"), "console.wav");
+        assert_eq!(fat(".hidden"), ".hidden");
+
+        let long = format!("{}.wav", "x".repeat(300));
+        let short = fat(&long);
+        assert_eq!(short.len(), FAT32_MAX_NAME_LEN);
+        assert!(short.ends_with("xx.wav"));
+        assert_eq!(sanitize_file_name("abc def.wav", 8), "abc.wav");
+    }
+}
@@ -0,0 +1,200 @@
+// Filesystem scope for file-manipulation commands.
+//
+// Commands that create, rename, move or delete files only act inside approved
+// roots: every location found by a device scan, and folders the user picked in
+// the native folder dialog (and what a scan of them finds). Nothing is approved
+// by default, and the webview cannot approve a path by naming it. Paths coming from the webview
+// are resolved (symlinks, `..`) before the check, so a request cannot climb out
+// of a root.
+
+use crate::device_detection::ScanResult;
+use once_cell::sync::Lazy;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use tauri::AppHandle;
+use tauri_plugin_dialog::DialogExt;
+
+static APPROVED_ROOTS: Lazy<RwLock<Vec<PathBuf>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
+/// Resolve `path` to an absolute, symlink-free path. A path that does not exist
+/// yet (a file about to be created) is resolved through its nearest existing
+/// ancestor.
+fn resolve(path: &Path) -> Result<PathBuf, String> {
+    if !path.is_absolute() {
+        return Err(format!("Path must be absolute: {}", path.display()));
+    }
+    let mut missing = Vec::new();
+    let mut existing = path;
+    // symlink_metadata: a dangling symlink exists and must fail to canonicalize,
+    // not be treated as a file still to be created.
+    while existing.symlink_metadata().is_err() {
+        let name = existing
+            .file_name()
+            .ok_or_else(|| format!("Invalid path: {}", path.display()))?;
+        missing.push(name.to_os_string());
+        existing = existing
+            .parent()
+            .ok_or_else(|| format!("Invalid path: {}", path.display()))?;
+    }
+    let mut resolved = existing
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve {}: {}", path.display(), e))?;
+    // file_name() never yields `.` or `..`: a path ending in one of those
+    // stops the walk above with an error instead.
+    for name in missing.into_iter().rev() {
+        resolved.push(name);
+    }
+    Ok(resolved)
+}
+
+/// True when `path` resolves inside one of `roots` (already canonical).
+fn is_within_roots(roots: &[PathBuf], path: &Path) -> Result<bool, String> {
+    let resolved = resolve(path)?;
+    Ok(roots.iter().any(|root| resolved.starts_with(root)))
+}
+
+/// Add `path` (an existing directory) to the approved roots. Filesystem roots
+/// such as `/` or `C:\` are refused: approving one would disable the scope.
+pub fn approve_root(path: &Path) -> Result<(), String> {
+    let root = path
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve {}: {}", path.display(), e))?;
+    if !root.is_dir() {
+        return Err(format!("Not a directory: {}", path.display()));
+    }
+    if root.parent().is_none() {
+        return Err("A filesystem root cannot be approved".to_string());
+    }
+    let mut roots = APPROVED_ROOTS.write().unwrap_or_else(|e| e.into_inner());
+    if !roots.contains(&root) {
+        roots.push(root);
+    }
+    Ok(())
+}
+
+/// Approve every location and standalone project a scan found.
+pub fn approve_scan_result(result: &ScanResult) {
+    let paths = result
+        .locations
+        .iter()
+        .map(|l| &l.path)
+        .chain(result.standalone_projects.iter().map(|p| &p.path));
+    for path in paths {
+        if let Err(e) = approve_root(Path::new(path)) {
+            eprintln!("[SCOPE] Not approving {}: {}", path, e);
+        }
+    }
+}
+
+/// Reject `path` unless it lies inside an approved root.
+pub fn ensure_allowed(path: &str) -> Result<(), String> {
+    let roots = APPROVED_ROOTS.read().unwrap_or_else(|e| e.into_inner());
+    if is_within_roots(&roots, Path::new(path))? {
+        Ok(())
+    } else {
+        Err(format!(
+            "Access denied: {} is outside the approved folders",
+            path
+        ))
+    }
+}
+
+/// `ensure_allowed` for every path in `paths`.
+pub fn ensure_all_allowed(paths: &[String]) -> Result<(), String> {
+    paths.iter().try_for_each(|p| ensure_allowed(p))
+}
+
+/// Let the user pick a library folder (or a folder to scan) in the native
+/// dialog and approve it. Returns the picked folder, or None when the dialog
+/// was cancelled.
+#[tauri::command]
+pub async fn pick_library_root(
+    app: AppHandle,
+    title: Option<String>,
+) -> Result<Option<String>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let Some(picked) = app
+            .dialog()
+            .file()
+            .set_title(title.as_deref().unwrap_or("Choose a library folder"))
+            .blocking_pick_folder()
+        else {
+            return Ok(None);
+        };
+        let path = picked
+            .into_path()
+            .map_err(|e| format!("Invalid folder: {}", e))?;
+        approve_root(&path)?;
+        Ok(Some(path.to_string_lossy().to_string()))
+    })
+    .await
+    .unwrap()
+}
+
+#[tauri::command]
+pub fn list_approved_roots() -> Vec<String> {
+    APPROVED_ROOTS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .map(|p| p.to_string_lossy().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_paths_inside_root_are_allowed() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        fs::create_dir_all(root.join("AUDIO")).unwrap();
+        fs::write(root.join("AUDIO/kick.wav"), b"RIFF").unwrap();
+        let roots = vec![root.clone()];
+
+        assert!(is_within_roots(&roots, &root.join("AUDIO/kick.wav")).unwrap());
+        // Not created yet
+        assert!(is_within_roots(&roots, &root.join("AUDIO/new/snare.wav")).unwrap());
+    }
+
+    #[test]
+    fn test_paths_escaping_root_are_rejected() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().canonicalize().unwrap().join("set");
+        fs::create_dir_all(&root).unwrap();
+        let roots = vec![root.clone()];
+
+        assert!(!is_within_roots(&roots, &root.join("../outside.wav")).unwrap());
+        assert!(!is_within_roots(&roots, &root.join("missing/../../outside.wav")).unwrap_or(false));
+        assert!(is_within_roots(&roots, Path::new("relative/path.wav")).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_out_of_root_is_rejected() {
+        let dir = TempDir::new().unwrap();
+        let base = dir.path().canonicalize().unwrap();
+        let root = base.join("set");
+        let outside = base.join("outside");
+        fs::create_dir_all(&root).unwrap();
+        fs::create_dir_all(&outside).unwrap();
+        std::os::unix::fs::symlink(&outside, root.join("link")).unwrap();
+
+        assert!(!is_within_roots(&[root.clone()], &root.join("link/file.wav")).unwrap());
+    }
+
+    #[test]
+    fn test_filesystem_root_cannot_be_approved() {
+        let fs_root = std::env::temp_dir()
+            .canonicalize()
+            .unwrap()
+            .ancestors()
+            .last()
+            .unwrap()
+            .to_path_buf();
+        assert!(approve_root(&fs_root).is_err());
+    }
+}
@@ -0,0 +1,283 @@
+// Level diagnostics for samples, to find problem files before a gig:
+//
+// - clipping: samples at full scale, and runs of them (a single full-scale
+//   sample can be legitimate, three in a row almost never are);
+// - true peak: the level between samples, estimated by 4x oversampling as in
+//   ITU-R BS.1770; overs above 0 dBTP can distort after resampling or in the
+//   Octatrack's own processing even when no sample is clipped;
+// - DC offset: the mean of each channel, which causes clicks at slice and
+//   trim points;
+// - near-silence: the whole file below -60 dBFS, and silent lead-in/tail.
+
+use crate::audio_pool::decode_audio_file;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::PI;
+use std::path::Path;
+
+/// Magnitude counted as clipped (full scale for 16-bit and above).
+const CLIP_LEVEL: f32 = 0.9999;
+/// Consecutive clipped samples that make a clipping run.
+const CLIP_RUN: usize = 3;
+/// Level below which audio is considered silent.
+const SILENCE_DBFS: f32 = -60.0;
+/// Channel mean above which a DC offset is reported (about -46 dBFS).
+const DC_LIMIT: f32 = 0.005;
+/// Interpolation taps on each side of the point being estimated.
+const TAPS: usize = 8;
+const OVERSAMPLING: usize = 4;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LevelReport {
+    pub path: String,
+    pub channels: u16,
+    pub sample_rate: u32,
+    pub duration_seconds: f64,
+    pub peak_dbfs: Option<f32>, // None for digital silence
+    pub true_peak_dbtp: Option<f32>,
+    pub rms_dbfs: Option<f32>,
+    pub clipped_samples: u64,
+    pub clip_runs: u64,
+    pub true_peak_overs: u64, // interpolated points above 0 dBTP
+    pub dc_offset: f32,       // largest channel mean, -1.0 to 1.0
+    pub leading_silence_ms: u32,
+    pub trailing_silence_ms: u32,
+    pub is_silent: bool,
+    pub issues: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LevelAnalysis {
+    pub path: String,
+    pub report: Option<LevelReport>,
+    pub error: Option<String>,
+}
+
+fn to_db(level: f32) -> Option<f32> {
+    (level > 0.0).then(|| 20.0 * level.log10())
+}
+
+/// Hann-windowed sinc coefficients for the points between two samples.
+fn interpolation_kernels() -> Vec<[f32; 2 * TAPS]> {
+    (1..OVERSAMPLING)
+        .map(|phase| {
+            let frac = phase as f32 / OVERSAMPLING as f32;
+            let mut kernel = [0.0f32; 2 * TAPS];
+            for (k, coefficient) in kernel.iter_mut().enumerate() {
+                // Tap k sits at offset (k - TAPS + 1) from the sample before the point
+                let x = k as f32 - (TAPS - 1) as f32 - frac;
+                let sinc = if x == 0.0 {
+                    1.0
+                } else {
+                    (PI * x).sin() / (PI * x)
+                };
+                let window = 0.5 + 0.5 * (PI * x / TAPS as f32).cos();
+                *coefficient = sinc * window;
+            }
+            kernel
+        })
+        .collect()
+}
+
+/// (true peak, points above full scale) of one channel.
+fn true_peak(channel: &[f32], kernels: &[[f32; 2 * TAPS]]) -> (f32, u64) {
+    let mut peak = 0.0f32;
+    let mut overs = 0u64;
+    for (i, &sample) in channel.iter().enumerate() {
+        peak = peak.max(sample.abs());
+        if sample.abs() > 1.0 {
+            overs += 1;
+        }
+        for kernel in kernels {
+            let mut value = 0.0f32;
+            for (k, coefficient) in kernel.iter().enumerate() {
+                let index = i as isize + k as isize - (TAPS as isize - 1);
+                if let Some(&s) = usize::try_from(index).ok().and_then(|j| channel.get(j)) {
+                    value += s * coefficient;
+                }
+            }
+            peak = peak.max(value.abs());
+            if value.abs() > 1.0 {
+                overs += 1;
+            }
+        }
+    }
+    (peak, overs)
+}
+
+/// Level diagnostics of decoded audio.
+pub(crate) fn analyze_channels(channels: &[Vec<f32>], sample_rate: u32) -> LevelReport {
+    let frames = channels.first().map_or(0, |c| c.len());
+    let kernels = interpolation_kernels();
+    let silence = 10f32.powf(SILENCE_DBFS / 20.0);
+
+    let (mut peak, mut true_peak_level, mut sum_squares) = (0.0f32, 0.0f32, 0.0f64);
+    let (mut clipped_samples, mut clip_runs, mut overs) = (0u64, 0u64, 0u64);
+    let mut dc_offset = 0.0f32;
+    for channel in channels {
+        let mut run = 0usize;
+        let mut sum = 0.0f64;
+        for &sample in channel {
+            peak = peak.max(sample.abs());
+            sum += sample as f64;
+            sum_squares += (sample as f64).powi(2);
+            if sample.abs() >= CLIP_LEVEL {
+                clipped_samples += 1;
+                run += 1;
+                if run == CLIP_RUN {
+                    clip_runs += 1;
+                }
+            } else {
+                run = 0;
+            }
+        }
+        let mean = (sum / channel.len().max(1) as f64) as f32;
+        if mean.abs() > dc_offset.abs() {
+            dc_offset = mean;
+        }
+        let (channel_peak, channel_overs) = true_peak(channel, &kernels);
+        true_peak_level = true_peak_level.max(channel_peak);
+        overs += channel_overs;
+    }
+    let sample_count = (frames * channels.len()).max(1) as f64;
+    let rms = (sum_squares / sample_count).sqrt() as f32;
+
+    let audible = |i: usize| channels.iter().any(|c| c[i].abs() > silence);
+    let first = (0..frames).find(|&i| audible(i));
+    let last = (0..frames).rev().find(|&i| audible(i));
+    let ms = |frames: usize| (frames as f64 * 1000.0 / sample_rate.max(1) as f64) as u32;
+    let (leading, trailing) = match (first, last) {
+        (Some(first), Some(last)) => (ms(first), ms(frames - 1 - last)),
+        _ => (ms(frames), ms(frames)),
+    };
+    let is_silent = first.is_none();
+
+    let mut issues = Vec::new();
+    if clip_runs > 0 {
+        issues.push(format!(
+            "Clipped: {} run(s) of full-scale samples",
+            clip_runs
+        ));
+    }
+    if overs > 0 {
+        issues.push(format!(
+            "True peak {:.1} dBTP: {} inter-sample over(s)",
+            to_db(true_peak_level).unwrap_or(0.0),
+            overs
+        ));
+    }
+    if dc_offset.abs() > DC_LIMIT {
+        issues.push(format!("DC offset of {:.2}%", dc_offset * 100.0));
+    }
+    if is_silent {
+        issues.push(format!("Silent: nothing above {} dBFS", SILENCE_DBFS));
+    } else if leading > 0 && leading >= ms(frames) / 4 {
+        issues.push(format!("Starts with {} ms of silence", leading));
+    }
+
+    LevelReport {
+        path: String::new(),
+        channels: channels.len() as u16,
+        sample_rate,
+        duration_seconds: frames as f64 / sample_rate.max(1) as f64,
+        peak_dbfs: to_db(peak),
+        true_peak_dbtp: to_db(true_peak_level),
+        rms_dbfs: to_db(rms),
+        clipped_samples,
+        clip_runs,
+        true_peak_overs: overs,
+        dc_offset,
+        leading_silence_ms: leading,
+        trailing_silence_ms: trailing,
+        is_silent,
+        issues,
+    }
+}
+
+/// Decode `path` and report its level diagnostics.
+pub fn analyze_levels(path: &Path) -> Result<LevelReport, String> {
+    let decoded = decode_audio_file(path)?;
+    let mut report = analyze_channels(&decoded.channels, decoded.sample_rate);
+    report.path = path.to_string_lossy().to_string();
+    Ok(report)
+}
+
+/// Scan files for clipping, true-peak overs, DC offset and silence.
+#[tauri::command]
+pub async fn analyze_sample_levels(paths: Vec<String>) -> Result<Vec<LevelAnalysis>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        Ok(paths
+            .into_iter()
+            .map(|path| match analyze_levels(Path::new(&path)) {
+                Ok(report) => LevelAnalysis {
+                    path,
+                    report: Some(report),
+                    error: None,
+                },
+                Err(e) => LevelAnalysis {
+                    path,
+                    report: None,
+                    error: Some(e),
+                },
+            })
+            .collect())
+    })
+    .await
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(freq: f32, amplitude: f32, phase: f32, frames: usize) -> Vec<f32> {
+        (0..frames)
+            .map(|i| amplitude * (2.0 * PI * freq * i as f32 / 44100.0 + phase).sin())
+            .collect()
+    }
+
+    #[test]
+    fn test_clean_sine_has_no_issues() {
+        let report = analyze_channels(&[sine(440.0, 0.5, 0.0, 44100)], 44100);
+        assert!(report.issues.is_empty(), "{:?}", report.issues);
+        assert!((report.peak_dbfs.unwrap() + 6.02).abs() < 0.1);
+        assert!((report.true_peak_dbtp.unwrap() + 6.02).abs() < 0.1);
+        assert!((report.rms_dbfs.unwrap() + 9.03).abs() < 0.1);
+        assert_eq!(report.leading_silence_ms, 0);
+    }
+
+    #[test]
+    fn test_detects_clipping_overs_dc_and_silence() {
+        let clipped: Vec<f32> = sine(100.0, 2.0, 0.0, 44100)
+            .into_iter()
+            .map(|s| s.clamp(-1.0, 1.0))
+            .collect();
+        let report = analyze_channels(&[clipped], 44100);
+        assert!(report.clip_runs >= 200, "{}", report.clip_runs);
+
+        // fs/4 at 45 degrees: every sample at 0.99 * 0.707, the peaks between them
+        let between = sine(11025.0, 0.99 * 2f32.sqrt(), PI / 4.0, 4410);
+        let report = analyze_channels(&[between], 44100);
+        assert_eq!(report.clip_runs, 0);
+        assert!(report.peak_dbfs.unwrap() < 0.0);
+        assert!(report.true_peak_overs > 0);
+        assert!(report.true_peak_dbtp.unwrap() > 2.0);
+
+        let offset: Vec<f32> = sine(440.0, 0.3, 0.0, 44100)
+            .into_iter()
+            .map(|s| s + 0.05)
+            .collect();
+        let report = analyze_channels(&[offset], 44100);
+        assert!((report.dc_offset - 0.05).abs() < 0.001);
+        assert_eq!(report.issues.len(), 1);
+
+        let mut tail = vec![0.0f32; 22050];
+        tail.extend(sine(440.0, 0.5, 0.0, 22050));
+        let report = analyze_channels(&[tail], 44100);
+        assert_eq!(report.leading_silence_ms, 500);
+        assert!(!report.is_silent);
+
+        let report = analyze_channels(&[vec![0.0001; 1000]], 44100);
+        assert!(report.is_silent);
+        assert_eq!(report.peak_dbfs.map(|db| db.round()), Some(-80.0));
+    }
+}
@@ -1,60 +1,144 @@
 // Allow certain clippy lints that would require significant refactoring
 #![allow(clippy::too_many_arguments)]
 
+mod app_data;
+mod archive_import;
+mod arrangement_reader;
+mod atomic_write;
+mod audio_analysis;
 mod audio_pool;
+mod audio_preview;
+mod audio_stream;
+mod bank_json;
+mod batch_convert;
 mod device_detection;
+mod dir_watcher;
+mod disk_space;
+mod edit_journal;
+mod external_decoder;
+mod feature_export;
+mod file_names;
+mod fs_scope;
+mod level_analysis;
+mod library_index;
+mod maintenance;
+mod metadata_cache;
+mod midi_file;
+mod operation_plan;
+mod os_compat;
+mod param_decode;
+mod part_presets;
+mod pattern_render;
+mod pool_index;
+mod pool_names;
+mod pool_watcher;
+mod project_archive;
+mod project_diff;
+mod project_lint;
+mod project_lock;
 pub mod project_manager;
+mod project_notes;
 mod project_reader;
+mod project_report;
+mod project_search;
+mod project_watcher;
+mod sample_attributes;
+mod sample_chain;
+mod sample_export;
+mod sample_pack;
+mod sandbox;
+mod set_pool_usage;
+mod setlist;
+#[cfg(test)]
+mod test_support;
+mod transfer_queue;
+mod transfer_verify;
+mod write_backup;
+mod write_verify;
 
 use audio_pool::{
     cancel_transfer, collect_audio_files_recursive, copy_audio_files_or_use_existing,
-    copy_files_with_overwrite, copy_single_file_with_progress, create_directory, delete_files,
-    get_parent_directory, list_directory, move_files, register_cancellation_token,
-    remove_cancellation_token, rename_file as rename_file_impl, AudioFileInfo,
+    copy_files_with_overwrite, copy_files_with_policy, copy_single_file_with_options,
+    create_directory, delete_files, get_parent_directory, list_directory, move_files,
+    register_cancellation_token, remove_cancellation_token, rename_file as rename_file_impl,
+    AudioFileInfo,
 };
 use device_detection::{discover_devices, scan_directory, ScanResult};
 use project_reader::{
+    apply_fx_preset as apply_fx_preset_data,
     are_projects_in_same_set,
     assign_samples_to_slots as assign_samples_to_slots_impl,
+    check_memory_settings as check_memory_settings_data,
     check_missing_source_files as check_missing_source_files_impl,
+    clear_pattern as clear_pattern_data,
     commit_all_parts_data,
     commit_part_data,
     compute_pool_usage as compute_pool_usage_data,
     compute_sample_usage as compute_sample_usage_data,
+    convert_slot_type as convert_slot_type_data,
     // Copy operations
     copy_bank as copy_bank_impl,
     copy_parts as copy_parts_impl,
     copy_patterns as copy_patterns_impl,
     copy_sample_slots as copy_sample_slots_impl,
+    copy_track_setup as copy_track_setup_data,
     copy_tracks as copy_tracks_impl,
     create_audio_pool as create_audio_pool_impl,
     get_audio_pool_status as get_audio_pool_status_impl,
     get_existing_bank_indices,
     // Set and Audio Pool helpers
+    init_bank as init_bank_data,
+    init_part as init_part_data,
     is_project_in_set,
     list_set_projects as list_set_projects_data,
+    load_bank_summary as load_bank_summary_data,
+    load_pattern as load_pattern_data,
+    quantize_micro_timing as quantize_micro_timing_data,
     read_parts_data,
-    read_project_banks,
-    read_project_metadata,
-    read_single_bank,
     reload_part_data,
+    rename_part as rename_part_data,
+    reorder_sample_slots as reorder_sample_slots_data,
+    resize_pattern as resize_pattern_data,
     save_memory_settings_data,
+    save_metronome_settings_data,
+    save_midi_settings_data,
+    save_mixer_settings_data,
     save_parts_data,
+    set_pattern_assignment as set_pattern_assignment_data,
+    set_pattern_tempo as set_pattern_tempo_data,
+    set_project_tempo as set_project_tempo_data,
+    set_slot_gain as set_slot_gain_data,
+    set_track_groove as set_track_groove_data,
+    set_trig_probability as set_trig_probability_data,
+    shift_track_trigs as shift_track_trigs_data,
+    transform_pattern as transform_pattern_data,
     // Slot assignment types
     AssignSamplesResult,
     AudioPoolStatus,
     Bank,
+    FxPresetTarget,
     // Types
     MemorySettings,
+    MemorySettingsCheck,
+    MetronomeSettings,
+    MidiSettings,
+    MixerSettings,
     PartData,
+    PartTrackFx,
     PartsDataResponse,
+    Pattern,
     PoolUsageEntry,
     ProjectMetadata,
+    ReadResult,
     SetProjectInfo,
     SlotAssignment,
+    SlotConversionResult,
+    SlotMove,
+    SlotReorderResult,
 };
 use serde::Serialize;
 use tauri::{AppHandle, Emitter, Manager};
+use write_verify::{VerificationFailure, VerificationReport};
 
 #[derive(Clone, Serialize)]
 struct CopyProgressEvent {
@@ -79,26 +163,33 @@ fn greet(name: &str) -> String {
 
 #[tauri::command]
 fn scan_devices() -> ScanResult {
-    discover_devices()
+    let result = discover_devices();
+    fs_scope::approve_scan_result(&result);
+    result
 }
 
 #[tauri::command]
-fn scan_custom_directory(path: String) -> ScanResult {
-    scan_directory(&path)
+fn scan_custom_directory(path: String) -> Result<ScanResult, String> {
+    // Only a folder picked in the native dialog (or inside a scanned location)
+    // can be scanned, so naming a path here approves nothing by itself.
+    fs_scope::ensure_allowed(&path)?;
+    let result = scan_directory(&path);
+    fs_scope::approve_scan_result(&result);
+    Ok(result)
 }
 
 #[tauri::command]
-async fn load_project_metadata(path: String) -> Result<ProjectMetadata, String> {
+async fn load_project_metadata(path: String) -> Result<ReadResult<ProjectMetadata>, String> {
     // Run on a blocking thread pool to avoid blocking the main event loop
-    tauri::async_runtime::spawn_blocking(move || read_project_metadata(&path))
+    tauri::async_runtime::spawn_blocking(move || metadata_cache::cached_project_metadata(&path))
         .await
         .unwrap()
 }
 
 #[tauri::command]
-async fn load_project_banks(path: String) -> Result<Vec<Bank>, String> {
+async fn load_project_banks(path: String) -> Result<ReadResult<Vec<Bank>>, String> {
     // Run on a blocking thread pool to avoid blocking the main event loop
-    tauri::async_runtime::spawn_blocking(move || read_project_banks(&path))
+    tauri::async_runtime::spawn_blocking(move || metadata_cache::cached_project_banks(&path))
         .await
         .unwrap()
 }
@@ -106,11 +197,48 @@ async fn load_project_banks(path: String) -> Result<Vec<Bank>, String> {
 #[tauri::command]
 async fn load_single_bank(path: String, bank_index: u8) -> Result<Option<Bank>, String> {
     // Run on a blocking thread pool to avoid blocking the main event loop
-    tauri::async_runtime::spawn_blocking(move || read_single_bank(&path, bank_index))
+    tauri::async_runtime::spawn_blocking(move || {
+        metadata_cache::cached_single_bank(&path, bank_index)
+    })
+    .await
+    .unwrap()
+}
+
+#[tauri::command]
+async fn load_bank_summary(path: String, bank_index: u8) -> Result<Option<Bank>, String> {
+    tauri::async_runtime::spawn_blocking(move || load_bank_summary_data(&path, bank_index))
         .await
         .unwrap()
 }
 
+#[tauri::command]
+async fn load_pattern(path: String, bank_index: u8, pattern_index: u8) -> Result<Pattern, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        load_pattern_data(&path, bank_index, pattern_index)
+    })
+    .await
+    .unwrap()
+}
+
+#[tauri::command]
+async fn find_sample_references(
+    path: String,
+    slot_type: Option<String>,
+    slot_id: Option<u8>,
+    file: Option<String>,
+) -> Result<Vec<project_reader::SampleReference>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        project_reader::find_sample_references(
+            &path,
+            slot_type.as_deref(),
+            slot_id,
+            file.as_deref(),
+        )
+    })
+    .await
+    .unwrap()
+}
+
 #[tauri::command]
 async fn compute_sample_usage(
     path: String,
@@ -159,18 +287,106 @@ async fn save_parts(
     path: String,
     bank_id: String,
     parts_data: Vec<PartData>,
-) -> Result<(), String> {
+    verify: Option<bool>,
+) -> Result<VerificationReport, VerificationFailure> {
+    fs_scope::ensure_allowed(&path)?;
+    let verify = verify.unwrap_or_else(write_verify::verify_by_default);
     // Run on a blocking thread pool to avoid blocking the main event loop
-    tauri::async_runtime::spawn_blocking(move || save_parts_data(&path, &bank_id, parts_data))
+    tauri::async_runtime::spawn_blocking(move || {
+        edit_journal::record_edit(
+            &path,
+            "save_parts",
+            &edit_journal::bank_files_for_id(&bank_id),
+            || save_parts_data(&path, &bank_id, parts_data, verify),
+        )
+    })
+    .await
+    .unwrap()
+}
+
+#[tauri::command]
+async fn check_memory_settings(
+    path: String,
+    settings: MemorySettings,
+) -> Result<MemorySettingsCheck, String> {
+    tauri::async_runtime::spawn_blocking(move || Ok(check_memory_settings_data(&path, &settings)))
         .await
         .unwrap()
 }
 
 #[tauri::command]
 async fn save_memory_settings(path: String, settings: MemorySettings) -> Result<f64, String> {
-    tauri::async_runtime::spawn_blocking(move || save_memory_settings_data(&path, settings))
-        .await
-        .unwrap()
+    fs_scope::ensure_allowed(&path)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        edit_journal::record_edit(
+            &path,
+            "save_memory_settings",
+            &edit_journal::project_files(),
+            || save_memory_settings_data(&path, settings),
+        )
+    })
+    .await
+    .unwrap()
+}
+
+/// Apply the same MIDI settings to every project in `paths`, so routing can be
+/// configured in bulk. Projects that fail are reported together after the
+/// others have been written.
+#[tauri::command]
+async fn save_midi_settings(paths: Vec<String>, settings: MidiSettings) -> Result<(), String> {
+    fs_scope::ensure_all_allowed(&paths)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let errors: Vec<String> = paths
+            .iter()
+            .filter_map(|path| {
+                edit_journal::record_edit(
+                    path,
+                    "save_midi_settings",
+                    &edit_journal::project_files(),
+                    || save_midi_settings_data(path, settings.clone()),
+                )
+                .err()
+                .map(|e| format!("{}: {}", path, e))
+            })
+            .collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors.join("\n"))
+        }
+    })
+    .await
+    .unwrap()
+}
+
+#[tauri::command]
+async fn save_mixer_settings(path: String, settings: MixerSettings) -> Result<(), String> {
+    fs_scope::ensure_allowed(&path)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        edit_journal::record_edit(
+            &path,
+            "save_mixer_settings",
+            &edit_journal::project_files(),
+            || save_mixer_settings_data(&path, settings),
+        )
+    })
+    .await
+    .unwrap()
+}
+
+#[tauri::command]
+async fn save_metronome_settings(path: String, settings: MetronomeSettings) -> Result<(), String> {
+    fs_scope::ensure_allowed(&path)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        edit_journal::record_edit(
+            &path,
+            "save_metronome_settings",
+            &edit_journal::project_files(),
+            || save_metronome_settings_data(&path, settings),
+        )
+    })
+    .await
+    .unwrap()
 }
 
 #[tauri::command]
@@ -179,8 +395,37 @@ async fn assign_samples_to_slots(
     slot_type: String,
     assignments: Vec<SlotAssignment>,
 ) -> Result<AssignSamplesResult, String> {
+    fs_scope::ensure_allowed(&path)?;
     tauri::async_runtime::spawn_blocking(move || {
-        assign_samples_to_slots_impl(&path, &slot_type, assignments)
+        edit_journal::record_edit(
+            &path,
+            "assign_samples_to_slots",
+            &edit_journal::project_files(),
+            || assign_samples_to_slots_impl(&path, &slot_type, assignments),
+        )
+    })
+    .await
+    .unwrap()
+}
+
+#[tauri::command]
+async fn purge_unused_slots(
+    path: String,
+    preview: bool,
+) -> Result<project_reader::PurgeSlotsResult, String> {
+    if !preview {
+        fs_scope::ensure_allowed(&path)?;
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        if preview {
+            return project_reader::purge_unused_slots(&path, true);
+        }
+        edit_journal::record_edit(
+            &path,
+            "purge_unused_slots",
+            &edit_journal::project_files(),
+            || project_reader::purge_unused_slots(&path, false),
+        )
     })
     .await
     .unwrap()
@@ -192,8 +437,14 @@ async fn clear_sample_slots(
     slot_type: String,
     slot_indices: Vec<u16>,
 ) -> Result<AssignSamplesResult, String> {
+    fs_scope::ensure_allowed(&path)?;
     tauri::async_runtime::spawn_blocking(move || {
-        project_reader::clear_sample_slots(&path, &slot_type, slot_indices)
+        edit_journal::record_edit(
+            &path,
+            "clear_sample_slots",
+            &edit_journal::project_files(),
+            || project_reader::clear_sample_slots(&path, &slot_type, slot_indices),
+        )
     })
     .await
     .unwrap()
@@ -205,8 +456,14 @@ async fn clear_sample_keep_attributes(
     slot_type: String,
     slot_indices: Vec<u16>,
 ) -> Result<AssignSamplesResult, String> {
+    fs_scope::ensure_allowed(&path)?;
     tauri::async_runtime::spawn_blocking(move || {
-        project_reader::clear_sample_keep_attributes(&path, &slot_type, slot_indices)
+        edit_journal::record_edit(
+            &path,
+            "clear_sample_keep_attributes",
+            &edit_journal::project_files(),
+            || project_reader::clear_sample_keep_attributes(&path, &slot_type, slot_indices),
+        )
     })
     .await
     .unwrap()
@@ -218,35 +475,504 @@ async fn reset_slot_attributes(
     slot_type: String,
     slot_indices: Vec<u16>,
 ) -> Result<AssignSamplesResult, String> {
+    fs_scope::ensure_allowed(&path)?;
     tauri::async_runtime::spawn_blocking(move || {
-        project_reader::reset_slot_attributes(&path, &slot_type, slot_indices)
+        edit_journal::record_edit(
+            &path,
+            "reset_slot_attributes",
+            &edit_journal::project_files(),
+            || project_reader::reset_slot_attributes(&path, &slot_type, slot_indices),
+        )
     })
     .await
     .unwrap()
 }
 
 #[tauri::command]
-async fn commit_part(path: String, bank_id: String, part_id: u8) -> Result<(), String> {
+async fn commit_part(
+    path: String,
+    bank_id: String,
+    part_id: u8,
+    verify: Option<bool>,
+) -> Result<VerificationReport, VerificationFailure> {
+    fs_scope::ensure_allowed(&path)?;
+    let verify = verify.unwrap_or_else(write_verify::verify_by_default);
     // Commit a part: copy parts.unsaved to parts.saved (like Octatrack's "SAVE" command)
-    tauri::async_runtime::spawn_blocking(move || commit_part_data(&path, &bank_id, part_id))
-        .await
-        .unwrap()
+    tauri::async_runtime::spawn_blocking(move || {
+        edit_journal::record_edit(
+            &path,
+            "commit_part",
+            &edit_journal::bank_files_for_id(&bank_id),
+            || commit_part_data(&path, &bank_id, part_id, verify),
+        )
+    })
+    .await
+    .unwrap()
 }
 
 #[tauri::command]
-async fn commit_all_parts(path: String, bank_id: String) -> Result<(), String> {
+async fn commit_all_parts(
+    path: String,
+    bank_id: String,
+    verify: Option<bool>,
+) -> Result<VerificationReport, VerificationFailure> {
+    fs_scope::ensure_allowed(&path)?;
+    let verify = verify.unwrap_or_else(write_verify::verify_by_default);
     // Commit all parts: copy all parts.unsaved to parts.saved (like Octatrack's "SAVE ALL" command)
-    tauri::async_runtime::spawn_blocking(move || commit_all_parts_data(&path, &bank_id))
-        .await
-        .unwrap()
+    tauri::async_runtime::spawn_blocking(move || {
+        edit_journal::record_edit(
+            &path,
+            "commit_all_parts",
+            &edit_journal::bank_files_for_id(&bank_id),
+            || commit_all_parts_data(&path, &bank_id, verify),
+        )
+    })
+    .await
+    .unwrap()
 }
 
 #[tauri::command]
-async fn reload_part(path: String, bank_id: String, part_id: u8) -> Result<PartData, String> {
+async fn reload_part(
+    path: String,
+    bank_id: String,
+    part_id: u8,
+    verify: Option<bool>,
+) -> Result<PartData, VerificationFailure> {
+    fs_scope::ensure_allowed(&path)?;
+    let verify = verify.unwrap_or_else(write_verify::verify_by_default);
     // Reload a part: copy parts.saved back to parts.unsaved (like Octatrack's "RELOAD" command)
-    tauri::async_runtime::spawn_blocking(move || reload_part_data(&path, &bank_id, part_id))
-        .await
-        .unwrap()
+    tauri::async_runtime::spawn_blocking(move || {
+        edit_journal::record_edit(
+            &path,
+            "reload_part",
+            &edit_journal::bank_files_for_id(&bank_id),
+            || reload_part_data(&path, &bank_id, part_id, verify),
+        )
+    })
+    .await
+    .unwrap()
+}
+
+#[tauri::command]
+async fn init_part(path: String, bank_id: String, part_id: u8) -> Result<PartData, String> {
+    fs_scope::ensure_allowed(&path)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        edit_journal::record_edit(
+            &path,
+            "init_part",
+            &edit_journal::bank_files_for_id(&bank_id),
+            || init_part_data(&path, &bank_id, part_id),
+        )
+    })
+    .await
+    .unwrap()
+}
+
+#[tauri::command]
+async fn copy_track_setup(
+    path: String,
+    bank_id: String,
+    source_part: u8,
+    source_track: u8,
+    dest_part: u8,
+    dest_tracks: Vec<u8>,
+) -> Result<(), String> {
+    fs_scope::ensure_allowed(&path)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        edit_journal::record_edit(
+            &path,
+            "copy_track_setup",
+            &edit_journal::bank_files_for_id(&bank_id),
+            || {
+                copy_track_setup_data(
+                    &path,
+                    &bank_id,
+                    source_part,
+                    source_track,
+                    dest_part,
+                    &dest_tracks,
+                )
+            },
+        )
+    })
+    .await
+    .unwrap()
+}
+
+#[tauri::command]
+async fn apply_fx_preset(
+    path: String,
+    fx: PartTrackFx,
+    targets: Vec<FxPresetTarget>,
+) -> Result<u32, String> {
+    fs_scope::ensure_allowed(&path)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut files: Vec<String> = Vec::new();
+        for target in &targets {
+            for file in edit_journal::bank_files_for_id(&target.bank_id) {
+                if !files.contains(&file) {
+                    files.push(file);
+                }
+            }
+        }
+        edit_journal::record_edit(&path, "apply_fx_preset", &files, || {
+            apply_fx_preset_data(&path, &fx, &targets)
+        })
+    })
+    .await
+    .unwrap()
+}
+
+#[tauri::command]
+async fn rename_part(
+    path: String,
+    bank_id: String,
+    part_id: u8,
+    new_name: String,
+) -> Result<(), String> {
+    fs_scope::ensure_allowed(&path)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        edit_journal::record_edit(
+            &path,
+            "rename_part",
+            &edit_journal::bank_files_for_id(&bank_id),
+            || rename_part_data(&path, &bank_id, part_id, &new_name),
+        )
+    })
+    .await
+    .unwrap()
+}
+
+#[tauri::command]
+async fn shift_track_trigs(
+    path: String,
+    bank_index: u8,
+    pattern_index: u8,
+    track_index: u8,
+    steps: i32,
+) -> Result<(), String> {
+    fs_scope::ensure_allowed(&path)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        edit_journal::record_edit(
+            &path,
+            "shift_track_trigs",
+            &edit_journal::bank_files(bank_index),
+            || shift_track_trigs_data(&path, bank_index, pattern_index, track_index, steps),
+        )
+    })
+    .await
+    .unwrap()
+}
+
+/// Returns the new pattern length.
+#[tauri::command]
+async fn resize_pattern(
+    path: String,
+    bank_index: u8,
+    pattern_index: u8,
+    double: bool,
+) -> Result<u16, String> {
+    fs_scope::ensure_allowed(&path)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        edit_journal::record_edit(
+            &path,
+            "resize_pattern",
+            &edit_journal::bank_files(bank_index),
+            || resize_pattern_data(&path, bank_index, pattern_index, double),
+        )
+    })
+    .await
+    .unwrap()
+}
+
+#[tauri::command]
+async fn clear_pattern(
+    path: String,
+    bank_index: u8,
+    pattern_index: u8,
+    scope: String,
+) -> Result<(), String> {
+    fs_scope::ensure_allowed(&path)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        edit_journal::record_edit(
+            &path,
+            "clear_pattern",
+            &edit_journal::bank_files(bank_index),
+            || clear_pattern_data(&path, bank_index, pattern_index, &scope),
+        )
+    })
+    .await
+    .unwrap()
+}
+
+#[tauri::command]
+async fn init_bank(path: String, bank_index: u8) -> Result<String, String> {
+    fs_scope::ensure_allowed(&path)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        edit_journal::record_edit(
+            &path,
+            "init_bank",
+            &edit_journal::bank_files(bank_index),
+            || init_bank_data(&path, bank_index),
+        )
+    })
+    .await
+    .unwrap()
+}
+
+#[tauri::command]
+async fn quantize_micro_timing(
+    path: String,
+    bank_index: u8,
+    pattern_index: u8,
+    track_index: Option<u8>,
+) -> Result<u32, String> {
+    fs_scope::ensure_allowed(&path)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        edit_journal::record_edit(
+            &path,
+            "quantize_micro_timing",
+            &edit_journal::bank_files(bank_index),
+            || quantize_micro_timing_data(&path, bank_index, pattern_index, track_index),
+        )
+    })
+    .await
+    .unwrap()
+}
+
+#[tauri::command]
+async fn set_track_groove(
+    path: String,
+    bank_index: u8,
+    pattern_index: u8,
+    track_index: u8,
+    swing_amount: Option<u8>,
+    per_track_len: Option<u8>,
+    per_track_scale: Option<String>,
+) -> Result<(), String> {
+    fs_scope::ensure_allowed(&path)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        edit_journal::record_edit(
+            &path,
+            "set_track_groove",
+            &edit_journal::bank_files(bank_index),
+            || {
+                set_track_groove_data(
+                    &path,
+                    bank_index,
+                    pattern_index,
+                    track_index,
+                    swing_amount,
+                    per_track_len,
+                    per_track_scale.as_deref(),
+                )
+            },
+        )
+    })
+    .await
+    .unwrap()
+}
+
+#[tauri::command]
+async fn set_trig_probability(
+    path: String,
+    bank_index: u8,
+    pattern_index: u8,
+    track_indices: Vec<u8>,
+    steps: Option<Vec<u8>>,
+    mode: String,
+    percent: f32,
+) -> Result<u32, String> {
+    fs_scope::ensure_allowed(&path)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        edit_journal::record_edit(
+            &path,
+            "set_trig_probability",
+            &edit_journal::bank_files(bank_index),
+            || {
+                set_trig_probability_data(
+                    &path,
+                    bank_index,
+                    pattern_index,
+                    &track_indices,
+                    steps.as_deref(),
+                    &mode,
+                    percent,
+                )
+            },
+        )
+    })
+    .await
+    .unwrap()
+}
+
+#[tauri::command]
+async fn transform_pattern(
+    path: String,
+    bank_index: u8,
+    pattern_index: u8,
+    track_index: Option<u8>,
+    op: String,
+) -> Result<(), String> {
+    fs_scope::ensure_allowed(&path)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        edit_journal::record_edit(
+            &path,
+            "transform_pattern",
+            &edit_journal::bank_files(bank_index),
+            || transform_pattern_data(&path, bank_index, pattern_index, track_index, &op),
+        )
+    })
+    .await
+    .unwrap()
+}
+
+#[tauri::command]
+async fn set_pattern_assignment(
+    path: String,
+    bank_index: u8,
+    pattern_indices: Vec<u8>,
+    part_assignment: Option<u8>,
+    chain_mode: Option<String>,
+) -> Result<(), String> {
+    fs_scope::ensure_allowed(&path)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        edit_journal::record_edit(
+            &path,
+            "set_pattern_assignment",
+            &edit_journal::bank_files(bank_index),
+            || {
+                set_pattern_assignment_data(
+                    &path,
+                    bank_index,
+                    &pattern_indices,
+                    part_assignment,
+                    chain_mode.as_deref(),
+                )
+            },
+        )
+    })
+    .await
+    .unwrap()
+}
+
+#[tauri::command]
+async fn set_pattern_tempo(
+    path: String,
+    bank_index: u8,
+    pattern_index: u8,
+    bpm: Option<f32>,
+) -> Result<(), String> {
+    fs_scope::ensure_allowed(&path)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        edit_journal::record_edit(
+            &path,
+            "set_pattern_tempo",
+            &edit_journal::bank_files(bank_index),
+            || set_pattern_tempo_data(&path, bank_index, pattern_index, bpm),
+        )
+    })
+    .await
+    .unwrap()
+}
+
+#[tauri::command]
+async fn set_project_tempo(
+    path: String,
+    bpm: f32,
+    pattern_tempo_enabled: Option<bool>,
+) -> Result<(), String> {
+    fs_scope::ensure_allowed(&path)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        edit_journal::record_edit(
+            &path,
+            "set_project_tempo",
+            &edit_journal::project_files(),
+            || set_project_tempo_data(&path, bpm, pattern_tempo_enabled),
+        )
+    })
+    .await
+    .unwrap()
+}
+
+#[tauri::command]
+async fn set_slot_gain(
+    path: String,
+    slot_type: Option<String>,
+    slot_indices: Option<Vec<u16>>,
+    path_contains: Option<String>,
+    mode: String,
+    db: f32,
+) -> Result<u32, String> {
+    fs_scope::ensure_allowed(&path)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        edit_journal::record_edit(
+            &path,
+            "set_slot_gain",
+            &edit_journal::project_files(),
+            || {
+                set_slot_gain_data(
+                    &path,
+                    slot_type.as_deref(),
+                    slot_indices.as_deref(),
+                    path_contains.as_deref(),
+                    &mode,
+                    db,
+                )
+            },
+        )
+    })
+    .await
+    .unwrap()
+}
+
+#[tauri::command]
+async fn convert_slot_type(
+    path: String,
+    slot_type: String,
+    slot_index: u16,
+    to_slot: Option<u16>,
+) -> Result<SlotConversionResult, String> {
+    fs_scope::ensure_allowed(&path)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        // Machine assignments and sample locks may be rewritten in any bank.
+        let files: Vec<String> = (0..16u8)
+            .flat_map(edit_journal::bank_files)
+            .chain(edit_journal::project_files())
+            .collect();
+        edit_journal::record_edit(&path, "convert_slot_type", &files, || {
+            convert_slot_type_data(&path, &slot_type, slot_index, to_slot)
+        })
+    })
+    .await
+    .unwrap()
+}
+
+#[tauri::command]
+async fn reorder_sample_slots(
+    path: String,
+    slot_type: String,
+    mode: String,
+    moves: Option<Vec<SlotMove>>,
+) -> Result<SlotReorderResult, String> {
+    fs_scope::ensure_allowed(&path)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        // Default slots and sample locks may be rewritten in any bank.
+        let files: Vec<String> = (0..16u8)
+            .flat_map(edit_journal::bank_files)
+            .chain(edit_journal::project_files())
+            .collect();
+        edit_journal::record_edit(&path, "reorder_sample_slots", &files, || {
+            reorder_sample_slots_data(
+                &path,
+                &slot_type,
+                &mode,
+                moves.as_deref().unwrap_or_default(),
+            )
+        })
+    })
+    .await
+    .unwrap()
 }
 
 #[tauri::command]
@@ -310,6 +1036,7 @@ fn navigate_to_parent(path: String) -> Result<String, String> {
 
 #[tauri::command]
 fn create_new_directory(path: String, name: String) -> Result<String, String> {
+    fs_scope::ensure_allowed(&std::path::Path::new(&path).join(&name).to_string_lossy())?;
     create_directory(&path, &name)
 }
 
@@ -319,10 +1046,41 @@ async fn copy_audio_files(
     destination_dir: String,
     overwrite: Option<bool>,
 ) -> Result<Vec<String>, String> {
+    fs_scope::ensure_allowed(&destination_dir)?;
     let should_overwrite = overwrite.unwrap_or(false);
     // Run on a blocking thread pool to avoid blocking the main event loop
     tauri::async_runtime::spawn_blocking(move || {
-        copy_files_with_overwrite(source_paths, &destination_dir, should_overwrite)
+        let copied =
+            copy_files_with_overwrite(source_paths.clone(), &destination_dir, should_overwrite)?;
+        for (source, dest) in source_paths.iter().zip(copied.iter()) {
+            let dest = std::path::Path::new(dest);
+            if dest.is_file() {
+                library_index::record_import(std::path::Path::new(source), dest);
+            }
+        }
+        Ok(copied)
+    })
+    .await
+    .unwrap()
+}
+
+#[tauri::command]
+async fn copy_audio_files_with_policy(
+    source_paths: Vec<String>,
+    destination_dir: String,
+    policy: Option<audio_pool::ConflictPolicy>,
+) -> Result<Vec<audio_pool::CopyOutcome>, String> {
+    fs_scope::ensure_allowed(&destination_dir)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let outcomes =
+            copy_files_with_policy(&source_paths, &destination_dir, policy.unwrap_or_default())?;
+        for outcome in outcomes.iter().filter(|o| o.action != "skipped") {
+            library_index::record_import(
+                std::path::Path::new(&outcome.source),
+                std::path::Path::new(&outcome.dest),
+            );
+        }
+        Ok(outcomes)
     })
     .await
     .unwrap()
@@ -333,6 +1091,7 @@ async fn copy_audio_files_to_project(
     source_paths: Vec<String>,
     destination_dir: String,
 ) -> Result<Vec<String>, String> {
+    fs_scope::ensure_allowed(&destination_dir)?;
     tauri::async_runtime::spawn_blocking(move || {
         copy_audio_files_or_use_existing(source_paths, &destination_dir)
     })
@@ -347,7 +1106,9 @@ async fn copy_audio_file_with_progress(
     destination_dir: String,
     transfer_id: String,
     overwrite: Option<bool>,
+    conversion: Option<audio_pool::ConversionOptions>,
 ) -> Result<String, String> {
+    fs_scope::ensure_allowed(&destination_dir)?;
     let should_overwrite = overwrite.unwrap_or(false);
     let source_path_clone = source_path.clone();
     let transfer_id_for_callback = transfer_id.clone();
@@ -371,13 +1132,19 @@ async fn copy_audio_file_with_progress(
 
     // Run on a blocking thread pool
     let result = tauri::async_runtime::spawn_blocking(move || {
-        copy_single_file_with_progress(
+        let dest = copy_single_file_with_options(
             &source_path,
             &destination_dir,
             should_overwrite,
+            &conversion.unwrap_or_default(),
             progress_callback,
             Some(cancel_token),
-        )
+        )?;
+        library_index::record_import(
+            std::path::Path::new(&source_path),
+            std::path::Path::new(&dest),
+        );
+        Ok(dest)
     })
     .await
     .unwrap();
@@ -398,6 +1165,8 @@ async fn move_audio_files(
     source_paths: Vec<String>,
     destination_dir: String,
 ) -> Result<Vec<String>, String> {
+    fs_scope::ensure_all_allowed(&source_paths)?;
+    fs_scope::ensure_allowed(&destination_dir)?;
     // Run on a blocking thread pool to avoid blocking the main event loop
     tauri::async_runtime::spawn_blocking(move || move_files(source_paths, &destination_dir))
         .await
@@ -406,6 +1175,7 @@ async fn move_audio_files(
 
 #[tauri::command]
 async fn delete_audio_files(file_paths: Vec<String>) -> Result<usize, String> {
+    fs_scope::ensure_all_allowed(&file_paths)?;
     // Run on a blocking thread pool to avoid blocking the main event loop
     tauri::async_runtime::spawn_blocking(move || delete_files(file_paths))
         .await
@@ -421,11 +1191,19 @@ fn get_home_directory() -> Result<String, String> {
 
 #[tauri::command]
 fn rename_file(old_path: String, new_name: String) -> Result<String, String> {
+    fs_scope::ensure_allowed(&old_path)?;
+    // The new name could itself contain `..` or separators
+    fs_scope::ensure_allowed(
+        &std::path::Path::new(&old_path)
+            .with_file_name(&new_name)
+            .to_string_lossy(),
+    )?;
     rename_file_impl(&old_path, &new_name)
 }
 
 #[tauri::command]
 fn delete_file(path: String) -> Result<usize, String> {
+    fs_scope::ensure_allowed(&path)?;
     delete_files(vec![path])
 }
 
@@ -528,6 +1306,7 @@ async fn get_audio_pool_status(project_path: String) -> Result<AudioPoolStatus,
 
 #[tauri::command]
 async fn create_audio_pool(project_path: String) -> Result<String, String> {
+    fs_scope::ensure_allowed(&project_path)?;
     tauri::async_runtime::spawn_blocking(move || create_audio_pool_impl(&project_path))
         .await
         .unwrap()
@@ -550,19 +1329,24 @@ async fn copy_bank(
     copy_attributes: Option<bool>,
     attribute_selection: Option<Vec<String>>,
 ) -> Result<project_reader::CopyBankResult, String> {
+    fs_scope::ensure_allowed(&dest_project)?;
     tauri::async_runtime::spawn_blocking(move || {
-        copy_bank_impl(
-            &source_project,
-            source_bank_index,
-            &dest_project,
-            &dest_bank_indices,
-            copy_samples.unwrap_or(false),
-            &sample_scope.unwrap_or_default(),
-            &audio_mode.unwrap_or_default(),
-            &slot_placement.unwrap_or_else(|| "keep_position".to_string()),
-            copy_attributes.unwrap_or(false),
-            &attribute_selection.unwrap_or_default(),
-        )
+        let mut files = edit_journal::banks_files(&dest_bank_indices);
+        files.extend(edit_journal::project_files());
+        edit_journal::record_edit(&dest_project, "copy_bank", &files, || {
+            copy_bank_impl(
+                &source_project,
+                source_bank_index,
+                &dest_project,
+                &dest_bank_indices,
+                copy_samples.unwrap_or(false),
+                &sample_scope.unwrap_or_default(),
+                &audio_mode.unwrap_or_default(),
+                &slot_placement.unwrap_or_else(|| "keep_position".to_string()),
+                copy_attributes.unwrap_or(false),
+                &attribute_selection.unwrap_or_default(),
+            )
+        })
     })
     .await
     .unwrap()
@@ -589,6 +1373,69 @@ async fn validate_bank_sample_slots(
     .unwrap()
 }
 
+/// Pre-flight check for a whole-bank transfer: destination conflict and slot mismatches.
+#[tauri::command]
+async fn check_bank_transfer(
+    source_project: String,
+    source_bank_index: u8,
+    dest_project: String,
+    dest_bank_index: u8,
+) -> Result<project_reader::BankTransferCheck, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        project_reader::check_bank_transfer(
+            &source_project,
+            source_bank_index,
+            &dest_project,
+            dest_bank_index,
+        )
+    })
+    .await
+    .unwrap()
+}
+
+/// Copy or move a whole bank file into another project.
+#[tauri::command]
+async fn transfer_bank(
+    source_project: String,
+    source_bank_index: u8,
+    dest_project: String,
+    dest_bank_index: u8,
+    overwrite: Option<bool>,
+    move_bank: Option<bool>,
+) -> Result<project_reader::BankTransferCheck, String> {
+    fs_scope::ensure_allowed(&dest_project)?;
+    if move_bank.unwrap_or(false) {
+        fs_scope::ensure_allowed(&source_project)?;
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        let move_bank = move_bank.unwrap_or(false);
+        let mut projects = vec![(
+            dest_project.as_str(),
+            edit_journal::bank_files(dest_bank_index),
+        )];
+        if move_bank {
+            let source_files = edit_journal::bank_files(source_bank_index);
+            if std::path::Path::new(&source_project) == std::path::Path::new(&dest_project) {
+                projects[0].1.extend(source_files);
+            } else {
+                projects.push((source_project.as_str(), source_files));
+            }
+        }
+        edit_journal::record_edits(&projects, "transfer_bank", || {
+            project_reader::transfer_bank(
+                &source_project,
+                source_bank_index,
+                &dest_project,
+                dest_bank_index,
+                overwrite.unwrap_or(false),
+                move_bank,
+            )
+        })
+    })
+    .await
+    .unwrap()
+}
+
 #[tauri::command]
 async fn copy_parts(
     source_project: String,
@@ -598,14 +1445,22 @@ async fn copy_parts(
     dest_bank_index: u8,
     dest_part_indices: Vec<u8>,
 ) -> Result<(), String> {
+    fs_scope::ensure_allowed(&dest_project)?;
     tauri::async_runtime::spawn_blocking(move || {
-        copy_parts_impl(
-            &source_project,
-            source_bank_index,
-            source_part_indices,
+        edit_journal::record_edit(
             &dest_project,
-            dest_bank_index,
-            dest_part_indices,
+            "copy_parts",
+            &edit_journal::bank_files(dest_bank_index),
+            || {
+                copy_parts_impl(
+                    &source_project,
+                    source_bank_index,
+                    source_part_indices,
+                    &dest_project,
+                    dest_bank_index,
+                    dest_part_indices,
+                )
+            },
         )
     })
     .await
@@ -626,19 +1481,61 @@ async fn copy_patterns(
     track_indices: Option<Vec<u8>>,
     mode_scope: Option<String>,
 ) -> Result<(), String> {
+    fs_scope::ensure_allowed(&dest_project)?;
     tauri::async_runtime::spawn_blocking(move || {
-        copy_patterns_impl(
-            &source_project,
-            source_bank_index,
-            source_pattern_indices,
+        edit_journal::record_edit(
             &dest_project,
-            dest_bank_index,
-            dest_pattern_indices,
-            &part_assignment_mode,
-            dest_part,
-            &track_mode,
-            track_indices,
-            mode_scope.as_deref().unwrap_or("audio"),
+            "copy_patterns",
+            &edit_journal::bank_files(dest_bank_index),
+            || {
+                copy_patterns_impl(
+                    &source_project,
+                    source_bank_index,
+                    source_pattern_indices,
+                    &dest_project,
+                    dest_bank_index,
+                    dest_pattern_indices,
+                    &part_assignment_mode,
+                    dest_part,
+                    &track_mode,
+                    track_indices,
+                    mode_scope.as_deref().unwrap_or("audio"),
+                )
+            },
+        )
+    })
+    .await
+    .unwrap()
+}
+
+/// Copy one full pattern to another bank/pattern slot, optionally across projects.
+#[tauri::command]
+async fn copy_pattern(
+    source_project: String,
+    source_bank_index: u8,
+    source_pattern_index: u8,
+    dest_project: String,
+    dest_bank_index: u8,
+    dest_pattern_index: u8,
+    dest_part: Option<u8>,
+) -> Result<(), String> {
+    fs_scope::ensure_allowed(&dest_project)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        edit_journal::record_edit(
+            &dest_project,
+            "copy_pattern",
+            &edit_journal::bank_files(dest_bank_index),
+            || {
+                project_reader::copy_pattern(
+                    &source_project,
+                    source_bank_index,
+                    source_pattern_index,
+                    &dest_project,
+                    dest_bank_index,
+                    dest_pattern_index,
+                    dest_part,
+                )
+            },
         )
     })
     .await
@@ -659,65 +1556,73 @@ async fn copy_tracks(
     source_pattern_index: Option<u8>, // None = all 16 patterns, Some(0-15) = specific
     dest_pattern_indices: Option<Vec<u8>>, // None = all 16 patterns, Some = specific (1-to-many)
 ) -> Result<(), String> {
+    fs_scope::ensure_allowed(&dest_project)?;
     tauri::async_runtime::spawn_blocking(move || {
-        // Build the list of (src_pattern, dest_pattern) pairs to process
-        let pattern_pairs: Vec<(Option<u8>, Option<u8>)> = match (&source_pattern_index, &dest_pattern_indices) {
-            (None, None) => vec![(None, None)],                  // All → All (1-to-1)
-            (Some(src), None) => vec![(Some(*src), None)],       // Specific → All
-            (Some(src), Some(dsts)) => {
-                // 1-to-many: copy source pattern to each destination pattern
-                dsts.iter().map(|&d| (Some(*src), Some(d))).collect()
-            }
-            (None, Some(_)) => {
-                return Err("Cannot specify destination patterns when source is 'All'".to_string());
-            }
-        };
-
-        match (source_part_index, &dest_part_indices) {
-            (None, None) => {
-                // Copy tracks across all 4 parts (1-to-1 mapping)
-                for part_idx in 0..4u8 {
-                    for &(src_pat, dst_pat) in &pattern_pairs {
-                        copy_tracks_impl(
-                            &source_project,
-                            source_bank_index,
-                            part_idx,
-                            source_track_indices.clone(),
-                            &dest_project,
-                            dest_bank_index,
-                            part_idx,
-                            dest_track_indices.clone(),
-                            &mode,
-                            src_pat,
-                            dst_pat,
-                        )?;
+        edit_journal::record_edit(
+            &dest_project,
+            "copy_tracks",
+            &edit_journal::bank_files(dest_bank_index),
+            || {
+                // Build the list of (src_pattern, dest_pattern) pairs to process
+                let pattern_pairs: Vec<(Option<u8>, Option<u8>)> = match (&source_pattern_index, &dest_pattern_indices) {
+                    (None, None) => vec![(None, None)],                  // All → All (1-to-1)
+                    (Some(src), None) => vec![(Some(*src), None)],       // Specific → All
+                    (Some(src), Some(dsts)) => {
+                        // 1-to-many: copy source pattern to each destination pattern
+                        dsts.iter().map(|&d| (Some(*src), Some(d))).collect()
                     }
-                }
-                Ok(())
-            }
-            (Some(src), Some(dst_indices)) => {
-                // Copy source part to each selected destination part (1-to-many)
-                for &dst in dst_indices {
-                    for &(src_pat, dst_pat) in &pattern_pairs {
-                        copy_tracks_impl(
-                            &source_project,
-                            source_bank_index,
-                            src,
-                            source_track_indices.clone(),
-                            &dest_project,
-                            dest_bank_index,
-                            dst,
-                            dest_track_indices.clone(),
-                            &mode,
-                            src_pat,
-                            dst_pat,
-                        )?;
+                    (None, Some(_)) => {
+                        return Err("Cannot specify destination patterns when source is 'All'".to_string());
+                    }
+                };
+
+                match (source_part_index, &dest_part_indices) {
+                    (None, None) => {
+                        // Copy tracks across all 4 parts (1-to-1 mapping)
+                        for part_idx in 0..4u8 {
+                            for &(src_pat, dst_pat) in &pattern_pairs {
+                                copy_tracks_impl(
+                                    &source_project,
+                                    source_bank_index,
+                                    part_idx,
+                                    source_track_indices.clone(),
+                                    &dest_project,
+                                    dest_bank_index,
+                                    part_idx,
+                                    dest_track_indices.clone(),
+                                    &mode,
+                                    src_pat,
+                                    dst_pat,
+                                )?;
+                            }
+                        }
+                        Ok(())
                     }
+                    (Some(src), Some(dst_indices)) => {
+                        // Copy source part to each selected destination part (1-to-many)
+                        for &dst in dst_indices {
+                            for &(src_pat, dst_pat) in &pattern_pairs {
+                                copy_tracks_impl(
+                                    &source_project,
+                                    source_bank_index,
+                                    src,
+                                    source_track_indices.clone(),
+                                    &dest_project,
+                                    dest_bank_index,
+                                    dst,
+                                    dest_track_indices.clone(),
+                                    &mode,
+                                    src_pat,
+                                    dst_pat,
+                                )?;
+                            }
+                        }
+                        Ok(())
+                    }
+                    _ => Err("Both source and destination part indices must be specified or both must be None (all parts)".to_string())
                 }
-                Ok(())
-            }
-            _ => Err("Both source and destination part indices must be specified or both must be None (all parts)".to_string())
-        }
+            },
+        )
     })
     .await
     .unwrap()
@@ -735,17 +1640,44 @@ async fn copy_sample_slots(
     copy_attributes: bool,
     attribute_selection: Vec<String>,
 ) -> Result<project_reader::CopySlotsResult, String> {
+    fs_scope::ensure_allowed(&dest_project)?;
     tauri::async_runtime::spawn_blocking(move || {
-        copy_sample_slots_impl(
-            &source_project,
+        edit_journal::record_edit(
+            &dest_project,
+            "copy_sample_slots",
+            &edit_journal::project_files(),
+            || {
+                copy_sample_slots_impl(
+                    &source_project,
+                    &dest_project,
+                    &slot_type,
+                    source_indices,
+                    dest_indices,
+                    copy_assignments,
+                    &audio_mode,
+                    copy_attributes,
+                    attribute_selection,
+                )
+            },
+        )
+    })
+    .await
+    .unwrap()
+}
+
+#[tauri::command]
+async fn copy_slot_assignments(
+    source_project: String,
+    dest_project: String,
+    slots: Vec<project_reader::SlotCopyRequest>,
+) -> Result<Vec<project_reader::SlotCopyOutcome>, String> {
+    fs_scope::ensure_allowed(&dest_project)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        edit_journal::record_edit(
             &dest_project,
-            &slot_type,
-            source_indices,
-            dest_indices,
-            copy_assignments,
-            &audio_mode,
-            copy_attributes,
-            attribute_selection,
+            "copy_slot_assignments",
+            &edit_journal::project_files(),
+            || project_reader::copy_slot_assignments(&source_project, &dest_project, &slots),
         )
     })
     .await
@@ -781,7 +1713,7 @@ async fn get_slot_audio_paths(
 
 /// Back up specific files from a project before modifying them.
 /// Creates a timestamped subdirectory under `<project_path>/backups/` and copies the listed files.
-fn backup_project_files_impl(
+pub(crate) fn backup_project_files_impl(
     project_path: &str,
     files: &[String],
     label: &str,
@@ -808,6 +1740,14 @@ fn backup_project_files_impl(
         return Ok("No files to back up".to_string());
     }
 
+    let required = disk_space::files_size(
+        &existing_files
+            .iter()
+            .map(|f| project_dir.join(f))
+            .collect::<Vec<_>>(),
+    );
+    disk_space::ensure_free_space(&backup_dir, required)?;
+
     std::fs::create_dir_all(&backup_dir)
         .map_err(|e| format!("Failed to create backup directory: {}", e))?;
 
@@ -838,6 +1778,7 @@ async fn backup_project_files(
     files: Vec<String>,
     label: String,
 ) -> Result<String, String> {
+    fs_scope::ensure_allowed(&project_path)?;
     tauri::async_runtime::spawn_blocking(move || {
         backup_project_files_impl(&project_path, &files, &label)
     })
@@ -933,6 +1874,7 @@ async fn fix_pool_files(
     file_paths: Vec<String>,
     transfer_id: String,
 ) -> Result<PoolFixResult, String> {
+    fs_scope::ensure_allowed(&pool_path)?;
     let cancel_token = register_cancellation_token(&transfer_id);
     let transfer_id_for_cleanup = transfer_id.clone();
 
@@ -1022,6 +1964,7 @@ async fn fix_project_samples(
     file_paths: Vec<String>,
     transfer_id: String,
 ) -> Result<PoolFixResult, String> {
+    fs_scope::ensure_allowed(&project_path)?;
     let cancel_token = register_cancellation_token(&transfer_id);
     let transfer_id_for_cleanup = transfer_id.clone();
 
@@ -1100,13 +2043,97 @@ async fn fix_project_samples(
     result
 }
 
+/// Merge one Audio Pool into another, renaming same-name/different-content files.
+#[tauri::command]
+async fn merge_pools(
+    src_root: String,
+    dst_root: String,
+) -> Result<audio_pool::PoolMergeReport, String> {
+    fs_scope::ensure_allowed(&dst_root)?;
+    tauri::async_runtime::spawn_blocking(move || audio_pool::merge_pools(&src_root, &dst_root))
+        .await
+        .unwrap()
+}
+
+/// Repoint slot paths in the destination pool's set onto files renamed by `merge_pools`.
+#[tauri::command]
+async fn apply_pool_merge_mapping(
+    dst_root: String,
+    entries: Vec<audio_pool::PoolMergeEntry>,
+) -> Result<project_reader::PoolReferenceUpdate, String> {
+    fs_scope::ensure_allowed(&dst_root)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let renames = audio_pool::pool_merge_renames(&dst_root, &entries);
+        if renames.is_empty() {
+            return Ok(project_reader::PoolReferenceUpdate {
+                projects_updated: vec![],
+                slots_updated: 0,
+            });
+        }
+        project_reader::update_pool_references(&dst_root, &renames)
+    })
+    .await
+    .unwrap()
+}
+
 #[tauri::command]
 async fn fix_missing_samples(
     project_path: String,
     resolutions: Vec<project_reader::SampleResolution>,
 ) -> Result<project_reader::FixResult, String> {
+    fs_scope::ensure_allowed(&project_path)?;
     tauri::async_runtime::spawn_blocking(move || {
-        project_reader::fix_missing_samples(&project_path, resolutions)
+        edit_journal::record_edit(
+            &project_path,
+            "fix_missing_samples",
+            &edit_journal::project_files(),
+            || project_reader::fix_missing_samples(&project_path, resolutions),
+        )
+    })
+    .await
+    .unwrap()
+}
+
+/// Copy samples referenced from outside the project into it and repoint the
+/// slots. Emits "copy-progress" events per file unless `dry_run`.
+#[tauri::command]
+async fn consolidate_samples(
+    app: AppHandle,
+    project_path: String,
+    dry_run: bool,
+    transfer_id: String,
+) -> Result<project_reader::ConsolidateResult, String> {
+    if !dry_run {
+        fs_scope::ensure_allowed(&project_path)?;
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        let consolidate = || {
+            project_reader::consolidate_samples(&project_path, dry_run, |file, progress| {
+                let _ = app.emit(
+                    "copy-progress",
+                    CopyProgressEvent {
+                        file_path: file.to_string(),
+                        transfer_id: transfer_id.clone(),
+                        stage: if progress >= 1.0 {
+                            "complete"
+                        } else {
+                            "copying"
+                        }
+                        .to_string(),
+                        progress,
+                    },
+                );
+            })
+        };
+        if dry_run {
+            return consolidate();
+        }
+        edit_journal::record_edit(
+            &project_path,
+            "consolidate_samples",
+            &edit_journal::project_files(),
+            consolidate,
+        )
     })
     .await
     .unwrap()
@@ -1127,6 +2154,9 @@ pub fn run() {
                 std::thread::sleep(std::time::Duration::from_millis(100));
                 let _ = window.eval("sessionStorage.clear()");
             });
+            library_index::resume_interrupted_rebuild(app.handle().clone());
+            transfer_queue::restore_saved_queue(app.handle().clone());
+            maintenance::start_scheduler();
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -1136,25 +2166,60 @@ pub fn run() {
             load_project_metadata,
             load_project_banks,
             load_single_bank,
+            load_bank_summary,
+            load_pattern,
             compute_sample_usage,
+            find_sample_references,
             get_pool_usage,
             list_set_projects,
             get_existing_banks,
             load_parts_data,
             save_parts,
+            check_memory_settings,
             save_memory_settings,
+            save_metronome_settings,
+            save_midi_settings,
+            save_mixer_settings,
             commit_part,
             commit_all_parts,
             reload_part,
+            rename_part,
+            init_part,
+            copy_track_setup,
+            apply_fx_preset,
+            shift_track_trigs,
+            resize_pattern,
+            quantize_micro_timing,
+            clear_pattern,
+            init_bank,
+            transform_pattern,
+            set_track_groove,
+            set_trig_probability,
+            set_pattern_assignment,
+            set_pattern_tempo,
+            set_project_tempo,
+            set_slot_gain,
+            convert_slot_type,
+            reorder_sample_slots,
             list_audio_directory,
             list_audio_files_recursive,
             list_audio_directory_recursive,
             navigate_to_parent,
             create_new_directory,
             copy_audio_files,
+            copy_audio_files_with_policy,
             copy_audio_files_to_project,
             copy_audio_file_with_progress,
             cancel_audio_transfer,
+            transfer_queue::enqueue_transfers,
+            transfer_queue::get_transfer_queue,
+            transfer_queue::pause_transfer_queue,
+            transfer_queue::resume_transfer_queue,
+            transfer_queue::discard_transfer_queue,
+            transfer_queue::set_transfer_concurrency,
+            transfer_queue::reorder_transfer_queue,
+            transfer_queue::cancel_queued_transfer,
+            transfer_queue::clear_finished_transfers,
             move_audio_files,
             delete_audio_files,
             get_home_directory,
@@ -1175,10 +2240,14 @@ pub fn run() {
             // Tools Tab - Copy Operations
             copy_bank,
             validate_bank_sample_slots,
+            check_bank_transfer,
+            transfer_bank,
             copy_parts,
             copy_patterns,
+            copy_pattern,
             copy_tracks,
             copy_sample_slots,
+            copy_slot_assignments,
             check_missing_source_files,
             get_slot_audio_paths,
             backup_project_files,
@@ -1190,11 +2259,119 @@ pub fn run() {
             search_parent_projects,
             search_directory,
             fix_missing_samples,
+            consolidate_samples,
             fix_pool_files,
             fix_project_samples,
+            merge_pools,
+            apply_pool_merge_mapping,
+            // Operation plans
+            operation_plan::preview_plan,
+            operation_plan::execute_plan,
+            // Library index
+            library_index::get_file_provenance,
+            library_index::start_library_rebuild,
+            library_index::pause_library_rebuild,
+            library_index::resume_library_rebuild,
+            library_index::cancel_library_rebuild,
+            feature_export::export_features,
+            feature_export::import_feature_list,
+            // Sample attributes (.ot)
+            sample_attributes::get_sample_attributes,
+            sample_attributes::set_sample_attributes,
+            sample_attributes::generate_slices,
+            sample_attributes::generate_transient_slices,
+            sample_chain::build_sample_chain,
+            sample_export::export_samples,
+            audio_analysis::detect_sample_bpm,
+            level_analysis::analyze_sample_levels,
+            // Sample packs
+            sample_pack::publish_pack,
+            // Filesystem scope
+            fs_scope::pick_library_root,
+            fs_scope::list_approved_roots,
+            // Disk space
+            disk_space::get_cleanup_suggestions,
+            disk_space::analyze_pool_usage,
+            pool_index::index_pool_directory,
+            pool_index::refresh_pool_index,
+            pool_index::search_pool_index,
+            pool_names::validate_pool_names,
+            // Project notes
+            project_notes::get_project_notes,
+            project_notes::set_project_note,
+            project_notes::set_color_label,
+            part_presets::list_part_presets,
+            part_presets::save_part_preset,
+            part_presets::delete_part_preset,
+            part_presets::apply_part_preset,
+            // Project history
+            project_diff::generate_project_changelog,
+            project_diff::diff_projects,
+            project_diff::diff_banks,
+            project_diff::get_unsaved_changes,
+            // Automatic write backups
+            write_backup::list_write_backups,
+            write_backup::restore_write_backup,
+            // Edit journal
+            edit_journal::undo_last_edit,
+            edit_journal::redo_edit,
+            edit_journal::get_edit_journal,
+            // Parameter decoding
+            param_decode::decode_part_parameters,
+            // Project validation
+            project_lint::validate_project,
+            project_lint::verify_bank_checksums,
+            project_lint::get_pregig_checklist,
+            os_compat::check_os_compatibility,
+            metadata_cache::clear_metadata_cache,
+            project_watcher::watch_project,
+            project_watcher::unwatch_project,
+            pool_watcher::watch_pool_directory,
+            pool_watcher::unwatch_pool_directory,
+            pattern_render::render_pattern_preview,
+            project_report::export_project_report,
+            project_archive::export_project_archive,
+            project_archive::inspect_project_archive,
+            project_archive::import_project_archive,
+            setlist::build_setlist_project,
+            set_pool_usage::get_set_pool_usage,
+            batch_convert::convert_directory,
+            archive_import::import_sample_archive,
+            project_lock::acquire_project_lock,
+            project_lock::release_project_lock,
+            // Audio streaming
+            audio_stream::get_stream_url,
+            audio_preview::preview_play,
+            audio_preview::preview_play_slot,
+            audio_preview::preview_stop,
+            audio_preview::preview_pause,
+            audio_preview::preview_resume,
+            audio_preview::preview_seek,
+            audio_preview::preview_set_volume,
+            // JSON export/import
+            bank_json::export_bank_json,
+            bank_json::export_pattern_json,
+            bank_json::import_bank_json,
+            bank_json::import_pattern_json,
+            // MIDI export/import
+            midi_file::export_pattern_midi,
+            midi_file::import_pattern_midi,
+            // Project search
+            project_search::search_project,
+            // Maintenance
+            maintenance::get_maintenance_settings,
+            maintenance::set_maintenance_settings,
+            maintenance::run_maintenance,
+            // Sandbox
+            sandbox::open_sandbox,
+            sandbox::apply_sandbox,
+            sandbox::discard_sandbox,
+            // Arranger
+            arrangement_reader::load_arrangements,
             // Sample slot assignment
             assign_samples_to_slots,
             clear_sample_slots,
+            purge_unused_slots,
             clear_sample_keep_attributes,
             reset_slot_attributes,
             // Project Management
@@ -1214,8 +2391,13 @@ pub fn run() {
             project_manager::rename_set,
             project_manager::delete_set,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|_app, event| {
+            if let tauri::RunEvent::Exit = event {
+                project_lock::release_all();
+            }
+        });
 }
 
 #[cfg(test)]
@@ -0,0 +1,597 @@
+// Library index: a per-user record of files imported through the app and of
+// the audio files under the library roots.
+//
+// Both live in the pool index database (pool_index.rs) in the OS data
+// directory, so nothing extra lands on the CF card: file facts in its `files`
+// table, where a rebuild syncs them, and provenance in its `provenance`
+// table. Records are keyed by the canonical path of the imported
+// (destination) file. Provenance recorded by earlier versions in
+// library_index.json is moved into the database the first time it is opened.
+
+use pool_index::{self, db_error};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+/// What the import did to the audio on its way into the library.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ConversionSettings {
+    pub converted: bool, // false = copied byte-for-byte
+    pub sample_rate: Option<u32>,
+    pub bit_depth: Option<u32>,
+    pub channels: Option<u32>,
+}
+
+/// Where an imported file came from.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FileProvenance {
+    pub original_path: String,
+    pub original_format: String, // uppercased extension: "WAV", "MP3", ...
+    pub original_sample_rate: Option<u32>,
+    pub original_bit_depth: Option<u32>,
+    pub original_channels: Option<u32>,
+    pub conversion: ConversionSettings,
+    pub imported_at: String, // RFC 3339, local time
+}
+
+/// library_index.json as written by earlier versions.
+#[derive(Deserialize)]
+struct LegacyIndex {
+    #[serde(default)]
+    files: BTreeMap<String, LegacyEntry>,
+}
+
+#[derive(Deserialize)]
+struct LegacyEntry {
+    #[serde(default)]
+    provenance: Option<FileProvenance>,
+}
+
+/// Canonical index key for a file path (falls back to the path as given when
+/// the file no longer exists).
+pub fn index_key(path: &Path) -> String {
+    fs::canonicalize(path)
+        .unwrap_or_else(|_| path.to_path_buf())
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Open the database at `db_path`, first moving in the provenance of a
+/// library_index.json left next to it by an earlier version.
+pub fn open_library(db_path: &Path) -> Result<Connection, String> {
+    let mut conn = pool_index::open_index(db_path)?;
+    let legacy = db_path.with_file_name("library_index.json");
+    if legacy.is_file() {
+        migrate_legacy_index(&mut conn, &legacy)?;
+    }
+    Ok(conn)
+}
+
+fn migrate_legacy_index(conn: &mut Connection, legacy: &Path) -> Result<(), String> {
+    let index: LegacyIndex = fs::read_to_string(legacy)
+        .map_err(|e| format!("Failed to read library index: {}", e))
+        .and_then(|data| {
+            serde_json::from_str(&data).map_err(|e| format!("Failed to parse library index: {}", e))
+        })?;
+    let tx = conn.transaction().map_err(db_error)?;
+    for (key, entry) in index.files {
+        if let Some(provenance) = entry.provenance {
+            // Never replace what this version already recorded
+            if provenance_of(&tx, &key)?.is_none() {
+                store_provenance(&tx, &key, &provenance)?;
+            }
+        }
+    }
+    tx.commit().map_err(db_error)?;
+    fs::remove_file(legacy).map_err(|e| format!("Failed to remove library index: {}", e))
+}
+
+fn store_provenance(conn: &Connection, key: &str, p: &FileProvenance) -> Result<(), String> {
+    conn.execute(
+        "INSERT OR REPLACE INTO provenance
+            (path, original_path, original_format, original_sample_rate, original_bit_depth,
+             original_channels, converted, sample_rate, bit_depth, channels, imported_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        params![
+            key,
+            p.original_path,
+            p.original_format,
+            p.original_sample_rate,
+            p.original_bit_depth,
+            p.original_channels,
+            p.conversion.converted,
+            p.conversion.sample_rate,
+            p.conversion.bit_depth,
+            p.conversion.channels,
+            p.imported_at,
+        ],
+    )
+    .map_err(db_error)?;
+    Ok(())
+}
+
+fn provenance_from_row(row: &Row) -> rusqlite::Result<FileProvenance> {
+    Ok(FileProvenance {
+        original_path: row.get(0)?,
+        original_format: row.get(1)?,
+        original_sample_rate: row.get(2)?,
+        original_bit_depth: row.get(3)?,
+        original_channels: row.get(4)?,
+        conversion: ConversionSettings {
+            converted: row.get(5)?,
+            sample_rate: row.get(6)?,
+            bit_depth: row.get(7)?,
+            channels: row.get(8)?,
+        },
+        imported_at: row.get(9)?,
+    })
+}
+
+/// Provenance recorded under `key` (an `index_key`).
+pub fn provenance_of(conn: &Connection, key: &str) -> Result<Option<FileProvenance>, String> {
+    conn.query_row(
+        "SELECT original_path, original_format, original_sample_rate, original_bit_depth,
+                original_channels, converted, sample_rate, bit_depth, channels, imported_at
+         FROM provenance WHERE path = ?1",
+        [key],
+        provenance_from_row,
+    )
+    .optional()
+    .map_err(db_error)
+}
+
+/// Build the provenance record for `source` imported as `dest`.
+fn build_provenance(source: &Path, dest: &Path) -> FileProvenance {
+    let paths = [
+        source.to_string_lossy().to_string(),
+        dest.to_string_lossy().to_string(),
+    ];
+    let info = crate::audio_pool::files_info(&paths);
+    let (src_info, dst_info) = (info.first(), info.get(1));
+    let original_format = source
+        .extension()
+        .map(|e| e.to_string_lossy().to_uppercase())
+        .unwrap_or_default();
+    // A byte-for-byte copy keeps the same name; conversion always produces a .wav.
+    let converted = source.file_name() != dest.file_name()
+        || fs::metadata(source).map(|m| m.len()).ok() != fs::metadata(dest).map(|m| m.len()).ok();
+
+    FileProvenance {
+        original_path: index_key(source),
+        original_format,
+        original_sample_rate: src_info.and_then(|i| i.sample_rate),
+        original_bit_depth: src_info.and_then(|i| i.bit_rate),
+        original_channels: src_info.and_then(|i| i.channels),
+        conversion: ConversionSettings {
+            converted,
+            sample_rate: dst_info.and_then(|i| i.sample_rate),
+            bit_depth: dst_info.and_then(|i| i.bit_rate),
+            channels: dst_info.and_then(|i| i.channels),
+        },
+        imported_at: chrono::Local::now().to_rfc3339(),
+    }
+}
+
+/// Record that `source` was imported as `dest` in the database at `db_path`,
+/// and index `dest` itself so it can be searched right away.
+pub fn record_import_in(db_path: &Path, source: &Path, dest: &Path) -> Result<(), String> {
+    let provenance = build_provenance(source, dest);
+    let key = index_key(dest);
+    let conn = open_library(db_path)?;
+    store_provenance(&conn, &key, &provenance)?;
+    pool_index::refresh_file(&conn, Path::new(&key))
+}
+
+/// Best-effort provenance recording in the default database: a failure here
+/// must never fail the import itself, so it is only logged.
+pub fn record_import(source: &Path, dest: &Path) {
+    let result = pool_index::default_db_path().and_then(|p| record_import_in(&p, source, dest));
+    if let Err(e) = result {
+        eprintln!(
+            "[LIBRARY] Could not record provenance for {}: {}",
+            dest.display(),
+            e
+        );
+    }
+}
+
+/// Provenance of `path` from the database at `db_path`, if it was imported
+/// through the app.
+pub fn get_file_provenance_in(
+    db_path: &Path,
+    path: &Path,
+) -> Result<Option<FileProvenance>, String> {
+    provenance_of(&open_library(db_path)?, &index_key(path))
+}
+
+#[tauri::command]
+pub async fn get_file_provenance(path: String) -> Result<Option<FileProvenance>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        get_file_provenance_in(&pool_index::default_db_path()?, Path::new(&path))
+    })
+    .await
+    .unwrap()
+}
+
+// ---------------------------------------------------------------------------
+// Background rebuild
+// ---------------------------------------------------------------------------
+//
+// A rebuild walks the library roots and brings their rows in the pool index
+// database (pool_index.rs) up to date: size, modification time, format,
+// duration and content hash of every audio file. It is incremental: files
+// whose size and mtime match their row are skipped, so resuming an
+// interrupted rebuild only redoes the files it had not reached. While a
+// rebuild runs, its roots are kept in a state file next to the database; the
+// file is removed when the rebuild finishes or is cancelled, so its presence
+// at launch means "interrupted".
+
+/// Files between two progress events (and two throttling pauses).
+const REBUILD_BATCH: usize = 100;
+/// Pause between batches so a rebuild does not starve transfers of disk IO.
+const REBUILD_THROTTLE: Duration = Duration::from_millis(20);
+
+static REBUILD_RUNNING: AtomicBool = AtomicBool::new(false);
+static REBUILD_PAUSED: AtomicBool = AtomicBool::new(false);
+static REBUILD_CANCELLED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RebuildState {
+    pub roots: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RebuildProgress {
+    pub stage: String, // "scanning", "indexing", "paused", "complete", "cancelled", "error"
+    pub processed: usize,
+    pub total: usize,
+    pub current_path: Option<String>,
+}
+
+/// Pause/cancel switches polled by a rebuild.
+pub struct RebuildControl<'a> {
+    pub paused: &'a AtomicBool,
+    pub cancelled: &'a AtomicBool,
+}
+
+fn rebuild_state_path(db_path: &Path) -> PathBuf {
+    db_path.with_file_name("library_rebuild.json")
+}
+
+/// Wait while paused. Returns false once cancelled.
+fn wait_while_paused<P: FnMut(RebuildProgress)>(
+    control: &RebuildControl,
+    progress: &mut P,
+    processed: usize,
+    total: usize,
+) -> bool {
+    if control.paused.load(Ordering::Relaxed) {
+        progress(RebuildProgress {
+            stage: "paused".to_string(),
+            processed,
+            total,
+            current_path: None,
+        });
+        while control.paused.load(Ordering::Relaxed) && !control.cancelled.load(Ordering::Relaxed) {
+            std::thread::sleep(Duration::from_millis(100));
+        }
+    }
+    !control.cancelled.load(Ordering::Relaxed)
+}
+
+/// Rebuild the pool index database at `db_path` for every audio file under
+/// `roots`. Returns the number of files (re)read; a cancelled rebuild returns
+/// "Cancelled".
+pub fn rebuild_index<P: FnMut(RebuildProgress)>(
+    db_path: &Path,
+    roots: &[String],
+    control: &RebuildControl,
+    mut progress: P,
+) -> Result<usize, String> {
+    let state_path = rebuild_state_path(db_path);
+    let state = RebuildState {
+        roots: roots.to_vec(),
+    };
+    if let Some(parent) = state_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create library index directory: {}", e))?;
+    }
+    let data = serde_json::to_string_pretty(&state)
+        .map_err(|e| format!("Failed to serialize rebuild state: {}", e))?;
+    fs::write(&state_path, data).map_err(|e| format!("Failed to write rebuild state: {}", e))?;
+
+    // Only a quit or crash mid-run leaves the state file behind.
+    let result = rebuild_files(db_path, roots, control, &mut progress);
+    let _ = fs::remove_file(&state_path);
+    result
+}
+
+fn rebuild_files<P: FnMut(RebuildProgress)>(
+    db_path: &Path,
+    roots: &[String],
+    control: &RebuildControl,
+    progress: &mut P,
+) -> Result<usize, String> {
+    progress(RebuildProgress {
+        stage: "scanning".to_string(),
+        processed: 0,
+        total: 0,
+        current_path: None,
+    });
+    // Canonical roots, so rows share their keys with the provenance records
+    let mut scanned = Vec::new();
+    for root in roots {
+        let root = PathBuf::from(index_key(Path::new(root)));
+        let files = crate::audio_pool::collect_audio_files_recursive(&root.to_string_lossy())?;
+        scanned.push((root, files));
+    }
+    let total = scanned.iter().map(|(_, files)| files.len()).sum();
+
+    let mut conn = open_library(db_path)?;
+    let mut updated = 0;
+    let mut offset = 0;
+    for (root, files) in &scanned {
+        let synced = pool_index::sync_files(&mut conn, root, files, |i, file| {
+            let processed = offset + i;
+            if !wait_while_paused(control, progress, processed, total) {
+                return false;
+            }
+            if processed > 0 && processed % REBUILD_BATCH == 0 {
+                progress(RebuildProgress {
+                    stage: "indexing".to_string(),
+                    processed,
+                    total,
+                    current_path: Some(file.to_string()),
+                });
+                std::thread::sleep(REBUILD_THROTTLE);
+            }
+            true
+        });
+        match synced {
+            Ok(stats) => updated += stats.added + stats.updated,
+            Err(e) => {
+                if e == "Cancelled" {
+                    progress(RebuildProgress {
+                        stage: "cancelled".to_string(),
+                        processed: offset,
+                        total,
+                        current_path: None,
+                    });
+                }
+                return Err(e);
+            }
+        }
+        offset += files.len();
+    }
+
+    progress(RebuildProgress {
+        stage: "complete".to_string(),
+        processed: total,
+        total,
+        current_path: None,
+    });
+    Ok(updated)
+}
+
+/// Roots of a rebuild that was interrupted (app quit or crash), if any.
+pub fn interrupted_rebuild(db_path: &Path) -> Option<RebuildState> {
+    let data = fs::read_to_string(rebuild_state_path(db_path)).ok()?;
+    serde_json::from_str(&data).ok()
+}
+
+/// Run a rebuild of the default database on a background thread, emitting
+/// "library-index-progress" events. Only one rebuild runs at a time.
+fn spawn_rebuild(app: AppHandle, roots: Vec<String>) -> Result<(), String> {
+    let db_path = pool_index::default_db_path()?;
+    if REBUILD_RUNNING.swap(true, Ordering::SeqCst) {
+        return Err("A library index rebuild is already running".to_string());
+    }
+    REBUILD_PAUSED.store(false, Ordering::SeqCst);
+    REBUILD_CANCELLED.store(false, Ordering::SeqCst);
+
+    std::thread::spawn(move || {
+        let control = RebuildControl {
+            paused: &REBUILD_PAUSED,
+            cancelled: &REBUILD_CANCELLED,
+        };
+        let result = rebuild_index(&db_path, &roots, &control, |p| {
+            let _ = app.emit("library-index-progress", p);
+        });
+        if let Err(e) = result {
+            if e != "Cancelled" {
+                eprintln!("[LIBRARY] Index rebuild failed: {}", e);
+                let _ = app.emit(
+                    "library-index-progress",
+                    RebuildProgress {
+                        stage: "error".to_string(),
+                        processed: 0,
+                        total: 0,
+                        current_path: Some(e),
+                    },
+                );
+            }
+        }
+        REBUILD_RUNNING.store(false, Ordering::SeqCst);
+    });
+    Ok(())
+}
+
+/// Called at launch: pick up a rebuild the previous session did not finish.
+pub fn resume_interrupted_rebuild(app: AppHandle) {
+    let Ok(db_path) = pool_index::default_db_path() else {
+        return;
+    };
+    if let Some(state) = interrupted_rebuild(&db_path) {
+        if let Err(e) = spawn_rebuild(app, state.roots) {
+            eprintln!("[LIBRARY] Could not resume index rebuild: {}", e);
+        }
+    }
+}
+
+#[tauri::command]
+pub fn start_library_rebuild(app: AppHandle, roots: Vec<String>) -> Result<(), String> {
+    spawn_rebuild(app, roots)
+}
+
+#[tauri::command]
+pub fn pause_library_rebuild() {
+    REBUILD_PAUSED.store(true, Ordering::SeqCst);
+}
+
+#[tauri::command]
+pub fn resume_library_rebuild() {
+    REBUILD_PAUSED.store(false, Ordering::SeqCst);
+}
+
+#[tauri::command]
+pub fn cancel_library_rebuild() {
+    REBUILD_CANCELLED.store(true, Ordering::SeqCst);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_record_and_get_provenance() {
+        let dir = TempDir::new().unwrap();
+        let db = dir.path().join("index.sqlite");
+        let source = dir.path().join("loop.mp3");
+        let dest = dir.path().join("loop.wav");
+        fs::write(&source, b"not really an mp3").unwrap();
+        fs::write(&dest, b"RIFF").unwrap();
+
+        record_import_in(&db, &source, &dest).unwrap();
+
+        let provenance = get_file_provenance_in(&db, &dest).unwrap().unwrap();
+        assert_eq!(provenance.original_path, index_key(&source));
+        assert_eq!(provenance.original_format, "MP3");
+        assert!(provenance.conversion.converted);
+        assert!(!provenance.imported_at.is_empty());
+        // The imported file is indexed along with its provenance
+        assert_eq!(indexed(&db)[0].path, index_key(&dest));
+    }
+
+    #[test]
+    fn test_legacy_json_index_is_moved_into_the_database() {
+        let dir = TempDir::new().unwrap();
+        let db = dir.path().join("index.sqlite");
+        let dest = dir.path().join("loop.wav");
+        fs::write(&dest, b"RIFF").unwrap();
+        let legacy = dir.path().join("library_index.json");
+        let key = serde_json::to_string(&index_key(&dest)).unwrap();
+        fs::write(
+            &legacy,
+            format!(
+                r#"{{"files": {{{}: {{"provenance": {{
+                    "original_path": "/samples/loop.mp3", "original_format": "MP3",
+                    "original_sample_rate": 48000, "original_bit_depth": null,
+                    "original_channels": 2,
+                    "conversion": {{"converted": true, "sample_rate": 44100,
+                                    "bit_depth": 16, "channels": 2}},
+                    "imported_at": "2024-01-01T00:00:00+00:00"
+                }}, "audio": null}}}}}}"#,
+                key
+            ),
+        )
+        .unwrap();
+
+        let provenance = get_file_provenance_in(&db, &dest).unwrap().unwrap();
+        assert_eq!(provenance.original_path, "/samples/loop.mp3");
+        assert_eq!(provenance.conversion.sample_rate, Some(44100));
+        assert!(!legacy.exists());
+    }
+
+    #[test]
+    fn test_unknown_file_has_no_provenance() {
+        let dir = TempDir::new().unwrap();
+        let db = dir.path().join("index.sqlite");
+        let result = get_file_provenance_in(&db, &dir.path().join("nope.wav")).unwrap();
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_plain_copy_is_not_marked_converted() {
+        let dir = TempDir::new().unwrap();
+        let db = dir.path().join("index.sqlite");
+        fs::create_dir_all(dir.path().join("pool")).unwrap();
+        let source = dir.path().join("kick.wav");
+        let dest = dir.path().join("pool/kick.wav");
+        fs::write(&source, b"same-bytes").unwrap();
+        fs::write(&dest, b"same-bytes").unwrap();
+
+        record_import_in(&db, &source, &dest).unwrap();
+
+        let provenance = get_file_provenance_in(&db, &dest).unwrap().unwrap();
+        assert!(!provenance.conversion.converted);
+        assert_eq!(provenance.original_format, "WAV");
+    }
+
+    fn run_rebuild(db: &Path, root: &Path, cancelled: bool) -> Result<usize, String> {
+        let paused = AtomicBool::new(false);
+        let cancelled = AtomicBool::new(cancelled);
+        let control = RebuildControl {
+            paused: &paused,
+            cancelled: &cancelled,
+        };
+        rebuild_index(db, &[root.to_string_lossy().to_string()], &control, |_| {})
+    }
+
+    fn indexed(db: &Path) -> Vec<pool_index::IndexedPoolFile> {
+        let conn = pool_index::open_index(db).unwrap();
+        pool_index::search(&conn, &Default::default()).unwrap()
+    }
+
+    #[test]
+    fn test_rebuild_is_incremental() {
+        let dir = TempDir::new().unwrap();
+        let db = dir.path().join("index/index.sqlite");
+        let library = dir.path().join("library");
+        fs::create_dir_all(&library).unwrap();
+        fs::write(library.join("kick.wav"), b"RIFF").unwrap();
+        fs::write(library.join("notes.txt"), b"text").unwrap();
+
+        assert_eq!(run_rebuild(&db, &library, false).unwrap(), 1);
+        let files = indexed(&db);
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, index_key(&library.join("kick.wav")));
+        assert_eq!(files[0].size, 4);
+        // Nothing changed: nothing to re-read, and the run left no state file
+        assert_eq!(run_rebuild(&db, &library, false).unwrap(), 0);
+        assert!(interrupted_rebuild(&db).is_none());
+    }
+
+    #[test]
+    fn test_rebuild_drops_deleted_files() {
+        let dir = TempDir::new().unwrap();
+        let db = dir.path().join("index.sqlite");
+        let library = dir.path().join("library");
+        fs::create_dir_all(&library).unwrap();
+        fs::write(library.join("kick.wav"), b"RIFF").unwrap();
+        run_rebuild(&db, &library, false).unwrap();
+
+        fs::remove_file(library.join("kick.wav")).unwrap();
+        run_rebuild(&db, &library, false).unwrap();
+
+        assert!(indexed(&db).is_empty());
+    }
+
+    #[test]
+    fn test_cancelled_rebuild_clears_state() {
+        let dir = TempDir::new().unwrap();
+        let db = dir.path().join("index.sqlite");
+        let library = dir.path().join("library");
+        fs::create_dir_all(&library).unwrap();
+        fs::write(library.join("kick.wav"), b"RIFF").unwrap();
+
+        assert_eq!(run_rebuild(&db, &library, true).unwrap_err(), "Cancelled");
+        assert!(interrupted_rebuild(&db).is_none());
+        assert!(indexed(&db).is_empty());
+    }
+}
@@ -0,0 +1,557 @@
+// Maintenance: keeps the app's own data stores from growing without bound.
+//
+// Only stores the app itself owns are pruned:
+//   - project backups  (<project>/backups/<timestamp>_<label>/), by age, always
+//                      keeping the newest few per project
+//   - the edit journal (<data dir>/octatrack-manager/edit_journal/<project>/):
+//                      journals of projects not edited for a while, and
+//                      snapshot folders no journal entry refers to
+//   - the stream cache (<temp dir>/octatrack-manager-stream/, samples decoded
+//                      for playback), oldest files first until it fits under
+//                      a size cap
+//   - import staging   (<temp dir>/octatrack-manager-import/), folders left by
+//                      an archive import that never finished
+//
+// Sandboxes are never removed: one left behind may hold edits not applied yet.
+// The WebView's cache is the WebView's and is left alone.
+//
+// Settings and the last run live in `<data dir>/octatrack-manager/maintenance.json`.
+// A background thread reruns maintenance every `interval_hours`.
+
+use chrono::{Local, NaiveDateTime, TimeZone};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use walkdir::WalkDir;
+
+// One maintenance pass at a time (manual run vs scheduled run).
+static RUN_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// Staging folders untouched for this long belong to no running import.
+const STAGING_MAX_AGE_SECS: u64 = 86_400;
+
+/// How often the scheduler wakes up to see whether a run is due.
+const SCHEDULER_TICK: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MaintenanceSettings {
+    pub enabled: bool,                    // scheduled runs; manual runs always work
+    pub interval_hours: u32,              // time between scheduled runs
+    pub backup_max_age_days: Option<u32>, // None = keep backups forever
+    pub backup_keep_min: u32,             // newest backups per project never pruned
+    #[serde(default = "default_journal_max_age_days")]
+    pub journal_max_age_days: Option<u32>, // None = keep undo history forever
+    pub cache_max_mb: Option<u64>,        // stream cache cap; None = no cap
+    #[serde(default = "default_write_backup_keep")]
+    pub write_backup_keep: u32, // bank copies kept per file in .otm-backups/; 0 = off
+    #[serde(default = "default_verify_writes")]
+    pub verify_writes: bool, // re-read part writes and roll back when they don't match
+}
+
+fn default_journal_max_age_days() -> Option<u32> {
+    Some(30)
+}
+
+fn default_write_backup_keep() -> u32 {
+    10
+}
+
+fn default_verify_writes() -> bool {
+    true
+}
+
+impl Default for MaintenanceSettings {
+    fn default() -> Self {
+        MaintenanceSettings {
+            enabled: true,
+            interval_hours: 24,
+            backup_max_age_days: Some(90),
+            backup_keep_min: 5,
+            journal_max_age_days: default_journal_max_age_days(),
+            cache_max_mb: Some(500),
+            write_backup_keep: default_write_backup_keep(),
+            verify_writes: default_verify_writes(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct MaintenanceStore {
+    #[serde(default)]
+    settings: MaintenanceSettings,
+    #[serde(default)]
+    last_run: Option<u64>, // seconds since the Unix epoch
+    #[serde(default)]
+    projects: Vec<String>, // projects whose backups scheduled runs prune
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RemovedItem {
+    pub store: String, // "backup", "journal", "cache", "staging"
+    pub path: String,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MaintenanceReport {
+    pub dry_run: bool,
+    pub removed: Vec<RemovedItem>,
+    pub freed_bytes: u64,
+    pub errors: Vec<String>,
+}
+
+/// Where pruning happens; tests point these at temp dirs.
+struct Stores {
+    journal_root: Option<PathBuf>,
+    cache_dir: PathBuf,
+    staging_root: PathBuf,
+}
+
+impl Stores {
+    fn default_locations() -> Self {
+        Stores {
+            journal_root: crate::edit_journal::default_journal_root().ok(),
+            cache_dir: crate::audio_stream::transcode_dir(),
+            staging_root: crate::archive_import::staging_root(),
+        }
+    }
+}
+
+/// Default store location: `<data dir>/octatrack-manager/maintenance.json`.
+pub fn default_store_path() -> Result<PathBuf, String> {
+    crate::app_data::data_path("maintenance.json")
+}
+
+fn load_store(store_path: &Path) -> Result<MaintenanceStore, String> {
+    if !store_path.exists() {
+        return Ok(MaintenanceStore::default());
+    }
+    let data = fs::read_to_string(store_path)
+        .map_err(|e| format!("Failed to read maintenance settings: {}", e))?;
+    serde_json::from_str(&data).map_err(|e| format!("Failed to parse maintenance settings: {}", e))
+}
+
+fn save_store(store_path: &Path, store: &MaintenanceStore) -> Result<(), String> {
+    crate::app_data::save_json(store_path, store, "maintenance settings")
+}
+
+/// Saved settings, or the defaults when the store is missing or unreadable.
+pub fn current_settings() -> MaintenanceSettings {
+    default_store_path()
+        .and_then(|p| load_store(&p))
+        .map(|s| s.settings)
+        .unwrap_or_default()
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn modified_secs(path: &Path) -> u64 {
+    fs::symlink_metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Total size of the regular files under `path` (symlinks are not followed).
+fn dir_size(path: &Path) -> u64 {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+/// Creation time of a backup: the `YYYY-MM-DD_HH-MM-SS` prefix of its folder
+/// name, or the folder's modification time for folders not made by the app.
+fn backup_time(dir: &Path) -> u64 {
+    let name = dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    name.get(..19)
+        .and_then(|stamp| NaiveDateTime::parse_from_str(stamp, "%Y-%m-%d_%H-%M-%S").ok())
+        .and_then(|naive| Local.from_local_datetime(&naive).earliest())
+        .map(|dt| dt.timestamp().max(0) as u64)
+        .unwrap_or_else(|| modified_secs(dir))
+}
+
+fn remove(report: &mut MaintenanceReport, store: &str, path: &Path, bytes: u64) {
+    if !report.dry_run {
+        let result = if path.is_dir() && !path.is_symlink() {
+            fs::remove_dir_all(path)
+        } else {
+            fs::remove_file(path)
+        };
+        if let Err(e) = result {
+            report
+                .errors
+                .push(format!("Failed to remove {}: {}", path.display(), e));
+            return;
+        }
+    }
+    report.freed_bytes += bytes;
+    report.removed.push(RemovedItem {
+        store: store.to_string(),
+        path: path.to_string_lossy().to_string(),
+        bytes,
+    });
+}
+
+fn prune_backups(
+    project_path: &str,
+    settings: &MaintenanceSettings,
+    now: u64,
+    report: &mut MaintenanceReport,
+) {
+    let Some(max_age_days) = settings.backup_max_age_days else {
+        return;
+    };
+    let Ok(entries) = fs::read_dir(Path::new(project_path).join("backups")) else {
+        return;
+    };
+    let mut backups: Vec<(u64, PathBuf)> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_dir() && !p.is_symlink())
+        .map(|p| (backup_time(&p), p))
+        .collect();
+    backups.sort_by(|a, b| b.0.cmp(&a.0)); // newest first
+    let cutoff = now.saturating_sub(max_age_days as u64 * 86_400);
+    for (time, dir) in backups.into_iter().skip(settings.backup_keep_min as usize) {
+        if time < cutoff {
+            let bytes = dir_size(&dir);
+            remove(report, "backup", &dir, bytes);
+        }
+    }
+}
+
+/// Newest modification time of the files under `dir` (or of `dir` itself).
+fn last_activity(dir: &Path) -> u64 {
+    WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .map(|e| modified_secs(e.path()))
+        .max()
+        .unwrap_or_else(|| modified_secs(dir))
+}
+
+fn subdirs(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_dir() && !p.is_symlink())
+        .collect()
+}
+
+fn prune_journals(
+    root: &Path,
+    settings: &MaintenanceSettings,
+    now: u64,
+    report: &mut MaintenanceReport,
+) {
+    crate::edit_journal::with_journal_lock(|| {
+        for dir in subdirs(root) {
+            // A journal is rewritten by every edit, undo and redo
+            let journal = dir.join("journal.json");
+            let stale = settings.journal_max_age_days.is_some_and(|days| {
+                modified_secs(&journal) < now.saturating_sub(days as u64 * 86_400)
+            });
+            if stale || !journal.exists() {
+                let bytes = dir_size(&dir);
+                remove(report, "journal", &dir, bytes);
+                continue;
+            }
+            let ids = match crate::edit_journal::journal_entry_ids(&dir) {
+                Ok(ids) => ids,
+                Err(e) => {
+                    report.errors.push(format!("{}: {}", dir.display(), e));
+                    continue;
+                }
+            };
+            for snapshot in subdirs(&dir) {
+                let id = snapshot
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .and_then(|n| n.parse::<u64>().ok());
+                if !id.is_some_and(|id| ids.contains(&id)) {
+                    let bytes = dir_size(&snapshot);
+                    remove(report, "journal", &snapshot, bytes);
+                }
+            }
+        }
+    })
+}
+
+fn prune_staging(root: &Path, now: u64, report: &mut MaintenanceReport) {
+    let own = format!("{}-", std::process::id());
+    for dir in subdirs(root) {
+        let name = dir.file_name().unwrap_or_default().to_string_lossy();
+        if name.starts_with(&own) {
+            continue; // an import of this run, removed when it ends
+        }
+        if last_activity(&dir) < now.saturating_sub(STAGING_MAX_AGE_SECS) {
+            let bytes = dir_size(&dir);
+            remove(report, "staging", &dir, bytes);
+        }
+    }
+}
+
+fn prune_cache(cache_dir: &Path, settings: &MaintenanceSettings, report: &mut MaintenanceReport) {
+    let Some(max_mb) = settings.cache_max_mb else {
+        return;
+    };
+    let mut files: Vec<(u64, u64, PathBuf)> = WalkDir::new(cache_dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| {
+            let len = e.metadata().ok()?.len();
+            Some((modified_secs(e.path()), len, e.into_path()))
+        })
+        .collect();
+    let mut total: u64 = files.iter().map(|(_, len, _)| len).sum();
+    let cap = max_mb * 1024 * 1024;
+    files.sort(); // oldest first
+    for (_, len, file) in files {
+        if total <= cap {
+            break;
+        }
+        let errors = report.errors.len();
+        remove(report, "cache", &file, len);
+        if report.errors.len() == errors {
+            total -= len;
+        }
+    }
+}
+
+fn run_with(
+    stores: &Stores,
+    settings: &MaintenanceSettings,
+    projects: &[String],
+    dry_run: bool,
+) -> MaintenanceReport {
+    let _guard = RUN_LOCK.lock().unwrap();
+    let now = now_secs();
+    let mut report = MaintenanceReport {
+        dry_run,
+        ..Default::default()
+    };
+    for project in projects {
+        prune_backups(project, settings, now, &mut report);
+    }
+    if let Some(journal_root) = &stores.journal_root {
+        prune_journals(journal_root, settings, now, &mut report);
+    }
+    prune_cache(&stores.cache_dir, settings, &mut report);
+    prune_staging(&stores.staging_root, now, &mut report);
+    report
+}
+
+/// Run maintenance with the saved settings. `project_paths` replaces the list
+/// of projects whose backups are pruned (None = the list from the last run).
+pub fn run_maintenance_in(
+    store_path: &Path,
+    project_paths: Option<Vec<String>>,
+    dry_run: bool,
+) -> Result<MaintenanceReport, String> {
+    let mut store = load_store(store_path)?;
+    if let Some(paths) = project_paths {
+        store.projects = paths;
+    }
+    let report = run_with(
+        &Stores::default_locations(),
+        &store.settings,
+        &store.projects,
+        dry_run,
+    );
+    if !dry_run {
+        store.last_run = Some(now_secs());
+        save_store(store_path, &store)?;
+    }
+    Ok(report)
+}
+
+/// Start the background thread that runs maintenance when it is due.
+pub fn start_scheduler() {
+    std::thread::spawn(|| loop {
+        if let Ok(store_path) = default_store_path() {
+            if let Ok(store) = load_store(&store_path) {
+                let interval = store.settings.interval_hours.max(1) as u64 * 3600;
+                let due = !matches!(store.last_run,
+                    Some(last) if now_secs().saturating_sub(last) < interval);
+                if store.settings.enabled && due {
+                    if let Err(e) = run_maintenance_in(&store_path, None, false) {
+                        eprintln!("Scheduled maintenance failed: {}", e);
+                    }
+                }
+            }
+        }
+        std::thread::sleep(SCHEDULER_TICK);
+    });
+}
+
+#[tauri::command]
+pub async fn get_maintenance_settings() -> Result<MaintenanceSettings, String> {
+    tauri::async_runtime::spawn_blocking(move || Ok(load_store(&default_store_path()?)?.settings))
+        .await
+        .unwrap()
+}
+
+#[tauri::command]
+pub async fn set_maintenance_settings(settings: MaintenanceSettings) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let store_path = default_store_path()?;
+        let mut store = load_store(&store_path)?;
+        store.settings = settings;
+        save_store(&store_path, &store)
+    })
+    .await
+    .unwrap()
+}
+
+#[tauri::command]
+pub async fn run_maintenance(
+    project_paths: Option<Vec<String>>,
+    dry_run: bool,
+) -> Result<MaintenanceReport, String> {
+    if let Some(paths) = project_paths.as_ref().filter(|_| !dry_run) {
+        crate::fs_scope::ensure_all_allowed(paths)?;
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        run_maintenance_in(&default_store_path()?, project_paths, dry_run)
+    })
+    .await
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn settings() -> MaintenanceSettings {
+        MaintenanceSettings {
+            backup_max_age_days: Some(30),
+            backup_keep_min: 1,
+            journal_max_age_days: Some(30),
+            cache_max_mb: Some(1),
+            ..Default::default()
+        }
+    }
+
+    fn set_age(path: &Path, days: u64) {
+        let time = SystemTime::now() - Duration::from_secs(days * 86_400);
+        fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(time)
+            .unwrap();
+    }
+
+    fn stores(temp: &TempDir) -> Stores {
+        Stores {
+            journal_root: Some(temp.path().join("journal")),
+            cache_dir: temp.path().join("cache"),
+            staging_root: temp.path().join("staging"),
+        }
+    }
+
+    #[test]
+    fn test_old_backups_pruned_keeping_newest() {
+        let temp = TempDir::new().unwrap();
+        let project = temp.path().join("PROJ");
+        let stamp = |days: i64| {
+            (Local::now() - chrono::Duration::days(days))
+                .format("%Y-%m-%d_%H-%M-%S")
+                .to_string()
+        };
+        for (days, label) in [(100, "a"), (60, "b"), (45, "c"), (2, "d")] {
+            let dir = project
+                .join("backups")
+                .join(format!("{}_{}", stamp(days), label));
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("bank01.work"), b"bank").unwrap();
+        }
+        let projects = vec![project.to_string_lossy().to_string()];
+
+        let preview = run_with(&stores(&temp), &settings(), &projects, true);
+        assert_eq!(preview.removed.len(), 3);
+        assert_eq!(preview.freed_bytes, 12);
+        assert_eq!(fs::read_dir(project.join("backups")).unwrap().count(), 4);
+
+        let mut keep_two = settings();
+        keep_two.backup_keep_min = 2;
+        let report = run_with(&stores(&temp), &keep_two, &projects, false);
+        assert_eq!(report.removed.len(), 2);
+        let mut left: Vec<_> = fs::read_dir(project.join("backups"))
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        left.sort();
+        assert!(left[0].ends_with("_c") && left[1].ends_with("_d"));
+    }
+
+    #[test]
+    fn test_stale_journals_staging_and_cache_overflow_pruned() {
+        let temp = TempDir::new().unwrap();
+        let journal = temp.path().join("journal");
+        for (name, days) in [("stale", 40), ("active", 1)] {
+            let dir = journal.join(name);
+            fs::create_dir_all(dir.join("0").join("before")).unwrap();
+            fs::create_dir_all(dir.join("7").join("before")).unwrap();
+            fs::write(dir.join("0").join("before").join("bank01.work"), b"b").unwrap();
+            fs::write(
+                dir.join("journal.json"),
+                r#"{"entries":[{"id":0,"label":"x","timestamp":"","files":[]}],"applied":1}"#,
+            )
+            .unwrap();
+            set_age(&dir.join("journal.json"), days);
+        }
+        let staging = temp.path().join("staging");
+        for (name, days) in [("1-crashed", 3), ("2-running", 0)] {
+            fs::create_dir_all(staging.join(name)).unwrap();
+            fs::write(staging.join(name).join("kick.wav"), b"RIFF").unwrap();
+            set_age(&staging.join(name).join("kick.wav"), days);
+        }
+        let cache = temp.path().join("cache");
+        fs::create_dir_all(&cache).unwrap();
+        let mb = vec![0u8; 600 * 1024];
+        for (name, days) in [("old.wav", 3), ("new.wav", 0)] {
+            fs::write(cache.join(name), &mb).unwrap();
+            set_age(&cache.join(name), days);
+        }
+
+        let report = run_with(&stores(&temp), &settings(), &[], false);
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        assert!(!journal.join("stale").exists());
+        assert!(journal.join("active").join("0").exists());
+        assert!(
+            !journal.join("active").join("7").exists(),
+            "snapshot without an entry"
+        );
+        assert!(!staging.join("1-crashed").exists());
+        assert!(staging.join("2-running").exists());
+        assert!(!cache.join("old.wav").exists());
+        assert!(cache.join("new.wav").exists());
+        let mut kinds: Vec<_> = report.removed.iter().map(|r| r.store.as_str()).collect();
+        kinds.dedup();
+        assert_eq!(kinds, vec!["journal", "cache", "staging"]);
+    }
+}
@@ -0,0 +1,344 @@
+// In-memory cache of decoded project metadata and banks, so switching back to
+// a project or re-opening a bank doesn't re-parse its files and re-probe every
+// sample slot.
+//
+// Every write the app makes through `atomic_write` drops the entries of the
+// project written to (or the whole cache, for files outside a project), so
+// the app's own edits are always seen. Changes made elsewhere (on the device,
+// by another tool) are caught by stamps: an entry is reused only while the
+// files it was decoded from keep the same modification time and size.
+// Besides the project, markers and bank files that covers the project folder,
+// every folder of its Set's Audio Pool (adding or removing samples changes
+// their mtime) and the notes store (color labels).
+//
+// FAT32 stores mtimes to 2 seconds and bank files always have the same size,
+// so a file written within 2 seconds of being stamped could change again
+// without its stamp changing. Such entries are not reused, as git does with
+// "racily clean" index entries. A sample rewritten in place by another tool
+// under the same name is not noticed; clearing the cache forces a fresh read.
+
+use crate::project_reader::{
+    read_project_banks, read_project_metadata, read_single_bank, Bank, ProjectMetadata, ReadResult,
+};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use walkdir::WalkDir;
+
+/// Entries kept per cache before it is emptied and refilled.
+const MAX_ENTRIES: usize = 64;
+
+/// Coarsest modification time resolution of the filesystems in use (FAT32).
+const MTIME_RESOLUTION: Duration = Duration::from_secs(2);
+
+/// Modification time and size of a file, both None when it doesn't exist.
+#[derive(Debug, Clone, PartialEq)]
+struct FileStamp {
+    modified: Option<SystemTime>,
+    size: Option<u64>,
+}
+
+fn stamp(paths: &[PathBuf]) -> Vec<FileStamp> {
+    paths
+        .iter()
+        .map(|p| {
+            let meta = std::fs::metadata(p).ok();
+            FileStamp {
+                modified: meta.as_ref().and_then(|m| m.modified().ok()),
+                size: meta.map(|m| m.len()),
+            }
+        })
+        .collect()
+}
+
+/// True when one of `stamps` is too recent to tell a later write apart.
+fn is_racy(stamps: &[FileStamp], stamped_at: SystemTime) -> bool {
+    let Some(threshold) = stamped_at.checked_sub(MTIME_RESOLUTION) else {
+        return true;
+    };
+    stamps
+        .iter()
+        .filter_map(|s| s.modified)
+        .any(|modified| modified >= threshold)
+}
+
+struct StampedCache<T> {
+    entries: HashMap<String, (Vec<FileStamp>, T)>,
+}
+
+impl<T: Clone> StampedCache<T> {
+    fn new() -> Self {
+        StampedCache {
+            entries: HashMap::new(),
+        }
+    }
+}
+
+static METADATA_CACHE: Lazy<Mutex<StampedCache<ReadResult<ProjectMetadata>>>> =
+    Lazy::new(|| Mutex::new(StampedCache::new()));
+static BANK_CACHE: Lazy<Mutex<StampedCache<Option<Bank>>>> =
+    Lazy::new(|| Mutex::new(StampedCache::new()));
+static BANKS_CACHE: Lazy<Mutex<StampedCache<ReadResult<Vec<Bank>>>>> =
+    Lazy::new(|| Mutex::new(StampedCache::new()));
+
+/// Cached value under `key` if `paths` are unchanged since it was stored,
+/// otherwise `load` it and store it. The lock is not held while loading.
+fn get_or_load<T: Clone>(
+    cache: &Mutex<StampedCache<T>>,
+    key: &str,
+    paths: &[PathBuf],
+    load: impl FnOnce() -> Result<T, String>,
+) -> Result<T, String> {
+    // Stamped before loading: a write during the load leaves a stale stamp,
+    // so the next read loads again instead of keeping outdated data.
+    let stamped_at = SystemTime::now();
+    let current = stamp(paths);
+    {
+        let cache = cache.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((stored, value)) = cache.entries.get(key) {
+            if *stored == current {
+                return Ok(value.clone());
+            }
+        }
+    }
+
+    let value = load()?;
+    if is_racy(&current, stamped_at) {
+        return Ok(value);
+    }
+    let mut cache = cache.lock().unwrap_or_else(|e| e.into_inner());
+    if cache.entries.len() >= MAX_ENTRIES {
+        cache.entries.clear();
+    }
+    cache
+        .entries
+        .insert(key.to_string(), (current, value.clone()));
+    Ok(value)
+}
+
+fn project_key(project_path: &str) -> String {
+    let path = Path::new(project_path);
+    path.canonicalize()
+        .unwrap_or_else(|_| path.to_path_buf())
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Files whose change invalidates anything decoded from the project.
+fn project_inputs(project_path: &str) -> Vec<PathBuf> {
+    let dir = Path::new(project_path);
+    let mut paths = vec![dir.to_path_buf()];
+    if let Some(set_dir) = dir.parent() {
+        let pool = set_dir.join("AUDIO");
+        paths.push(pool.clone());
+        paths.extend(
+            WalkDir::new(&pool)
+                .min_depth(1)
+                .into_iter()
+                .filter_entry(|e| e.file_type().is_dir())
+                .flatten()
+                .map(|e| e.into_path()),
+        );
+    }
+    for name in [
+        "project.work",
+        "project.strd",
+        "markers.work",
+        "markers.strd",
+    ] {
+        paths.push(dir.join(name));
+    }
+    if let Ok(notes) = crate::project_notes::default_notes_path() {
+        paths.push(notes);
+    }
+    paths
+}
+
+fn bank_inputs(project_path: &str, bank_index: u8) -> Vec<PathBuf> {
+    let dir = Path::new(project_path);
+    vec![
+        dir.join(format!("bank{:02}.work", bank_index as usize + 1)),
+        dir.join(format!("bank{:02}.strd", bank_index as usize + 1)),
+    ]
+}
+
+fn all_inputs(project_path: &str) -> Vec<PathBuf> {
+    let mut paths = project_inputs(project_path);
+    for bank_index in 0..16 {
+        paths.extend(bank_inputs(project_path, bank_index));
+    }
+    paths
+}
+
+/// [`read_project_metadata`] through the cache.
+pub fn cached_project_metadata(project_path: &str) -> Result<ReadResult<ProjectMetadata>, String> {
+    // The metadata includes the current pattern's length, from its bank file.
+    get_or_load(
+        &METADATA_CACHE,
+        &project_key(project_path),
+        &all_inputs(project_path),
+        || read_project_metadata(project_path),
+    )
+}
+
+/// [`read_project_banks`] through the cache.
+pub fn cached_project_banks(project_path: &str) -> Result<ReadResult<Vec<Bank>>, String> {
+    get_or_load(
+        &BANKS_CACHE,
+        &project_key(project_path),
+        &all_inputs(project_path),
+        || read_project_banks(project_path),
+    )
+}
+
+/// [`read_single_bank`] through the cache.
+pub fn cached_single_bank(project_path: &str, bank_index: u8) -> Result<Option<Bank>, String> {
+    let mut paths = project_inputs(project_path);
+    paths.extend(bank_inputs(project_path, bank_index));
+    let key = format!("{}#{}", project_key(project_path), bank_index);
+    get_or_load(&BANK_CACHE, &key, &paths, || {
+        read_single_bank(project_path, bank_index)
+    })
+}
+
+/// Remove the entries of the project keyed `key` (its banks included).
+fn drop_project<T>(cache: &Mutex<StampedCache<T>>, key: &str) {
+    let bank_prefix = format!("{}#", key);
+    cache
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .entries
+        .retain(|k, _| k != key && !k.starts_with(&bank_prefix));
+}
+
+/// Forget what was decoded from the project `path` was written into; a file
+/// outside a project (a sample, a sidecar, the notes store) may be used by
+/// any project, so everything is dropped.
+pub fn invalidate_written(path: &Path) {
+    let Some(dir) = path.parent() else {
+        return;
+    };
+    if !dir.join("project.work").exists() && !dir.join("project.strd").exists() {
+        clear();
+        return;
+    }
+    let key = project_key(&dir.to_string_lossy());
+    drop_project(&METADATA_CACHE, &key);
+    drop_project(&BANK_CACHE, &key);
+    drop_project(&BANKS_CACHE, &key);
+}
+
+/// Drop every cached entry.
+pub fn clear() {
+    METADATA_CACHE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .entries
+        .clear();
+    BANK_CACHE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .entries
+        .clear();
+    BANKS_CACHE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .entries
+        .clear();
+}
+
+#[tauri::command]
+pub fn clear_metadata_cache() {
+    clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn write_old(path: &Path, data: &[u8]) {
+        fs::write(path, data).unwrap();
+        let file = fs::File::options().write(true).open(path).unwrap();
+        file.set_modified(SystemTime::now() - Duration::from_secs(60))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_reuses_value_until_inputs_change() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("bank01.work");
+        write_old(&file, b"v1");
+        let cache = Mutex::new(StampedCache::new());
+        let loads = Cell::new(0);
+        let load = || {
+            loads.set(loads.get() + 1);
+            Ok(fs::read(&file).unwrap())
+        };
+
+        let paths = vec![file.clone(), dir.path().join("missing.work")];
+        assert_eq!(get_or_load(&cache, "k", &paths, load).unwrap(), b"v1");
+        assert_eq!(get_or_load(&cache, "k", &paths, load).unwrap(), b"v1");
+        assert_eq!(loads.get(), 1);
+
+        // Size change invalidates even with the same mtime
+        write_old(&file, b"v2 longer");
+        assert_eq!(
+            get_or_load(&cache, "k", &paths, load).unwrap(),
+            b"v2 longer"
+        );
+        assert_eq!(loads.get(), 2);
+
+        // A file appearing also counts as a change
+        write_old(&dir.path().join("missing.work"), b"");
+        get_or_load(&cache, "k", &paths, load).unwrap();
+        assert_eq!(loads.get(), 3);
+    }
+
+    #[test]
+    fn test_recently_written_files_are_not_trusted() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("bank01.work");
+        fs::write(&file, b"v1").unwrap();
+        let cache = Mutex::new(StampedCache::new());
+        let loads = Cell::new(0);
+        let load = || {
+            loads.set(loads.get() + 1);
+            Ok(fs::read(&file).unwrap())
+        };
+
+        // Written just now: a same-size rewrite could keep the stamp
+        let paths = vec![file.clone()];
+        get_or_load(&cache, "k", &paths, load).unwrap();
+        get_or_load(&cache, "k", &paths, load).unwrap();
+        assert_eq!(loads.get(), 2);
+
+        write_old(&file, b"v1");
+        get_or_load(&cache, "k", &paths, load).unwrap();
+        get_or_load(&cache, "k", &paths, load).unwrap();
+        assert_eq!(loads.get(), 3);
+    }
+
+    #[test]
+    fn test_drop_project_keeps_other_projects() {
+        let cache = Mutex::new(StampedCache::new());
+        for key in ["/a/P1", "/a/P1#3", "/a/P10", "/a/P2#0"] {
+            get_or_load(&cache, key, &[], || Ok(1)).unwrap();
+        }
+        drop_project(&cache, "/a/P1");
+        let mut left: Vec<_> = cache.lock().unwrap().entries.keys().cloned().collect();
+        left.sort();
+        assert_eq!(left, vec!["/a/P10", "/a/P2#0"]);
+    }
+
+    #[test]
+    fn test_failed_loads_are_not_cached() {
+        let cache: Mutex<StampedCache<u8>> = Mutex::new(StampedCache::new());
+        assert!(get_or_load(&cache, "k", &[], || Err("boom".to_string())).is_err());
+        assert_eq!(get_or_load(&cache, "k", &[], || Ok(7)).unwrap(), 7);
+    }
+}
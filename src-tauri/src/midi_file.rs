@@ -0,0 +1,746 @@
+// Standard MIDI File export of a pattern's MIDI tracks, to continue sketches in
+// a DAW.
+//
+// The file is format 1 at 96 PPQ: a conductor track carrying the project
+// tempo, then one track per OT MIDI track that has trigger trigs. Each trig
+// plays the track's NOTE (or its lock) plus the NOT2-NOT4 chord offsets, with
+// the locked or part VEL and LEN, shifted by micro-timing and swing. Trig
+// conditions and repeats are not rendered: every trigger trig plays once, over
+// one pass of the pattern (in per-track mode, one pass of each track).
+//
+// Import is the inverse: the notes of an SMF are quantized to the target
+// track's step grid, the remainder kept as micro-timing, and the notes landing
+// on one step become a chord trig (up to four notes) with NOTE/VEL/LEN locks.
+
+use crate::project_reader::{
+    decode_trig_masks, edit_bank_file, encode_trig_masks, read_project_metadata,
+};
+use ot_tools_io::BankFile;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Ticks per quarter note; a 1x step (1/16) is PPQ / 4 ticks.
+const PPQ: u16 = 96;
+
+/// Step duration relative to 1x for scale codes 0-6 (2x, 3/2x, 1x, 3/4x, 1/2x, 1/4x, 1/8x).
+pub(crate) const SCALE_STEP_FACTORS: [f64; 7] = [0.5, 2.0 / 3.0, 1.0, 4.0 / 3.0, 2.0, 4.0, 8.0];
+
+/// LEN value meaning "infinite": the note is held to the end of the track.
+const LEN_INF: u8 = 127;
+
+/// No offset for NOT2-NOT4 (stored as 64 + semitone offset).
+const CHORD_CENTER: u8 = 64;
+
+/// Note length in steps for a LEN value: 1/8 step at 0, growing in ranges of
+/// 16 values whose resolution halves each time, up to 128 steps at 126.
+fn len_steps(len: u8) -> f64 {
+    match len {
+        0..=30 => 0.125 + len as f64 * 0.0625,
+        31..=126 => {
+            let range = (len - 31) / 16; // 0 = 2..4 steps, 1 = 4..8, ...
+            let base = 2.0 * 2f64.powi(range as i32);
+            base + ((len - 31) % 16 + 1) as f64 * base / 16.0
+        }
+        _ => f64::INFINITY,
+    }
+}
+
+/// Signed micro-timing offset in 1/24 step: the low 5 bits of byte 0 and the
+/// top bit of byte 1 form a 6-bit two's complement value.
+pub(crate) fn micro_timing(bytes: [u8; 2]) -> i32 {
+    let value = (((bytes[0] & 0x1F) as i32) << 1) | (bytes[1] >> 7) as i32;
+    if value >= 32 {
+        value - 64
+    } else {
+        value
+    }
+}
+
+fn write_vlq(out: &mut Vec<u8>, mut value: u32) {
+    let mut bytes = vec![(value & 0x7F) as u8];
+    value >>= 7;
+    while value > 0 {
+        bytes.push((value & 0x7F) as u8 | 0x80);
+        value >>= 7;
+    }
+    out.extend(bytes.iter().rev());
+}
+
+/// A timed MIDI event. Note offs sort before note ons at the same tick.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct Event {
+    tick: u32,
+    order: u8, // 0 = note off, 1 = note on
+    data: Vec<u8>,
+}
+
+fn track_chunk(name: &str, mut events: Vec<Event>, end_tick: u32) -> Vec<u8> {
+    events.sort();
+    let mut body = Vec::new();
+    write_vlq(&mut body, 0);
+    body.extend([0xFF, 0x03, name.len() as u8]);
+    body.extend(name.as_bytes());
+    let mut last = 0;
+    for event in &events {
+        write_vlq(&mut body, event.tick - last);
+        body.extend(&event.data);
+        last = event.tick;
+    }
+    write_vlq(&mut body, end_tick.saturating_sub(last));
+    body.extend([0xFF, 0x2F, 0x00]);
+
+    let mut chunk = b"MTrk".to_vec();
+    chunk.extend((body.len() as u32).to_be_bytes());
+    chunk.extend(body);
+    chunk
+}
+
+/// Note events of MIDI track `t` (0-7) and the tick its pass ends on.
+fn midi_track_events(bank: &BankFile, pattern_idx: usize, t: usize) -> (Vec<Event>, u32) {
+    let pattern = &bank.patterns.0[pattern_idx];
+    let track = &pattern.midi_track_trigs.0[t];
+    let part = &bank.parts.unsaved[pattern.part_assignment.min(3) as usize];
+    let defaults = &part.midi_track_params_values[t].midi;
+    let channel = part.midi_track_params_setup[t].note.chan.min(15);
+
+    let (len, scale) = if pattern.scale.scale_mode == 1 {
+        (
+            track.scale_per_track_mode.per_track_len,
+            track.scale_per_track_mode.per_track_scale,
+        )
+    } else {
+        (pattern.scale.master_len, pattern.scale.master_scale)
+    };
+    let len = len.clamp(1, 64) as usize;
+    let step_ticks = (PPQ / 4) as f64 * SCALE_STEP_FACTORS[(scale as usize).min(6)];
+    let end_tick = (len as f64 * step_ticks).round() as u32;
+
+    let triggers = decode_trig_masks(&track.trig_masks.trigger);
+    let swings = decode_trig_masks(&track.trig_masks.swing);
+    let mut events = Vec::new();
+    for step in (0..len).filter(|&s| triggers[s]) {
+        let plock = &track.plocks[step];
+        let locked = |value: u8, default: u8| if value != 255 { value } else { default };
+        let base = locked(plock.midi.note, defaults.note).min(127);
+        let velocity = locked(plock.midi.vel, defaults.vel).min(127);
+        let note_len = locked(plock.midi.len, defaults.len);
+
+        let mut notes = vec![base];
+        for (lock, default) in [
+            (plock.midi.not2, defaults.not2),
+            (plock.midi.not3, defaults.not3),
+            (plock.midi.not4, defaults.not4),
+        ] {
+            let offset = locked(lock, default);
+            if offset == CHORD_CENTER || offset > 127 {
+                continue;
+            }
+            let note = (base as i16 + offset as i16 - CHORD_CENTER as i16).clamp(0, 127) as u8;
+            if !notes.contains(&note) {
+                notes.push(note);
+            }
+        }
+
+        let mut start = step as f64 * step_ticks
+            + micro_timing(track.trig_offsets_repeats_conditions[step]) as f64 * step_ticks / 24.0;
+        if step % 2 == 1 && swings[step] {
+            // Swing 50-80%: the off-beat step sits at that fraction of a step pair.
+            start += track.swing_amount as f64 / 100.0 * 2.0 * step_ticks;
+        }
+        let start = start.round().max(0.0) as u32;
+        let stop = if note_len >= LEN_INF {
+            end_tick.max(start + 1)
+        } else {
+            start + ((len_steps(note_len) * step_ticks).round() as u32).max(1)
+        };
+
+        for note in notes {
+            events.push(Event {
+                tick: start,
+                order: 1,
+                data: vec![0x90 | channel, note, velocity],
+            });
+            events.push(Event {
+                tick: stop,
+                order: 0,
+                data: vec![0x80 | channel, note, 0],
+            });
+        }
+    }
+    (events, end_tick)
+}
+
+/// Encode the MIDI tracks of a pattern as a format 1 SMF at `bpm`.
+fn pattern_smf(bank: &BankFile, pattern_idx: usize, bpm: f64) -> Vec<u8> {
+    let tracks: Vec<(usize, Vec<Event>, u32)> = (0..8)
+        .map(|t| {
+            let (events, end) = midi_track_events(bank, pattern_idx, t);
+            (t, events, end)
+        })
+        .filter(|(_, events, _)| !events.is_empty())
+        .collect();
+    let end_tick = tracks
+        .iter()
+        .flat_map(|(_, events, end)| events.iter().map(|e| e.tick).chain([*end]))
+        .max()
+        .unwrap_or(0);
+
+    let mut out = b"MThd".to_vec();
+    out.extend(6u32.to_be_bytes());
+    out.extend(1u16.to_be_bytes()); // format 1
+    out.extend((tracks.len() as u16 + 1).to_be_bytes());
+    out.extend(PPQ.to_be_bytes());
+
+    let tempo = (60_000_000.0 / bpm.clamp(30.0, 300.0)).round() as u32;
+    let conductor = vec![
+        Event {
+            tick: 0,
+            order: 0,
+            data: [&[0xFF, 0x51, 0x03][..], &tempo.to_be_bytes()[1..]].concat(),
+        },
+        Event {
+            tick: 0,
+            order: 1,
+            data: vec![0xFF, 0x58, 0x04, 4, 2, 24, 8], // 4/4
+        },
+    ];
+    out.extend(track_chunk("Tempo", conductor, end_tick));
+    for (t, events, end) in tracks {
+        out.extend(track_chunk(&format!("M{}", t + 1), events, end));
+    }
+    out
+}
+
+pub(crate) fn bank_index(bank_id: &str, pattern_index: u8) -> Result<u8, String> {
+    if pattern_index > 15 {
+        return Err("Pattern index must be between 0 and 15".to_string());
+    }
+    crate::project_reader::bank_index(bank_id)
+}
+
+/// Write the MIDI tracks of pattern `pattern_index` (0-15) of bank `bank_id`
+/// to `out_file` as a Standard MIDI File at the project tempo.
+pub fn export_pattern_smf(
+    project_path: &str,
+    bank_id: &str,
+    pattern_index: u8,
+    out_file: &str,
+) -> Result<(), String> {
+    let bank_index = bank_index(bank_id, pattern_index)?;
+    let path = Path::new(project_path);
+    let work = path.join(format!("bank{:02}.work", bank_index + 1));
+    let file = if work.exists() {
+        work
+    } else {
+        path.join(format!("bank{:02}.strd", bank_index + 1))
+    };
+    if !file.exists() {
+        return Err(format!("Bank {} does not exist in this project", bank_id));
+    }
+    let bank = crate::os_compat::read_bank_file(&file)
+        .map_err(|e| format!("Failed to read bank {}: {}", bank_id, e))?;
+    let bpm = read_project_metadata(project_path)?.data.tempo as f64;
+
+    let data = pattern_smf(&bank, pattern_index as usize, bpm);
+    let out = Path::new(out_file);
+    if let Some(parent) = out.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    fs::write(out, data).map_err(|e| format!("Failed to write {}: {}", out_file, e))
+}
+
+/// A note read from an SMF, in source ticks.
+#[derive(Debug, Clone, PartialEq)]
+struct SmfNote {
+    tick: u32,
+    len: u32,
+    note: u8,
+    velocity: u8,
+}
+
+struct ChunkReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ChunkReader<'a> {
+    fn bytes(&mut self, n: usize) -> Result<&'a [u8], String> {
+        let end = self.pos + n;
+        let slice = self
+            .data
+            .get(self.pos..end)
+            .ok_or_else(|| "Truncated MIDI file".to_string())?;
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn vlq(&mut self) -> Result<u32, String> {
+        let mut value = 0u32;
+        for _ in 0..4 {
+            let byte = self.u8()?;
+            value = (value << 7) | (byte & 0x7F) as u32;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err("Invalid variable-length value in MIDI file".to_string())
+    }
+}
+
+/// Notes of one MTrk chunk. Unterminated notes end with the track.
+fn parse_track(data: &[u8]) -> Result<Vec<SmfNote>, String> {
+    let mut reader = ChunkReader { data, pos: 0 };
+    let mut open: Vec<(u8, u8, u32, u8)> = Vec::new(); // channel, note, tick, velocity
+    let mut notes = Vec::new();
+    let mut tick = 0u32;
+    let mut running = 0u8;
+    while reader.pos < data.len() {
+        tick += reader.vlq()?;
+        let mut status = reader.u8()?;
+        if status < 0x80 {
+            // Running status: this byte is the first data byte.
+            if running == 0 {
+                return Err("Invalid running status in MIDI file".to_string());
+            }
+            reader.pos -= 1;
+            status = running;
+        }
+        match status {
+            0xFF => {
+                let kind = reader.u8()?;
+                let len = reader.vlq()? as usize;
+                reader.bytes(len)?;
+                if kind == 0x2F {
+                    break;
+                }
+            }
+            0xF0 | 0xF7 => {
+                let len = reader.vlq()? as usize;
+                reader.bytes(len)?;
+            }
+            _ => {
+                running = status;
+                let channel = status & 0x0F;
+                match status & 0xF0 {
+                    0x80 | 0x90 => {
+                        let note = reader.u8()?;
+                        let velocity = reader.u8()?;
+                        if status & 0xF0 == 0x90 && velocity > 0 {
+                            open.push((channel, note, tick, velocity));
+                        } else if let Some(i) =
+                            open.iter().position(|o| o.0 == channel && o.1 == note)
+                        {
+                            let (_, note, start, velocity) = open.remove(i);
+                            notes.push(SmfNote {
+                                tick: start,
+                                len: tick - start,
+                                note,
+                                velocity,
+                            });
+                        }
+                    }
+                    0xC0 | 0xD0 => {
+                        reader.u8()?;
+                    }
+                    _ => {
+                        reader.bytes(2)?;
+                    }
+                }
+            }
+        }
+    }
+    notes.extend(open.into_iter().map(|(_, note, start, velocity)| SmfNote {
+        tick: start,
+        len: tick - start,
+        note,
+        velocity,
+    }));
+    Ok(notes)
+}
+
+/// Parse an SMF into its ticks per quarter note and the notes of every track
+/// (or only of MTrk chunk `source_track`, 0-based), sorted by time.
+fn parse_smf(data: &[u8], source_track: Option<usize>) -> Result<(u16, Vec<SmfNote>), String> {
+    let mut reader = ChunkReader { data, pos: 0 };
+    if reader.bytes(4).ok() != Some(&b"MThd"[..]) {
+        return Err("Not a Standard MIDI File".to_string());
+    }
+    let header_len = u32::from_be_bytes(reader.bytes(4)?.try_into().unwrap()) as usize;
+    let header = reader.bytes(header_len.max(6))?;
+    let division = u16::from_be_bytes([header[4], header[5]]);
+    if division & 0x8000 != 0 || division == 0 {
+        return Err("SMPTE-timed MIDI files are not supported".to_string());
+    }
+
+    let mut notes = Vec::new();
+    let mut track = 0;
+    while reader.pos + 8 <= data.len() {
+        let id = reader.bytes(4)?;
+        let len = u32::from_be_bytes(reader.bytes(4)?.try_into().unwrap()) as usize;
+        let body = reader.bytes(len)?;
+        if id != b"MTrk" {
+            continue;
+        }
+        if !matches!(source_track, Some(t) if t != track) {
+            notes.extend(parse_track(body)?);
+        }
+        track += 1;
+    }
+    if source_track.is_some_and(|t| t >= track) {
+        return Err(format!("The MIDI file has {} track(s)", track));
+    }
+    notes.sort_by_key(|n| (n.tick, n.note));
+    Ok((division, notes))
+}
+
+/// LEN value closest to a duration of `steps`.
+fn nearest_len(steps: f64) -> u8 {
+    (0..LEN_INF)
+        .min_by(|a, b| {
+            (len_steps(*a) - steps)
+                .abs()
+                .total_cmp(&(len_steps(*b) - steps).abs())
+        })
+        .unwrap_or(14)
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MidiImportReport {
+    pub notes_read: usize,
+    pub trigs_written: usize,
+    pub merged: usize, // notes folded into a chord with an earlier note of the same step
+    pub dropped: usize, // notes past the pattern end or beyond a 4-note chord
+    pub warnings: Vec<String>,
+}
+
+/// One trig built from the notes quantized onto a step.
+#[derive(Debug, Clone, PartialEq)]
+struct StepTrig {
+    step: usize,
+    micro: i32,     // 1/24 step
+    notes: Vec<u8>, // base note first
+    velocity: u8,
+    len: u8,
+}
+
+/// Quantize notes to steps of `step_ticks` source ticks, keeping the offset
+/// as micro-timing, and fold the notes of each step into one chord trig.
+fn quantize(
+    notes: &[SmfNote],
+    step_ticks: f64,
+    length: usize,
+) -> (Vec<StepTrig>, MidiImportReport) {
+    let mut report = MidiImportReport {
+        notes_read: notes.len(),
+        ..Default::default()
+    };
+    let mut past_end = 0;
+    let mut steps: std::collections::BTreeMap<usize, Vec<&SmfNote>> = Default::default();
+    for note in notes {
+        let step = (note.tick as f64 / step_ticks).round() as usize;
+        if step >= length {
+            past_end += 1;
+        } else {
+            steps.entry(step).or_default().push(note);
+        }
+    }
+
+    let mut trigs = Vec::new();
+    for (step, group) in steps {
+        let first = group.iter().min_by_key(|n| n.tick).unwrap();
+        let mut chord: Vec<&SmfNote> = Vec::new();
+        let mut by_pitch = group.clone();
+        by_pitch.sort_by_key(|n| n.note);
+        for note in by_pitch {
+            let fits = chord.is_empty()
+                || (chord.len() < 4 && note.note as i16 - (chord[0].note as i16) < 64);
+            if chord.iter().any(|c| c.note == note.note) {
+                report.merged += 1;
+            } else if fits {
+                if !chord.is_empty() {
+                    report.merged += 1;
+                }
+                chord.push(note);
+            } else {
+                report.dropped += 1;
+            }
+        }
+        let longest = chord.iter().map(|n| n.len).max().unwrap_or(0);
+        trigs.push(StepTrig {
+            step,
+            micro: ((first.tick as f64 / step_ticks - step as f64) * 24.0).round() as i32,
+            notes: chord.iter().map(|n| n.note.min(127)).collect(),
+            velocity: chord
+                .iter()
+                .map(|n| n.velocity)
+                .max()
+                .unwrap_or(100)
+                .min(127),
+            len: nearest_len(longest as f64 / step_ticks),
+        });
+    }
+    report.trigs_written = trigs.len();
+    if past_end > 0 {
+        report.dropped += past_end;
+        report.warnings.push(format!(
+            "{} note(s) past the end of the pattern ({} steps) were dropped",
+            past_end, length
+        ));
+    }
+    (trigs, report)
+}
+
+/// Replace the trigs of MIDI track `track` (0-7) of pattern `pattern_index`
+/// with the notes of the SMF `in_file`, quantized to the track's step grid.
+pub fn import_pattern_smf(
+    project_path: &str,
+    bank_id: &str,
+    pattern_index: u8,
+    track: u8,
+    in_file: &str,
+    source_track: Option<usize>,
+) -> Result<MidiImportReport, String> {
+    let bank_index = bank_index(bank_id, pattern_index)?;
+    if track > 7 {
+        return Err("MIDI track must be between 0 and 7".to_string());
+    }
+    let data = fs::read(in_file).map_err(|e| format!("Failed to read {}: {}", in_file, e))?;
+    let (ppq, notes) = parse_smf(&data, source_track)?;
+
+    edit_bank_file(project_path, bank_index, |bank| {
+        let blank = BankFile::default();
+        let pattern = &mut bank.patterns.0[pattern_index as usize];
+        let t = track as usize;
+        let (len, scale) = if pattern.scale.scale_mode == 1 {
+            let settings = &pattern.midi_track_trigs.0[t].scale_per_track_mode;
+            (settings.per_track_len, settings.per_track_scale)
+        } else {
+            (pattern.scale.master_len, pattern.scale.master_scale)
+        };
+        let step_ticks = ppq as f64 / 4.0 * SCALE_STEP_FACTORS[(scale as usize).min(6)];
+        let (trigs, report) = quantize(&notes, step_ticks, len.clamp(1, 64) as usize);
+
+        let dst = &mut pattern.midi_track_trigs.0[t];
+        dst.plocks = blank.patterns.0[0].midi_track_trigs.0[t].plocks.clone();
+        dst.trig_offsets_repeats_conditions = [[0, 0]; 64];
+        let mut triggers = [false; 64];
+        for trig in &trigs {
+            triggers[trig.step] = true;
+            // 6-bit two's complement split over the two offset bytes, see `micro_timing`
+            let micro = (trig.micro as i8 as u8) & 0x3F;
+            dst.trig_offsets_repeats_conditions[trig.step] = [micro >> 1, (micro & 1) << 7];
+            let lock = &mut dst.plocks.0[trig.step].midi;
+            lock.note = trig.notes[0];
+            lock.vel = trig.velocity;
+            lock.len = trig.len;
+            let mut offsets = trig.notes[1..]
+                .iter()
+                .map(|n| CHORD_CENTER + n - trig.notes[0]);
+            lock.not2 = offsets.next().unwrap_or(CHORD_CENTER);
+            lock.not3 = offsets.next().unwrap_or(CHORD_CENTER);
+            lock.not4 = offsets.next().unwrap_or(CHORD_CENTER);
+        }
+        dst.trig_masks.trigger = encode_trig_masks(&triggers);
+        dst.trig_masks.plock = encode_trig_masks(&triggers);
+        dst.trig_masks.trigless = encode_trig_masks(&[false; 64]);
+        Ok(report)
+    })
+}
+
+#[tauri::command]
+pub async fn export_pattern_midi(
+    path: String,
+    bank_id: String,
+    pattern_id: u8,
+    out_file: String,
+) -> Result<(), String> {
+    crate::fs_scope::ensure_allowed(&out_file)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        export_pattern_smf(&path, &bank_id, pattern_id, &out_file)
+    })
+    .await
+    .unwrap()
+}
+
+#[tauri::command]
+pub async fn import_pattern_midi(
+    path: String,
+    bank_id: String,
+    pattern_id: u8,
+    track_id: u8,
+    in_file: String,
+    source_track: Option<usize>,
+) -> Result<MidiImportReport, String> {
+    crate::fs_scope::ensure_allowed(&path)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::edit_journal::record_edit(
+            &path,
+            "import_pattern_midi",
+            &crate::edit_journal::bank_files_for_id(&bank_id),
+            || {
+                import_pattern_smf(
+                    &path,
+                    &bank_id,
+                    pattern_id,
+                    track_id,
+                    &in_file,
+                    source_track,
+                )
+            },
+        )
+    })
+    .await
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ot_tools_io::{OctatrackFileIO, ProjectFile};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_len_and_micro_timing_tables() {
+        assert_eq!(len_steps(0), 0.125);
+        assert_eq!(len_steps(14), 1.0);
+        assert_eq!(len_steps(30), 2.0);
+        assert_eq!(len_steps(46), 4.0);
+        assert_eq!(len_steps(126), 128.0);
+        assert!(len_steps(LEN_INF).is_infinite());
+        assert_eq!(micro_timing([6, 0]), 12); // +1/32
+        assert_eq!(micro_timing([30, 0x80]), -3); // -1/128
+        assert_eq!(micro_timing([2 * 32, 5]), 0); // repeats and condition only
+
+        let mut out = Vec::new();
+        write_vlq(&mut out, 0x80);
+        assert_eq!(out, vec![0x81, 0x00]);
+    }
+
+    #[test]
+    fn test_export_pattern_writes_chords_at_micro_timed_ticks() {
+        let dir = TempDir::new().unwrap();
+        ProjectFile::default()
+            .to_data_file(&dir.path().join("project.work"))
+            .unwrap();
+        let mut bank = BankFile::default();
+        let pattern = &mut bank.patterns.0[3];
+        pattern.scale.master_len = 16;
+        let midi = &mut pattern.midi_track_trigs.0[1];
+        midi.trig_masks.trigger = encode_trig_masks(&std::array::from_fn(|s| s == 2));
+        midi.plocks.0[2].midi.note = 60;
+        midi.plocks.0[2].midi.vel = 100;
+        midi.plocks.0[2].midi.len = 14; // one step
+        midi.plocks.0[2].midi.not2 = 67; // +3 semitones
+        midi.trig_offsets_repeats_conditions[2] = [6, 0]; // +1/32 = half a step
+        let part = &mut bank.parts.unsaved.0[0];
+        part.midi_track_params_values[1].midi.not2 = CHORD_CENTER;
+        part.midi_track_params_values[1].midi.not3 = CHORD_CENTER;
+        part.midi_track_params_values[1].midi.not4 = CHORD_CENTER;
+        let channel = part.midi_track_params_setup[1].note.chan.min(15);
+        bank.to_data_file(&dir.path().join("bank03.work")).unwrap();
+
+        let out = dir.path().join("midi/pattern.mid");
+        let path = dir.path().to_string_lossy().to_string();
+        export_pattern_smf(&path, "C", 3, &out.to_string_lossy()).unwrap();
+        let data = fs::read(&out).unwrap();
+
+        assert_eq!(&data[..4], b"MThd");
+        assert_eq!(u16::from_be_bytes([data[10], data[11]]), 2); // conductor + M2
+        let m2 = data.windows(2).position(|w| w == b"M2").unwrap();
+        // Note ons at 2 steps + half a step = 60 ticks (VLQ 0x3C), chord in the same tick
+        let on = [0x3C, 0x90 | channel, 60, 100, 0x00, 0x90 | channel, 63, 100];
+        assert!(data[m2..].windows(on.len()).any(|w| w == on));
+        // Note offs one step (24 ticks) later
+        let off = [
+            0x18,
+            0x80 | channel,
+            60,
+            0x00,
+            0x00,
+            0x80 | channel,
+            63,
+            0x00,
+        ];
+        assert!(data[m2..].windows(off.len()).any(|w| w == off));
+
+        assert!(export_pattern_smf(&path, "D", 3, &out.to_string_lossy()).is_err());
+    }
+
+    #[test]
+    fn test_quantize_merges_chords_and_drops_overflow() {
+        let note = |tick, note| SmfNote {
+            tick,
+            len: 24,
+            note,
+            velocity: 90,
+        };
+        let notes = [
+            note(0, 60),
+            note(2, 64), // same step: the highest of five pitches is dropped
+            note(1, 60), // duplicate pitch: merged
+            note(0, 61),
+            note(0, 62),
+            note(0, 63),
+            note(50, 70),  // step 2, +2/24
+            note(400, 72), // past 16 steps
+        ];
+        let (trigs, report) = quantize(&notes, 24.0, 16);
+        assert_eq!(trigs.len(), 2);
+        assert_eq!(trigs[0].notes, vec![60, 61, 62, 63]);
+        assert_eq!(trigs[0].micro, 0);
+        assert_eq!(trigs[0].len, 14);
+        assert_eq!((trigs[1].step, trigs[1].micro), (2, 2));
+        assert_eq!(report.notes_read, 8);
+        assert_eq!(report.merged, 4);
+        assert_eq!(report.dropped, 2);
+        assert_eq!(report.warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_import_round_trips_exported_pattern() {
+        let dir = TempDir::new().unwrap();
+        ProjectFile::default()
+            .to_data_file(&dir.path().join("project.work"))
+            .unwrap();
+        let mut bank = BankFile::default();
+        for idx in [3, 5] {
+            bank.patterns.0[idx].scale.master_len = 16;
+        }
+        let midi = &mut bank.patterns.0[3].midi_track_trigs.0[1];
+        midi.trig_masks.trigger = encode_trig_masks(&std::array::from_fn(|s| s == 2 || s == 9));
+        midi.plocks.0[2].midi.note = 60;
+        midi.plocks.0[2].midi.len = 30; // two steps
+        midi.plocks.0[2].midi.not2 = 67;
+        midi.trig_offsets_repeats_conditions[9] = [30, 0x80]; // -1/128
+        let part = &mut bank.parts.unsaved.0[0];
+        part.midi_track_params_values[1].midi.not2 = CHORD_CENTER;
+        part.midi_track_params_values[1].midi.not3 = CHORD_CENTER;
+        part.midi_track_params_values[1].midi.not4 = CHORD_CENTER;
+        bank.to_data_file(&dir.path().join("bank01.work")).unwrap();
+
+        let path = dir.path().to_string_lossy().to_string();
+        let mid = dir.path().join("pattern.mid").to_string_lossy().to_string();
+        export_pattern_smf(&path, "A", 3, &mid).unwrap();
+        let report = import_pattern_smf(&path, "A", 5, 0, &mid, None).unwrap();
+        assert_eq!(report.trigs_written, 2);
+        assert_eq!(report.dropped, 0);
+
+        let bank = BankFile::from_data_file(&dir.path().join("bank01.work")).unwrap();
+        let track = &bank.patterns.0[5].midi_track_trigs.0[0];
+        let triggers = decode_trig_masks(&track.trig_masks.trigger);
+        assert_eq!(
+            (0..64).filter(|&s| triggers[s]).collect::<Vec<_>>(),
+            vec![2, 9]
+        );
+        let lock = &track.plocks.0[2].midi;
+        assert_eq!((lock.note, lock.not2, lock.len), (60, 67, 30));
+        assert_eq!(track.trig_offsets_repeats_conditions[9], [30, 0x80]);
+        assert!(import_pattern_smf(&path, "A", 5, 8, &mid, None).is_err());
+    }
+}
@@ -0,0 +1,682 @@
+// Operation plans: the full file/slot mapping of a large operation, computed
+// without touching anything, so the UI can show it, let the user rename
+// targets or drop items, and then execute exactly what was approved.
+//
+// Supported operations:
+//   pool_merge - merge one Audio Pool into another (see audio_pool::merge_pools)
+//   kit_import - copy a set of samples into the project's Audio Pool and
+//                assign them to consecutive sample slots
+//   chain_build - join samples into one chain (see sample_chain::build_chain),
+//                one item per slice, in slice order
+
+use crate::audio_pool;
+use crate::project_reader::{self, AssignSamplesResult, SlotAssignment};
+use crate::sample_attributes::MAX_SLICES;
+use crate::sample_chain::{self, ChainOptions, SampleChainReport};
+use ot_tools_io::{OctatrackFileIO, ProjectFile};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PoolMergeParams {
+    pub src_root: String,
+    pub dst_root: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct KitImportParams {
+    pub project_path: String,
+    pub slot_type: String, // "FLEX" or "STATIC"
+    pub files: Vec<String>,
+    pub start_slot: u16, // 1-128, first slot of the kit
+    #[serde(default)]
+    pub folder: Option<String>, // sub-folder of the Audio Pool, e.g. "kits/808"
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChainBuildParams {
+    pub files: Vec<String>,
+    pub dest: String, // the chain WAV to write
+    #[serde(default)]
+    pub options: ChainOptions,
+}
+
+/// One planned file operation. The UI may edit `target` and `include`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PlanItem {
+    pub source: String,
+    pub target: String,    // absolute destination path
+    pub action: String, // "copy", "convert", "reuse" (identical file already there), "rename" or "slice"
+    pub slot: Option<u16>, // kit_import: slot the file is assigned to; chain_build: slice number
+    pub include: bool,  // false = dropped by the user
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperationPlan {
+    pub operation: String,
+    pub params: serde_json::Value, // the params the plan was made from, echoed back on execute
+    pub items: Vec<PlanItem>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanExecution {
+    pub operation: String,
+    pub written: Vec<String>, // targets created
+    pub reused: Vec<String>,  // targets already present
+    pub skipped: usize,       // items dropped from the plan
+    pub pool_merge: Option<audio_pool::PoolMergeReport>,
+    pub assignment: Option<AssignSamplesResult>,
+    pub chain: Option<SampleChainReport>,
+}
+
+fn parse<T: DeserializeOwned>(operation: &str, params: &serde_json::Value) -> Result<T, String> {
+    serde_json::from_value(params.clone())
+        .map_err(|e| format!("Invalid parameters for {}: {}", operation, e))
+}
+
+/// Action for copying `source` to `target`, and the target actually used
+/// (a free `_N` name when a different file already holds the name).
+fn plan_file(source: &Path, target: PathBuf, convert: bool) -> Result<(String, PathBuf), String> {
+    if !target.exists() {
+        let action = if convert { "convert" } else { "copy" };
+        return Ok((action.to_string(), target));
+    }
+    if !convert && audio_pool::same_file_contents(source, &target)? {
+        return Ok(("reuse".to_string(), target));
+    }
+    Ok((
+        "rename".to_string(),
+        audio_pool::next_free_file_name(&target)?,
+    ))
+}
+
+fn plan_pool_merge(params: &PoolMergeParams) -> Result<(Vec<PlanItem>, Vec<String>), String> {
+    let src = Path::new(&params.src_root);
+    let dst = Path::new(&params.dst_root);
+    if !src.is_dir() {
+        return Err(format!("Source pool does not exist: {}", params.src_root));
+    }
+    if !dst.is_dir() {
+        return Err(format!(
+            "Destination pool does not exist: {}",
+            params.dst_root
+        ));
+    }
+    if fs::canonicalize(src).ok() == fs::canonicalize(dst).ok() {
+        return Err("Source and destination pools are the same directory".to_string());
+    }
+
+    let mut items = Vec::new();
+    let mut warnings = Vec::new();
+    let walker = walkdir::WalkDir::new(src)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.'));
+    for entry in walker {
+        let entry = entry.map_err(|e| format!("Failed to scan source pool: {}", e))?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let rel = entry.path().strip_prefix(src).map_err(|e| e.to_string())?;
+        let (action, target) = plan_file(entry.path(), dst.join(rel), false)?;
+        let note = (action == "rename").then(|| {
+            warnings.push(format!(
+                "{} differs from the file of the same name in the destination",
+                rel.display()
+            ));
+            "name taken by a different file".to_string()
+        });
+        items.push(PlanItem {
+            source: entry.path().to_string_lossy().to_string(),
+            target: target.to_string_lossy().to_string(),
+            action,
+            slot: None,
+            include: true,
+            note,
+        });
+    }
+    Ok((items, warnings))
+}
+
+fn project_file(project_path: &str) -> Result<ProjectFile, String> {
+    let path = Path::new(project_path);
+    let file = if path.join("project.work").exists() {
+        path.join("project.work")
+    } else if path.join("project.strd").exists() {
+        path.join("project.strd")
+    } else {
+        return Err("Project file not found".to_string());
+    };
+    ProjectFile::from_data_file(&file).map_err(|e| format!("Failed to read project: {:?}", e))
+}
+
+fn kit_folder(params: &KitImportParams) -> Result<PathBuf, String> {
+    let status = project_reader::get_audio_pool_status(&params.project_path)?;
+    let pool = status
+        .path
+        .ok_or_else(|| "Project has no Audio Pool (AUDIO folder in its Set)".to_string())?;
+    let folder = params.folder.as_deref().unwrap_or("").trim_matches('/');
+    if folder.split('/').any(|part| part == "..") {
+        return Err(format!("Invalid folder: {}", folder));
+    }
+    Ok(if folder.is_empty() {
+        PathBuf::from(pool)
+    } else {
+        Path::new(&pool).join(folder)
+    })
+}
+
+fn plan_kit_import(params: &KitImportParams) -> Result<(Vec<PlanItem>, Vec<String>), String> {
+    let slot_type = params.slot_type.to_uppercase();
+    if !["FLEX", "STATIC"].contains(&slot_type.as_str()) {
+        return Err(format!(
+            "Invalid slot_type: {}. Must be 'FLEX' or 'STATIC'",
+            params.slot_type
+        ));
+    }
+    if !(1..=128).contains(&params.start_slot) {
+        return Err(format!(
+            "Slot index {} out of range. Must be 1-128",
+            params.start_slot
+        ));
+    }
+    let folder = kit_folder(params)?;
+    let project = project_file(&params.project_path)?;
+    let slots = if slot_type == "FLEX" {
+        &project.slots.flex_slots[..]
+    } else {
+        &project.slots.static_slots[..]
+    };
+
+    let mut items = Vec::new();
+    let mut warnings = Vec::new();
+    let mut planned_targets = HashSet::new();
+    for (i, file) in params.files.iter().enumerate() {
+        let source = Path::new(file);
+        if !source.is_file() {
+            return Err(format!("Source file does not exist: {}", file));
+        }
+        let slot = params.start_slot + i as u16;
+        let convert = audio_pool::needs_conversion(source);
+        let mut target = folder.join(audio_pool::dest_filename_for(source));
+        // Two kit files converting to the same name ("kick.aif" + "kick.wav")
+        while planned_targets.contains(&target) {
+            target = audio_pool::next_free_file_name(&target)?;
+        }
+        let (action, target) = plan_file(source, target, convert)?;
+        planned_targets.insert(target.clone());
+
+        let mut note = None;
+        if slot > 128 {
+            note = Some("no slot left".to_string());
+        } else if let Some(existing) = slots
+            .get(slot as usize - 1)
+            .and_then(|s| s.as_ref())
+            .and_then(|s| s.path.as_ref())
+        {
+            warnings.push(format!(
+                "{} slot {} currently holds {} and will be replaced",
+                slot_type,
+                slot,
+                existing.display()
+            ));
+            note = Some(format!("replaces {}", existing.display()));
+        }
+        items.push(PlanItem {
+            source: file.clone(),
+            target: target.to_string_lossy().to_string(),
+            action,
+            slot: (slot <= 128).then_some(slot),
+            include: slot <= 128,
+            note,
+        });
+    }
+    if params.start_slot as usize + params.files.len() > 129 {
+        warnings
+            .push("Kit does not fit in the remaining slots; extra files are dropped".to_string());
+    }
+    Ok((items, warnings))
+}
+
+fn plan_chain_build(params: &ChainBuildParams) -> Result<(Vec<PlanItem>, Vec<String>), String> {
+    if params.files.is_empty() {
+        return Err("Select at least one sample".to_string());
+    }
+    let mut warnings = Vec::new();
+    let mut dest = PathBuf::from(&params.dest);
+    if dest.exists() && !params.options.overwrite {
+        dest = audio_pool::next_free_file_name(&dest)?;
+        warnings.push(format!(
+            "{} already exists; the chain is written as {}",
+            params.dest,
+            dest.display()
+        ));
+    }
+    let mut items = Vec::new();
+    for (i, file) in params.files.iter().enumerate() {
+        if !Path::new(file).is_file() {
+            return Err(format!("Source file does not exist: {}", file));
+        }
+        let fits = i < MAX_SLICES;
+        items.push(PlanItem {
+            source: file.clone(),
+            target: dest.to_string_lossy().to_string(),
+            action: "slice".to_string(),
+            slot: fits.then_some(i as u16 + 1),
+            include: fits,
+            note: (!fits).then(|| "no slice left".to_string()),
+        });
+    }
+    if params.files.len() > MAX_SLICES {
+        warnings.push(format!(
+            "A chain holds at most {} slices; extra files are dropped",
+            MAX_SLICES
+        ));
+    }
+    Ok((items, warnings))
+}
+
+/// Plan `operation` without changing anything on disk.
+pub fn build_plan(operation: &str, params: serde_json::Value) -> Result<OperationPlan, String> {
+    let (items, warnings) = match operation {
+        "pool_merge" => plan_pool_merge(&parse(operation, &params)?)?,
+        "kit_import" => plan_kit_import(&parse(operation, &params)?)?,
+        "chain_build" => plan_chain_build(&parse(operation, &params)?)?,
+        _ => {
+            return Err(format!(
+                "Unknown operation: {}. Must be 'pool_merge', 'kit_import' or 'chain_build'",
+                operation
+            ))
+        }
+    };
+    Ok(OperationPlan {
+        operation: operation.to_string(),
+        params,
+        items,
+        warnings,
+    })
+}
+
+/// Check that every included item reads from what the plan was made for: a
+/// file under the source pool, or one of the files the plan was asked for.
+fn validate_sources(plan: &OperationPlan) -> Result<(), String> {
+    let allowed: Box<dyn Fn(&Path) -> bool> = match plan.operation.as_str() {
+        "pool_merge" => {
+            let params: PoolMergeParams = parse(&plan.operation, &plan.params)?;
+            let root = project_reader::normalize_path_lexically(Path::new(&params.src_root));
+            Box::new(move |source: &Path| source.starts_with(&root))
+        }
+        _ => {
+            let files: Vec<String> = parse(&plan.operation, &plan.params["files"])?;
+            let files: HashSet<PathBuf> = files
+                .iter()
+                .map(|f| project_reader::normalize_path_lexically(Path::new(f)))
+                .collect();
+            Box::new(move |source: &Path| files.contains(source))
+        }
+    };
+    for item in plan.items.iter().filter(|i| i.include) {
+        let source = project_reader::normalize_path_lexically(Path::new(&item.source));
+        if !allowed(&source) {
+            return Err(format!("Source is not part of the plan: {}", item.source));
+        }
+    }
+    Ok(())
+}
+
+/// Check the (possibly edited) plan before anything is written: every target
+/// stays under `root`, no two items write the same file, and only "reuse"
+/// items may point at an existing file.
+fn validate_targets(plan: &OperationPlan, root: &Path) -> Result<(), String> {
+    let root = project_reader::normalize_path_lexically(root);
+    let mut seen = HashSet::new();
+    for item in plan.items.iter().filter(|i| i.include) {
+        let target = project_reader::normalize_path_lexically(Path::new(&item.target));
+        if !target.starts_with(&root) {
+            return Err(format!(
+                "Target is outside {}: {}",
+                root.display(),
+                item.target
+            ));
+        }
+        if !seen.insert(target.clone()) {
+            return Err(format!("Two items write the same file: {}", item.target));
+        }
+        if item.action != "reuse" && target.exists() {
+            return Err(format!("Target already exists: {}", item.target));
+        }
+        if item.action == "reuse" && !target.is_file() {
+            return Err(format!("File to reuse does not exist: {}", item.target));
+        }
+    }
+    Ok(())
+}
+
+/// Copy one included item to its target, converting it for the Octatrack
+/// when `convert` (kit imports; pool merges copy files as-is).
+fn write_item(item: &PlanItem, convert: bool) -> Result<(), String> {
+    let source = Path::new(&item.source);
+    let target = Path::new(&item.target);
+    let parent = target
+        .parent()
+        .ok_or_else(|| format!("Invalid target: {}", item.target))?;
+    fs::create_dir_all(parent)
+        .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    if convert && audio_pool::needs_conversion(source) {
+        // Convert in a scratch folder, then move under the planned name.
+        let scratch = parent.join(".plan_convert");
+        fs::create_dir_all(&scratch)
+            .map_err(|e| format!("Failed to create {}: {}", scratch.display(), e))?;
+        let result =
+            audio_pool::copy_and_convert_audio(source, &scratch, true).and_then(|converted| {
+                fs::rename(&converted, target)
+                    .map_err(|e| format!("Failed to write {}: {}", item.target, e))
+            });
+        let _ = fs::remove_dir_all(&scratch);
+        result
+    } else {
+        let data =
+            fs::read(source).map_err(|e| format!("Failed to read {}: {}", item.source, e))?;
+        crate::atomic_write::write_atomic(target, &data)
+            .map_err(|e| format!("Failed to copy {}: {}", item.source, e))
+    }
+}
+
+/// Execute a plan returned by `build_plan`, as edited by the user.
+pub fn execute_plan_sync(plan: &OperationPlan) -> Result<PlanExecution, String> {
+    let mut execution = PlanExecution {
+        operation: plan.operation.clone(),
+        written: Vec::new(),
+        reused: Vec::new(),
+        skipped: plan.items.iter().filter(|i| !i.include).count(),
+        pool_merge: None,
+        assignment: None,
+        chain: None,
+    };
+    let root = match plan.operation.as_str() {
+        "pool_merge" => {
+            let params: PoolMergeParams = parse(&plan.operation, &plan.params)?;
+            PathBuf::from(params.dst_root)
+        }
+        "kit_import" => kit_folder(&parse(&plan.operation, &plan.params)?)?,
+        "chain_build" => {
+            let params: ChainBuildParams = parse(&plan.operation, &plan.params)?;
+            return execute_chain_build(plan, &params, execution);
+        }
+        other => return Err(format!("Unknown operation: {}", other)),
+    };
+    validate_sources(plan)?;
+    validate_targets(plan, &root)?;
+
+    for item in plan.items.iter().filter(|i| i.include) {
+        if item.action == "reuse" {
+            execution.reused.push(item.target.clone());
+        } else {
+            write_item(item, plan.operation == "kit_import")?;
+            execution.written.push(item.target.clone());
+        }
+    }
+
+    match plan.operation.as_str() {
+        "pool_merge" => {
+            let params: PoolMergeParams = parse(&plan.operation, &plan.params)?;
+            // Same report as merge_pools: any target off its original name is a
+            // rename that apply_pool_merge_mapping repoints projects onto.
+            let src = Path::new(&params.src_root);
+            let dst = Path::new(&params.dst_root);
+            let mut report = audio_pool::PoolMergeReport {
+                entries: Vec::new(),
+                copied: 0,
+                identical: 0,
+                renamed: 0,
+            };
+            for item in plan.items.iter().filter(|i| i.include) {
+                let rel = Path::new(&item.source)
+                    .strip_prefix(src)
+                    .map_err(|e| e.to_string())?;
+                let status = if item.action == "reuse" {
+                    report.identical += 1;
+                    "identical"
+                } else if Path::new(&item.target) != dst.join(rel) {
+                    report.renamed += 1;
+                    "renamed"
+                } else {
+                    report.copied += 1;
+                    "copied"
+                };
+                report.entries.push(audio_pool::PoolMergeEntry {
+                    source_path: item.source.clone(),
+                    dest_path: item.target.clone(),
+                    relative_path: rel.to_string_lossy().replace('\\', "/"),
+                    status: status.to_string(),
+                });
+            }
+            execution.pool_merge = Some(report);
+        }
+        _ => {
+            let params: KitImportParams = parse(&plan.operation, &plan.params)?;
+            let set_dir = Path::new(&params.project_path)
+                .parent()
+                .ok_or_else(|| "Cannot determine parent directory".to_string())?
+                .to_path_buf();
+            let assignments = plan
+                .items
+                .iter()
+                .filter(|i| i.include)
+                .filter_map(|i| {
+                    let slot = i.slot?;
+                    let rel = Path::new(&i.target).strip_prefix(&set_dir).ok()?;
+                    Some(SlotAssignment {
+                        slot_index: slot,
+                        audio_path: format!("../{}", rel.to_string_lossy().replace('\\', "/")),
+                        set_defaults: true,
+                    })
+                })
+                .collect();
+            execution.assignment = Some(project_reader::assign_samples_to_slots(
+                &params.project_path,
+                &params.slot_type,
+                assignments,
+            )?);
+        }
+    }
+    Ok(execution)
+}
+
+/// Build the chain from the included items, in plan order. Every item names
+/// the same chain file, which must stay in the folder of the planned one.
+fn execute_chain_build(
+    plan: &OperationPlan,
+    params: &ChainBuildParams,
+    mut execution: PlanExecution,
+) -> Result<PlanExecution, String> {
+    validate_sources(plan)?;
+    let included: Vec<&PlanItem> = plan.items.iter().filter(|i| i.include).collect();
+    let target = match included.first() {
+        Some(first) => PathBuf::from(&first.target),
+        None => return Err("The plan has no slices left".to_string()),
+    };
+    if included.iter().any(|i| Path::new(&i.target) != target) {
+        return Err("Every slice of a chain must have the same target".to_string());
+    }
+    let folder = Path::new(&params.dest)
+        .parent()
+        .map(project_reader::normalize_path_lexically)
+        .unwrap_or_default();
+    let target = project_reader::normalize_path_lexically(&target);
+    if target.parent() != Some(folder.as_path()) {
+        return Err(format!(
+            "Target is outside {}: {}",
+            folder.display(),
+            target.display()
+        ));
+    }
+    if target.exists() && !params.options.overwrite {
+        return Err(format!("Target already exists: {}", target.display()));
+    }
+    let sources: Vec<PathBuf> = included.iter().map(|i| PathBuf::from(&i.source)).collect();
+    let report = sample_chain::build_chain(&sources, &target, &params.options)?;
+    execution.written.push(report.path.clone());
+    execution.chain = Some(report);
+    Ok(execution)
+}
+
+#[tauri::command]
+pub async fn preview_plan(
+    operation: String,
+    params: serde_json::Value,
+) -> Result<OperationPlan, String> {
+    tauri::async_runtime::spawn_blocking(move || build_plan(&operation, params))
+        .await
+        .unwrap()
+}
+
+#[tauri::command]
+pub async fn execute_plan(plan: OperationPlan) -> Result<PlanExecution, String> {
+    let root = match plan.operation.as_str() {
+        "pool_merge" => plan.params["dst_root"].as_str().map(str::to_string),
+        "chain_build" => plan.params["dest"].as_str().map(str::to_string),
+        _ => plan.params["project_path"].as_str().map(str::to_string),
+    }
+    .ok_or_else(|| "Plan parameters are incomplete".to_string())?;
+    crate::fs_scope::ensure_allowed(&root)?;
+    tauri::async_runtime::spawn_blocking(move || execute_plan_sync(&plan))
+        .await
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::write_wav;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn pools() -> (TempDir, PathBuf, PathBuf) {
+        let temp = TempDir::new().unwrap();
+        let src = temp.path().join("SRC");
+        let dst = temp.path().join("DST");
+        fs::create_dir_all(src.join("drums")).unwrap();
+        fs::create_dir_all(dst.join("drums")).unwrap();
+        fs::write(src.join("drums/kick.txt"), b"kick").unwrap();
+        fs::write(src.join("drums/snare.txt"), b"snare-a").unwrap();
+        fs::write(src.join("pad.txt"), b"pad").unwrap();
+        fs::write(dst.join("drums/kick.txt"), b"kick").unwrap();
+        fs::write(dst.join("drums/snare.txt"), b"snare-b").unwrap();
+        (temp, src, dst)
+    }
+
+    #[test]
+    fn test_pool_merge_plan_changes_nothing() {
+        let (_temp, src, dst) = pools();
+        let plan = build_plan(
+            "pool_merge",
+            json!({ "src_root": src.to_string_lossy(), "dst_root": dst.to_string_lossy() }),
+        )
+        .unwrap();
+
+        let actions: Vec<_> = plan.items.iter().map(|i| i.action.as_str()).collect();
+        assert_eq!(actions, vec!["reuse", "rename", "copy"]);
+        assert!(plan.items[1].target.ends_with("snare_2.txt"));
+        assert_eq!(plan.warnings.len(), 1);
+        assert!(!dst.join("pad.txt").exists());
+        assert!(!dst.join("drums/snare_2.txt").exists());
+    }
+
+    #[test]
+    fn test_pool_merge_executes_edited_plan() {
+        let (_temp, src, dst) = pools();
+        let mut plan = build_plan(
+            "pool_merge",
+            json!({ "src_root": src.to_string_lossy(), "dst_root": dst.to_string_lossy() }),
+        )
+        .unwrap();
+        plan.items[1].target = dst
+            .join("drums/snare_alt.txt")
+            .to_string_lossy()
+            .to_string();
+        plan.items[2].include = false;
+
+        let execution = execute_plan_sync(&plan).unwrap();
+        assert_eq!(execution.skipped, 1);
+        assert_eq!(
+            fs::read(dst.join("drums/snare_alt.txt")).unwrap(),
+            b"snare-a"
+        );
+        assert_eq!(fs::read(dst.join("drums/snare.txt")).unwrap(), b"snare-b");
+        assert!(!dst.join("pad.txt").exists());
+        let report = execution.pool_merge.unwrap();
+        assert_eq!((report.identical, report.renamed, report.copied), (1, 1, 0));
+
+        // A target edited to climb out of the destination pool is refused.
+        plan.items[2].include = true;
+        plan.items[2].target = src.join("escape.txt").to_string_lossy().to_string();
+        assert!(execute_plan_sync(&plan).is_err());
+    }
+
+    #[test]
+    fn test_pool_merge_refuses_sources_outside_the_source_pool() {
+        let (temp, src, dst) = pools();
+        let mut plan = build_plan(
+            "pool_merge",
+            json!({ "src_root": src.to_string_lossy(), "dst_root": dst.to_string_lossy() }),
+        )
+        .unwrap();
+        let secret = temp.path().join("secret.txt");
+        fs::write(&secret, b"secret").unwrap();
+        plan.items[2].source = secret.to_string_lossy().to_string();
+        let err = execute_plan_sync(&plan).unwrap_err();
+        assert!(err.contains("not part of the plan"), "{}", err);
+        assert!(!dst.join("pad.txt").exists());
+    }
+
+    #[test]
+    fn test_chain_build_plan_executes_kept_slices_in_order() {
+        let temp = TempDir::new().unwrap();
+        let files: Vec<String> = ["kick", "snare", "hat"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                let path = temp.path().join(format!("{}.wav", name));
+                write_wav(&path, 1, 44100, &vec![1000; 100 * (i + 1)]);
+                path.to_string_lossy().to_string()
+            })
+            .collect();
+        let dest = temp.path().join("chain.wav");
+        let mut plan = build_plan(
+            "chain_build",
+            json!({ "files": files, "dest": dest.to_string_lossy() }),
+        )
+        .unwrap();
+        let slices: Vec<_> = plan.items.iter().map(|i| i.slot).collect();
+        assert_eq!(slices, vec![Some(1), Some(2), Some(3)]);
+        assert!(!dest.exists());
+
+        plan.items[1].include = false;
+        let execution = execute_plan_sync(&plan).unwrap();
+        let chain = execution.chain.unwrap();
+        assert_eq!(chain.total_frames, 400);
+        assert_eq!(chain.slices.len(), 2);
+        assert_eq!(execution.skipped, 1);
+
+        // Planned again, the chain goes to a free name next to the first one
+        let again = build_plan(
+            "chain_build",
+            json!({ "files": files, "dest": dest.to_string_lossy() }),
+        )
+        .unwrap();
+        assert!(again.items[0].target.ends_with("chain_2.wav"));
+        assert_eq!(again.warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_unknown_operation_is_rejected() {
+        assert!(build_plan("chain_move", json!({})).is_err());
+        assert!(build_plan("pool_merge", json!({ "src_root": "/x" })).is_err());
+    }
+}
@@ -0,0 +1,421 @@
+// OS version compatibility: which Octatrack OS wrote a project, which file
+// format versions its project and bank files carry, and whether this app can
+// safely write them.
+//
+// The app reads and writes the layout used by OS 1.40 (project VERSION=19,
+// bank format 23). Files in any other known layout are refused by the shared
+// write helpers (`edit_bank_file` and the surgical project.work writers)
+// instead of being rewritten with a layout the device would misread. Files
+// whose version can't be determined are left to the parser, as before.
+//
+// Reads degrade instead: a bank in another format is decoded with the OS 1.40
+// layout when that works (reported as a warning), and otherwise fails with an
+// "unsupported bank version" error naming the blocks that don't line up,
+// rather than the parser's own error.
+
+use ot_tools_io::{BankFile, OctatrackFileIO};
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::Path;
+
+/// OS release whose file layout the app reads and writes.
+pub const SUPPORTED_OS: (u8, u8) = (1, 40);
+/// `VERSION` in the [META] block of project files written by OS 1.40.
+pub const SUPPORTED_PROJECT_VERSION: u32 = 19;
+/// Format byte following the bank header of bank files written by OS 1.40.
+pub const SUPPORTED_BANK_VERSION: u8 = 23;
+
+/// "FORM", a zeroed size, then "DPS1BANK"; the format byte sits at offset 21.
+const BANK_MAGIC: &[u8] = b"DPS1BANK";
+const BANK_MAGIC_OFFSET: usize = 8;
+const BANK_VERSION_OFFSET: usize = 21;
+
+/// Block headers in a bank file and how many the OS 1.40 layout holds:
+/// 16 patterns, and 4 parts stored twice (unsaved and saved copies).
+const BANK_BLOCKS: [(&str, &[u8], usize); 2] = [("patterns", b"PTRN", 16), ("parts", b"PART", 8)];
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BankFormatVersion {
+    pub bank: u8, // 0-based
+    pub file: String,
+    pub version: Option<u8>, // None when the header isn't a bank header
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OsCompatibility {
+    pub os_version: String,         // raw OS_VERSION, e.g. "R0177     1.40B"
+    pub os_release: Option<String>, // "1.40B"
+    pub project_format_version: Option<u32>,
+    pub banks: Vec<BankFormatVersion>,
+    pub writable: bool, // the app will write this project's files
+    pub upgrade_required: Option<String>, // why the device needs a newer OS
+    pub issues: Vec<String>,
+}
+
+/// Release part of an OS_VERSION string ("R0177     1.40B" -> "1.40B").
+fn os_release(os_version: &str) -> Option<&str> {
+    os_version
+        .split_whitespace()
+        .last()
+        .filter(|release| release.contains('.'))
+}
+
+/// (major, minor) of a release string: "1.40B" -> (1, 40).
+pub fn parse_os_release(release: &str) -> Option<(u8, u8)> {
+    let (major, rest) = release.split_once('.')?;
+    let minor: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+    Some((major.parse().ok()?, minor.parse().ok()?))
+}
+
+fn release_label((major, minor): (u8, u8)) -> String {
+    format!("{}.{:02}", major, minor)
+}
+
+/// Raw value of `key` in the [META] block of a decoded project file.
+fn meta_field<'a>(content: &'a str, key: &str) -> Option<&'a str> {
+    content
+        .lines()
+        .map(|line| line.trim_end_matches('\r'))
+        .skip_while(|line| *line != "[META]")
+        .take_while(|line| *line != "[/META]")
+        .find_map(|line| line.strip_prefix(key)?.strip_prefix('='))
+}
+
+/// Format version declared by a decoded project file, if any.
+pub fn project_format_version(content: &str) -> Option<u32> {
+    meta_field(content, "VERSION")?.trim().parse().ok()
+}
+
+/// Format byte of a bank file's raw bytes, if they start with a bank header.
+pub fn bank_format_version(bytes: &[u8]) -> Option<u8> {
+    let magic = bytes.get(BANK_MAGIC_OFFSET..BANK_MAGIC_OFFSET + BANK_MAGIC.len())?;
+    if bytes.get(..4)? != b"FORM" || magic != BANK_MAGIC {
+        return None;
+    }
+    bytes.get(BANK_VERSION_OFFSET).copied()
+}
+
+/// Blocks of a bank file that the OS 1.40 layout can't decode, e.g.
+/// "patterns (12 of 16 found)". Empty when every block is present.
+pub fn undecodable_blocks(bytes: &[u8]) -> Vec<String> {
+    BANK_BLOCKS
+        .iter()
+        .filter_map(|(name, magic, expected)| {
+            let found = bytes.windows(magic.len()).filter(|w| w == magic).count();
+            (found < *expected).then(|| format!("{} ({} of {} found)", name, found, expected))
+        })
+        .collect()
+}
+
+/// Read a bank file, explaining a failure by its format version instead of
+/// passing on the parser's error.
+pub fn read_bank_file(path: &Path) -> Result<BankFile, String> {
+    let parse_error = match BankFile::from_data_file(path) {
+        Ok(bank) => return Ok(bank),
+        Err(e) => e,
+    };
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let bytes = std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", name, e))?;
+    let blocks = undecodable_blocks(&bytes);
+    let cant_decode = if blocks.is_empty() {
+        "the block layout differs".to_string()
+    } else {
+        blocks.join(", ")
+    };
+    match bank_format_version(&bytes) {
+        None => Err(format!(
+            "{} is not an Octatrack bank file (no bank header)",
+            name
+        )),
+        Some(version) if version != SUPPORTED_BANK_VERSION => Err(format!(
+            "Unsupported bank version {} in {} (this app decodes version {}, OS {}); \
+             can't decode: {}",
+            version,
+            name,
+            SUPPORTED_BANK_VERSION,
+            release_label(SUPPORTED_OS),
+            cant_decode
+        )),
+        Some(_) if !blocks.is_empty() => Err(format!(
+            "{} is damaged; can't decode: {}",
+            name, cant_decode
+        )),
+        Some(_) => Err(format!("Failed to decode {}: {:?}", name, parse_error)),
+    }
+}
+
+/// Warning for a bank that decoded but carries another format version, so
+/// its values were mapped with the OS 1.40 layout.
+pub fn bank_version_warning(path: &Path) -> Option<String> {
+    let mut header = Vec::new();
+    std::fs::File::open(path)
+        .ok()?
+        .take(BANK_VERSION_OFFSET as u64 + 1)
+        .read_to_end(&mut header)
+        .ok()?;
+    let version = bank_format_version(&header)?;
+    (version != SUPPORTED_BANK_VERSION).then(|| {
+        format!(
+            "Bank format version {} read with the version {} (OS {}) layout; values may be \
+             off and the bank can't be edited",
+            version,
+            SUPPORTED_BANK_VERSION,
+            release_label(SUPPORTED_OS)
+        )
+    })
+}
+
+fn version_mismatch(what: &str, found: u32, supported: u32) -> String {
+    if found > supported {
+        format!(
+            "{} uses format version {}, newer than the OS {} format ({}) this app writes",
+            what,
+            found,
+            release_label(SUPPORTED_OS),
+            supported
+        )
+    } else {
+        format!(
+            "{} uses format version {} from an older OS; open and save the project on OS {} \
+             or later before editing it here",
+            what,
+            found,
+            release_label(SUPPORTED_OS)
+        )
+    }
+}
+
+/// Refuse to write a project file whose format isn't the supported one.
+pub fn ensure_project_writable(content: &str) -> Result<(), String> {
+    match project_format_version(content) {
+        Some(version) if version != SUPPORTED_PROJECT_VERSION => Err(version_mismatch(
+            "Project file",
+            version,
+            SUPPORTED_PROJECT_VERSION,
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Refuse to write a bank file whose format isn't the supported one.
+pub fn ensure_bank_writable(bank_file_path: &Path) -> Result<(), String> {
+    let bytes =
+        std::fs::read(bank_file_path).map_err(|e| format!("Failed to read bank file: {}", e))?;
+    match bank_format_version(&bytes) {
+        Some(version) if version != SUPPORTED_BANK_VERSION => {
+            let name = bank_file_path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            Err(version_mismatch(
+                &format!("Bank file {}", name),
+                version as u32,
+                SUPPORTED_BANK_VERSION as u32,
+            ))
+        }
+        _ => Ok(()),
+    }
+}
+
+pub fn check_project_compatibility(project_path: &str) -> Result<OsCompatibility, String> {
+    let dir = Path::new(project_path);
+    let project_file = ["project.work", "project.strd"]
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|p| p.exists())
+        .ok_or_else(|| "No project file found".to_string())?;
+    let raw =
+        std::fs::read(&project_file).map_err(|e| format!("Failed to read project file: {}", e))?;
+    let (decoded, _, _) = encoding_rs::WINDOWS_1258.decode(&raw);
+
+    let os_version = meta_field(&decoded, "OS_VERSION")
+        .unwrap_or_default()
+        .to_string();
+    let release = os_release(&os_version).map(str::to_string);
+    let project_format_version = project_format_version(&decoded);
+
+    let mut issues = Vec::new();
+    let mut writable = true;
+    if let Err(e) = ensure_project_writable(&decoded) {
+        issues.push(e);
+        writable = false;
+    }
+
+    let mut banks = Vec::new();
+    for bank in 0..16u8 {
+        let file = [
+            format!("bank{:02}.work", bank + 1),
+            format!("bank{:02}.strd", bank + 1),
+        ]
+        .into_iter()
+        .find(|name| dir.join(name).exists());
+        let Some(file) = file else { continue };
+        let path = dir.join(&file);
+        let version = std::fs::read(&path)
+            .ok()
+            .and_then(|bytes| bank_format_version(&bytes));
+        if let Err(e) = ensure_bank_writable(&path) {
+            issues.push(e);
+            writable = false;
+        }
+        banks.push(BankFormatVersion {
+            bank,
+            file,
+            version,
+        });
+    }
+
+    // Files newer than the supported layout came from an OS this app predates;
+    // a project saved by an older OS must be upgraded on the device first.
+    let newer_files = project_format_version.is_some_and(|v| v > SUPPORTED_PROJECT_VERSION)
+        || banks
+            .iter()
+            .any(|b| b.version.is_some_and(|v| v > SUPPORTED_BANK_VERSION));
+    let parsed_release = release.as_deref().and_then(parse_os_release);
+    let upgrade_required = match parsed_release {
+        Some(found) if found < SUPPORTED_OS => Some(format!(
+            "Saved by OS {}; the device needs OS {} or later to open edits made here",
+            release.as_deref().unwrap_or_default(),
+            release_label(SUPPORTED_OS)
+        )),
+        _ => None,
+    };
+    if newer_files {
+        issues.push(format!(
+            "Some files use a format newer than OS {}; the project was likely saved by a \
+             later OS and is opened read-only",
+            release_label(SUPPORTED_OS)
+        ));
+    }
+    if release.is_none() {
+        issues.push("Project file has no OS_VERSION".to_string());
+    }
+
+    Ok(OsCompatibility {
+        os_version,
+        os_release: release,
+        project_format_version,
+        banks,
+        writable,
+        upgrade_required,
+        issues,
+    })
+}
+
+#[tauri::command]
+pub async fn check_os_compatibility(path: String) -> Result<OsCompatibility, String> {
+    tauri::async_runtime::spawn_blocking(move || check_project_compatibility(&path))
+        .await
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn project(os_version: &str, version: u32) -> String {
+        format!(
+            "[META]\r\nTYPE=OCTATRACK DPS-1 PROJECT\r\nVERSION={}\r\nOS_VERSION={}\r\n[/META]\r\n\r\n[SETTINGS]\r\nWRITEPROTECTED=0\r\n[/SETTINGS]\r\n",
+            version, os_version
+        )
+    }
+
+    fn bank_bytes(version: u8) -> Vec<u8> {
+        let mut bytes = b"FORM\0\0\0\0DPS1BANK\0\0\0\0\0".to_vec();
+        bytes.push(version);
+        bytes.extend_from_slice(b"PTRN");
+        bytes
+    }
+
+    #[test]
+    fn test_parse_os_release() {
+        assert_eq!(os_release("R0177     1.40B"), Some("1.40B"));
+        assert_eq!(parse_os_release("1.40B"), Some((1, 40)));
+        assert_eq!(parse_os_release("1.25H"), Some((1, 25)));
+        assert_eq!(parse_os_release("R0177"), None);
+    }
+
+    #[test]
+    fn test_supported_project_is_writable() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("project.work"),
+            project("R0177     1.40B", SUPPORTED_PROJECT_VERSION),
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("bank01.work"),
+            bank_bytes(SUPPORTED_BANK_VERSION),
+        )
+        .unwrap();
+
+        let report = check_project_compatibility(dir.path().to_str().unwrap()).unwrap();
+        assert_eq!(report.os_release.as_deref(), Some("1.40B"));
+        assert_eq!(report.project_format_version, Some(19));
+        assert_eq!(report.banks[0].version, Some(SUPPORTED_BANK_VERSION));
+        assert!(report.writable);
+        assert!(report.upgrade_required.is_none());
+        assert!(report.issues.is_empty());
+    }
+
+    #[test]
+    fn test_other_formats_refuse_writes() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("project.work"),
+            project("R0150     1.25H", 17),
+        )
+        .unwrap();
+        fs::write(dir.path().join("bank01.work"), bank_bytes(24)).unwrap();
+
+        let report = check_project_compatibility(dir.path().to_str().unwrap()).unwrap();
+        assert!(!report.writable);
+        assert!(report.upgrade_required.is_some());
+        assert_eq!(report.issues.len(), 3);
+        assert!(ensure_bank_writable(&dir.path().join("bank01.work")).is_err());
+        assert!(ensure_project_writable(&project("R0177     1.40B", 19)).is_ok());
+        // Unknown layouts are left to the parser.
+        assert!(ensure_project_writable("[SETTINGS]\r\n[/SETTINGS]\r\n").is_ok());
+        assert_eq!(bank_format_version(b"NOT_FORM_HEADER_DATA_HERE"), None);
+    }
+
+    #[test]
+    fn test_unreadable_bank_reports_its_version() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("bank01.work");
+        let mut bytes = bank_bytes(24);
+        for _ in 0..3 {
+            bytes.extend_from_slice(b"PTRN");
+        }
+        fs::write(&path, &bytes).unwrap();
+
+        let err = read_bank_file(&path).unwrap_err();
+        assert!(
+            err.starts_with("Unsupported bank version 24 in bank01.work"),
+            "{}",
+            err
+        );
+        assert!(err.contains("patterns (4 of 16 found)"), "{}", err);
+        assert!(err.contains("parts (0 of 8 found)"), "{}", err);
+        assert!(bank_version_warning(&path).is_some());
+
+        fs::write(&path, b"garbage").unwrap();
+        assert!(read_bank_file(&path)
+            .unwrap_err()
+            .contains("not an Octatrack bank file"));
+    }
+
+    #[test]
+    fn test_supported_bank_reads_without_warning() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("bank01.work");
+        BankFile::default().to_data_file(&path).unwrap();
+        assert!(read_bank_file(&path).is_ok());
+        assert_eq!(bank_version_warning(&path), None);
+    }
+}
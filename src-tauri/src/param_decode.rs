@@ -0,0 +1,711 @@
+// Parameter decoding: turns the raw u8 values of a Part into the names and
+// display values the Octatrack shows on screen (FX type names, LFO targets,
+// machine-specific SRC labels, bipolar values, arpeggiator modes...).
+//
+// Labels follow the Octatrack manual (Appendix B for FX pages). `field` is the
+// PartData field path the value comes from, so the frontend can map a decoded
+// entry back to the value it edits.
+
+use crate::project_reader::{read_parts_data, PartData};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DecodedParam {
+    pub page: String,    // "SRC", "AMP SETUP", "FX1", "ARP", ...
+    pub field: String,   // PartData field, e.g. "fx1_param3", "machine_params.ptch"
+    pub label: String,   // on-screen label, e.g. "TIME"
+    pub raw: u8,         // value as stored in the bank file
+    pub display: String, // value as the Octatrack shows it, e.g. "-12", "SINE", "C4"
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecodedTrack {
+    pub track_id: u8,       // 0-7
+    pub track_type: String, // "Audio" or "MIDI"
+    pub machine: Option<String>,
+    pub fx1: Option<String>,
+    pub fx2: Option<String>,
+    pub params: Vec<DecodedParam>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecodedPart {
+    pub part_id: u8,
+    pub tracks: Vec<DecodedTrack>, // T1-T8 then M1-M8
+}
+
+/// Audio machine names, indexed by the type id stored in parts.
+pub(crate) const MACHINE_TYPES: [&str; 5] = ["Static", "Flex", "Thru", "Neighbor", "Pickup"];
+
+/// FX type ids as stored in parts, with their display names (same table as
+/// the Parts panel).
+pub(crate) const FX_TYPES: [(u8, &str); 15] = [
+    (0, "Off"),
+    (4, "Filter"),
+    (5, "Spatializer"),
+    (8, "Delay"),
+    (12, "EQ"),
+    (13, "DJ EQ"),
+    (16, "Phaser"),
+    (17, "Flanger"),
+    (18, "Chorus"),
+    (19, "Comb Filter"),
+    (20, "Plate Reverb"),
+    (21, "Spring Reverb"),
+    (22, "Dark Reverb"),
+    (24, "Compressor"),
+    (28, "Lo-Fi"),
+];
+
+/// Display name of an audio machine type id.
+pub fn machine_type_name(machine_type: u8) -> &'static str {
+    MACHINE_TYPES
+        .get(machine_type as usize)
+        .copied()
+        .unwrap_or("Unknown")
+}
+
+/// Display name of an FX type id.
+pub fn fx_type_name(fx_type: u8) -> String {
+    FX_TYPES
+        .iter()
+        .find(|(id, _)| *id == fx_type)
+        .map(|(_, name)| name.to_string())
+        .unwrap_or_else(|| format!("FX {}", fx_type))
+}
+
+/// MAIN page labels of an FX type ("" = unused knob).
+pub fn fx_main_labels(fx_type: u8) -> [&'static str; 6] {
+    match fx_type {
+        0 => ["", "", "", "", "", ""],
+        4 => ["BASE", "WIDTH", "Q", "DEPTH", "ATK", "DEC"],
+        5 => ["INP", "DPTH", "WDTH", "HP", "LP", "SEND"],
+        8 => ["TIME", "FB", "VOL", "BASE", "WDTH", "SEND"],
+        12 => ["FRQ1", "GN1", "Q1", "FRQ2", "GN2", "Q2"],
+        13 => ["LS F", "HS F", "LOWG", "MIDG", "HI G", ""],
+        16 => ["CNTR", "DEP", "SPD", "FB", "WID", "MIX"],
+        17 | 18 => ["DEL", "DEP", "SPD", "FB", "WID", "MIX"],
+        19 => ["PTCH", "TUNE", "LP", "FB", "MIX", ""],
+        20 => ["TIME", "DAMP", "GATE", "HP", "LP", "MIX"],
+        21 => ["TIME", "HP", "LP", "MIX", "", ""],
+        22 => ["TIME", "SHVG", "SHVF", "HP", "LP", "MIX"],
+        24 => ["ATK", "REL", "THRS", "RAT", "GAIN", "MIX"],
+        28 => ["DIST", "AMF", "SRR", "BRR", "AMD", ""],
+        _ => ["P1", "P2", "P3", "P4", "P5", "P6"],
+    }
+}
+
+/// SETUP page labels of an FX type ("" = unused knob).
+pub fn fx_setup_labels(fx_type: u8) -> [&'static str; 6] {
+    match fx_type {
+        0 | 13 | 17 | 19 => ["", "", "", "", "", ""],
+        4 => ["HP", "LP", "ENV", "HOLD", "Q", "DIST"],
+        5 => ["PHSE", "M/S", "MG", "SG", "", ""],
+        8 => ["X", "TAPE", "DIR", "SYNC", "LOCK", "PASS"],
+        12 => ["TYP1", "TYP2", "", "", "", ""],
+        16 => ["NUM", "", "", "", "", ""],
+        18 => ["TAPS", "FBLP", "", "", "", ""],
+        20 => ["GVOL", "BAL", "MONO", "MIXF", "", ""],
+        21 => ["TYPE", "BAL", "", "", "", ""],
+        22 => ["PRE", "BAL", "MONO", "MIXF", "", ""],
+        24 => ["RMS", "", "", "", "", ""],
+        28 => ["AMPH", "", "", "", "", ""],
+        _ => ["S1", "S2", "S3", "S4", "S5", "S6"],
+    }
+}
+
+/// SRC MAIN labels per machine (Neighbor has no source parameters).
+pub fn src_main_labels(machine_type: &str) -> [&'static str; 6] {
+    match machine_type {
+        "Static" | "Flex" => ["PTCH", "STRT", "LEN", "RATE", "RTRG", "RTIM"],
+        "Thru" => ["INAB", "VOL", "INCD", "VOL", "", ""],
+        "Pickup" => ["PTCH", "DIR", "LEN", "", "GAIN", "OP"],
+        _ => ["", "", "", "", "", ""],
+    }
+}
+
+/// SRC SETUP labels per machine.
+pub fn src_setup_labels(machine_type: &str) -> [&'static str; 6] {
+    match machine_type {
+        "Static" | "Flex" => ["LOOP", "SLIC", "LEN", "RATE", "TSTR", "TSNS"],
+        _ => ["", "", "", "", "", ""],
+    }
+}
+
+const LFO_WAVES: [&str; 8] = ["TRI", "SINE", "SQR", "SAW", "EXP", "RAMP", "RAND", "DSGN"];
+const LFO_TRIG_MODES: [&str; 5] = ["FREE", "TRIG", "HOLD", "ONE", "HALF"];
+const LFO_MULTIPLIERS: [&str; 12] = [
+    "1", "2", "4", "8", "16", "32", "64", "128", "256", "512", "1K", "2K",
+];
+const ARP_MODES: [&str; 6] = ["OFF", "UP", "DOWN", "CYC", "SHUF", "RND"];
+const FX_ENV_TRIG: [&str; 4] = ["ANLG", "RTRG", "R+T", "TTRG"];
+const AMP_ENVELOPES: [&str; 2] = ["ANLG", "LIN"];
+const REC_IN_AB: [&str; 4] = ["-", "A", "B", "A+B"];
+const REC_IN_CD: [&str; 4] = ["-", "C", "D", "C+D"];
+const REC_TRIG_MODES: [&str; 3] = ["ONE", "ONE2", "HOLD"];
+const REC_SRC3: [&str; 11] = [
+    "-", "MAIN", "CUE", "T1", "T2", "T3", "T4", "T5", "T6", "T7", "T8",
+];
+pub(crate) const NOTE_NAMES: [&str; 12] = [
+    "C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B",
+];
+
+fn table_name(table: &[&str], raw: u8) -> String {
+    table
+        .get(raw as usize)
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| raw.to_string())
+}
+
+/// Knob centred on 64: shown as -64..+63.
+fn bipolar(raw: u8) -> String {
+    let value = raw as i16 - 64;
+    if value > 0 {
+        format!("+{}", value)
+    } else {
+        value.to_string()
+    }
+}
+
+/// MIDI note number as a note name, C4 = 60.
+pub fn note_name(raw: u8) -> String {
+    format!(
+        "{}{}",
+        NOTE_NAMES[(raw % 12) as usize],
+        (raw / 12) as i16 - 1
+    )
+}
+
+/// Target of an audio-track LFO. The PMTR value walks the parameter pages in
+/// order: SRC, AMP, LFO (speeds then depths), FX1, FX2, six knobs each.
+pub fn audio_lfo_target(raw: u8, machine_type: &str, fx1_type: u8, fx2_type: u8) -> String {
+    const AMP: [&str; 6] = ["ATK", "HOLD", "REL", "VOL", "BAL", "F"];
+    const LFO: [&str; 6] = ["SPD1", "SPD2", "SPD3", "DEP1", "DEP2", "DEP3"];
+    let (page, labels) = match raw / 6 {
+        0 => ("SRC", src_main_labels(machine_type)),
+        1 => ("AMP", AMP),
+        2 => ("LFO", LFO),
+        3 => ("FX1", fx_main_labels(fx1_type)),
+        4 => ("FX2", fx_main_labels(fx2_type)),
+        _ => return raw.to_string(),
+    };
+    match labels[(raw % 6) as usize] {
+        "" => format!("{} {}", page, raw % 6 + 1),
+        label => format!("{} {}", page, label),
+    }
+}
+
+/// Target of a MIDI-track LFO: NOTE, ARP, LFO, CTRL1, CTRL2 pages in order.
+pub fn midi_lfo_target(raw: u8) -> String {
+    const PAGES: [(&str, [&str; 6]); 5] = [
+        ("NOTE", ["NOTE", "VEL", "LEN", "NOT2", "NOT3", "NOT4"]),
+        ("ARP", ["TRAN", "LEG", "MODE", "SPD", "RNGE", "NLEN"]),
+        ("LFO", ["SPD1", "SPD2", "SPD3", "DEP1", "DEP2", "DEP3"]),
+        ("CTRL1", ["PB", "AT", "CC1", "CC2", "CC3", "CC4"]),
+        ("CTRL2", ["CC5", "CC6", "CC7", "CC8", "CC9", "CC10"]),
+    ];
+    match PAGES.get((raw / 6) as usize) {
+        Some((page, labels)) => format!("{} {}", page, labels[(raw % 6) as usize]),
+        None => raw.to_string(),
+    }
+}
+
+/// Collects decoded parameters of one track, skipping unused knobs.
+struct ParamList(Vec<DecodedParam>);
+
+impl ParamList {
+    fn push(&mut self, page: &str, field: &str, label: &str, raw: u8, display: String) {
+        if label.is_empty() {
+            return;
+        }
+        self.0.push(DecodedParam {
+            page: page.to_string(),
+            field: field.to_string(),
+            label: label.to_string(),
+            raw,
+            display,
+        });
+    }
+
+    fn plain(&mut self, page: &str, field: &str, label: &str, raw: u8) {
+        self.push(page, field, label, raw, raw.to_string());
+    }
+
+    fn lfo(&mut self, lfo: &crate::project_reader::PartTrackLfo, target: impl Fn(u8) -> String) {
+        let speeds = [lfo.spd1, lfo.spd2, lfo.spd3];
+        let depths = [lfo.dep1, lfo.dep2, lfo.dep3];
+        for (n, (spd, dep)) in (1..).zip(speeds.into_iter().zip(depths)) {
+            self.push(
+                "LFO",
+                &format!("spd{}", n),
+                &format!("SPD{}", n),
+                spd,
+                bipolar(spd),
+            );
+            self.push(
+                "LFO",
+                &format!("dep{}", n),
+                &format!("DEP{}", n),
+                dep,
+                bipolar(dep),
+            );
+        }
+        let setups = [
+            (lfo.lfo1_pmtr, lfo.lfo1_wave, lfo.lfo1_mult, lfo.lfo1_trig),
+            (lfo.lfo2_pmtr, lfo.lfo2_wave, lfo.lfo2_mult, lfo.lfo2_trig),
+            (lfo.lfo3_pmtr, lfo.lfo3_wave, lfo.lfo3_mult, lfo.lfo3_trig),
+        ];
+        for (n, (pmtr, wave, mult, trig)) in (1..).zip(setups) {
+            let page = "LFO SETUP";
+            self.push(
+                page,
+                &format!("lfo{}_pmtr", n),
+                &format!("PMTR{}", n),
+                pmtr,
+                target(pmtr),
+            );
+            self.push(
+                page,
+                &format!("lfo{}_wave", n),
+                &format!("WAVE{}", n),
+                wave,
+                table_name(&LFO_WAVES, wave),
+            );
+            self.push(
+                page,
+                &format!("lfo{}_mult", n),
+                &format!("MULT{}", n),
+                mult,
+                table_name(&LFO_MULTIPLIERS, mult),
+            );
+            self.push(
+                page,
+                &format!("lfo{}_trig", n),
+                &format!("TRIG{}", n),
+                trig,
+                table_name(&LFO_TRIG_MODES, trig),
+            );
+        }
+    }
+}
+
+fn decode_audio_track(part: &PartData, track: usize) -> DecodedTrack {
+    let mut params = ParamList(Vec::new());
+    let machine = part.machines.get(track);
+    let machine_type = machine.map(|m| m.machine_type.as_str()).unwrap_or("");
+    let fx = part.fxs.get(track);
+
+    if let Some(m) = machine {
+        let p = &m.machine_params;
+        let (main, main_fields): ([Option<u8>; 6], [&str; 6]) = match machine_type {
+            "Thru" => (
+                [p.in_ab, p.vol_ab, p.in_cd, p.vol_cd, None, None],
+                ["in_ab", "vol_ab", "in_cd", "vol_cd", "", ""],
+            ),
+            "Pickup" => (
+                [p.ptch, p.dir, p.len, None, p.gain, p.op],
+                ["ptch", "dir", "len", "", "gain", "op"],
+            ),
+            _ => (
+                [p.ptch, p.strt, p.len, p.rate, p.rtrg, p.rtim],
+                ["ptch", "strt", "len", "rate", "rtrg", "rtim"],
+            ),
+        };
+        for ((raw, field), label) in main
+            .iter()
+            .zip(main_fields)
+            .zip(src_main_labels(machine_type))
+        {
+            if let Some(raw) = *raw {
+                let display = match label {
+                    "PTCH" | "GAIN" => bipolar(raw),
+                    _ => raw.to_string(),
+                };
+                params.push(
+                    "SRC",
+                    &format!("machine_params.{}", field),
+                    label,
+                    raw,
+                    display,
+                );
+            }
+        }
+        let s = &m.machine_setup;
+        let setup = [s.xloop, s.slic, s.len, s.rate, s.tstr, s.tsns];
+        let setup_fields = ["xloop", "slic", "len", "rate", "tstr", "tsns"];
+        for ((raw, field), label) in setup
+            .iter()
+            .zip(setup_fields)
+            .zip(src_setup_labels(machine_type))
+        {
+            if let Some(raw) = *raw {
+                let display = match label {
+                    "SLIC" => if raw == 0 { "OFF" } else { "ON" }.to_string(),
+                    _ => raw.to_string(),
+                };
+                params.push(
+                    "SRC SETUP",
+                    &format!("machine_setup.{}", field),
+                    label,
+                    raw,
+                    display,
+                );
+            }
+        }
+    }
+
+    if let Some(a) = part.amps.get(track) {
+        params.plain("AMP", "atk", "ATK", a.atk);
+        params.plain("AMP", "hold", "HOLD", a.hold);
+        params.plain("AMP", "rel", "REL", a.rel);
+        params.plain("AMP", "vol", "VOL", a.vol);
+        params.push("AMP", "bal", "BAL", a.bal, bipolar(a.bal));
+        params.plain("AMP", "f", "F", a.f);
+        params.push(
+            "AMP SETUP",
+            "amp_setup_amp",
+            "AMP",
+            a.amp_setup_amp,
+            table_name(&AMP_ENVELOPES, a.amp_setup_amp),
+        );
+        params.plain("AMP SETUP", "amp_setup_sync", "SYNC", a.amp_setup_sync);
+        params.plain("AMP SETUP", "amp_setup_atck", "ATCK", a.amp_setup_atck);
+        params.push(
+            "AMP SETUP",
+            "amp_setup_fx1",
+            "FX1",
+            a.amp_setup_fx1,
+            table_name(&FX_ENV_TRIG, a.amp_setup_fx1),
+        );
+        params.push(
+            "AMP SETUP",
+            "amp_setup_fx2",
+            "FX2",
+            a.amp_setup_fx2,
+            table_name(&FX_ENV_TRIG, a.amp_setup_fx2),
+        );
+    }
+
+    if let Some(lfo) = part.lfos.get(track) {
+        let (fx1, fx2) = fx.map(|f| (f.fx1_type, f.fx2_type)).unwrap_or((0, 0));
+        params.lfo(lfo, |raw| audio_lfo_target(raw, machine_type, fx1, fx2));
+    }
+
+    if let Some(f) = fx {
+        for (slot, fx_type, main, setup) in [
+            (
+                1,
+                f.fx1_type,
+                [
+                    f.fx1_param1,
+                    f.fx1_param2,
+                    f.fx1_param3,
+                    f.fx1_param4,
+                    f.fx1_param5,
+                    f.fx1_param6,
+                ],
+                [
+                    f.fx1_setup1,
+                    f.fx1_setup2,
+                    f.fx1_setup3,
+                    f.fx1_setup4,
+                    f.fx1_setup5,
+                    f.fx1_setup6,
+                ],
+            ),
+            (
+                2,
+                f.fx2_type,
+                [
+                    f.fx2_param1,
+                    f.fx2_param2,
+                    f.fx2_param3,
+                    f.fx2_param4,
+                    f.fx2_param5,
+                    f.fx2_param6,
+                ],
+                [
+                    f.fx2_setup1,
+                    f.fx2_setup2,
+                    f.fx2_setup3,
+                    f.fx2_setup4,
+                    f.fx2_setup5,
+                    f.fx2_setup6,
+                ],
+            ),
+        ] {
+            let page = format!("FX{}", slot);
+            for (i, label) in fx_main_labels(fx_type).iter().enumerate() {
+                params.plain(&page, &format!("fx{}_param{}", slot, i + 1), label, main[i]);
+            }
+            let setup_page = format!("FX{} SETUP", slot);
+            for (i, label) in fx_setup_labels(fx_type).iter().enumerate() {
+                params.plain(
+                    &setup_page,
+                    &format!("fx{}_setup{}", slot, i + 1),
+                    label,
+                    setup[i],
+                );
+            }
+        }
+    }
+
+    if let Some(r) = part.recorders.get(track) {
+        let page = "REC SETUP 1";
+        params.push(
+            page,
+            "in_ab",
+            "INAB",
+            r.in_ab,
+            table_name(&REC_IN_AB, r.in_ab),
+        );
+        params.push(
+            page,
+            "in_cd",
+            "INCD",
+            r.in_cd,
+            table_name(&REC_IN_CD, r.in_cd),
+        );
+        params.plain(page, "rlen", "RLEN", r.rlen);
+        params.push(
+            page,
+            "trig",
+            "TRIG",
+            r.trig,
+            table_name(&REC_TRIG_MODES, r.trig),
+        );
+        params.push(page, "src3", "SRC3", r.src3, table_name(&REC_SRC3, r.src3));
+        params.push(
+            page,
+            "xloop",
+            "LOOP",
+            r.xloop,
+            if r.xloop == 0 { "OFF" } else { "ON" }.to_string(),
+        );
+        let page = "REC SETUP 2";
+        params.plain(page, "fin", "FIN", r.fin);
+        params.plain(page, "fout", "FOUT", r.fout);
+        params.plain(page, "ab", "AB", r.ab);
+        params.plain(page, "qrec", "QREC", r.qrec);
+        params.plain(page, "qpl", "QPL", r.qpl);
+        params.plain(page, "cd", "CD", r.cd);
+    }
+
+    DecodedTrack {
+        track_id: track as u8,
+        track_type: "Audio".to_string(),
+        machine: machine.map(|m| m.machine_type.clone()),
+        fx1: fx.map(|f| fx_type_name(f.fx1_type)),
+        fx2: fx.map(|f| fx_type_name(f.fx2_type)),
+        params: params.0,
+    }
+}
+
+fn decode_midi_track(part: &PartData, track: usize) -> DecodedTrack {
+    let mut params = ParamList(Vec::new());
+
+    if let Some(n) = part.midi_notes.get(track) {
+        params.push("NOTE", "note", "NOTE", n.note, note_name(n.note));
+        params.plain("NOTE", "vel", "VEL", n.vel);
+        params.plain("NOTE", "len", "LEN", n.len);
+        // Chord notes are offsets from NOTE.
+        params.push("NOTE", "not2", "NOT2", n.not2, bipolar(n.not2));
+        params.push("NOTE", "not3", "NOT3", n.not3, bipolar(n.not3));
+        params.push("NOTE", "not4", "NOT4", n.not4, bipolar(n.not4));
+        params.push(
+            "NOTE SETUP",
+            "chan",
+            "CHAN",
+            n.chan,
+            (n.chan as u16 + 1).to_string(),
+        );
+        params.plain("NOTE SETUP", "bank", "BANK", n.bank);
+        params.plain("NOTE SETUP", "prog", "PROG", n.prog);
+        params.plain("NOTE SETUP", "sbnk", "SBNK", n.sbnk);
+    }
+
+    if let Some(a) = part.midi_arps.get(track) {
+        params.push("ARP", "tran", "TRAN", a.tran, bipolar(a.tran));
+        params.push(
+            "ARP",
+            "leg",
+            "LEG",
+            a.leg,
+            if a.leg == 0 { "OFF" } else { "ON" }.to_string(),
+        );
+        params.push(
+            "ARP",
+            "mode",
+            "MODE",
+            a.mode,
+            table_name(&ARP_MODES, a.mode),
+        );
+        params.plain("ARP", "spd", "SPD", a.spd);
+        params.plain("ARP", "rnge", "RNGE", a.rnge);
+        params.plain("ARP", "nlen", "NLEN", a.nlen);
+        params.plain("ARP SETUP", "len", "LEN", a.len);
+        params.plain("ARP SETUP", "key", "KEY", a.key);
+    }
+
+    if let Some(lfo) = part.midi_lfos.get(track) {
+        params.lfo(lfo, midi_lfo_target);
+    }
+
+    if let Some(c) = part.midi_ctrl1s.get(track) {
+        params.push("CTRL1", "pb", "PB", c.pb, bipolar(c.pb));
+        params.plain("CTRL1", "at", "AT", c.at);
+        for (i, (value, num)) in [
+            (c.cc1, c.cc1_num),
+            (c.cc2, c.cc2_num),
+            (c.cc3, c.cc3_num),
+            (c.cc4, c.cc4_num),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let n = i + 1;
+            params.plain("CTRL1", &format!("cc{}", n), &format!("CC{}", n), value);
+            params.push(
+                "CTRL1 SETUP",
+                &format!("cc{}_num", n),
+                &format!("VAR{}", n),
+                num,
+                format!("CC {}", num),
+            );
+        }
+    }
+
+    if let Some(c) = part.midi_ctrl2s.get(track) {
+        for (i, (value, num)) in [
+            (c.cc5, c.cc5_num),
+            (c.cc6, c.cc6_num),
+            (c.cc7, c.cc7_num),
+            (c.cc8, c.cc8_num),
+            (c.cc9, c.cc9_num),
+            (c.cc10, c.cc10_num),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let n = i + 5;
+            params.plain("CTRL2", &format!("cc{}", n), &format!("CC{}", n), value);
+            params.push(
+                "CTRL2 SETUP",
+                &format!("cc{}_num", n),
+                &format!("VAR{}", n),
+                num,
+                format!("CC {}", num),
+            );
+        }
+    }
+
+    DecodedTrack {
+        track_id: track as u8,
+        track_type: "MIDI".to_string(),
+        machine: None,
+        fx1: None,
+        fx2: None,
+        params: params.0,
+    }
+}
+
+/// Every parameter of a part with its on-screen label and display value.
+pub fn decode_part(part: &PartData) -> DecodedPart {
+    let mut tracks: Vec<DecodedTrack> = (0..8).map(|t| decode_audio_track(part, t)).collect();
+    tracks.extend((0..8).map(|t| decode_midi_track(part, t)));
+    DecodedPart {
+        part_id: part.part_id,
+        tracks,
+    }
+}
+
+#[tauri::command]
+pub async fn decode_part_parameters(
+    path: String,
+    bank_id: String,
+) -> Result<Vec<DecodedPart>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        Ok(read_parts_data(&path, &bank_id)?
+            .parts
+            .iter()
+            .map(decode_part)
+            .collect())
+    })
+    .await
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ot_tools_io::{BankFile, OctatrackFileIO};
+    use tempfile::TempDir;
+
+    fn decoded_bank(modifier: impl FnOnce(&mut BankFile)) -> Vec<DecodedPart> {
+        let dir = TempDir::new().unwrap();
+        let mut bank = BankFile::default();
+        modifier(&mut bank);
+        bank.to_data_file(&dir.path().join("bank01.work")).unwrap();
+        read_parts_data(&dir.path().to_string_lossy(), "A")
+            .unwrap()
+            .parts
+            .iter()
+            .map(decode_part)
+            .collect()
+    }
+
+    fn param<'a>(track: &'a DecodedTrack, field: &str) -> &'a DecodedParam {
+        track.params.iter().find(|p| p.field == field).unwrap()
+    }
+
+    #[test]
+    fn test_fx_pages_use_type_specific_labels() {
+        let parts = decoded_bank(|bank| bank.parts.unsaved.0[0].audio_track_fx1[2] = 20);
+        let track = &parts[0].tracks[2];
+        assert_eq!(track.fx1.as_deref(), Some("Plate Reverb"));
+        let p = param(track, "fx1_param2");
+        assert_eq!((p.page.as_str(), p.label.as_str()), ("FX1", "DAMP"));
+    }
+
+    #[test]
+    fn test_recorder_setup_pages() {
+        let parts = decoded_bank(|bank| {
+            let rec = &mut bank.parts.unsaved.0[0].recorder_setup[1];
+            rec.src.in_ab = 3;
+            rec.src.src3 = 4;
+        });
+        let track = &parts[0].tracks[1];
+        let p = param(track, "in_ab");
+        assert_eq!(
+            (p.page.as_str(), p.display.as_str()),
+            ("REC SETUP 1", "A+B")
+        );
+        assert_eq!(param(track, "src3").display, "T2");
+        assert_eq!(param(track, "qrec").page, "REC SETUP 2");
+    }
+
+    #[test]
+    fn test_value_formatting() {
+        assert_eq!(bipolar(64), "0");
+        assert_eq!(bipolar(52), "-12");
+        assert_eq!(bipolar(127), "+63");
+        assert_eq!(note_name(60), "C4");
+        assert_eq!(note_name(0), "C-1");
+        assert_eq!(audio_lfo_target(0, "Flex", 0, 0), "SRC PTCH");
+        assert_eq!(audio_lfo_target(19, "Flex", 8, 0), "FX1 FB");
+        assert_eq!(midi_lfo_target(14), "LFO SPD3");
+        assert_eq!(table_name(&ARP_MODES, 1), "UP");
+        assert_eq!(table_name(&ARP_MODES, 42), "42");
+    }
+
+    #[test]
+    fn test_midi_tracks_follow_audio_tracks() {
+        let parts = decoded_bank(|_| {});
+        assert_eq!(parts.len(), 4);
+        let tracks = &parts[0].tracks;
+        assert_eq!(tracks.len(), 16);
+        assert_eq!(tracks[8].track_type, "MIDI");
+        assert_eq!(param(&tracks[8], "mode").page, "ARP");
+        assert!(tracks[0].params.iter().all(|p| !p.label.is_empty()));
+    }
+}
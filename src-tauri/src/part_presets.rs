@@ -0,0 +1,297 @@
+// Part presets: named snapshots of a Part's sound design (machines, amps,
+// LFOs, FX, recorder and MIDI setups), or of a subset of its tracks, that can
+// be applied to any part of any project.
+//
+// Presets live in the app's data directory, never on the card. Applying one
+// goes through `save_parts_data`, so only the target's working copy
+// (parts.unsaved) changes and "Reload Part" still restores the old sound.
+
+use crate::project_reader::{read_parts_data, save_parts_data, PartData};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+static PRESETS_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PartPreset {
+    pub name: String,
+    pub created_at: String,
+    pub tracks: Option<Vec<u8>>, // 0-7 audio, 8-15 MIDI; None = whole part
+    pub part: PartData,
+}
+
+/// What the preset list shows; the part data itself is only read on apply.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PartPresetInfo {
+    pub name: String,
+    pub created_at: String,
+    pub tracks: Option<Vec<u8>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct PresetStore {
+    #[serde(default)]
+    presets: BTreeMap<String, PartPreset>,
+}
+
+/// Default store location: `<data dir>/octatrack-manager/part_presets.json`.
+pub fn default_presets_path() -> Result<PathBuf, String> {
+    crate::app_data::data_path("part_presets.json")
+}
+
+fn load_store(presets_path: &Path) -> Result<PresetStore, String> {
+    if !presets_path.exists() {
+        return Ok(PresetStore::default());
+    }
+    let data =
+        fs::read_to_string(presets_path).map_err(|e| format!("Failed to read presets: {}", e))?;
+    serde_json::from_str(&data).map_err(|e| format!("Failed to parse presets: {}", e))
+}
+
+fn save_store(presets_path: &Path, store: &PresetStore) -> Result<(), String> {
+    crate::app_data::save_json(presets_path, store, "presets")
+}
+
+fn check_tracks(tracks: &Option<Vec<u8>>) -> Result<(), String> {
+    match tracks {
+        Some(tracks) if tracks.is_empty() => Err("No tracks selected".to_string()),
+        Some(tracks) if tracks.iter().any(|&t| t > 15) => {
+            Err("Track indices must be between 0 and 15".to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+fn includes(tracks: &Option<Vec<u8>>, track: u8) -> bool {
+    tracks.as_ref().is_none_or(|t| t.contains(&track))
+}
+
+/// Copy the preset's tracks into `target`, keeping the target's other tracks.
+fn merge_tracks(target: &mut PartData, preset: &PartData, tracks: &Option<Vec<u8>>) {
+    fn copy<T: Clone>(dst: &mut [T], src: &[T], i: usize) {
+        if let (Some(d), Some(s)) = (dst.get_mut(i), src.get(i)) {
+            *d = s.clone();
+        }
+    }
+    for i in 0..8usize {
+        if includes(tracks, i as u8) {
+            copy(&mut target.machines, &preset.machines, i);
+            copy(&mut target.amps, &preset.amps, i);
+            copy(&mut target.lfos, &preset.lfos, i);
+            copy(&mut target.fxs, &preset.fxs, i);
+            copy(&mut target.recorders, &preset.recorders, i);
+        }
+        if includes(tracks, i as u8 + 8) {
+            copy(&mut target.midi_notes, &preset.midi_notes, i);
+            copy(&mut target.midi_arps, &preset.midi_arps, i);
+            copy(&mut target.midi_lfos, &preset.midi_lfos, i);
+            copy(&mut target.midi_ctrl1s, &preset.midi_ctrl1s, i);
+            copy(&mut target.midi_ctrl2s, &preset.midi_ctrl2s, i);
+        }
+    }
+}
+
+fn find_part(project_path: &str, bank_id: &str, part_id: u8) -> Result<PartData, String> {
+    if part_id > 3 {
+        return Err(format!("Invalid part ID: {} (must be 0-3)", part_id));
+    }
+    read_parts_data(project_path, bank_id)?
+        .parts
+        .into_iter()
+        .find(|p| p.part_id == part_id)
+        .ok_or_else(|| format!("Failed to find part {}", part_id))
+}
+
+pub fn list_presets_in(presets_path: &Path) -> Result<Vec<PartPresetInfo>, String> {
+    Ok(load_store(presets_path)?
+        .presets
+        .into_values()
+        .map(|p| PartPresetInfo {
+            name: p.name,
+            created_at: p.created_at,
+            tracks: p.tracks,
+        })
+        .collect())
+}
+
+/// Save a part of a project (or only `tracks` of it) as preset `name`,
+/// replacing any preset of the same name.
+pub fn save_preset_in(
+    presets_path: &Path,
+    name: &str,
+    project_path: &str,
+    bank_id: &str,
+    part_id: u8,
+    tracks: Option<Vec<u8>>,
+) -> Result<PartPresetInfo, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Preset name cannot be empty".to_string());
+    }
+    check_tracks(&tracks)?;
+    let part = find_part(project_path, bank_id, part_id)?;
+
+    let _guard = PRESETS_LOCK.lock().unwrap();
+    let mut store = load_store(presets_path)?;
+    let preset = PartPreset {
+        name: name.to_string(),
+        created_at: chrono::Local::now().to_rfc3339(),
+        tracks,
+        part,
+    };
+    let info = PartPresetInfo {
+        name: preset.name.clone(),
+        created_at: preset.created_at.clone(),
+        tracks: preset.tracks.clone(),
+    };
+    store.presets.insert(name.to_string(), preset);
+    save_store(presets_path, &store)?;
+    Ok(info)
+}
+
+pub fn delete_preset_in(presets_path: &Path, name: &str) -> Result<(), String> {
+    let _guard = PRESETS_LOCK.lock().unwrap();
+    let mut store = load_store(presets_path)?;
+    if store.presets.remove(name).is_none() {
+        return Err(format!("Preset not found: {}", name));
+    }
+    save_store(presets_path, &store)
+}
+
+/// Apply preset `name` to a part. A track-subset preset only overwrites
+/// those tracks. Slot assignments are applied as stored: they refer to the
+/// sample slots of the target project. Returns the updated part.
+pub fn apply_preset_in(
+    presets_path: &Path,
+    name: &str,
+    project_path: &str,
+    bank_id: &str,
+    part_id: u8,
+) -> Result<PartData, String> {
+    let preset = load_store(presets_path)?
+        .presets
+        .remove(name)
+        .ok_or_else(|| format!("Preset not found: {}", name))?;
+    let mut part = find_part(project_path, bank_id, part_id)?;
+    merge_tracks(&mut part, &preset.part, &preset.tracks);
+    save_parts_data(
+        project_path,
+        bank_id,
+        vec![part],
+        crate::write_verify::verify_by_default(),
+    )?;
+    find_part(project_path, bank_id, part_id)
+}
+
+#[tauri::command]
+pub fn list_part_presets() -> Result<Vec<PartPresetInfo>, String> {
+    list_presets_in(&default_presets_path()?)
+}
+
+#[tauri::command]
+pub async fn save_part_preset(
+    name: String,
+    path: String,
+    bank_id: String,
+    part_id: u8,
+    tracks: Option<Vec<u8>>,
+) -> Result<PartPresetInfo, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        save_preset_in(
+            &default_presets_path()?,
+            &name,
+            &path,
+            &bank_id,
+            part_id,
+            tracks,
+        )
+    })
+    .await
+    .unwrap()
+}
+
+#[tauri::command]
+pub fn delete_part_preset(name: String) -> Result<(), String> {
+    delete_preset_in(&default_presets_path()?, &name)
+}
+
+#[tauri::command]
+pub async fn apply_part_preset(
+    name: String,
+    path: String,
+    bank_id: String,
+    part_id: u8,
+) -> Result<PartData, String> {
+    crate::fs_scope::ensure_allowed(&path)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::edit_journal::record_edit(
+            &path,
+            "apply_part_preset",
+            &crate::edit_journal::bank_files_for_id(&bank_id),
+            || apply_preset_in(&default_presets_path()?, &name, &path, &bank_id, part_id),
+        )
+    })
+    .await
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::blank_project;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_save_list_apply_delete() {
+        let store_dir = TempDir::new().unwrap();
+        let store = store_dir.path().join("presets.json");
+        let project = blank_project();
+        let path = project.path().to_str().unwrap();
+
+        let mut part = find_part(path, "A", 0).unwrap();
+        part.amps[2].atk = 99;
+        part.amps[3].atk = 77;
+        part.fxs[2].fx1_type = 4;
+        save_parts_data(path, "A", vec![part], true).unwrap();
+
+        save_preset_in(&store, "pad", path, "A", 0, Some(vec![2])).unwrap();
+        save_preset_in(&store, "  full  ", path, "A", 0, None).unwrap();
+        let names: Vec<String> = list_presets_in(&store)
+            .unwrap()
+            .into_iter()
+            .map(|p| p.name)
+            .collect();
+        assert_eq!(names, vec!["full", "pad"]);
+
+        // Track 2 only lands on part 3; its track 3 keeps its own amp.
+        let applied = apply_preset_in(&store, "pad", path, "A", 2).unwrap();
+        assert_eq!(applied.amps[2].atk, 99);
+        assert_eq!(applied.fxs[2].fx1_type, 4);
+        assert_ne!(applied.amps[3].atk, 77);
+
+        let applied = apply_preset_in(&store, "full", path, "A", 1).unwrap();
+        assert_eq!((applied.amps[2].atk, applied.amps[3].atk), (99, 77));
+
+        delete_preset_in(&store, "pad").unwrap();
+        assert!(delete_preset_in(&store, "pad").is_err());
+        assert!(apply_preset_in(&store, "pad", path, "A", 0).is_err());
+    }
+
+    #[test]
+    fn test_invalid_presets_are_rejected() {
+        let store_dir = TempDir::new().unwrap();
+        let store = store_dir.path().join("presets.json");
+        let project = blank_project();
+        let path = project.path().to_str().unwrap();
+
+        assert!(save_preset_in(&store, " ", path, "A", 0, None).is_err());
+        assert!(save_preset_in(&store, "x", path, "A", 4, None).is_err());
+        assert!(save_preset_in(&store, "x", path, "A", 0, Some(vec![16])).is_err());
+        assert!(save_preset_in(&store, "x", path, "A", 0, Some(vec![])).is_err());
+        assert!(!store.exists());
+    }
+}
@@ -0,0 +1,423 @@
+// Offline audio preview of a pattern: bounces its audio tracks to a WAV so a
+// pattern can be auditioned roughly without the hardware connected.
+//
+// Each trigger trig of a Static or Flex track starts its slot's sample (the
+// part's slot, or the trig's slot lock) at the trig's micro-timed, swung
+// position, at the pattern tempo when the project uses pattern tempos and the
+// project tempo otherwise. Per trig the PTCH, STRT, RATE, VOL and BAL values
+// of the part, or their p-locks, are applied: PTCH in semitones, STRT as a
+// fraction of the sample, RATE as a speed factor (127 = 1x), VOL as a gain
+// (64 = unity) and BAL as an equal-power pan. Tracks are monophonic: a trig
+// cuts the previous one. Trig conditions, repeats, the amp envelope, LFOs,
+// FX and Thru/Neighbor/Pickup machines are not rendered.
+
+use crate::audio_pool::{decode_audio_file, DecodedAudio, OCTATRACK_SAMPLE_RATE};
+use crate::midi_file::{bank_index, micro_timing, SCALE_STEP_FACTORS};
+use crate::project_reader::{decode_pattern_tempo, decode_trig_masks, read_project_metadata};
+use ot_tools_io::{OctatrackFileIO, ProjectFile};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Frames faded out where a trig cuts the previous one, to avoid clicks.
+const DECLICK_FRAMES: usize = 64;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatternRender {
+    pub out_file: String,
+    pub tempo: f64,
+    pub duration_secs: f64,
+    pub voices: u32, // trigs rendered
+    pub warnings: Vec<String>,
+}
+
+/// One triggered sample, in output frames.
+struct Voice {
+    sample: Arc<DecodedAudio>,
+    start: usize,
+    end: usize,        // exclusive; the next trig of the track or the render end
+    offset: f64,       // first source frame (STRT)
+    increment: f64,    // source frames per output frame
+    gains: (f32, f32), // left, right
+}
+
+/// Source-rate-independent playback speed for PTCH and RATE.
+fn playback_speed(ptch: u8, rate: u8) -> f64 {
+    let semitones = ptch as f64 - 64.0;
+    2f64.powf(semitones / 12.0) * (rate.min(127) as f64 / 127.0)
+}
+
+/// Left/right gains for VOL and BAL.
+fn voice_gains(vol: u8, bal: u8) -> (f32, f32) {
+    let gain = vol.min(127) as f32 / 64.0;
+    let pan = (bal.min(127) as f32 - 64.0) / 64.0; // -1 left .. +1 right
+    let angle = (pan + 1.0) * std::f32::consts::FRAC_PI_4;
+    (gain * angle.cos(), gain * angle.sin())
+}
+
+fn mix_voice(voice: &Voice, left: &mut [f32], right: &mut [f32]) {
+    let channels = &voice.sample.channels;
+    let frames = channels.first().map_or(0, |c| c.len());
+    let end = voice.end.min(left.len());
+    for out in voice.start..end {
+        let pos = voice.offset + (out - voice.start) as f64 * voice.increment;
+        let index = pos as usize;
+        if index + 1 >= frames {
+            break;
+        }
+        let frac = (pos - index as f64) as f32;
+        let at = |c: &Vec<f32>| c[index] + (c[index + 1] - c[index]) * frac;
+        let (l, r) = match channels.len() {
+            1 => (at(&channels[0]), at(&channels[0])),
+            _ => (at(&channels[0]), at(&channels[1])),
+        };
+        let remaining = end - out;
+        let fade = if remaining < DECLICK_FRAMES {
+            remaining as f32 / DECLICK_FRAMES as f32
+        } else {
+            1.0
+        };
+        left[out] += l * voice.gains.0 * fade;
+        right[out] += r * voice.gains.1 * fade;
+    }
+}
+
+fn write_wav(out: &Path, left: &[f32], right: &[f32]) -> Result<(), String> {
+    if let Some(parent) = out.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let spec = hound::WavSpec {
+        channels: 2,
+        sample_rate: OCTATRACK_SAMPLE_RATE,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(out, spec)
+        .map_err(|e| format!("Failed to create WAV file: {}", e))?;
+    for (l, r) in left.iter().zip(right) {
+        for s in [l, r] {
+            writer
+                .write_sample((s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
+                .map_err(|e| format!("Failed to write WAV file: {}", e))?;
+        }
+    }
+    writer
+        .finalize()
+        .map_err(|e| format!("Failed to write WAV file: {}", e))
+}
+
+/// Render `loops` passes of pattern `pattern_index` (0-15) of bank `bank_id`
+/// to a 44.1 kHz stereo WAV at `out_file`.
+pub fn render_pattern(
+    project_path: &str,
+    bank_id: &str,
+    pattern_index: u8,
+    loops: u8,
+    out_file: &str,
+) -> Result<PatternRender, String> {
+    let bank_idx = bank_index(bank_id, pattern_index)?;
+    if !(1..=16).contains(&loops) {
+        return Err("Loops must be between 1 and 16".to_string());
+    }
+    let dir = Path::new(project_path);
+    let find = |stem: String| {
+        let work = dir.join(format!("{}.work", stem));
+        if work.exists() {
+            work
+        } else {
+            dir.join(format!("{}.strd", stem))
+        }
+    };
+    let bank_file = find(format!("bank{:02}", bank_idx + 1));
+    if !bank_file.exists() {
+        return Err(format!("Bank {} does not exist in this project", bank_id));
+    }
+    let bank = crate::os_compat::read_bank_file(&bank_file)
+        .map_err(|e| format!("Failed to read bank {}: {}", bank_id, e))?;
+    let project = ProjectFile::from_data_file(&find("project".to_string()))
+        .map_err(|e| format!("Failed to read project file: {:?}", e))?;
+    let metadata = read_project_metadata(project_path)?.data;
+
+    let pattern = &bank.patterns.0[pattern_index as usize];
+    let part = &bank.parts.unsaved.0[pattern.part_assignment.min(3) as usize];
+    let tempo = if metadata.pattern_tempo_enabled {
+        decode_pattern_tempo(pattern.tempo_1, pattern.tempo_2) as f64
+    } else {
+        metadata.tempo as f64
+    };
+    let frames_per_1x_step = OCTATRACK_SAMPLE_RATE as f64 * 60.0 / tempo.clamp(30.0, 300.0) / 4.0;
+
+    // Steps and step duration of each audio track; one pass lasts as long as
+    // the longest track.
+    let timing: Vec<(usize, f64)> = (0..8)
+        .map(|t| {
+            let track = &pattern.audio_track_trigs.0[t];
+            let (len, scale) = if pattern.scale.scale_mode == 1 {
+                (
+                    track.scale_per_track_mode.per_track_len,
+                    track.scale_per_track_mode.per_track_scale,
+                )
+            } else {
+                (pattern.scale.master_len, pattern.scale.master_scale)
+            };
+            let step = frames_per_1x_step * SCALE_STEP_FACTORS[(scale as usize).min(6)];
+            (len.clamp(1, 64) as usize, step)
+        })
+        .collect();
+    let pass_frames = timing
+        .iter()
+        .map(|(len, step)| *len as f64 * step)
+        .fold(0.0, f64::max);
+    let total_frames = (pass_frames * loops as f64).round() as usize;
+
+    let mut samples: HashMap<(u8, u8), Option<Arc<DecodedAudio>>> = HashMap::new();
+    let mut warnings = Vec::new();
+    let mut voices = Vec::new();
+
+    for (t, &(len, step_frames)) in timing.iter().enumerate() {
+        let machine_type = part.audio_track_machine_types[t];
+        if machine_type > 1 {
+            continue; // Thru/Neighbor/Pickup play no sample
+        }
+        let track = &pattern.audio_track_trigs.0[t];
+        let triggers = decode_trig_masks(&track.trig_masks.trigger);
+        let swings = decode_trig_masks(&track.trig_masks.swing);
+        let machine = &part.audio_track_machine_params[t].static_machine;
+        let amp = &part.audio_track_params_values[t].amp;
+        let slots = &part.audio_track_machine_slots[t];
+        let part_slot = if machine_type == 0 {
+            slots.static_slot_id
+        } else {
+            slots.flex_slot_id
+        };
+
+        let mut track_voices: Vec<Voice> = Vec::new();
+        let track_frames = len as f64 * step_frames;
+        let passes = (total_frames as f64 / track_frames).ceil() as usize;
+        for pass in 0..passes {
+            for step in (0..len).filter(|&s| triggers[s]) {
+                let plock = &track.plocks[step];
+                let locked = |value: u8, default: u8| if value != 255 { value } else { default };
+                let slot = locked(plock.flex_slot_id, part_slot);
+
+                let mut start = pass as f64 * track_frames
+                    + step as f64 * step_frames
+                    + micro_timing(track.trig_offsets_repeats_conditions[step]) as f64
+                        * step_frames
+                        / 24.0;
+                if step % 2 == 1 && swings[step] {
+                    start += track.swing_amount as f64 / 100.0 * 2.0 * step_frames;
+                }
+                let start = start.round().max(0.0) as usize;
+                if start >= total_frames {
+                    continue;
+                }
+
+                let sample = samples
+                    .entry((machine_type, slot))
+                    .or_insert_with(|| {
+                        let pool = if machine_type == 0 {
+                            &project.slots.static_slots
+                        } else {
+                            &project.slots.flex_slots
+                        };
+                        let kind = if machine_type == 0 { "Static" } else { "Flex" };
+                        let path = pool
+                            .get(slot as usize)
+                            .and_then(|s| s.as_ref())
+                            .and_then(|s| s.path.as_ref());
+                        let Some(path) = path else {
+                            warnings.push(format!(
+                                "T{}: {} slot {} is empty",
+                                t + 1,
+                                kind,
+                                slot + 1
+                            ));
+                            return None;
+                        };
+                        match decode_audio_file(&dir.join(path)) {
+                            Ok(audio) if !audio.channels.is_empty() => Some(Arc::new(audio)),
+                            Ok(_) => None,
+                            Err(e) => {
+                                warnings.push(format!(
+                                    "T{}: {} slot {} ({}): {}",
+                                    t + 1,
+                                    kind,
+                                    slot + 1,
+                                    path.display(),
+                                    e
+                                ));
+                                None
+                            }
+                        }
+                    })
+                    .clone();
+                let Some(sample) = sample else { continue };
+
+                let frames = sample.channels[0].len() as f64;
+                let speed = playback_speed(
+                    locked(plock.machine.param1, machine.ptch),
+                    locked(plock.machine.param4, machine.rate),
+                );
+                track_voices.push(Voice {
+                    start,
+                    end: total_frames,
+                    offset: locked(plock.machine.param2, machine.strt).min(127) as f64 / 128.0
+                        * frames,
+                    increment: speed * sample.sample_rate as f64 / OCTATRACK_SAMPLE_RATE as f64,
+                    gains: voice_gains(
+                        locked(plock.amp.vol, amp.vol),
+                        locked(plock.amp.bal, amp.bal),
+                    ),
+                    sample,
+                });
+            }
+        }
+
+        // Monophonic track: each trig cuts the previous one.
+        track_voices.sort_by_key(|v| v.start);
+        let next_starts: Vec<usize> = track_voices.iter().skip(1).map(|v| v.start).collect();
+        for (voice, next_start) in track_voices.iter_mut().zip(next_starts) {
+            voice.end = next_start;
+        }
+        voices.extend(track_voices);
+    }
+
+    let mut left = vec![0.0f32; total_frames];
+    let mut right = vec![0.0f32; total_frames];
+    for voice in &voices {
+        mix_voice(voice, &mut left, &mut right);
+    }
+    // Scale down rather than clip when tracks sum above full scale.
+    let peak = left
+        .iter()
+        .chain(&right)
+        .fold(0.0f32, |m, s| m.max(s.abs()));
+    if peak > 1.0 {
+        left.iter_mut()
+            .chain(right.iter_mut())
+            .for_each(|s| *s /= peak);
+    }
+    write_wav(Path::new(out_file), &left, &right)?;
+
+    Ok(PatternRender {
+        out_file: out_file.to_string(),
+        tempo,
+        duration_secs: total_frames as f64 / OCTATRACK_SAMPLE_RATE as f64,
+        voices: voices.len() as u32,
+        warnings,
+    })
+}
+
+#[tauri::command]
+pub async fn render_pattern_preview(
+    path: String,
+    bank_id: String,
+    pattern_id: u8,
+    loops: Option<u8>,
+    out_file: String,
+) -> Result<PatternRender, String> {
+    crate::fs_scope::ensure_allowed(&out_file)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        render_pattern(&path, &bank_id, pattern_id, loops.unwrap_or(1), &out_file)
+    })
+    .await
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project_reader::encode_trig_masks;
+    use ot_tools_io::projects::SlotAttributes;
+    use ot_tools_io::settings::SlotType;
+    use ot_tools_io::BankFile;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_render_places_trigs_on_the_step_grid() {
+        let dir = TempDir::new().unwrap();
+        // 100 frames of full-scale DC, mono
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 44100,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(dir.path().join("click.wav"), spec).unwrap();
+        for _ in 0..100 {
+            writer.write_sample(i16::MAX / 2).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let mut project = ProjectFile::default();
+        project.slots.static_slots[0] = Some(
+            SlotAttributes::new(
+                SlotType::Static,
+                1,
+                Some(PathBuf::from("click.wav")),
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap(),
+        );
+        project
+            .to_data_file(&dir.path().join("project.work"))
+            .unwrap();
+
+        let mut bank = BankFile::default();
+        let pattern = &mut bank.patterns.0[0];
+        pattern.scale.master_len = 16;
+        pattern.scale.master_scale = 2; // 1x
+        let track = &mut pattern.audio_track_trigs.0[0];
+        track.trig_masks.trigger = encode_trig_masks(&std::array::from_fn(|s| s == 0 || s == 4));
+        let part = &mut bank.parts.unsaved.0[0];
+        part.audio_track_machine_types[0] = 0;
+        part.audio_track_machine_slots[0].static_slot_id = 0;
+        part.audio_track_machine_params[0].static_machine.ptch = 64;
+        part.audio_track_machine_params[0].static_machine.strt = 0;
+        part.audio_track_machine_params[0].static_machine.rate = 127;
+        part.audio_track_params_values[0].amp.vol = 64;
+        part.audio_track_params_values[0].amp.bal = 64;
+        bank.to_data_file(&dir.path().join("bank01.work")).unwrap();
+
+        let out = dir.path().join("render/preview.wav");
+        let path = dir.path().to_string_lossy().to_string();
+        let render = render_pattern(&path, "A", 0, 1, &out.to_string_lossy()).unwrap();
+        assert_eq!(render.voices, 2);
+        assert!(render.warnings.is_empty(), "{:?}", render.warnings);
+
+        let reader = hound::WavReader::open(&out).unwrap();
+        assert_eq!(reader.spec().channels, 2);
+        let left: Vec<i16> = reader
+            .into_samples::<i16>()
+            .step_by(2)
+            .map(|s| s.unwrap())
+            .collect();
+        // 16 steps of 1/16 at the project tempo
+        let step = 44100.0 * 60.0 / render.tempo / 4.0;
+        assert_eq!(left.len(), (16.0 * step).round() as usize);
+        let step = step.round() as usize;
+        assert!(left[10] > 0, "first trig sounds");
+        assert_eq!(left[500], 0, "sample ended");
+        assert!(left[4 * step + 10] > 0, "second trig on step 5");
+    }
+
+    #[test]
+    fn test_pitch_and_pan() {
+        assert_eq!(playback_speed(64, 127), 1.0);
+        assert!((playback_speed(76, 127) - 2.0).abs() < 1e-9);
+        assert!((playback_speed(64, 64) - 64.0 / 127.0).abs() < 1e-9);
+        let (l, r) = voice_gains(64, 64);
+        assert!((l - r).abs() < 1e-6);
+        let (l, r) = voice_gains(64, 0);
+        assert!(l > 0.99 && r < 1e-6);
+    }
+}
@@ -0,0 +1,455 @@
+// Audio Pool index: an SQLite table of every audio file under the indexed
+// folders with its size, mtime, duration, format and content hash, so the
+// file browser can search and filter without re-probing directories. The
+// same database holds the library index's provenance records
+// (library_index.rs), and library rebuilds sync their roots into it.
+//
+// The database lives in the OS data directory, never on the CF card.
+// `sync_directory` is incremental: a file is probed again only when its size
+// or mtime changed, and rows of files that disappeared are dropped. It
+// commits every `SYNC_BATCH` files, so an interrupted sync keeps its work.
+// `refresh_file` updates a single file.
+
+use crate::audio_pool::{collect_audio_files_recursive, extract_audio_metadata, file_content_hash};
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf, MAIN_SEPARATOR};
+use std::time::{Duration, UNIX_EPOCH};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS files (
+    path        TEXT PRIMARY KEY,
+    name        TEXT NOT NULL,
+    size        INTEGER NOT NULL,
+    modified    INTEGER NOT NULL,
+    duration    REAL,
+    sample_rate INTEGER,
+    bit_depth   INTEGER,
+    channels    INTEGER,
+    hash        TEXT
+);
+CREATE INDEX IF NOT EXISTS files_name ON files (name);
+CREATE TABLE IF NOT EXISTS provenance (
+    path                 TEXT PRIMARY KEY,
+    original_path        TEXT NOT NULL,
+    original_format      TEXT NOT NULL,
+    original_sample_rate INTEGER,
+    original_bit_depth   INTEGER,
+    original_channels    INTEGER,
+    converted            INTEGER NOT NULL,
+    sample_rate          INTEGER,
+    bit_depth            INTEGER,
+    channels             INTEGER,
+    imported_at          TEXT NOT NULL
+);
+";
+/// Files probed per transaction by a sync.
+const SYNC_BATCH: usize = 200;
+/// How long a write waits for another connection (a concurrent import or
+/// sync) before failing.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+/// Results returned by a search when the query sets no limit.
+const DEFAULT_SEARCH_LIMIT: u32 = 500;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IndexedPoolFile {
+    pub path: String,
+    pub name: String,
+    pub size: u64,
+    pub modified: u64,         // seconds since the Unix epoch
+    pub duration: Option<f64>, // seconds; WAV/AIFF only
+    pub sample_rate: Option<u32>,
+    pub bit_depth: Option<u32>,
+    pub channels: Option<u32>,
+    pub hash: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct PoolSyncStats {
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+    pub unchanged: usize,
+}
+
+/// Filters for `search`; every field is optional.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PoolQuery {
+    pub root: Option<String>, // only files under this folder
+    pub text: Option<String>, // case-insensitive file name match
+    pub sample_rate: Option<u32>,
+    pub bit_depth: Option<u32>,
+    pub channels: Option<u32>,
+    pub min_duration: Option<f64>,
+    pub max_duration: Option<f64>,
+    pub limit: Option<u32>,
+}
+
+/// Default database location: `<data dir>/octatrack-manager/pool_index.sqlite`.
+pub fn default_db_path() -> Result<PathBuf, String> {
+    crate::app_data::data_path("pool_index.sqlite")
+}
+
+/// Open (creating if needed) the index database at `db_path`.
+pub fn open_index(db_path: &Path) -> Result<Connection, String> {
+    if let Some(parent) = db_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create pool index directory: {}", e))?;
+    }
+    let conn =
+        Connection::open(db_path).map_err(|e| format!("Failed to open pool index: {}", e))?;
+    conn.busy_timeout(BUSY_TIMEOUT)
+        .map_err(|e| format!("Failed to open pool index: {}", e))?;
+    conn.execute_batch(SCHEMA)
+        .map_err(|e| format!("Failed to initialise pool index: {}", e))?;
+    Ok(conn)
+}
+
+pub(crate) fn db_error(e: rusqlite::Error) -> String {
+    format!("Pool index error: {}", e)
+}
+
+/// `root` as a prefix that only matches paths inside it.
+fn folder_prefix(root: &Path) -> String {
+    let mut prefix = root.to_string_lossy().to_string();
+    if !prefix.ends_with(MAIN_SEPARATOR) {
+        prefix.push(MAIN_SEPARATOR);
+    }
+    prefix
+}
+
+fn size_and_mtime(path: &Path) -> Option<(u64, u64)> {
+    let meta = fs::metadata(path).ok()?;
+    let modified = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    Some((meta.len(), modified))
+}
+
+/// Read everything the index stores about `path`.
+fn probe(path: &Path, size: u64, modified: u64) -> IndexedPoolFile {
+    let (channels, bit_depth, sample_rate) = extract_audio_metadata(&path.to_path_buf());
+    let duration = crate::project_reader::audio_frames_and_rate(path)
+        .filter(|(_, rate)| *rate > 0)
+        .map(|(frames, rate)| frames as f64 / rate as f64);
+    IndexedPoolFile {
+        path: path.to_string_lossy().to_string(),
+        name: path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        size,
+        modified,
+        duration,
+        sample_rate,
+        bit_depth,
+        channels,
+        hash: file_content_hash(path).ok().map(|h| format!("{:016x}", h)),
+    }
+}
+
+fn upsert(conn: &Connection, file: &IndexedPoolFile) -> Result<(), String> {
+    conn.execute(
+        "INSERT OR REPLACE INTO files
+            (path, name, size, modified, duration, sample_rate, bit_depth, channels, hash)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            file.path,
+            file.name,
+            file.size as i64,
+            file.modified as i64,
+            file.duration,
+            file.sample_rate,
+            file.bit_depth,
+            file.channels,
+            file.hash,
+        ],
+    )
+    .map_err(db_error)?;
+    Ok(())
+}
+
+/// Bring the rows for every audio file under `root` up to date.
+pub fn sync_directory(conn: &mut Connection, root: &Path) -> Result<PoolSyncStats, String> {
+    let files = collect_audio_files_recursive(&root.to_string_lossy())?;
+    sync_files(conn, root, &files, |_, _| true)
+}
+
+/// Sync the rows under `root` against `files`, the audio files found there.
+/// `step(done, path)` runs before each file; returning false stops the sync
+/// with "Cancelled", keeping the rows written so far. Rows of files that
+/// disappeared are only dropped by a complete sync.
+pub fn sync_files(
+    conn: &mut Connection,
+    root: &Path,
+    files: &[String],
+    mut step: impl FnMut(usize, &str) -> bool,
+) -> Result<PoolSyncStats, String> {
+    let prefix = folder_prefix(root);
+
+    let known: HashMap<String, (u64, u64)> = {
+        let mut stmt = conn
+            .prepare(
+                "SELECT path, size, modified FROM files WHERE substr(path, 1, length(?1)) = ?1",
+            )
+            .map_err(db_error)?;
+        let rows = stmt
+            .query_map([&prefix], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    (row.get::<_, i64>(1)? as u64, row.get::<_, i64>(2)? as u64),
+                ))
+            })
+            .map_err(db_error)?;
+        rows.collect::<Result<_, _>>().map_err(db_error)?
+    };
+
+    let mut stats = PoolSyncStats::default();
+    let mut seen = std::collections::HashSet::new();
+    let mut tx = conn.transaction().map_err(db_error)?;
+    for (i, file) in files.iter().enumerate() {
+        if i > 0 && i % SYNC_BATCH == 0 {
+            tx.commit().map_err(db_error)?;
+            tx = conn.transaction().map_err(db_error)?;
+        }
+        if !step(i, file) {
+            tx.commit().map_err(db_error)?;
+            return Err("Cancelled".to_string());
+        }
+        let path = Path::new(file);
+        let Some((size, modified)) = size_and_mtime(path) else {
+            continue;
+        };
+        seen.insert(file.as_str());
+        match known.get(file) {
+            Some(&stamp) if stamp == (size, modified) => stats.unchanged += 1,
+            Some(_) => {
+                upsert(&tx, &probe(path, size, modified))?;
+                stats.updated += 1;
+            }
+            None => {
+                upsert(&tx, &probe(path, size, modified))?;
+                stats.added += 1;
+            }
+        }
+    }
+    for path in known.keys().filter(|p| !seen.contains(p.as_str())) {
+        tx.execute("DELETE FROM files WHERE path = ?1", [path])
+            .map_err(db_error)?;
+        stats.removed += 1;
+    }
+    tx.commit().map_err(db_error)?;
+    Ok(stats)
+}
+
+/// Update (or drop, when it no longer exists) the row of a single file.
+pub fn refresh_file(conn: &Connection, path: &Path) -> Result<(), String> {
+    let key = path.to_string_lossy().to_string();
+    let is_audio = path
+        .file_name()
+        .is_some_and(|n| crate::audio_pool::is_audio_file(&n.to_string_lossy()));
+    match size_and_mtime(path).filter(|_| is_audio && path.is_file()) {
+        Some((size, modified)) => {
+            let known: Option<(i64, i64)> = conn
+                .query_row(
+                    "SELECT size, modified FROM files WHERE path = ?1",
+                    [&key],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .optional()
+                .map_err(db_error)?;
+            if known != Some((size as i64, modified as i64)) {
+                upsert(conn, &probe(path, size, modified))?;
+            }
+        }
+        None => {
+            conn.execute("DELETE FROM files WHERE path = ?1", [&key])
+                .map_err(db_error)?;
+        }
+    }
+    Ok(())
+}
+
+/// Indexed files matching `query`, sorted by path.
+pub fn search(conn: &Connection, query: &PoolQuery) -> Result<Vec<IndexedPoolFile>, String> {
+    let mut conditions: Vec<&str> = Vec::new();
+    let mut values: Vec<Value> = Vec::new();
+    if let Some(root) = &query.root {
+        conditions.push("substr(path, 1, length(?)) = ?");
+        let prefix = folder_prefix(Path::new(root));
+        values.push(Value::Text(prefix.clone()));
+        values.push(Value::Text(prefix));
+    }
+    if let Some(text) = query.text.as_deref().filter(|t| !t.is_empty()) {
+        conditions.push("instr(lower(name), lower(?)) > 0");
+        values.push(Value::Text(text.to_string()));
+    }
+    for (condition, value) in [
+        ("sample_rate = ?", query.sample_rate),
+        ("bit_depth = ?", query.bit_depth),
+        ("channels = ?", query.channels),
+    ] {
+        if let Some(value) = value {
+            conditions.push(condition);
+            values.push(Value::Integer(value as i64));
+        }
+    }
+    if let Some(min) = query.min_duration {
+        conditions.push("duration >= ?");
+        values.push(Value::Real(min));
+    }
+    if let Some(max) = query.max_duration {
+        conditions.push("duration <= ?");
+        values.push(Value::Real(max));
+    }
+
+    let mut sql =
+        "SELECT path, name, size, modified, duration, sample_rate, bit_depth, channels, hash
+                   FROM files"
+            .to_string();
+    if !conditions.is_empty() {
+        sql.push_str(" WHERE ");
+        sql.push_str(&conditions.join(" AND "));
+    }
+    sql.push_str(" ORDER BY path LIMIT ?");
+    values.push(Value::Integer(
+        query.limit.unwrap_or(DEFAULT_SEARCH_LIMIT) as i64
+    ));
+
+    let mut stmt = conn.prepare(&sql).map_err(db_error)?;
+    let rows = stmt
+        .query_map(params_from_iter(values), |row| {
+            Ok(IndexedPoolFile {
+                path: row.get(0)?,
+                name: row.get(1)?,
+                size: row.get::<_, i64>(2)? as u64,
+                modified: row.get::<_, i64>(3)? as u64,
+                duration: row.get(4)?,
+                sample_rate: row.get(5)?,
+                bit_depth: row.get(6)?,
+                channels: row.get(7)?,
+                hash: row.get(8)?,
+            })
+        })
+        .map_err(db_error)?;
+    rows.collect::<Result<_, _>>().map_err(db_error)
+}
+
+#[tauri::command]
+pub async fn index_pool_directory(path: String) -> Result<PoolSyncStats, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut conn = open_index(&default_db_path()?)?;
+        sync_directory(&mut conn, Path::new(&path))
+    })
+    .await
+    .unwrap()
+}
+
+/// Re-read the given files (e.g. right after an import) without a full sync.
+#[tauri::command]
+pub async fn refresh_pool_index(paths: Vec<String>) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let conn = open_index(&default_db_path()?)?;
+        for path in &paths {
+            refresh_file(&conn, Path::new(path))?;
+        }
+        Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+#[tauri::command]
+pub async fn search_pool_index(query: PoolQuery) -> Result<Vec<IndexedPoolFile>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let conn = open_index(&default_db_path()?)?;
+        search(&conn, &query)
+    })
+    .await
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::write_wav;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_sync_is_incremental_and_search_filters() {
+        let dir = TempDir::new().unwrap();
+        let pool = dir.path().join("AUDIO");
+        fs::create_dir_all(pool.join("drums")).unwrap();
+        write_wav(&pool.join("drums").join("Kick.wav"), 1, 44100, &[0; 44100]);
+        write_wav(&pool.join("pad.wav"), 1, 48000, &[0; 96000]);
+        let mut conn = open_index(&dir.path().join("index.sqlite")).unwrap();
+
+        let stats = sync_directory(&mut conn, &pool).unwrap();
+        assert_eq!((stats.added, stats.unchanged), (2, 0));
+        let again = sync_directory(&mut conn, &pool).unwrap();
+        assert_eq!((again.added, again.updated, again.unchanged), (0, 0, 2));
+
+        let kick = search(
+            &conn,
+            &PoolQuery {
+                text: Some("kick".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(kick.len(), 1);
+        assert_eq!(kick[0].duration, Some(1.0));
+        assert_eq!(kick[0].sample_rate, Some(44100));
+        assert!(kick[0].hash.is_some());
+        let long = search(
+            &conn,
+            &PoolQuery {
+                root: Some(pool.to_string_lossy().to_string()),
+                min_duration: Some(1.5),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(long.len(), 1);
+        assert_eq!(long[0].name, "pad.wav");
+
+        fs::remove_file(pool.join("pad.wav")).unwrap();
+        write_wav(&pool.join("drums").join("Kick.wav"), 1, 44100, &[0; 22050]);
+        let stats = sync_directory(&mut conn, &pool).unwrap();
+        assert_eq!((stats.updated, stats.removed), (1, 1));
+
+        refresh_file(&conn, &pool.join("drums").join("Kick.wav")).unwrap();
+        fs::remove_file(pool.join("drums").join("Kick.wav")).unwrap();
+        refresh_file(&conn, &pool.join("drums").join("Kick.wav")).unwrap();
+        assert!(search(&conn, &PoolQuery::default()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_stopped_sync_keeps_rows_written_so_far() {
+        let dir = TempDir::new().unwrap();
+        let pool = dir.path().join("AUDIO");
+        fs::create_dir_all(&pool).unwrap();
+        let files: Vec<String> = ["a", "b", "c"]
+            .iter()
+            .map(|name| {
+                let path = pool.join(format!("{}.wav", name));
+                write_wav(&path, 1, 44100, &[0; 100]);
+                path.to_string_lossy().to_string()
+            })
+            .collect();
+        let mut conn = open_index(&dir.path().join("index.sqlite")).unwrap();
+
+        let stopped = sync_files(&mut conn, &pool, &files, |i, _| i < 2);
+        assert_eq!(stopped.unwrap_err(), "Cancelled");
+        assert_eq!(search(&conn, &PoolQuery::default()).unwrap().len(), 2);
+        // The next sync picks up where it stopped
+        let stats = sync_files(&mut conn, &pool, &files, |_, _| true).unwrap();
+        assert_eq!((stats.added, stats.unchanged), (1, 2));
+    }
+}
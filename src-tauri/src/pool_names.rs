@@ -0,0 +1,285 @@
+// Audio Pool names the Octatrack can't show or load. `validate_pool_names`
+// walks the pool and reports, for every file and folder:
+//
+// - names longer than the device shows, which it cuts off;
+// - non-ASCII characters, which it can't display and may refuse to load;
+// - characters FAT32 doesn't allow or drops (trailing dots and spaces);
+// - names that become identical once cut off, which can't be told apart on
+//   the device.
+//
+// In fix-up mode, flagged files are renamed to the sanitized name (see
+// `file_names`), made unique within their folder, with their .ot sidecar;
+// project slots of the Set that load them are repointed. Folders are only
+// reported: slot paths would need every path component rewritten.
+
+use crate::file_names::sanitize_file_name;
+use crate::project_reader::{update_pool_references, PoolReferenceUpdate};
+use crate::sample_attributes::{is_sidecar_with_sample, move_sidecar};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Longest name, extension included, the Octatrack shows in full in its file
+/// browser; longer names are cut off on screen.
+pub(crate) const OCTATRACK_NAME_LEN: usize = 32;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PoolNameIssue {
+    pub path: String,
+    pub relative: String, // relative to the Audio Pool, forward slashes
+    pub is_directory: bool,
+    pub problems: Vec<String>,
+    pub suggested_name: String,
+    pub renamed_to: Option<String>, // set in fix-up mode once renamed
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PoolNameReport {
+    pub pool_path: String,
+    pub checked: usize,
+    pub issues: Vec<PoolNameIssue>,
+    pub references: Option<PoolReferenceUpdate>, // fix-up mode only
+    pub errors: Vec<String>,                     // renames that failed
+}
+
+/// The Audio Pool for `root`: `root/AUDIO` when `root` is a Set, else `root`.
+fn pool_dir(root: &Path) -> PathBuf {
+    let audio = root.join("AUDIO");
+    if audio.is_dir() {
+        audio
+    } else {
+        root.to_path_buf()
+    }
+}
+
+/// What the device shows of `name`, for comparison (FAT32 ignores case).
+fn shown(name: &str) -> String {
+    name.chars()
+        .take(OCTATRACK_NAME_LEN)
+        .collect::<String>()
+        .to_lowercase()
+}
+
+fn problems_of(name: &str) -> Vec<String> {
+    let mut problems = Vec::new();
+    let length = name.chars().count();
+    if length > OCTATRACK_NAME_LEN {
+        problems.push(format!(
+            "Too long: {} characters, the Octatrack shows {}",
+            length, OCTATRACK_NAME_LEN
+        ));
+    }
+    if !name.is_ascii() {
+        problems.push("Contains non-ASCII characters".to_string());
+    }
+    // Non-ASCII characters are reported above; check the rest of the name
+    let ascii = name.replace(|c: char| !c.is_ascii(), "x");
+    if sanitize_file_name(&ascii, usize::MAX) != ascii {
+        problems.push(
+            "Not a valid FAT32 name (reserved name, forbidden characters or trailing dots)"
+                .to_string(),
+        );
+    }
+    problems
+}
+
+/// `name`, or `name` with a "-N" suffix, not in `taken` and within the limit.
+fn unique_name(name: &str, taken: &HashSet<String>) -> String {
+    if !taken.contains(&name.to_lowercase()) {
+        return name.to_string();
+    }
+    let (stem, extension) = match name.rfind('.') {
+        Some(i) if i > 0 => (&name[..i], &name[i..]),
+        _ => (name, ""),
+    };
+    (1..)
+        .map(|n| {
+            let suffix = format!("-{}{}", n, extension);
+            let room = OCTATRACK_NAME_LEN.saturating_sub(suffix.len()).max(1);
+            format!("{}{}", &stem[..stem.len().min(room)], suffix)
+        })
+        .find(|candidate| !taken.contains(&candidate.to_lowercase()))
+        .unwrap()
+}
+
+/// Check every name in the Audio Pool at `root`, renaming flagged files when `fix`.
+pub fn validate_pool_names_sync(root: &Path, fix: bool) -> Result<PoolNameReport, String> {
+    let pool = pool_dir(root);
+    if !pool.is_dir() {
+        return Err(format!("Not a directory: {}", pool.display()));
+    }
+
+    // folder -> names in it; sidecars follow their sample and aren't checked
+    let mut folders: HashMap<PathBuf, Vec<(String, bool)>> = HashMap::new();
+    let mut checked = 0;
+    for entry in WalkDir::new(&pool).min_depth(1).sort_by_file_name() {
+        let entry = entry.map_err(|e| format!("Failed to read {}: {}", pool.display(), e))?;
+        if entry.file_type().is_file() && is_sidecar_with_sample(entry.path()) {
+            continue;
+        }
+        let parent = entry.path().parent().unwrap_or(&pool).to_path_buf();
+        let name = entry.file_name().to_string_lossy().to_string();
+        folders
+            .entry(parent)
+            .or_default()
+            .push((name, entry.file_type().is_dir()));
+        checked += 1;
+    }
+
+    let mut issues = Vec::new();
+    let mut folder_list: Vec<_> = folders.into_iter().collect();
+    folder_list.sort();
+    for (folder, names) in folder_list {
+        let mut by_shown: HashMap<String, Vec<&str>> = HashMap::new();
+        for (name, _) in &names {
+            by_shown.entry(shown(name)).or_default().push(name);
+        }
+        let mut flagged = Vec::new();
+        for (name, is_directory) in &names {
+            let mut problems = problems_of(name);
+            if name.chars().count() > OCTATRACK_NAME_LEN {
+                let others: Vec<_> = by_shown[&shown(name)]
+                    .iter()
+                    .filter(|other| **other != name.as_str())
+                    .copied()
+                    .collect();
+                if !others.is_empty() {
+                    problems.push(format!("Shows the same as {}", others.join(", ")));
+                }
+            }
+            if !problems.is_empty() {
+                flagged.push((name.clone(), *is_directory, problems));
+            }
+        }
+
+        // Suggested names are unique among the names that stay
+        let renamed: HashSet<&str> = flagged.iter().map(|(name, _, _)| name.as_str()).collect();
+        let mut taken: HashSet<String> = names
+            .iter()
+            .filter(|(name, _)| !renamed.contains(name.as_str()))
+            .map(|(name, _)| name.to_lowercase())
+            .collect();
+        for (name, is_directory, problems) in flagged {
+            let suggested = unique_name(&sanitize_file_name(&name, OCTATRACK_NAME_LEN), &taken);
+            taken.insert(suggested.to_lowercase());
+            let path = folder.join(&name);
+            issues.push(PoolNameIssue {
+                path: path.to_string_lossy().to_string(),
+                relative: path
+                    .strip_prefix(&pool)
+                    .unwrap_or(&path)
+                    .to_string_lossy()
+                    .replace('\\', "/"),
+                is_directory,
+                problems,
+                suggested_name: suggested,
+                renamed_to: None,
+            });
+        }
+    }
+
+    let mut report = PoolNameReport {
+        pool_path: pool.to_string_lossy().to_string(),
+        checked,
+        issues,
+        references: None,
+        errors: Vec::new(),
+    };
+    if !fix {
+        return Ok(report);
+    }
+
+    let mut renames = Vec::new();
+    for issue in report.issues.iter_mut().filter(|i| !i.is_directory) {
+        let old = PathBuf::from(&issue.path);
+        let new = old.with_file_name(&issue.suggested_name);
+        // Case-only changes are a rename of the same entry on FAT32
+        let old_name = old.file_name().unwrap_or_default().to_string_lossy();
+        let free = !new.exists() || issue.suggested_name.eq_ignore_ascii_case(&old_name);
+        let result = if free {
+            fs::rename(&old, &new)
+                .map_err(|e| format!("Failed to rename {}: {}", old.display(), e))
+                .and_then(|_| move_sidecar(&old, &new))
+        } else {
+            Err(format!("{} already exists", new.display()))
+        };
+        match result {
+            Ok(()) => {
+                issue.renamed_to = Some(new.to_string_lossy().to_string());
+                renames.push((issue.path.clone(), new.to_string_lossy().to_string()));
+            }
+            Err(e) => report.errors.push(e),
+        }
+    }
+    if !renames.is_empty() {
+        report.references = Some(update_pool_references(&report.pool_path, &renames)?);
+    }
+    Ok(report)
+}
+
+/// Report Audio Pool names the Octatrack will cut off or refuse; with `fix`,
+/// rename the flagged files and repoint the Set's projects onto them.
+#[tauri::command]
+pub async fn validate_pool_names(
+    root: String,
+    fix: Option<bool>,
+) -> Result<PoolNameReport, String> {
+    let fix = fix.unwrap_or(false);
+    if fix {
+        crate::fs_scope::ensure_allowed(&root)?;
+    }
+    tauri::async_runtime::spawn_blocking(move || validate_pool_names_sync(Path::new(&root), fix))
+        .await
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_validate_and_fix_pool_names() {
+        let tmp = TempDir::new().unwrap();
+        let pool = tmp.path().join("AUDIO");
+        fs::create_dir_all(pool.join("Drums é")).unwrap();
+        let long_a = "A very long breakbeat name take 1.wav";
+        let long_b = "A very long breakbeat name take 2.wav";
+        for name in ["kick.wav", "Café.wav", long_a, long_b] {
+            fs::write(pool.join(name), b"RIFF").unwrap();
+        }
+        fs::write(pool.join("Café.ot"), b"FORM").unwrap();
+
+        let report = validate_pool_names_sync(tmp.path(), false).unwrap();
+        assert_eq!(report.checked, 5);
+        let names: Vec<_> = report.issues.iter().map(|i| i.relative.as_str()).collect();
+        assert_eq!(names, vec![long_a, long_b, "Café.wav", "Drums é"]);
+        assert!(report.issues[0]
+            .problems
+            .iter()
+            .any(|p| p.contains("Shows the same as")));
+        assert_ne!(
+            report.issues[0].suggested_name.to_lowercase(),
+            report.issues[1].suggested_name.to_lowercase()
+        );
+        assert!(report
+            .issues
+            .iter()
+            .all(|i| i.suggested_name.len() <= OCTATRACK_NAME_LEN));
+        assert!(
+            pool.join("Café.wav").exists(),
+            "nothing renamed without fix"
+        );
+
+        let fixed = validate_pool_names_sync(&pool, true).unwrap();
+        assert!(fixed.errors.is_empty(), "{:?}", fixed.errors);
+        assert!(pool.join("Cafe.wav").exists());
+        assert!(pool.join("Cafe.ot").exists());
+        assert!(pool.join("Drums é").exists(), "folders are only reported");
+
+        let again = validate_pool_names_sync(&pool, false).unwrap();
+        assert_eq!(again.issues.len(), 1);
+    }
+}
@@ -0,0 +1,147 @@
+// Watches the directory shown in the Audio Pool browser and emits a
+// "pool-directory-changed" event per file added, removed or modified by
+// something else (the device over USB, another tool, a sync client), so the
+// browser stays in sync without polling.
+//
+// Works like the project watcher (both on dir_watcher.rs): one directory at a
+// time, non-recursive, with event bursts coalesced. Whether a path was added,
+// removed or modified is decided against the directory's listing rather than
+// the raw notify event kinds, which differ between platforms (a rename may
+// arrive as create/remove or as a pair of modify events).
+
+use crate::dir_watcher::{self, WatchSlot};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+
+static WATCHER: WatchSlot = WatchSlot::new(None);
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PoolDirectoryChangedEvent {
+    pub directory: String,
+    pub path: String,
+    pub name: String,
+    pub kind: String, // "added", "removed" or "modified"
+    pub is_directory: bool,
+}
+
+/// Names the browser lists: hidden files and in-progress temp files are left out.
+fn is_listed(name: &str) -> bool {
+    !name.starts_with('.') && !name.ends_with(".tmp")
+}
+
+fn listing(dir: &Path) -> HashSet<String> {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .filter(|name| is_listed(name))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// What happened to `path`, given the names listed before, which are updated.
+fn classify(
+    directory: &Path,
+    known: &mut HashSet<String>,
+    path: &Path,
+) -> Option<PoolDirectoryChangedEvent> {
+    if path.parent() != Some(directory) {
+        return None;
+    }
+    let name = path.file_name()?.to_string_lossy().to_string();
+    if !is_listed(&name) {
+        return None;
+    }
+    let exists = path.exists();
+    let kind = match (known.contains(&name), exists) {
+        (false, true) => {
+            known.insert(name.clone());
+            "added"
+        }
+        (true, false) => {
+            known.remove(&name);
+            "removed"
+        }
+        (true, true) => "modified",
+        (false, false) => return None, // created and deleted within one burst
+    };
+    Some(PoolDirectoryChangedEvent {
+        directory: directory.to_string_lossy().to_string(),
+        path: path.to_string_lossy().to_string(),
+        name,
+        kind: kind.to_string(),
+        is_directory: path.is_dir(),
+    })
+}
+
+/// Start watching `directory`, replacing any previously watched one.
+pub fn watch(app: AppHandle, directory: &str) -> Result<(), String> {
+    let dir = PathBuf::from(directory);
+    if !dir.is_dir() {
+        return Err(format!("Directory not found: {}", directory));
+    }
+
+    let mut known = listing(&dir);
+    let watched = dir.clone();
+    dir_watcher::watch(&WATCHER, &dir, move |changed| {
+        for event in changed
+            .iter()
+            .filter_map(|p| classify(&watched, &mut known, p))
+        {
+            let _ = app.emit("pool-directory-changed", event);
+        }
+    })
+}
+
+/// Stop watching. Returns the directory that was being watched, if any.
+pub fn unwatch() -> Option<String> {
+    dir_watcher::unwatch(&WATCHER)
+}
+
+#[tauri::command]
+pub fn watch_pool_directory(app: AppHandle, path: String) -> Result<(), String> {
+    watch(app, &path)
+}
+
+#[tauri::command]
+pub fn unwatch_pool_directory() -> Option<String> {
+    unwatch()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_classify_against_listing() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        fs::write(root.join("kick.wav"), b"x").unwrap();
+        let mut known = listing(root);
+
+        fs::write(root.join("snare.wav"), b"x").unwrap();
+        let added = classify(root, &mut known, &root.join("snare.wav")).unwrap();
+        assert_eq!(added.kind, "added");
+        assert_eq!(added.name, "snare.wav");
+
+        let modified = classify(root, &mut known, &root.join("kick.wav")).unwrap();
+        assert_eq!(modified.kind, "modified");
+
+        fs::remove_file(root.join("kick.wav")).unwrap();
+        let removed = classify(root, &mut known, &root.join("kick.wav")).unwrap();
+        assert_eq!(removed.kind, "removed");
+        assert!(!known.contains("kick.wav"));
+
+        // Created and deleted within one burst, hidden, or in a sub-folder
+        assert!(classify(root, &mut known, &root.join("gone.wav")).is_none());
+        fs::write(root.join(".DS_Store"), b"x").unwrap();
+        assert!(classify(root, &mut known, &root.join(".DS_Store")).is_none());
+        assert!(classify(root, &mut known, &root.join("sub").join("a.wav")).is_none());
+    }
+}
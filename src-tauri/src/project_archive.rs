@@ -0,0 +1,1031 @@
+// Project archives: a zip holding one project and every sample it loads, so
+// a complete project can be shared and unpacked into another Set.
+//
+// Layout mirrors a Set, so the zip also works when unzipped by hand:
+//   <PROJECT>/...      every file of the project directory
+//   AUDIO/...          Audio Pool samples the project loads, same sub-folders
+//   manifest.json      what was packed and from where
+// Samples outside both the project and the pool are stored in the project
+// folder. Slot paths in the packed project files are rewritten to match
+// (`../AUDIO/...` for pool samples), so nothing points outside the archive.
+//
+// Importing is done in two steps: `inspect_archive` lists what would collide
+// with files already in the target Set, then `import_archive` unpacks with
+// the user's choice for each conflict.
+
+use crate::project_reader::{file_name_of, normalize_path_lexically, read_raw_sample_fields};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+use walkdir::WalkDir;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+pub const MANIFEST_NAME: &str = "manifest.json";
+const ARCHIVE_FORMAT: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ArchiveEntry {
+    pub archive_path: String,
+    pub kind: String,                  // "project", "pool_sample", "external_sample"
+    pub original_path: Option<String>, // where an external sample came from
+    pub size: u64,
+}
+
+/// manifest.json as written into the archive.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveManifest {
+    pub format: u32,
+    pub project: String,
+    pub created_at: String, // RFC 3339, local time
+    pub files: Vec<ArchiveEntry>,
+    pub missing_samples: Vec<String>, // slot paths whose file wasn't found
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveProgress {
+    pub archive: String,
+    pub stage: String, // "packing", "complete"
+    pub processed_bytes: u64,
+    pub total_bytes: u64,
+    pub current_file: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveExportResult {
+    pub archive_path: String,
+    pub file_count: usize,
+    pub total_bytes: u64,
+    pub missing_samples: Vec<String>,
+}
+
+/// Files of the project directory worth packing: everything but this app's
+/// own lock, backup and temp files.
+fn project_dir_files(project_dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = WalkDir::new(project_dir)
+        .into_iter()
+        .filter_entry(|e| !e.file_name().to_string_lossy().starts_with(".otm"))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| !e.file_name().to_string_lossy().ends_with(".otm-tmp"))
+        .map(|e| e.into_path())
+        .collect();
+    files.sort();
+    files
+}
+
+fn zip_path_of(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// Replace the PATH= value of every [SAMPLE] block found in `new_paths`
+/// (keyed by the current value). All other bytes are kept. Returns the new
+/// content and the number of slots changed.
+pub(crate) fn rewrite_slot_paths(
+    content: &str,
+    new_paths: &HashMap<String, String>,
+) -> (String, usize) {
+    let mut result = String::with_capacity(content.len());
+    let mut updated = 0;
+    let mut in_sample = false;
+    for line in content.split_inclusive('\n') {
+        let trimmed = line.trim_end_matches(['\r', '\n']);
+        match trimmed {
+            "[SAMPLE]" => in_sample = true,
+            "[/SAMPLE]" => in_sample = false,
+            _ => {}
+        }
+        if in_sample {
+            if let Some(new_path) = trimmed
+                .strip_prefix("PATH=")
+                .and_then(|value| new_paths.get(value))
+            {
+                result.push_str("PATH=");
+                result.push_str(new_path);
+                result.push_str(&line[trimmed.len()..]);
+                updated += 1;
+                continue;
+            }
+        }
+        result.push_str(line);
+    }
+    (result, updated)
+}
+
+/// A sample to pack: where it is and where it goes in the archive.
+struct PackedSample {
+    source: PathBuf,
+    archive_path: String,
+    kind: &'static str,
+}
+
+/// Pack `project_path` into `dest` (a zip file, or a directory to put
+/// `<PROJECT>.zip` in). `on_progress` is called after every file.
+pub fn export_archive(
+    project_path: &str,
+    dest: &str,
+    mut on_progress: impl FnMut(ArchiveProgress),
+) -> Result<ArchiveExportResult, String> {
+    let project_dir = normalize_path_lexically(Path::new(project_path));
+    if !project_dir.join("project.work").exists() && !project_dir.join("project.strd").exists() {
+        return Err(format!("No project file found in {}", project_path));
+    }
+    let project_name = file_name_of(&project_dir);
+    let pool_dir = project_dir
+        .parent()
+        .map(|set| set.join("AUDIO"))
+        .unwrap_or_default();
+    let dest = Path::new(dest);
+    let zip_path = if dest.is_dir() {
+        dest.join(format!("{}.zip", project_name))
+    } else {
+        dest.to_path_buf()
+    };
+
+    let project_files = project_dir_files(&project_dir);
+    let in_project: HashSet<PathBuf> = project_files.iter().cloned().collect();
+
+    // Decide where every loaded sample goes and what its slot path becomes.
+    let mut samples: Vec<PackedSample> = Vec::new();
+    let mut new_paths: HashMap<String, String> = HashMap::new();
+    let mut missing_samples = Vec::new();
+    let mut packed: HashMap<PathBuf, String> = HashMap::new(); // source -> slot path
+    let mut taken: HashSet<String> = in_project
+        .iter()
+        .filter_map(|p| p.strip_prefix(&project_dir).ok())
+        .map(|p| zip_path_of(p).to_lowercase())
+        .collect();
+    let mut slot_paths: Vec<String> = Vec::new();
+    for name in ["project.work", "project.strd"] {
+        let file = project_dir.join(name);
+        if file.exists() {
+            for fields in read_raw_sample_fields(&file)?.values() {
+                if let Some(path) = fields.get("PATH").filter(|p| !p.is_empty()) {
+                    slot_paths.push(path.clone());
+                }
+            }
+        }
+    }
+    slot_paths.sort();
+    slot_paths.dedup();
+    for slot_path in slot_paths {
+        let source = normalize_path_lexically(&project_dir.join(slot_path.replace('\\', "/")));
+        if !source.is_file() {
+            missing_samples.push(slot_path);
+            continue;
+        }
+        let new_path = match packed.get(&source) {
+            Some(p) => p.clone(),
+            None => {
+                let new_path = if let Ok(rel) = source.strip_prefix(&project_dir) {
+                    zip_path_of(rel) // packed with the project files
+                } else if let Ok(rel) = source.strip_prefix(&pool_dir) {
+                    let rel = zip_path_of(rel);
+                    for file in [source.clone(), source.with_extension("ot")] {
+                        if file.is_file() {
+                            let file_rel = zip_path_of(file.strip_prefix(&pool_dir).unwrap());
+                            samples.push(PackedSample {
+                                source: file,
+                                archive_path: format!("AUDIO/{}", file_rel),
+                                kind: "pool_sample",
+                            });
+                        }
+                    }
+                    format!("../AUDIO/{}", rel)
+                } else {
+                    // Outside project and pool: store next to the project files
+                    // under a name no project file uses.
+                    let stem = source.file_stem().unwrap_or_default().to_string_lossy();
+                    let ext = source
+                        .extension()
+                        .map(|e| format!(".{}", e.to_string_lossy()))
+                        .unwrap_or_default();
+                    let is_free = |name: &str| {
+                        let ot = zip_path_of(&Path::new(name).with_extension("ot"));
+                        !taken.contains(&name.to_lowercase()) && !taken.contains(&ot.to_lowercase())
+                    };
+                    let mut name = format!("{}{}", stem, ext);
+                    let mut n = 2;
+                    while !is_free(&name) {
+                        name = format!("{}_{}{}", stem, n, ext);
+                        n += 1;
+                    }
+                    taken.insert(name.to_lowercase());
+                    samples.push(PackedSample {
+                        source: source.clone(),
+                        archive_path: format!("{}/{}", project_name, name),
+                        kind: "external_sample",
+                    });
+                    let ot = source.with_extension("ot");
+                    if ot.is_file() {
+                        let ot_name = Path::new(&name).with_extension("ot");
+                        taken.insert(zip_path_of(&ot_name).to_lowercase());
+                        samples.push(PackedSample {
+                            source: ot,
+                            archive_path: format!("{}/{}", project_name, zip_path_of(&ot_name)),
+                            kind: "external_sample",
+                        });
+                    }
+                    name
+                };
+                packed.insert(source, new_path.clone());
+                new_path
+            }
+        };
+        if new_path != slot_path {
+            new_paths.insert(slot_path, new_path);
+        }
+    }
+
+    let total_bytes = crate::disk_space::files_size(&project_files)
+        + crate::disk_space::files_size(
+            &samples.iter().map(|s| s.source.clone()).collect::<Vec<_>>(),
+        );
+    crate::disk_space::ensure_free_space(&zip_path, total_bytes)?;
+
+    // Write next to the destination and rename at the end: a failed export
+    // never leaves a truncated zip behind.
+    let tmp_path = zip_path.with_extension("zip.tmp");
+    let archive = zip_path.to_string_lossy().to_string();
+    let result = (|| {
+        let file = File::create(&tmp_path)
+            .map_err(|e| format!("Failed to create {}: {}", tmp_path.display(), e))?;
+        let mut zip = ZipWriter::new(file);
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        let mut entries = Vec::new();
+        let mut processed_bytes = 0u64;
+        let mut add = |zip: &mut ZipWriter<File>,
+                       archive_path: String,
+                       data: &mut dyn Read,
+                       kind: &str,
+                       original_path: Option<String>|
+         -> Result<(), String> {
+            zip.start_file(archive_path.as_str(), options)
+                .map_err(|e| format!("Failed to add {} to archive: {}", archive_path, e))?;
+            let size = io::copy(data, zip)
+                .map_err(|e| format!("Failed to add {} to archive: {}", archive_path, e))?;
+            processed_bytes += size;
+            on_progress(ArchiveProgress {
+                archive: archive.clone(),
+                stage: "packing".to_string(),
+                processed_bytes,
+                total_bytes,
+                current_file: Some(archive_path.clone()),
+            });
+            entries.push(ArchiveEntry {
+                archive_path,
+                kind: kind.to_string(),
+                original_path,
+                size,
+            });
+            Ok(())
+        };
+
+        for file in &project_files {
+            let rel = zip_path_of(file.strip_prefix(&project_dir).unwrap_or(file));
+            let mut data =
+                fs::read(file).map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+            let is_project_file = rel == "project.work" || rel == "project.strd";
+            if is_project_file && !new_paths.is_empty() {
+                let (decoded, _, _) = encoding_rs::WINDOWS_1258.decode(&data);
+                let (rewritten, _) = rewrite_slot_paths(&decoded, &new_paths);
+                data = encoding_rs::WINDOWS_1258.encode(&rewritten).0.into_owned();
+            }
+            add(
+                &mut zip,
+                format!("{}/{}", project_name, rel),
+                &mut data.as_slice(),
+                "project",
+                None,
+            )?;
+        }
+        for sample in &samples {
+            let mut data = File::open(&sample.source)
+                .map_err(|e| format!("Failed to read {}: {}", sample.source.display(), e))?;
+            let original_path = (sample.kind == "external_sample")
+                .then(|| sample.source.to_string_lossy().to_string());
+            add(
+                &mut zip,
+                sample.archive_path.clone(),
+                &mut data,
+                sample.kind,
+                original_path,
+            )?;
+        }
+
+        let manifest = ArchiveManifest {
+            format: ARCHIVE_FORMAT,
+            project: project_name.clone(),
+            created_at: chrono::Local::now().to_rfc3339(),
+            files: entries,
+            missing_samples: missing_samples.clone(),
+        };
+        let json = serde_json::to_string_pretty(&manifest)
+            .map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+        zip.start_file(MANIFEST_NAME, options)
+            .map_err(|e| format!("Failed to add manifest to archive: {}", e))?;
+        zip.write_all(json.as_bytes())
+            .map_err(|e| format!("Failed to add manifest to archive: {}", e))?;
+        zip.finish()
+            .map_err(|e| format!("Failed to finish archive: {}", e))?;
+        fs::rename(&tmp_path, &zip_path)
+            .map_err(|e| format!("Failed to write {}: {}", zip_path.display(), e))?;
+        Ok(manifest.files.len())
+    })();
+
+    let file_count = match result {
+        Ok(count) => count,
+        Err(e) => {
+            let _ = fs::remove_file(&tmp_path);
+            return Err(e);
+        }
+    };
+    on_progress(ArchiveProgress {
+        archive: archive.clone(),
+        stage: "complete".to_string(),
+        processed_bytes: total_bytes,
+        total_bytes,
+        current_file: None,
+    });
+    Ok(ArchiveExportResult {
+        archive_path: archive,
+        file_count,
+        total_bytes,
+        missing_samples,
+    })
+}
+
+/// Emits "archive-progress" events while packing.
+#[tauri::command]
+pub async fn export_project_archive(
+    app: AppHandle,
+    path: String,
+    dest: String,
+) -> Result<ArchiveExportResult, String> {
+    crate::fs_scope::ensure_allowed(&dest)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        export_archive(&path, &dest, |progress| {
+            let _ = app.emit("archive-progress", progress);
+        })
+    })
+    .await
+    .unwrap()
+}
+
+/// A file of the archive that would land on something already on disk.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ArchiveConflict {
+    pub archive_path: String,
+    pub dest_path: String,
+    pub kind: String, // "project_exists", "sample_identical", "sample_differs"
+}
+
+/// What importing an archive into a Set would do, for the user to decide on
+/// conflicts before anything is written.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveImportReport {
+    pub project: String,
+    pub file_count: usize,
+    pub total_bytes: u64,
+    pub missing_samples: Vec<String>,
+    pub conflicts: Vec<ArchiveConflict>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ArchiveImportOptions {
+    /// Name for the imported project; defaults to the archived name.
+    #[serde(default)]
+    pub project_name: Option<String>,
+    /// Put Audio Pool samples in the project directory instead of the pool.
+    #[serde(default)]
+    pub samples_in_project: bool,
+    /// "skip" (keep the file on disk), "overwrite" or "rename" (the default).
+    #[serde(default)]
+    pub on_conflict: Option<String>,
+    /// Per-file choices keyed by archive path, overriding `on_conflict`.
+    #[serde(default)]
+    pub resolutions: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveImportResult {
+    pub project_path: String,
+    pub written: Vec<String>,           // files created or overwritten
+    pub reused: Vec<String>,            // existing files used as they are
+    pub renamed: Vec<(String, String)>, // (archive path, file written instead)
+    pub slot_paths_updated: usize,
+}
+
+/// A file in an opened archive; its data is read from the zip when needed.
+struct ArchiveFile {
+    name: String, // archive path
+    index: usize, // position in the zip
+    size: u64,
+}
+
+struct ArchiveContents {
+    manifest: ArchiveManifest,
+    entries: Vec<ArchiveFile>,
+    zip: zip::ZipArchive<File>,
+}
+
+/// Open an archive and list its files. Only the manifest is read here; file
+/// data stays in the zip until it is compared or unpacked.
+fn read_archive(archive_path: &str) -> Result<ArchiveContents, String> {
+    let file =
+        File::open(archive_path).map_err(|e| format!("Failed to open {}: {}", archive_path, e))?;
+    let mut zip =
+        zip::ZipArchive::new(file).map_err(|e| format!("Not a project archive: {}", e))?;
+    let mut manifest = None;
+    let mut entries = Vec::new();
+    for index in 0..zip.len() {
+        let mut entry = zip
+            .by_index(index)
+            .map_err(|e| format!("Failed to read archive: {}", e))?;
+        if entry.is_dir() {
+            continue;
+        }
+        // Refuse entries that would escape the destination (../, absolute paths).
+        let name = entry
+            .enclosed_name()
+            .map(|p| zip_path_of(&p))
+            .ok_or_else(|| format!("Unsafe path in archive: {}", entry.name()))?;
+        if name == MANIFEST_NAME {
+            let mut data = Vec::new();
+            entry
+                .read_to_end(&mut data)
+                .map_err(|e| format!("Failed to read {} from archive: {}", name, e))?;
+            manifest = Some(
+                serde_json::from_slice::<ArchiveManifest>(&data)
+                    .map_err(|e| format!("Invalid archive manifest: {}", e))?,
+            );
+        } else {
+            entries.push(ArchiveFile {
+                name,
+                index,
+                size: entry.size(),
+            });
+        }
+    }
+    let manifest = manifest.ok_or_else(|| "Not a project archive: no manifest".to_string())?;
+    if manifest.format > ARCHIVE_FORMAT {
+        return Err(format!(
+            "Archive format {} is newer than this app supports ({})",
+            manifest.format, ARCHIVE_FORMAT
+        ));
+    }
+    let prefix = format!("{}/", manifest.project);
+    if let Some(file) = entries
+        .iter()
+        .find(|f| !f.name.starts_with(&prefix) && !f.name.starts_with("AUDIO/"))
+    {
+        return Err(format!("Unexpected file in archive: {}", file.name));
+    }
+    Ok(ArchiveContents {
+        manifest,
+        entries,
+        zip,
+    })
+}
+
+impl ArchiveContents {
+    fn total_bytes(&self) -> u64 {
+        self.entries.iter().map(|f| f.size).sum()
+    }
+
+    /// Whether `path` holds the same bytes as the archived file.
+    fn same_contents(&mut self, file: &ArchiveFile, path: &Path) -> bool {
+        if fs::metadata(path).ok().map(|m| m.len()) != Some(file.size) {
+            return false;
+        }
+        let archived = match self.zip.by_index(file.index) {
+            Ok(entry) => crate::audio_pool::content_hash(entry).ok(),
+            Err(_) => None,
+        };
+        archived.is_some() && archived == crate::audio_pool::file_content_hash(path).ok()
+    }
+
+    fn read(&mut self, file: &ArchiveFile) -> Result<Vec<u8>, String> {
+        let mut data = Vec::new();
+        self.zip
+            .by_index(file.index)
+            .and_then(|mut entry| Ok(entry.read_to_end(&mut data)?))
+            .map_err(|e| format!("Failed to read {} from archive: {}", file.name, e))?;
+        Ok(data)
+    }
+
+    /// Stream an archived file to `dest`.
+    fn unpack(&mut self, file: &ArchiveFile, dest: &Path) -> Result<(), String> {
+        let mut entry = self
+            .zip
+            .by_index(file.index)
+            .map_err(|e| format!("Failed to read {} from archive: {}", file.name, e))?;
+        let mut out =
+            File::create(dest).map_err(|e| format!("Failed to write {}: {}", dest.display(), e))?;
+        io::copy(&mut entry, &mut out)
+            .map_err(|e| format!("Failed to write {}: {}", dest.display(), e))?;
+        Ok(())
+    }
+}
+
+/// `path` if free, otherwise `<stem>_N.<ext>` next to it.
+fn free_path(path: &Path) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let ext = path
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+    let mut candidate = path.to_path_buf();
+    let mut n = 2;
+    while candidate.exists() || candidate.with_extension("ot").exists() {
+        candidate = path.with_file_name(format!("{}_{}{}", stem, n, ext));
+        n += 1;
+    }
+    candidate
+}
+
+/// Check what importing `archive_path` into `set_path` would collide with.
+pub fn inspect_archive(
+    archive_path: &str,
+    set_path: &str,
+    project_name: Option<&str>,
+) -> Result<ArchiveImportReport, String> {
+    let mut contents = read_archive(archive_path)?;
+    let set_dir = Path::new(set_path);
+    let project = project_name.unwrap_or(&contents.manifest.project);
+    let mut conflicts = Vec::new();
+    let project_dir = set_dir.join(project);
+    if project_dir.exists() {
+        conflicts.push(ArchiveConflict {
+            archive_path: format!("{}/", contents.manifest.project),
+            dest_path: project_dir.to_string_lossy().to_string(),
+            kind: "project_exists".to_string(),
+        });
+    }
+    let entries = std::mem::take(&mut contents.entries);
+    for file in entries.iter().filter(|f| f.name.starts_with("AUDIO/")) {
+        let dest = set_dir.join(&file.name);
+        if dest.is_file() {
+            let identical = contents.same_contents(file, &dest);
+            conflicts.push(ArchiveConflict {
+                archive_path: file.name.clone(),
+                dest_path: dest.to_string_lossy().to_string(),
+                kind: if identical {
+                    "sample_identical"
+                } else {
+                    "sample_differs"
+                }
+                .to_string(),
+            });
+        }
+    }
+    Ok(ArchiveImportReport {
+        project: contents.manifest.project.clone(),
+        file_count: entries.len(),
+        total_bytes: entries.iter().map(|f| f.size).sum(),
+        missing_samples: contents.manifest.missing_samples.clone(),
+        conflicts,
+    })
+}
+
+/// Unpack `archive_path` into the Set at `set_path`. The project goes in its
+/// own directory (which must not exist yet); pool samples go to the Set's
+/// Audio Pool, or the project directory with `samples_in_project`. A pool
+/// sample that already exists with the same contents is reused; one that
+/// differs is kept, overwritten or written under a new name according to the
+/// options. Slot paths are rewritten to wherever the samples ended up. The
+/// project directory is removed again if the import fails.
+pub fn import_archive(
+    archive_path: &str,
+    set_path: &str,
+    options: &ArchiveImportOptions,
+    mut on_progress: impl FnMut(ArchiveProgress),
+) -> Result<ArchiveImportResult, String> {
+    let mut contents = read_archive(archive_path)?;
+    let set_dir = Path::new(set_path);
+    if !set_dir.is_dir() {
+        return Err(format!("Set directory not found: {}", set_path));
+    }
+    let archived_name = contents.manifest.project.clone();
+    let project = options
+        .project_name
+        .clone()
+        .unwrap_or(archived_name.clone());
+    crate::project_manager::validate_project_name(&project)?;
+    let project_dir = set_dir.join(&project);
+    if project_dir.exists() {
+        return Err(format!(
+            "A project named {} already exists in this Set; choose another name",
+            project
+        ));
+    }
+    let default_policy = options.on_conflict.as_deref().unwrap_or("rename");
+    for policy in
+        std::iter::once(default_policy).chain(options.resolutions.values().map(String::as_str))
+    {
+        if !["skip", "overwrite", "rename"].contains(&policy) {
+            return Err(format!(
+                "Invalid conflict choice: {}. Must be 'skip', 'overwrite' or 'rename'",
+                policy
+            ));
+        }
+    }
+
+    let total_bytes = contents.total_bytes();
+    crate::disk_space::ensure_free_space(set_dir, total_bytes)?;
+    let prefix = format!("{}/", archived_name);
+
+    let mut result = ArchiveImportResult {
+        project_path: project_dir.to_string_lossy().to_string(),
+        written: Vec::new(),
+        reused: Vec::new(),
+        renamed: Vec::new(),
+        slot_paths_updated: 0,
+    };
+    let outcome = (|| {
+        fs::create_dir_all(&project_dir)
+            .map_err(|e| format!("Failed to create {}: {}", project_dir.display(), e))?;
+
+        // Pool samples first, audio before .ot so a renamed sample takes its
+        // sidecar along.
+        let entries = std::mem::take(&mut contents.entries);
+        let mut pool_entries: Vec<&ArchiveFile> = entries
+            .iter()
+            .filter(|f| f.name.starts_with("AUDIO/"))
+            .collect();
+        pool_entries.sort_by_key(|f| (f.name.to_lowercase().ends_with(".ot"), f.name.clone()));
+        let mut new_paths: HashMap<String, String> = HashMap::new();
+        // Archive stem of a renamed (Some) or skipped (None) sample, for its .ot.
+        let mut moved_stems: HashMap<String, Option<PathBuf>> = HashMap::new();
+        let mut processed_bytes = 0u64;
+        for file in pool_entries {
+            let name = &file.name;
+            let rel = &name["AUDIO/".len()..];
+            let mut dest = if options.samples_in_project {
+                project_dir.join(rel)
+            } else {
+                set_dir.join(name)
+            };
+            let stem = Path::new(name)
+                .with_extension("")
+                .to_string_lossy()
+                .to_string();
+            let is_sidecar = name.to_lowercase().ends_with(".ot");
+            let mut write = true;
+            match moved_stems.get(&stem).cloned() {
+                Some(Some(sample)) if is_sidecar => dest = sample.with_extension("ot"),
+                Some(None) if is_sidecar => write = false,
+                _ if dest.is_file() => {
+                    if contents.same_contents(file, &dest) {
+                        result.reused.push(dest.to_string_lossy().to_string());
+                        write = false;
+                    } else {
+                        let policy = options
+                            .resolutions
+                            .get(name)
+                            .map(String::as_str)
+                            .unwrap_or(default_policy);
+                        match policy {
+                            "skip" => {
+                                result.reused.push(dest.to_string_lossy().to_string());
+                                moved_stems.insert(stem.clone(), None);
+                                write = false;
+                            }
+                            "rename" => {
+                                let renamed = free_path(&dest);
+                                result
+                                    .renamed
+                                    .push((name.clone(), renamed.to_string_lossy().to_string()));
+                                moved_stems.insert(stem.clone(), Some(renamed.clone()));
+                                dest = renamed;
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                _ => {}
+            }
+            if write {
+                if let Some(parent) = dest.parent() {
+                    fs::create_dir_all(parent)
+                        .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+                }
+                contents.unpack(file, &dest)?;
+                result.written.push(dest.to_string_lossy().to_string());
+            }
+            if !is_sidecar {
+                let slot_path = if options.samples_in_project {
+                    zip_path_of(dest.strip_prefix(&project_dir).unwrap_or(&dest))
+                } else {
+                    format!(
+                        "../{}",
+                        zip_path_of(dest.strip_prefix(set_dir).unwrap_or(&dest))
+                    )
+                };
+                let archived = format!("../{}", name);
+                if slot_path != archived {
+                    new_paths.insert(archived, slot_path);
+                }
+            }
+            processed_bytes += file.size;
+            on_progress(ArchiveProgress {
+                archive: archive_path.to_string(),
+                stage: "unpacking".to_string(),
+                processed_bytes,
+                total_bytes,
+                current_file: Some(name.clone()),
+            });
+        }
+
+        for file in entries.iter().filter(|f| f.name.starts_with(&prefix)) {
+            let name = &file.name;
+            let rel = &name[prefix.len()..];
+            let dest = project_dir.join(rel);
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+            }
+            if (rel == "project.work" || rel == "project.strd") && !new_paths.is_empty() {
+                let data = contents.read(file)?;
+                let (decoded, _, _) = encoding_rs::WINDOWS_1258.decode(&data);
+                let (rewritten, updated) = rewrite_slot_paths(&decoded, &new_paths);
+                result.slot_paths_updated += updated;
+                let encoded = encoding_rs::WINDOWS_1258.encode(&rewritten).0;
+                crate::atomic_write::write_atomic(&dest, &encoded)?;
+            } else {
+                contents.unpack(file, &dest)?;
+            }
+            processed_bytes += file.size;
+            on_progress(ArchiveProgress {
+                archive: archive_path.to_string(),
+                stage: "unpacking".to_string(),
+                processed_bytes,
+                total_bytes,
+                current_file: Some(name.clone()),
+            });
+        }
+        Ok::<(), String>(())
+    })();
+
+    if let Err(e) = outcome {
+        let _ = fs::remove_dir_all(&project_dir);
+        return Err(e);
+    }
+    on_progress(ArchiveProgress {
+        archive: archive_path.to_string(),
+        stage: "complete".to_string(),
+        processed_bytes: total_bytes,
+        total_bytes,
+        current_file: None,
+    });
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn inspect_project_archive(
+    archive: String,
+    set_path: String,
+    project_name: Option<String>,
+) -> Result<ArchiveImportReport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        inspect_archive(&archive, &set_path, project_name.as_deref())
+    })
+    .await
+    .unwrap()
+}
+
+/// Emits "archive-progress" events while unpacking.
+#[tauri::command]
+pub async fn import_project_archive(
+    app: AppHandle,
+    archive: String,
+    set_path: String,
+    options: Option<ArchiveImportOptions>,
+) -> Result<ArchiveImportResult, String> {
+    crate::fs_scope::ensure_allowed(&set_path)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        import_archive(
+            &archive,
+            &set_path,
+            &options.unwrap_or_default(),
+            |progress| {
+                let _ = app.emit("archive-progress", progress);
+            },
+        )
+    })
+    .await
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ot_tools_io::projects::SlotAttributes;
+    use ot_tools_io::settings::SlotType;
+    use ot_tools_io::{BankFile, OctatrackFileIO, ProjectFile};
+    use std::io::Read;
+    use tempfile::TempDir;
+
+    fn slot(slot_type: SlotType, id: u8, path: &str) -> Option<SlotAttributes> {
+        Some(
+            SlotAttributes::new(
+                slot_type,
+                id,
+                Some(PathBuf::from(path)),
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap(),
+        )
+    }
+
+    /// A Set with project SONG loading a pool sample (with .ot), a sample
+    /// from elsewhere on disk and a missing file.
+    fn make_set(root: &Path) -> PathBuf {
+        let set = root.join("SET");
+        let project = set.join("SONG");
+        fs::create_dir_all(set.join("AUDIO/drums")).unwrap();
+        fs::create_dir_all(&project).unwrap();
+        fs::create_dir_all(root.join("elsewhere")).unwrap();
+        fs::write(set.join("AUDIO/drums/kick.wav"), b"RIFF kick").unwrap();
+        fs::write(set.join("AUDIO/drums/kick.ot"), b"FORM kick").unwrap();
+        fs::write(set.join("AUDIO/unused.wav"), b"RIFF unused").unwrap();
+        let pad = root.join("elsewhere/pad.wav");
+        fs::write(&pad, b"RIFF pad").unwrap();
+
+        let mut file = ProjectFile::default();
+        file.slots.static_slots[0] = slot(SlotType::Static, 1, "../AUDIO/drums/kick.wav");
+        file.slots.flex_slots[0] = slot(SlotType::Flex, 1, &pad.to_string_lossy());
+        file.slots.flex_slots[1] = slot(SlotType::Flex, 2, "../AUDIO/gone.wav");
+        file.to_data_file(&project.join("project.work")).unwrap();
+        BankFile::default()
+            .to_data_file(&project.join("bank01.work"))
+            .unwrap();
+        fs::write(project.join(crate::project_lock::LOCK_FILE), b"lock").unwrap();
+        project
+    }
+
+    fn read_entry(zip_path: &str, name: &str) -> Vec<u8> {
+        let mut archive = zip::ZipArchive::new(File::open(zip_path).unwrap()).unwrap();
+        let mut data = Vec::new();
+        archive
+            .by_name(name)
+            .unwrap()
+            .read_to_end(&mut data)
+            .unwrap();
+        data
+    }
+
+    #[test]
+    fn test_export_archive_packs_project_and_samples() {
+        let dir = TempDir::new().unwrap();
+        let project = make_set(dir.path());
+        let mut events = Vec::new();
+
+        let result = export_archive(
+            &project.to_string_lossy(),
+            &dir.path().to_string_lossy(),
+            |p| events.push(p.stage),
+        )
+        .unwrap();
+
+        assert!(result.archive_path.ends_with("SONG.zip"));
+        assert_eq!(result.missing_samples, vec!["../AUDIO/gone.wav"]);
+        assert_eq!(events.last().map(String::as_str), Some("complete"));
+
+        let mut archive = zip::ZipArchive::new(File::open(&result.archive_path).unwrap()).unwrap();
+        let mut names: Vec<String> = archive.file_names().map(str::to_string).collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                "AUDIO/drums/kick.ot",
+                "AUDIO/drums/kick.wav",
+                "SONG/bank01.work",
+                "SONG/pad.wav",
+                "SONG/project.work",
+                "manifest.json",
+            ]
+        );
+
+        // The external sample now sits next to the project files.
+        let packed = dir.path().join("packed.work");
+        fs::write(
+            &packed,
+            read_entry(&result.archive_path, "SONG/project.work"),
+        )
+        .unwrap();
+        let raw = read_raw_sample_fields(&packed).unwrap();
+        assert_eq!(raw[&("FLEX".to_string(), 1)]["PATH"], "pad.wav");
+        assert_eq!(
+            raw[&("STATIC".to_string(), 1)]["PATH"],
+            "../AUDIO/drums/kick.wav"
+        );
+
+        let manifest: ArchiveManifest =
+            serde_json::from_slice(&read_entry(&result.archive_path, MANIFEST_NAME)).unwrap();
+        assert_eq!(manifest.project, "SONG");
+        let pad = manifest
+            .files
+            .iter()
+            .find(|f| f.archive_path == "SONG/pad.wav")
+            .unwrap();
+        assert_eq!(pad.kind, "external_sample");
+        assert!(pad.original_path.as_ref().unwrap().ends_with("pad.wav"));
+    }
+
+    #[test]
+    fn test_import_archive_restores_project_and_resolves_conflicts() {
+        let dir = TempDir::new().unwrap();
+        let project = make_set(dir.path());
+        let zip_path = export_archive(
+            &project.to_string_lossy(),
+            &dir.path().join("song.zip").to_string_lossy(),
+            |_| {},
+        )
+        .unwrap()
+        .archive_path;
+
+        // The target Set already has a different kick.
+        let target = dir.path().join("OTHER");
+        fs::create_dir_all(target.join("AUDIO/drums")).unwrap();
+        fs::write(target.join("AUDIO/drums/kick.wav"), b"RIFF other").unwrap();
+        let target_str = target.to_string_lossy().to_string();
+
+        let report = inspect_archive(&zip_path, &target_str, None).unwrap();
+        assert_eq!(report.project, "SONG");
+        assert_eq!(
+            report.conflicts,
+            vec![ArchiveConflict {
+                archive_path: "AUDIO/drums/kick.wav".to_string(),
+                dest_path: target
+                    .join("AUDIO/drums/kick.wav")
+                    .to_string_lossy()
+                    .to_string(),
+                kind: "sample_differs".to_string(),
+            }]
+        );
+
+        let result = import_archive(
+            &zip_path,
+            &target_str,
+            &ArchiveImportOptions::default(),
+            |_| {},
+        )
+        .unwrap();
+        assert_eq!(result.renamed.len(), 1);
+        assert_eq!(result.slot_paths_updated, 1);
+        assert_eq!(
+            fs::read(target.join("AUDIO/drums/kick_2.wav")).unwrap(),
+            b"RIFF kick"
+        );
+        assert!(target.join("AUDIO/drums/kick_2.ot").exists());
+        assert_eq!(
+            fs::read(target.join("AUDIO/drums/kick.wav")).unwrap(),
+            b"RIFF other"
+        );
+        let raw = read_raw_sample_fields(&target.join("SONG/project.work")).unwrap();
+        assert_eq!(
+            raw[&("STATIC".to_string(), 1)]["PATH"],
+            "../AUDIO/drums/kick_2.wav"
+        );
+        assert!(target.join("SONG/pad.wav").exists());
+
+        // Importing again needs a new name; skipping keeps the Set's own kick.
+        assert!(import_archive(
+            &zip_path,
+            &target_str,
+            &ArchiveImportOptions::default(),
+            |_| {}
+        )
+        .is_err());
+        let options = ArchiveImportOptions {
+            project_name: Some("SONG2".to_string()),
+            on_conflict: Some("skip".to_string()),
+            ..Default::default()
+        };
+        let again = import_archive(&zip_path, &target_str, &options, |_| {}).unwrap();
+        assert!(again.renamed.is_empty());
+        assert!(target.join("SONG2/bank01.work").exists());
+    }
+
+    #[test]
+    fn test_rewrite_slot_paths_only_touches_sample_blocks() {
+        let content = "[SETTINGS]\r\nPATH=x\r\n[/SETTINGS]\r\n[SAMPLE]\r\nPATH=x\r\n[/SAMPLE]\r\n";
+        let new_paths = HashMap::from([("x".to_string(), "../AUDIO/x".to_string())]);
+        assert_eq!(
+            rewrite_slot_paths(content, &new_paths),
+            (
+                "[SETTINGS]\r\nPATH=x\r\n[/SETTINGS]\r\n[SAMPLE]\r\nPATH=../AUDIO/x\r\n[/SAMPLE]\r\n"
+                    .to_string(),
+                1
+            )
+        );
+    }
+}
@@ -0,0 +1,893 @@
+// Project diff: compares two copies of a project (a backup snapshot, an older
+// copy, or the live project) and turns the differences into changelog lines.
+//
+// Either side may be a partial snapshot (backups only hold the files that were
+// about to be modified); banks or the project file missing on one side are
+// simply not compared.
+
+use crate::app_data::{staging_dir, StagingDir};
+use crate::param_decode::{fx_type_name, machine_type_name};
+use crate::project_reader::{
+    bank_file_path, bank_index, bank_letter, read_parts_data, read_project_metadata,
+    read_single_bank, Bank, PartData, Pattern, ProjectMetadata,
+};
+use ot_tools_io::BankFile;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+
+/// One difference between two project states.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProjectChange {
+    pub bank: Option<u8>, // 0-15, None for project-level changes
+    pub location: String, // "Bank C Pattern 5", "Bank C Part 2", "Project"
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectChangelog {
+    pub changes: Vec<ProjectChange>,
+    /// One line per location, e.g. "Bank C Pattern 5: +3 trigs on T2; length 16 → 32".
+    pub lines: Vec<String>,
+}
+
+fn count_steps(masks: &[u8]) -> i32 {
+    masks.iter().map(|&m| m.count_ones() as i32).sum()
+}
+
+fn part_name(bank: &BankFile, part_idx: usize) -> String {
+    let bytes = &bank.part_names[part_idx];
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).to_string()
+}
+
+/// "+3 trigs on T2" / "-1 trig on M4"
+fn trig_delta(delta: i32, kind: &str, track: &str) -> String {
+    let plural = if delta.abs() == 1 { "" } else { "s" };
+    format!("{:+} {}{} on {}", delta, kind, plural, track)
+}
+
+/// Differences between two versions of the same bank.
+pub fn bank_changes(bank_index: u8, old: &BankFile, new: &BankFile) -> Vec<ProjectChange> {
+    let letter = bank_letter(bank_index);
+    let mut changes = Vec::new();
+    let mut push = |location: String, description: String| {
+        changes.push(ProjectChange {
+            bank: Some(bank_index),
+            location,
+            description,
+        });
+    };
+
+    for p in 0..16 {
+        let (old_pat, new_pat) = (&old.patterns.0[p], &new.patterns.0[p]);
+        let location = format!("Bank {} Pattern {}", letter, p + 1);
+
+        if old_pat.part_assignment != new_pat.part_assignment {
+            push(
+                location.clone(),
+                format!("now uses Part {}", new_pat.part_assignment as usize + 1),
+            );
+        }
+        if old_pat.scale.master_len != new_pat.scale.master_len {
+            push(
+                location.clone(),
+                format!(
+                    "length {} → {}",
+                    old_pat.scale.master_len, new_pat.scale.master_len
+                ),
+            );
+        }
+
+        for t in 0..8 {
+            let (old_trk, new_trk) = (
+                &old_pat.audio_track_trigs.0[t],
+                &new_pat.audio_track_trigs.0[t],
+            );
+            let track = format!("T{}", t + 1);
+            let trigs =
+                count_steps(&new_trk.trig_masks.trigger) - count_steps(&old_trk.trig_masks.trigger);
+            if trigs != 0 {
+                push(location.clone(), trig_delta(trigs, "trig", &track));
+            }
+            let trigless = count_steps(&new_trk.trig_masks.trigless)
+                - count_steps(&old_trk.trig_masks.trigless);
+            if trigless != 0 {
+                push(
+                    location.clone(),
+                    trig_delta(trigless, "trigless trig", &track),
+                );
+            }
+        }
+        for t in 0..8 {
+            let (old_trk, new_trk) = (
+                &old_pat.midi_track_trigs.0[t],
+                &new_pat.midi_track_trigs.0[t],
+            );
+            let track = format!("M{}", t + 1);
+            let trigs =
+                count_steps(&new_trk.trig_masks.trigger) - count_steps(&old_trk.trig_masks.trigger);
+            if trigs != 0 {
+                push(location.clone(), trig_delta(trigs, "trig", &track));
+            }
+            let trigless = count_steps(&new_trk.trig_masks.trigless)
+                - count_steps(&old_trk.trig_masks.trigless);
+            if trigless != 0 {
+                push(
+                    location.clone(),
+                    trig_delta(trigless, "trigless trig", &track),
+                );
+            }
+        }
+    }
+
+    // Parts: compare the working (unsaved) state, which is what the OT plays.
+    for part_idx in 0..4 {
+        let (old_part, new_part) = (
+            &old.parts.unsaved.0[part_idx],
+            &new.parts.unsaved.0[part_idx],
+        );
+        let location = format!("Bank {} Part {}", letter, part_idx + 1);
+
+        let (old_name, new_name) = (part_name(old, part_idx), part_name(new, part_idx));
+        if old_name != new_name {
+            push(location.clone(), format!("renamed to \"{}\"", new_name));
+        }
+
+        for t in 0..8 {
+            let track = format!("T{}", t + 1);
+            if old_part.audio_track_machine_types[t] != new_part.audio_track_machine_types[t] {
+                push(
+                    location.clone(),
+                    format!(
+                        "{} machine changed to {}",
+                        track,
+                        machine_type_name(new_part.audio_track_machine_types[t])
+                    ),
+                );
+            }
+            let (old_slot, new_slot) = (
+                &old_part.audio_track_machine_slots[t],
+                &new_part.audio_track_machine_slots[t],
+            );
+            if old_slot.static_slot_id != new_slot.static_slot_id
+                || old_slot.flex_slot_id != new_slot.flex_slot_id
+            {
+                let slot = match new_part.audio_track_machine_types[t] {
+                    1 => format!("Flex {}", new_slot.flex_slot_id as u16 + 1),
+                    _ => format!("Static {}", new_slot.static_slot_id as u16 + 1),
+                };
+                push(
+                    location.clone(),
+                    format!("{} sample slot changed to {}", track, slot),
+                );
+            }
+            if old_part.audio_track_fx1[t] != new_part.audio_track_fx1[t] {
+                push(
+                    location.clone(),
+                    format!(
+                        "{} FX1 changed to {}",
+                        track,
+                        fx_type_name(new_part.audio_track_fx1[t])
+                    ),
+                );
+            }
+            if old_part.audio_track_fx2[t] != new_part.audio_track_fx2[t] {
+                push(
+                    location.clone(),
+                    format!(
+                        "{} FX2 changed to {}",
+                        track,
+                        fx_type_name(new_part.audio_track_fx2[t])
+                    ),
+                );
+            }
+        }
+    }
+
+    changes
+}
+
+/// Project-level differences: tempo and sample slot assignments.
+pub fn project_setting_changes(old: &ProjectMetadata, new: &ProjectMetadata) -> Vec<ProjectChange> {
+    let mut changes = Vec::new();
+    let mut push = |description: String| {
+        changes.push(ProjectChange {
+            bank: None,
+            location: "Project".to_string(),
+            description,
+        });
+    };
+
+    if (old.tempo - new.tempo).abs() > f32::EPSILON {
+        push(format!("tempo {} → {} BPM", old.tempo, new.tempo));
+    }
+
+    for (kind, old_slots, new_slots) in [
+        (
+            "Static",
+            &old.sample_slots.static_slots,
+            &new.sample_slots.static_slots,
+        ),
+        (
+            "Flex",
+            &old.sample_slots.flex_slots,
+            &new.sample_slots.flex_slots,
+        ),
+    ] {
+        let mut ids: Vec<u8> = old_slots
+            .iter()
+            .chain(new_slots.iter())
+            .map(|s| s.slot_id)
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+        for id in ids {
+            let old_path = old_slots
+                .iter()
+                .find(|s| s.slot_id == id)
+                .and_then(|s| s.path.clone());
+            let new_path = new_slots
+                .iter()
+                .find(|s| s.slot_id == id)
+                .and_then(|s| s.path.clone());
+            if old_path == new_path {
+                continue;
+            }
+            match new_path {
+                Some(path) => push(format!("{} slot {} → {}", kind, id, path)),
+                None => push(format!("{} slot {} cleared", kind, id)),
+            }
+        }
+    }
+
+    changes
+}
+
+/// All differences from `old_dir` to `new_dir`.
+pub fn project_changes(old_dir: &str, new_dir: &str) -> Result<Vec<ProjectChange>, String> {
+    let (old_path, new_path) = (Path::new(old_dir), Path::new(new_dir));
+    if !old_path.is_dir() {
+        return Err(format!("Snapshot not found: {}", old_dir));
+    }
+    if !new_path.is_dir() {
+        return Err(format!("Snapshot not found: {}", new_dir));
+    }
+
+    let mut changes = Vec::new();
+
+    if let (Ok(old_meta), Ok(new_meta)) = (
+        read_project_metadata(old_dir),
+        read_project_metadata(new_dir),
+    ) {
+        changes.extend(project_setting_changes(&old_meta.data, &new_meta.data));
+    }
+
+    for bank_index in 0..16u8 {
+        let (Ok(old_file), Ok(new_file)) = (
+            bank_file_path(old_path, bank_index),
+            bank_file_path(new_path, bank_index),
+        ) else {
+            continue;
+        };
+        let old_bank = crate::os_compat::read_bank_file(&old_file)
+            .map_err(|e| format!("Failed to read {}: {}", old_file.display(), e))?;
+        let new_bank = crate::os_compat::read_bank_file(&new_file)
+            .map_err(|e| format!("Failed to read {}: {}", new_file.display(), e))?;
+        changes.extend(bank_changes(bank_index, &old_bank, &new_bank));
+    }
+
+    Ok(changes)
+}
+
+/// Join changes into one line per location, keeping first-seen order.
+pub fn changelog_lines(changes: &[ProjectChange]) -> Vec<String> {
+    let mut grouped: Vec<(&str, Vec<&str>)> = Vec::new();
+    for change in changes {
+        match grouped.iter_mut().find(|(loc, _)| *loc == change.location) {
+            Some((_, descriptions)) => descriptions.push(&change.description),
+            None => grouped.push((&change.location, vec![&change.description])),
+        }
+    }
+    grouped
+        .into_iter()
+        .map(|(location, descriptions)| format!("{}: {}", location, descriptions.join("; ")))
+        .collect()
+}
+
+/// Human-readable changelog between two snapshots (or a snapshot and the live project).
+pub fn generate_changelog(old_dir: &str, new_dir: &str) -> Result<ProjectChangelog, String> {
+    let changes = project_changes(old_dir, new_dir)?;
+    let lines = changelog_lines(&changes);
+    Ok(ProjectChangelog { changes, lines })
+}
+
+#[tauri::command]
+pub async fn generate_project_changelog(
+    old_path: String,
+    new_path: String,
+) -> Result<ProjectChangelog, String> {
+    tauri::async_runtime::spawn_blocking(move || generate_changelog(&old_path, &new_path))
+        .await
+        .unwrap()
+}
+
+// Structured diff: every changed value with its before/after, for side-by-side
+// comparison of two projects (typically a backup against the working card).
+// Works on the decoded structures the UI already shows, so parameter paths
+// match the field names the frontend uses.
+
+/// Decoded fields that are derived from other fields or from files around the
+/// project rather than stored in it; comparing them would only add noise.
+const DERIVED_FIELDS: &[&str] = &[
+    "trig_counts",
+    "active_tracks",
+    "has_swing",
+    "plock_count",
+    "color_label",
+    "assigned_sample_name",
+    "slice_count",
+    "file_exists",
+    "compatibility",
+    "file_format",
+    "bit_depth",
+    "sample_rate",
+    "ot_size_bytes",
+    "attributes_at_default",
+    "ot_attributes",
+    "source_location",
+    "flex_ram_free_mb",
+    "flex_ram_free_bytes",
+];
+
+/// One value that differs between project A and project B.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ValueChange {
+    pub section: String,   // "settings", "sample_slots", "parts", "patterns"
+    pub bank: Option<u8>,  // 0-15, None for project-level values
+    pub location: String,  // "Project", "Flex slot 3", "Bank C Part 2", "Bank C Pattern 5"
+    pub parameter: String, // dotted field path, e.g. "fxs.0.fx1_type", "tracks.1.steps.4.trigger"
+    pub before: Value,     // null when absent in A
+    pub after: Value,      // null when absent in B
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectDiff {
+    pub changes: Vec<ValueChange>,
+    pub banks_only_in_a: Vec<String>, // bank letters
+    pub banks_only_in_b: Vec<String>,
+}
+
+fn field_path(parent: &str, key: &str) -> String {
+    if parent.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", parent, key)
+    }
+}
+
+/// Walk two JSON trees and record every differing leaf as (path, before, after).
+fn collect_value_changes(
+    path: &str,
+    before: &Value,
+    after: &Value,
+    out: &mut Vec<(String, Value, Value)>,
+) {
+    if before == after {
+        return;
+    }
+    match (before, after) {
+        (Value::Object(a), Value::Object(b)) => {
+            let keys = a.keys().chain(b.keys().filter(|k| !a.contains_key(*k)));
+            for key in keys {
+                if DERIVED_FIELDS.contains(&key.as_str()) {
+                    continue;
+                }
+                collect_value_changes(
+                    &field_path(path, key),
+                    a.get(key).unwrap_or(&Value::Null),
+                    b.get(key).unwrap_or(&Value::Null),
+                    out,
+                );
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            for i in 0..a.len().max(b.len()) {
+                collect_value_changes(
+                    &field_path(path, &i.to_string()),
+                    a.get(i).unwrap_or(&Value::Null),
+                    b.get(i).unwrap_or(&Value::Null),
+                    out,
+                );
+            }
+        }
+        _ => out.push((path.to_string(), before.clone(), after.clone())),
+    }
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<Value, String> {
+    serde_json::to_value(value).map_err(|e| format!("Failed to encode for diff: {}", e))
+}
+
+fn push_value_changes(
+    changes: &mut Vec<ValueChange>,
+    section: &str,
+    bank: Option<u8>,
+    location: &str,
+    before: &Value,
+    after: &Value,
+) {
+    let mut leaves = Vec::new();
+    collect_value_changes("", before, after, &mut leaves);
+    changes.extend(
+        leaves
+            .into_iter()
+            .map(|(parameter, before, after)| ValueChange {
+                section: section.to_string(),
+                bank,
+                location: location.to_string(),
+                parameter,
+                before,
+                after,
+            }),
+    );
+}
+
+/// Project settings (tempo, mixer, memory, MIDI, metronome) and sample slots.
+/// The "current state" block (selected bank/track, mutes) is UI state and skipped.
+fn project_value_changes(
+    a: &ProjectMetadata,
+    b: &ProjectMetadata,
+) -> Result<Vec<ValueChange>, String> {
+    let mut changes = Vec::new();
+
+    let settings = |meta: &ProjectMetadata| -> Result<Value, String> {
+        let mut value = to_json(meta)?;
+        if let Value::Object(map) = &mut value {
+            map.remove("current_state");
+            map.remove("sample_slots");
+        }
+        Ok(value)
+    };
+    push_value_changes(
+        &mut changes,
+        "settings",
+        None,
+        "Project",
+        &settings(a)?,
+        &settings(b)?,
+    );
+
+    for (kind, slots_a, slots_b) in [
+        (
+            "Static",
+            &a.sample_slots.static_slots,
+            &b.sample_slots.static_slots,
+        ),
+        (
+            "Flex",
+            &a.sample_slots.flex_slots,
+            &b.sample_slots.flex_slots,
+        ),
+    ] {
+        let mut ids: Vec<u8> = slots_a
+            .iter()
+            .chain(slots_b.iter())
+            .map(|s| s.slot_id)
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+        for id in ids {
+            let slot = |slots: &[crate::project_reader::SampleSlot]| -> Result<Value, String> {
+                match slots.iter().find(|s| s.slot_id == id) {
+                    Some(s) => to_json(s),
+                    None => Ok(Value::Null),
+                }
+            };
+            push_value_changes(
+                &mut changes,
+                "sample_slots",
+                None,
+                &format!("{} slot {}", kind, id),
+                &slot(slots_a.as_slice())?,
+                &slot(slots_b.as_slice())?,
+            );
+        }
+    }
+
+    Ok(changes)
+}
+
+/// Patterns of a decoded bank in pattern order (the reader groups them by part).
+fn bank_patterns(bank: &Bank) -> Vec<&Pattern> {
+    let mut patterns: Vec<&Pattern> = bank.parts.iter().flat_map(|p| &p.patterns).collect();
+    patterns.sort_by_key(|p| p.id);
+    patterns
+}
+
+fn bank_value_changes(
+    project_a: &str,
+    project_b: &str,
+    bank_index: u8,
+) -> Result<Vec<ValueChange>, String> {
+    let letter = bank_letter(bank_index);
+    let bank_id = letter.to_string();
+    let load = |project: &str| -> Result<Bank, String> {
+        read_single_bank(project, bank_index)?
+            .ok_or_else(|| format!("Bank {} does not exist in {}", letter, project))
+    };
+    let (bank_a, bank_b) = (load(project_a)?, load(project_b)?);
+    let (parts_a, parts_b) = (
+        read_parts_data(project_a, &bank_id)?.parts,
+        read_parts_data(project_b, &bank_id)?.parts,
+    );
+
+    let mut changes = Vec::new();
+    let bank = Some(bank_index);
+
+    for part_idx in 0..4 {
+        let location = format!("Bank {} Part {}", letter, part_idx + 1);
+        let name = |b: &Bank| {
+            b.parts
+                .get(part_idx)
+                .map(|p| Value::String(p.name.clone()))
+                .unwrap_or(Value::Null)
+        };
+        let (name_a, name_b) = (name(&bank_a), name(&bank_b));
+        if name_a != name_b {
+            changes.push(ValueChange {
+                section: "parts".to_string(),
+                bank,
+                location: location.clone(),
+                parameter: "name".to_string(),
+                before: name_a,
+                after: name_b,
+            });
+        }
+        let part = |parts: &[PartData]| -> Result<Value, String> {
+            match parts.iter().find(|p| p.part_id as usize == part_idx) {
+                Some(p) => to_json(p),
+                None => Ok(Value::Null),
+            }
+        };
+        push_value_changes(
+            &mut changes,
+            "parts",
+            bank,
+            &location,
+            &part(&parts_a[..])?,
+            &part(&parts_b[..])?,
+        );
+    }
+
+    let (patterns_a, patterns_b) = (bank_patterns(&bank_a), bank_patterns(&bank_b));
+    for p in 0..16u8 {
+        let pattern = |patterns: &[&Pattern]| -> Result<Value, String> {
+            match patterns.iter().find(|pat| pat.id == p) {
+                Some(pat) => to_json(pat),
+                None => Ok(Value::Null),
+            }
+        };
+        push_value_changes(
+            &mut changes,
+            "patterns",
+            bank,
+            &format!("Bank {} Pattern {}", letter, p + 1),
+            &pattern(&patterns_a[..])?,
+            &pattern(&patterns_b[..])?,
+        );
+    }
+
+    Ok(changes)
+}
+
+fn check_project_dir(path: &str) -> Result<(), String> {
+    if Path::new(path).is_dir() {
+        Ok(())
+    } else {
+        Err(format!("Project not found: {}", path))
+    }
+}
+
+/// Every changed setting, sample slot, part parameter and pattern value from
+/// project A to project B. Banks present on one side only are listed, not diffed.
+pub fn project_diff(project_a: &str, project_b: &str) -> Result<ProjectDiff, String> {
+    check_project_dir(project_a)?;
+    check_project_dir(project_b)?;
+
+    let mut changes = project_value_changes(
+        &read_project_metadata(project_a)?.data,
+        &read_project_metadata(project_b)?.data,
+    )?;
+    let mut banks_only_in_a = Vec::new();
+    let mut banks_only_in_b = Vec::new();
+
+    for bank_index in 0..16u8 {
+        match (
+            bank_file_path(Path::new(project_a), bank_index).is_ok(),
+            bank_file_path(Path::new(project_b), bank_index).is_ok(),
+        ) {
+            (true, true) => changes.extend(bank_value_changes(project_a, project_b, bank_index)?),
+            (true, false) => banks_only_in_a.push(bank_letter(bank_index).to_string()),
+            (false, true) => banks_only_in_b.push(bank_letter(bank_index).to_string()),
+            (false, false) => {}
+        }
+    }
+
+    Ok(ProjectDiff {
+        changes,
+        banks_only_in_a,
+        banks_only_in_b,
+    })
+}
+
+/// Parts and patterns of one bank, compared between two projects.
+pub fn bank_diff(
+    project_a: &str,
+    project_b: &str,
+    bank_id: &str,
+) -> Result<Vec<ValueChange>, String> {
+    check_project_dir(project_a)?;
+    check_project_dir(project_b)?;
+    bank_value_changes(project_a, project_b, bank_index(bank_id)?)
+}
+
+#[tauri::command]
+pub async fn diff_projects(path_a: String, path_b: String) -> Result<ProjectDiff, String> {
+    tauri::async_runtime::spawn_blocking(move || project_diff(&path_a, &path_b))
+        .await
+        .unwrap()
+}
+
+#[tauri::command]
+pub async fn diff_banks(
+    path_a: String,
+    path_b: String,
+    bank_id: String,
+) -> Result<Vec<ValueChange>, String> {
+    tauri::async_runtime::spawn_blocking(move || bank_diff(&path_a, &path_b, &bank_id))
+        .await
+        .unwrap()
+}
+
+// Unsaved device changes: the Octatrack keeps the working state in *.work
+// files and the last explicit save in *.strd files. Comparing the two shows
+// what a project reload on the device would throw away. The decoded readers
+// always prefer .work, so each side is staged into its own temp folder with
+// the files renamed to .work and then diffed like two projects.
+
+/// Copy the `ext` variant of each of `stems` into `dest` as `<stem>.work`.
+fn stage_files(project: &Path, dest: &Path, stems: &[String], ext: &str) -> Result<(), String> {
+    for stem in stems {
+        let src = project.join(format!("{}.{}", stem, ext));
+        if src.exists() {
+            std::fs::copy(&src, dest.join(format!("{}.work", stem)))
+                .map_err(|e| format!("Failed to stage {}: {}", src.display(), e))?;
+        }
+    }
+    Ok(())
+}
+
+fn stage_saved_and_working(project_path: &str, stems: &[String]) -> Result<StagingDir, String> {
+    let project = Path::new(project_path);
+    if !project.join("project.strd").exists() {
+        return Err("Project has no saved state (project.strd) to compare with".to_string());
+    }
+    if !project.join("project.work").exists() {
+        return Err("Project has no working state (project.work) to compare with".to_string());
+    }
+    let staging = staging_dir(&std::env::temp_dir().join("octatrack-manager-unsaved"))?;
+    for side in ["saved", "working"] {
+        std::fs::create_dir_all(staging.0.join(side))
+            .map_err(|e| format!("Failed to create staging directory: {}", e))?;
+    }
+    stage_files(project, &staging.0.join("saved"), stems, "strd")?;
+    stage_files(project, &staging.0.join("working"), stems, "work")?;
+    Ok(staging)
+}
+
+fn staged_path(staging: &StagingDir, side: &str) -> String {
+    staging.0.join(side).to_string_lossy().to_string()
+}
+
+/// What changed in the working state since the last save on the device:
+/// `before` is the saved (.strd) value, `after` the working (.work) one.
+/// `banks_only_in_a` lists banks that were never saved, `banks_only_in_b`
+/// saved banks without working state.
+pub fn unsaved_changes(project_path: &str) -> Result<ProjectDiff, String> {
+    let mut stems = vec!["project".to_string(), "markers".to_string()];
+    stems.extend((1..=16).map(|n| format!("bank{:02}", n)));
+    let staging = stage_saved_and_working(project_path, &stems)?;
+    project_diff(
+        &staged_path(&staging, "saved"),
+        &staged_path(&staging, "working"),
+    )
+}
+
+/// [`unsaved_changes`] restricted to the parts and patterns of one bank.
+pub fn unsaved_bank_changes(project_path: &str, bank_id: &str) -> Result<Vec<ValueChange>, String> {
+    let bank_index = bank_index(bank_id)?;
+    let stems = vec![
+        "project".to_string(),
+        "markers".to_string(),
+        format!("bank{:02}", bank_index + 1),
+    ];
+    let staging = stage_saved_and_working(project_path, &stems)?;
+    bank_diff(
+        &staged_path(&staging, "saved"),
+        &staged_path(&staging, "working"),
+        bank_id,
+    )
+}
+
+#[tauri::command]
+pub async fn get_unsaved_changes(
+    project_path: String,
+    bank_id: Option<String>,
+) -> Result<Vec<ValueChange>, String> {
+    tauri::async_runtime::spawn_blocking(move || match bank_id {
+        Some(bank_id) => unsaved_bank_changes(&project_path, &bank_id),
+        None => unsaved_changes(&project_path).map(|diff| diff.changes),
+    })
+    .await
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ot_tools_io::{HasChecksumField, OctatrackFileIO};
+    use tempfile::TempDir;
+
+    fn write_bank(dir: &Path, bank_index: u8, modifier: impl FnOnce(&mut BankFile)) {
+        let mut bank = BankFile::default();
+        modifier(&mut bank);
+        bank.checksum = bank.calculate_checksum().unwrap();
+        bank.to_data_file(&dir.join(format!("bank{:02}.work", bank_index + 1)))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_changelog_reports_trigs_and_fx() {
+        let old = TempDir::new().unwrap();
+        let new = TempDir::new().unwrap();
+        write_bank(old.path(), 2, |_| {});
+        write_bank(new.path(), 2, |bank| {
+            // 3 trigs on T2 of pattern 5
+            bank.patterns.0[4].audio_track_trigs.0[1].trig_masks.trigger[0] = 0b0000_0111;
+            bank.parts.unsaved.0[1].audio_track_fx2[0] = 20;
+        });
+
+        let log = generate_changelog(&old.path().to_string_lossy(), &new.path().to_string_lossy())
+            .unwrap();
+
+        assert!(log
+            .lines
+            .contains(&"Bank C Pattern 5: +3 trigs on T2".to_string()));
+        assert!(log
+            .lines
+            .contains(&"Bank C Part 2: T1 FX2 changed to Plate Reverb".to_string()));
+    }
+
+    #[test]
+    fn test_identical_snapshots_have_empty_changelog() {
+        let old = TempDir::new().unwrap();
+        let new = TempDir::new().unwrap();
+        write_bank(old.path(), 0, |_| {});
+        write_bank(new.path(), 0, |_| {});
+
+        let log = generate_changelog(&old.path().to_string_lossy(), &new.path().to_string_lossy())
+            .unwrap();
+        assert!(log.changes.is_empty());
+    }
+
+    #[test]
+    fn test_changelog_lines_group_by_location() {
+        let change = |location: &str, description: &str| ProjectChange {
+            bank: Some(0),
+            location: location.to_string(),
+            description: description.to_string(),
+        };
+        let lines = changelog_lines(&[
+            change("Bank A Pattern 1", "+1 trig on T1"),
+            change("Bank A Part 1", "renamed to \"LEAD\""),
+            change("Bank A Pattern 1", "-2 trigs on M3"),
+        ]);
+        assert_eq!(
+            lines,
+            vec![
+                "Bank A Pattern 1: +1 trig on T1; -2 trigs on M3".to_string(),
+                "Bank A Part 1: renamed to \"LEAD\"".to_string(),
+            ]
+        );
+    }
+
+    fn project_with_bank(bank_index: u8, modifier: impl FnOnce(&mut BankFile)) -> TempDir {
+        let dir = TempDir::new().unwrap();
+        ot_tools_io::ProjectFile::default()
+            .to_data_file(&dir.path().join("project.work"))
+            .unwrap();
+        write_bank(dir.path(), bank_index, modifier);
+        dir
+    }
+
+    #[test]
+    fn test_project_diff_reports_before_and_after_values() {
+        let a = project_with_bank(0, |_| {});
+        let b = project_with_bank(0, |bank| {
+            bank.parts.unsaved.0[1].audio_track_fx1[2] = 20;
+            bank.patterns.0[3].audio_track_trigs.0[0].trig_masks.trigger[0] = 0b0000_0001;
+        });
+        write_bank(b.path(), 4, |_| {});
+
+        let diff = project_diff(&a.path().to_string_lossy(), &b.path().to_string_lossy()).unwrap();
+
+        assert_eq!(diff.banks_only_in_b, vec!["E".to_string()]);
+        let fx = diff
+            .changes
+            .iter()
+            .find(|c| c.location == "Bank A Part 2" && c.parameter == "fxs.2.fx1_type")
+            .unwrap();
+        assert_eq!(fx.section, "parts");
+        assert_eq!(fx.after, Value::from(20));
+        assert!(diff.changes.iter().any(|c| c.location == "Bank A Pattern 4"
+            && c.parameter.ends_with(".trigger")
+            && c.after == Value::Bool(true)));
+        assert!(diff
+            .changes
+            .iter()
+            .all(|c| !c.parameter.contains("trig_counts")));
+    }
+
+    #[test]
+    fn test_bank_diff_of_identical_banks_is_empty() {
+        let a = project_with_bank(2, |_| {});
+        let b = project_with_bank(2, |_| {});
+        let changes = bank_diff(
+            &a.path().to_string_lossy(),
+            &b.path().to_string_lossy(),
+            "C",
+        )
+        .unwrap();
+        assert!(changes.is_empty());
+        assert!(bank_diff(
+            &a.path().to_string_lossy(),
+            &b.path().to_string_lossy(),
+            "Q"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_unsaved_changes_compare_work_against_strd() {
+        let dir = project_with_bank(1, |bank| {
+            bank.parts.unsaved.0[0].audio_track_fx2[3] = 20;
+        });
+        ot_tools_io::ProjectFile::default()
+            .to_data_file(&dir.path().join("project.strd"))
+            .unwrap();
+        let mut saved = BankFile::default();
+        saved.checksum = saved.calculate_checksum().unwrap();
+        saved.to_data_file(&dir.path().join("bank02.strd")).unwrap();
+        let path = dir.path().to_string_lossy().to_string();
+
+        let changes = unsaved_bank_changes(&path, "B").unwrap();
+        let fx = changes
+            .iter()
+            .find(|c| c.parameter == "fxs.3.fx2_type")
+            .unwrap();
+        assert_eq!(fx.location, "Bank B Part 1");
+        assert_eq!(fx.after, Value::from(20));
+        assert_ne!(fx.before, fx.after);
+
+        let diff = unsaved_changes(&path).unwrap();
+        assert!(diff.changes.contains(fx));
+        assert!(diff.banks_only_in_a.is_empty() && diff.banks_only_in_b.is_empty());
+    }
+
+    #[test]
+    fn test_unsaved_changes_need_a_saved_project() {
+        let dir = project_with_bank(0, |_| {});
+        assert!(unsaved_changes(&dir.path().to_string_lossy()).is_err());
+    }
+}
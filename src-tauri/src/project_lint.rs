@@ -0,0 +1,670 @@
+// Project lint: static checks that surface problems before the Octatrack
+// does (as load errors or silence).
+
+use crate::arrangement_reader::read_arrangements;
+use crate::project_reader::{
+    bank_file_lock, bank_index, bank_letter, calculate_flex_ram_bytes, compute_sample_usage,
+    read_project_metadata, sum_flex_sample_sizes, SampleSlot,
+};
+use ot_tools_io::{BankFile, HasChecksumField};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProjectIssue {
+    pub severity: String, // "error", "warning", "info"
+    // "missing_sample", "sample_format", "checksum", "out_of_range", "empty_slot_reference",
+    // "memory", "unsaved_part", "empty_pattern", "oneshot"
+    pub category: String,
+    pub message: String,
+    pub bank: Option<u8>,          // 0-based
+    pub slot_type: Option<String>, // "Static" or "Flex"
+    pub slot_id: Option<u8>,       // 1-based
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectValidation {
+    pub issues: Vec<ProjectIssue>,
+    pub errors: usize,
+    pub warnings: usize,
+}
+
+/// Checksum state of one bank file.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BankChecksumStatus {
+    pub bank: u8, // 0-based
+    pub file: String,
+    pub stored: Option<u16>,
+    pub expected: Option<u16>,
+    pub error: Option<String>, // file could not be read or checksummed
+}
+
+impl BankChecksumStatus {
+    pub fn is_valid(&self) -> bool {
+        self.error.is_none() && self.stored == self.expected
+    }
+}
+
+fn issue(severity: &str, category: &str, message: String) -> ProjectIssue {
+    ProjectIssue {
+        severity: severity.to_string(),
+        category: category.to_string(),
+        message,
+        bank: None,
+        slot_type: None,
+        slot_id: None,
+    }
+}
+
+/// Every bank file of a project (`.work` and `.strd`), sorted.
+pub fn bank_files(project_path: &Path) -> Vec<(u8, PathBuf)> {
+    let mut files = Vec::new();
+    for bank in 0..16u8 {
+        for ext in ["work", "strd"] {
+            let file = project_path.join(format!("bank{:02}.{}", bank + 1, ext));
+            if file.exists() {
+                files.push((bank, file));
+            }
+        }
+    }
+    files
+}
+
+/// Compare the stored checksum of every bank file with the computed one.
+pub fn bank_checksums(project_path: &Path) -> Vec<BankChecksumStatus> {
+    bank_files(project_path)
+        .into_iter()
+        .map(|(bank, file)| {
+            let mut status = BankChecksumStatus {
+                bank,
+                file: file.to_string_lossy().to_string(),
+                stored: None,
+                expected: None,
+                error: None,
+            };
+            match crate::os_compat::read_bank_file(&file) {
+                Ok(data) => {
+                    status.stored = Some(data.checksum);
+                    match data.calculate_checksum() {
+                        Ok(expected) => status.expected = Some(expected),
+                        Err(e) => status.error = Some(format!("{:?}", e)),
+                    }
+                }
+                Err(e) => status.error = Some(e),
+            }
+            status
+        })
+        .collect()
+}
+
+/// Result of a checksum scan, and of the repair when one was requested.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChecksumRepairReport {
+    pub files: Vec<BankChecksumStatus>, // state before any repair
+    pub repaired: Vec<String>,          // file names rewritten with the correct checksum
+    pub backup: Option<String>,         // backup summary, when files were repaired
+}
+
+/// Verify every bank file checksum. With `repair`, files whose data reads fine
+/// but whose stored checksum is wrong (typically after a third-party tool edited
+/// them) are backed up, then rewritten with the computed checksum. Unreadable
+/// files are reported but never touched.
+pub fn repair_bank_checksums(
+    project_path: &str,
+    repair: bool,
+) -> Result<ChecksumRepairReport, String> {
+    let path = Path::new(project_path);
+    if !path.is_dir() {
+        return Err(format!("Project path does not exist: {}", project_path));
+    }
+    let files = bank_checksums(path);
+    let mut report = ChecksumRepairReport {
+        files,
+        repaired: Vec::new(),
+        backup: None,
+    };
+    if !repair {
+        return Ok(report);
+    }
+
+    let to_repair: Vec<String> = report
+        .files
+        .iter()
+        .filter(|s| s.error.is_none() && !s.is_valid())
+        .filter_map(|s| Path::new(&s.file).file_name())
+        .map(|n| n.to_string_lossy().to_string())
+        .collect();
+    if to_repair.is_empty() {
+        return Ok(report);
+    }
+    report.backup = Some(crate::backup_project_files_impl(
+        project_path,
+        &to_repair,
+        "checksum_repair",
+    )?);
+
+    for name in to_repair {
+        let file = path.join(&name);
+        let lock = bank_file_lock(&file);
+        let _guard = lock.lock().unwrap_or_else(|e| e.into_inner());
+        let mut data = crate::os_compat::read_bank_file(&file)
+            .map_err(|e| format!("Failed to read {}: {}", name, e))?;
+        data.checksum = data
+            .calculate_checksum()
+            .map_err(|e| format!("Failed to calculate checksum: {:?}", e))?;
+        crate::atomic_write::write_data_file(&data, &file)
+            .map_err(|e| format!("Failed to write {}: {}", name, e))?;
+        report.repaired.push(name);
+    }
+    Ok(report)
+}
+
+fn check_slots(slots: &[SampleSlot], issues: &mut Vec<ProjectIssue>) {
+    for slot in slots {
+        let Some(path) = &slot.path else {
+            continue;
+        };
+        let (severity, category, message) = if !slot.file_exists {
+            (
+                "error",
+                "missing_sample",
+                format!("Sample file not found: {}", path),
+            )
+        } else {
+            match slot.compatibility.as_deref() {
+                Some("incompatible") => (
+                    "error",
+                    "sample_format",
+                    format!(
+                        "{} is {}-bit: the Octatrack plays 16 or 24-bit only",
+                        path,
+                        slot.bit_depth.unwrap_or(0)
+                    ),
+                ),
+                Some("wrong_rate") => (
+                    "warning",
+                    "sample_format",
+                    format!(
+                        "{} is {} Hz: it will play at the wrong speed (44100 Hz expected)",
+                        path,
+                        slot.sample_rate.unwrap_or(0)
+                    ),
+                ),
+                Some("unknown") => (
+                    "warning",
+                    "sample_format",
+                    format!("{} is not a readable WAV or AIFF file", path),
+                ),
+                _ => continue,
+            }
+        };
+        issues.push(ProjectIssue {
+            slot_type: Some(slot.slot_type.clone()),
+            slot_id: Some(slot.slot_id),
+            ..issue(severity, category, message)
+        });
+    }
+}
+
+fn check_bank_values(bank: u8, data: &BankFile, issues: &mut Vec<ProjectIssue>) {
+    let mut out_of_range = |message: String| {
+        issues.push(ProjectIssue {
+            bank: Some(bank),
+            ..issue("error", "out_of_range", message)
+        });
+    };
+    for (p, pattern) in data.patterns.0.iter().enumerate() {
+        if pattern.part_assignment > 3 {
+            out_of_range(format!(
+                "Pattern {}{:02}: part assignment {} (must be 0-3)",
+                bank_letter(bank),
+                p + 1,
+                pattern.part_assignment
+            ));
+        }
+        if pattern.scale.scale_mode == 0 && !(1..=64).contains(&(pattern.scale.master_len as u32)) {
+            out_of_range(format!(
+                "Pattern {}{:02}: length {} (must be 1-64)",
+                bank_letter(bank),
+                p + 1,
+                pattern.scale.master_len
+            ));
+        }
+    }
+    for (part_id, part) in data.parts.unsaved.0.iter().enumerate() {
+        for t in 0..8 {
+            let machine_type = part.audio_track_machine_types[t];
+            let slots = &part.audio_track_machine_slots[t];
+            if machine_type > 4 {
+                out_of_range(format!(
+                    "Bank {} Part {} T{}: unknown machine type {}",
+                    bank_letter(bank),
+                    part_id + 1,
+                    t + 1,
+                    machine_type
+                ));
+            }
+            if slots.static_slot_id > 127 {
+                out_of_range(format!(
+                    "Bank {} Part {} T{}: static slot {} does not exist",
+                    bank_letter(bank),
+                    part_id + 1,
+                    t + 1,
+                    slots.static_slot_id as u16 + 1
+                ));
+            }
+            // Flex ids 128-135 are the recorder buffers.
+            if slots.flex_slot_id > 135 {
+                out_of_range(format!(
+                    "Bank {} Part {} T{}: flex slot {} does not exist",
+                    bank_letter(bank),
+                    part_id + 1,
+                    t + 1,
+                    slots.flex_slot_id as u16 + 1
+                ));
+            }
+        }
+    }
+}
+
+/// Run every check on the project at `project_path`.
+pub fn lint_project(project_path: &str) -> Result<ProjectValidation, String> {
+    let path = Path::new(project_path);
+    let metadata = read_project_metadata(project_path)?.data;
+    let mut issues = Vec::new();
+
+    if !(30.0..=300.0).contains(&metadata.tempo) {
+        issues.push(issue(
+            "warning",
+            "out_of_range",
+            format!("Project tempo {} BPM (must be 30-300)", metadata.tempo),
+        ));
+    }
+
+    check_slots(&metadata.sample_slots.static_slots, &mut issues);
+    check_slots(&metadata.sample_slots.flex_slots, &mut issues);
+
+    for status in bank_checksums(path) {
+        let file_name = Path::new(&status.file)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let message = match (&status.error, status.is_valid()) {
+            (Some(e), _) => format!("{} could not be read: {}", file_name, e),
+            (None, false) => format!(
+                "{} has a bad checksum (stored {:#06x}, expected {:#06x})",
+                file_name,
+                status.stored.unwrap_or(0),
+                status.expected.unwrap_or(0)
+            ),
+            (None, true) => {
+                if let Ok(data) = crate::os_compat::read_bank_file(Path::new(&status.file)) {
+                    check_bank_values(status.bank, &data, &mut issues);
+                }
+                continue;
+            }
+        };
+        issues.push(ProjectIssue {
+            bank: Some(status.bank),
+            ..issue("error", "checksum", message)
+        });
+    }
+
+    // Audible references to slots without a sample play silence.
+    let usage = compute_sample_usage(project_path)?;
+    for (slot_type, pool, slots) in [
+        (
+            "Static",
+            &usage.static_usage,
+            &metadata.sample_slots.static_slots,
+        ),
+        ("Flex", &usage.flex_usage, &metadata.sample_slots.flex_slots),
+    ] {
+        for (idx, entries) in pool.iter().enumerate() {
+            let is_empty = slots.get(idx).is_some_and(|s| s.path.is_none());
+            let Some(first) = entries.iter().find(|e| e.audible) else {
+                continue;
+            };
+            if !is_empty {
+                continue;
+            }
+            issues.push(ProjectIssue {
+                bank: Some(first.bank),
+                slot_type: Some(slot_type.to_string()),
+                slot_id: Some(idx as u8 + 1),
+                ..issue(
+                    "warning",
+                    "empty_slot_reference",
+                    format!(
+                        "{} slot {} is empty but used by Bank {} T{}",
+                        slot_type,
+                        idx + 1,
+                        bank_letter(first.bank),
+                        first.track + 1
+                    ),
+                )
+            });
+        }
+    }
+
+    let errors = issues.iter().filter(|i| i.severity == "error").count();
+    let warnings = issues.len() - errors;
+    Ok(ProjectValidation {
+        issues,
+        errors,
+        warnings,
+    })
+}
+
+/// Things to sort out before playing a project live.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PregigChecklist {
+    pub items: Vec<ProjectIssue>,
+    pub ready: bool, // no errors
+}
+
+/// Combine sample, memory, part, arrangement and one-shot checks into a list
+/// of actionable items for a live set.
+pub fn pregig_checklist(project_path: &str) -> Result<PregigChecklist, String> {
+    let path = Path::new(project_path);
+    let metadata = read_project_metadata(project_path)?.data;
+    let mut items = Vec::new();
+
+    check_slots(&metadata.sample_slots.static_slots, &mut items);
+    check_slots(&metadata.sample_slots.flex_slots, &mut items);
+
+    let memory = &metadata.memory_settings;
+    let capacity = calculate_flex_ram_bytes(memory);
+    let used = sum_flex_sample_sizes(path, memory.load_24bit_flex)?;
+    if used > capacity {
+        let mib = |bytes: u64| bytes as f64 / (1024.0 * 1024.0);
+        items.push(issue(
+            "error",
+            "memory",
+            format!(
+                "Flex samples need {:.1} MB but only {:.1} MB is available: \
+                 free slots or shorten the reserved recorders",
+                mib(used),
+                mib(capacity)
+            ),
+        ));
+    }
+
+    // One file per bank, .work preferred (bank_files lists it first).
+    let mut banks: BTreeMap<u8, BankFile> = BTreeMap::new();
+    for (bank, file) in bank_files(path) {
+        if banks.contains_key(&bank) {
+            continue;
+        }
+        if let Ok(data) = crate::os_compat::read_bank_file(&file) {
+            banks.insert(bank, data);
+        }
+    }
+
+    for (&bank, data) in &banks {
+        for part in 0..4u8 {
+            if data.parts_edited_bitmask & (1 << part) != 0 {
+                items.push(ProjectIssue {
+                    bank: Some(bank),
+                    ..issue(
+                        "warning",
+                        "unsaved_part",
+                        format!(
+                            "Bank {} Part {} has unsaved edits: save it, or they are lost on reload",
+                            bank_letter(bank),
+                            part + 1
+                        ),
+                    )
+                });
+            }
+        }
+
+        let oneshot_patterns: Vec<String> = data
+            .patterns
+            .0
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| {
+                p.audio_track_trigs
+                    .0
+                    .iter()
+                    .any(|t| t.trig_masks.oneshot.iter().any(|&m| m != 0))
+            })
+            .map(|(i, _)| format!("{}{:02}", bank_letter(bank), i + 1))
+            .collect();
+        if !oneshot_patterns.is_empty() {
+            items.push(ProjectIssue {
+                bank: Some(bank),
+                ..issue(
+                    "info",
+                    "oneshot",
+                    format!(
+                        "Patterns {} use one-shot trigs: they play once until re-armed",
+                        oneshot_patterns.join(", ")
+                    ),
+                )
+            });
+        }
+    }
+
+    // Patterns the arrangements play that have no trigs at all.
+    let mut empty_reported: BTreeSet<(u8, u8)> = BTreeSet::new();
+    for arrangement in read_arrangements(project_path)? {
+        for row in &arrangement.rows {
+            let (Some(letter), Some(pattern)) = (&row.bank, row.pattern) else {
+                continue;
+            };
+            let Ok(bank) = bank_index(letter) else {
+                continue;
+            };
+            let Some(data) = banks.get(&bank) else {
+                continue;
+            };
+            let Some(p) = data.patterns.0.get(pattern.saturating_sub(1) as usize) else {
+                continue;
+            };
+            let has_trigs = p.audio_track_trigs.0.iter().any(|t| {
+                t.trig_masks.trigger.iter().any(|&m| m != 0)
+                    || t.trig_masks.trigless.iter().any(|&m| m != 0)
+            }) || p.midi_track_trigs.0.iter().any(|t| {
+                t.trig_masks.trigger.iter().any(|&m| m != 0)
+                    || t.trig_masks.trigless.iter().any(|&m| m != 0)
+            });
+            if !has_trigs && empty_reported.insert((bank, pattern)) {
+                items.push(ProjectIssue {
+                    bank: Some(bank),
+                    ..issue(
+                        "warning",
+                        "empty_pattern",
+                        format!(
+                            "Arrangement \"{}\" row {} plays {}{:02}, which has no trigs",
+                            arrangement.name,
+                            row.index + 1,
+                            letter,
+                            pattern
+                        ),
+                    )
+                });
+            }
+        }
+    }
+
+    let ready = !items.iter().any(|i| i.severity == "error");
+    Ok(PregigChecklist { items, ready })
+}
+
+#[tauri::command]
+pub async fn validate_project(path: String) -> Result<ProjectValidation, String> {
+    tauri::async_runtime::spawn_blocking(move || lint_project(&path))
+        .await
+        .unwrap()
+}
+
+#[tauri::command]
+pub async fn get_pregig_checklist(path: String) -> Result<PregigChecklist, String> {
+    tauri::async_runtime::spawn_blocking(move || pregig_checklist(&path))
+        .await
+        .unwrap()
+}
+
+#[tauri::command]
+pub async fn verify_bank_checksums(
+    path: String,
+    repair: bool,
+) -> Result<ChecksumRepairReport, String> {
+    if repair {
+        crate::fs_scope::ensure_allowed(&path)?;
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        if !repair {
+            return repair_bank_checksums(&path, false);
+        }
+        crate::edit_journal::record_edit(
+            &path,
+            "repair_bank_checksums",
+            &crate::edit_journal::all_bank_files(),
+            || repair_bank_checksums(&path, true),
+        )
+    })
+    .await
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::blank_project;
+    use ot_tools_io::{OctatrackFileIO, ProjectFile};
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_blank_project_has_no_errors() {
+        let dir = blank_project();
+        let result = lint_project(&dir.path().to_string_lossy()).unwrap();
+        assert_eq!(result.errors, 0, "{:?}", result.issues);
+    }
+
+    #[test]
+    fn test_bad_checksum_is_reported() {
+        let dir = blank_project();
+        let bank_path = dir.path().join("bank03.work");
+        let mut bank = BankFile::from_data_file(&bank_path).unwrap();
+        bank.checksum = bank.calculate_checksum().unwrap().wrapping_add(1);
+        bank.to_data_file(&bank_path).unwrap();
+
+        let result = lint_project(&dir.path().to_string_lossy()).unwrap();
+
+        let checksum: Vec<_> = result
+            .issues
+            .iter()
+            .filter(|i| i.category == "checksum")
+            .collect();
+        assert_eq!(checksum.len(), 1);
+        assert_eq!(checksum[0].bank, Some(2));
+        assert_eq!(checksum[0].severity, "error");
+    }
+
+    #[test]
+    fn test_missing_sample_is_reported() {
+        let dir = blank_project();
+        let project_path = dir.path().join("project.work");
+        let mut pf = ProjectFile::from_data_file(&project_path).unwrap();
+        pf.slots.static_slots[4] = Some(
+            ot_tools_io::projects::SlotAttributes::new(
+                ot_tools_io::settings::SlotType::Static,
+                5,
+                Some(PathBuf::from("../AUDIO/gone.wav")),
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap(),
+        );
+        pf.to_data_file(&project_path).unwrap();
+
+        let result = lint_project(&dir.path().to_string_lossy()).unwrap();
+
+        let missing = result
+            .issues
+            .iter()
+            .find(|i| i.category == "missing_sample")
+            .unwrap();
+        assert_eq!(missing.slot_id, Some(5));
+        assert_eq!(missing.slot_type.as_deref(), Some("Static"));
+    }
+
+    #[test]
+    fn test_repair_bank_checksums() {
+        let dir = blank_project();
+        let project = dir.path().to_string_lossy().to_string();
+        let bank_path = dir.path().join("bank05.work");
+        let mut bank = BankFile::from_data_file(&bank_path).unwrap();
+        let expected = bank.calculate_checksum().unwrap();
+        bank.checksum = expected.wrapping_add(7);
+        bank.to_data_file(&bank_path).unwrap();
+
+        // Verify only: nothing is written.
+        let report = repair_bank_checksums(&project, false).unwrap();
+        let bad: Vec<_> = report.files.iter().filter(|s| !s.is_valid()).collect();
+        assert_eq!(bad.len(), 1);
+        assert_eq!(bad[0].bank, 4);
+        assert!(report.repaired.is_empty());
+        assert!(!dir.path().join("backups").exists());
+
+        let report = repair_bank_checksums(&project, true).unwrap();
+        assert_eq!(report.repaired, vec!["bank05.work".to_string()]);
+        assert!(report.backup.is_some());
+        assert_eq!(
+            BankFile::from_data_file(&bank_path).unwrap().checksum,
+            expected
+        );
+        let backup_dir = fs::read_dir(dir.path().join("backups"))
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap()
+            .path();
+        assert_eq!(
+            BankFile::from_data_file(&backup_dir.join("bank05.work"))
+                .unwrap()
+                .checksum,
+            expected.wrapping_add(7)
+        );
+        assert!(bank_checksums(dir.path()).iter().all(|s| s.is_valid()));
+    }
+
+    #[test]
+    fn test_pregig_checklist() {
+        let dir = blank_project();
+        let project = dir.path().to_string_lossy().to_string();
+        let checklist = pregig_checklist(&project).unwrap();
+        assert!(checklist.ready);
+        assert!(checklist.items.is_empty(), "{:?}", checklist.items);
+
+        let bank_path = dir.path().join("bank02.work");
+        let mut bank = BankFile::from_data_file(&bank_path).unwrap();
+        bank.parts_edited_bitmask = 0b0100;
+        bank.patterns.0[3].audio_track_trigs.0[0].trig_masks.oneshot[0] = 1;
+        bank.checksum = bank.calculate_checksum().unwrap();
+        bank.to_data_file(&bank_path).unwrap();
+
+        let checklist = pregig_checklist(&project).unwrap();
+        let unsaved = checklist
+            .items
+            .iter()
+            .find(|i| i.category == "unsaved_part")
+            .unwrap();
+        assert!(unsaved.message.contains("Bank B Part 3"));
+        let oneshot = checklist
+            .items
+            .iter()
+            .find(|i| i.category == "oneshot")
+            .unwrap();
+        assert!(oneshot.message.contains("B04"));
+        assert!(checklist.ready, "warnings and info do not block");
+    }
+}
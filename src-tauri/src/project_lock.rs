@@ -0,0 +1,226 @@
+// Project lock file: marks a project as open for editing by one app instance,
+// so two instances (or the app and another tool that honours the file) don't
+// interleave writes to the same bank.
+//
+// Opening a project writes `<project>/.otm-lock` with the instance's PID, host
+// and start time. While another live instance holds it, the shared write
+// paths (`edit_bank_file`, the Parts writers, bank/part/pattern/track copies,
+// the surgical project.work writers, journaled edits and undo/redo) refuse to
+// write. The file is created exclusively, so two instances opening the same
+// project at once can't both win. A lock whose process is gone is stale and
+// is taken over silently; a lock from another host can't be checked and is
+// only taken over on request.
+
+use chrono::Local;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+pub const LOCK_FILE: &str = ".otm-lock";
+
+/// Project directories this instance holds the lock of, released on exit.
+static HELD: Lazy<Mutex<HashSet<PathBuf>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProjectLock {
+    pub pid: u32,
+    pub host: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LockStatus {
+    pub acquired: bool,
+    pub held_by: Option<ProjectLock>, // the other holder when not acquired
+}
+
+fn this_host() -> String {
+    sysinfo::System::host_name().unwrap_or_default()
+}
+
+fn ours() -> ProjectLock {
+    ProjectLock {
+        pid: std::process::id(),
+        host: this_host(),
+        created_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+    }
+}
+
+fn read_lock(project_dir: &Path) -> Option<ProjectLock> {
+    let data = fs::read_to_string(project_dir.join(LOCK_FILE)).ok()?;
+    serde_json::from_str(&data).ok()
+}
+
+fn process_alive(pid: u32) -> bool {
+    use sysinfo::{Pid, ProcessesToUpdate, System};
+    let pid = Pid::from_u32(pid);
+    let mut sys = System::new();
+    sys.refresh_processes(ProcessesToUpdate::Some(&[pid]), true);
+    sys.process(pid).is_some()
+}
+
+/// The lock of another instance that still holds `project_dir`, if any.
+fn other_holder(project_dir: &Path) -> Option<ProjectLock> {
+    let lock = read_lock(project_dir)?;
+    let host = this_host();
+    if lock.host == host && lock.pid == std::process::id() {
+        return None;
+    }
+    if lock.host == host && !process_alive(lock.pid) {
+        return None; // stale: that instance exited without releasing
+    }
+    Some(lock)
+}
+
+/// Take the lock of `project_dir` unless another live instance holds it;
+/// `force` takes it over regardless.
+pub fn acquire(project_dir: &Path, force: bool) -> Result<LockStatus, String> {
+    if !project_dir.is_dir() {
+        return Err(format!(
+            "Project directory not found: {}",
+            project_dir.display()
+        ));
+    }
+    let path = project_dir.join(LOCK_FILE);
+    let data = serde_json::to_string_pretty(&ours())
+        .map_err(|e| format!("Failed to serialize lock: {}", e))?;
+    // A second attempt covers a stale (or forced) lock removed in the first
+    let mut created = false;
+    for _ in 0..2 {
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                file.write_all(data.as_bytes())
+                    .map_err(|e| format!("Failed to write lock file: {}", e))?;
+                created = true;
+                break;
+            }
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                if let Some(holder) = other_holder(project_dir) {
+                    if !force {
+                        return Ok(LockStatus {
+                            acquired: false,
+                            held_by: Some(holder),
+                        });
+                    }
+                }
+                match fs::remove_file(&path) {
+                    Ok(()) => {}
+                    Err(e) if e.kind() == ErrorKind::NotFound => {}
+                    Err(e) => return Err(format!("Failed to replace lock file: {}", e)),
+                }
+            }
+            Err(e) => return Err(format!("Failed to write lock file: {}", e)),
+        }
+    }
+    if !created {
+        // Another instance created the lock between our removal and retry
+        return Ok(LockStatus {
+            acquired: false,
+            held_by: read_lock(project_dir),
+        });
+    }
+    HELD.lock().unwrap().insert(project_dir.to_path_buf());
+    Ok(LockStatus {
+        acquired: true,
+        held_by: None,
+    })
+}
+
+/// Remove the lock of `project_dir` if this instance holds it.
+pub fn release(project_dir: &Path) -> Result<(), String> {
+    HELD.lock().unwrap().remove(project_dir);
+    let Some(lock) = read_lock(project_dir) else {
+        return Ok(());
+    };
+    if lock.pid != std::process::id() || lock.host != this_host() {
+        return Ok(()); // taken over by another instance meanwhile
+    }
+    fs::remove_file(project_dir.join(LOCK_FILE))
+        .map_err(|e| format!("Failed to remove lock file: {}", e))
+}
+
+/// Release every lock this instance holds (on exit).
+pub fn release_all() {
+    let held: Vec<PathBuf> = HELD.lock().unwrap().drain().collect();
+    for dir in held {
+        let _ = release(&dir);
+    }
+}
+
+/// Refuse to write to `project_dir` while another instance holds its lock.
+pub fn ensure_unlocked(project_dir: &Path) -> Result<(), String> {
+    match other_holder(project_dir) {
+        Some(holder) => Err(format!(
+            "Project is open in another instance (PID {} on {}, since {}); close it there \
+             or take over the lock before editing",
+            holder.pid, holder.host, holder.created_at
+        )),
+        None => Ok(()),
+    }
+}
+
+#[tauri::command]
+pub fn acquire_project_lock(path: String, force: Option<bool>) -> Result<LockStatus, String> {
+    crate::fs_scope::ensure_allowed(&path)?;
+    acquire(Path::new(&path), force.unwrap_or(false))
+}
+
+#[tauri::command]
+pub fn release_project_lock(path: String) -> Result<(), String> {
+    crate::fs_scope::ensure_allowed(&path)?;
+    release(Path::new(&path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_lock(dir: &Path, pid: u32, host: &str) {
+        let lock = ProjectLock {
+            pid,
+            host: host.to_string(),
+            created_at: "2026-10-15 10:00:00".to_string(),
+        };
+        fs::write(dir.join(LOCK_FILE), serde_json::to_string(&lock).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_acquire_and_release() {
+        let dir = TempDir::new().unwrap();
+        assert!(acquire(dir.path(), false).unwrap().acquired);
+        assert_eq!(read_lock(dir.path()).unwrap().pid, std::process::id());
+        // Our own lock never blocks our writes.
+        assert!(ensure_unlocked(dir.path()).is_ok());
+        release(dir.path()).unwrap();
+        assert!(!dir.path().join(LOCK_FILE).exists());
+    }
+
+    #[test]
+    fn test_other_host_blocks_until_forced() {
+        let dir = TempDir::new().unwrap();
+        write_lock(dir.path(), 1, "some-other-host.invalid");
+
+        let status = acquire(dir.path(), false).unwrap();
+        assert!(!status.acquired);
+        assert_eq!(status.held_by.unwrap().pid, 1);
+        assert!(ensure_unlocked(dir.path()).is_err());
+
+        assert!(acquire(dir.path(), true).unwrap().acquired);
+        assert!(ensure_unlocked(dir.path()).is_ok());
+        release(dir.path()).unwrap();
+    }
+
+    #[test]
+    fn test_stale_lock_is_taken_over() {
+        let dir = TempDir::new().unwrap();
+        write_lock(dir.path(), u32::MAX - 1, &this_host());
+        assert!(ensure_unlocked(dir.path()).is_ok());
+        assert!(acquire(dir.path(), false).unwrap().acquired);
+        release(dir.path()).unwrap();
+    }
+}
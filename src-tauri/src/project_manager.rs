@@ -33,7 +33,7 @@ pub const OT_CHARSET: &str = concat!(
 /// Subset of `OT_CHARSET` that the host filesystem cannot accept in a folder name.
 /// These chars get a more specific error so the user understands why an
 /// otherwise-legal Octatrack character is rejected at the desktop layer.
-const FS_FORBIDDEN: &[char] = &['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
+pub(crate) const FS_FORBIDDEN: &[char] = &['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
 
 /// Hardware-imposed maximum length of an Octatrack project folder name.
 pub(crate) const MAX_NAME_LEN: usize = 32;
@@ -162,7 +162,8 @@ fn normalize_trig_quantization(project_work_path: &Path) -> std::io::Result<usiz
         }
     }
     if count > 0 {
-        fs::write(project_work_path, &out)?;
+        crate::atomic_write::write_atomic(project_work_path, &out)
+            .map_err(std::io::Error::other)?;
     }
     Ok(count)
 }
@@ -196,7 +197,7 @@ pub(crate) fn create_project_sync(set: &Path, name: &str) -> Result<String, Stri
 
     let project_file = ProjectFile::default();
     let project_work_path = project_path.join("project.work");
-    project_file.to_data_file(&project_work_path).map_err(|e| {
+    crate::atomic_write::write_data_file(&project_file, &project_work_path).map_err(|e| {
         // Best-effort cleanup on partial failure.
         let _ = fs::remove_dir_all(&project_path);
         format!("Failed to write project.work: {}", e)
@@ -212,7 +213,7 @@ pub(crate) fn create_project_sync(set: &Path, name: &str) -> Result<String, Stri
     for i in 1u8..=16 {
         let bank = BankFile::default();
         let bank_path: PathBuf = project_path.join(format!("bank{:02}.work", i));
-        bank.to_data_file(&bank_path).map_err(|e| {
+        crate::atomic_write::write_data_file(&bank, &bank_path).map_err(|e| {
             let _ = fs::remove_dir_all(&project_path);
             format!("Failed to write bank{:02}.work: {}", i, e)
         })?;
@@ -231,12 +232,12 @@ pub(crate) fn create_project_sync(set: &Path, name: &str) -> Result<String, Stri
     }
 
     let markers = MarkersFile::default();
-    markers
-        .to_data_file(&project_path.join("markers.work"))
-        .map_err(|e| {
+    crate::atomic_write::write_data_file(&markers, &project_path.join("markers.work")).map_err(
+        |e| {
             let _ = fs::remove_dir_all(&project_path);
             format!("Failed to write markers.work: {}", e)
-        })?;
+        },
+    )?;
 
     Ok(project_path.to_string_lossy().into_owned())
 }
@@ -248,6 +249,7 @@ pub(crate) fn create_project_sync(set: &Path, name: &str) -> Result<String, Stri
 /// responsive while the 17 file writes hit (potentially slow) SD media.
 #[tauri::command]
 pub async fn create_project(set_path: String, name: String) -> Result<String, String> {
+    crate::fs_scope::ensure_allowed(&set_path)?;
     tauri::async_runtime::spawn_blocking(move || create_project_sync(Path::new(&set_path), &name))
         .await
         .map_err(|e| format!("Background task failed: {}", e))?
@@ -419,6 +421,7 @@ pub(crate) fn copy_project_sync(src: &Path, dest_set: &Path) -> Result<String, S
 /// Runs on the blocking thread pool.
 #[tauri::command]
 pub async fn copy_project(src_path: String, dest_set_path: String) -> Result<String, String> {
+    crate::fs_scope::ensure_allowed(&dest_set_path)?;
     tauri::async_runtime::spawn_blocking(move || {
         copy_project_sync(Path::new(&src_path), Path::new(&dest_set_path))
     })
@@ -434,6 +437,7 @@ pub async fn copy_project_with_progress(
     dest_set_path: String,
     transfer_id: String,
 ) -> Result<String, String> {
+    crate::fs_scope::ensure_allowed(&dest_set_path)?;
     let cancel_token = register_cancellation_token(&transfer_id);
     let tid = transfer_id.clone();
 
@@ -551,6 +555,7 @@ pub async fn copy_set(
     dest_location_path: String,
     transfer_id: String,
 ) -> Result<String, String> {
+    crate::fs_scope::ensure_allowed(&dest_location_path)?;
     let cancel_token = register_cancellation_token(&transfer_id);
     let tid = transfer_id.clone();
 
@@ -685,6 +690,7 @@ pub(crate) fn rename_project_sync(src: &Path, new_name: &str) -> Result<String,
 /// Runs on the blocking thread pool.
 #[tauri::command]
 pub async fn rename_project(project_path: String, new_name: String) -> Result<String, String> {
+    crate::fs_scope::ensure_allowed(&project_path)?;
     tauri::async_runtime::spawn_blocking(move || {
         rename_project_sync(Path::new(&project_path), &new_name)
     })
@@ -814,6 +820,8 @@ fn walk_count_size(p: &Path) -> std::io::Result<(usize, u64)> {
 /// Runs on the blocking thread pool.
 #[tauri::command]
 pub async fn move_project(src_path: String, dest_set_path: String) -> Result<String, String> {
+    crate::fs_scope::ensure_allowed(&src_path)?;
+    crate::fs_scope::ensure_allowed(&dest_set_path)?;
     tauri::async_runtime::spawn_blocking(move || {
         move_project_sync(Path::new(&src_path), Path::new(&dest_set_path))
     })
@@ -861,6 +869,8 @@ pub(crate) fn move_set_sync(src: &Path, dest_location: &Path) -> Result<String,
 /// Runs on the blocking thread pool.
 #[tauri::command]
 pub async fn move_set(src_path: String, dest_location_path: String) -> Result<String, String> {
+    crate::fs_scope::ensure_allowed(&src_path)?;
+    crate::fs_scope::ensure_allowed(&dest_location_path)?;
     tauri::async_runtime::spawn_blocking(move || {
         move_set_sync(Path::new(&src_path), Path::new(&dest_location_path))
     })
@@ -878,6 +888,8 @@ pub async fn move_project_with_progress(
     dest_set_path: String,
     transfer_id: String,
 ) -> Result<String, String> {
+    crate::fs_scope::ensure_allowed(&src_path)?;
+    crate::fs_scope::ensure_allowed(&dest_set_path)?;
     let cancel_token = register_cancellation_token(&transfer_id);
     let tid = transfer_id.clone();
 
@@ -1032,6 +1044,8 @@ pub async fn move_set_with_progress(
     dest_location_path: String,
     transfer_id: String,
 ) -> Result<String, String> {
+    crate::fs_scope::ensure_allowed(&src_path)?;
+    crate::fs_scope::ensure_allowed(&dest_location_path)?;
     let cancel_token = register_cancellation_token(&transfer_id);
     let tid = transfer_id.clone();
 
@@ -1204,6 +1218,7 @@ pub(crate) fn delete_project_sync(p: &Path) -> Result<(), String> {
 /// Runs on the blocking thread pool.
 #[tauri::command]
 pub async fn delete_project(project_path: String) -> Result<(), String> {
+    crate::fs_scope::ensure_allowed(&project_path)?;
     tauri::async_runtime::spawn_blocking(move || delete_project_sync(Path::new(&project_path)))
         .await
         .map_err(|e| format!("Background task failed: {}", e))?
@@ -1288,6 +1303,7 @@ pub(crate) fn create_set_sync(location: &Path, name: &str) -> Result<String, Str
 
 #[tauri::command]
 pub async fn create_set(location_path: String, name: String) -> Result<String, String> {
+    crate::fs_scope::ensure_allowed(&location_path)?;
     tauri::async_runtime::spawn_blocking(move || create_set_sync(Path::new(&location_path), &name))
         .await
         .map_err(|e| format!("Background task failed: {}", e))?
@@ -1327,6 +1343,7 @@ pub(crate) fn rename_set_sync(src: &Path, new_name: &str) -> Result<String, Stri
 
 #[tauri::command]
 pub async fn rename_set(set_path: String, new_name: String) -> Result<String, String> {
+    crate::fs_scope::ensure_allowed(&set_path)?;
     tauri::async_runtime::spawn_blocking(move || rename_set_sync(Path::new(&set_path), &new_name))
         .await
         .map_err(|e| format!("Background task failed: {}", e))?
@@ -1349,6 +1366,7 @@ pub(crate) fn delete_set_sync(p: &Path) -> Result<(), String> {
 
 #[tauri::command]
 pub async fn delete_set(set_path: String) -> Result<(), String> {
+    crate::fs_scope::ensure_allowed(&set_path)?;
     tauri::async_runtime::spawn_blocking(move || delete_set_sync(Path::new(&set_path)))
         .await
         .map_err(|e| format!("Background task failed: {}", e))?
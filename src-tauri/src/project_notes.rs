@@ -0,0 +1,390 @@
+// Project notes: free-text annotations per project, bank and pattern
+// ("pattern 7 = chorus, crossfader does filter sweep"), plus color labels for
+// banks, patterns and parts.
+//
+// Everything lives in the app's data directory, keyed by the project's
+// canonical path. Nothing is ever written into the project folder: the Octatrack
+// must not see foreign files on the card.
+
+use crate::project_reader::BANK_LETTERS;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+static NOTES_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// Colors a label can take (the UI's palette).
+pub const LABEL_COLORS: [&str; 8] = [
+    "red", "orange", "yellow", "green", "cyan", "blue", "purple", "gray",
+];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ColorLabels {
+    #[serde(default)]
+    pub banks: BTreeMap<String, String>, // "A".."P"
+    #[serde(default)]
+    pub patterns: BTreeMap<String, String>, // "A01".."P16"
+    #[serde(default)]
+    pub parts: BTreeMap<String, String>, // "A1".."P4"
+}
+
+impl ColorLabels {
+    pub fn is_empty(&self) -> bool {
+        self.banks.is_empty() && self.patterns.is_empty() && self.parts.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ProjectNotes {
+    #[serde(default)]
+    pub project: Option<String>,
+    #[serde(default)]
+    pub banks: BTreeMap<String, String>, // "A".."P"
+    #[serde(default)]
+    pub patterns: BTreeMap<String, String>, // "A01".."P16"
+    #[serde(default)]
+    pub labels: ColorLabels,
+}
+
+impl ProjectNotes {
+    pub fn is_empty(&self) -> bool {
+        self.project.is_none()
+            && self.banks.is_empty()
+            && self.patterns.is_empty()
+            && self.labels.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct NotesStore {
+    #[serde(default)]
+    projects: BTreeMap<String, ProjectNotes>,
+}
+
+/// Default store location: `<data dir>/octatrack-manager/project_notes.json`.
+pub fn default_notes_path() -> Result<PathBuf, String> {
+    crate::app_data::data_path("project_notes.json")
+}
+
+/// Key of a project in the store: its canonical path. Set and project folder
+/// names alone are not enough, two cards (or a card and a backup drive) often
+/// hold unrelated projects with the same names.
+pub fn project_fingerprint(project_path: &Path) -> String {
+    fs::canonicalize(project_path)
+        .unwrap_or_else(|_| project_path.to_path_buf())
+        .to_string_lossy()
+        .to_string()
+}
+
+fn load_store(notes_path: &Path) -> Result<NotesStore, String> {
+    if !notes_path.exists() {
+        return Ok(NotesStore::default());
+    }
+    let data =
+        fs::read_to_string(notes_path).map_err(|e| format!("Failed to read notes: {}", e))?;
+    serde_json::from_str(&data).map_err(|e| format!("Failed to parse notes: {}", e))
+}
+
+fn save_store(notes_path: &Path, store: &NotesStore) -> Result<(), String> {
+    crate::app_data::save_json(notes_path, store, "notes")
+}
+
+/// Key of a pattern note: bank letter + 1-based pattern number ("A01").
+pub fn pattern_key(bank_index: u8, pattern_index: u8) -> Result<String, String> {
+    let bank = BANK_LETTERS
+        .get(bank_index as usize)
+        .ok_or_else(|| format!("Invalid bank index: {} (must be 0-15)", bank_index))?;
+    if pattern_index > 15 {
+        return Err(format!(
+            "Invalid pattern index: {} (must be 0-15)",
+            pattern_index
+        ));
+    }
+    Ok(format!("{}{:02}", bank, pattern_index + 1))
+}
+
+/// Notes of one project from the store at `notes_path` (empty when none).
+pub fn get_notes_in(notes_path: &Path, project_path: &Path) -> Result<ProjectNotes, String> {
+    let store = load_store(notes_path)?;
+    Ok(store
+        .projects
+        .get(&project_fingerprint(project_path))
+        .cloned()
+        .unwrap_or_default())
+}
+
+/// Apply `f` to one project's notes under the store lock and save. A project
+/// left without notes or labels is dropped from the store.
+fn update_project<F>(notes_path: &Path, project_path: &Path, f: F) -> Result<ProjectNotes, String>
+where
+    F: FnOnce(&mut ProjectNotes) -> Result<(), String>,
+{
+    let _guard = NOTES_LOCK.lock().unwrap();
+    let mut store = load_store(notes_path)?;
+    let key = project_fingerprint(project_path);
+    let notes = store.projects.entry(key.clone()).or_default();
+    f(notes)?;
+    let result = notes.clone();
+    if result.is_empty() {
+        store.projects.remove(&key);
+    }
+    save_store(notes_path, &store)?;
+    Ok(result)
+}
+
+fn bank_letter(bank_index: u8) -> Result<String, String> {
+    BANK_LETTERS
+        .get(bank_index as usize)
+        .map(|b| b.to_string())
+        .ok_or_else(|| format!("Invalid bank index: {} (must be 0-15)", bank_index))
+}
+
+/// Insert `value` under `key`, or remove the entry when `value` is None.
+fn set_entry(map: &mut BTreeMap<String, String>, key: String, value: Option<String>) {
+    match value {
+        Some(v) => map.insert(key, v),
+        None => map.remove(&key),
+    };
+}
+
+/// Set (or, with blank `text`, remove) one note. `bank_index` and
+/// `pattern_index` are 0-based; a pattern note needs both, a bank note only
+/// the bank, a project note neither.
+pub fn set_note_in(
+    notes_path: &Path,
+    project_path: &Path,
+    bank_index: Option<u8>,
+    pattern_index: Option<u8>,
+    text: &str,
+) -> Result<ProjectNotes, String> {
+    let text = text.trim();
+    let value = (!text.is_empty()).then(|| text.to_string());
+
+    update_project(notes_path, project_path, |notes| {
+        match (bank_index, pattern_index) {
+            (None, None) => notes.project = value,
+            (Some(bank), None) => set_entry(&mut notes.banks, bank_letter(bank)?, value),
+            (Some(bank), Some(pattern)) => {
+                set_entry(&mut notes.patterns, pattern_key(bank, pattern)?, value)
+            }
+            (None, Some(_)) => return Err("A pattern note needs a bank".to_string()),
+        }
+        Ok(())
+    })
+}
+
+/// Set (or, with `color` None, remove) the color label of a bank, pattern or
+/// part. `target` is "bank", "pattern" or "part"; `index` (0-based) is the
+/// pattern or part within the bank and is ignored for "bank".
+pub fn set_label_in(
+    notes_path: &Path,
+    project_path: &Path,
+    target: &str,
+    bank_index: u8,
+    index: Option<u8>,
+    color: Option<&str>,
+) -> Result<ProjectNotes, String> {
+    if let Some(color) = color {
+        if !LABEL_COLORS.contains(&color) {
+            return Err(format!("Unknown label color: {}", color));
+        }
+    }
+    let value = color.map(str::to_string);
+    let bank = bank_letter(bank_index)?;
+    let need_index = || index.ok_or_else(|| format!("A {} label needs an index", target));
+
+    update_project(notes_path, project_path, |notes| {
+        let labels = &mut notes.labels;
+        match target {
+            "bank" => set_entry(&mut labels.banks, bank, value),
+            "pattern" => set_entry(
+                &mut labels.patterns,
+                pattern_key(bank_index, need_index()?)?,
+                value,
+            ),
+            "part" => {
+                let part = need_index()?;
+                if part > 3 {
+                    return Err(format!("Invalid part index: {} (must be 0-3)", part));
+                }
+                set_entry(&mut labels.parts, format!("{}{}", bank, part + 1), value)
+            }
+            _ => {
+                return Err(format!(
+                    "Invalid label target: {}. Must be 'bank', 'pattern' or 'part'",
+                    target
+                ))
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Color labels of a project from the default store. Best-effort: a missing or
+/// unreadable store just means no labels.
+pub fn project_labels(project_path: &Path) -> ColorLabels {
+    default_notes_path()
+        .and_then(|p| get_notes_in(&p, project_path))
+        .map(|n| n.labels)
+        .unwrap_or_default()
+}
+
+/// Notes as report lines: project note first, then banks, then patterns.
+pub fn notes_report_lines(notes: &ProjectNotes) -> Vec<String> {
+    let mut lines = Vec::new();
+    if let Some(project) = &notes.project {
+        lines.push(format!("Project: {}", project));
+    }
+    for (bank, text) in &notes.banks {
+        lines.push(format!("Bank {}: {}", bank, text));
+    }
+    for (pattern, text) in &notes.patterns {
+        lines.push(format!("Pattern {}: {}", pattern, text));
+    }
+    lines
+}
+
+#[tauri::command]
+pub fn get_project_notes(project_path: String) -> Result<ProjectNotes, String> {
+    get_notes_in(&default_notes_path()?, Path::new(&project_path))
+}
+
+#[tauri::command]
+pub fn set_project_note(
+    project_path: String,
+    bank_index: Option<u8>,
+    pattern_index: Option<u8>,
+    text: String,
+) -> Result<ProjectNotes, String> {
+    set_note_in(
+        &default_notes_path()?,
+        Path::new(&project_path),
+        bank_index,
+        pattern_index,
+        &text,
+    )
+}
+
+#[tauri::command]
+pub fn set_color_label(
+    project_path: String,
+    target: String,
+    bank_index: u8,
+    index: Option<u8>,
+    color: Option<String>,
+) -> Result<ProjectNotes, String> {
+    set_label_in(
+        &default_notes_path()?,
+        Path::new(&project_path),
+        &target,
+        bank_index,
+        index,
+        color.as_deref(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_set_and_get_notes() {
+        let dir = TempDir::new().unwrap();
+        let store = dir.path().join("notes.json");
+        let project = dir.path().join("PROJECT");
+        fs::create_dir_all(&project).unwrap();
+
+        set_note_in(&store, &project, None, None, "live set v2").unwrap();
+        set_note_in(&store, &project, Some(1), None, "drums").unwrap();
+        set_note_in(
+            &store,
+            &project,
+            Some(0),
+            Some(6),
+            "chorus, crossfader = filter sweep",
+        )
+        .unwrap();
+
+        let notes = get_notes_in(&store, &project).unwrap();
+        assert_eq!(notes.project.as_deref(), Some("live set v2"));
+        assert_eq!(notes.banks["B"], "drums");
+        assert_eq!(notes.patterns["A07"], "chorus, crossfader = filter sweep");
+        assert_eq!(
+            notes_report_lines(&notes),
+            vec![
+                "Project: live set v2",
+                "Bank B: drums",
+                "Pattern A07: chorus, crossfader = filter sweep"
+            ]
+        );
+        // Nothing lands in the project folder
+        assert_eq!(fs::read_dir(&project).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_blank_text_removes_note() {
+        let dir = TempDir::new().unwrap();
+        let store = dir.path().join("notes.json");
+        let project = dir.path().join("PROJECT");
+
+        set_note_in(&store, &project, Some(2), Some(0), "intro").unwrap();
+        let notes = set_note_in(&store, &project, Some(2), Some(0), "  ").unwrap();
+
+        assert!(notes.is_empty());
+        assert!(load_store(&store).unwrap().projects.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_indices_are_rejected() {
+        let dir = TempDir::new().unwrap();
+        let store = dir.path().join("notes.json");
+        let project = dir.path().join("PROJECT");
+
+        assert!(set_note_in(&store, &project, Some(16), None, "x").is_err());
+        assert!(set_note_in(&store, &project, Some(0), Some(16), "x").is_err());
+        assert!(set_note_in(&store, &project, None, Some(0), "x").is_err());
+    }
+
+    #[test]
+    fn test_color_labels() {
+        let dir = TempDir::new().unwrap();
+        let store = dir.path().join("notes.json");
+        let project = dir.path().join("SET/PROJECT");
+
+        set_label_in(&store, &project, "bank", 0, None, Some("red")).unwrap();
+        set_label_in(&store, &project, "pattern", 1, Some(15), Some("blue")).unwrap();
+        let notes = set_label_in(&store, &project, "part", 2, Some(3), Some("green")).unwrap();
+
+        assert_eq!(notes.labels.banks["A"], "red");
+        assert_eq!(notes.labels.patterns["B16"], "blue");
+        assert_eq!(notes.labels.parts["C4"], "green");
+
+        let notes = set_label_in(&store, &project, "bank", 0, None, None).unwrap();
+        assert!(notes.labels.banks.is_empty());
+        assert!(set_label_in(&store, &project, "bank", 0, None, Some("mauve")).is_err());
+        assert!(set_label_in(&store, &project, "part", 0, Some(4), Some("red")).is_err());
+        assert!(set_label_in(&store, &project, "pattern", 0, None, Some("red")).is_err());
+    }
+
+    #[test]
+    fn test_same_named_projects_keep_separate_notes() {
+        let dir = TempDir::new().unwrap();
+        let store = dir.path().join("notes.json");
+        let card = dir.path().join("CARD/MySet/SONG1");
+        let backup = dir.path().join("BACKUP/MySet/SONG1");
+        fs::create_dir_all(&card).unwrap();
+        fs::create_dir_all(&backup).unwrap();
+
+        set_note_in(&store, &card, None, None, "live set").unwrap();
+        assert_ne!(project_fingerprint(&card), project_fingerprint(&backup));
+        assert!(get_notes_in(&store, &backup).unwrap().is_empty());
+        assert_eq!(
+            get_notes_in(&store, &card).unwrap().project.as_deref(),
+            Some("live set")
+        );
+    }
+}
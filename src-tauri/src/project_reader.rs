@@ -2,19 +2,51 @@
 #![allow(clippy::collapsible_if)]
 #![allow(clippy::collapsible_match)]
 
+use crate::sample_attributes::{sidecar_attributes, OtSampleAttributes};
+use crate::write_verify::{VerificationFailure, VerificationReport};
+use once_cell::sync::Lazy;
 use ot_tools_io::settings::{LoopMode, TimeStretchMode, TrigQuantizationMode};
 use ot_tools_io::types::{Slice, SlotAttributes, SlotMarkers, SlotType};
 use ot_tools_io::{
     BankFile, HasChecksumField, MarkersFile, OctatrackFileIO, ProjectFile, SampleSettingsFile,
 };
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use walkdir::WalkDir;
 
+/// Something a read skipped or fell back on instead of failing outright.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ReadWarning {
+    pub file: String, // file name within the project, e.g. "bank03.work"
+    pub kind: String, // "parse_error", "io_error" or "format_version"
+    pub message: String,
+}
+
+impl ReadWarning {
+    fn new(file: impl Into<String>, kind: &str, message: impl Into<String>) -> Self {
+        ReadWarning {
+            file: file.into(),
+            kind: kind.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Data decoded from a project, with warnings about the parts that couldn't
+/// be read (the data then holds defaults for those).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadResult<T> {
+    pub data: T,
+    pub warnings: Vec<ReadWarning>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProjectMetadata {
     pub name: String,
     pub tempo: f32,
+    pub pattern_tempo_enabled: bool, // patterns play their own tempo instead of the project's
     pub time_signature: String,
     pub pattern_length: u16,
     pub current_state: CurrentState,
@@ -115,6 +147,7 @@ pub struct SampleSlot {
     pub sample_rate: Option<u32>,      // 44100, 48000, etc.
     pub ot_size_bytes: Option<u64>, // PCM data size as the OT measures it (None if empty/unparsable)
     pub attributes_at_default: bool, // true => audio-editor attributes equal OT defaults (reset is a no-op)
+    pub ot_attributes: Option<OtSampleAttributes>, // from the sample's .ot file, if any
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -200,21 +233,23 @@ pub struct MidiParams {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrigStep {
-    pub step: u8,                                  // Step number (0-63)
-    pub trigger: bool,                             // Has trigger trig
-    pub trigless: bool,                            // Has trigless trig
-    pub plock: bool,                               // Has parameter lock
-    pub oneshot: bool,                             // Has oneshot trig (audio only)
-    pub swing: bool,                               // Has swing trig
-    pub slide: bool,                               // Has slide trig (audio only)
-    pub recorder: bool,                            // Has recorder trig (audio only)
-    pub recorder_oneshot: bool,                    // Recorder trig is one-shot (audio only)
+    pub step: u8,               // Step number (0-63)
+    pub trigger: bool,          // Has trigger trig
+    pub trigless: bool,         // Has trigless trig
+    pub plock: bool,            // Has parameter lock
+    pub oneshot: bool,          // Has oneshot trig (audio only)
+    pub swing: bool,            // Has swing trig
+    pub slide: bool,            // Has slide trig (audio only)
+    pub recorder: bool,         // Has recorder trig (audio only)
+    pub recorder_oneshot: bool, // Recorder trig is one-shot (audio only)
+    #[serde(default)]
+    pub recorder_sources: Vec<String>, // Sources the rec trig records: "INAB", "INCD", "SRC3"
     pub trig_condition: Option<String>, // Trig condition (Fill, NotFill, Pre, percentages, etc.)
-    pub trig_repeats: u8,               // Number of trig repeats (0-7)
-    pub micro_timing: Option<String>,   // Micro-timing offset (e.g., "+1/32", "-1/64")
-    pub notes: Vec<u8>, // MIDI note values (up to 4 notes for chords on MIDI tracks)
-    pub velocity: Option<u8>, // Velocity/level value (0-127)
-    pub plock_count: u8, // Number of parameter locks on this step
+    pub trig_repeats: u8,       // Number of trig repeats (0-7)
+    pub micro_timing: Option<String>, // Micro-timing offset (e.g., "+1/32", "-1/64")
+    pub notes: Vec<u8>,         // MIDI note values (up to 4 notes for chords on MIDI tracks)
+    pub velocity: Option<u8>,   // Velocity/level value (0-127)
+    pub plock_count: u8,        // Number of parameter locks on this step
     pub sample_slot: Option<u8>, // Sample slot ID if locked (audio tracks), 1-based for display
     pub audio_plocks: Option<AudioParameterLocks>, // Audio parameter locks (audio tracks only)
     pub midi_plocks: Option<MidiParameterLocks>, // MIDI parameter locks (MIDI tracks only)
@@ -228,10 +263,12 @@ pub struct TrackInfo {
     pub per_track_len: Option<u8>,       // Track length in per-track mode
     pub per_track_scale: Option<String>, // Track scale in per-track mode
     pub pattern_settings: TrackSettings,
-    pub trig_counts: TrigCounts,          // Per-track trig statistics
-    pub steps: Vec<TrigStep>,             // Per-step trig information (64 steps)
-    pub default_note: Option<u8>,         // Default note for MIDI tracks (0-127)
+    pub trig_counts: TrigCounts,              // Per-track trig statistics
+    pub steps: Vec<TrigStep>,                 // Per-step trig information (64 steps)
+    pub default_note: Option<u8>,             // Default note for MIDI tracks (0-127)
     pub assigned_sample_slot: Option<u8>, // Part-assigned sample slot for this track (1-based); None for MIDI / non-sample machines
+    pub assigned_slot_type: Option<String>, // "Static" or "Flex" pool of assigned_sample_slot
+    pub assigned_sample_name: Option<String>, // File name loaded in that slot; None if the slot is empty
     pub slice_count: Option<u32>, // Slice count of the part-assigned sample when the machine's SLIC setting is on; STRT p-locks then select slices (slice = value/2 + 1)
 }
 
@@ -249,6 +286,7 @@ pub struct Pattern {
     pub trig_counts: TrigCounts, // Detailed trig statistics
     pub per_track_settings: Option<PerTrackSettings>, // Settings for per-track mode
     pub has_swing: bool,     // Whether pattern has any swing trigs
+    pub color_label: Option<String>, // User color label (app sidecar, see project_notes)
     pub tracks: Vec<TrackInfo>, // Per-track information
 }
 
@@ -257,6 +295,7 @@ pub struct Part {
     pub id: u8,
     pub name: String,
     pub patterns: Vec<Pattern>,
+    pub color_label: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -264,6 +303,7 @@ pub struct Bank {
     pub id: String,
     pub name: String,
     pub parts: Vec<Part>,
+    pub color_label: Option<String>,
 }
 
 // Parts machine parameter structures
@@ -386,6 +426,25 @@ pub struct PartTrackFx {
     pub fx2_setup6: u8,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PartTrackRecorder {
+    pub track_id: u8, // 0-7 for audio tracks T1-T8
+    // RECORDING SETUP 1 parameters
+    pub in_ab: u8, // Input A/B source (0=-, 1=A, 2=B, 3=A+B)
+    pub in_cd: u8, // Input C/D source (0=-, 1=C, 2=D, 3=C+D)
+    pub rlen: u8,  // Recording length in steps
+    pub trig: u8,  // Rec trig mode (0=ONE, 1=ONE2, 2=HOLD)
+    pub src3: u8,  // Internal source (0=-, 1=MAIN, 2=CUE, 3-10=T1-T8)
+    pub xloop: u8, // Loop the recording
+    // RECORDING SETUP 2 parameters
+    pub fin: u8,  // Fade in
+    pub fout: u8, // Fade out
+    pub ab: u8,   // Input A/B recording gain
+    pub qrec: u8, // Recording start quantization
+    pub qpl: u8,  // Manual playback quantization
+    pub cd: u8,   // Input C/D recording gain
+}
+
 // MIDI track parameter structures
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PartTrackMidiNote {
@@ -457,14 +516,16 @@ pub struct PartTrackMidiCtrl2 {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PartData {
-    pub part_id: u8,                          // 0-3 for Parts 1-4
-    pub machines: Vec<PartTrackMachine>,      // 8 audio tracks
-    pub amps: Vec<PartTrackAmp>,              // 8 audio tracks
-    pub lfos: Vec<PartTrackLfo>,              // 8 audio tracks (also used for MIDI LFOs)
-    pub fxs: Vec<PartTrackFx>,                // 8 audio tracks
-    pub midi_notes: Vec<PartTrackMidiNote>,   // 8 MIDI tracks
-    pub midi_arps: Vec<PartTrackMidiArp>,     // 8 MIDI tracks
-    pub midi_lfos: Vec<PartTrackLfo>,         // 8 MIDI tracks (reuses audio LFO structure)
+    pub part_id: u8,                     // 0-3 for Parts 1-4
+    pub machines: Vec<PartTrackMachine>, // 8 audio tracks
+    pub amps: Vec<PartTrackAmp>,         // 8 audio tracks
+    pub lfos: Vec<PartTrackLfo>,         // 8 audio tracks (also used for MIDI LFOs)
+    pub fxs: Vec<PartTrackFx>,           // 8 audio tracks
+    #[serde(default)]
+    pub recorders: Vec<PartTrackRecorder>, // 8 audio tracks (track recorder setup)
+    pub midi_notes: Vec<PartTrackMidiNote>, // 8 MIDI tracks
+    pub midi_arps: Vec<PartTrackMidiArp>, // 8 MIDI tracks
+    pub midi_lfos: Vec<PartTrackLfo>,    // 8 MIDI tracks (reuses audio LFO structure)
     pub midi_ctrl1s: Vec<PartTrackMidiCtrl1>, // 8 MIDI tracks
     pub midi_ctrl2s: Vec<PartTrackMidiCtrl2>, // 8 MIDI tracks
 }
@@ -557,8 +618,9 @@ fn check_audio_compatibility(file_path: &Path) -> AudioInfo {
     }
 }
 
-pub fn read_project_metadata(project_path: &str) -> Result<ProjectMetadata, String> {
+pub fn read_project_metadata(project_path: &str) -> Result<ReadResult<ProjectMetadata>, String> {
     let path = Path::new(project_path);
+    let mut warnings = Vec::new();
 
     // Look for project.work or project.strd file
     let project_file_path = if path.join("project.work").exists() {
@@ -571,8 +633,15 @@ pub fn read_project_metadata(project_path: &str) -> Result<ProjectMetadata, Stri
 
     match ProjectFile::from_data_file(&project_file_path) {
         Ok(project) => {
-            // Extract tempo
-            let tempo = project.settings.tempo.tempo as f32;
+            // Extract tempo. ot-tools-io truncates fractional TEMPOx24 values, so
+            // prefer the raw line when it is readable.
+            let tempo = read_settings_field(&project_file_path, "TEMPOx24")
+                .and_then(|v| v.parse::<f32>().ok())
+                .map(|x24| x24 / 24.0)
+                .unwrap_or(project.settings.tempo.tempo as f32);
+            let pattern_tempo_enabled =
+                read_settings_field(&project_file_path, "PATTERN_TEMPO_ENABLED")
+                    .is_some_and(|v| v == "1");
 
             // Extract time signature
             let numerator = project.settings.control.metronome.metronome_time_signature + 1;
@@ -586,10 +655,7 @@ pub fn read_project_metadata(project_path: &str) -> Result<ProjectMetadata, Stri
             let time_signature = format!("{}/{}", numerator, denominator);
 
             // Extract current state
-            let bank_letters = [
-                "A", "B", "C", "D", "E", "F", "G", "H", "I", "J", "K", "L", "M", "N", "O", "P",
-            ];
-            let bank_name = bank_letters
+            let bank_name = BANK_LETTERS
                 .get(project.states.bank as usize)
                 .unwrap_or(&"A")
                 .to_string();
@@ -661,8 +727,15 @@ pub fn read_project_metadata(project_path: &str) -> Result<ProjectMetadata, Stri
 
             // Compute flex RAM free: capacity minus loaded sample sizes
             let flex_ram_capacity = calculate_flex_ram_bytes(&memory_settings);
-            let flex_ram_used =
-                sum_flex_sample_sizes(path, memory_settings.load_24bit_flex).unwrap_or(0);
+            let flex_ram_used = sum_flex_sample_sizes(path, memory_settings.load_24bit_flex)
+                .unwrap_or_else(|e| {
+                    warnings.push(ReadWarning::new(
+                        "project.work",
+                        "io_error",
+                        format!("Flex RAM usage unknown: {}", e),
+                    ));
+                    0
+                });
             let flex_ram_free = flex_ram_capacity.saturating_sub(flex_ram_used);
             let flex_ram_free_mb = truncate_bytes_to_mib(flex_ram_free);
             let memory_settings = MemorySettings {
@@ -761,6 +834,11 @@ pub fn read_project_metadata(project_path: &str) -> Result<ProjectMetadata, Stri
                             } else {
                                 None
                             },
+                            ot_attributes: if file_exists {
+                                sidecar_attributes(&full_path)
+                            } else {
+                                None
+                            },
                         });
                     } else {
                         // Slot exists but has no sample - still show attributes
@@ -779,6 +857,7 @@ pub fn read_project_metadata(project_path: &str) -> Result<ProjectMetadata, Stri
                             bit_depth: None,
                             sample_rate: None,
                             ot_size_bytes: None,
+                            ot_attributes: None,
                         });
                     }
                 } else {
@@ -798,6 +877,7 @@ pub fn read_project_metadata(project_path: &str) -> Result<ProjectMetadata, Stri
                         bit_depth: None,
                         sample_rate: None,
                         ot_size_bytes: None,
+                        ot_attributes: None,
                     });
                 }
             }
@@ -852,6 +932,11 @@ pub fn read_project_metadata(project_path: &str) -> Result<ProjectMetadata, Stri
                             } else {
                                 None
                             },
+                            ot_attributes: if file_exists {
+                                sidecar_attributes(&full_path)
+                            } else {
+                                None
+                            },
                         });
                     } else {
                         // Slot exists but has no sample - still show attributes
@@ -870,6 +955,7 @@ pub fn read_project_metadata(project_path: &str) -> Result<ProjectMetadata, Stri
                             bit_depth: None,
                             sample_rate: None,
                             ot_size_bytes: None,
+                            ot_attributes: None,
                         });
                     }
                 } else {
@@ -889,6 +975,7 @@ pub fn read_project_metadata(project_path: &str) -> Result<ProjectMetadata, Stri
                         bit_depth: None,
                         sample_rate: None,
                         ot_size_bytes: None,
+                        ot_attributes: None,
                     });
                 }
             }
@@ -919,21 +1006,31 @@ pub fn read_project_metadata(project_path: &str) -> Result<ProjectMetadata, Stri
                 };
 
                 // Try to read the bank file and extract pattern length
-                if let Ok(bank_data) = BankFile::from_data_file(&bank_file_path) {
-                    bank_data.patterns.0[current_pattern].scale.master_len as u16
-                } else {
-                    16 // Default to 16 if bank file can't be read
+                match crate::os_compat::read_bank_file(&bank_file_path) {
+                    Ok(bank_data) => bank_data.patterns.0[current_pattern].scale.master_len as u16,
+                    Err(e) => {
+                        // Default to 16 if bank file can't be read
+                        if bank_file_path.exists() {
+                            warnings.push(ReadWarning::new(
+                                file_name_of(&bank_file_path),
+                                "parse_error",
+                                format!("Failed to read bank for pattern length: {}", e),
+                            ));
+                        }
+                        16
+                    }
                 }
             };
 
             // Extract metadata from the project file
-            Ok(ProjectMetadata {
+            let data = ProjectMetadata {
                 name: path
                     .file_name()
                     .and_then(|n| n.to_str())
                     .unwrap_or("Unknown")
                     .to_string(),
                 tempo,
+                pattern_tempo_enabled,
                 time_signature,
                 pattern_length,
                 current_state,
@@ -943,16 +1040,37 @@ pub fn read_project_metadata(project_path: &str) -> Result<ProjectMetadata, Stri
                 metronome_settings,
                 sample_slots,
                 os_version,
-            })
+            };
+            Ok(ReadResult { data, warnings })
         }
         Err(e) => Err(format!("Failed to read project file: {:?}", e)),
     }
 }
 
-const BANK_LETTERS: [&str; 16] = [
+pub(crate) const BANK_LETTERS: [&str; 16] = [
     "A", "B", "C", "D", "E", "F", "G", "H", "I", "J", "K", "L", "M", "N", "O", "P",
 ];
 
+/// Letter ("A"-"P") of a bank index, "?" when out of range.
+pub(crate) fn bank_letter(bank_index: u8) -> &'static str {
+    BANK_LETTERS
+        .get(bank_index as usize)
+        .copied()
+        .unwrap_or("?")
+}
+
+/// Pattern scale names, indexed by their stored code.
+pub(crate) const PATTERN_SCALES: [&str; 7] = ["2x", "3/2x", "1x", "3/4x", "1/2x", "1/4x", "1/8x"];
+
+/// Index (0-15) of a bank letter ("A"-"P").
+pub(crate) fn bank_index(bank_id: &str) -> Result<u8, String> {
+    BANK_LETTERS
+        .iter()
+        .position(|&letter| letter == bank_id)
+        .map(|idx| idx as u8)
+        .ok_or_else(|| format!("Invalid bank ID: {}", bank_id))
+}
+
 /// Read a single bank by index (0-15, corresponding to banks A-P)
 /// This is optimized to only read the single bank file.
 /// Returns a list of bank indices (0-15) that have existing bank files
@@ -987,8 +1105,83 @@ pub fn get_existing_bank_indices(project_path: &str) -> Vec<u8> {
 // byte[7]: steps 0-7   (1st half of 1st page)
 const BYTE_TO_STEP_OFFSET: [usize; 8] = [56, 48, 40, 32, 24, 16, 8, 0];
 
+/// Trig condition name from the condition byte (byte 1 of the
+/// offset/repeat/condition pair), or None for no condition.
+pub(crate) fn decode_trig_condition(condition_byte: u8) -> Option<String> {
+    // Need to handle micro-timing offset in upper bit
+    let condition = condition_byte % 128;
+    match condition {
+        0 => None,
+        1 => Some("Fill".to_string()),
+        2 => Some("NotFill".to_string()),
+        3 => Some("Pre".to_string()),
+        4 => Some("NotPre".to_string()),
+        5 => Some("Nei".to_string()),
+        6 => Some("NotNei".to_string()),
+        7 => Some("1st".to_string()),
+        8 => Some("Not1st".to_string()),
+        9 => Some("1%".to_string()),
+        10 => Some("2%".to_string()),
+        11 => Some("4%".to_string()),
+        12 => Some("6%".to_string()),
+        13 => Some("9%".to_string()),
+        14 => Some("13%".to_string()),
+        15 => Some("19%".to_string()),
+        16 => Some("25%".to_string()),
+        17 => Some("33%".to_string()),
+        18 => Some("41%".to_string()),
+        19 => Some("50%".to_string()),
+        20 => Some("59%".to_string()),
+        21 => Some("67%".to_string()),
+        22 => Some("75%".to_string()),
+        23 => Some("81%".to_string()),
+        24 => Some("87%".to_string()),
+        25 => Some("91%".to_string()),
+        26 => Some("94%".to_string()),
+        27 => Some("96%".to_string()),
+        28 => Some("98%".to_string()),
+        29 => Some("99%".to_string()),
+        30 => Some("1:2".to_string()),
+        31 => Some("2:2".to_string()),
+        32 => Some("1:3".to_string()),
+        33 => Some("2:3".to_string()),
+        34 => Some("3:3".to_string()),
+        35 => Some("1:4".to_string()),
+        36 => Some("2:4".to_string()),
+        37 => Some("3:4".to_string()),
+        38 => Some("4:4".to_string()),
+        39 => Some("1:5".to_string()),
+        40 => Some("2:5".to_string()),
+        41 => Some("3:5".to_string()),
+        42 => Some("4:5".to_string()),
+        43 => Some("5:5".to_string()),
+        44 => Some("1:6".to_string()),
+        45 => Some("2:6".to_string()),
+        46 => Some("3:6".to_string()),
+        47 => Some("4:6".to_string()),
+        48 => Some("5:6".to_string()),
+        49 => Some("6:6".to_string()),
+        50 => Some("1:7".to_string()),
+        51 => Some("2:7".to_string()),
+        52 => Some("3:7".to_string()),
+        53 => Some("4:7".to_string()),
+        54 => Some("5:7".to_string()),
+        55 => Some("6:7".to_string()),
+        56 => Some("7:7".to_string()),
+        57 => Some("1:8".to_string()),
+        58 => Some("2:8".to_string()),
+        59 => Some("3:8".to_string()),
+        60 => Some("4:8".to_string()),
+        61 => Some("5:8".to_string()),
+        62 => Some("6:8".to_string()),
+        63 => Some("7:8".to_string()),
+        64 => Some("8:8".to_string()),
+        _ => None,
+    }
+}
+
 /// Decode an 8-byte trig bitmask into a 64-element boolean array (bit N = step offset+N).
-fn decode_trig_masks(masks: &[u8]) -> [bool; 64] {
+pub(crate) fn decode_trig_masks(masks: &[u8]) -> [bool; 64] {
     let mut steps = [false; 64];
     for (byte_idx, &mask) in masks.iter().take(8).enumerate() {
         let step_offset = BYTE_TO_STEP_OFFSET[byte_idx];
@@ -1021,6 +1214,26 @@ fn decode_recorder_masks(masks: &[u8]) -> ([bool; 64], [bool; 64]) {
     (recorder, oneshot)
 }
 
+/// Recorder trig sources, in the order of their masks in the recorder trig array.
+pub(crate) const RECORDER_SOURCES: [&str; 3] = ["INAB", "INCD", "SRC3"];
+
+/// Sources each recorder trig is armed for, from the first three 8-byte masks
+/// of the recorder trig array (INAB, INCD, SRC3).
+fn decode_recorder_sources(masks: &[u8]) -> Vec<Vec<String>> {
+    let mut sources = vec![Vec::new(); 64];
+    for (source_idx, name) in RECORDER_SOURCES.iter().enumerate() {
+        let Some(mask) = masks.get(source_idx * 8..source_idx * 8 + 8) else {
+            break;
+        };
+        for (step, on) in decode_trig_masks(mask).iter().enumerate() {
+            if *on {
+                sources[step].push(name.to_string());
+            }
+        }
+    }
+    sources
+}
+
 /// One place a sample slot is referenced from.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SlotUsageEntry {
@@ -1064,7 +1277,7 @@ pub fn compute_sample_usage(project_path: &str) -> Result<SampleSlotUsage, Strin
                 continue;
             }
         }
-        let bank = match BankFile::from_data_file(&bank_path) {
+        let bank = match crate::os_compat::read_bank_file(&bank_path) {
             Ok(b) => b,
             Err(_) => continue,
         };
@@ -1187,7 +1400,7 @@ pub struct PoolUsageEntry {
 /// separators, so results are stable across Windows (`\`) and Unix (`/`)
 /// path construction — the frontend's usageKey() always normalizes lookups
 /// to forward-slash, so this side must match unconditionally.
-fn pool_usage_key(path: &Path) -> String {
+pub(crate) fn pool_usage_key(path: &Path) -> String {
     path.to_string_lossy().to_lowercase().replace('\\', "/")
 }
 
@@ -1262,6 +1475,129 @@ pub fn compute_pool_usage(
     Ok(result)
 }
 
+/// One place a sample slot is referenced from, for the cross-reference report.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SampleReference {
+    pub slot_type: String,   // "Static" or "Flex"
+    pub slot_id: u8,         // 1-based
+    pub bank: u8,            // 0-based
+    pub kind: String,        // "machine" (part assignment) or "lock" (sample p-lock)
+    pub track: u8,           // 0-based audio track
+    pub part: Option<u8>,    // machine usage: 0-based part
+    pub patterns: Vec<u8>,   // machine usage: 0-based patterns playing that part
+    pub pattern: Option<u8>, // lock usage: 0-based pattern
+    pub step: Option<u8>,    // lock usage: 0-based step
+    pub audible: bool,
+}
+
+/// Every bank/part/pattern/track/step referencing a slot (`slot_type` +
+/// 1-based `slot_id`) or, with `file`, any slot loaded with that file (path
+/// as stored in the slot, or absolute). Same rules as
+/// [`compute_sample_usage`]: an untrigged factory default is not a reference.
+pub fn find_sample_references(
+    project_path: &str,
+    slot_type: Option<&str>,
+    slot_id: Option<u8>,
+    file: Option<&str>,
+) -> Result<Vec<SampleReference>, String> {
+    let path = Path::new(project_path);
+    // (slot type, 0-based index) to report
+    let mut targets: Vec<(&str, usize)> = Vec::new();
+    match (slot_type, slot_id, file) {
+        (Some(t), Some(id), None) if (1..=128).contains(&id) => {
+            let t = match t.to_lowercase().as_str() {
+                "static" => "Static",
+                "flex" => "Flex",
+                _ => {
+                    return Err(format!(
+                        "Invalid slot_type: {}. Must be 'static' or 'flex'",
+                        t
+                    ))
+                }
+            };
+            targets.push((t, (id - 1) as usize));
+        }
+        (None, None, Some(file)) => {
+            let project_file = if path.join("project.work").exists() {
+                path.join("project.work")
+            } else if path.join("project.strd").exists() {
+                path.join("project.strd")
+            } else {
+                return Err("Project file not found".to_string());
+            };
+            let project = ProjectFile::from_data_file(&project_file)
+                .map_err(|e| format!("Failed to read project: {:?}", e))?;
+            let wanted = pool_usage_key(&normalize_path_lexically(
+                &path.join(file.replace('\\', "/")),
+            ));
+            for (t, slots) in [
+                ("Static", &project.slots.static_slots[..]),
+                ("Flex", &project.slots.flex_slots[..]),
+            ] {
+                for (idx, slot) in slots.iter().enumerate().take(128) {
+                    let Some(slot_path) = slot.as_ref().and_then(|s| s.path.as_ref()) else {
+                        continue;
+                    };
+                    let resolved = pool_usage_key(&normalize_path_lexically(
+                        &path.join(slot_path.to_string_lossy().replace('\\', "/")),
+                    ));
+                    if resolved == wanted {
+                        targets.push((t, idx));
+                    }
+                }
+            }
+        }
+        _ => return Err("Give either a slot (slot_type and slot_id 1-128) or a file".to_string()),
+    }
+
+    let usage = compute_sample_usage(project_path)?;
+    // Patterns playing each (bank, part), loaded once per bank.
+    let mut part_patterns: std::collections::HashMap<u8, [Vec<u8>; 4]> =
+        std::collections::HashMap::new();
+    let mut references = Vec::new();
+    for (t, idx) in targets {
+        let entries = if t == "Static" {
+            &usage.static_usage[idx]
+        } else {
+            &usage.flex_usage[idx]
+        };
+        for e in entries {
+            let patterns = match e.part {
+                Some(part) => {
+                    let by_part = part_patterns.entry(e.bank).or_insert_with(|| {
+                        let mut by_part: [Vec<u8>; 4] = Default::default();
+                        let mut bank_path = path.join(format!("bank{:02}.work", e.bank + 1));
+                        if !bank_path.exists() {
+                            bank_path = path.join(format!("bank{:02}.strd", e.bank + 1));
+                        }
+                        if let Ok(bank) = crate::os_compat::read_bank_file(&bank_path) {
+                            for (p, pattern) in bank.patterns.0.iter().enumerate() {
+                                by_part[(pattern.part_assignment as usize).min(3)].push(p as u8);
+                            }
+                        }
+                        by_part
+                    });
+                    by_part[(part as usize).min(3)].clone()
+                }
+                None => Vec::new(),
+            };
+            references.push(SampleReference {
+                slot_type: t.to_string(),
+                slot_id: (idx + 1) as u8,
+                bank: e.bank,
+                kind: e.kind.clone(),
+                track: e.track,
+                part: e.part,
+                patterns,
+                pattern: e.pattern,
+                step: e.step,
+                audible: e.audible,
+            });
+        }
+    }
+    Ok(references)
+}
+
 pub fn read_single_bank(project_path: &str, bank_index: u8) -> Result<Option<Bank>, String> {
     if bank_index >= 16 {
         return Err(format!("Invalid bank index: {}. Must be 0-15.", bank_index));
@@ -1283,22 +1619,92 @@ pub fn read_single_bank(project_path: &str, bank_index: u8) -> Result<Option<Ban
     }
 
     // Read only this bank using read_project_banks_internal
-    match read_project_banks_internal(project_path, Some(bank_index)) {
-        Ok(banks) => Ok(banks.into_iter().next()),
+    let scope = BankReadScope {
+        bank: Some(bank_index),
+        ..BankReadScope::default()
+    };
+    match read_project_banks_internal(project_path, scope) {
+        Ok(banks) => Ok(banks.data.into_iter().next()),
         Err(e) => Err(e),
     }
 }
 
-pub fn read_project_banks(project_path: &str) -> Result<Vec<Bank>, String> {
-    read_project_banks_internal(project_path, None)
+/// Every bank of a project. Banks that fail to parse are left out and
+/// reported in the warnings.
+pub fn read_project_banks(project_path: &str) -> Result<ReadResult<Vec<Bank>>, String> {
+    read_project_banks_internal(project_path, BankReadScope::default())
+}
+
+/// Light version of [`read_single_bank`]: pattern settings and trig counts
+/// only, every pattern's `tracks` left empty. Fetch a pattern's tracks with
+/// [`load_pattern`] when it is opened.
+pub fn load_bank_summary(project_path: &str, bank_index: u8) -> Result<Option<Bank>, String> {
+    if bank_index >= 16 {
+        return Err(format!("Invalid bank index: {}. Must be 0-15.", bank_index));
+    }
+    let scope = BankReadScope {
+        bank: Some(bank_index),
+        tracks: false,
+        ..BankReadScope::default()
+    };
+    Ok(read_project_banks_internal(project_path, scope)?
+        .data
+        .into_iter()
+        .next())
+}
+
+/// One pattern with its full track and step details, decoded against the
+/// part it is assigned to.
+pub fn load_pattern(
+    project_path: &str,
+    bank_index: u8,
+    pattern_index: u8,
+) -> Result<Pattern, String> {
+    if bank_index >= 16 {
+        return Err(format!("Invalid bank index: {}. Must be 0-15.", bank_index));
+    }
+    check_pattern_index(pattern_index)?;
+    let scope = BankReadScope {
+        bank: Some(bank_index),
+        pattern: Some(pattern_index),
+        tracks: true,
+    };
+    read_project_banks_internal(project_path, scope)?
+        .data
+        .into_iter()
+        .flat_map(|bank| bank.parts)
+        .flat_map(|part| part.patterns)
+        .next()
+        .ok_or_else(|| format!("Bank {} not found", BANK_LETTERS[bank_index as usize]))
+}
+
+/// What [`read_project_banks_internal`] decodes.
+#[derive(Debug, Clone, Copy)]
+struct BankReadScope {
+    bank: Option<u8>,    // None = every bank
+    pattern: Option<u8>, // only this pattern, under its assigned part
+    tracks: bool,        // per-track and per-step details
+}
+
+impl Default for BankReadScope {
+    fn default() -> Self {
+        BankReadScope {
+            bank: None,
+            pattern: None,
+            tracks: true,
+        }
+    }
 }
 
 fn read_project_banks_internal(
     project_path: &str,
-    target_bank_index: Option<u8>,
-) -> Result<Vec<Bank>, String> {
+    scope: BankReadScope,
+) -> Result<ReadResult<Vec<Bank>>, String> {
+    let target_bank_index = scope.bank;
     let path = Path::new(project_path);
     let mut banks = Vec::new();
+    let mut warnings = Vec::new();
+    let labels = crate::project_notes::project_labels(path);
 
     // Slice counts per sample slot (for slice-mode STRT p-lock display).
     // Missing/corrupt markers file just means no slice info.
@@ -1308,7 +1714,56 @@ fn read_project_banks_internal(
         } else {
             path.join("markers.strd")
         };
-        MarkersFile::from_data_file(&markers_path).ok()
+        match MarkersFile::from_data_file(&markers_path) {
+            Ok(markers) => Some(markers),
+            Err(e) => {
+                if markers_path.exists() {
+                    warnings.push(ReadWarning::new(
+                        file_name_of(&markers_path),
+                        "parse_error",
+                        format!("Slice counts unavailable: {:?}", e),
+                    ));
+                }
+                None
+            }
+        }
+    };
+
+    // File name loaded in each sample slot (0-based), to show what each
+    // track's machine plays. Missing/corrupt project file = no names.
+    let slot_file_names = |slots: &[Option<SlotAttributes>]| -> Vec<Option<String>> {
+        slots
+            .iter()
+            .map(|slot| {
+                slot.as_ref()
+                    .and_then(|s| s.path.as_ref())
+                    .and_then(|p| p.file_name())
+                    .map(|n| n.to_string_lossy().to_string())
+            })
+            .collect()
+    };
+    let (static_names, flex_names) = {
+        let project_file = if path.join("project.work").exists() {
+            path.join("project.work")
+        } else {
+            path.join("project.strd")
+        };
+        match ProjectFile::from_data_file(&project_file) {
+            Ok(project) => (
+                slot_file_names(&project.slots.static_slots[..]),
+                slot_file_names(&project.slots.flex_slots[..]),
+            ),
+            Err(e) => {
+                if project_file.exists() {
+                    warnings.push(ReadWarning::new(
+                        file_name_of(&project_file),
+                        "parse_error",
+                        format!("Sample names unavailable: {:?}", e),
+                    ));
+                }
+                (Vec::new(), Vec::new())
+            }
+        }
     };
 
     // Bank files are named bank01.work, bank02.work, etc.
@@ -1335,8 +1790,15 @@ fn read_project_banks_internal(
             }
         }
 
-        match BankFile::from_data_file(&bank_file_path) {
+        match crate::os_compat::read_bank_file(&bank_file_path) {
             Ok(bank_data) => {
+                if let Some(message) = crate::os_compat::bank_version_warning(&bank_file_path) {
+                    warnings.push(ReadWarning::new(
+                        file_name_of(&bank_file_path),
+                        "format_version",
+                        message,
+                    ));
+                }
                 // Debug print basic bank info
                 eprintln!(
                     "Bank {} loaded successfully, part_names: {:?}",
@@ -1365,6 +1827,13 @@ fn read_project_banks_internal(
 
                     // Each part has 16 patterns (1-16)
                     for pattern_id in 0..16 {
+                        if let Some(target) = scope.pattern {
+                            let assigned =
+                                bank_data.patterns.0[target as usize].part_assignment.min(3);
+                            if pattern_id != target || part_id != assigned {
+                                continue;
+                            }
+                        }
                         // Extract actual pattern length from bank data
                         // Each pattern stores its master length in the scale settings
                         let pattern = &bank_data.patterns.0[pattern_id as usize];
@@ -1406,80 +1875,6 @@ fn read_project_banks_internal(
                             masks.iter().map(|&mask| mask.count_ones() as u16).sum()
                         }
 
-                        // Helper function to decode trig condition from byte value
-                        fn decode_trig_condition(condition_byte: u8) -> Option<String> {
-                            // Need to handle micro-timing offset in upper bit
-                            let condition = condition_byte % 128;
-                            match condition {
-                                0 => None,
-                                1 => Some("Fill".to_string()),
-                                2 => Some("NotFill".to_string()),
-                                3 => Some("Pre".to_string()),
-                                4 => Some("NotPre".to_string()),
-                                5 => Some("Nei".to_string()),
-                                6 => Some("NotNei".to_string()),
-                                7 => Some("1st".to_string()),
-                                8 => Some("Not1st".to_string()),
-                                9 => Some("1%".to_string()),
-                                10 => Some("2%".to_string()),
-                                11 => Some("4%".to_string()),
-                                12 => Some("6%".to_string()),
-                                13 => Some("9%".to_string()),
-                                14 => Some("13%".to_string()),
-                                15 => Some("19%".to_string()),
-                                16 => Some("25%".to_string()),
-                                17 => Some("33%".to_string()),
-                                18 => Some("41%".to_string()),
-                                19 => Some("50%".to_string()),
-                                20 => Some("59%".to_string()),
-                                21 => Some("67%".to_string()),
-                                22 => Some("75%".to_string()),
-                                23 => Some("81%".to_string()),
-                                24 => Some("87%".to_string()),
-                                25 => Some("91%".to_string()),
-                                26 => Some("94%".to_string()),
-                                27 => Some("96%".to_string()),
-                                28 => Some("98%".to_string()),
-                                29 => Some("99%".to_string()),
-                                30 => Some("1:2".to_string()),
-                                31 => Some("2:2".to_string()),
-                                32 => Some("1:3".to_string()),
-                                33 => Some("2:3".to_string()),
-                                34 => Some("3:3".to_string()),
-                                35 => Some("1:4".to_string()),
-                                36 => Some("2:4".to_string()),
-                                37 => Some("3:4".to_string()),
-                                38 => Some("4:4".to_string()),
-                                39 => Some("1:5".to_string()),
-                                40 => Some("2:5".to_string()),
-                                41 => Some("3:5".to_string()),
-                                42 => Some("4:5".to_string()),
-                                43 => Some("5:5".to_string()),
-                                44 => Some("1:6".to_string()),
-                                45 => Some("2:6".to_string()),
-                                46 => Some("3:6".to_string()),
-                                47 => Some("4:6".to_string()),
-                                48 => Some("5:6".to_string()),
-                                49 => Some("6:6".to_string()),
-                                50 => Some("1:7".to_string()),
-                                51 => Some("2:7".to_string()),
-                                52 => Some("3:7".to_string()),
-                                53 => Some("4:7".to_string()),
-                                54 => Some("5:7".to_string()),
-                                55 => Some("6:7".to_string()),
-                                56 => Some("7:7".to_string()),
-                                57 => Some("1:8".to_string()),
-                                58 => Some("2:8".to_string()),
-                                59 => Some("3:8".to_string()),
-                                60 => Some("4:8".to_string()),
-                                61 => Some("5:8".to_string()),
-                                62 => Some("6:8".to_string()),
-                                63 => Some("7:8".to_string()),
-                                64 => Some("8:8".to_string()),
-                                _ => None,
-                            }
-                        }
-
                         // Helper function to get trig repeat count from byte
                         fn get_trig_repeats(repeat_byte: u8) -> u8 {
                             // Trig repeats are encoded as: repeats * 32
@@ -1713,22 +2108,30 @@ fn read_project_banks_internal(
                             None
                         };
 
-                        // Calculate BPM from tempo bytes
-                        // Formula: BPM = (tempo_1 + 1) * 10
-                        // Default values are tempo_1: 11, tempo_2: 64 (= 120 BPM)
-                        let bpm = (pattern.tempo_1 as u32 + 1) * 10;
-                        let tempo_info = if pattern.tempo_1 != 11 || pattern.tempo_2 != 64 {
-                            // Pattern has custom tempo
-                            Some(format!("{} BPM", bpm))
-                        } else {
-                            None
-                        };
+                        // Pattern tempo, unless the bytes hold the "not set" default
+                        let tempo_info =
+                            if (pattern.tempo_1, pattern.tempo_2) != DEFAULT_PATTERN_TEMPO {
+                                Some(format!(
+                                    "{} BPM",
+                                    decode_pattern_tempo(pattern.tempo_1, pattern.tempo_2)
+                                ))
+                            } else {
+                                None
+                            };
 
-                        // Extract per-track information
+                        // Extract per-track information (skipped for summaries)
                         let mut tracks = Vec::new();
+                        let (audio_tracks, midi_tracks) = if scope.tracks {
+                            (
+                                &pattern.audio_track_trigs.0[..],
+                                &pattern.midi_track_trigs.0[..],
+                            )
+                        } else {
+                            (&[][..], &[][..])
+                        };
 
                         // Process audio tracks (0-7)
-                        for (idx, audio_track) in pattern.audio_track_trigs.0.iter().enumerate() {
+                        for (idx, audio_track) in audio_tracks.iter().enumerate() {
                             let track_trigger_count = count_trigs(&audio_track.trig_masks.trigger);
                             let track_trigless_count =
                                 count_trigs(&audio_track.trig_masks.trigless);
@@ -1797,6 +2200,8 @@ fn read_project_banks_internal(
                             let slide_steps = decode_trig_masks(&audio_track.trig_masks.slide);
                             let (recorder_steps, recorder_oneshot_steps) =
                                 decode_recorder_masks(&audio_track.trig_masks.recorder);
+                            let mut recorder_sources =
+                                decode_recorder_sources(&audio_track.trig_masks.recorder);
                             // Swing trigs with the default swing amount (50 on device,
                             // stored as 0) don't do anything, so don't display them.
                             let swing_active = audio_track.swing_amount > 0;
@@ -1959,6 +2364,7 @@ fn read_project_banks_internal(
                                     slide: slide_steps[step],
                                     recorder: recorder_steps[step],
                                     recorder_oneshot: recorder_oneshot_steps[step],
+                                    recorder_sources: std::mem::take(&mut recorder_sources[step]),
                                     trig_condition,
                                     trig_repeats,
                                     micro_timing,
@@ -1975,14 +2381,28 @@ fn read_project_banks_internal(
                             // (part-assigned). The part slot is 0-based; expose
                             // it 1-based for display. None for non-sample
                             // machines (Thru/Neighbor/Pickup).
-                            let assigned_sample_slot = {
+                            let (assigned_sample_slot, assigned_slot_type, assigned_sample_name) = {
                                 let part =
                                     &bank_data.parts.unsaved.0[(part_assignment as usize).min(3)];
                                 let slot = &part.audio_track_machine_slots[idx];
                                 match part.audio_track_machine_types[idx] {
-                                    0 => Some(slot.static_slot_id.saturating_add(1)),
-                                    1 => Some(slot.flex_slot_id.saturating_add(1)),
-                                    _ => None,
+                                    0 => (
+                                        Some(slot.static_slot_id.saturating_add(1)),
+                                        Some("Static".to_string()),
+                                        static_names
+                                            .get(slot.static_slot_id as usize)
+                                            .cloned()
+                                            .flatten(),
+                                    ),
+                                    1 => (
+                                        Some(slot.flex_slot_id.saturating_add(1)),
+                                        Some("Flex".to_string()),
+                                        flex_names
+                                            .get(slot.flex_slot_id as usize)
+                                            .cloned()
+                                            .flatten(),
+                                    ),
+                                    _ => (None, None, None),
                                 }
                             };
 
@@ -2051,12 +2471,14 @@ fn read_project_banks_internal(
                                 steps,
                                 default_note: None, // Audio tracks don't have default notes
                                 assigned_sample_slot,
+                                assigned_slot_type,
+                                assigned_sample_name,
                                 slice_count,
                             });
                         }
 
                         // Process MIDI tracks (8-15)
-                        for (idx, midi_track) in pattern.midi_track_trigs.0.iter().enumerate() {
+                        for (idx, midi_track) in midi_tracks.iter().enumerate() {
                             // Get default note from BankFile's Part data for this MIDI track
                             let track_default_note = bank_data.parts.unsaved[part_id as usize]
                                 .midi_track_params_values[idx]
@@ -2287,6 +2709,7 @@ fn read_project_banks_internal(
                                     slide: false, // MIDI tracks don't have slide trigs
                                     recorder: false, // MIDI tracks don't have recorder trigs
                                     recorder_oneshot: false, // MIDI tracks don't have recorder trigs
+                                    recorder_sources: Vec::new(),
                                     trig_condition,
                                     trig_repeats,
                                     micro_timing,
@@ -2338,6 +2761,8 @@ fn read_project_banks_internal(
                                 steps,
                                 default_note, // Default NOTE value from Part file
                                 assigned_sample_slot: None, // MIDI tracks have no sample slot
+                                assigned_slot_type: None,
+                                assigned_sample_name: None,
                                 slice_count: None, // MIDI tracks have no samples
                             });
                         }
@@ -2356,6 +2781,10 @@ fn read_project_banks_internal(
                             per_track_settings,
                             has_swing,
                             tracks,
+                            color_label: labels
+                                .patterns
+                                .get(&format!("{}{:02}", bank_letter, pattern_id + 1))
+                                .cloned(),
                         });
                     }
 
@@ -2363,6 +2792,10 @@ fn read_project_banks_internal(
                         id: part_id,
                         name: part_name,
                         patterns,
+                        color_label: labels
+                            .parts
+                            .get(&format!("{}{}", bank_letter, part_id + 1))
+                            .cloned(),
                     });
                 }
 
@@ -2370,20 +2803,34 @@ fn read_project_banks_internal(
                     id: bank_letter.to_string(),
                     name: format!("Bank {}", bank_letter),
                     parts,
+                    color_label: labels.banks.get(*bank_letter).cloned(),
                 });
             }
             Err(e) => {
-                eprintln!("Warning: Failed to read bank {}: {:?}", bank_letter, e);
                 // If we're targeting a specific bank and it failed, return the error
                 if target_bank_index.is_some() {
-                    return Err(format!("Failed to read bank {}: {:?}", bank_letter, e));
+                    return Err(format!("Failed to read bank {}: {}", bank_letter, e));
                 }
-                // Otherwise continue with other banks
+                // Otherwise report it and continue with other banks
+                warnings.push(ReadWarning::new(
+                    file_name_of(&bank_file_path),
+                    "parse_error",
+                    format!("Failed to read bank {}: {}", bank_letter, e),
+                ));
             }
         }
     }
 
-    Ok(banks)
+    Ok(ReadResult {
+        data: banks,
+        warnings,
+    })
+}
+
+pub(crate) fn file_name_of(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default()
 }
 
 /// Read Parts machine and AMP parameters from a specific bank
@@ -2391,15 +2838,7 @@ pub fn read_parts_data(project_path: &str, bank_id: &str) -> Result<PartsDataRes
     let path = Path::new(project_path);
 
     // Convert bank letter (A-P) to bank number (1-16)
-    let bank_letters = [
-        "A", "B", "C", "D", "E", "F", "G", "H", "I", "J", "K", "L", "M", "N", "O", "P",
-    ];
-
-    let bank_num = bank_letters
-        .iter()
-        .position(|&letter| letter == bank_id)
-        .map(|idx| idx + 1)
-        .ok_or_else(|| format!("Invalid bank ID: {}", bank_id))?;
+    let bank_num = bank_index(bank_id)? + 1;
 
     let bank_file_name = format!("bank{:02}.work", bank_num);
     let mut bank_file_path = path.join(&bank_file_name);
@@ -2413,8 +2852,8 @@ pub fn read_parts_data(project_path: &str, bank_id: &str) -> Result<PartsDataRes
         }
     }
 
-    let bank_data = BankFile::from_data_file(&bank_file_path)
-        .map_err(|e| format!("Failed to read bank file: {:?}", e))?;
+    let bank_data = crate::os_compat::read_bank_file(&bank_file_path)
+        .map_err(|e| format!("Failed to read bank file: {}", e))?;
 
     let mut parts_data = Vec::new();
 
@@ -2428,20 +2867,13 @@ pub fn read_parts_data(project_path: &str, bank_id: &str) -> Result<PartsDataRes
         let mut amps = Vec::new();
         let mut lfos = Vec::new();
         let mut fxs = Vec::new();
+        let mut recorders = Vec::new();
 
         // Process 8 audio tracks (tracks 0-7)
         for track_id in 0..8 {
             // Get machine type (0=Static, 1=Flex, 2=Thru, 3=Neighbor, 4=Pickup)
             let machine_type_id = part.audio_track_machine_types[track_id as usize];
-            let machine_type = match machine_type_id {
-                0 => "Static",
-                1 => "Flex",
-                2 => "Thru",
-                3 => "Neighbor",
-                4 => "Pickup",
-                _ => "Unknown",
-            }
-            .to_string();
+            let machine_type = crate::param_decode::machine_type_name(machine_type_id).to_string();
 
             // Get machine parameters (SRC page)
             let machine_params_values = &part.audio_track_machine_params[track_id as usize];
@@ -2675,6 +3107,25 @@ pub fn read_parts_data(project_path: &str, bank_id: &str) -> Result<PartsDataRes
                 fx2_setup5: fx2_setup.setting5,
                 fx2_setup6: fx2_setup.setting6,
             });
+
+            // Get track recorder setup (RECORDING SETUP 1 and 2 pages)
+            let recorder_setup = &part.recorder_setup[track_id as usize];
+
+            recorders.push(PartTrackRecorder {
+                track_id,
+                in_ab: recorder_setup.src.in_ab,
+                in_cd: recorder_setup.src.in_cd,
+                rlen: recorder_setup.src.rlen,
+                trig: recorder_setup.src.trig,
+                src3: recorder_setup.src.src3,
+                xloop: recorder_setup.src.xloop,
+                fin: recorder_setup.proc.fin,
+                fout: recorder_setup.proc.fout,
+                ab: recorder_setup.proc.ab,
+                qrec: recorder_setup.proc.qrec,
+                qpl: recorder_setup.proc.qpl,
+                cd: recorder_setup.proc.cd,
+            });
         }
 
         // Process 8 MIDI tracks (tracks 0-7)
@@ -2807,6 +3258,7 @@ pub fn read_parts_data(project_path: &str, bank_id: &str) -> Result<PartsDataRes
             amps,
             lfos,
             fxs,
+            recorders,
             midi_notes,
             midi_arps,
             midi_lfos,
@@ -2822,48 +3274,39 @@ pub fn read_parts_data(project_path: &str, bank_id: &str) -> Result<PartsDataRes
     })
 }
 
-/// Save modified Parts data back to a bank file
-pub fn save_parts_data(
-    project_path: &str,
-    bank_id: &str,
-    parts_data: Vec<PartData>,
-) -> Result<(), String> {
-    let path = Path::new(project_path);
-
-    // Convert bank letter (A-P) to bank number (1-16)
-    let bank_letters = [
-        "A", "B", "C", "D", "E", "F", "G", "H", "I", "J", "K", "L", "M", "N", "O", "P",
-    ];
-
-    let bank_num = bank_letters
-        .iter()
-        .position(|&letter| letter == bank_id)
-        .map(|idx| idx + 1)
-        .ok_or_else(|| format!("Invalid bank ID: {}", bank_id))?;
-
-    let bank_file_name = format!("bank{:02}.work", bank_num);
-    let mut bank_file_path = path.join(&bank_file_name);
-
-    if !bank_file_path.exists() {
-        // Try .strd extension
-        let bank_file_name = format!("bank{:02}.strd", bank_num);
-        bank_file_path = path.join(&bank_file_name);
-        if !bank_file_path.exists() {
-            return Err(format!("Bank file not found: {}", bank_id));
-        }
-    }
-
-    // Read the existing bank file
-    let mut bank_data = BankFile::from_data_file(&bank_file_path)
-        .map_err(|e| format!("Failed to read bank file: {:?}", e))?;
+/// One lock per bank, shared by every command that rewrites a bank file.
+///
+/// Commands run on the blocking pool, so two overlapping requests (e.g.
+/// save_parts immediately followed by commit_part) could otherwise interleave
+/// their read-modify-write cycles: the later write would be based on a stale
+/// read and drop the earlier change, or pair it with the wrong checksum.
+static BANK_FILE_LOCKS: Lazy<Mutex<HashMap<PathBuf, Arc<Mutex<()>>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Lock handle for a bank file. `bankNN.work` and `bankNN.strd` share one lock.
+/// Hold the guard from the read to the final write:
+/// `let lock = bank_file_lock(&path); let _guard = lock.lock().unwrap_or_else(|e| e.into_inner());`
+pub(crate) fn bank_file_lock(bank_file_path: &Path) -> Arc<Mutex<()>> {
+    let dir = bank_file_path.parent().unwrap_or(Path::new("."));
+    let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+    let stem = bank_file_path
+        .file_stem()
+        .map(|s| s.to_ascii_lowercase())
+        .unwrap_or_default();
+    let mut locks = BANK_FILE_LOCKS.lock().unwrap_or_else(|e| e.into_inner());
+    locks.entry(dir.join(stem)).or_default().clone()
+}
 
+/// Write `parts_data` into the working copy (parts.unsaved) of a bank and mark
+/// those parts as edited.
+pub(crate) fn apply_parts_data(bank_data: &mut BankFile, parts_data: &[PartData]) {
     // Update the parts with the provided data
     // We ONLY write to parts.unsaved (the working copy), NOT parts.saved (the backup)
     // - parts.unsaved = working state that gets loaded; this is what we modify
     // - parts.saved = backup state used by "Reload Part" function on Octatrack
     // By keeping parts.saved unchanged, the user can use "Reload Part" on the Octatrack
     // to restore the original values before our edits.
-    for part_data in &parts_data {
+    for part_data in parts_data {
         let part_id = part_data.part_id as usize;
         if part_id >= 4 {
             continue; // Skip invalid part IDs
@@ -2956,41 +3399,25 @@ pub fn save_parts_data(
 
             // Update FX parameters
             if let Some(fx) = part_data.fxs.get(track_id) {
-                // FX types
-                part_unsaved.audio_track_fx1[track_id] = fx.fx1_type;
-                part_unsaved.audio_track_fx2[track_id] = fx.fx2_type;
-
-                // FX1 main parameters
-                part_unsaved.audio_track_params_values[track_id].fx1.param_1 = fx.fx1_param1;
-                part_unsaved.audio_track_params_values[track_id].fx1.param_2 = fx.fx1_param2;
-                part_unsaved.audio_track_params_values[track_id].fx1.param_3 = fx.fx1_param3;
-                part_unsaved.audio_track_params_values[track_id].fx1.param_4 = fx.fx1_param4;
-                part_unsaved.audio_track_params_values[track_id].fx1.param_5 = fx.fx1_param5;
-                part_unsaved.audio_track_params_values[track_id].fx1.param_6 = fx.fx1_param6;
-
-                // FX2 main parameters
-                part_unsaved.audio_track_params_values[track_id].fx2.param_1 = fx.fx2_param1;
-                part_unsaved.audio_track_params_values[track_id].fx2.param_2 = fx.fx2_param2;
-                part_unsaved.audio_track_params_values[track_id].fx2.param_3 = fx.fx2_param3;
-                part_unsaved.audio_track_params_values[track_id].fx2.param_4 = fx.fx2_param4;
-                part_unsaved.audio_track_params_values[track_id].fx2.param_5 = fx.fx2_param5;
-                part_unsaved.audio_track_params_values[track_id].fx2.param_6 = fx.fx2_param6;
-
-                // FX1 setup parameters
-                part_unsaved.audio_track_params_setup[track_id].fx1.setting1 = fx.fx1_setup1;
-                part_unsaved.audio_track_params_setup[track_id].fx1.setting2 = fx.fx1_setup2;
-                part_unsaved.audio_track_params_setup[track_id].fx1.setting3 = fx.fx1_setup3;
-                part_unsaved.audio_track_params_setup[track_id].fx1.setting4 = fx.fx1_setup4;
-                part_unsaved.audio_track_params_setup[track_id].fx1.setting5 = fx.fx1_setup5;
-                part_unsaved.audio_track_params_setup[track_id].fx1.setting6 = fx.fx1_setup6;
+                write_track_fx(part_unsaved, track_id, fx);
+            }
 
-                // FX2 setup parameters
-                part_unsaved.audio_track_params_setup[track_id].fx2.setting1 = fx.fx2_setup1;
-                part_unsaved.audio_track_params_setup[track_id].fx2.setting2 = fx.fx2_setup2;
-                part_unsaved.audio_track_params_setup[track_id].fx2.setting3 = fx.fx2_setup3;
-                part_unsaved.audio_track_params_setup[track_id].fx2.setting4 = fx.fx2_setup4;
-                part_unsaved.audio_track_params_setup[track_id].fx2.setting5 = fx.fx2_setup5;
-                part_unsaved.audio_track_params_setup[track_id].fx2.setting6 = fx.fx2_setup6;
+            // Update track recorder setup (RECORDING SETUP 1 and 2). Absent from
+            // payloads written before recorders were exposed; those leave it as is.
+            if let Some(recorder) = part_data.recorders.get(track_id) {
+                let setup = &mut part_unsaved.recorder_setup[track_id];
+                setup.src.in_ab = recorder.in_ab;
+                setup.src.in_cd = recorder.in_cd;
+                setup.src.rlen = recorder.rlen;
+                setup.src.trig = recorder.trig;
+                setup.src.src3 = recorder.src3;
+                setup.src.xloop = recorder.xloop;
+                setup.proc.fin = recorder.fin;
+                setup.proc.fout = recorder.fout;
+                setup.proc.ab = recorder.ab;
+                setup.proc.qrec = recorder.qrec;
+                setup.proc.qpl = recorder.qpl;
+                setup.proc.cd = recorder.cd;
             }
 
             // Update Machine parameters (SRC page)
@@ -3251,13 +3678,48 @@ pub fn save_parts_data(
     // Bitmask: Part 1 = bit 0 (1), Part 2 = bit 1 (2), Part 3 = bit 2 (4), Part 4 = bit 3 (8)
     // NOTE: We do NOT set parts_saved_state here because we're only editing parts.unsaved,
     // not committing changes to parts.saved. This allows "Reload Part" to work on the Octatrack.
-    for part_data in &parts_data {
+    for part_data in parts_data {
         let part_id = part_data.part_id as usize;
         if part_id < 4 {
             bank_data.parts_edited_bitmask |= 1 << part_id;
             // Don't touch parts_saved_state - we're editing, not saving/committing
         }
     }
+}
+
+/// Save modified Parts data back to a bank file
+pub fn save_parts_data(
+    project_path: &str,
+    bank_id: &str,
+    parts_data: Vec<PartData>,
+    verify: bool,
+) -> Result<VerificationReport, VerificationFailure> {
+    let path = Path::new(project_path);
+
+    // Convert bank letter (A-P) to bank number (1-16)
+    let bank_num = bank_index(bank_id)? + 1;
+
+    let bank_file_name = format!("bank{:02}.work", bank_num);
+    let mut bank_file_path = path.join(&bank_file_name);
+
+    if !bank_file_path.exists() {
+        // Try .strd extension
+        let bank_file_name = format!("bank{:02}.strd", bank_num);
+        bank_file_path = path.join(&bank_file_name);
+        if !bank_file_path.exists() {
+            return Err(format!("Bank file not found: {}", bank_id).into());
+        }
+    }
+
+    let bank_lock = bank_file_lock(&bank_file_path);
+    let _bank_guard = bank_lock.lock().unwrap_or_else(|e| e.into_inner());
+    crate::project_lock::ensure_unlocked(path)?;
+
+    // Read the existing bank file
+    let mut bank_data = crate::os_compat::read_bank_file(&bank_file_path)
+        .map_err(|e| format!("Failed to read bank file: {}", e))?;
+
+    apply_parts_data(&mut bank_data, &parts_data);
     println!(
         "[DEBUG] parts_edited_bitmask after update: {}",
         bank_data.parts_edited_bitmask
@@ -3298,53 +3760,71 @@ pub fn save_parts_data(
         old_checksum, bank_data.checksum
     );
 
-    // Write the modified bank file back
-    bank_data
-        .to_data_file(&bank_file_path)
-        .map_err(|e| format!("Failed to write bank file: {:?}", e))?;
-    println!("[DEBUG] Bank file written successfully");
-
-    // VERIFICATION: Read the file back and verify the data persisted correctly
-    let verify_bank = BankFile::from_data_file(&bank_file_path)
-        .map_err(|e| format!("Failed to verify bank file: {:?}", e))?;
-    println!(
-        "[DEBUG VERIFY] parts_saved_state after re-read: {:?}",
-        verify_bank.parts_saved_state
-    );
-    println!(
-        "[DEBUG VERIFY] parts_edited_bitmask after re-read: {}",
-        verify_bank.parts_edited_bitmask
-    );
-    println!(
-        "[DEBUG VERIFY] checksum after re-read: {}",
-        verify_bank.checksum
-    );
-    for i in 0..4 {
-        let saved = &verify_bank.parts.saved.0[i];
-        println!(
-            "[DEBUG VERIFY] Part {} saved ATK[0]: {}",
-            i, saved.audio_track_params_values[0].amp.atk
-        );
-    }
+    // Write the modified bank file back and check it reads back as written
+    let touched: Vec<usize> = parts_data
+        .iter()
+        .map(|p| p.part_id as usize)
+        .filter(|&p| p < 4)
+        .collect();
+    crate::write_verify::write_bank_verified(
+        &bank_file_path,
+        &bank_data,
+        crate::write_verify::part_fields(&touched),
+        verify,
+    )
+}
 
-    Ok(())
+/// Write one audio track's FX types, main parameters and setup into a part.
+fn write_track_fx(part: &mut ot_tools_io::parts::Part, track_id: usize, fx: &PartTrackFx) {
+    // FX types
+    part.audio_track_fx1[track_id] = fx.fx1_type;
+    part.audio_track_fx2[track_id] = fx.fx2_type;
+
+    // FX1 main parameters
+    part.audio_track_params_values[track_id].fx1.param_1 = fx.fx1_param1;
+    part.audio_track_params_values[track_id].fx1.param_2 = fx.fx1_param2;
+    part.audio_track_params_values[track_id].fx1.param_3 = fx.fx1_param3;
+    part.audio_track_params_values[track_id].fx1.param_4 = fx.fx1_param4;
+    part.audio_track_params_values[track_id].fx1.param_5 = fx.fx1_param5;
+    part.audio_track_params_values[track_id].fx1.param_6 = fx.fx1_param6;
+
+    // FX2 main parameters
+    part.audio_track_params_values[track_id].fx2.param_1 = fx.fx2_param1;
+    part.audio_track_params_values[track_id].fx2.param_2 = fx.fx2_param2;
+    part.audio_track_params_values[track_id].fx2.param_3 = fx.fx2_param3;
+    part.audio_track_params_values[track_id].fx2.param_4 = fx.fx2_param4;
+    part.audio_track_params_values[track_id].fx2.param_5 = fx.fx2_param5;
+    part.audio_track_params_values[track_id].fx2.param_6 = fx.fx2_param6;
+
+    // FX1 setup parameters
+    part.audio_track_params_setup[track_id].fx1.setting1 = fx.fx1_setup1;
+    part.audio_track_params_setup[track_id].fx1.setting2 = fx.fx1_setup2;
+    part.audio_track_params_setup[track_id].fx1.setting3 = fx.fx1_setup3;
+    part.audio_track_params_setup[track_id].fx1.setting4 = fx.fx1_setup4;
+    part.audio_track_params_setup[track_id].fx1.setting5 = fx.fx1_setup5;
+    part.audio_track_params_setup[track_id].fx1.setting6 = fx.fx1_setup6;
+
+    // FX2 setup parameters
+    part.audio_track_params_setup[track_id].fx2.setting1 = fx.fx2_setup1;
+    part.audio_track_params_setup[track_id].fx2.setting2 = fx.fx2_setup2;
+    part.audio_track_params_setup[track_id].fx2.setting3 = fx.fx2_setup3;
+    part.audio_track_params_setup[track_id].fx2.setting4 = fx.fx2_setup4;
+    part.audio_track_params_setup[track_id].fx2.setting5 = fx.fx2_setup5;
+    part.audio_track_params_setup[track_id].fx2.setting6 = fx.fx2_setup6;
 }
 
 /// Commit a single part: copy parts.unsaved to parts.saved (like Octatrack's "SAVE" command)
 /// This makes the current working state become the "saved" state that can be reloaded to later.
-pub fn commit_part_data(project_path: &str, bank_id: &str, part_id: u8) -> Result<(), String> {
+pub fn commit_part_data(
+    project_path: &str,
+    bank_id: &str,
+    part_id: u8,
+    verify: bool,
+) -> Result<VerificationReport, VerificationFailure> {
     let path = Path::new(project_path);
 
     // Convert bank letter (A-P) to bank number (1-16)
-    let bank_letters = [
-        "A", "B", "C", "D", "E", "F", "G", "H", "I", "J", "K", "L", "M", "N", "O", "P",
-    ];
-
-    let bank_num = bank_letters
-        .iter()
-        .position(|&letter| letter == bank_id)
-        .map(|idx| idx + 1)
-        .ok_or_else(|| format!("Invalid bank ID: {}", bank_id))?;
+    let bank_num = bank_index(bank_id)? + 1;
 
     let bank_file_name = format!("bank{:02}.work", bank_num);
     let mut bank_file_path = path.join(&bank_file_name);
@@ -3353,17 +3833,21 @@ pub fn commit_part_data(project_path: &str, bank_id: &str, part_id: u8) -> Resul
         let bank_file_name = format!("bank{:02}.strd", bank_num);
         bank_file_path = path.join(&bank_file_name);
         if !bank_file_path.exists() {
-            return Err(format!("Bank file not found: {}", bank_id));
+            return Err(format!("Bank file not found: {}", bank_id).into());
         }
     }
 
+    let bank_lock = bank_file_lock(&bank_file_path);
+    let _bank_guard = bank_lock.lock().unwrap_or_else(|e| e.into_inner());
+    crate::project_lock::ensure_unlocked(path)?;
+
     // Read the existing bank file
-    let mut bank_data = BankFile::from_data_file(&bank_file_path)
-        .map_err(|e| format!("Failed to read bank file: {:?}", e))?;
+    let mut bank_data = crate::os_compat::read_bank_file(&bank_file_path)
+        .map_err(|e| format!("Failed to read bank file: {}", e))?;
 
     let part_idx = part_id as usize;
     if part_idx >= 4 {
-        return Err(format!("Invalid part ID: {} (must be 0-3)", part_id));
+        return Err(format!("Invalid part ID: {} (must be 0-3)", part_id).into());
     }
 
     println!(
@@ -3396,28 +3880,27 @@ pub fn commit_part_data(project_path: &str, bank_id: &str, part_id: u8) -> Resul
         .map_err(|e| format!("Failed to calculate checksum: {:?}", e))?;
 
     // Write the modified bank file back
-    bank_data
-        .to_data_file(&bank_file_path)
-        .map_err(|e| format!("Failed to write bank file: {:?}", e))?;
+    let report = crate::write_verify::write_bank_verified(
+        &bank_file_path,
+        &bank_data,
+        crate::write_verify::part_fields(&[part_idx]),
+        verify,
+    )?;
 
     println!("[DEBUG] Part {} committed successfully", part_idx);
 
-    Ok(())
+    Ok(report)
 }
 
 /// Commit all parts: copy all parts.unsaved to parts.saved (like Octatrack's "SAVE ALL" command)
-pub fn commit_all_parts_data(project_path: &str, bank_id: &str) -> Result<(), String> {
+pub fn commit_all_parts_data(
+    project_path: &str,
+    bank_id: &str,
+    verify: bool,
+) -> Result<VerificationReport, VerificationFailure> {
     let path = Path::new(project_path);
 
-    let bank_letters = [
-        "A", "B", "C", "D", "E", "F", "G", "H", "I", "J", "K", "L", "M", "N", "O", "P",
-    ];
-
-    let bank_num = bank_letters
-        .iter()
-        .position(|&letter| letter == bank_id)
-        .map(|idx| idx + 1)
-        .ok_or_else(|| format!("Invalid bank ID: {}", bank_id))?;
+    let bank_num = bank_index(bank_id)? + 1;
 
     let bank_file_name = format!("bank{:02}.work", bank_num);
     let mut bank_file_path = path.join(&bank_file_name);
@@ -3426,12 +3909,16 @@ pub fn commit_all_parts_data(project_path: &str, bank_id: &str) -> Result<(), St
         let bank_file_name = format!("bank{:02}.strd", bank_num);
         bank_file_path = path.join(&bank_file_name);
         if !bank_file_path.exists() {
-            return Err(format!("Bank file not found: {}", bank_id));
+            return Err(format!("Bank file not found: {}", bank_id).into());
         }
     }
 
-    let mut bank_data = BankFile::from_data_file(&bank_file_path)
-        .map_err(|e| format!("Failed to read bank file: {:?}", e))?;
+    let bank_lock = bank_file_lock(&bank_file_path);
+    let _bank_guard = bank_lock.lock().unwrap_or_else(|e| e.into_inner());
+    crate::project_lock::ensure_unlocked(path)?;
+
+    let mut bank_data = crate::os_compat::read_bank_file(&bank_file_path)
+        .map_err(|e| format!("Failed to read bank file: {}", e))?;
 
     println!("[DEBUG] Committing all parts (copying unsaved to saved)");
 
@@ -3457,13 +3944,16 @@ pub fn commit_all_parts_data(project_path: &str, bank_id: &str) -> Result<(), St
         .calculate_checksum()
         .map_err(|e| format!("Failed to calculate checksum: {:?}", e))?;
 
-    bank_data
-        .to_data_file(&bank_file_path)
-        .map_err(|e| format!("Failed to write bank file: {:?}", e))?;
+    let report = crate::write_verify::write_bank_verified(
+        &bank_file_path,
+        &bank_data,
+        crate::write_verify::part_fields(&[0, 1, 2, 3]),
+        verify,
+    )?;
 
     println!("[DEBUG] All parts committed successfully");
 
-    Ok(())
+    Ok(report)
 }
 
 /// Reload a single part: copy parts.saved back to parts.unsaved (like Octatrack's "RELOAD" command)
@@ -3472,18 +3962,11 @@ pub fn reload_part_data(
     project_path: &str,
     bank_id: &str,
     part_id: u8,
-) -> Result<PartData, String> {
+    verify: bool,
+) -> Result<PartData, VerificationFailure> {
     let path = Path::new(project_path);
 
-    let bank_letters = [
-        "A", "B", "C", "D", "E", "F", "G", "H", "I", "J", "K", "L", "M", "N", "O", "P",
-    ];
-
-    let bank_num = bank_letters
-        .iter()
-        .position(|&letter| letter == bank_id)
-        .map(|idx| idx + 1)
-        .ok_or_else(|| format!("Invalid bank ID: {}", bank_id))?;
+    let bank_num = bank_index(bank_id)? + 1;
 
     let bank_file_name = format!("bank{:02}.work", bank_num);
     let mut bank_file_path = path.join(&bank_file_name);
@@ -3492,21 +3975,25 @@ pub fn reload_part_data(
         let bank_file_name = format!("bank{:02}.strd", bank_num);
         bank_file_path = path.join(&bank_file_name);
         if !bank_file_path.exists() {
-            return Err(format!("Bank file not found: {}", bank_id));
+            return Err(format!("Bank file not found: {}", bank_id).into());
         }
     }
 
-    let mut bank_data = BankFile::from_data_file(&bank_file_path)
-        .map_err(|e| format!("Failed to read bank file: {:?}", e))?;
+    let bank_lock = bank_file_lock(&bank_file_path);
+    let _bank_guard = bank_lock.lock().unwrap_or_else(|e| e.into_inner());
+    crate::project_lock::ensure_unlocked(path)?;
+
+    let mut bank_data = crate::os_compat::read_bank_file(&bank_file_path)
+        .map_err(|e| format!("Failed to read bank file: {}", e))?;
 
     let part_idx = part_id as usize;
     if part_idx >= 4 {
-        return Err(format!("Invalid part ID: {} (must be 0-3)", part_id));
+        return Err(format!("Invalid part ID: {} (must be 0-3)", part_id).into());
     }
 
     // Check if this part has valid saved data to reload from
     if bank_data.parts_saved_state[part_idx] != 1 {
-        return Err("SAVE PART FIRST".to_string());
+        return Err("SAVE PART FIRST".to_string().into());
     }
 
     println!(
@@ -3529,9 +4016,12 @@ pub fn reload_part_data(
         .calculate_checksum()
         .map_err(|e| format!("Failed to calculate checksum: {:?}", e))?;
 
-    bank_data
-        .to_data_file(&bank_file_path)
-        .map_err(|e| format!("Failed to write bank file: {:?}", e))?;
+    crate::write_verify::write_bank_verified(
+        &bank_file_path,
+        &bank_data,
+        crate::write_verify::part_fields(&[part_idx]),
+        verify,
+    )?;
 
     println!("[DEBUG] Part {} reloaded successfully", part_idx);
 
@@ -3541,7 +4031,951 @@ pub fn reload_part_data(
         .parts
         .into_iter()
         .find(|p| p.part_id == part_id)
-        .ok_or_else(|| format!("Failed to find reloaded part {}", part_id))
+        .ok_or_else(|| format!("Failed to find reloaded part {}", part_id).into())
+}
+
+/// One byte per character: the OT cannot display anything outside printable ASCII.
+fn check_part_name(name: &str) -> Result<(), String> {
+    match name.chars().find(|c| !(' '..='~').contains(c)) {
+        Some(c) => Err(format!("Character '{}' is not supported in Part names", c)),
+        None => Ok(()),
+    }
+}
+
+/// Store `name` as the name of part `part_id` (0-3) of a bank, truncated to
+/// 7 bytes and padded with NUL bytes.
+pub(crate) fn set_part_name(
+    bank_data: &mut BankFile,
+    part_id: u8,
+    name: &str,
+) -> Result<(), String> {
+    if part_id >= 4 {
+        return Err(format!("Invalid part ID: {} (must be 0-3)", part_id));
+    }
+    check_part_name(name)?;
+    let name_bytes = &mut bank_data.part_names[part_id as usize];
+    name_bytes.fill(0);
+    for (dst, src) in name_bytes.iter_mut().zip(name.bytes()) {
+        *dst = src;
+    }
+    Ok(())
+}
+
+/// Rename a Part. The name is stored as a fixed 7-byte array: longer names are
+/// truncated, shorter ones padded with NUL bytes. An empty name restores the
+/// default "Part N" display.
+pub fn rename_part(
+    project_path: &str,
+    bank_id: &str,
+    part_id: u8,
+    new_name: &str,
+) -> Result<(), String> {
+    let path = Path::new(project_path);
+
+    let bank_num = bank_index(bank_id)? + 1;
+
+    let part_idx = part_id as usize;
+    if part_idx >= 4 {
+        return Err(format!("Invalid part ID: {} (must be 0-3)", part_id));
+    }
+
+    check_part_name(new_name)?;
+
+    let bank_file_name = format!("bank{:02}.work", bank_num);
+    let mut bank_file_path = path.join(&bank_file_name);
+
+    if !bank_file_path.exists() {
+        let bank_file_name = format!("bank{:02}.strd", bank_num);
+        bank_file_path = path.join(&bank_file_name);
+        if !bank_file_path.exists() {
+            return Err(format!("Bank file not found: {}", bank_id));
+        }
+    }
+
+    let bank_lock = bank_file_lock(&bank_file_path);
+    let _bank_guard = bank_lock.lock().unwrap_or_else(|e| e.into_inner());
+    crate::project_lock::ensure_unlocked(path)?;
+
+    let mut bank_data = crate::os_compat::read_bank_file(&bank_file_path)
+        .map_err(|e| format!("Failed to read bank file: {}", e))?;
+
+    set_part_name(&mut bank_data, part_id, new_name)?;
+
+    bank_data.checksum = bank_data
+        .calculate_checksum()
+        .map_err(|e| format!("Failed to calculate checksum: {:?}", e))?;
+
+    crate::write_backup::backup_before_write(&bank_file_path)?;
+    crate::atomic_write::write_data_file(&bank_data, &bank_file_path)
+        .map_err(|e| format!("Failed to write bank file: {}", e))?;
+
+    Ok(())
+}
+
+/// Reset a single part's working copy (parts.unsaved): machines, amps, LFOs, FX,
+/// recorder and MIDI setups go back to the device defaults. Like any other
+/// part edit, parts.saved is kept so "Reload Part" restores the previous state,
+/// and the part is flagged as edited. Returns the reset part.
+pub fn init_part(project_path: &str, bank_id: &str, part_id: u8) -> Result<PartData, String> {
+    let bank_index = bank_index(bank_id)?;
+    let part_idx = part_id as usize;
+    if part_idx >= 4 {
+        return Err(format!("Invalid part ID: {} (must be 0-3)", part_id));
+    }
+
+    edit_bank_file(project_path, bank_index, |bank| {
+        bank.parts.unsaved.0[part_idx] = BankFile::default().parts.unsaved.0[part_idx];
+        bank.parts_edited_bitmask |= 1 << part_idx;
+        Ok(())
+    })?;
+
+    read_parts_data(project_path, bank_id)?
+        .parts
+        .into_iter()
+        .find(|p| p.part_id == part_id)
+        .ok_or_else(|| format!("Failed to find part {}", part_id))
+}
+
+/// Copy one track's setup to other tracks of the same or another part of the
+/// bank: machine, amp, LFO and FX setup for an audio track (0-7), or note,
+/// arp, CTRL and LFO setup for a MIDI track (8-15). Only the working copy
+/// (parts.unsaved) is edited and the destination part is flagged as edited.
+/// Track volumes and recorder setups are left alone.
+pub fn copy_track_setup(
+    project_path: &str,
+    bank_id: &str,
+    source_part: u8,
+    source_track: u8,
+    dest_part: u8,
+    dest_tracks: &[u8],
+) -> Result<(), String> {
+    let bank_index = bank_index(bank_id)?;
+    if source_part > 3 || dest_part > 3 {
+        return Err("Part index must be between 0 and 3".to_string());
+    }
+    if source_track > 15 || dest_tracks.iter().any(|&t| t > 15) {
+        return Err("Track indices must be between 0 and 15".to_string());
+    }
+    if dest_tracks.is_empty() {
+        return Err("No destination tracks selected".to_string());
+    }
+    let is_audio = source_track < 8;
+    if dest_tracks.iter().any(|&t| (t < 8) != is_audio) {
+        return Err(
+            "Cannot mix audio tracks (0-7) and MIDI tracks (8-15) in copy operation".to_string(),
+        );
+    }
+
+    edit_bank_file(project_path, bank_index, |bank| {
+        let src = bank.parts.unsaved.0[source_part as usize];
+        let dst = &mut bank.parts.unsaved.0[dest_part as usize];
+        for &dest_track in dest_tracks {
+            if is_audio {
+                let (s, d) = (source_track as usize, dest_track as usize);
+                dst.audio_track_machine_types[d] = src.audio_track_machine_types[s];
+                dst.audio_track_machine_params[d] = src.audio_track_machine_params[s];
+                dst.audio_track_machine_setup[d] = src.audio_track_machine_setup[s];
+                dst.audio_track_machine_slots[d] = src.audio_track_machine_slots[s];
+                dst.audio_track_params_values[d] = src.audio_track_params_values[s];
+                dst.audio_track_params_setup[d] = src.audio_track_params_setup[s];
+                dst.audio_track_fx1[d] = src.audio_track_fx1[s];
+                dst.audio_track_fx2[d] = src.audio_track_fx2[s];
+                dst.audio_tracks_custom_lfo_designs[d] = src.audio_tracks_custom_lfo_designs[s];
+                dst.audio_tracks_custom_lfos_interpolation_masks[d] =
+                    src.audio_tracks_custom_lfos_interpolation_masks[s];
+            } else {
+                let (s, d) = ((source_track - 8) as usize, (dest_track - 8) as usize);
+                dst.midi_track_params_values[d] = src.midi_track_params_values[s];
+                dst.midi_track_params_setup[d] = src.midi_track_params_setup[s];
+                dst.midi_tracks_custom_lfos[d] = src.midi_tracks_custom_lfos[s];
+                dst.midi_tracks_custom_lfos_interpolation_masks[d] =
+                    src.midi_tracks_custom_lfos_interpolation_masks[s];
+                dst.midi_tracks_arp_seqs[d] = src.midi_tracks_arp_seqs[s];
+                dst.midi_tracks_arp_mute_masks[d * 2] = src.midi_tracks_arp_mute_masks[s * 2];
+                dst.midi_tracks_arp_mute_masks[d * 2 + 1] =
+                    src.midi_tracks_arp_mute_masks[s * 2 + 1];
+            }
+        }
+        bank.parts_edited_bitmask |= 1 << dest_part;
+        Ok(())
+    })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FxPresetTarget {
+    pub bank_id: String, // "A".."P"
+    pub part_id: u8,     // 0-3
+    pub track_id: u8,    // 0-7
+}
+
+/// Apply one FX configuration (types, main parameters and setup) to many
+/// audio tracks across parts and banks. The `track_id` of `fx` is ignored.
+/// Every bank is read and written once; edited parts are flagged like any
+/// other part edit. Returns the number of tracks written.
+pub fn apply_fx_preset(
+    project_path: &str,
+    fx: &PartTrackFx,
+    targets: &[FxPresetTarget],
+) -> Result<u32, String> {
+    let mut by_bank: std::collections::BTreeMap<u8, Vec<(usize, usize)>> =
+        std::collections::BTreeMap::new();
+    for target in targets {
+        let bank_index = bank_index(&target.bank_id)?;
+        if target.part_id > 3 {
+            return Err(format!("Invalid part ID: {} (must be 0-3)", target.part_id));
+        }
+        if target.track_id > 7 {
+            return Err(format!(
+                "Invalid track ID: {} (FX presets apply to audio tracks 0-7)",
+                target.track_id
+            ));
+        }
+        by_bank
+            .entry(bank_index)
+            .or_default()
+            .push((target.part_id as usize, target.track_id as usize));
+    }
+
+    let mut written = 0u32;
+    for (bank_index, tracks) in by_bank {
+        edit_bank_file(project_path, bank_index, |bank| {
+            for &(part_id, track_id) in &tracks {
+                write_track_fx(&mut bank.parts.unsaved.0[part_id], track_id, fx);
+                bank.parts_edited_bitmask |= 1 << part_id;
+            }
+            Ok(())
+        })?;
+        written += tracks.len() as u32;
+    }
+    Ok(written)
+}
+
+// ============================================================================
+// Pattern Timing Tools
+// ============================================================================
+
+/// Inverse of [`decode_trig_masks`]: pack 64 steps into the 8-byte mask layout.
+pub(crate) fn encode_trig_masks(steps: &[bool; 64]) -> [u8; 8] {
+    let mut masks = [0u8; 8];
+    for (byte_idx, mask) in masks.iter_mut().enumerate() {
+        let step_offset = BYTE_TO_STEP_OFFSET[byte_idx];
+        for bit_pos in 0..8 {
+            if steps[step_offset + bit_pos] {
+                *mask |= 1 << bit_pos;
+            }
+        }
+    }
+    masks
+}
+
+/// A structural edit applied to every per-step array of a track.
+#[derive(Debug, Clone, Copy)]
+enum StepEdit {
+    /// Rotate the first `len` steps right by `steps` (negative = left).
+    Shift { len: usize, steps: i32 },
+    /// Copy steps `0..len` onto `len..2*len`.
+    Double { len: usize },
+    /// Play the first `len` steps backwards.
+    Reverse { len: usize },
+    /// Reflect the first half onto the second half (palindrome).
+    Mirror { len: usize },
+    /// Clear every other step (2nd, 4th, ...).
+    Thin { len: usize },
+}
+
+fn apply_step_edit<T: Clone + Default>(steps: &mut [T], edit: StepEdit) {
+    match edit {
+        StepEdit::Shift { len, steps: n } => {
+            steps[..len].rotate_right(n.rem_euclid(len as i32) as usize);
+        }
+        StepEdit::Double { len } => {
+            for i in 0..len {
+                steps[len + i] = steps[i].clone();
+            }
+        }
+        StepEdit::Reverse { len } => steps[..len].reverse(),
+        StepEdit::Mirror { len } => {
+            for i in 0..len / 2 {
+                steps[len - 1 - i] = steps[i].clone();
+            }
+        }
+        StepEdit::Thin { len } => {
+            for step in steps[..len].iter_mut().skip(1).step_by(2) {
+                *step = T::default();
+            }
+        }
+    }
+}
+
+/// Apply `edit` to a track's trig masks (8-byte groups), p-locks and
+/// offset/repeat/condition bytes, so every per-step attribute moves together.
+fn edit_track_steps<P: Clone + Default>(
+    masks: &mut [&mut [u8]],
+    plocks: &mut [P],
+    offsets_repeats_conditions: &mut [[u8; 2]],
+    edit: StepEdit,
+) {
+    for mask in masks.iter_mut() {
+        for group in mask.chunks_mut(8) {
+            let mut steps = decode_trig_masks(group);
+            apply_step_edit(&mut steps, edit);
+            group.copy_from_slice(&encode_trig_masks(&steps));
+        }
+    }
+    apply_step_edit(plocks, edit);
+    apply_step_edit(offsets_repeats_conditions, edit);
+}
+
+/// Apply `edit` to track `track_index` (0-7 audio, 8-15 MIDI) of `pattern`.
+fn edit_pattern_track(
+    pattern: &mut ot_tools_io::patterns::Pattern,
+    track_index: usize,
+    edit: StepEdit,
+) {
+    if track_index < 8 {
+        let track = &mut pattern.audio_track_trigs.0[track_index];
+        let m = &mut track.trig_masks;
+        edit_track_steps(
+            &mut [
+                &mut m.trigger[..],
+                &mut m.trigless[..],
+                &mut m.plock[..],
+                &mut m.oneshot[..],
+                &mut m.recorder[..],
+                &mut m.swing[..],
+                &mut m.slide[..],
+            ],
+            &mut track.plocks.0[..],
+            &mut track.trig_offsets_repeats_conditions[..],
+            edit,
+        );
+    } else {
+        let track = &mut pattern.midi_track_trigs.0[track_index - 8];
+        let m = &mut track.trig_masks;
+        edit_track_steps(
+            &mut [
+                &mut m.trigger[..],
+                &mut m.trigless[..],
+                &mut m.plock[..],
+                &mut m.swing[..],
+            ],
+            &mut track.plocks.0[..],
+            &mut track.trig_offsets_repeats_conditions[..],
+            edit,
+        );
+    }
+}
+
+/// The file holding bank `bank_index` (0-15) of a project: `.work` first, then
+/// `.strd`.
+pub(crate) fn bank_file_path(project_path: &Path, bank_index: u8) -> Result<PathBuf, String> {
+    [
+        format!("bank{:02}.work", bank_index + 1),
+        format!("bank{:02}.strd", bank_index + 1),
+    ]
+    .into_iter()
+    .map(|name| project_path.join(name))
+    .find(|p| p.exists())
+    .ok_or_else(|| format!("Bank file not found: {}", BANK_LETTERS[bank_index as usize]))
+}
+
+/// Read bank `bank_index` (0-15) of a project under its file lock, let `f`
+/// modify it, then write it back with a fresh checksum.
+pub(crate) fn edit_bank_file<T>(
+    project_path: &str,
+    bank_index: u8,
+    f: impl FnOnce(&mut BankFile) -> Result<T, String>,
+) -> Result<T, String> {
+    if bank_index > 15 {
+        return Err(format!("Invalid bank index: {} (must be 0-15)", bank_index));
+    }
+    let path = Path::new(project_path);
+    let bank_file_path = bank_file_path(path, bank_index)?;
+
+    let bank_lock = bank_file_lock(&bank_file_path);
+    let _bank_guard = bank_lock.lock().unwrap_or_else(|e| e.into_inner());
+
+    crate::os_compat::ensure_bank_writable(&bank_file_path)?;
+    crate::project_lock::ensure_unlocked(path)?;
+    let mut bank_data = crate::os_compat::read_bank_file(&bank_file_path)
+        .map_err(|e| format!("Failed to read bank file: {}", e))?;
+    let result = f(&mut bank_data)?;
+
+    bank_data.checksum = bank_data
+        .calculate_checksum()
+        .map_err(|e| format!("Failed to calculate checksum: {:?}", e))?;
+    crate::write_backup::backup_before_write(&bank_file_path)?;
+    crate::atomic_write::write_data_file(&bank_data, &bank_file_path)
+        .map_err(|e| format!("Failed to write bank file: {}", e))?;
+    Ok(result)
+}
+
+/// Number of steps of a track: its own length in per-track scale mode, the
+/// pattern length otherwise.
+fn track_length(pattern: &ot_tools_io::patterns::Pattern, track_index: usize) -> usize {
+    let len = if pattern.scale.scale_mode == 1 {
+        if track_index < 8 {
+            pattern.audio_track_trigs.0[track_index]
+                .scale_per_track_mode
+                .per_track_len as usize
+        } else {
+            pattern.midi_track_trigs.0[track_index - 8]
+                .scale_per_track_mode
+                .per_track_len as usize
+        }
+    } else {
+        pattern.scale.master_len as usize
+    };
+    len.clamp(1, 64)
+}
+
+/// Pattern tempo bytes as stored when no pattern tempo was set (120 BPM).
+pub(crate) const DEFAULT_PATTERN_TEMPO: (u8, u8) = (11, 64);
+
+/// Pattern tempo is a 16-bit value in 1/24 BPM: tempo_1 * 256 + tempo_2.
+pub(crate) fn decode_pattern_tempo(tempo_1: u8, tempo_2: u8) -> f32 {
+    (tempo_1 as u16 * 256 + tempo_2 as u16) as f32 / 24.0
+}
+
+/// Inverse of [`decode_pattern_tempo`], rounded to the nearest 1/24 BPM.
+pub(crate) fn encode_pattern_tempo(bpm: f32) -> Result<(u8, u8), String> {
+    if !(30.0..=300.0).contains(&bpm) {
+        return Err(format!("Tempo out of range (30-300): {}", bpm));
+    }
+    let value = (bpm * 24.0).round() as u16;
+    Ok(((value / 256) as u8, (value % 256) as u8))
+}
+
+fn check_pattern_index(pattern_index: u8) -> Result<usize, String> {
+    if pattern_index > 15 {
+        return Err(format!(
+            "Invalid pattern index: {} (must be 0-15)",
+            pattern_index
+        ));
+    }
+    Ok(pattern_index as usize)
+}
+
+/// Shift every trig of one track by `steps` (negative = earlier), wrapping
+/// around the track length. P-locks, conditions and micro-timing move with
+/// their trigs. In per-track scale mode the track's own length is used.
+pub fn shift_track_trigs(
+    project_path: &str,
+    bank_index: u8,
+    pattern_index: u8,
+    track_index: u8,
+    steps: i32,
+) -> Result<(), String> {
+    let pattern_idx = check_pattern_index(pattern_index)?;
+    if track_index > 15 {
+        return Err(format!(
+            "Invalid track index: {} (must be 0-15)",
+            track_index
+        ));
+    }
+    let track_idx = track_index as usize;
+
+    edit_bank_file(project_path, bank_index, |bank| {
+        let pattern = &mut bank.patterns.0[pattern_idx];
+        let len = track_length(pattern, track_idx);
+        edit_pattern_track(pattern, track_idx, StepEdit::Shift { len, steps });
+        Ok(())
+    })
+}
+
+/// Set the tempo of one pattern, or clear it with `None` (the pattern then
+/// stores the device default and follows the project tempo). Pattern tempos
+/// only play while PATTERN_TEMPO_ENABLED is on, see [`set_project_tempo`].
+pub fn set_pattern_tempo(
+    project_path: &str,
+    bank_index: u8,
+    pattern_index: u8,
+    bpm: Option<f32>,
+) -> Result<(), String> {
+    let pattern_idx = check_pattern_index(pattern_index)?;
+    let (tempo_1, tempo_2) = match bpm {
+        Some(bpm) => encode_pattern_tempo(bpm)?,
+        None => DEFAULT_PATTERN_TEMPO,
+    };
+    edit_bank_file(project_path, bank_index, |bank| {
+        let pattern = &mut bank.patterns.0[pattern_idx];
+        pattern.tempo_1 = tempo_1;
+        pattern.tempo_2 = tempo_2;
+        Ok(())
+    })
+}
+
+/// Assign patterns to a part (0-3) and/or set their chain-after behaviour:
+/// "Project" follows the project setting, "Pattern" chains after the pattern
+/// length. `None` leaves that setting unchanged.
+pub fn set_pattern_assignment(
+    project_path: &str,
+    bank_index: u8,
+    pattern_indices: &[u8],
+    part_assignment: Option<u8>,
+    chain_mode: Option<&str>,
+) -> Result<(), String> {
+    let pattern_idxs = pattern_indices
+        .iter()
+        .map(|&p| check_pattern_index(p))
+        .collect::<Result<Vec<_>, _>>()?;
+    if pattern_idxs.is_empty() {
+        return Err("No patterns selected".to_string());
+    }
+    if part_assignment.is_some_and(|p| p > 3) {
+        return Err(format!(
+            "Invalid part: {} (must be 0-3)",
+            part_assignment.unwrap_or_default()
+        ));
+    }
+    let use_project_setting = match chain_mode {
+        None => None,
+        Some("Project") => Some(1),
+        Some("Pattern") => Some(0),
+        Some(other) => return Err(format!("Unknown chain mode: {}", other)),
+    };
+
+    edit_bank_file(project_path, bank_index, |bank| {
+        for idx in pattern_idxs {
+            let pattern = &mut bank.patterns.0[idx];
+            if let Some(part) = part_assignment {
+                pattern.part_assignment = part;
+            }
+            if let Some(flag) = use_project_setting {
+                pattern.chain_behaviour.use_project_setting = flag;
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Set the project master tempo (TEMPOx24, fractional BPM kept to 1/24) and,
+/// when given, whether patterns use their own tempo. Edited surgically like
+/// the memory settings.
+pub fn set_project_tempo(
+    project_path: &str,
+    bpm: f32,
+    pattern_tempo_enabled: Option<bool>,
+) -> Result<(), String> {
+    if !(30.0..=300.0).contains(&bpm) {
+        return Err(format!("Tempo out of range (30-300): {}", bpm));
+    }
+    let path = Path::new(project_path);
+    let project_file_path = if path.join("project.work").exists() {
+        path.join("project.work")
+    } else if path.join("project.strd").exists() {
+        path.join("project.strd")
+    } else {
+        return Err("Project file not found".to_string());
+    };
+
+    let mut updates = vec![("TEMPOx24", ((bpm * 24.0).round() as u32).to_string())];
+    if let Some(enabled) = pattern_tempo_enabled {
+        updates.push(("PATTERN_TEMPO_ENABLED", (enabled as u8).to_string()));
+    }
+    replace_settings_fields_surgical(&project_file_path, &updates)
+}
+
+/// Transform the trigs of one track, or of every track with `None`, within
+/// each track's length. `op`: "reverse", "mirror" (first half reflected onto
+/// the second), "rotate_left", "rotate_right" (one step, wrapping) or "thin"
+/// (clear every other step). Per-step data moves with the trigs.
+pub fn transform_pattern(
+    project_path: &str,
+    bank_index: u8,
+    pattern_index: u8,
+    track_index: Option<u8>,
+    op: &str,
+) -> Result<(), String> {
+    let pattern_idx = check_pattern_index(pattern_index)?;
+    if track_index.is_some_and(|t| t > 15) {
+        return Err("Track index must be between 0 and 15".to_string());
+    }
+    let make_edit: fn(usize) -> StepEdit = match op {
+        "reverse" => |len| StepEdit::Reverse { len },
+        "mirror" => |len| StepEdit::Mirror { len },
+        "rotate_left" => |len| StepEdit::Shift { len, steps: -1 },
+        "rotate_right" => |len| StepEdit::Shift { len, steps: 1 },
+        "thin" => |len| StepEdit::Thin { len },
+        _ => {
+            return Err(format!(
+            "Invalid op: {}. Must be 'reverse', 'mirror', 'rotate_left', 'rotate_right' or 'thin'",
+            op
+        ))
+        }
+    };
+
+    edit_bank_file(project_path, bank_index, |bank| {
+        let pattern = &mut bank.patterns.0[pattern_idx];
+        for track_idx in 0..16usize {
+            if track_index.is_some_and(|t| t as usize != track_idx) {
+                continue;
+            }
+            let len = track_length(pattern, track_idx);
+            edit_pattern_track(pattern, track_idx, make_edit(len));
+        }
+        Ok(())
+    })
+}
+
+/// Double (`double = true`) or halve a pattern's length. Doubling copies the
+/// existing steps into the new second half; halving only shortens the pattern
+/// (the OT keeps steps beyond the length, hidden). Normal scale mode only.
+pub fn resize_pattern(
+    project_path: &str,
+    bank_index: u8,
+    pattern_index: u8,
+    double: bool,
+) -> Result<u16, String> {
+    let pattern_idx = check_pattern_index(pattern_index)?;
+
+    edit_bank_file(project_path, bank_index, |bank| {
+        let pattern = &mut bank.patterns.0[pattern_idx];
+        if pattern.scale.scale_mode == 1 {
+            return Err("Double/halve is not supported in per-track scale mode".to_string());
+        }
+        let len = (pattern.scale.master_len as usize).clamp(1, 64);
+        let new_len = if double {
+            if len * 2 > 64 {
+                return Err(format!("Pattern is {} steps: doubling exceeds 64", len));
+            }
+            for track_idx in 0..16 {
+                edit_pattern_track(pattern, track_idx, StepEdit::Double { len });
+            }
+            len * 2
+        } else {
+            if len < 2 {
+                return Err("Pattern is too short to halve".to_string());
+            }
+            len / 2
+        };
+        pattern.scale.master_len = new_len as _;
+        Ok(new_len as u16)
+    })
+}
+
+/// Clear one pattern. `scope`: "trigs" removes every trig with its locks,
+/// conditions and micro-timing; "plocks" strips parameter locks and keeps the
+/// trigs; "full" resets the whole pattern (length, scale, tempo, part) to the
+/// device default.
+pub fn clear_pattern(
+    project_path: &str,
+    bank_index: u8,
+    pattern_index: u8,
+    scope: &str,
+) -> Result<(), String> {
+    let pattern_idx = check_pattern_index(pattern_index)?;
+    if !["trigs", "plocks", "full"].contains(&scope) {
+        return Err(format!(
+            "Invalid scope: {}. Must be 'trigs', 'plocks' or 'full'",
+            scope
+        ));
+    }
+
+    edit_bank_file(project_path, bank_index, |bank| {
+        let pattern = &mut bank.patterns.0[pattern_idx];
+        if scope == "full" {
+            *pattern = BankFile::default().patterns.0[pattern_idx].clone();
+            return Ok(());
+        }
+        let clear_trigs = scope == "trigs";
+        for track in pattern.audio_track_trigs.0.iter_mut() {
+            let m = &mut track.trig_masks;
+            if clear_trigs {
+                for mask in [
+                    &mut m.trigger[..],
+                    &mut m.trigless[..],
+                    &mut m.oneshot[..],
+                    &mut m.recorder[..],
+                    &mut m.swing[..],
+                    &mut m.slide[..],
+                ] {
+                    mask.fill(0);
+                }
+                track.trig_offsets_repeats_conditions.fill([0, 0]);
+            }
+            m.plock.fill(0);
+            track.plocks.0.fill_with(Default::default);
+        }
+        for track in pattern.midi_track_trigs.0.iter_mut() {
+            let m = &mut track.trig_masks;
+            if clear_trigs {
+                for mask in [&mut m.trigger[..], &mut m.trigless[..], &mut m.swing[..]] {
+                    mask.fill(0);
+                }
+                track.trig_offsets_repeats_conditions.fill([0, 0]);
+            }
+            m.plock.fill(0);
+            track.plocks.0.fill_with(Default::default);
+        }
+        Ok(())
+    })
+}
+
+/// Reset a whole bank (16 patterns, 4 parts and their saved copies, part
+/// names) to the device's blank bank. The current bank file is always copied
+/// to `backups/` first, whatever the `write_backup_keep` setting; the reset
+/// is refused if that fails. A missing bank is created blank. Returns the
+/// backup summary.
+pub fn init_bank(project_path: &str, bank_index: u8) -> Result<String, String> {
+    if bank_index > 15 {
+        return Err(format!("Invalid bank index: {} (must be 0-15)", bank_index));
+    }
+    let work = format!("bank{:02}.work", bank_index + 1);
+    let strd = format!("bank{:02}.strd", bank_index + 1);
+    let path = Path::new(project_path);
+    let existing = bank_file_path(path, bank_index).ok();
+    let target = existing.clone().unwrap_or_else(|| path.join(&work));
+
+    let bank_lock = bank_file_lock(&target);
+    let _bank_guard = bank_lock.lock().unwrap_or_else(|e| e.into_inner());
+    if existing.is_some() {
+        crate::os_compat::ensure_bank_writable(&target)?;
+    }
+    crate::project_lock::ensure_unlocked(path)?;
+
+    let summary = match existing {
+        Some(_) => crate::backup_project_files_impl(project_path, &[work, strd], "init_bank")?,
+        None => format!("Created blank {}; there was no bank file to back up", work),
+    };
+
+    let mut bank_data = BankFile::default();
+    bank_data.checksum = bank_data
+        .calculate_checksum()
+        .map_err(|e| format!("Failed to calculate checksum: {:?}", e))?;
+    crate::write_backup::backup_before_write(&target)?;
+    crate::atomic_write::write_data_file(&bank_data, &target)
+        .map_err(|e| format!("Failed to write bank file: {}", e))?;
+    Ok(summary)
+}
+
+/// Set the micro-timing of every trig to zero, in one track or (with `None`)
+/// the whole pattern. Repeats and conditions sharing the same bytes are kept.
+/// Returns the number of steps that had an offset.
+pub fn quantize_micro_timing(
+    project_path: &str,
+    bank_index: u8,
+    pattern_index: u8,
+    track_index: Option<u8>,
+) -> Result<u32, String> {
+    let pattern_idx = check_pattern_index(pattern_index)?;
+    if track_index.is_some_and(|t| t > 15) {
+        return Err("Track index must be between 0 and 15".to_string());
+    }
+
+    edit_bank_file(project_path, bank_index, |bank| {
+        let pattern = &mut bank.patterns.0[pattern_idx];
+        let mut changed = 0;
+        // Micro-timing = low 5 bits of byte 0 + top bit of byte 1
+        // (byte 0's top 3 bits are repeats, byte 1's low 7 bits the condition).
+        let mut clear = |bytes: &mut [[u8; 2]]| {
+            for b in bytes.iter_mut() {
+                if b[0] & 0x1F != 0 || b[1] & 0x80 != 0 {
+                    b[0] &= 0xE0;
+                    b[1] &= 0x7F;
+                    changed += 1;
+                }
+            }
+        };
+        for track_idx in 0..16usize {
+            if track_index.is_some_and(|t| t as usize != track_idx) {
+                continue;
+            }
+            if track_idx < 8 {
+                clear(
+                    &mut pattern.audio_track_trigs.0[track_idx].trig_offsets_repeats_conditions[..],
+                );
+            } else {
+                clear(
+                    &mut pattern.midi_track_trigs.0[track_idx - 8].trig_offsets_repeats_conditions
+                        [..],
+                );
+            }
+        }
+        Ok(changed)
+    })
+}
+
+/// Edit a track's groove and polymetric settings in one pattern: swing amount
+/// (0-30, shown as 50-80 on the device) and, in per-track scale mode, the
+/// track's length (1-64) and scale ("2x" ... "1/8x"). `None` keeps the current
+/// value. Swing only affects steps carrying a swing trig.
+pub fn set_track_groove(
+    project_path: &str,
+    bank_index: u8,
+    pattern_index: u8,
+    track_index: u8,
+    swing_amount: Option<u8>,
+    per_track_len: Option<u8>,
+    per_track_scale: Option<&str>,
+) -> Result<(), String> {
+    let pattern_idx = check_pattern_index(pattern_index)?;
+    let track_idx = track_index as usize;
+    if track_idx > 15 {
+        return Err("Track index must be between 0 and 15".to_string());
+    }
+    if swing_amount.is_some_and(|s| s > 30) {
+        return Err("Swing amount must be between 0 and 30 (50-80 on the device)".to_string());
+    }
+    if per_track_len.is_some_and(|len| !(1..=64).contains(&len)) {
+        return Err("Track length must be between 1 and 64".to_string());
+    }
+    let scale_code = match per_track_scale {
+        Some(scale) => Some(
+            PATTERN_SCALES
+                .iter()
+                .position(|&s| s == scale)
+                .ok_or_else(|| format!("Unknown scale: {}", scale))? as u8,
+        ),
+        None => None,
+    };
+
+    edit_bank_file(project_path, bank_index, |bank| {
+        let pattern = &mut bank.patterns.0[pattern_idx];
+        if (per_track_len.is_some() || scale_code.is_some()) && pattern.scale.scale_mode != 1 {
+            return Err(
+                "Track length and scale can only be set in per-track scale mode".to_string(),
+            );
+        }
+        // Audio and MIDI tracks store these the same way in different types.
+        let (swing, len, scale) = if track_idx < 8 {
+            let track = &mut pattern.audio_track_trigs.0[track_idx];
+            let mode = &mut track.scale_per_track_mode;
+            (
+                &mut track.swing_amount,
+                &mut mode.per_track_len,
+                &mut mode.per_track_scale,
+            )
+        } else {
+            let track = &mut pattern.midi_track_trigs.0[track_idx - 8];
+            let mode = &mut track.scale_per_track_mode;
+            (
+                &mut track.swing_amount,
+                &mut mode.per_track_len,
+                &mut mode.per_track_scale,
+            )
+        };
+        if let Some(amount) = swing_amount {
+            *swing = amount;
+        }
+        if let Some(new_len) = per_track_len {
+            *len = new_len;
+        }
+        if let Some(code) = scale_code {
+            *scale = code;
+        }
+        Ok(())
+    })
+}
+
+/// Percent of a probability trig condition code (9-29), from its display
+/// name ("33%").
+fn condition_probability(condition: u8) -> Option<u8> {
+    if !(9..=29).contains(&condition) {
+        return None;
+    }
+    decode_trig_condition(condition)?
+        .strip_suffix('%')?
+        .parse()
+        .ok()
+}
+
+/// Condition code of the closest probability the OT offers, or 0 (no
+/// condition) for 100% and above.
+fn probability_condition(percent: f32) -> u8 {
+    if percent >= 99.5 {
+        return 0;
+    }
+    (9..=29)
+        .filter_map(|code| Some((code, condition_probability(code)?)))
+        .min_by(|a, b| {
+            (a.1 as f32 - percent)
+                .abs()
+                .total_cmp(&(b.1 as f32 - percent).abs())
+        })
+        .map(|(code, _)| code)
+        .unwrap_or(0)
+}
+
+/// Set or scale the probability condition of the trigs on `steps` (0-based,
+/// `None` = whole track length) of `track_indices` (0-15, empty = all tracks).
+/// `mode` "set": every trig gets `percent`. `mode` "scale": each trig's
+/// probability (100% without condition) is multiplied by `percent` / 100.
+/// Trigs carrying another condition (Fill, Pre, A:B, ...) are left alone.
+/// Returns the number of trigs changed.
+pub fn set_trig_probability(
+    project_path: &str,
+    bank_index: u8,
+    pattern_index: u8,
+    track_indices: &[u8],
+    steps: Option<&[u8]>,
+    mode: &str,
+    percent: f32,
+) -> Result<u32, String> {
+    let pattern_idx = check_pattern_index(pattern_index)?;
+    if track_indices.iter().any(|&t| t > 15) {
+        return Err("Track index must be between 0 and 15".to_string());
+    }
+    if steps.is_some_and(|s| s.iter().any(|&step| step > 63)) {
+        return Err("Step index must be between 0 and 63".to_string());
+    }
+    if mode != "set" && mode != "scale" {
+        return Err(format!("Invalid mode: {}. Must be 'set' or 'scale'", mode));
+    }
+    if !(0.0..=1000.0).contains(&percent) {
+        return Err(format!("Invalid percentage: {}", percent));
+    }
+    let new_probability = |current: u8| -> f32 {
+        match mode {
+            "scale" => current as f32 * percent / 100.0,
+            _ => percent,
+        }
+    };
+
+    edit_bank_file(project_path, bank_index, |bank| {
+        let pattern = &mut bank.patterns.0[pattern_idx];
+        let mut changed = 0;
+        for track_idx in 0..16usize {
+            if !track_indices.is_empty() && !track_indices.contains(&(track_idx as u8)) {
+                continue;
+            }
+            let len = track_length(pattern, track_idx);
+            let (trigger, bytes) = if track_idx < 8 {
+                let track = &mut pattern.audio_track_trigs.0[track_idx];
+                (
+                    decode_trig_masks(&track.trig_masks.trigger),
+                    &mut track.trig_offsets_repeats_conditions,
+                )
+            } else {
+                let track = &mut pattern.midi_track_trigs.0[track_idx - 8];
+                (
+                    decode_trig_masks(&track.trig_masks.trigger),
+                    &mut track.trig_offsets_repeats_conditions,
+                )
+            };
+            for step in 0..len {
+                if !trigger[step] || steps.is_some_and(|s| !s.contains(&(step as u8))) {
+                    continue;
+                }
+                // Condition = low 7 bits of byte 1 (top bit is micro-timing)
+                let condition = bytes[step][1] & 0x7F;
+                let current = if condition == 0 {
+                    100
+                } else {
+                    match condition_probability(condition) {
+                        Some(p) => p,
+                        None => continue,
+                    }
+                };
+                let new_condition = probability_condition(new_probability(current));
+                if new_condition != condition {
+                    bytes[step][1] = (bytes[step][1] & 0x80) | new_condition;
+                    changed += 1;
+                }
+            }
+        }
+        Ok(changed)
+    })
 }
 
 // ============================================================================
@@ -4151,10 +5585,12 @@ pub fn search_directory(
 /// Read raw field values from `[SAMPLE]` blocks in a project.work file.
 /// Returns a map of (TYPE, SLOT) → (field_name_upper → raw_value_string).
 /// This bypasses ot-tools-io parsing to preserve original values like TRIGQUANTIZATION=-1.
-type RawSampleFieldsMap =
+pub(crate) type RawSampleFieldsMap =
     std::collections::HashMap<(String, u16), std::collections::HashMap<String, String>>;
 
-fn read_raw_sample_fields(project_file_path: &Path) -> Result<RawSampleFieldsMap, String> {
+pub(crate) fn read_raw_sample_fields(
+    project_file_path: &Path,
+) -> Result<RawSampleFieldsMap, String> {
     let raw_bytes = std::fs::read(project_file_path)
         .map_err(|e| format!("Failed to read project file: {}", e))?;
     let (decoded, _, _) = encoding_rs::WINDOWS_1258.decode(&raw_bytes);
@@ -4223,6 +5659,10 @@ fn replace_sample_fields_surgical(
         .map_err(|e| format!("Failed to read project file: {}", e))?;
     let (decoded, _, _) = encoding_rs::WINDOWS_1258.decode(&raw_bytes);
     let content = decoded.into_owned();
+    crate::os_compat::ensure_project_writable(&content)?;
+    if let Some(project_dir) = project_file_path.parent() {
+        crate::project_lock::ensure_unlocked(project_dir)?;
+    }
 
     // Phase 1: Extract all [SAMPLE] blocks and the non-sample parts of the file
     let pre_samples; // Everything before first [SAMPLE]
@@ -4255,75 +5695,181 @@ fn replace_sample_fields_surgical(
             .unwrap_or_default();
         let slot_id = block
             .lines()
-            .find(|l| l.starts_with("SLOT="))
-            .and_then(|l| l.trim_end_matches('\r')[5..].parse::<u16>().ok())
+            .find(|l| l.starts_with("SLOT="))
+            .and_then(|l| l.trim_end_matches('\r')[5..].parse::<u16>().ok())
+            .unwrap_or(0);
+
+        let key = (slot_type.clone(), slot_id);
+
+        // Apply patches if needed
+        let final_block = if let Some(updates) = field_updates.get(&key) {
+            applied.insert(key.clone());
+            patch_sample_block_fields(block, updates)
+        } else {
+            block.to_string()
+        };
+
+        existing_blocks.push((slot_type, slot_id, final_block));
+        last_block_end = block_end;
+        pos = block_end;
+    }
+
+    // Determine pre/post content
+    if let Some(fbs) = first_block_start {
+        pre_samples = content[..fbs].to_string();
+        post_samples = content[last_block_end..].to_string();
+    } else {
+        // No existing blocks at all — put pre as everything, post as empty
+        pre_samples = content.clone();
+    }
+
+    // Phase 2: Add new blocks for unapplied updates
+    for (key, fields) in field_updates {
+        if !applied.contains(key) {
+            let new_block = build_new_sample_block(&key.0, key.1, fields);
+            existing_blocks.push((key.0.clone(), key.1, new_block));
+        }
+    }
+
+    // Phase 3: Sort ALL blocks in OT canonical order:
+    // FLEX 001-128, FLEX 129-136 (recording buffers), STATIC 001-128
+    existing_blocks.sort_by(|(type_a, slot_a, _), (type_b, slot_b, _)| {
+        sample_block_order(type_a, *slot_a).cmp(&sample_block_order(type_b, *slot_b))
+    });
+
+    // Phase 4: Rebuild file
+    let mut result = String::with_capacity(content.len());
+    result.push_str(&pre_samples);
+
+    for (i, (_, _, block_text)) in existing_blocks.iter().enumerate() {
+        if i > 0 {
+            result.push_str("\r\n\r\n");
+        }
+        result.push_str(block_text);
+    }
+
+    result.push_str(&post_samples);
+
+    // Encode back to Windows-1258 and write
+    let (encoded, _, _) = encoding_rs::WINDOWS_1258.encode(&result);
+    crate::atomic_write::write_atomic(project_file_path, &encoded)
+        .map_err(|e| format!("Failed to write project file: {}", e))?;
+
+    Ok(())
+}
+
+/// Position of a [SAMPLE] block in the OT canonical order.
+fn sample_block_order(slot_type: &str, slot: u16) -> (u8, u16) {
+    match slot_type.to_uppercase().as_str() {
+        "FLEX" if slot <= 128 => (0, slot),
+        "FLEX" => (1, slot), // recording buffers 129-136
+        "STATIC" => (2, slot),
+        _ => (3, slot),
+    }
+}
+
+/// Surgically renumber the [SAMPLE] blocks of one pool: the block in slot `from`
+/// moves to slot `to` for every `(from, to)` in `moves` (1-based, a permutation
+/// of the slots involved). Only the SLOT= line changes; every other byte of the
+/// block is kept, then the blocks are put back in canonical order.
+fn renumber_sample_blocks_surgical(
+    project_file_path: &Path,
+    slot_type: &str,
+    moves: &std::collections::HashMap<u16, u16>,
+) -> Result<(), String> {
+    if moves.is_empty() {
+        return Ok(());
+    }
+    let raw_bytes = std::fs::read(project_file_path)
+        .map_err(|e| format!("Failed to read project file: {}", e))?;
+    let (decoded, _, _) = encoding_rs::WINDOWS_1258.decode(&raw_bytes);
+    let content = decoded.into_owned();
+    crate::os_compat::ensure_project_writable(&content)?;
+    if let Some(project_dir) = project_file_path.parent() {
+        crate::project_lock::ensure_unlocked(project_dir)?;
+    }
+
+    let mut blocks: Vec<(String, u16, String)> = Vec::new();
+    let mut first_block_start: Option<usize> = None;
+    let mut last_block_end = 0;
+    let mut pos = 0;
+    while let Some(offset) = content[pos..].find("[SAMPLE]") {
+        let block_start = pos + offset;
+        first_block_start.get_or_insert(block_start);
+        let block_end = content[block_start..]
+            .find("[/SAMPLE]")
+            .map(|i| block_start + i + "[/SAMPLE]".len())
+            .ok_or_else(|| "Malformed project file: unclosed [SAMPLE] block".to_string())?;
+        let block = &content[block_start..block_end];
+
+        let block_type = block
+            .lines()
+            .find_map(|l| l.trim_end_matches('\r').strip_prefix("TYPE="))
+            .unwrap_or_default()
+            .to_string();
+        let slot = block
+            .lines()
+            .find_map(|l| l.trim_end_matches('\r').strip_prefix("SLOT="))
+            .and_then(|v| v.parse::<u16>().ok())
             .unwrap_or(0);
 
-        let key = (slot_type.clone(), slot_id);
-
-        // Apply patches if needed
-        let final_block = if let Some(updates) = field_updates.get(&key) {
-            applied.insert(key.clone());
-            patch_sample_block_fields(block, updates)
-        } else {
+        let target = match moves.get(&slot) {
+            Some(&to) if block_type.eq_ignore_ascii_case(slot_type) => to,
+            _ => slot,
+        };
+        let text = if target == slot {
             block.to_string()
+        } else {
+            block
+                .split('\n')
+                .map(|line| match line.strip_prefix("SLOT=") {
+                    Some(rest) => {
+                        let cr = if rest.ends_with('\r') { "\r" } else { "" };
+                        format!("SLOT={:0>3}{}", target, cr)
+                    }
+                    None => line.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
         };
-
-        existing_blocks.push((slot_type, slot_id, final_block));
+        blocks.push((block_type, target, text));
         last_block_end = block_end;
         pos = block_end;
     }
+    let Some(first_block_start) = first_block_start else {
+        return Ok(());
+    };
 
-    // Determine pre/post content
-    if let Some(fbs) = first_block_start {
-        pre_samples = content[..fbs].to_string();
-        post_samples = content[last_block_end..].to_string();
-    } else {
-        // No existing blocks at all — put pre as everything, post as empty
-        pre_samples = content.clone();
-    }
-
-    // Phase 2: Add new blocks for unapplied updates
-    for (key, fields) in field_updates {
-        if !applied.contains(key) {
-            let new_block = build_new_sample_block(&key.0, key.1, fields);
-            existing_blocks.push((key.0.clone(), key.1, new_block));
-        }
-    }
-
-    // Phase 3: Sort ALL blocks in OT canonical order:
-    // FLEX 001-128, FLEX 129-136 (recording buffers), STATIC 001-128
-    existing_blocks.sort_by(|(type_a, slot_a, _), (type_b, slot_b, _)| {
-        let type_order = |t: &str, s: u16| -> (u8, u16) {
-            match t.to_uppercase().as_str() {
-                "FLEX" if s <= 128 => (0, s),
-                "FLEX" => (1, s), // recording buffers 129-136
-                "STATIC" => (2, s),
-                _ => (3, s),
-            }
-        };
-        type_order(type_a, *slot_a).cmp(&type_order(type_b, *slot_b))
+    blocks.sort_by(|(type_a, slot_a, _), (type_b, slot_b, _)| {
+        sample_block_order(type_a, *slot_a).cmp(&sample_block_order(type_b, *slot_b))
     });
-
-    // Phase 4: Rebuild file
     let mut result = String::with_capacity(content.len());
-    result.push_str(&pre_samples);
-
-    for (i, (_, _, block_text)) in existing_blocks.iter().enumerate() {
+    result.push_str(&content[..first_block_start]);
+    for (i, (_, _, block_text)) in blocks.iter().enumerate() {
         if i > 0 {
             result.push_str("\r\n\r\n");
         }
         result.push_str(block_text);
     }
+    result.push_str(&content[last_block_end..]);
 
-    result.push_str(&post_samples);
-
-    // Encode back to Windows-1258 and write
     let (encoded, _, _) = encoding_rs::WINDOWS_1258.encode(&result);
-    std::fs::write(project_file_path, &*encoded)
-        .map_err(|e| format!("Failed to write project file: {}", e))?;
+    crate::atomic_write::write_atomic(project_file_path, &encoded)
+        .map_err(|e| format!("Failed to write project file: {}", e))
+}
 
-    Ok(())
+/// Raw value of `key` inside the [SETTINGS] block of a project file, for the
+/// fields ot-tools-io reads lossily (see below).
+fn read_settings_field(project_file_path: &Path, key: &str) -> Option<String> {
+    let raw_bytes = std::fs::read(project_file_path).ok()?;
+    let (decoded, _, _) = encoding_rs::WINDOWS_1258.decode(&raw_bytes);
+    decoded
+        .lines()
+        .map(|line| line.trim_end_matches('\r'))
+        .skip_while(|line| *line != "[SETTINGS]")
+        .take_while(|line| *line != "[/SETTINGS]")
+        .find_map(|line| line.strip_prefix(key)?.strip_prefix('='))
+        .map(|value| value.to_string())
 }
 
 /// Surgically replace `KEY=value` lines inside the [SETTINGS] block of a project file.
@@ -4345,6 +5891,10 @@ fn replace_settings_fields_surgical(
     if !content.contains("[SETTINGS]") {
         return Err("Malformed project file: no [SETTINGS] block".to_string());
     }
+    crate::os_compat::ensure_project_writable(&content)?;
+    if let Some(project_dir) = project_file_path.parent() {
+        crate::project_lock::ensure_unlocked(project_dir)?;
+    }
 
     let mut pending: std::collections::HashMap<&str, &String> =
         updates.iter().map(|(k, v)| (*k, v)).collect();
@@ -4381,7 +5931,7 @@ fn replace_settings_fields_surgical(
     }
 
     let (encoded, _, _) = encoding_rs::WINDOWS_1258.encode(&result);
-    std::fs::write(project_file_path, &*encoded)
+    crate::atomic_write::write_atomic(project_file_path, &encoded)
         .map_err(|e| format!("Failed to write project file: {}", e))
 }
 
@@ -4423,7 +5973,7 @@ fn slot_attributes_at_default(slot: &SlotAttributes) -> bool {
 }
 
 /// Read (frame count, sample rate) from a WAV or AIFF file. Returns None if unreadable.
-fn audio_frames_and_rate(path: &Path) -> Option<(u64, u32)> {
+pub(crate) fn audio_frames_and_rate(path: &Path) -> Option<(u64, u32)> {
     if let Ok(reader) = hound::WavReader::open(path) {
         let spec = reader.spec();
         let channels = (spec.channels as u64).max(1);
@@ -4571,7 +6121,7 @@ pub fn assign_samples_to_slots(
     update_markers_trim_end(path, &slot_type_upper, &assignments)?;
 
     // Re-read the affected slots to return updated state
-    let metadata = read_project_metadata(project_path)?;
+    let metadata = read_project_metadata(project_path)?.data;
     let all_slots = match slot_type_upper.as_str() {
         "FLEX" => metadata.sample_slots.flex_slots,
         "STATIC" => metadata.sample_slots.static_slots,
@@ -4648,9 +6198,8 @@ fn update_markers_trim_end(
     }
 
     if modified {
-        markers
-            .to_data_file(&markers_path)
-            .map_err(|e| format!("Failed to write markers file: {:?}", e))?;
+        crate::atomic_write::write_data_file(&markers, &markers_path)
+            .map_err(|e| format!("Failed to write markers file: {}", e))?;
     }
     Ok(())
 }
@@ -4728,7 +6277,7 @@ pub fn reset_slot_attributes(
     };
 
     // Look up each target slot's current PATH to split filled vs empty and locate .ot siblings.
-    let metadata = read_project_metadata(project_path)?;
+    let metadata = read_project_metadata(project_path)?.data;
     let all_slots = match slot_type_upper.as_str() {
         "FLEX" => &metadata.sample_slots.flex_slots,
         "STATIC" => &metadata.sample_slots.static_slots,
@@ -4771,7 +6320,7 @@ pub fn reset_slot_attributes(
     }
 
     // Re-read affected slots for the response.
-    let metadata = read_project_metadata(project_path)?;
+    let metadata = read_project_metadata(project_path)?.data;
     let all_slots = match slot_type_upper.as_str() {
         "FLEX" => metadata.sample_slots.flex_slots,
         "STATIC" => metadata.sample_slots.static_slots,
@@ -4791,12 +6340,561 @@ pub fn reset_slot_attributes(
         (None, None)
     };
 
-    Ok(AssignSamplesResult {
-        assigned_count: slot_indices.len(),
-        updated_slots,
-        flex_ram_free_mb,
-        flex_ram_free_bytes,
-    })
+    Ok(AssignSamplesResult {
+        assigned_count: slot_indices.len(),
+        updated_slots,
+        flex_ram_free_mb,
+        flex_ram_free_bytes,
+    })
+}
+
+/// Slot GAIN is stored in half-dB steps around 48 (0 dB): 0-96 = -24..+24 dB.
+fn gain_db_to_raw(db: f32) -> u8 {
+    (48.0 + db * 2.0).round().clamp(0.0, 96.0) as u8
+}
+
+/// Set (`mode` "set") or shift (`mode` "offset") the gain, in dB, of every slot
+/// holding a sample that matches the filters: `slot_type` "FLEX"/"STATIC" (both
+/// when None), explicit 1-based `slot_indices`, and a case-insensitive
+/// `path_contains` substring of the sample path (e.g. "/AUDIO/drums").
+/// Results are clamped to -24..+24 dB and written in a single pass.
+/// Returns the number of slots whose gain changed.
+pub fn set_slot_gain(
+    project_path: &str,
+    slot_type: Option<&str>,
+    slot_indices: Option<&[u16]>,
+    path_contains: Option<&str>,
+    mode: &str,
+    db: f32,
+) -> Result<u32, String> {
+    let slot_type_upper = slot_type.map(|t| t.to_uppercase());
+    if let Some(t) = &slot_type_upper {
+        if !["FLEX", "STATIC"].contains(&t.as_str()) {
+            return Err(format!(
+                "Invalid slot_type: {}. Must be 'FLEX' or 'STATIC'",
+                t
+            ));
+        }
+    }
+    if let Some(idx) = slot_indices
+        .unwrap_or_default()
+        .iter()
+        .find(|idx| !(1..=128).contains(*idx))
+    {
+        return Err(format!("Slot index {} out of range. Must be 1-128", idx));
+    }
+    if !["set", "offset"].contains(&mode) {
+        return Err(format!("Unknown gain mode: {}", mode));
+    }
+    if !db.is_finite() || (mode == "set" && !(-24.0..=24.0).contains(&db)) {
+        return Err(format!("Gain out of range (-24 to +24 dB): {}", db));
+    }
+    let needle = path_contains.map(|p| p.replace('\\', "/").to_lowercase());
+
+    let project_dir = Path::new(project_path);
+    let project_file_path = if project_dir.join("project.work").exists() {
+        project_dir.join("project.work")
+    } else if project_dir.join("project.strd").exists() {
+        project_dir.join("project.strd")
+    } else {
+        return Err("No project file found".to_string());
+    };
+
+    let mut field_updates: RawSampleFieldsMap = std::collections::HashMap::new();
+    for ((block_type, slot_id), fields) in read_raw_sample_fields(&project_file_path)? {
+        if slot_type_upper.as_ref().is_some_and(|t| *t != block_type)
+            || slot_indices.is_some_and(|ids| !ids.contains(&slot_id))
+        {
+            continue;
+        }
+        // Recorder buffers (FLEX 129-136) are not sample slots.
+        if slot_id > 128 {
+            continue;
+        }
+        let path = fields.get("PATH").map(|p| p.as_str()).unwrap_or("");
+        if path.is_empty() {
+            continue;
+        }
+        if let Some(needle) = &needle {
+            if !path.replace('\\', "/").to_lowercase().contains(needle) {
+                continue;
+            }
+        }
+        let current: u8 = fields
+            .get("GAIN")
+            .and_then(|g| g.parse().ok())
+            .unwrap_or(48);
+        let gain = match mode {
+            "set" => gain_db_to_raw(db),
+            _ => gain_db_to_raw((current as f32 - 48.0) / 2.0 + db),
+        };
+        if gain != current {
+            let mut update = std::collections::HashMap::new();
+            update.insert("GAIN".to_string(), gain.to_string());
+            field_updates.insert((block_type, slot_id), update);
+        }
+    }
+
+    replace_sample_fields_surgical(&project_file_path, &field_updates)?;
+    Ok(field_updates.len() as u32)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlotConversionResult {
+    pub slot_type: String,     // pool the sample moved to: "FLEX" or "STATIC"
+    pub slot_index: u16,       // 1-based slot it now occupies
+    pub tracks_converted: u32, // part tracks switched to the other machine type
+    pub locks_moved: u32,      // sample locks rewritten to the new slot
+    pub warnings: Vec<String>,
+}
+
+/// Carry a slot's trim/loop/slice markers over to another pool slot, resetting the
+/// source entry. A missing markers file is a no-op (malformed project).
+fn move_slot_markers(
+    project_dir: &Path,
+    from_type: &str,
+    from_slot: u16,
+    to_type: &str,
+    to_slot: u16,
+) -> Result<(), String> {
+    let markers_path = if project_dir.join("markers.work").exists() {
+        project_dir.join("markers.work")
+    } else if project_dir.join("markers.strd").exists() {
+        project_dir.join("markers.strd")
+    } else {
+        return Ok(());
+    };
+
+    let mut markers = MarkersFile::from_data_file(&markers_path)
+        .map_err(|e| format!("Failed to read markers file: {:?}", e))?;
+
+    let (from_idx, to_idx) = ((from_slot - 1) as usize, (to_slot - 1) as usize);
+    let moved = match from_type {
+        "FLEX" => std::mem::take(&mut markers.flex_slots[from_idx]),
+        _ => std::mem::take(&mut markers.static_slots[from_idx]),
+    };
+    match to_type {
+        "FLEX" => markers.flex_slots[to_idx] = moved,
+        _ => markers.static_slots[to_idx] = moved,
+    }
+
+    crate::atomic_write::write_data_file(&markers, &markers_path)
+        .map_err(|e| format!("Failed to write markers file: {}", e))
+}
+
+/// Move a sample from a Static slot to a Flex slot or the reverse, keeping its
+/// attributes, then follow it in every bank: part tracks playing the old slot
+/// switch machine type (Static <-> Flex) and point at the new slot, and their
+/// sample locks on it are rewritten. `to_slot` picks the destination (1-based),
+/// otherwise the first empty slot of the other pool is used.
+pub fn convert_slot_type(
+    project_path: &str,
+    slot_type: &str,
+    slot_index: u16,
+    to_slot: Option<u16>,
+) -> Result<SlotConversionResult, String> {
+    let from_type = slot_type.to_uppercase();
+    let (to_type, from_machine, to_machine) = match from_type.as_str() {
+        "STATIC" => ("FLEX", 0u8, 1u8),
+        "FLEX" => ("STATIC", 1u8, 0u8),
+        _ => {
+            return Err(format!(
+                "Invalid slot_type: {}. Must be 'FLEX' or 'STATIC'",
+                slot_type
+            ))
+        }
+    };
+    for idx in std::iter::once(slot_index).chain(to_slot) {
+        if !(1..=128).contains(&idx) {
+            return Err(format!("Slot index {} out of range. Must be 1-128", idx));
+        }
+    }
+
+    let project_dir = Path::new(project_path);
+    let project_file_path = if project_dir.join("project.work").exists() {
+        project_dir.join("project.work")
+    } else if project_dir.join("project.strd").exists() {
+        project_dir.join("project.strd")
+    } else {
+        return Err("No project file found".to_string());
+    };
+
+    let raw_fields = read_raw_sample_fields(&project_file_path)?;
+    let is_filled = |stype: &str, slot: u16| {
+        raw_fields
+            .get(&(stype.to_string(), slot))
+            .and_then(|f| f.get("PATH"))
+            .is_some_and(|p| !p.is_empty())
+    };
+    if !is_filled(&from_type, slot_index) {
+        return Err(format!("{} slot {} holds no sample", from_type, slot_index));
+    }
+    let target = match to_slot {
+        Some(slot) if is_filled(to_type, slot) => {
+            return Err(format!("{} slot {} is not empty", to_type, slot));
+        }
+        Some(slot) => slot,
+        None => (1..=128u16)
+            .find(|&slot| !is_filled(to_type, slot))
+            .ok_or_else(|| format!("No empty {} slot left", to_type))?,
+    };
+
+    // Move the sample block: copy every field to the target, then empty the source.
+    let mut fields = raw_fields[&(from_type.clone(), slot_index)].clone();
+    fields.remove("TYPE");
+    fields.remove("SLOT");
+    let mut field_updates: RawSampleFieldsMap = std::collections::HashMap::new();
+    field_updates.insert((to_type.to_string(), target), fields);
+    replace_sample_fields_surgical(&project_file_path, &field_updates)?;
+    move_slot_markers(project_dir, &from_type, slot_index, to_type, target)?;
+    clear_sample_slots(project_path, &from_type, vec![slot_index])?;
+
+    // Slot ids in banks are 0-based.
+    let (old_id, new_id) = ((slot_index - 1) as u8, (target - 1) as u8);
+    let mut tracks_converted = 0u32;
+    let mut locks_moved = 0u32;
+    let mut stranded_locks = 0u32;
+    for bank_index in 0..16u8 {
+        let num = bank_index + 1;
+        if !project_dir.join(format!("bank{:02}.work", num)).exists()
+            && !project_dir.join(format!("bank{:02}.strd", num)).exists()
+        {
+            continue;
+        }
+        let (converted, moved, stranded) = edit_bank_file(project_path, bank_index, |bank| {
+            // Tracks of the working parts playing the moved slot, decided before
+            // any machine type changes so pattern locks use the original layout.
+            let playing: Vec<[bool; 8]> = bank
+                .parts
+                .unsaved
+                .0
+                .iter()
+                .map(|part| {
+                    std::array::from_fn(|t| {
+                        let slot = &part.audio_track_machine_slots[t];
+                        let slot_id = match from_machine {
+                            0 => slot.static_slot_id,
+                            _ => slot.flex_slot_id,
+                        };
+                        part.audio_track_machine_types[t] == from_machine && slot_id == old_id
+                    })
+                })
+                .collect();
+            let original_types: Vec<[u8; 8]> = bank
+                .parts
+                .unsaved
+                .0
+                .iter()
+                .map(|part| std::array::from_fn(|t| part.audio_track_machine_types[t]))
+                .collect();
+
+            let mut converted = 0u32;
+            for (state_idx, parts_state) in [&mut bank.parts.unsaved, &mut bank.parts.saved]
+                .into_iter()
+                .enumerate()
+            {
+                for part in parts_state.0.iter_mut() {
+                    for t in 0..8 {
+                        let slot = &mut part.audio_track_machine_slots[t];
+                        let slot_id = match from_machine {
+                            0 => slot.static_slot_id,
+                            _ => slot.flex_slot_id,
+                        };
+                        if part.audio_track_machine_types[t] != from_machine || slot_id != old_id {
+                            continue;
+                        }
+                        part.audio_track_machine_types[t] = to_machine;
+                        match to_machine {
+                            0 => slot.static_slot_id = new_id,
+                            _ => slot.flex_slot_id = new_id,
+                        }
+                        if state_idx == 0 {
+                            converted += 1;
+                        }
+                    }
+                }
+            }
+
+            let (mut moved, mut stranded) = (0u32, 0u32);
+            for pattern in bank.patterns.0.iter_mut() {
+                let part_idx = (pattern.part_assignment as usize).min(3);
+                for (t, track) in pattern.audio_track_trigs.0.iter_mut().enumerate() {
+                    if original_types[part_idx][t] != from_machine {
+                        continue;
+                    }
+                    for plock in track.plocks.0.iter_mut() {
+                        if plock.flex_slot_id != old_id {
+                            continue;
+                        }
+                        if playing[part_idx][t] {
+                            plock.flex_slot_id = new_id;
+                            moved += 1;
+                        } else {
+                            stranded += 1;
+                        }
+                    }
+                }
+            }
+            Ok((converted, moved, stranded))
+        })?;
+        tracks_converted += converted;
+        locks_moved += moved;
+        stranded_locks += stranded;
+    }
+
+    let mut warnings = Vec::new();
+    if stranded_locks > 0 {
+        warnings.push(format!(
+            "{} sample lock(s) on {} tracks playing other slots still point at the emptied {} slot {}",
+            stranded_locks,
+            if from_machine == 0 { "Static" } else { "Flex" },
+            from_type,
+            slot_index
+        ));
+    }
+    // Everything is written by now, so an unreadable memory setup only skips the check.
+    if let (true, Ok(memory)) = (to_type == "FLEX", read_project_memory_settings(project_dir)) {
+        let used = sum_flex_sample_sizes(project_dir, memory.load_24bit_flex).unwrap_or(0);
+        if used > calculate_flex_ram_bytes(&memory) {
+            warnings.push("Flex samples now exceed the available Flex RAM".to_string());
+        }
+    }
+
+    Ok(SlotConversionResult {
+        slot_type: to_type.to_string(),
+        slot_index: target,
+        tracks_converted,
+        locks_moved,
+        warnings,
+    })
+}
+
+/// One slot move in [`reorder_sample_slots`] (1-based slot numbers).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SlotMove {
+    pub from: u16,
+    pub to: u16,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlotReorderResult {
+    pub slot_type: String,    // "FLEX" or "STATIC"
+    pub moves: Vec<SlotMove>, // every slot whose number changed
+    pub tracks_updated: u32,  // part tracks (working copies) pointed at a new slot
+    pub locks_updated: u32,   // sample locks rewritten
+}
+
+/// New layout of a pool: `order[n]` is the current slot that ends up in slot
+/// `n + 1`. Filled slots are the ones with a non-empty PATH.
+fn plan_slot_order(
+    raw_fields: &RawSampleFieldsMap,
+    slot_type: &str,
+    mode: &str,
+    moves: &[SlotMove],
+) -> Result<Vec<u16>, String> {
+    let path_of = |slot: u16| {
+        raw_fields
+            .get(&(slot_type.to_string(), slot))
+            .and_then(|f| f.get("PATH"))
+            .filter(|p| !p.is_empty())
+    };
+    let (filled, empty): (Vec<u16>, Vec<u16>) =
+        (1..=128u16).partition(|&slot| path_of(slot).is_some());
+    match mode {
+        "compact" => Ok(filled.into_iter().chain(empty).collect()),
+        "alphabetical" => {
+            let name = |slot: u16| {
+                let path = path_of(slot).map(String::as_str).unwrap_or_default();
+                Path::new(&path.replace('\\', "/"))
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_lowercase())
+                    .unwrap_or_default()
+            };
+            let mut sorted = filled;
+            sorted.sort_by_cached_key(|&slot| (name(slot), slot));
+            Ok(sorted.into_iter().chain(empty).collect())
+        }
+        "custom" => {
+            // Moved slots land where asked; the rest keep their order and
+            // close up around them, as when dragging slots in a list.
+            let mut order: Vec<Option<u16>> = vec![None; 128];
+            let mut moved = std::collections::HashSet::new();
+            for m in moves {
+                if !(1..=128).contains(&m.from) || !(1..=128).contains(&m.to) {
+                    return Err(format!(
+                        "Slot move {} -> {} out of range. Must be 1-128",
+                        m.from, m.to
+                    ));
+                }
+                if !moved.insert(m.from) || order[(m.to - 1) as usize].is_some() {
+                    return Err(format!(
+                        "Slot move {} -> {} conflicts with another move",
+                        m.from, m.to
+                    ));
+                }
+                order[(m.to - 1) as usize] = Some(m.from);
+            }
+            let mut rest = (1..=128u16).filter(|slot| !moved.contains(slot));
+            Ok(order
+                .into_iter()
+                .map(|slot| slot.or_else(|| rest.next()).unwrap_or_default())
+                .collect())
+        }
+        _ => Err(format!(
+            "Invalid mode: {}. Must be 'alphabetical', 'compact' or 'custom'",
+            mode
+        )),
+    }
+}
+
+/// Reorder the samples of one pool, by file name (`alphabetical`), by closing
+/// the gaps between filled slots (`compact`) or by explicit `moves` (`custom`).
+/// Slot attributes and markers travel with their sample, and every bank is
+/// rewritten to follow: the part tracks' default slots (both working and saved
+/// copies, whatever machine is currently selected) and the sample locks of
+/// tracks playing that pool. Recorder buffers (Flex 129-136) never move.
+pub fn reorder_sample_slots(
+    project_path: &str,
+    slot_type: &str,
+    mode: &str,
+    moves: &[SlotMove],
+) -> Result<SlotReorderResult, String> {
+    let pool = slot_type.to_uppercase();
+    let machine = match pool.as_str() {
+        "STATIC" => 0u8,
+        "FLEX" => 1u8,
+        _ => {
+            return Err(format!(
+                "Invalid slot_type: {}. Must be 'FLEX' or 'STATIC'",
+                slot_type
+            ))
+        }
+    };
+
+    let project_dir = Path::new(project_path);
+    let project_file_path = if project_dir.join("project.work").exists() {
+        project_dir.join("project.work")
+    } else if project_dir.join("project.strd").exists() {
+        project_dir.join("project.strd")
+    } else {
+        return Err("No project file found".to_string());
+    };
+
+    let raw_fields = read_raw_sample_fields(&project_file_path)?;
+    let order = plan_slot_order(&raw_fields, &pool, mode, moves)?;
+    let slot_moves: Vec<SlotMove> = order
+        .iter()
+        .enumerate()
+        .map(|(n, &from)| SlotMove {
+            from,
+            to: n as u16 + 1,
+        })
+        .filter(|m| m.from != m.to)
+        .collect();
+    let mut result = SlotReorderResult {
+        slot_type: pool.clone(),
+        moves: slot_moves,
+        tracks_updated: 0,
+        locks_updated: 0,
+    };
+    if result.moves.is_empty() {
+        return Ok(result);
+    }
+
+    let block_moves: std::collections::HashMap<u16, u16> =
+        result.moves.iter().map(|m| (m.from, m.to)).collect();
+    renumber_sample_blocks_surgical(&project_file_path, &pool, &block_moves)?;
+
+    let markers_path = ["markers.work", "markers.strd"]
+        .iter()
+        .map(|name| project_dir.join(name))
+        .find(|p| p.exists());
+    if let Some(markers_path) = markers_path {
+        let mut markers = MarkersFile::from_data_file(&markers_path)
+            .map_err(|e| format!("Failed to read markers file: {:?}", e))?;
+        let slots = match machine {
+            0 => &mut markers.static_slots[..],
+            _ => &mut markers.flex_slots[..],
+        };
+        let taken: Vec<_> = result
+            .moves
+            .iter()
+            .map(|m| std::mem::take(&mut slots[(m.from - 1) as usize]))
+            .collect();
+        for (m, slot_markers) in result.moves.iter().zip(taken) {
+            slots[(m.to - 1) as usize] = slot_markers;
+        }
+        crate::atomic_write::write_data_file(&markers, &markers_path)
+            .map_err(|e| format!("Failed to write markers file: {}", e))?;
+    }
+
+    // Slot ids in banks are 0-based.
+    let id_moves: std::collections::HashMap<u8, u8> = result
+        .moves
+        .iter()
+        .map(|m| ((m.from - 1) as u8, (m.to - 1) as u8))
+        .collect();
+    for bank_index in 0..16u8 {
+        let num = bank_index + 1;
+        if !project_dir.join(format!("bank{:02}.work", num)).exists()
+            && !project_dir.join(format!("bank{:02}.strd", num)).exists()
+        {
+            continue;
+        }
+        let (tracks, locks) = edit_bank_file(project_path, bank_index, |bank| {
+            let mut tracks = 0u32;
+            for (state_idx, parts_state) in [&mut bank.parts.unsaved, &mut bank.parts.saved]
+                .into_iter()
+                .enumerate()
+            {
+                for part in parts_state.0.iter_mut() {
+                    for t in 0..8 {
+                        let slot = &mut part.audio_track_machine_slots[t];
+                        let slot_id = match machine {
+                            0 => &mut slot.static_slot_id,
+                            _ => &mut slot.flex_slot_id,
+                        };
+                        if let Some(&new_id) = id_moves.get(&*slot_id) {
+                            *slot_id = new_id;
+                            if state_idx == 0 && part.audio_track_machine_types[t] == machine {
+                                tracks += 1;
+                            }
+                        }
+                    }
+                }
+            }
+
+            // Machine types don't change here, so the working parts tell which
+            // pool each pattern's locks point into.
+            let machine_types: Vec<[u8; 8]> = bank
+                .parts
+                .unsaved
+                .0
+                .iter()
+                .map(|part| std::array::from_fn(|t| part.audio_track_machine_types[t]))
+                .collect();
+            let mut locks = 0u32;
+            for pattern in bank.patterns.0.iter_mut() {
+                let part_idx = (pattern.part_assignment as usize).min(3);
+                for (t, track) in pattern.audio_track_trigs.0.iter_mut().enumerate() {
+                    if machine_types[part_idx][t] != machine {
+                        continue;
+                    }
+                    for plock in track.plocks.0.iter_mut() {
+                        if let Some(&new_id) = id_moves.get(&plock.flex_slot_id) {
+                            plock.flex_slot_id = new_id;
+                            locks += 1;
+                        }
+                    }
+                }
+            }
+            Ok((tracks, locks))
+        })?;
+        result.tracks_updated += tracks;
+        result.locks_updated += locks;
+    }
+
+    Ok(result)
 }
 
 /// Clear the assigned sample from the given slots **without** touching their attributes:
@@ -4840,7 +6938,7 @@ pub fn clear_sample_keep_attributes(
     };
 
     // Only blank the PATH of slots that actually hold a sample (leave empty slots untouched).
-    let metadata = read_project_metadata(project_path)?;
+    let metadata = read_project_metadata(project_path)?.data;
     let all_slots = match slot_type_upper.as_str() {
         "FLEX" => &metadata.sample_slots.flex_slots,
         "STATIC" => &metadata.sample_slots.static_slots,
@@ -4868,7 +6966,7 @@ pub fn clear_sample_keep_attributes(
     }
 
     // Re-read affected slots for the response.
-    let metadata = read_project_metadata(project_path)?;
+    let metadata = read_project_metadata(project_path)?.data;
     let all_slots = match slot_type_upper.as_str() {
         "FLEX" => metadata.sample_slots.flex_slots,
         "STATIC" => metadata.sample_slots.static_slots,
@@ -4993,12 +7091,12 @@ pub fn clear_sample_slots(
         }
         result.push_str(&post);
         let (encoded, _, _) = encoding_rs::WINDOWS_1258.encode(&result);
-        std::fs::write(&project_file_path, &*encoded)
+        crate::atomic_write::write_atomic(&project_file_path, &encoded)
             .map_err(|e| format!("Failed to write project file: {}", e))?;
     }
 
     // Re-read affected slots
-    let metadata = read_project_metadata(project_path)?;
+    let metadata = read_project_metadata(project_path)?.data;
     let all_slots = match slot_type_upper.as_str() {
         "FLEX" => metadata.sample_slots.flex_slots,
         "STATIC" => metadata.sample_slots.static_slots,
@@ -5029,6 +7127,99 @@ pub fn clear_sample_slots(
     })
 }
 
+/// A sample slot holding a file that no bank references.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnusedSlot {
+    pub slot_type: String, // "Static" or "Flex"
+    pub slot_id: u16,      // 1-based
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PurgeSlotsResult {
+    pub slots: Vec<UnusedSlot>,
+    pub purged: bool, // false for a preview
+}
+
+/// Slots holding a sample that no part machine assignment and no sample lock
+/// in any bank points at. A slot whose only reference is the untrigged factory
+/// default (static slot N on track N) counts as unused, as in
+/// [`compute_sample_usage`].
+pub fn find_unused_slots(project_path: &str) -> Result<Vec<UnusedSlot>, String> {
+    let path = Path::new(project_path);
+    let project_file_path = if path.join("project.work").exists() {
+        path.join("project.work")
+    } else if path.join("project.strd").exists() {
+        path.join("project.strd")
+    } else {
+        return Err("No project file found".to_string());
+    };
+    let project_data = ProjectFile::from_data_file(&project_file_path)
+        .map_err(|e| format!("Failed to read project: {:?}", e))?;
+    let usage = compute_sample_usage(project_path)?;
+
+    let mut unused = Vec::new();
+    for (slot_type, slots, slot_usage) in [
+        (
+            "Static",
+            &project_data.slots.static_slots,
+            &usage.static_usage,
+        ),
+        ("Flex", &project_data.slots.flex_slots, &usage.flex_usage),
+    ] {
+        for idx in 0..128usize {
+            let Some(Some(slot)) = slots.get(idx) else {
+                continue;
+            };
+            let Some(sample_path) = &slot.path else {
+                continue;
+            };
+            let sample_path = sample_path.to_string_lossy().to_string();
+            if sample_path.is_empty() || !slot_usage[idx].is_empty() {
+                continue;
+            }
+            unused.push(UnusedSlot {
+                slot_type: slot_type.to_string(),
+                slot_id: (idx + 1) as u16,
+                path: sample_path,
+            });
+        }
+    }
+    Ok(unused)
+}
+
+/// Clear every unused slot (see [`find_unused_slots`]). With `preview`, only
+/// list them. The project file is backed up before writing.
+pub fn purge_unused_slots(project_path: &str, preview: bool) -> Result<PurgeSlotsResult, String> {
+    let slots = find_unused_slots(project_path)?;
+    if preview || slots.is_empty() {
+        return Ok(PurgeSlotsResult {
+            slots,
+            purged: false,
+        });
+    }
+
+    let path = Path::new(project_path);
+    let project_file = if path.join("project.work").exists() {
+        "project.work"
+    } else {
+        "project.strd"
+    };
+    crate::backup_project_files_impl(project_path, &[project_file.to_string()], "purge_slots")?;
+    for slot_type in ["Static", "Flex"] {
+        let ids: Vec<u16> = slots
+            .iter()
+            .filter(|s| s.slot_type == slot_type)
+            .map(|s| s.slot_id)
+            .collect();
+        clear_sample_slots(project_path, slot_type, ids)?;
+    }
+    Ok(PurgeSlotsResult {
+        slots,
+        purged: true,
+    })
+}
+
 /// Sentinel update value meaning "delete this field line if present" (and don't insert it).
 /// Used to strip stale timing fields (e.g. BPMx24) when normalizing a slot's attributes.
 const FIELD_DELETE: &str = "\u{0}__DELETE__";
@@ -5254,7 +7445,7 @@ fn update_project_file_paths_surgical(
     if modified {
         // Encode back to Windows-1258 and write
         let (encoded, _, _) = encoding_rs::WINDOWS_1258.encode(&result);
-        std::fs::write(project_file_path, &*encoded)
+        crate::atomic_write::write_atomic(project_file_path, &encoded)
             .map_err(|e| format!("Failed to write project file: {}", e))?;
     }
 
@@ -5426,6 +7617,240 @@ pub fn fix_missing_samples(
     })
 }
 
+// ============================================================================
+// Consolidate Samples
+// ============================================================================
+
+/// One sample slot considered by [`consolidate_samples`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsolidateEntry {
+    pub slot_type: String,        // "Static" or "Flex"
+    pub slot_id: u8,              // 1-based
+    pub source: String,           // resolved source path
+    pub new_path: Option<String>, // slot PATH after consolidation (None when skipped)
+    pub action: String,           // "copy", "reuse" (same file already in the project), "missing"
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsolidateResult {
+    pub entries: Vec<ConsolidateEntry>,
+    pub files_copied: u32,
+    pub bytes_copied: u64,
+    pub slots_updated: u32,
+    pub dry_run: bool,
+}
+
+/// Repoint [SAMPLE] PATH= lines whose resolved path (lowercased) is a key of
+/// `new_paths` onto the mapped value. Other bytes are preserved. Returns the
+/// number of slots rewritten.
+fn rewrite_sample_paths(
+    project_file_path: &Path,
+    project_dir: &Path,
+    new_paths: &std::collections::HashMap<String, String>,
+) -> Result<u32, String> {
+    let raw_bytes = std::fs::read(project_file_path)
+        .map_err(|e| format!("Failed to read project file: {}", e))?;
+    let (decoded, _, _) = encoding_rs::WINDOWS_1258.decode(&raw_bytes);
+    let content = decoded.into_owned();
+
+    let mut modified = 0u32;
+    let mut result = String::with_capacity(content.len());
+    let mut pos = 0;
+
+    while let Some(block_start) = content[pos..].find("[SAMPLE]") {
+        let block_start = pos + block_start;
+        let block_end_tag = "[/SAMPLE]";
+        let block_end = content[block_start..]
+            .find(block_end_tag)
+            .map(|i| block_start + i + block_end_tag.len())
+            .ok_or_else(|| "Malformed project file: unclosed [SAMPLE] block".to_string())?;
+
+        result.push_str(&content[pos..block_start]);
+        let block = &content[block_start..block_end];
+
+        let mut replaced = false;
+        if let Some(path_line_start) = block.find("\nPATH=") {
+            let path_value_start = path_line_start + "\nPATH=".len();
+            let path_value_end = block[path_value_start..]
+                .find(['\r', '\n'])
+                .map(|i| path_value_start + i)
+                .unwrap_or(block.len());
+            let current_path = &block[path_value_start..path_value_end];
+            let resolved =
+                normalize_path_lexically(&project_dir.join(current_path.replace('\\', "/")))
+                    .to_string_lossy()
+                    .to_lowercase();
+            if let Some(new_path) = new_paths.get(&resolved) {
+                result.push_str(&block[..path_value_start]);
+                result.push_str(new_path);
+                result.push_str(&block[path_value_end..]);
+                replaced = true;
+            }
+        }
+
+        if replaced {
+            modified += 1;
+        } else {
+            result.push_str(block);
+        }
+        pos = block_end;
+    }
+    result.push_str(&content[pos..]);
+
+    if modified > 0 {
+        let (encoded, _, _) = encoding_rs::WINDOWS_1258.encode(&result);
+        crate::atomic_write::write_atomic(project_file_path, &encoded)
+            .map_err(|e| format!("Failed to write project file: {}", e))?;
+    }
+    Ok(modified)
+}
+
+/// Copy every sample referenced from outside the project directory (Audio
+/// Pool or elsewhere) into the project and repoint the slots at the copies,
+/// like the device's COLLECT SAMPLES. A sample used by several slots is copied
+/// once; a name already taken by a different file gets a `_2`, `_3`... suffix.
+/// With `dry_run`, nothing is written and the returned plan shows what would
+/// happen. `progress` receives each copied file and the overall fraction.
+pub fn consolidate_samples(
+    project_path: &str,
+    dry_run: bool,
+    mut progress: impl FnMut(&str, f32),
+) -> Result<ConsolidateResult, String> {
+    let project_dir = normalize_path_lexically(Path::new(project_path));
+    let project_file_path = if project_dir.join("project.work").exists() {
+        project_dir.join("project.work")
+    } else if project_dir.join("project.strd").exists() {
+        project_dir.join("project.strd")
+    } else {
+        return Err("Project file not found".to_string());
+    };
+    let project_data = ProjectFile::from_data_file(&project_file_path)
+        .map_err(|e| format!("Failed to read project: {:?}", e))?;
+
+    let mut entries = Vec::new();
+    // resolved source (lowercased) -> new slot PATH
+    let mut new_paths: std::collections::HashMap<String, String> = std::collections::HashMap::new();
+    // (source, destination) pairs to copy
+    let mut copies: Vec<(std::path::PathBuf, std::path::PathBuf)> = Vec::new();
+    let mut taken: std::collections::HashSet<String> = std::collections::HashSet::new();
+
+    for (slot_type, slots) in [
+        ("Static", &project_data.slots.static_slots),
+        ("Flex", &project_data.slots.flex_slots),
+    ] {
+        for (idx, slot) in slots.iter().enumerate().take(128) {
+            let Some(rel) = slot.as_ref().and_then(|s| s.path.as_ref()) else {
+                continue;
+            };
+            let rel = rel.to_string_lossy().replace('\\', "/");
+            if rel.is_empty() {
+                continue;
+            }
+            let source = normalize_path_lexically(&project_dir.join(&rel));
+            if source.parent() == Some(project_dir.as_path()) {
+                continue; // already in the project
+            }
+            let key = source.to_string_lossy().to_lowercase();
+            let mut entry = ConsolidateEntry {
+                slot_type: slot_type.to_string(),
+                slot_id: (idx + 1) as u8,
+                source: source.to_string_lossy().to_string(),
+                new_path: None,
+                action: "missing".to_string(),
+            };
+            if !source.is_file() {
+                entries.push(entry);
+                continue;
+            }
+            if let Some(new_path) = new_paths.get(&key) {
+                entry.new_path = Some(new_path.clone());
+                entry.action = "reuse".to_string();
+                entries.push(entry);
+                continue;
+            }
+
+            let file_name = source
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let stem = Path::new(&file_name)
+                .file_stem()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let ext = Path::new(&file_name)
+                .extension()
+                .map(|e| format!(".{}", e.to_string_lossy()))
+                .unwrap_or_default();
+            let mut name = file_name.clone();
+            let mut n = 2;
+            let action = loop {
+                let dest = project_dir.join(&name);
+                if !taken.contains(&name.to_lowercase()) {
+                    if !dest.exists() {
+                        break "copy";
+                    }
+                    // An identical copy is already in the project: point at it
+                    let same = std::fs::metadata(&dest).ok().map(|m| m.len())
+                        == std::fs::metadata(&source).ok().map(|m| m.len())
+                        && std::fs::read(&dest).ok() == std::fs::read(&source).ok();
+                    if same {
+                        break "reuse";
+                    }
+                }
+                name = format!("{}_{}{}", stem, n, ext);
+                n += 1;
+            };
+            taken.insert(name.to_lowercase());
+            if action == "copy" {
+                copies.push((source.clone(), project_dir.join(&name)));
+            }
+            new_paths.insert(key, name.clone());
+            entry.new_path = Some(name);
+            entry.action = action.to_string();
+            entries.push(entry);
+        }
+    }
+
+    let bytes_copied: u64 = copies
+        .iter()
+        .filter_map(|(src, _)| std::fs::metadata(src).ok())
+        .map(|m| m.len())
+        .sum();
+    let slots_updated = entries.iter().filter(|e| e.new_path.is_some()).count() as u32;
+    if dry_run || new_paths.is_empty() {
+        return Ok(ConsolidateResult {
+            entries,
+            files_copied: copies.len() as u32,
+            bytes_copied,
+            slots_updated,
+            dry_run,
+        });
+    }
+
+    crate::disk_space::ensure_free_space(&project_dir, bytes_copied)?;
+    let total = copies.len().max(1) as f32;
+    for (i, (src, dest)) in copies.iter().enumerate() {
+        progress(&src.to_string_lossy(), i as f32 / total);
+        std::fs::copy(src, dest).map_err(|e| format!("Failed to copy {}: {}", src.display(), e))?;
+    }
+    progress("", 1.0);
+
+    let file_name = project_file_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    crate::backup_project_files_impl(&project_dir.to_string_lossy(), &[file_name], "consolidate")?;
+    let slots_updated = rewrite_sample_paths(&project_file_path, &project_dir, &new_paths)?;
+
+    Ok(ConsolidateResult {
+        entries,
+        files_copied: copies.len() as u32,
+        bytes_copied,
+        slots_updated,
+        dry_run,
+    })
+}
+
 // ============================================================================
 // Fix Audio Pool Samples
 // ============================================================================
@@ -5438,7 +7863,7 @@ pub struct PoolReferenceUpdate {
 
 /// Lexically resolve `.` and `..` components without touching the filesystem
 /// (the old pool file may already be deleted when references get updated).
-fn normalize_path_lexically(path: &Path) -> std::path::PathBuf {
+pub(crate) fn normalize_path_lexically(path: &Path) -> std::path::PathBuf {
     let mut out = std::path::PathBuf::new();
     for comp in path.components() {
         match comp {
@@ -5457,7 +7882,7 @@ fn normalize_path_lexically(path: &Path) -> std::path::PathBuf {
 /// Every project directory directly under `set_dir`, paired with its project
 /// file (`project.work` preferred, falling back to `project.strd`). Skips the
 /// pool directory itself and any directory with neither project file.
-fn set_project_files(
+pub(crate) fn set_project_files(
     set_dir: &Path,
     pool_dir: Option<&Path>,
 ) -> Result<Vec<(std::path::PathBuf, std::path::PathBuf)>, String> {
@@ -5614,7 +8039,7 @@ fn update_references_in_set(
             )?;
 
             let (encoded, _, _) = encoding_rs::WINDOWS_1258.encode(&result);
-            std::fs::write(&project_file, &*encoded)
+            crate::atomic_write::write_atomic(&project_file, &encoded)
                 .map_err(|e| format!("Failed to write project file: {}", e))?;
 
             slots_updated += modified;
@@ -6091,7 +8516,7 @@ fn remap_bank_slot_references(
 }
 
 /// Total Octatrack RAM in bytes (exactly 85.5 MiB = 0x5580000).
-const OT_TOTAL_RAM_BYTES: u64 = 89_653_248;
+pub(crate) const OT_TOTAL_RAM_BYTES: u64 = 89_653_248;
 
 /// Audio PCM metadata needed for RAM calculation.
 struct AudioPcmInfo {
@@ -6313,14 +8738,18 @@ pub fn inspect_audio_file(path: &Path) -> AudioFileCheck {
 /// Formula: Total RAM - recorder buffer allocation
 /// Recorder buffer = reserved_recorder_count × reserved_recorder_length (seconds) × 44100 Hz × 2 channels × bytes_per_sample
 /// bytes_per_sample = 2 (16-bit) or 3 (24-bit, based on record_24bit setting)
-fn calculate_flex_ram_bytes(memory_settings: &MemorySettings) -> u64 {
+pub(crate) fn calculate_flex_ram_bytes(memory_settings: &MemorySettings) -> u64 {
+    OT_TOTAL_RAM_BYTES.saturating_sub(recorder_ram_bytes(memory_settings))
+}
+
+/// RAM reserved for the track recorder buffers.
+fn recorder_ram_bytes(memory_settings: &MemorySettings) -> u64 {
     let bytes_per_sample: u64 = if memory_settings.record_24bit { 3 } else { 2 };
-    let recorder_bytes = memory_settings.reserved_recorder_count as u64
+    memory_settings.reserved_recorder_count as u64
         * memory_settings.reserved_recorder_length as u64
         * 44100
         * 2 // stereo
-        * bytes_per_sample;
-    OT_TOTAL_RAM_BYTES.saturating_sub(recorder_bytes)
+        * bytes_per_sample
 }
 
 /// Truncate a byte count to MiB for display, matching Octatrack behavior:
@@ -6337,7 +8766,10 @@ fn truncate_bytes_to_mib(bytes: u64) -> f64 {
 
 /// Sum the RAM usage of all flex samples in a project (all 128 flex slots).
 /// Uses actual PCM data size from WAV headers, accounting for load_24bit_flex setting.
-fn sum_flex_sample_sizes(project_path: &Path, load_24bit_flex: bool) -> Result<u64, String> {
+pub(crate) fn sum_flex_sample_sizes(
+    project_path: &Path,
+    load_24bit_flex: bool,
+) -> Result<u64, String> {
     let project_file_path = if project_path.join("project.work").exists() {
         project_path.join("project.work")
     } else if project_path.join("project.strd").exists() {
@@ -6445,8 +8877,58 @@ fn read_project_memory_settings(project_path: &Path) -> Result<MemorySettings, S
     })
 }
 
+/// RAM consequences of a memory configuration for one project.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemorySettingsCheck {
+    pub recorder_ram_bytes: u64,      // reserved for track recorder buffers
+    pub flex_ram_capacity_bytes: u64, // left for flex samples
+    pub flex_ram_used_bytes: u64,     // needed by the project's flex samples
+    pub flex_ram_free_bytes: u64,
+    pub errors: Vec<String>, // why the settings cannot be saved; empty when valid
+}
+
+/// Check memory settings against the Octatrack RAM: the recorder reservation
+/// must fit, and the flex samples already assigned must still fit in what is
+/// left (the device would otherwise fail to load some of them).
+pub fn check_memory_settings(project_path: &str, settings: &MemorySettings) -> MemorySettingsCheck {
+    let recorder_ram = recorder_ram_bytes(settings);
+    let flex_ram_capacity = calculate_flex_ram_bytes(settings);
+    let flex_ram_used =
+        sum_flex_sample_sizes(Path::new(project_path), settings.load_24bit_flex).unwrap_or(0);
+
+    let mut errors = Vec::new();
+    if settings.reserved_recorder_count > 8 {
+        errors.push(format!(
+            "Reserved recorder count must be 0-8, got {}",
+            settings.reserved_recorder_count
+        ));
+    }
+    if recorder_ram > OT_TOTAL_RAM_BYTES {
+        errors.push(format!(
+            "Recorder reservation needs {} MiB but the Octatrack has {} MiB",
+            truncate_bytes_to_mib(recorder_ram),
+            truncate_bytes_to_mib(OT_TOTAL_RAM_BYTES)
+        ));
+    } else if flex_ram_used > flex_ram_capacity {
+        errors.push(format!(
+            "Flex samples need {} MiB but only {} MiB would be left for them",
+            truncate_bytes_to_mib(flex_ram_used),
+            truncate_bytes_to_mib(flex_ram_capacity)
+        ));
+    }
+
+    MemorySettingsCheck {
+        recorder_ram_bytes: recorder_ram,
+        flex_ram_capacity_bytes: flex_ram_capacity,
+        flex_ram_used_bytes: flex_ram_used,
+        flex_ram_free_bytes: flex_ram_capacity.saturating_sub(flex_ram_used),
+        errors,
+    }
+}
+
 /// Save memory settings to a project's project.work file.
-/// Returns the recomputed flex_ram_free_mb after the change.
+/// Returns the recomputed flex_ram_free_mb after the change. Settings failing
+/// [`check_memory_settings`] are rejected without writing.
 pub fn save_memory_settings_data(
     project_path: &str,
     settings: MemorySettings,
@@ -6461,6 +8943,11 @@ pub fn save_memory_settings_data(
         return Err("Project file not found".to_string());
     };
 
+    let check = check_memory_settings(project_path, &settings);
+    if !check.errors.is_empty() {
+        return Err(check.errors.join("; "));
+    }
+
     // Surgically edit only the memory lines: a full ot-tools-io rewrite corrupts
     // unrelated device data (see replace_settings_fields_surgical).
     let updates = [
@@ -6478,19 +8965,171 @@ pub fn save_memory_settings_data(
             settings.reserved_recorder_count.to_string(),
         ),
         (
-            "RESERVED_RECORDER_LENGTH",
-            settings.reserved_recorder_length.to_string(),
+            "RESERVED_RECORDER_LENGTH",
+            settings.reserved_recorder_length.to_string(),
+        ),
+    ];
+    replace_settings_fields_surgical(&project_file_path, &updates)?;
+
+    Ok(truncate_bytes_to_mib(check.flex_ram_free_bytes))
+}
+
+/// Save MIDI settings (trig and auto channels, sync, program change) to a
+/// project's project.work file. Channels use the stored values: 0-15, or -1
+/// for disabled. On/off flags already matching the file are left as written,
+/// since the device stores some of them as values other than 0/1 (e.g.
+/// MIDI_CLOCK_SEND=2).
+pub fn save_midi_settings_data(project_path: &str, settings: MidiSettings) -> Result<(), String> {
+    if settings.trig_channels.len() != 8 {
+        return Err(format!(
+            "Expected 8 MIDI trig channels, got {}",
+            settings.trig_channels.len()
+        ));
+    }
+    let mut channels: Vec<(String, i8)> = settings
+        .trig_channels
+        .iter()
+        .enumerate()
+        .map(|(i, &ch)| (format!("MIDI_TRIG_CH{}", i + 1), ch))
+        .collect();
+    channels.push(("MIDI_AUTO_CHANNEL".to_string(), settings.auto_channel));
+    channels.push((
+        "MIDI_PROGRAM_CHANGE_SEND_CH".to_string(),
+        settings.prog_change_send_channel,
+    ));
+    channels.push((
+        "MIDI_PROGRAM_CHANGE_RECEIVE_CH".to_string(),
+        settings.prog_change_receive_channel,
+    ));
+    if let Some((key, ch)) = channels.iter().find(|(_, ch)| !(-1..=15).contains(ch)) {
+        return Err(format!("Invalid {}: {} (must be 0-15 or -1)", key, ch));
+    }
+
+    let path = Path::new(project_path);
+    let project_file_path = if path.join("project.work").exists() {
+        path.join("project.work")
+    } else if path.join("project.strd").exists() {
+        path.join("project.strd")
+    } else {
+        return Err("Project file not found".to_string());
+    };
+
+    let mut updates: Vec<(&str, String)> = channels
+        .iter()
+        .map(|(key, ch)| (key.as_str(), ch.to_string()))
+        .collect();
+    for (key, enabled) in [
+        ("MIDI_CLOCK_SEND", settings.clock_send),
+        ("MIDI_CLOCK_RECEIVE", settings.clock_receive),
+        ("MIDI_TRANSPORT_SEND", settings.transport_send),
+        ("MIDI_TRANSPORT_RECEIVE", settings.transport_receive),
+        ("MIDI_PROGRAM_CHANGE_SEND", settings.prog_change_send),
+        ("MIDI_PROGRAM_CHANGE_RECEIVE", settings.prog_change_receive),
+    ] {
+        let current = read_settings_field(&project_file_path, key)
+            .and_then(|v| v.parse::<i32>().ok())
+            .map(|v| v != 0);
+        if current != Some(enabled) {
+            updates.push((key, (enabled as u8).to_string()));
+        }
+    }
+    replace_settings_fields_surgical(&project_file_path, &updates)
+}
+
+/// Save mixer settings (input gain and direct levels, phones mix, main/cue
+/// levels) to a project's project.work file. All values are 0-127.
+pub fn save_mixer_settings_data(project_path: &str, settings: MixerSettings) -> Result<(), String> {
+    let updates = [
+        ("GAIN_AB", settings.gain_ab),
+        ("GAIN_CD", settings.gain_cd),
+        ("DIR_AB", settings.dir_ab),
+        ("DIR_CD", settings.dir_cd),
+        ("PHONES_MIX", settings.phones_mix),
+        ("MAIN_LEVEL", settings.main_level),
+        ("CUE_LEVEL", settings.cue_level),
+    ];
+    if let Some((key, value)) = updates.iter().find(|(_, value)| *value > 127) {
+        return Err(format!("Invalid {}: {} (must be 0-127)", key, value));
+    }
+
+    let path = Path::new(project_path);
+    let project_file_path = if path.join("project.work").exists() {
+        path.join("project.work")
+    } else if path.join("project.strd").exists() {
+        path.join("project.strd")
+    } else {
+        return Err("Project file not found".to_string());
+    };
+
+    let updates: Vec<(&str, String)> = updates
+        .iter()
+        .map(|(key, value)| (*key, value.to_string()))
+        .collect();
+    replace_settings_fields_surgical(&project_file_path, &updates)
+}
+
+/// Save metronome settings to a project's project.work file.
+/// The time signature is stored as numerator - 1 and log2(denominator).
+pub fn save_metronome_settings_data(
+    project_path: &str,
+    settings: MetronomeSettings,
+) -> Result<(), String> {
+    let numerator = settings.time_signature_numerator;
+    if !(1..=16).contains(&numerator) {
+        return Err(format!(
+            "Invalid time signature numerator: {} (must be 1-16)",
+            numerator
+        ));
+    }
+    let denominator = settings.time_signature_denominator;
+    if !matches!(denominator, 1 | 2 | 4 | 8 | 16) {
+        return Err(format!(
+            "Invalid time signature denominator: {} (must be 1, 2, 4, 8 or 16)",
+            denominator
+        ));
+    }
+    for (name, value) in [
+        ("main volume", settings.main_volume),
+        ("cue volume", settings.cue_volume),
+        ("pitch", settings.pitch),
+    ] {
+        if value > 127 {
+            return Err(format!(
+                "Invalid metronome {}: {} (must be 0-127)",
+                name, value
+            ));
+        }
+    }
+    if settings.preroll > 16 {
+        return Err(format!(
+            "Invalid metronome preroll: {} (must be 0-16)",
+            settings.preroll
+        ));
+    }
+
+    let path = Path::new(project_path);
+    let project_file_path = if path.join("project.work").exists() {
+        path.join("project.work")
+    } else if path.join("project.strd").exists() {
+        path.join("project.strd")
+    } else {
+        return Err("Project file not found".to_string());
+    };
+
+    let updates = [
+        ("METRONOME_TIME_SIGNATURE", (numerator - 1).to_string()),
+        (
+            "METRONOME_TIME_SIGNATURE_DENOMINATOR",
+            denominator.trailing_zeros().to_string(),
         ),
+        ("METRONOME_PREROLL", settings.preroll.to_string()),
+        ("METRONOME_CUE_VOLUME", settings.cue_volume.to_string()),
+        ("METRONOME_MAIN_VOLUME", settings.main_volume.to_string()),
+        ("METRONOME_PITCH", settings.pitch.to_string()),
+        ("METRONOME_TONAL", (settings.tonal as u8).to_string()),
+        ("METRONOME_ENABLED", (settings.enabled as u8).to_string()),
     ];
-    replace_settings_fields_surgical(&project_file_path, &updates)?;
-
-    // Recompute flex RAM free
-    let flex_ram_capacity = calculate_flex_ram_bytes(&settings);
-    let flex_ram_used = sum_flex_sample_sizes(path, settings.load_24bit_flex).unwrap_or(0);
-    let flex_ram_free = flex_ram_capacity.saturating_sub(flex_ram_used);
-    let flex_ram_free_mb = truncate_bytes_to_mib(flex_ram_free);
-
-    Ok(flex_ram_free_mb)
+    replace_settings_fields_surgical(&project_file_path, &updates)
 }
 
 /// Validate whether the destination project has enough free slots to accommodate
@@ -6521,8 +9160,8 @@ pub fn validate_bank_sample_slots(
         return Err(format!("Source bank {} not found", source_bank_index));
     };
 
-    let bank = BankFile::from_data_file(&source_bank_path)
-        .map_err(|e| format!("Failed to read source bank: {:?}", e))?;
+    let bank = crate::os_compat::read_bank_file(&source_bank_path)
+        .map_err(|e| format!("Failed to read source bank: {}", e))?;
 
     // Collect source slots
     let (source_static, source_flex) = match sample_scope {
@@ -6692,6 +9331,7 @@ pub fn copy_bank(
 
     let source_path = Path::new(source_project);
     let dest_path = Path::new(dest_project);
+    crate::project_lock::ensure_unlocked(dest_path)?;
 
     // Build source bank file path (try .work first, then .strd)
     let source_bank_num = source_bank_index + 1;
@@ -6707,8 +9347,8 @@ pub fn copy_bank(
     };
 
     // Read the source bank once
-    let mut bank_data = BankFile::from_data_file(&source_bank_path)
-        .map_err(|e| format!("Failed to read source bank: {:?}", e))?;
+    let mut bank_data = crate::os_compat::read_bank_file(&source_bank_path)
+        .map_err(|e| format!("Failed to read source bank: {}", e))?;
 
     let mut result = CopyBankResult {
         slots_copied_static: 0,
@@ -6895,10 +9535,13 @@ pub fn copy_bank(
         let dest_bank_num = dest_bank_index + 1;
         let dest_bank_file = format!("bank{:02}.work", dest_bank_num);
         let dest_bank_path = dest_path.join(&dest_bank_file);
+        let bank_lock = bank_file_lock(&dest_bank_path);
+        let _bank_guard = bank_lock.lock().unwrap_or_else(|e| e.into_inner());
 
-        bank_data.to_data_file(&dest_bank_path).map_err(|e| {
+        crate::write_backup::backup_before_write(&dest_bank_path)?;
+        crate::atomic_write::write_data_file(&bank_data, &dest_bank_path).map_err(|e| {
             format!(
-                "Failed to write destination bank {}: {:?}",
+                "Failed to write destination bank {}: {}",
                 dest_bank_index, e
             )
         })?;
@@ -6912,6 +9555,185 @@ pub fn copy_bank(
     Ok(result)
 }
 
+/// A sample slot referenced by a bank whose assignment differs between two projects.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlotReferenceMismatch {
+    pub slot_type: String, // "static" or "flex"
+    pub slot_id: u8,       // 1-based
+    pub source_file: Option<String>,
+    pub dest_file: Option<String>,
+}
+
+/// Pre-flight report for moving a whole bank file between projects.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BankTransferCheck {
+    /// The destination bank already holds trigs or edited Parts.
+    pub dest_bank_in_use: bool,
+    /// Slots the source bank references that point to a different file (or
+    /// nothing) in the destination project, so the bank will play other samples.
+    pub slot_mismatches: Vec<SlotReferenceMismatch>,
+}
+
+/// Whether a bank holds any user content: a trig on any track or an edited Part.
+fn bank_has_content(bank: &BankFile) -> bool {
+    if bank.parts_edited_bitmask != 0 {
+        return true;
+    }
+    bank.patterns.0.iter().any(|pattern| {
+        pattern.audio_track_trigs.0.iter().any(|t| {
+            t.trig_masks.trigger.iter().any(|&m| m != 0)
+                || t.trig_masks.trigless.iter().any(|&m| m != 0)
+        }) || pattern.midi_track_trigs.0.iter().any(|t| {
+            t.trig_masks.trigger.iter().any(|&m| m != 0)
+                || t.trig_masks.trigless.iter().any(|&m| m != 0)
+        })
+    })
+}
+
+/// Check a bank transfer without writing anything: detects a destination bank
+/// that would be overwritten and sample slots whose assignment differs between
+/// the two projects.
+pub fn check_bank_transfer(
+    source_project: &str,
+    source_bank_index: u8,
+    dest_project: &str,
+    dest_bank_index: u8,
+) -> Result<BankTransferCheck, String> {
+    if source_bank_index > 15 {
+        return Err("Source bank index must be between 0 and 15".to_string());
+    }
+    if dest_bank_index > 15 {
+        return Err("Destination bank index must be between 0 and 15".to_string());
+    }
+
+    let source_path = Path::new(source_project);
+    let dest_path = Path::new(dest_project);
+
+    let source_bank_num = source_bank_index + 1;
+    let source_work_file = format!("bank{:02}.work", source_bank_num);
+    let source_strd_file = format!("bank{:02}.strd", source_bank_num);
+    let source_bank_path = if source_path.join(&source_work_file).exists() {
+        source_path.join(&source_work_file)
+    } else if source_path.join(&source_strd_file).exists() {
+        source_path.join(&source_strd_file)
+    } else {
+        return Err(format!("Source bank {} not found", source_bank_index));
+    };
+    let source_bank = crate::os_compat::read_bank_file(&source_bank_path)
+        .map_err(|e| format!("Failed to read source bank: {}", e))?;
+
+    let dest_bank_path = dest_path.join(format!("bank{:02}.work", dest_bank_index + 1));
+    let dest_bank_in_use = if dest_bank_path.exists() {
+        let dest_bank = crate::os_compat::read_bank_file(&dest_bank_path)
+            .map_err(|e| format!("Failed to read destination bank: {}", e))?;
+        bank_has_content(&dest_bank)
+    } else {
+        false
+    };
+
+    // Only slots the bank actually plays matter. Compare by filename, like the
+    // dedup logic of copy_bank.
+    let (referenced_static, referenced_flex) = collect_referenced_slots(&source_bank);
+    let (src_static, src_flex) =
+        get_source_slot_filenames(source_path, &referenced_static, &referenced_flex)?;
+    let (dest_static, dest_flex) = get_dest_slot_state(dest_path)?;
+
+    let mut slot_mismatches = Vec::new();
+    for (slot_type, referenced, src, dest) in [
+        ("static", &referenced_static, &src_static, &dest_static),
+        ("flex", &referenced_flex, &src_flex, &dest_flex),
+    ] {
+        let mut ids: Vec<u8> = referenced.iter().copied().filter(|&id| id < 128).collect();
+        ids.sort_unstable();
+        for id in ids {
+            let source_file = src.get(&id).cloned();
+            let dest_file = dest.get(&id).cloned();
+            if source_file != dest_file {
+                slot_mismatches.push(SlotReferenceMismatch {
+                    slot_type: slot_type.to_string(),
+                    slot_id: id + 1,
+                    source_file,
+                    dest_file,
+                });
+            }
+        }
+    }
+
+    Ok(BankTransferCheck {
+        dest_bank_in_use,
+        slot_mismatches,
+    })
+}
+
+/// Copy or move a whole bank file into another project under a chosen bank (A-P).
+///
+/// Refuses to replace a destination bank that holds content unless `overwrite`
+/// is set. Sample slot references are written as-is; mismatches are reported in
+/// the returned check so the caller can warn. A move resets the source bank to
+/// an empty one (a project always keeps all 16 bank files).
+pub fn transfer_bank(
+    source_project: &str,
+    source_bank_index: u8,
+    dest_project: &str,
+    dest_bank_index: u8,
+    overwrite: bool,
+    move_bank: bool,
+) -> Result<BankTransferCheck, String> {
+    if Path::new(source_project) == Path::new(dest_project) && source_bank_index == dest_bank_index
+    {
+        return Err("Source and destination bank are the same".to_string());
+    }
+
+    let check = check_bank_transfer(
+        source_project,
+        source_bank_index,
+        dest_project,
+        dest_bank_index,
+    )?;
+    if check.dest_bank_in_use && !overwrite {
+        return Err(format!(
+            "Destination bank {} already contains data",
+            (b'A' + dest_bank_index) as char
+        ));
+    }
+
+    if move_bank {
+        crate::project_lock::ensure_unlocked(Path::new(source_project))?;
+    }
+    copy_bank(
+        source_project,
+        source_bank_index,
+        dest_project,
+        &[dest_bank_index],
+        false,
+        "",
+        "",
+        "keep_position",
+        false,
+        &[],
+    )?;
+
+    if move_bank {
+        let empty = BankFile::default();
+        let source_bank_path =
+            Path::new(source_project).join(format!("bank{:02}.work", source_bank_index + 1));
+        let bank_lock = bank_file_lock(&source_bank_path);
+        let _bank_guard = bank_lock.lock().unwrap_or_else(|e| e.into_inner());
+        crate::write_backup::backup_before_write(&source_bank_path)?;
+        crate::atomic_write::write_data_file(&empty, &source_bank_path)
+            .map_err(|e| format!("Failed to reset source bank: {}", e))?;
+        // Reset the saved state too, or a reload would bring the old bank back.
+        let strd = source_bank_path.with_extension("strd");
+        if strd.exists() {
+            crate::write_backup::backup_before_write(&strd)?;
+            crate::atomic_write::write_data_file(&empty, &strd)
+                .map_err(|e| format!("Failed to reset saved source bank: {}", e))?;
+        }
+    }
+
+    Ok(check)
+}
+
 /// Copy specific Parts from one bank to another.
 /// Parts contain all track sound design parameters (machines, amps, LFOs, FX).
 ///
@@ -6953,6 +9775,7 @@ pub fn copy_parts(
 
     let source_path = Path::new(source_project);
     let dest_path = Path::new(dest_project);
+    crate::project_lock::ensure_unlocked(dest_path)?;
 
     // Read source bank
     let source_bank_num = source_bank_index + 1;
@@ -6967,21 +9790,23 @@ pub fn copy_parts(
         return Err(format!("Source bank {} not found", source_bank_index));
     };
 
-    let source_bank = BankFile::from_data_file(&source_bank_path)
-        .map_err(|e| format!("Failed to read source bank: {:?}", e))?;
+    let source_bank = crate::os_compat::read_bank_file(&source_bank_path)
+        .map_err(|e| format!("Failed to read source bank: {}", e))?;
 
     // Read or create destination bank
     let dest_bank_num = dest_bank_index + 1;
     let dest_work_file = format!("bank{:02}.work", dest_bank_num);
     let dest_strd_file = format!("bank{:02}.strd", dest_bank_num);
     let dest_bank_path = dest_path.join(&dest_work_file);
+    let bank_lock = bank_file_lock(&dest_bank_path);
+    let _bank_guard = bank_lock.lock().unwrap_or_else(|e| e.into_inner());
 
     let mut dest_bank = if dest_bank_path.exists() {
-        BankFile::from_data_file(&dest_bank_path)
-            .map_err(|e| format!("Failed to read destination bank: {:?}", e))?
+        crate::os_compat::read_bank_file(&dest_bank_path)
+            .map_err(|e| format!("Failed to read destination bank: {}", e))?
     } else if dest_path.join(&dest_strd_file).exists() {
-        BankFile::from_data_file(&dest_path.join(&dest_strd_file))
-            .map_err(|e| format!("Failed to read destination bank: {:?}", e))?
+        crate::os_compat::read_bank_file(&dest_path.join(&dest_strd_file))
+            .map_err(|e| format!("Failed to read destination bank: {}", e))?
     } else {
         return Err(format!("Destination bank {} not found", dest_bank_index));
     };
@@ -7036,9 +9861,9 @@ pub fn copy_parts(
         .map_err(|e| format!("Failed to calculate checksum: {:?}", e))?;
 
     // Write the destination bank
-    dest_bank
-        .to_data_file(&dest_bank_path)
-        .map_err(|e| format!("Failed to write destination bank: {:?}", e))?;
+    crate::write_backup::backup_before_write(&dest_bank_path)?;
+    crate::atomic_write::write_data_file(&dest_bank, &dest_bank_path)
+        .map_err(|e| format!("Failed to write destination bank: {}", e))?;
 
     println!(
         "[DEBUG] Copied {} source part(s) to {} destination part(s) from bank {} to bank {}",
@@ -7117,6 +9942,7 @@ pub fn copy_patterns(
 
     let source_path = Path::new(source_project);
     let dest_path = Path::new(dest_project);
+    crate::project_lock::ensure_unlocked(dest_path)?;
 
     // Read source bank
     let source_bank_num = source_bank_index + 1;
@@ -7131,21 +9957,23 @@ pub fn copy_patterns(
         return Err(format!("Source bank {} not found", source_bank_index));
     };
 
-    let source_bank = BankFile::from_data_file(&source_bank_path)
-        .map_err(|e| format!("Failed to read source bank: {:?}", e))?;
+    let source_bank = crate::os_compat::read_bank_file(&source_bank_path)
+        .map_err(|e| format!("Failed to read source bank: {}", e))?;
 
     // Read destination bank
     let dest_bank_num = dest_bank_index + 1;
     let dest_work_file = format!("bank{:02}.work", dest_bank_num);
     let dest_strd_file = format!("bank{:02}.strd", dest_bank_num);
     let dest_bank_path = dest_path.join(&dest_work_file);
+    let bank_lock = bank_file_lock(&dest_bank_path);
+    let _bank_guard = bank_lock.lock().unwrap_or_else(|e| e.into_inner());
 
     let mut dest_bank = if dest_bank_path.exists() {
-        BankFile::from_data_file(&dest_bank_path)
-            .map_err(|e| format!("Failed to read destination bank: {:?}", e))?
+        crate::os_compat::read_bank_file(&dest_bank_path)
+            .map_err(|e| format!("Failed to read destination bank: {}", e))?
     } else if dest_path.join(&dest_strd_file).exists() {
-        BankFile::from_data_file(&dest_path.join(&dest_strd_file))
-            .map_err(|e| format!("Failed to read destination bank: {:?}", e))?
+        crate::os_compat::read_bank_file(&dest_path.join(&dest_strd_file))
+            .map_err(|e| format!("Failed to read destination bank: {}", e))?
     } else {
         return Err(format!("Destination bank {} not found", dest_bank_index));
     };
@@ -7262,9 +10090,9 @@ pub fn copy_patterns(
         .map_err(|e| format!("Failed to calculate checksum: {:?}", e))?;
 
     // Write the destination bank
-    dest_bank
-        .to_data_file(&dest_bank_path)
-        .map_err(|e| format!("Failed to write destination bank: {:?}", e))?;
+    crate::write_backup::backup_before_write(&dest_bank_path)?;
+    crate::atomic_write::write_data_file(&dest_bank, &dest_bank_path)
+        .map_err(|e| format!("Failed to write destination bank: {}", e))?;
 
     println!(
         "[DEBUG] Copied {} patterns from bank {} to bank {}",
@@ -7276,6 +10104,45 @@ pub fn copy_patterns(
     Ok(())
 }
 
+/// Copy one full pattern (audio + MIDI trigs, p-locks, per-track settings, scale,
+/// tempo) to a pattern slot in the same or another project, like the device's
+/// pattern COPY/PASTE. Delegates to `copy_patterns` with every track selected.
+///
+/// # Arguments
+/// * `dest_part` - Part (0-3) to assign the pasted pattern to; `None` keeps the
+///   source pattern's part assignment
+pub fn copy_pattern(
+    source_project: &str,
+    source_bank_index: u8,
+    source_pattern_index: u8,
+    dest_project: &str,
+    dest_bank_index: u8,
+    dest_pattern_index: u8,
+    dest_part: Option<u8>,
+) -> Result<(), String> {
+    if dest_part.is_some_and(|p| p > 3) {
+        return Err("Part index must be between 0 and 3".to_string());
+    }
+    let part_assignment_mode = if dest_part.is_some() {
+        "select_specific"
+    } else {
+        "copy_source_part"
+    };
+    copy_patterns(
+        source_project,
+        source_bank_index,
+        vec![source_pattern_index],
+        dest_project,
+        dest_bank_index,
+        vec![dest_pattern_index],
+        part_assignment_mode,
+        dest_part,
+        "all",
+        None,
+        "both",
+    )
+}
+
 /// Copy tracks from one bank to another with mode selection.
 /// Tracks have two components:
 /// - Part-level parameters (sound design: machines, amps, LFOs, FX)
@@ -7346,6 +10213,7 @@ pub fn copy_tracks(
 
     let source_path = Path::new(source_project);
     let dest_path = Path::new(dest_project);
+    crate::project_lock::ensure_unlocked(dest_path)?;
 
     // Read source bank
     let source_bank_num = source_bank_index + 1;
@@ -7360,21 +10228,23 @@ pub fn copy_tracks(
         return Err(format!("Source bank {} not found", source_bank_index));
     };
 
-    let source_bank = BankFile::from_data_file(&source_bank_path)
-        .map_err(|e| format!("Failed to read source bank: {:?}", e))?;
+    let source_bank = crate::os_compat::read_bank_file(&source_bank_path)
+        .map_err(|e| format!("Failed to read source bank: {}", e))?;
 
     // Read destination bank
     let dest_bank_num = dest_bank_index + 1;
     let dest_work_file = format!("bank{:02}.work", dest_bank_num);
     let dest_strd_file = format!("bank{:02}.strd", dest_bank_num);
     let dest_bank_path = dest_path.join(&dest_work_file);
+    let bank_lock = bank_file_lock(&dest_bank_path);
+    let _bank_guard = bank_lock.lock().unwrap_or_else(|e| e.into_inner());
 
     let mut dest_bank = if dest_bank_path.exists() {
-        BankFile::from_data_file(&dest_bank_path)
-            .map_err(|e| format!("Failed to read destination bank: {:?}", e))?
+        crate::os_compat::read_bank_file(&dest_bank_path)
+            .map_err(|e| format!("Failed to read destination bank: {}", e))?
     } else if dest_path.join(&dest_strd_file).exists() {
-        BankFile::from_data_file(&dest_path.join(&dest_strd_file))
-            .map_err(|e| format!("Failed to read destination bank: {:?}", e))?
+        crate::os_compat::read_bank_file(&dest_path.join(&dest_strd_file))
+            .map_err(|e| format!("Failed to read destination bank: {}", e))?
     } else {
         return Err(format!("Destination bank {} not found", dest_bank_index));
     };
@@ -7564,9 +10434,9 @@ pub fn copy_tracks(
         .map_err(|e| format!("Failed to calculate checksum: {:?}", e))?;
 
     // Write the destination bank
-    dest_bank
-        .to_data_file(&dest_bank_path)
-        .map_err(|e| format!("Failed to write destination bank: {:?}", e))?;
+    crate::write_backup::backup_before_write(&dest_bank_path)?;
+    crate::atomic_write::write_data_file(&dest_bank, &dest_bank_path)
+        .map_err(|e| format!("Failed to write destination bank: {}", e))?;
 
     println!(
         "[DEBUG] Copied {} tracks from bank {} Part {} to bank {} Part {} (mode: {})",
@@ -8142,9 +11012,8 @@ pub fn copy_sample_slots(
     // Write destination markers file if modified
     if markers_modified {
         let dest_markers_final = dest_path.join("markers.work");
-        dest_markers
-            .to_data_file(&dest_markers_final)
-            .map_err(|e| format!("Failed to write destination markers file: {:?}", e))?;
+        crate::atomic_write::write_data_file(&dest_markers, &dest_markers_final)
+            .map_err(|e| format!("Failed to write destination markers file: {}", e))?;
         println!("[DEBUG] Wrote markers file: {:?}", dest_markers_final);
     }
 
@@ -8165,9 +11034,8 @@ pub fn copy_sample_slots(
         if source_markers_reintegration_modified {
             if let Some(ref src_markers) = source_markers_for_reintegration {
                 let src_markers_final = source_path.join("markers.work");
-                src_markers
-                    .to_data_file(&src_markers_final)
-                    .map_err(|e| format!("Failed to write source markers file: {:?}", e))?;
+                crate::atomic_write::write_data_file(&src_markers, &src_markers_final)
+                    .map_err(|e| format!("Failed to write source markers file: {}", e))?;
                 println!("[DEBUG] Wrote source markers file after .ot reintegration");
             }
         }
@@ -8243,6 +11111,179 @@ pub fn copy_sample_slots(
     Ok(CopySlotsResult { shared_files_kept })
 }
 
+/// One slot to carry over in [`copy_slot_assignments`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlotCopyRequest {
+    pub slot_type: String, // "static" or "flex"
+    pub source_slot: u8,   // 1-128
+    pub dest_slot: u8,     // 1-128
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlotCopyOutcome {
+    pub slot_type: String,
+    pub source_slot: u8,
+    pub dest_slot: u8,
+    pub path: Option<String>,  // slot path in the destination
+    pub action: String,        // "linked" (reachable as-is), "copied", "converted", "missing"
+    pub error: Option<String>, // conversion failure (the copied file is kept as-is)
+}
+
+/// Copy slot configurations (path, gain, timestretch) from one project to
+/// another, to rebuild a kit quickly. A sample the destination can already
+/// reach through the same relative path (e.g. the shared Audio Pool of the
+/// same Set) is linked; any other is copied into the destination project, and
+/// converted to an Octatrack-compatible WAV when needed.
+pub fn copy_slot_assignments(
+    source_project: &str,
+    dest_project: &str,
+    slots: &[SlotCopyRequest],
+) -> Result<Vec<SlotCopyOutcome>, String> {
+    if source_project == dest_project {
+        return Err("Source and destination must be different projects".to_string());
+    }
+    let source_path = Path::new(source_project);
+    let dest_path = Path::new(dest_project);
+    let source_file = if source_path.join("project.work").exists() {
+        source_path.join("project.work")
+    } else if source_path.join("project.strd").exists() {
+        source_path.join("project.strd")
+    } else {
+        return Err("Source project file not found".to_string());
+    };
+    let source_data = ProjectFile::from_data_file(&source_file)
+        .map_err(|e| format!("Failed to read source project: {:?}", e))?;
+
+    // Decide per slot: link, copy, or nothing to do (missing file).
+    let mut outcomes = Vec::new();
+    // (slot_type, audio_mode) -> (source ids, dest ids)
+    let mut groups: std::collections::BTreeMap<(String, &str), (Vec<u8>, Vec<u8>)> =
+        std::collections::BTreeMap::new();
+    for slot in slots {
+        let slot_type = slot.slot_type.to_lowercase();
+        if slot_type != "static" && slot_type != "flex" {
+            return Err(format!(
+                "Invalid slot_type: {}. Must be 'static' or 'flex'",
+                slot.slot_type
+            ));
+        }
+        if !(1..=128).contains(&slot.source_slot) || !(1..=128).contains(&slot.dest_slot) {
+            return Err("Slot indices must be between 1 and 128".to_string());
+        }
+        let pool = if slot_type == "static" {
+            &source_data.slots.static_slots
+        } else {
+            &source_data.slots.flex_slots
+        };
+        let rel = pool
+            .get((slot.source_slot - 1) as usize)
+            .and_then(|s| s.as_ref())
+            .and_then(|s| s.path.as_ref())
+            .map(|p| p.to_string_lossy().to_string())
+            .filter(|p| !p.is_empty());
+
+        let mut outcome = SlotCopyOutcome {
+            slot_type: slot_type.clone(),
+            source_slot: slot.source_slot,
+            dest_slot: slot.dest_slot,
+            path: rel.clone(),
+            action: "missing".to_string(),
+            error: None,
+        };
+        let mode = match &rel {
+            Some(rel) if source_path.join(rel).is_file() => {
+                let from_source = normalize_path_lexically(&source_path.join(rel));
+                let from_dest = normalize_path_lexically(&dest_path.join(rel));
+                if from_source == from_dest {
+                    outcome.action = "linked".to_string();
+                    "mirror"
+                } else {
+                    outcome.action = "copied".to_string();
+                    "copy"
+                }
+            }
+            // Empty slot or missing file: still carry the attributes over.
+            _ => "mirror",
+        };
+        let group = groups.entry((slot_type, mode)).or_default();
+        group.0.push(slot.source_slot);
+        group.1.push(slot.dest_slot);
+        outcomes.push(outcome);
+    }
+
+    let attributes = vec!["gain".to_string(), "timestretch".to_string()];
+    for ((slot_type, mode), (source_ids, dest_ids)) in groups {
+        copy_sample_slots(
+            source_project,
+            dest_project,
+            &slot_type,
+            source_ids,
+            dest_ids,
+            true,
+            mode,
+            true,
+            attributes.clone(),
+        )?;
+    }
+
+    // Copied files land in the destination root under their file name.
+    let dest_file = if dest_path.join("project.work").exists() {
+        dest_path.join("project.work")
+    } else {
+        dest_path.join("project.strd")
+    };
+    // file name -> Ok((new file name, converted)) / Err(conversion error)
+    let mut conversions: std::collections::HashMap<String, Result<(String, bool), String>> =
+        std::collections::HashMap::new();
+    for outcome in outcomes.iter_mut().filter(|o| o.action == "copied") {
+        let Some(file_name) = outcome
+            .path
+            .as_deref()
+            .and_then(|p| Path::new(p).file_name())
+            .map(|n| n.to_string_lossy().to_string())
+        else {
+            continue;
+        };
+        let result = conversions.entry(file_name.clone()).or_insert_with(|| {
+            let copied = dest_path.join(&file_name);
+            if check_audio_compatibility(&copied).compatibility == "compatible" {
+                return Ok((file_name.clone(), false));
+            }
+            crate::audio_pool::convert_pool_file_in_place(&copied, |_, _| {}, None).map(|p| {
+                let name = p
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                (name, true)
+            })
+        });
+        match result {
+            Ok((new_name, was_converted)) => {
+                if *was_converted {
+                    outcome.action = "converted".to_string();
+                }
+                outcome.path = Some(new_name.clone());
+            }
+            Err(e) => {
+                outcome.path = Some(file_name);
+                outcome.error = Some(e.clone());
+            }
+        }
+    }
+    // Conversion may change the extension (kick.mp3 -> kick.wav). Only slots
+    // whose file is now gone are repointed.
+    let renames: Vec<(String, String)> = conversions
+        .iter()
+        .filter_map(|(old, result)| match result {
+            Ok((new, _)) if new != old => Some((old.clone(), new.clone())),
+            _ => None,
+        })
+        .collect();
+    update_project_file_paths_surgical(&dest_file, &renames, dest_path, true)?;
+
+    Ok(outcomes)
+}
+
 /// Handle audio file operations (mirror/copy/move_to_pool) for a single slot.
 #[allow(clippy::too_many_arguments)]
 fn handle_audio_file(
@@ -8637,6 +11678,9 @@ mod tests {
             assert!(s(6).recorder_oneshot);
             assert!(!s(5).recorder_oneshot && !s(8).recorder_oneshot);
             assert!(!s(4).recorder);
+            assert_eq!(s(5).recorder_sources, ["INAB", "INCD", "SRC3"]);
+            assert_eq!(s(8).recorder_sources, ["SRC3"]);
+            assert!(s(4).recorder_sources.is_empty());
         }
 
         #[test]
@@ -8701,6 +11745,118 @@ mod tests {
             assert_eq!(total, 0, "untrigged factory defaults must not be reported");
         }
 
+        #[test]
+        fn purge_unused_slots_clears_only_unreferenced_samples() {
+            let project = TestProject::with_modified_bank(0, |bank| {
+                let part = &mut bank.parts.unsaved.0[0];
+                part.audio_track_machine_types[0] = 1;
+                part.audio_track_machine_slots[0].flex_slot_id = 5;
+                bank.patterns.0[0].audio_track_trigs.0[0].trig_masks.trigger =
+                    [0, 1, 0, 0, 0, 0, 0, 0];
+            });
+            let project_path = Path::new(&project.path).join("project.work");
+            let mut pf = ProjectFile::from_data_file(&project_path).unwrap();
+            for (slot_type, idx, file) in [
+                (ot_tools_io::settings::SlotType::Flex, 5, "used.wav"),
+                (ot_tools_io::settings::SlotType::Flex, 6, "unused.wav"),
+                (ot_tools_io::settings::SlotType::Static, 3, "idle.wav"),
+            ] {
+                let is_flex = matches!(slot_type, ot_tools_io::settings::SlotType::Flex);
+                let slot = ot_tools_io::projects::SlotAttributes::new(
+                    slot_type,
+                    (idx + 1) as u8,
+                    Some(std::path::PathBuf::from(file)),
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                )
+                .unwrap();
+                if is_flex {
+                    pf.slots.flex_slots[idx] = Some(slot);
+                } else {
+                    pf.slots.static_slots[idx] = Some(slot);
+                }
+            }
+            pf.to_data_file(&project_path).unwrap();
+
+            let preview = purge_unused_slots(&project.path, true).unwrap();
+            assert!(!preview.purged);
+            let listed: Vec<_> = preview
+                .slots
+                .iter()
+                .map(|s| (s.slot_type.as_str(), s.slot_id))
+                .collect();
+            assert_eq!(listed, vec![("Static", 4), ("Flex", 7)]);
+            let pf = ProjectFile::from_data_file(&project_path).unwrap();
+            assert!(pf.slots.flex_slots[6].as_ref().unwrap().path.is_some());
+
+            let result = purge_unused_slots(&project.path, false).unwrap();
+            assert!(result.purged);
+            let pf = ProjectFile::from_data_file(&project_path).unwrap();
+            assert!(!pf.slots.flex_slots[6]
+                .as_ref()
+                .is_some_and(|s| s.path.is_some()));
+            assert!(!pf.slots.static_slots[3]
+                .as_ref()
+                .is_some_and(|s| s.path.is_some()));
+            assert!(pf.slots.flex_slots[5].as_ref().unwrap().path.is_some());
+            assert!(find_unused_slots(&project.path).unwrap().is_empty());
+        }
+
+        #[test]
+        fn sample_references_by_slot_and_by_file() {
+            let project = TestProject::with_modified_bank(1, |bank| {
+                let part = &mut bank.parts.unsaved.0[2];
+                part.audio_track_machine_types[0] = 1;
+                part.audio_track_machine_slots[0].flex_slot_id = 4;
+                bank.patterns.0[5].part_assignment = 2;
+                bank.patterns.0[9].part_assignment = 2;
+                bank.patterns.0[5].audio_track_trigs.0[0].trig_masks.trigger =
+                    [0, 1, 0, 0, 0, 0, 0, 0];
+                // Sample lock on pattern 10, track 1, step 3 (same flex track)
+                bank.patterns.0[9].scale.master_len = 16;
+                bank.patterns.0[9].audio_track_trigs.0[0].plocks.0[2].flex_slot_id = 4;
+            });
+            let project_path = Path::new(&project.path).join("project.work");
+            let mut pf = ProjectFile::from_data_file(&project_path).unwrap();
+            pf.slots.flex_slots[4] = Some(
+                ot_tools_io::projects::SlotAttributes::new(
+                    ot_tools_io::settings::SlotType::Flex,
+                    5,
+                    Some(std::path::PathBuf::from("../AUDIO/pad.wav")),
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                )
+                .unwrap(),
+            );
+            pf.to_data_file(&project_path).unwrap();
+
+            let refs = find_sample_references(&project.path, Some("flex"), Some(5), None).unwrap();
+            let machine = refs
+                .iter()
+                .find(|r| r.kind == "machine" && r.bank == 1)
+                .unwrap();
+            assert_eq!(machine.part, Some(2));
+            assert_eq!(machine.patterns, vec![5, 9]);
+            let lock = refs.iter().find(|r| r.kind == "lock").unwrap();
+            assert_eq!((lock.bank, lock.pattern, lock.step), (1, Some(9), Some(2)));
+
+            let by_file =
+                find_sample_references(&project.path, None, None, Some("../AUDIO/pad.wav"))
+                    .unwrap();
+            assert_eq!(by_file.len(), refs.len());
+            assert!(by_file
+                .iter()
+                .all(|r| r.slot_type == "Flex" && r.slot_id == 5));
+
+            assert!(find_sample_references(&project.path, None, None, None).is_err());
+        }
+
         #[test]
         fn default_assignment_counts_once_trigged() {
             // The factory default (static machine, slot == track) is reported
@@ -9256,6 +12412,105 @@ mod tests {
         }
     }
 
+    // ==================== TRANSFER BANK TESTS ====================
+
+    mod bank_transfer_tests {
+        use super::*;
+
+        fn assign_static_slot(project: &TestProject, slot_idx: usize, path: &str) {
+            let project_path = Path::new(&project.path).join("project.work");
+            let mut pf = ProjectFile::from_data_file(&project_path).unwrap();
+            let slot = ot_tools_io::projects::SlotAttributes::new(
+                ot_tools_io::settings::SlotType::Static,
+                (slot_idx + 1) as u8,
+                Some(std::path::PathBuf::from(path)),
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+            pf.slots.static_slots[slot_idx] = Some(slot);
+            pf.to_data_file(&project_path).unwrap();
+        }
+
+        #[test]
+        fn test_transfer_bank_refuses_to_overwrite_used_bank() {
+            let source = TestProject::with_modified_bank(0, |bank| {
+                bank.parts_edited_bitmask = 0b0011;
+            });
+            let dest = TestProject::with_modified_bank(2, |bank| {
+                bank.patterns.0[0].audio_track_trigs.0[0].trig_masks.trigger[0] = 1;
+            });
+
+            let check = check_bank_transfer(&source.path, 0, &dest.path, 2).unwrap();
+            assert!(check.dest_bank_in_use);
+
+            let result = transfer_bank(&source.path, 0, &dest.path, 2, false, false);
+            assert!(
+                result.is_err(),
+                "Used destination bank must not be replaced"
+            );
+            assert_eq!(source_bank_data(&dest.path, 2).parts_edited_bitmask, 0);
+
+            transfer_bank(&source.path, 0, &dest.path, 2, true, false).unwrap();
+            assert_eq!(source_bank_data(&dest.path, 2).parts_edited_bitmask, 0b0011);
+            // Copy leaves the source untouched
+            assert_eq!(
+                source_bank_data(&source.path, 0).parts_edited_bitmask,
+                0b0011
+            );
+        }
+
+        #[test]
+        fn test_transfer_bank_move_resets_source() {
+            let source = TestProject::with_modified_bank(1, |bank| {
+                bank.parts_edited_bitmask = 0b0100;
+            });
+            let dest = TestProject::new();
+
+            let check = transfer_bank(&source.path, 1, &dest.path, 5, false, true).unwrap();
+            assert!(!check.dest_bank_in_use);
+            assert_eq!(source_bank_data(&dest.path, 5).parts_edited_bitmask, 0b0100);
+            assert_eq!(source_bank_data(&source.path, 1).parts_edited_bitmask, 0);
+        }
+
+        #[test]
+        fn test_transfer_bank_same_bank_rejected() {
+            let project = TestProject::new();
+            assert!(transfer_bank(&project.path, 3, &project.path, 3, true, false).is_err());
+        }
+
+        #[test]
+        fn test_check_bank_transfer_reports_slot_mismatches() {
+            let source = TestProject::with_modified_bank(0, |bank| {
+                bank.parts.unsaved.0[0].audio_track_machine_types[0] = 0; // Static
+                bank.parts.unsaved.0[0].audio_track_machine_slots[0].static_slot_id = 4;
+                bank.parts.unsaved.0[0].audio_track_machine_types[1] = 0; // Static
+                bank.parts.unsaved.0[0].audio_track_machine_slots[1].static_slot_id = 5;
+            });
+            assign_static_slot(&source, 4, "../AUDIO/kick.wav");
+            assign_static_slot(&source, 5, "../AUDIO/snare.wav");
+            let dest = TestProject::new();
+            assign_static_slot(&dest, 4, "../AUDIO/kick.wav");
+            assign_static_slot(&dest, 5, "../AUDIO/clap.wav");
+
+            let check = check_bank_transfer(&source.path, 0, &dest.path, 0).unwrap();
+            assert_eq!(
+                check.slot_mismatches.len(),
+                1,
+                "{:?}",
+                check.slot_mismatches
+            );
+            let mismatch = &check.slot_mismatches[0];
+            assert_eq!(mismatch.slot_type, "static");
+            assert_eq!(mismatch.slot_id, 6);
+            assert_eq!(mismatch.source_file.as_deref(), Some("snare.wav"));
+            assert_eq!(mismatch.dest_file.as_deref(), Some("clap.wav"));
+        }
+    }
+
     // ==================== BANK REMAP TESTS ====================
 
     mod bank_remap_tests {
@@ -10887,6 +14142,58 @@ mod tests {
         }
     }
 
+    mod copy_pattern_tests {
+        use super::*;
+
+        #[test]
+        fn test_copy_pattern_copies_audio_midi_and_scale() {
+            let source = TestProject::with_modified_bank(2, |bank| {
+                let pattern = &mut bank.patterns.0[4];
+                pattern.part_assignment = 1;
+                pattern.scale.master_len = 32;
+                pattern.audio_track_trigs.0[1].trig_masks.trigger = [0, 0, 0, 0, 0, 0, 0, 1];
+                pattern.midi_track_trigs.0[3].trig_masks.trigger = [0, 0, 0, 0, 0, 0, 0, 16];
+            });
+            let dest = TestProject::new();
+
+            copy_pattern(&source.path, 2, 4, &dest.path, 7, 9, None).unwrap();
+
+            let dest_bank = source_bank_data(&dest.path, 7);
+            let pasted = &dest_bank.patterns.0[9];
+            assert_eq!(pasted.part_assignment, 1, "source part kept by default");
+            assert_eq!(pasted.scale.master_len, 32);
+            assert_eq!(
+                pasted.audio_track_trigs.0[1].trig_masks.trigger,
+                [0, 0, 0, 0, 0, 0, 0, 1]
+            );
+            assert_eq!(
+                pasted.midi_track_trigs.0[3].trig_masks.trigger,
+                [0, 0, 0, 0, 0, 0, 0, 16]
+            );
+            assert_eq!(dest_bank.checksum, dest_bank.calculate_checksum().unwrap());
+        }
+
+        #[test]
+        fn test_copy_pattern_rewrites_part_assignment() {
+            let source = TestProject::with_modified_bank(0, |bank| {
+                bank.patterns.0[0].part_assignment = 0;
+            });
+
+            copy_pattern(&source.path, 0, 0, &source.path, 0, 3, Some(2)).unwrap();
+
+            let bank = source_bank_data(&source.path, 0);
+            assert_eq!(bank.patterns.0[3].part_assignment, 2);
+            assert_eq!(bank.patterns.0[0].part_assignment, 0, "source untouched");
+        }
+
+        #[test]
+        fn test_copy_pattern_rejects_invalid_part() {
+            let project = TestProject::new();
+            let result = copy_pattern(&project.path, 0, 0, &project.path, 0, 1, Some(4));
+            assert!(result.is_err());
+        }
+    }
+
     // ==================== COPY TRACKS TESTS ====================
 
     mod copy_tracks_tests {
@@ -12012,6 +15319,114 @@ mod tests {
     mod copy_sample_slots_tests {
         use super::*;
 
+        fn write_wav(path: &Path, sample_rate: u32) {
+            let spec = hound::WavSpec {
+                channels: 1,
+                sample_rate,
+                bits_per_sample: 16,
+                sample_format: hound::SampleFormat::Int,
+            };
+            let mut w = hound::WavWriter::create(path, spec).unwrap();
+            for _ in 0..4800 {
+                w.write_sample(0i16).unwrap();
+            }
+            w.finalize().unwrap();
+        }
+
+        fn slot_with_path(slot_type: SlotType, id: u8, path: &str) -> SlotAttributes {
+            SlotAttributes::new(
+                slot_type,
+                id,
+                Some(std::path::PathBuf::from(path)),
+                None,
+                None,
+                None,
+                Some(90),
+                None,
+            )
+            .unwrap()
+        }
+
+        #[test]
+        fn test_copy_slot_assignments_links_or_copies_and_converts() {
+            let temp = TempDir::new().unwrap();
+            let set = temp.path().join("SET");
+            let other_set = temp.path().join("OTHER");
+            for dir in [
+                set.join("AUDIO"),
+                set.join("SRC"),
+                set.join("DST"),
+                other_set.join("DST"),
+            ] {
+                fs::create_dir_all(dir).unwrap();
+            }
+            write_wav(&set.join("AUDIO").join("kick.wav"), 44100);
+            write_wav(&set.join("SRC").join("snare.wav"), 48000);
+
+            let mut pf = ProjectFile::default();
+            pf.slots.flex_slots[0] = Some(slot_with_path(SlotType::Flex, 1, "../AUDIO/kick.wav"));
+            pf.slots.static_slots[1] = Some(slot_with_path(SlotType::Static, 2, "snare.wav"));
+            pf.to_data_file(&set.join("SRC").join("project.work"))
+                .unwrap();
+            ProjectFile::default()
+                .to_data_file(&set.join("DST").join("project.work"))
+                .unwrap();
+            ProjectFile::default()
+                .to_data_file(&other_set.join("DST").join("project.work"))
+                .unwrap();
+
+            let requests = vec![
+                SlotCopyRequest {
+                    slot_type: "flex".to_string(),
+                    source_slot: 1,
+                    dest_slot: 3,
+                },
+                SlotCopyRequest {
+                    slot_type: "static".to_string(),
+                    source_slot: 2,
+                    dest_slot: 5,
+                },
+            ];
+
+            // Same Set: the pool sample is linked, the project-local one copied
+            // and converted to 44.1 kHz.
+            let dest = set.join("DST");
+            let outcomes = copy_slot_assignments(
+                &set.join("SRC").to_string_lossy(),
+                &dest.to_string_lossy(),
+                &requests,
+            )
+            .unwrap();
+            assert_eq!(outcomes[0].action, "linked");
+            assert_eq!(outcomes[0].path.as_deref(), Some("../AUDIO/kick.wav"));
+            assert_eq!(outcomes[1].action, "converted", "{:?}", outcomes[1]);
+            assert_eq!(outcomes[1].path.as_deref(), Some("snare.wav"));
+            assert_eq!(
+                check_audio_compatibility(&dest.join("snare.wav")).compatibility,
+                "compatible"
+            );
+            let pf = ProjectFile::from_data_file(&dest.join("project.work")).unwrap();
+            let flex = pf.slots.flex_slots[2].as_ref().unwrap();
+            assert_eq!(
+                flex.path.as_ref().unwrap().to_string_lossy(),
+                "../AUDIO/kick.wav"
+            );
+            assert_eq!(flex.gain, 90);
+            assert!(pf.slots.static_slots[4].is_some());
+
+            // Another Set cannot reach ../AUDIO: the pool sample is copied.
+            let dest = other_set.join("DST");
+            let outcomes = copy_slot_assignments(
+                &set.join("SRC").to_string_lossy(),
+                &dest.to_string_lossy(),
+                &requests[..1],
+            )
+            .unwrap();
+            assert_eq!(outcomes[0].action, "copied");
+            assert_eq!(outcomes[0].path.as_deref(), Some("kick.wav"));
+            assert!(dest.join("kick.wav").exists());
+        }
+
         #[test]
         fn test_copy_single_slot() {
             // CSS-01: Copy single slot
@@ -15036,7 +18451,10 @@ mod tests {
             project_file.states.midi_track_solo_mask = 0b0100_0000; // MIDI track 7
             project_file.to_data_file(&project_file_path).unwrap();
 
-            let state = read_project_metadata(&project.path).unwrap().current_state;
+            let state = read_project_metadata(&project.path)
+                .unwrap()
+                .data
+                .current_state;
             assert_eq!(state.audio_muted_tracks, vec![1, 2]);
             assert_eq!(state.audio_soloed_tracks, vec![1, 4]);
             assert_eq!(state.audio_cued_tracks, vec![3]);
@@ -15047,7 +18465,7 @@ mod tests {
         #[test]
         fn test_read_project_metadata_has_current_state() {
             let project = TestProject::new();
-            let metadata = read_project_metadata(&project.path).unwrap();
+            let metadata = read_project_metadata(&project.path).unwrap().data;
 
             // Current state should have valid values
             assert!(metadata.current_state.bank <= 15, "Bank should be 0-15");
@@ -15065,7 +18483,7 @@ mod tests {
         #[test]
         fn test_read_project_metadata_has_mixer_settings() {
             let project = TestProject::new();
-            let metadata = read_project_metadata(&project.path).unwrap();
+            let metadata = read_project_metadata(&project.path).unwrap().data;
 
             // Mixer settings should have valid default values (u8 fields exist and are readable)
             assert!(
@@ -15081,7 +18499,7 @@ mod tests {
         #[test]
         fn test_read_project_metadata_has_sample_slots() {
             let project = TestProject::new();
-            let metadata = read_project_metadata(&project.path).unwrap();
+            let metadata = read_project_metadata(&project.path).unwrap().data;
 
             // Sample slots should be initialized
             assert!(
@@ -15097,7 +18515,7 @@ mod tests {
         #[test]
         fn test_read_project_metadata_time_signature_format() {
             let project = TestProject::new();
-            let metadata = read_project_metadata(&project.path).unwrap();
+            let metadata = read_project_metadata(&project.path).unwrap().data;
 
             // Time signature should be in format "X/Y"
             assert!(
@@ -15111,6 +18529,7 @@ mod tests {
             let project = TestProject::new();
             let ms = read_project_metadata(&project.path)
                 .unwrap()
+                .data
                 .memory_settings;
             // The exact byte figure must truncate to the displayed MiB value (display stays
             // OT-faithful; validation uses the un-truncated bytes).
@@ -15168,6 +18587,31 @@ mod tests {
             assert!(indices.contains(&9), "Should contain index 9 (bank10)");
         }
 
+        #[test]
+        fn test_bank_summary_and_lazy_pattern() {
+            let project = TestProject::with_modified_bank(1, |bank| {
+                bank.patterns.0[6].part_assignment = 2;
+                bank.patterns.0[6].audio_track_trigs.0[0].trig_masks.trigger =
+                    [0, 0, 0, 0, 0, 0, 0, 1];
+            });
+
+            let summary = load_bank_summary(&project.path, 1).unwrap().unwrap();
+            let patterns: Vec<&Pattern> = summary.parts.iter().flat_map(|p| &p.patterns).collect();
+            assert_eq!(patterns.len(), 64);
+            assert!(patterns.iter().all(|p| p.tracks.is_empty()));
+            assert_eq!(summary.parts[2].patterns[6].trig_counts.trigger, 1);
+
+            let pattern = load_pattern(&project.path, 1, 6).unwrap();
+            assert_eq!((pattern.id, pattern.part_assignment), (6, 2));
+            assert_eq!(pattern.tracks.len(), 16);
+            assert!(pattern.tracks[0].steps[0].trigger);
+            let full = read_single_bank(&project.path, 1).unwrap().unwrap();
+            assert_eq!(full.parts[2].patterns[6].tracks.len(), pattern.tracks.len());
+
+            assert!(load_pattern(&project.path, 1, 16).is_err());
+            assert!(load_bank_summary(&project.path, 16).is_err());
+        }
+
         #[test]
         fn test_read_single_bank_success() {
             let project = TestProject::new();
@@ -15275,13 +18719,59 @@ mod tests {
             assert_eq!(slot_of(5), None, "Thru machine has no sample slot");
         }
 
+        #[test]
+        fn test_track_assigned_sample_name() {
+            let project = TestProject::with_modified_bank(0, |bank| {
+                bank.patterns.0[0].part_assignment = 0;
+                bank.parts.unsaved.0[0].audio_track_machine_types[1] = 1;
+                bank.parts.unsaved.0[0].audio_track_machine_slots[1].flex_slot_id = 2;
+                bank.parts.unsaved.0[0].audio_track_machine_types[2] = 0;
+                bank.parts.unsaved.0[0].audio_track_machine_slots[2].static_slot_id = 7;
+            });
+            let project_path = Path::new(&project.path).join("project.work");
+            let mut pf = ProjectFile::from_data_file(&project_path).unwrap();
+            pf.slots.flex_slots[2] = Some(
+                ot_tools_io::projects::SlotAttributes::new(
+                    ot_tools_io::settings::SlotType::Flex,
+                    3,
+                    Some(std::path::PathBuf::from("../AUDIO/loops/break.wav")),
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                )
+                .unwrap(),
+            );
+            pf.to_data_file(&project_path).unwrap();
+
+            let bank = read_single_bank(&project.path, 0).unwrap().unwrap();
+            let pattern = bank
+                .parts
+                .iter()
+                .flat_map(|p| &p.patterns)
+                .find(|p| p.id == 0)
+                .expect("pattern 0");
+            let track = |tid: u8| {
+                pattern
+                    .tracks
+                    .iter()
+                    .find(|t| t.track_id == tid && t.track_type == "Audio")
+                    .unwrap()
+            };
+            assert_eq!(track(1).assigned_slot_type.as_deref(), Some("Flex"));
+            assert_eq!(track(1).assigned_sample_name.as_deref(), Some("break.wav"));
+            assert_eq!(track(2).assigned_slot_type.as_deref(), Some("Static"));
+            assert_eq!(track(2).assigned_sample_name, None, "empty slot");
+        }
+
         #[test]
         fn test_read_project_banks_success() {
             let project = TestProject::new();
             let result = read_project_banks(&project.path);
 
             assert!(result.is_ok(), "Should read all banks: {:?}", result);
-            let banks = result.unwrap();
+            let banks = result.unwrap().data;
             assert_eq!(banks.len(), 16, "Should read all 16 banks");
         }
 
@@ -15291,14 +18781,41 @@ mod tests {
             let result = read_project_banks(&temp_dir.path().to_string_lossy());
 
             assert!(result.is_ok());
-            let banks = result.unwrap();
+            let banks = result.unwrap().data;
             assert!(banks.is_empty(), "Empty project should have no banks");
         }
 
+        #[test]
+        fn test_read_project_banks_reports_unreadable_banks() {
+            let project = TestProject::new();
+            let dir = Path::new(&project.path);
+            std::fs::write(dir.join("bank03.work"), b"not a bank").unwrap();
+            std::fs::write(dir.join("markers.work"), b"not markers").unwrap();
+
+            let result = read_project_banks(&project.path).unwrap();
+            assert_eq!(result.data.len(), 15);
+            assert!(result.data.iter().all(|b| b.id != "C"));
+            let files: Vec<(&str, &str)> = result
+                .warnings
+                .iter()
+                .map(|w| (w.file.as_str(), w.kind.as_str()))
+                .collect();
+            assert_eq!(
+                files,
+                vec![
+                    ("markers.work", "parse_error"),
+                    ("bank03.work", "parse_error")
+                ]
+            );
+
+            // Reading that bank alone still fails outright.
+            assert!(read_single_bank(&project.path, 2).is_err());
+        }
+
         #[test]
         fn test_read_project_banks_has_patterns() {
             let project = TestProject::new();
-            let banks = read_project_banks(&project.path).unwrap();
+            let banks = read_project_banks(&project.path).unwrap().data;
 
             for bank in banks {
                 // Each part should have patterns
@@ -15365,6 +18882,22 @@ mod tests {
             }
         }
 
+        #[test]
+        fn test_read_parts_data_has_recorder_setup() {
+            let project = TestProject::with_modified_bank(0, |bank| {
+                bank.parts.unsaved.0[1].recorder_setup[2].src.src3 = 4;
+                bank.parts.unsaved.0[1].recorder_setup[2].proc.qrec = 7;
+            });
+            let parts_response = read_parts_data(&project.path, "A").unwrap();
+
+            for part in &parts_response.parts {
+                assert_eq!(part.recorders.len(), 8, "Each part should have 8 recorders");
+            }
+            let recorder = &parts_response.parts[1].recorders[2];
+            assert_eq!(recorder.track_id, 2);
+            assert_eq!((recorder.src3, recorder.qrec), (4, 7));
+        }
+
         #[test]
         fn test_read_parts_data_has_amps() {
             let project = TestProject::new();
@@ -15422,7 +18955,7 @@ mod tests {
             let original_parts = read_parts_data(&project.path, "A").unwrap();
 
             // Save the same data back
-            let result = save_parts_data(&project.path, "A", original_parts.parts.clone());
+            let result = save_parts_data(&project.path, "A", original_parts.parts.clone(), true);
             assert!(result.is_ok(), "Should save parts data: {:?}", result);
 
             // Read again and verify
@@ -15430,12 +18963,39 @@ mod tests {
             assert_eq!(reloaded_parts.parts.len(), original_parts.parts.len());
         }
 
+        #[test]
+        fn test_save_parts_data_writes_recorder_setup() {
+            let project = TestProject::new();
+            let mut parts = read_parts_data(&project.path, "A").unwrap().parts;
+            let recorder = &mut parts[3].recorders[5];
+            recorder.in_cd = 2;
+            recorder.rlen = 17;
+            recorder.qrec = 3;
+            save_parts_data(&project.path, "A", parts, true).unwrap();
+
+            let bank = source_bank_data(&project.path, 0);
+            let setup = &bank.parts.unsaved.0[3].recorder_setup[5];
+            assert_eq!(
+                (setup.src.in_cd, setup.src.rlen, setup.proc.qrec),
+                (2, 17, 3)
+            );
+            // Only the working copy is edited, so Reload Part still restores it
+            assert_ne!(bank.parts.saved.0[3].recorder_setup[5].src.rlen, 17);
+
+            // Payloads without recorders leave the setup untouched
+            let mut parts = read_parts_data(&project.path, "A").unwrap().parts;
+            parts.iter_mut().for_each(|p| p.recorders.clear());
+            save_parts_data(&project.path, "A", parts, true).unwrap();
+            let bank = source_bank_data(&project.path, 0);
+            assert_eq!(bank.parts.unsaved.0[3].recorder_setup[5].src.rlen, 17);
+        }
+
         #[test]
         fn test_save_parts_data_invalid_bank() {
             let project = TestProject::new();
             let parts = read_parts_data(&project.path, "A").unwrap();
 
-            let result = save_parts_data(&project.path, "Z", parts.parts);
+            let result = save_parts_data(&project.path, "Z", parts.parts, true);
             assert!(result.is_err());
         }
 
@@ -15533,7 +19093,7 @@ mod tests {
             });
 
             let original = read_parts_data(&project.path, "A").unwrap();
-            save_parts_data(&project.path, "A", original.parts.clone()).unwrap();
+            save_parts_data(&project.path, "A", original.parts.clone(), true).unwrap();
             let reloaded = read_parts_data(&project.path, "A").unwrap();
 
             assert_eq!(reloaded.parts[0].machines[0].machine_type, "Static");
@@ -15564,13 +19124,179 @@ mod tests {
 
     // ==================== COMMIT/RELOAD PARTS TESTS ====================
 
+    mod bank_file_lock_tests {
+        use super::*;
+
+        #[test]
+        fn test_work_and_strd_share_a_lock() {
+            let project = TestProject::new();
+            let dir = Path::new(&project.path);
+            let a = bank_file_lock(&dir.join("bank03.work"));
+            let b = bank_file_lock(&dir.join("bank03.strd"));
+            let c = bank_file_lock(&dir.join("bank04.work"));
+            assert!(Arc::ptr_eq(&a, &b));
+            assert!(!Arc::ptr_eq(&a, &c));
+        }
+
+        #[test]
+        fn test_concurrent_writes_to_one_bank_are_not_lost() {
+            let project = TestProject::new();
+            let names = ["KICK", "SNARE", "HATS", "BASS"];
+
+            std::thread::scope(|scope| {
+                for (part_id, name) in names.iter().enumerate() {
+                    let path = project.path.clone();
+                    scope.spawn(move || {
+                        for _ in 0..10 {
+                            rename_part(&path, "A", part_id as u8, name).unwrap();
+                        }
+                    });
+                }
+            });
+
+            let bank = source_bank_data(&project.path, 0);
+            for (part_id, name) in names.iter().enumerate() {
+                let stored = &bank.part_names[part_id];
+                assert_eq!(&stored[..name.len()], name.as_bytes());
+            }
+        }
+    }
+
     mod commit_reload_tests {
         use super::*;
 
+        #[test]
+        fn test_rename_part_pads_with_nul() {
+            let project = TestProject::new();
+            rename_part(&project.path, "B", 2, "BASS").unwrap();
+
+            let bank = source_bank_data(&project.path, 1);
+            assert_eq!(bank.part_names[2], [b'B', b'A', b'S', b'S', 0, 0, 0]);
+        }
+
+        #[test]
+        fn test_rename_part_truncates_long_names() {
+            let project = TestProject::with_modified_bank(0, |bank| {
+                bank.part_names[0] = [b'O', b'L', b'D', 0, 0, 0, 0];
+            });
+            rename_part(&project.path, "A", 0, "VERYLONGNAME").unwrap();
+
+            let bank = source_bank_data(&project.path, 0);
+            assert_eq!(bank.part_names[0], *b"VERYLON");
+        }
+
+        #[test]
+        fn test_rename_part_rejects_invalid_input() {
+            let project = TestProject::new();
+            assert!(rename_part(&project.path, "A", 4, "X").is_err());
+            assert!(rename_part(&project.path, "Q", 0, "X").is_err());
+            assert!(rename_part(&project.path, "A", 0, "CAFÉ").is_err());
+        }
+
+        #[test]
+        fn test_init_part_resets_unsaved_only() {
+            let project = TestProject::with_modified_bank(2, |bank| {
+                for parts_state in [&mut bank.parts.unsaved, &mut bank.parts.saved] {
+                    parts_state.0[1].audio_track_machine_types[3] = 1;
+                    parts_state.0[1].audio_track_machine_slots[3].flex_slot_id = 9;
+                }
+            });
+
+            let part = init_part(&project.path, "C", 1).unwrap();
+            assert_eq!(part.part_id, 1);
+
+            let bank = source_bank_data(&project.path, 2);
+            let default_part = BankFile::default().parts.unsaved.0[1];
+            assert_eq!(
+                bank.parts.unsaved.0[1].audio_track_machine_types,
+                default_part.audio_track_machine_types
+            );
+            assert_eq!(
+                bank.parts.unsaved.0[1].audio_track_machine_slots[3].flex_slot_id,
+                default_part.audio_track_machine_slots[3].flex_slot_id
+            );
+            assert_eq!(bank.parts.saved.0[1].audio_track_machine_types[3], 1);
+            assert_eq!(bank.parts_edited_bitmask, 0b0010);
+
+            assert!(init_part(&project.path, "C", 4).is_err());
+            assert!(init_part(&project.path, "Q", 0).is_err());
+        }
+
+        #[test]
+        fn test_copy_track_setup_to_other_tracks() {
+            let project = TestProject::with_modified_bank(0, |bank| {
+                let part = &mut bank.parts.unsaved.0[0];
+                part.audio_track_machine_types[0] = 1;
+                part.audio_track_machine_slots[0].flex_slot_id = 5;
+                part.audio_track_fx1[0] = 4; // FILTER
+                part.audio_track_fx1[5] = 8;
+            });
+
+            copy_track_setup(&project.path, "A", 0, 0, 0, &[2, 3]).unwrap();
+            copy_track_setup(&project.path, "A", 0, 0, 3, &[7]).unwrap();
+
+            let bank = source_bank_data(&project.path, 0);
+            let part = &bank.parts.unsaved.0[0];
+            for t in [2, 3] {
+                assert_eq!(part.audio_track_machine_types[t], 1);
+                assert_eq!(part.audio_track_machine_slots[t].flex_slot_id, 5);
+                assert_eq!(part.audio_track_fx1[t], 4);
+            }
+            assert_eq!(part.audio_track_fx1[5], 8);
+            assert_eq!(bank.parts.unsaved.0[3].audio_track_machine_types[7], 1);
+            assert_ne!(bank.parts.saved.0[0].audio_track_machine_types[2], 1);
+            assert_eq!(bank.parts_edited_bitmask, 0b1001);
+
+            assert!(copy_track_setup(&project.path, "A", 0, 0, 0, &[8]).is_err());
+            assert!(copy_track_setup(&project.path, "A", 0, 0, 0, &[]).is_err());
+            assert!(copy_track_setup(&project.path, "A", 4, 0, 0, &[1]).is_err());
+        }
+
+        #[test]
+        fn test_apply_fx_preset_across_banks() {
+            let project = TestProject::new();
+            let mut fx = read_parts_data(&project.path, "A").unwrap().parts[0].fxs[0].clone();
+            fx.fx1_type = 4; // FILTER
+            fx.fx2_type = 12;
+            fx.fx1_param2 = 90;
+            fx.fx2_setup3 = 7;
+            let targets: Vec<FxPresetTarget> = [("A", 0), ("A", 2), ("D", 1)]
+                .iter()
+                .map(|&(bank_id, part_id)| FxPresetTarget {
+                    bank_id: bank_id.to_string(),
+                    part_id,
+                    track_id: 7,
+                })
+                .collect();
+
+            assert_eq!(apply_fx_preset(&project.path, &fx, &targets).unwrap(), 3);
+
+            let bank_a = source_bank_data(&project.path, 0);
+            for part in [0, 2] {
+                let written = &read_parts_data(&project.path, "A").unwrap().parts[part].fxs[7];
+                assert_eq!((written.fx1_type, written.fx2_type), (4, 12));
+                assert_eq!((written.fx1_param2, written.fx2_setup3), (90, 7));
+            }
+            assert_eq!(bank_a.parts_edited_bitmask, 0b0101);
+            assert_eq!(
+                bank_a.parts.unsaved.0[1].audio_track_fx1[7],
+                bank_a.parts.saved.0[1].audio_track_fx1[7]
+            );
+            let bank_d = source_bank_data(&project.path, 3);
+            assert_eq!(bank_d.parts.unsaved.0[1].audio_track_fx1[7], 4);
+
+            let bad = FxPresetTarget {
+                bank_id: "A".to_string(),
+                part_id: 0,
+                track_id: 8,
+            };
+            assert!(apply_fx_preset(&project.path, &fx, &[bad]).is_err());
+        }
+
         #[test]
         fn test_commit_part_data_success() {
             let project = TestProject::new();
-            let result = commit_part_data(&project.path, "A", 0);
+            let result = commit_part_data(&project.path, "A", 0, true);
 
             assert!(result.is_ok(), "Should commit part data: {:?}", result);
         }
@@ -15580,7 +19306,7 @@ mod tests {
             let project = TestProject::new();
 
             for part_id in 0..4u8 {
-                let result = commit_part_data(&project.path, "A", part_id);
+                let result = commit_part_data(&project.path, "A", part_id, true);
                 assert!(
                     result.is_ok(),
                     "Should commit part {}: {:?}",
@@ -15593,16 +19319,16 @@ mod tests {
         #[test]
         fn test_commit_part_data_invalid_part() {
             let project = TestProject::new();
-            let result = commit_part_data(&project.path, "A", 4);
+            let result = commit_part_data(&project.path, "A", 4, true);
 
             assert!(result.is_err());
-            assert!(result.unwrap_err().contains("Invalid part ID"));
+            assert!(result.unwrap_err().message.contains("Invalid part ID"));
         }
 
         #[test]
         fn test_commit_part_data_invalid_bank() {
             let project = TestProject::new();
-            let result = commit_part_data(&project.path, "Z", 0);
+            let result = commit_part_data(&project.path, "Z", 0, true);
 
             assert!(result.is_err());
         }
@@ -15610,7 +19336,7 @@ mod tests {
         #[test]
         fn test_commit_all_parts_data_success() {
             let project = TestProject::new();
-            let result = commit_all_parts_data(&project.path, "A");
+            let result = commit_all_parts_data(&project.path, "A", true);
 
             assert!(result.is_ok(), "Should commit all parts: {:?}", result);
         }
@@ -15623,7 +19349,7 @@ mod tests {
             ];
 
             for bank_id in bank_ids {
-                let result = commit_all_parts_data(&project.path, bank_id);
+                let result = commit_all_parts_data(&project.path, bank_id, true);
                 assert!(
                     result.is_ok(),
                     "Should commit all parts for bank {}: {:?}",
@@ -15638,9 +19364,9 @@ mod tests {
             let project = TestProject::new();
 
             // Must commit the part first before it can be reloaded
-            commit_part_data(&project.path, "A", 0).unwrap();
+            commit_part_data(&project.path, "A", 0, true).unwrap();
 
-            let result = reload_part_data(&project.path, "A", 0);
+            let result = reload_part_data(&project.path, "A", 0, true);
             assert!(result.is_ok(), "Should reload part data: {:?}", result);
         }
 
@@ -15649,9 +19375,9 @@ mod tests {
             let project = TestProject::new();
 
             // Must commit the part first before it can be reloaded
-            commit_part_data(&project.path, "A", 0).unwrap();
+            commit_part_data(&project.path, "A", 0, true).unwrap();
 
-            let part_data = reload_part_data(&project.path, "A", 0).unwrap();
+            let part_data = reload_part_data(&project.path, "A", 0, true).unwrap();
             assert_eq!(part_data.part_id, 0, "Should return correct part ID");
             assert_eq!(part_data.machines.len(), 8, "Should have 8 machines");
         }
@@ -15660,19 +19386,19 @@ mod tests {
         fn test_reload_part_data_requires_saved_state() {
             // Reload requires the part to have been saved first
             let project = TestProject::new();
-            let result = reload_part_data(&project.path, "A", 0);
+            let result = reload_part_data(&project.path, "A", 0, true);
 
             // Should fail because part hasn't been committed/saved
             assert!(result.is_err());
-            assert!(result.unwrap_err().contains("SAVE PART FIRST"));
+            assert!(result.unwrap_err().message.contains("SAVE PART FIRST"));
         }
 
         #[test]
         fn test_reload_part_data_invalid_part() {
             let project = TestProject::new();
-            commit_part_data(&project.path, "A", 0).unwrap();
+            commit_part_data(&project.path, "A", 0, true).unwrap();
 
-            let result = reload_part_data(&project.path, "A", 4);
+            let result = reload_part_data(&project.path, "A", 4, true);
             assert!(result.is_err());
         }
     }
@@ -18192,17 +21918,99 @@ mod tests {
             )]);
             write_raw_project_work(&set.join("PROJ"), &content);
 
-            let pool = set.join("AUDIO");
-            let renames = vec![(
-                pool.join("kick.mp3").to_string_lossy().to_string(),
-                pool.join("kick.wav").to_string_lossy().to_string(),
-            )];
-            let res = update_pool_references(&pool.to_string_lossy(), &renames).unwrap();
+            let pool = set.join("AUDIO");
+            let renames = vec![(
+                pool.join("kick.mp3").to_string_lossy().to_string(),
+                pool.join("kick.wav").to_string_lossy().to_string(),
+            )];
+            let res = update_pool_references(&pool.to_string_lossy(), &renames).unwrap();
+
+            assert_eq!(res.slots_updated, 0);
+            assert!(res.projects_updated.is_empty());
+            assert_eq!(read_raw_project_work(&set.join("PROJ")), content);
+            assert!(!set.join("PROJ").join("backups").exists(), "no backup made");
+        }
+    }
+
+    mod consolidate_samples_tests {
+        use super::surgical_write_tests::{
+            create_raw_project_work_with_custom_fields, read_raw_project_work,
+            write_raw_project_work,
+        };
+        use super::*;
+
+        fn setup_set() -> (TempDir, std::path::PathBuf) {
+            let temp = TempDir::new().unwrap();
+            let set = temp.path();
+            fs::create_dir_all(set.join("AUDIO").join("drums")).unwrap();
+            fs::create_dir(set.join("AUDIO").join("more")).unwrap();
+            fs::create_dir(set.join("PROJ")).unwrap();
+            fs::write(set.join("AUDIO/drums/kick.wav"), b"kick-a").unwrap();
+            fs::write(set.join("AUDIO/more/kick.wav"), b"kick-b").unwrap();
+            fs::write(set.join("PROJ/local.wav"), b"local").unwrap();
+
+            let content = create_raw_project_work_with_custom_fields(&[
+                ("FLEX", 1, "../AUDIO/drums/kick.wav", Some(3408), None, None),
+                ("STATIC", 1, "../AUDIO/drums/kick.wav", None, None, None),
+                ("STATIC", 2, "../AUDIO/more/kick.wav", None, None, None),
+                ("STATIC", 3, "../AUDIO/gone.wav", None, None, None),
+                ("FLEX", 2, "local.wav", None, None, None),
+            ]);
+            write_raw_project_work(&set.join("PROJ"), &content);
+            let project = set.join("PROJ");
+            (temp, project)
+        }
+
+        #[test]
+        fn dry_run_plans_without_writing() {
+            let (_temp, project) = setup_set();
+            let before = read_raw_project_work(&project);
+
+            let res = consolidate_samples(&project.to_string_lossy(), true, |_, _| {}).unwrap();
+
+            assert!(res.dry_run);
+            assert_eq!(res.files_copied, 2);
+            assert_eq!(res.slots_updated, 3);
+            let missing: Vec<_> = res
+                .entries
+                .iter()
+                .filter(|e| e.action == "missing")
+                .collect();
+            assert_eq!(missing.len(), 1);
+            assert_eq!(missing[0].slot_id, 3);
+            assert_eq!(read_raw_project_work(&project), before);
+            assert!(!project.join("kick.wav").exists());
+        }
+
+        #[test]
+        fn copies_once_renames_clashes_and_repoints_slots() {
+            let (_temp, project) = setup_set();
+            let mut calls = 0;
+
+            let res =
+                consolidate_samples(&project.to_string_lossy(), false, |_, _| calls += 1).unwrap();
+
+            assert_eq!(res.files_copied, 2);
+            assert_eq!(res.slots_updated, 3);
+            assert_eq!(calls, 3, "one event per file plus completion");
+            assert_eq!(fs::read(project.join("kick.wav")).unwrap(), b"kick-a");
+            assert_eq!(fs::read(project.join("kick_2.wav")).unwrap(), b"kick-b");
 
+            let out = read_raw_project_work(&project);
+            assert!(!out.contains("PATH=../AUDIO/drums/kick.wav"));
+            assert!(out.contains("PATH=kick_2.wav"));
+            assert!(
+                out.contains("PATH=../AUDIO/gone.wav"),
+                "missing file untouched"
+            );
+            assert!(out.contains("PATH=local.wav"));
+            assert!(out.contains("BPMx24=3408"), "other fields preserved");
+            assert!(project.join("backups").exists());
+
+            // Running again finds nothing left to collect
+            let res = consolidate_samples(&project.to_string_lossy(), false, |_, _| {}).unwrap();
+            assert_eq!(res.files_copied, 0);
             assert_eq!(res.slots_updated, 0);
-            assert!(res.projects_updated.is_empty());
-            assert_eq!(read_raw_project_work(&set.join("PROJ")), content);
-            assert!(!set.join("PROJ").join("backups").exists(), "no backup made");
         }
     }
 
@@ -19557,6 +23365,41 @@ mod tests {
         assert!(err.contains("not found"), "got: {}", err);
     }
 
+    #[test]
+    fn test_save_memory_settings_rejects_what_does_not_fit() {
+        let project = TestProject::new();
+        let settings = |count: u8, length: u32| MemorySettings {
+            load_24bit_flex: false,
+            dynamic_recorders: false,
+            record_24bit: true,
+            reserved_recorder_count: count,
+            reserved_recorder_length: length,
+            flex_ram_free_mb: 0.0,
+            flex_ram_free_bytes: 0,
+        };
+        let before = fs::read(Path::new(&project.path).join("project.work")).unwrap();
+
+        let err = save_memory_settings_data(&project.path, settings(9, 1)).unwrap_err();
+        assert!(err.contains("0-8"), "got: {}", err);
+        // 8 x 120 s of 24-bit stereo is ~242 MiB, far beyond the 85.5 MiB of RAM
+        let check = check_memory_settings(&project.path, &settings(8, 120));
+        assert_eq!(check.flex_ram_capacity_bytes, 0);
+        assert_eq!(check.errors.len(), 1);
+        assert!(save_memory_settings_data(&project.path, settings(8, 120)).is_err());
+        assert_eq!(
+            fs::read(Path::new(&project.path).join("project.work")).unwrap(),
+            before,
+            "rejected settings must not be written"
+        );
+
+        let check = check_memory_settings(&project.path, &settings(8, 16));
+        assert!(check.errors.is_empty(), "{:?}", check.errors);
+        assert_eq!(
+            check.recorder_ram_bytes + check.flex_ram_capacity_bytes,
+            OT_TOTAL_RAM_BYTES
+        );
+    }
+
     #[test]
     fn test_save_memory_settings_strd_fallback() {
         let project = TestProject::new();
@@ -19589,6 +23432,164 @@ mod tests {
         assert_eq!(reread.reserved_recorder_length, 50);
     }
 
+    // ============================================================================
+    // save_midi_settings_data tests
+    // ============================================================================
+
+    #[test]
+    fn test_save_midi_settings_roundtrip() {
+        let project = TestProject::new();
+        let settings = MidiSettings {
+            trig_channels: vec![9, 8, 7, 6, 5, 4, 3, -1],
+            auto_channel: 15,
+            clock_send: true,
+            clock_receive: false,
+            transport_send: true,
+            transport_receive: false,
+            prog_change_send: true,
+            prog_change_send_channel: 3,
+            prog_change_receive: true,
+            prog_change_receive_channel: -1,
+        };
+        save_midi_settings_data(&project.path, settings).expect("should save");
+
+        let reread = read_project_metadata(&project.path)
+            .expect("should re-read")
+            .data
+            .midi_settings;
+        assert_eq!(reread.trig_channels, vec![9, 8, 7, 6, 5, 4, 3, -1]);
+        assert_eq!(reread.auto_channel, 15);
+        assert!(reread.clock_send && !reread.clock_receive);
+        assert!(reread.transport_send && !reread.transport_receive);
+        assert!(reread.prog_change_send && reread.prog_change_receive);
+        assert_eq!(reread.prog_change_send_channel, 3);
+        assert_eq!(reread.prog_change_receive_channel, -1);
+    }
+
+    #[test]
+    fn test_save_midi_settings_keeps_nonstandard_flag_values() {
+        let project = TestProject::new();
+        let project_file = Path::new(&project.path).join("project.work");
+        let settings = MidiSettings {
+            clock_send: true,
+            ..read_project_metadata(&project.path)
+                .unwrap()
+                .data
+                .midi_settings
+        };
+        replace_settings_fields_surgical(&project_file, &[("MIDI_CLOCK_SEND", "2".to_string())])
+            .unwrap();
+
+        save_midi_settings_data(&project.path, settings.clone()).unwrap();
+        assert_eq!(
+            read_settings_field(&project_file, "MIDI_CLOCK_SEND").as_deref(),
+            Some("2")
+        );
+
+        let invalid = MidiSettings {
+            auto_channel: 16,
+            ..settings
+        };
+        assert!(save_midi_settings_data(&project.path, invalid).is_err());
+    }
+
+    // ============================================================================
+    // save_mixer_settings_data tests
+    // ============================================================================
+
+    #[test]
+    fn test_save_mixer_settings_roundtrip() {
+        let project = TestProject::new();
+        let settings = MixerSettings {
+            gain_ab: 80,
+            gain_cd: 20,
+            dir_ab: 127,
+            dir_cd: 0,
+            phones_mix: 96,
+            main_level: 70,
+            cue_level: 10,
+        };
+        save_mixer_settings_data(&project.path, settings).expect("should save");
+
+        let reread = read_project_metadata(&project.path)
+            .expect("should re-read")
+            .data
+            .mixer_settings;
+        assert_eq!((reread.gain_ab, reread.gain_cd), (80, 20));
+        assert_eq!((reread.dir_ab, reread.dir_cd), (127, 0));
+        assert_eq!(reread.phones_mix, 96);
+        assert_eq!((reread.main_level, reread.cue_level), (70, 10));
+
+        let too_loud = MixerSettings {
+            main_level: 128,
+            ..reread
+        };
+        let err = save_mixer_settings_data(&project.path, too_loud).unwrap_err();
+        assert!(err.contains("MAIN_LEVEL"), "got: {}", err);
+    }
+
+    // ============================================================================
+    // save_metronome_settings_data tests
+    // ============================================================================
+
+    #[test]
+    fn test_save_metronome_settings_roundtrip() {
+        let project = TestProject::new();
+        let settings = MetronomeSettings {
+            enabled: true,
+            main_volume: 40,
+            cue_volume: 100,
+            pitch: 7,
+            tonal: false,
+            preroll: 2,
+            time_signature_numerator: 7,
+            time_signature_denominator: 8,
+        };
+        save_metronome_settings_data(&project.path, settings).expect("should save");
+
+        let metadata = read_project_metadata(&project.path)
+            .expect("should re-read")
+            .data;
+        let reread = metadata.metronome_settings;
+        assert!(reread.enabled && !reread.tonal);
+        assert_eq!((reread.main_volume, reread.cue_volume), (40, 100));
+        assert_eq!((reread.pitch, reread.preroll), (7, 2));
+        assert_eq!(
+            (
+                reread.time_signature_numerator,
+                reread.time_signature_denominator
+            ),
+            (7, 8)
+        );
+        assert_eq!(metadata.time_signature, "7/8");
+    }
+
+    #[test]
+    fn test_save_metronome_settings_rejects_invalid_time_signature() {
+        let project = TestProject::new();
+        let settings = read_project_metadata(&project.path)
+            .unwrap()
+            .data
+            .metronome_settings;
+        let invalid = [(0, 4), (17, 4), (4, 3), (4, 32)];
+        for (numerator, denominator) in invalid {
+            let result = save_metronome_settings_data(
+                &project.path,
+                MetronomeSettings {
+                    time_signature_numerator: numerator,
+                    time_signature_denominator: denominator,
+                    ..settings.clone()
+                },
+            );
+            assert!(
+                result.is_err(),
+                "{}/{} must be rejected",
+                numerator,
+                denominator
+            );
+        }
+    }
+
     #[test]
     #[ignore] // Requires OT CF card mounted
     fn verify_ot_hardware_memory_settings() {
@@ -19618,7 +23619,8 @@ mod tests {
         for (name, expected_24bit, expected_count, expected_length, expected_free) in &cases {
             let path = format!("{}/{}", base, name);
             let meta = read_project_metadata(&path)
-                .unwrap_or_else(|e| panic!("Failed to read {}: {}", name, e));
+                .unwrap_or_else(|e| panic!("Failed to read {}: {}", name, e))
+                .data;
             let ms = &meta.memory_settings;
 
             assert_eq!(
@@ -20097,6 +24099,166 @@ mod tests {
             assert!(raw.contains("TRIGQUANTIZATION=-1"));
         }
 
+        #[test]
+        fn test_set_slot_gain_filters_and_offsets() {
+            let dir = setup_project_for_assign(&[
+                ("FLEX", 1, "../AUDIO/drums/kick.wav"),
+                ("FLEX", 2, "../AUDIO/Drums/snare.wav"),
+                ("FLEX", 3, "../AUDIO/bass/sub.wav"),
+                ("STATIC", 1, "../AUDIO/drums/loop.wav"),
+            ]);
+            let project_path = dir.path().to_str().unwrap();
+            let project_file = dir.path().join("project.work");
+            let gain = |stype: &str, slot: u16| -> u8 {
+                read_raw_sample_fields(&project_file).unwrap()[&(stype.to_string(), slot)]["GAIN"]
+                    .parse()
+                    .unwrap()
+            };
+
+            let changed = set_slot_gain(
+                project_path,
+                Some("flex"),
+                None,
+                Some("/audio/drums"),
+                "set",
+                -6.0,
+            )
+            .unwrap();
+            assert_eq!(changed, 2);
+            assert_eq!((gain("FLEX", 1), gain("FLEX", 2)), (36, 36));
+            assert_ne!(gain("FLEX", 3), 36);
+            assert_ne!(gain("STATIC", 1), 36);
+
+            // Offsets clamp at +24 dB
+            set_slot_gain(project_path, None, Some(&[1]), None, "offset", 100.0).unwrap();
+            assert_eq!((gain("FLEX", 1), gain("STATIC", 1)), (96, 96));
+
+            assert!(set_slot_gain(project_path, None, None, None, "set", 30.0).is_err());
+            assert!(set_slot_gain(project_path, None, None, None, "scale", 1.0).is_err());
+        }
+
+        #[test]
+        fn test_convert_slot_type_rewrites_bank_references() {
+            let dir = setup_project_for_assign(&[
+                ("STATIC", 3, "../AUDIO/loop.wav"),
+                ("FLEX", 1, "../AUDIO/kick.wav"),
+            ]);
+            let project_path = dir.path().to_str().unwrap();
+
+            // Track 1 plays Static slot 3 with a lock on it; track 2 plays Static
+            // slot 1 but also locks slot 3.
+            let mut bank = BankFile::default();
+            for parts_state in [&mut bank.parts.unsaved, &mut bank.parts.saved] {
+                let part = &mut parts_state.0[0];
+                part.audio_track_machine_types[0] = 0;
+                part.audio_track_machine_slots[0].static_slot_id = 2;
+                part.audio_track_machine_types[1] = 0;
+                part.audio_track_machine_slots[1].static_slot_id = 0;
+            }
+            bank.patterns.0[0].part_assignment = 0;
+            bank.patterns.0[0].audio_track_trigs.0[0].plocks.0[4].flex_slot_id = 2;
+            bank.patterns.0[0].audio_track_trigs.0[1].plocks.0[0].flex_slot_id = 2;
+            bank.to_data_file(&dir.path().join("bank01.work")).unwrap();
+
+            let result = convert_slot_type(project_path, "static", 3, None).unwrap();
+            assert_eq!(result.slot_type, "FLEX");
+            assert_eq!(result.slot_index, 2);
+            assert_eq!(result.tracks_converted, 1);
+            assert_eq!(result.locks_moved, 1);
+            assert_eq!(result.warnings.len(), 1);
+
+            let raw = read_raw_sample_fields(&dir.path().join("project.work")).unwrap();
+            assert_eq!(
+                raw[&("FLEX".to_string(), 2)]["PATH"],
+                "../AUDIO/loop.wav".to_string()
+            );
+            assert!(!raw.contains_key(&("STATIC".to_string(), 3)));
+
+            let bank = source_bank_data(project_path, 0);
+            for parts_state in [&bank.parts.unsaved, &bank.parts.saved] {
+                let part = &parts_state.0[0];
+                assert_eq!(part.audio_track_machine_types[0], 1);
+                assert_eq!(part.audio_track_machine_slots[0].flex_slot_id, 1);
+                assert_eq!(part.audio_track_machine_types[1], 0);
+            }
+            let trigs = &bank.patterns.0[0].audio_track_trigs.0;
+            assert_eq!(trigs[0].plocks.0[4].flex_slot_id, 1);
+            assert_eq!(trigs[1].plocks.0[0].flex_slot_id, 2);
+
+            // The target slot is now taken and the source is empty.
+            assert!(convert_slot_type(project_path, "static", 3, None).is_err());
+            assert!(convert_slot_type(project_path, "flex", 2, Some(1)).is_ok());
+            assert!(convert_slot_type(project_path, "flex", 2, Some(129)).is_err());
+        }
+
+        #[test]
+        fn test_reorder_sample_slots_follows_references() {
+            let dir = setup_project_for_assign(&[
+                ("FLEX", 2, "../AUDIO/snare.wav"),
+                ("FLEX", 5, "../AUDIO/Bass.wav"),
+                ("FLEX", 9, "../AUDIO/kick.wav"),
+                ("STATIC", 5, "../AUDIO/loop.wav"),
+            ]);
+            let project_path = dir.path().to_str().unwrap();
+
+            // Track 1 plays Flex slot 9 with a lock on slot 2; track 2 is a
+            // Static track, so its lock on slot 5 is a Static slot. All other
+            // tracks are Thru.
+            let mut bank = BankFile::default();
+            for parts_state in [&mut bank.parts.unsaved, &mut bank.parts.saved] {
+                for part in parts_state.0.iter_mut() {
+                    part.audio_track_machine_types = [2; 8];
+                }
+                let part = &mut parts_state.0[0];
+                part.audio_track_machine_types[0] = 1;
+                part.audio_track_machine_slots[0].flex_slot_id = 8;
+                part.audio_track_machine_types[1] = 0;
+                part.audio_track_machine_slots[1].static_slot_id = 4;
+            }
+            bank.patterns.0[0].part_assignment = 0;
+            bank.patterns.0[0].audio_track_trigs.0[0].plocks.0[3].flex_slot_id = 1;
+            bank.patterns.0[0].audio_track_trigs.0[1].plocks.0[0].flex_slot_id = 4;
+            bank.to_data_file(&dir.path().join("bank01.work")).unwrap();
+
+            let result = reorder_sample_slots(project_path, "flex", "alphabetical", &[]).unwrap();
+            assert_eq!(result.tracks_updated, 1);
+            assert_eq!(result.locks_updated, 1);
+            assert!(result.moves.contains(&SlotMove { from: 5, to: 1 }));
+
+            let raw = read_raw_sample_fields(&dir.path().join("project.work")).unwrap();
+            let path = |stype: &str, slot: u16| raw[&(stype.to_string(), slot)]["PATH"].clone();
+            assert_eq!(path("FLEX", 1), "../AUDIO/Bass.wav");
+            assert_eq!(path("FLEX", 2), "../AUDIO/kick.wav");
+            assert_eq!(path("FLEX", 3), "../AUDIO/snare.wav");
+            assert_eq!(path("STATIC", 5), "../AUDIO/loop.wav");
+
+            let bank = source_bank_data(project_path, 0);
+            for parts_state in [&bank.parts.unsaved, &bank.parts.saved] {
+                let part = &parts_state.0[0];
+                assert_eq!(part.audio_track_machine_slots[0].flex_slot_id, 1);
+                assert_eq!(part.audio_track_machine_slots[1].static_slot_id, 4);
+            }
+            let trigs = &bank.patterns.0[0].audio_track_trigs.0;
+            assert_eq!(trigs[0].plocks.0[3].flex_slot_id, 2);
+            assert_eq!(trigs[1].plocks.0[0].flex_slot_id, 4);
+
+            // Already sorted: nothing moves.
+            let again = reorder_sample_slots(project_path, "flex", "compact", &[]).unwrap();
+            assert!(again.moves.is_empty());
+        }
+
+        #[test]
+        fn test_plan_slot_order_custom_moves() {
+            let raw = RawSampleFieldsMap::new();
+            let order =
+                plan_slot_order(&raw, "FLEX", "custom", &[SlotMove { from: 4, to: 1 }]).unwrap();
+            assert_eq!(&order[..5], &[4, 1, 2, 3, 5]);
+
+            let clash = [SlotMove { from: 1, to: 3 }, SlotMove { from: 2, to: 3 }];
+            assert!(plan_slot_order(&raw, "FLEX", "custom", &clash).is_err());
+            assert!(plan_slot_order(&raw, "FLEX", "shuffle", &[]).is_err());
+        }
+
         #[test]
         fn test_slot_attributes_at_default() {
             // Flex defaults: gain 48, TS Normal(2), Loop Normal(1), TrigQuant Direct(-1).
@@ -20315,7 +24477,7 @@ mod tests {
             updates.insert(("FLEX".to_string(), 1u16), fields);
             replace_sample_fields_surgical(&dir.path().join("project.work"), &updates).unwrap();
 
-            let meta = read_project_metadata(project_path).unwrap();
+            let meta = read_project_metadata(project_path).unwrap().data;
             let slot1 = meta
                 .sample_slots
                 .flex_slots
@@ -21201,4 +25363,341 @@ mod tests {
             assert!(read_project(&dir).contains("PATH=../AUDIO/télé çà.wav"));
         }
     }
+
+    mod pattern_timing_tests {
+        use super::*;
+
+        fn set_trig(bank: &mut BankFile, pattern: usize, track: usize, step: usize) {
+            let mut steps = decode_trig_masks(
+                &bank.patterns.0[pattern].audio_track_trigs.0[track]
+                    .trig_masks
+                    .trigger,
+            );
+            steps[step] = true;
+            bank.patterns.0[pattern].audio_track_trigs.0[track]
+                .trig_masks
+                .trigger = encode_trig_masks(&steps);
+        }
+
+        fn trig_steps(project: &TestProject, pattern: usize, track: usize) -> Vec<usize> {
+            let bank = source_bank_data(&project.path, 0);
+            decode_trig_masks(
+                &bank.patterns.0[pattern].audio_track_trigs.0[track]
+                    .trig_masks
+                    .trigger,
+            )
+            .iter()
+            .enumerate()
+            .filter(|(_, &on)| on)
+            .map(|(i, _)| i)
+            .collect()
+        }
+
+        #[test]
+        fn test_encode_is_inverse_of_decode() {
+            let masks = [243, 1, 0, 4, 12, 0, 8, 2];
+            assert_eq!(encode_trig_masks(&decode_trig_masks(&masks)), masks);
+        }
+
+        #[test]
+        fn test_shift_wraps_around_pattern_length() {
+            let project = TestProject::with_modified_bank(0, |bank| {
+                bank.patterns.0[0].scale.master_len = 16;
+                set_trig(bank, 0, 2, 0);
+                set_trig(bank, 0, 2, 14);
+                bank.patterns.0[0].audio_track_trigs.0[2].trig_offsets_repeats_conditions[14] =
+                    [0, 7];
+            });
+
+            shift_track_trigs(&project.path, 0, 0, 2, 3).unwrap();
+            assert_eq!(trig_steps(&project, 0, 2), vec![1, 3]);
+            // The condition moved with its trig
+            let bank = source_bank_data(&project.path, 0);
+            assert_eq!(
+                bank.patterns.0[0].audio_track_trigs.0[2].trig_offsets_repeats_conditions[1],
+                [0, 7]
+            );
+
+            shift_track_trigs(&project.path, 0, 0, 2, -4).unwrap();
+            assert_eq!(trig_steps(&project, 0, 2), vec![13, 15]);
+        }
+
+        #[test]
+        fn test_transform_ops() {
+            let project = TestProject::with_modified_bank(0, |bank| {
+                bank.patterns.0[0].scale.master_len = 8;
+                set_trig(bank, 0, 0, 0);
+                set_trig(bank, 0, 0, 1);
+                set_trig(bank, 0, 1, 5);
+                set_trig(bank, 0, 2, 2);
+            });
+
+            transform_pattern(&project.path, 0, 0, Some(0), "reverse").unwrap();
+            assert_eq!(trig_steps(&project, 0, 0), vec![6, 7]);
+            assert_eq!(trig_steps(&project, 0, 1), vec![5], "other track untouched");
+
+            transform_pattern(&project.path, 0, 0, None, "rotate_left").unwrap();
+            assert_eq!(trig_steps(&project, 0, 0), vec![5, 6]);
+            assert_eq!(trig_steps(&project, 0, 1), vec![4]);
+
+            transform_pattern(&project.path, 0, 0, Some(0), "thin").unwrap();
+            assert_eq!(trig_steps(&project, 0, 0), vec![6]);
+
+            // Steps 0-3 reflected onto 7-4: step 4 is overwritten by empty step 3
+            transform_pattern(&project.path, 0, 0, Some(1), "mirror").unwrap();
+            assert!(trig_steps(&project, 0, 1).is_empty());
+            transform_pattern(&project.path, 0, 0, Some(2), "mirror").unwrap();
+            assert_eq!(trig_steps(&project, 0, 2), vec![1, 6]);
+
+            assert!(transform_pattern(&project.path, 0, 0, None, "shuffle").is_err());
+        }
+
+        #[test]
+        fn test_double_copies_steps_and_halve_shortens() {
+            let project = TestProject::with_modified_bank(0, |bank| {
+                bank.patterns.0[1].scale.master_len = 16;
+                set_trig(bank, 1, 0, 4);
+            });
+
+            assert_eq!(resize_pattern(&project.path, 0, 1, true).unwrap(), 32);
+            assert_eq!(trig_steps(&project, 1, 0), vec![4, 20]);
+
+            assert_eq!(resize_pattern(&project.path, 0, 1, false).unwrap(), 16);
+            let bank = source_bank_data(&project.path, 0);
+            assert_eq!(bank.patterns.0[1].scale.master_len as u16, 16);
+        }
+
+        #[test]
+        fn test_double_rejects_over_64_steps() {
+            let project = TestProject::with_modified_bank(0, |bank| {
+                bank.patterns.0[0].scale.master_len = 48;
+            });
+            assert!(resize_pattern(&project.path, 0, 0, true).is_err());
+        }
+
+        #[test]
+        fn test_clear_pattern_scopes() {
+            let project = TestProject::with_modified_bank(0, |bank| {
+                bank.patterns.0[0].scale.master_len = 32;
+                set_trig(bank, 0, 0, 4);
+                let track = &mut bank.patterns.0[0].audio_track_trigs.0[0];
+                track.trig_masks.plock = [0, 0, 0, 0, 0, 0, 0, 16];
+                track.plocks.0[4].flex_slot_id = 3;
+                track.trig_offsets_repeats_conditions[4] = [0, 7];
+            });
+
+            clear_pattern(&project.path, 0, 0, "plocks").unwrap();
+            let bank = source_bank_data(&project.path, 0);
+            let track = &bank.patterns.0[0].audio_track_trigs.0[0];
+            assert_eq!(trig_steps(&project, 0, 0), vec![4]);
+            assert_eq!(track.plocks.0[4].flex_slot_id, 255);
+            assert_eq!(track.trig_masks.plock, [0; 8]);
+            assert_eq!(track.trig_offsets_repeats_conditions[4], [0, 7]);
+
+            clear_pattern(&project.path, 0, 0, "trigs").unwrap();
+            let bank = source_bank_data(&project.path, 0);
+            assert!(trig_steps(&project, 0, 0).is_empty());
+            assert_eq!(
+                bank.patterns.0[0].audio_track_trigs.0[0].trig_offsets_repeats_conditions[4],
+                [0, 0]
+            );
+            assert_eq!(bank.patterns.0[0].scale.master_len, 32);
+
+            clear_pattern(&project.path, 0, 0, "full").unwrap();
+            let bank = source_bank_data(&project.path, 0);
+            assert_eq!(bank.patterns.0[0].scale.master_len, 16);
+
+            assert!(clear_pattern(&project.path, 0, 0, "all").is_err());
+            assert!(clear_pattern(&project.path, 0, 16, "full").is_err());
+        }
+
+        #[test]
+        fn test_init_bank_backs_up_and_resets() {
+            let project = TestProject::with_modified_bank(0, |bank| {
+                set_trig(bank, 5, 3, 2);
+                bank.parts.unsaved.0[1].audio_track_machine_types[0] = 1;
+            });
+
+            let summary = init_bank(&project.path, 0).unwrap();
+            assert_eq!(summary, "1 file(s) backed up");
+            let backups: Vec<_> = fs::read_dir(Path::new(&project.path).join("backups"))
+                .unwrap()
+                .collect();
+            assert_eq!(backups.len(), 1);
+
+            let bank = source_bank_data(&project.path, 0);
+            assert!(trig_steps(&project, 5, 3).is_empty());
+            assert_eq!(bank.parts.unsaved.0[1].audio_track_machine_types[0], 0);
+            assert_eq!(
+                bank.checksum,
+                BankFile::default().calculate_checksum().unwrap()
+            );
+
+            assert!(init_bank(&project.path, 16).is_err());
+        }
+
+        #[test]
+        fn test_init_bank_creates_a_missing_bank() {
+            let project = TestProject::new();
+            let bank_path = Path::new(&project.path).join("bank02.work");
+            fs::remove_file(&bank_path).unwrap();
+
+            let summary = init_bank(&project.path, 1).unwrap();
+            assert!(summary.contains("no bank file to back up"), "{}", summary);
+            assert!(!Path::new(&project.path).join("backups").exists());
+            let bank = crate::os_compat::read_bank_file(&bank_path).unwrap();
+            assert_eq!(
+                bank.checksum,
+                BankFile::default().calculate_checksum().unwrap()
+            );
+        }
+
+        #[test]
+        fn test_quantize_clears_micro_timing_only() {
+            let project = TestProject::with_modified_bank(0, |bank| {
+                let track = &mut bank.patterns.0[0].audio_track_trigs.0[0];
+                track.trig_offsets_repeats_conditions[0] = [0b0010_0011, 0b1000_0101];
+                track.trig_offsets_repeats_conditions[1] = [0b0100_0000, 0b0000_0001];
+            });
+
+            assert_eq!(quantize_micro_timing(&project.path, 0, 0, None).unwrap(), 1);
+
+            let bank = source_bank_data(&project.path, 0);
+            let bytes = bank.patterns.0[0].audio_track_trigs.0[0].trig_offsets_repeats_conditions;
+            assert_eq!(bytes[0], [0b0010_0000, 0b0000_0101]);
+            assert_eq!(bytes[1], [0b0100_0000, 0b0000_0001]);
+        }
+
+        #[test]
+        fn test_set_track_groove() {
+            let project = TestProject::with_modified_bank(0, |bank| {
+                bank.patterns.0[1].scale.scale_mode = 1;
+            });
+
+            set_track_groove(&project.path, 0, 0, 9, Some(12), None, None).unwrap();
+            let bank = source_bank_data(&project.path, 0);
+            assert_eq!(bank.patterns.0[0].midi_track_trigs.0[1].swing_amount, 12);
+            // Normal scale mode has no per-track length.
+            assert!(set_track_groove(&project.path, 0, 0, 0, None, Some(12), None).is_err());
+
+            set_track_groove(&project.path, 0, 1, 2, None, Some(12), Some("3/4x")).unwrap();
+            let bank = source_bank_data(&project.path, 0);
+            let scale = &bank.patterns.0[1].audio_track_trigs.0[2].scale_per_track_mode;
+            assert_eq!((scale.per_track_len, scale.per_track_scale), (12, 3));
+
+            assert!(set_track_groove(&project.path, 0, 1, 2, Some(31), None, None).is_err());
+            assert!(set_track_groove(&project.path, 0, 1, 2, None, Some(0), None).is_err());
+            assert!(set_track_groove(&project.path, 0, 1, 2, None, None, Some("5x")).is_err());
+        }
+
+        #[test]
+        fn test_set_and_scale_trig_probability() {
+            let project = TestProject::with_modified_bank(0, |bank| {
+                bank.patterns.0[0].scale.master_len = 16;
+                for step in [0, 4, 8, 12] {
+                    set_trig(bank, 0, 1, step);
+                }
+                let bytes =
+                    &mut bank.patterns.0[0].audio_track_trigs.0[1].trig_offsets_repeats_conditions;
+                bytes[4] = [0, 0x80 | 22]; // 75% with micro-timing bit
+                bytes[8] = [0, 1]; // Fill
+            });
+
+            // Scale by half: 100% -> 50%, 75% -> 41% (closest to 37.5), Fill untouched
+            let changed =
+                set_trig_probability(&project.path, 0, 0, &[1], None, "scale", 50.0).unwrap();
+            assert_eq!(changed, 3);
+            let bank = source_bank_data(&project.path, 0);
+            let bytes = bank.patterns.0[0].audio_track_trigs.0[1].trig_offsets_repeats_conditions;
+            assert_eq!(bytes[0][1], 19);
+            assert_eq!(bytes[4][1], 0x80 | 18);
+            assert_eq!(bytes[8][1], 1);
+            assert_eq!(bytes[12][1], 19);
+
+            // Set on selected steps only; 100% clears the condition
+            let changed =
+                set_trig_probability(&project.path, 0, 0, &[1], Some(&[0, 4]), "set", 100.0)
+                    .unwrap();
+            assert_eq!(changed, 2);
+            let bank = source_bank_data(&project.path, 0);
+            let bytes = bank.patterns.0[0].audio_track_trigs.0[1].trig_offsets_repeats_conditions;
+            assert_eq!(bytes[0][1], 0);
+            assert_eq!(bytes[4][1], 0x80);
+            assert_eq!(bytes[12][1], 19);
+
+            assert!(set_trig_probability(&project.path, 0, 0, &[], None, "double", 50.0).is_err());
+        }
+
+        #[test]
+        fn test_set_pattern_assignment() {
+            let project = TestProject::new();
+            set_pattern_assignment(&project.path, 0, &[1, 4, 15], Some(2), Some("Pattern"))
+                .unwrap();
+            let bank = source_bank_data(&project.path, 0);
+            for idx in [1, 4, 15] {
+                let pattern = &bank.patterns.0[idx];
+                assert_eq!(pattern.part_assignment, 2);
+                assert_eq!(pattern.chain_behaviour.use_project_setting, 0);
+            }
+            assert_eq!(bank.patterns.0[0].part_assignment, 0);
+
+            // Chain mode alone keeps the part
+            set_pattern_assignment(&project.path, 0, &[4], None, Some("Project")).unwrap();
+            let bank = source_bank_data(&project.path, 0);
+            assert_eq!(bank.patterns.0[4].part_assignment, 2);
+            assert_eq!(bank.patterns.0[4].chain_behaviour.use_project_setting, 1);
+
+            assert!(set_pattern_assignment(&project.path, 0, &[0], Some(4), None).is_err());
+            assert!(set_pattern_assignment(&project.path, 0, &[16], Some(1), None).is_err());
+            assert!(set_pattern_assignment(&project.path, 0, &[0], None, Some("Song")).is_err());
+        }
+
+        #[test]
+        fn test_pattern_tempo_encoding() {
+            assert_eq!(encode_pattern_tempo(120.0).unwrap(), DEFAULT_PATTERN_TEMPO);
+            assert_eq!(decode_pattern_tempo(11, 64), 120.0);
+            let (t1, t2) = encode_pattern_tempo(126.125).unwrap();
+            assert_eq!(decode_pattern_tempo(t1, t2), 126.125);
+            assert!(encode_pattern_tempo(29.0).is_err());
+            assert!(encode_pattern_tempo(300.5).is_err());
+        }
+
+        #[test]
+        fn test_set_pattern_tempo_and_reset() {
+            let project = TestProject::new();
+            set_pattern_tempo(&project.path, 0, 3, Some(95.5)).unwrap();
+            let bank = read_single_bank(&project.path, 0).unwrap().unwrap();
+            let tempo_of = |id: u8| {
+                bank.parts
+                    .iter()
+                    .flat_map(|part| &part.patterns)
+                    .find(|pattern| pattern.id == id)
+                    .and_then(|pattern| pattern.tempo_info.clone())
+            };
+            assert_eq!(tempo_of(3).as_deref(), Some("95.5 BPM"));
+            assert_eq!(tempo_of(2), None);
+
+            set_pattern_tempo(&project.path, 0, 3, None).unwrap();
+            let bank = source_bank_data(&project.path, 0);
+            let pattern = &bank.patterns.0[3];
+            assert_eq!((pattern.tempo_1, pattern.tempo_2), DEFAULT_PATTERN_TEMPO);
+            assert!(set_pattern_tempo(&project.path, 0, 16, Some(120.0)).is_err());
+        }
+
+        #[test]
+        fn test_set_project_tempo_keeps_fraction_and_flag() {
+            let project = TestProject::new();
+            set_project_tempo(&project.path, 126.125, Some(true)).unwrap();
+            let metadata = read_project_metadata(&project.path).unwrap().data;
+            assert_eq!(metadata.tempo, 126.125);
+            assert!(metadata.pattern_tempo_enabled);
+
+            // The flag is left alone when not given
+            set_project_tempo(&project.path, 90.0, None).unwrap();
+            let metadata = read_project_metadata(&project.path).unwrap().data;
+            assert_eq!(metadata.tempo, 90.0);
+            assert!(metadata.pattern_tempo_enabled);
+            assert!(set_project_tempo(&project.path, 400.0, None).is_err());
+        }
+    }
 }
@@ -0,0 +1,429 @@
+// Project report: a human-readable document of a project for gig notes and
+// archival, written as Markdown or standalone HTML.
+//
+// The report lists the sample slots (with missing files called out), one
+// overview table per bank of the patterns that hold trigs, and per part the
+// machine, sample and FX of each audio track. The document is built once as
+// a list of blocks and rendered to either format.
+
+use crate::project_reader::{
+    read_project_banks, read_project_metadata, Bank, ProjectMetadata, SampleSlot,
+};
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Block {
+    Heading(u8, String),
+    Paragraph(String),
+    List(Vec<String>),
+    Table(Vec<&'static str>, Vec<Vec<String>>),
+}
+
+/// "html" or "markdown" from an explicit format or the output file extension.
+fn resolve_format(format: Option<&str>, out_file: &str) -> Result<&'static str, String> {
+    let wanted = match format {
+        Some(f) => f.to_ascii_lowercase(),
+        None => Path::new(out_file)
+            .extension()
+            .map(|e| e.to_string_lossy().to_ascii_lowercase())
+            .unwrap_or_default(),
+    };
+    match wanted.as_str() {
+        "html" | "htm" => Ok("html"),
+        "markdown" | "md" => Ok("markdown"),
+        other => Err(format!(
+            "Unsupported report format '{}' (use html or markdown)",
+            other
+        )),
+    }
+}
+
+fn slot_file(slot: &SampleSlot) -> String {
+    slot.path
+        .as_deref()
+        .map(|p| p.rsplit(['/', '\\']).next().unwrap_or(p).to_string())
+        .unwrap_or_default()
+}
+
+fn slot_status(slot: &SampleSlot) -> String {
+    if !slot.file_exists {
+        return "MISSING".to_string();
+    }
+    match slot.compatibility.as_deref() {
+        Some("compatible") | None => "ok".to_string(),
+        Some("wrong_rate") => "wrong sample rate".to_string(),
+        Some(other) => other.to_string(),
+    }
+}
+
+fn slot_blocks(title: &str, prefix: char, slots: &[SampleSlot]) -> Vec<Block> {
+    let rows: Vec<Vec<String>> = slots
+        .iter()
+        .filter(|s| s.path.is_some())
+        .map(|s| {
+            let format = match (&s.file_format, s.sample_rate, s.bit_depth) {
+                (Some(f), Some(rate), Some(bits)) => format!("{} {} Hz {}-bit", f, rate, bits),
+                (Some(f), _, _) => f.clone(),
+                _ => String::new(),
+            };
+            vec![
+                format!("{}{:03}", prefix, s.slot_id),
+                s.path.clone().unwrap_or_default(),
+                format,
+                slot_status(s),
+            ]
+        })
+        .collect();
+    let mut blocks = vec![Block::Heading(3, title.to_string())];
+    if rows.is_empty() {
+        blocks.push(Block::Paragraph("No samples assigned.".to_string()));
+    } else {
+        blocks.push(Block::Table(vec!["Slot", "Path", "Format", "Status"], rows));
+    }
+    blocks
+}
+
+fn missing_blocks(meta: &ProjectMetadata) -> Vec<Block> {
+    let missing: Vec<String> = [
+        ("Static", &meta.sample_slots.static_slots),
+        ("Flex", &meta.sample_slots.flex_slots),
+    ]
+    .iter()
+    .flat_map(|(kind, slots)| {
+        slots
+            .iter()
+            .filter(|s| s.path.is_some() && !s.file_exists)
+            .map(move |s| {
+                format!(
+                    "{} slot {}: {}",
+                    kind,
+                    s.slot_id,
+                    s.path.as_deref().unwrap_or_default()
+                )
+            })
+    })
+    .collect();
+    let mut blocks = vec![Block::Heading(2, "Missing samples".to_string())];
+    if missing.is_empty() {
+        blocks.push(Block::Paragraph(
+            "All assigned samples were found.".to_string(),
+        ));
+    } else {
+        blocks.push(Block::List(missing));
+    }
+    blocks
+}
+
+fn pattern_blocks(bank: &Bank) -> Vec<Block> {
+    // Every part lists all 16 patterns; take each pattern once.
+    let mut patterns: Vec<_> = bank.parts.iter().flat_map(|p| &p.patterns).collect();
+    patterns.sort_by_key(|p| p.id);
+    patterns.dedup_by_key(|p| p.id);
+
+    let rows: Vec<Vec<String>> = patterns
+        .iter()
+        .filter(|p| p.trig_counts.total > 0)
+        .map(|p| {
+            vec![
+                format!("{}{:02}", bank.id, p.id + 1),
+                format!("{}", p.part_assignment.min(3) + 1),
+                p.length.to_string(),
+                p.master_scale.clone(),
+                p.tempo_info
+                    .clone()
+                    .unwrap_or_else(|| "project".to_string()),
+                p.active_tracks.to_string(),
+                p.trig_counts.trigger.to_string(),
+            ]
+        })
+        .collect();
+    let empty = patterns.len() - rows.len();
+    let mut blocks = vec![Block::Heading(3, "Patterns".to_string())];
+    if !rows.is_empty() {
+        blocks.push(Block::Table(
+            vec![
+                "Pattern", "Part", "Length", "Scale", "Tempo", "Tracks", "Trigs",
+            ],
+            rows,
+        ));
+    }
+    if empty > 0 {
+        blocks.push(Block::Paragraph(format!(
+            "{} empty pattern(s) not listed.",
+            empty
+        )));
+    }
+    blocks
+}
+
+/// Per-part machine, sample and FX tables from the raw bank file.
+fn part_blocks(project_dir: &Path, bank: &Bank, meta: &ProjectMetadata) -> Vec<Block> {
+    let Some(bank_index) = crate::project_reader::BANK_LETTERS
+        .iter()
+        .position(|l| *l == bank.id)
+    else {
+        return Vec::new();
+    };
+    let work = project_dir.join(format!("bank{:02}.work", bank_index + 1));
+    let file = if work.exists() {
+        work
+    } else {
+        project_dir.join(format!("bank{:02}.strd", bank_index + 1))
+    };
+    let raw = match crate::os_compat::read_bank_file(&file) {
+        Ok(raw) => raw,
+        Err(e) => return vec![Block::Paragraph(format!("Parts unavailable: {}", e))],
+    };
+
+    let slot_label = |slots: &[SampleSlot], prefix: char, id: u8| {
+        let file = slots
+            .iter()
+            .find(|s| s.slot_id as u16 == id as u16 + 1)
+            .map(slot_file)
+            .filter(|f| !f.is_empty())
+            .unwrap_or_else(|| "(empty)".to_string());
+        format!("{}{:03} {}", prefix, id as u16 + 1, file)
+    };
+
+    let mut blocks = Vec::new();
+    for (part_index, part) in bank.parts.iter().enumerate().take(4) {
+        let unsaved = &raw.parts.unsaved.0[part_index];
+        let rows = (0..8)
+            .map(|t| {
+                let machine_type = unsaved.audio_track_machine_types[t];
+                let slots = &unsaved.audio_track_machine_slots[t];
+                let sample = match machine_type {
+                    0 => slot_label(&meta.sample_slots.static_slots, 'S', slots.static_slot_id),
+                    1 => slot_label(&meta.sample_slots.flex_slots, 'F', slots.flex_slot_id),
+                    _ => String::new(),
+                };
+                vec![
+                    format!("T{}", t + 1),
+                    crate::param_decode::machine_type_name(machine_type).to_string(),
+                    sample,
+                    crate::param_decode::fx_type_name(unsaved.audio_track_fx1[t]),
+                    crate::param_decode::fx_type_name(unsaved.audio_track_fx2[t]),
+                ]
+            })
+            .collect();
+        blocks.push(Block::Heading(
+            3,
+            format!("Part {}: {}", part_index + 1, part.name),
+        ));
+        blocks.push(Block::Table(
+            vec!["Track", "Machine", "Sample", "FX1", "FX2"],
+            rows,
+        ));
+    }
+    blocks
+}
+
+fn build_report(project_path: &str) -> Result<(String, Vec<Block>), String> {
+    let meta_result = read_project_metadata(project_path)?;
+    let banks_result = read_project_banks(project_path)?;
+    let meta = meta_result.data;
+    let project_dir = Path::new(project_path);
+
+    let mut blocks = vec![
+        Block::Paragraph(format!(
+            "Tempo {:.1} BPM, {}, OS {}. Generated {}.",
+            meta.tempo,
+            meta.time_signature,
+            meta.os_version.trim(),
+            chrono::Local::now().format("%Y-%m-%d %H:%M")
+        )),
+        Block::Heading(2, "Sample slots".to_string()),
+    ];
+    blocks.extend(slot_blocks("Static", 'S', &meta.sample_slots.static_slots));
+    blocks.extend(slot_blocks("Flex", 'F', &meta.sample_slots.flex_slots));
+    blocks.extend(missing_blocks(&meta));
+
+    for bank in &banks_result.data {
+        blocks.push(Block::Heading(2, format!("Bank {}", bank.id)));
+        blocks.extend(pattern_blocks(bank));
+        blocks.extend(part_blocks(project_dir, bank, &meta));
+    }
+
+    let warnings: Vec<String> = meta_result
+        .warnings
+        .iter()
+        .chain(&banks_result.warnings)
+        .map(|w| format!("{}: {}", w.file, w.message))
+        .collect();
+    if !warnings.is_empty() {
+        blocks.push(Block::Heading(2, "Read warnings".to_string()));
+        blocks.push(Block::List(warnings));
+    }
+    Ok((meta.name, blocks))
+}
+
+fn md_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+fn render_markdown(title: &str, blocks: &[Block]) -> String {
+    let mut out = format!("# {}\n", title);
+    for block in blocks {
+        out.push('\n');
+        match block {
+            Block::Heading(level, text) => {
+                out.push_str(&format!("{} {}\n", "#".repeat(*level as usize), text))
+            }
+            Block::Paragraph(text) => out.push_str(&format!("{}\n", text)),
+            Block::List(items) => {
+                for item in items {
+                    out.push_str(&format!("- {}\n", item));
+                }
+            }
+            Block::Table(headers, rows) => {
+                out.push_str(&format!("| {} |\n", headers.join(" | ")));
+                out.push_str(&format!("|{}\n", " --- |".repeat(headers.len())));
+                for row in rows {
+                    let cells: Vec<String> = row.iter().map(|c| md_cell(c)).collect();
+                    out.push_str(&format!("| {} |\n", cells.join(" | ")));
+                }
+            }
+        }
+    }
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn render_html(title: &str, blocks: &[Block]) -> String {
+    let title = escape_html(title);
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+         <style>body{{font-family:sans-serif;margin:2em}}table{{border-collapse:collapse;\
+         margin-bottom:1em}}th,td{{border:1px solid #999;padding:2px 8px;text-align:left}}\
+         </style>\n</head>\n<body>\n<h1>{}</h1>\n",
+        title, title
+    );
+    for block in blocks {
+        match block {
+            Block::Heading(level, text) => {
+                out.push_str(&format!("<h{0}>{1}</h{0}>\n", level, escape_html(text)))
+            }
+            Block::Paragraph(text) => out.push_str(&format!("<p>{}</p>\n", escape_html(text))),
+            Block::List(items) => {
+                out.push_str("<ul>\n");
+                for item in items {
+                    out.push_str(&format!("<li>{}</li>\n", escape_html(item)));
+                }
+                out.push_str("</ul>\n");
+            }
+            Block::Table(headers, rows) => {
+                out.push_str("<table>\n<tr>");
+                for header in headers {
+                    out.push_str(&format!("<th>{}</th>", header));
+                }
+                out.push_str("</tr>\n");
+                for row in rows {
+                    out.push_str("<tr>");
+                    for cell in row {
+                        out.push_str(&format!("<td>{}</td>", escape_html(cell)));
+                    }
+                    out.push_str("</tr>\n");
+                }
+                out.push_str("</table>\n");
+            }
+        }
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+/// Write the report of `project_path` to `out_file` as "html" or "markdown"
+/// (inferred from the extension when `format` is None).
+pub fn export_report(
+    project_path: &str,
+    out_file: &str,
+    format: Option<&str>,
+) -> Result<(), String> {
+    let format = resolve_format(format, out_file)?;
+    let (title, blocks) = build_report(project_path)?;
+    let document = match format {
+        "html" => render_html(&title, &blocks),
+        _ => render_markdown(&title, &blocks),
+    };
+    let out = Path::new(out_file);
+    if let Some(parent) = out.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    fs::write(out, document).map_err(|e| format!("Failed to write {}: {}", out_file, e))
+}
+
+#[tauri::command]
+pub async fn export_project_report(
+    path: String,
+    out_file: String,
+    format: Option<String>,
+) -> Result<(), String> {
+    crate::fs_scope::ensure_allowed(&out_file)?;
+    tauri::async_runtime::spawn_blocking(move || export_report(&path, &out_file, format.as_deref()))
+        .await
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ot_tools_io::{BankFile, OctatrackFileIO, ProjectFile};
+    use tempfile::TempDir;
+
+    fn project_with_pattern() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        ProjectFile::default()
+            .to_data_file(&dir.path().join("project.work"))
+            .unwrap();
+        let mut bank = BankFile::default();
+        bank.patterns.0[2].audio_track_trigs.0[0].trig_masks.trigger =
+            crate::project_reader::encode_trig_masks(&[true; 64]);
+        bank.to_data_file(&dir.path().join("bank01.work")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_resolve_format() {
+        assert_eq!(resolve_format(None, "/tmp/gig.HTML"), Ok("html"));
+        assert_eq!(resolve_format(None, "/tmp/gig.md"), Ok("markdown"));
+        assert_eq!(
+            resolve_format(Some("markdown"), "/tmp/gig.txt"),
+            Ok("markdown")
+        );
+        assert!(resolve_format(None, "/tmp/gig.pdf").is_err());
+    }
+
+    #[test]
+    fn test_markdown_report_lists_used_patterns_and_parts() {
+        let dir = project_with_pattern();
+        let out = dir.path().join("report.md");
+        export_report(&dir.path().to_string_lossy(), &out.to_string_lossy(), None).unwrap();
+
+        let report = fs::read_to_string(&out).unwrap();
+        assert!(report.contains("## Bank A"), "{}", report);
+        assert!(report.contains("| A03 | 1 |"), "{}", report);
+        assert!(report.contains("15 empty pattern(s) not listed."));
+        assert!(report.contains("### Part 1:"));
+        assert!(report.contains("## Missing samples"));
+    }
+
+    #[test]
+    fn test_html_escapes_text() {
+        let blocks = vec![
+            Block::Paragraph("a < b & c".to_string()),
+            Block::Table(vec!["Slot"], vec![vec!["<S001>".to_string()]]),
+        ];
+        let html = render_html("Live & loud", &blocks);
+        assert!(html.contains("<title>Live &amp; loud</title>"));
+        assert!(html.contains("<p>a &lt; b &amp; c</p>"));
+        assert!(html.contains("<td>&lt;S001&gt;</td>"));
+    }
+}
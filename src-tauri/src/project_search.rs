@@ -0,0 +1,360 @@
+// Project-wide search: typed queries over parts, patterns, steps and sample
+// slots, answered with bank/part/pattern/track/step coordinates.
+
+use crate::param_decode::{fx_type_name, FX_TYPES, MACHINE_TYPES};
+use crate::project_reader::{decode_trig_condition, decode_trig_masks};
+use ot_tools_io::{BankFile, OctatrackFileIO, ProjectFile};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SearchQuery {
+    /// Audio tracks whose part uses this machine ("flex", "static", "thru", ...).
+    Machine { machine: String },
+    /// Audio tracks whose part has this effect, in FX1/FX2 (`slot` 1 or 2) or either.
+    Fx { fx: String, slot: Option<u8> },
+    /// Trigged steps carrying this trig condition ("Fill", "50%", "1:4", ...).
+    Condition { condition: String },
+    /// Sample slots whose file name contains `text` (case-insensitive).
+    Sample { text: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SearchHit {
+    pub kind: String,              // "machine", "fx", "condition", "sample"
+    pub bank: Option<u8>,          // 0-based
+    pub part: Option<u8>,          // 0-based
+    pub pattern: Option<u8>,       // 0-based
+    pub track: Option<u8>,         // 0-7 audio, 8-15 MIDI
+    pub step: Option<u8>,          // 0-based
+    pub slot_type: Option<String>, // "Static" or "Flex"
+    pub slot_id: Option<u8>,       // 1-based
+    pub label: String,             // what matched, for display
+}
+
+impl SearchHit {
+    fn new(kind: &str, label: String) -> Self {
+        SearchHit {
+            kind: kind.to_string(),
+            bank: None,
+            part: None,
+            pattern: None,
+            track: None,
+            step: None,
+            slot_type: None,
+            slot_id: None,
+            label,
+        }
+    }
+}
+
+/// Lowercase with spaces, dashes and underscores dropped ("Lo-Fi" == "lofi").
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '_'))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Each bank that exists, `.work` preferred over `.strd`.
+fn read_banks(path: &Path) -> Vec<(u8, BankFile)> {
+    (0..16u8)
+        .filter_map(|bank| {
+            let work = path.join(format!("bank{:02}.work", bank + 1));
+            let file = if work.exists() {
+                work
+            } else {
+                path.join(format!("bank{:02}.strd", bank + 1))
+            };
+            crate::os_compat::read_bank_file(&file)
+                .ok()
+                .map(|data| (bank, data))
+        })
+        .collect()
+}
+
+fn search_parts(
+    banks: &[(u8, BankFile)],
+    kind: &str,
+    matches: impl Fn(&BankFile, usize, usize) -> Option<String>,
+) -> Vec<SearchHit> {
+    let mut hits = Vec::new();
+    for (bank, data) in banks {
+        for part_id in 0..data.parts.unsaved.0.len() {
+            for t in 0..8 {
+                if let Some(label) = matches(data, part_id, t) {
+                    hits.push(SearchHit {
+                        bank: Some(*bank),
+                        part: Some(part_id as u8),
+                        track: Some(t as u8),
+                        ..SearchHit::new(kind, label)
+                    });
+                }
+            }
+        }
+    }
+    hits
+}
+
+fn search_conditions(banks: &[(u8, BankFile)], condition: &str) -> Vec<SearchHit> {
+    let wanted = normalize(condition);
+    let mut hits = Vec::new();
+    for (bank, data) in banks {
+        for (p, pattern) in data.patterns.0.iter().enumerate() {
+            for t in 0..16usize {
+                let (masks, bytes, len) = if t < 8 {
+                    let track = &pattern.audio_track_trigs.0[t];
+                    let len = if pattern.scale.scale_mode == 1 {
+                        track.scale_per_track_mode.per_track_len
+                    } else {
+                        pattern.scale.master_len
+                    };
+                    (
+                        &track.trig_masks.trigger,
+                        &track.trig_offsets_repeats_conditions,
+                        len,
+                    )
+                } else {
+                    let track = &pattern.midi_track_trigs.0[t - 8];
+                    let len = if pattern.scale.scale_mode == 1 {
+                        track.scale_per_track_mode.per_track_len
+                    } else {
+                        pattern.scale.master_len
+                    };
+                    (
+                        &track.trig_masks.trigger,
+                        &track.trig_offsets_repeats_conditions,
+                        len,
+                    )
+                };
+                let trigs = decode_trig_masks(masks);
+                for step in 0..(len as usize).min(64) {
+                    if !trigs[step] {
+                        continue;
+                    }
+                    let Some(name) = decode_trig_condition(bytes[step][1]) else {
+                        continue;
+                    };
+                    if normalize(&name) == wanted {
+                        hits.push(SearchHit {
+                            bank: Some(*bank),
+                            part: Some(pattern.part_assignment.min(3)),
+                            pattern: Some(p as u8),
+                            track: Some(t as u8),
+                            step: Some(step as u8),
+                            ..SearchHit::new("condition", name)
+                        });
+                    }
+                }
+            }
+        }
+    }
+    hits
+}
+
+fn search_samples(path: &Path, text: &str) -> Result<Vec<SearchHit>, String> {
+    let project_file = if path.join("project.work").exists() {
+        path.join("project.work")
+    } else if path.join("project.strd").exists() {
+        path.join("project.strd")
+    } else {
+        return Err("Project file not found".to_string());
+    };
+    let project = ProjectFile::from_data_file(&project_file)
+        .map_err(|e| format!("Failed to read project: {:?}", e))?;
+    let wanted = text.to_lowercase();
+    let mut hits = Vec::new();
+    for (slot_type, slots) in [
+        ("Static", &project.slots.static_slots[..]),
+        ("Flex", &project.slots.flex_slots[..]),
+    ] {
+        for (idx, slot) in slots.iter().enumerate().take(128) {
+            let Some(sample_path) = slot.as_ref().and_then(|s| s.path.as_ref()) else {
+                continue;
+            };
+            let file_name = sample_path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            if file_name.to_lowercase().contains(&wanted) {
+                hits.push(SearchHit {
+                    slot_type: Some(slot_type.to_string()),
+                    slot_id: Some(idx as u8 + 1),
+                    ..SearchHit::new("sample", sample_path.to_string_lossy().to_string())
+                });
+            }
+        }
+    }
+    Ok(hits)
+}
+
+/// Run `query` over the project at `project_path`.
+pub fn run_search(project_path: &str, query: &SearchQuery) -> Result<Vec<SearchHit>, String> {
+    let path = Path::new(project_path);
+    if !path.is_dir() {
+        return Err(format!("Project path does not exist: {}", project_path));
+    }
+    match query {
+        SearchQuery::Machine { machine } => {
+            let wanted = normalize(machine);
+            let Some(machine_type) = MACHINE_TYPES.iter().position(|m| normalize(m) == wanted)
+            else {
+                return Err(format!(
+                    "Unknown machine: {}. Must be one of {}",
+                    machine,
+                    MACHINE_TYPES.join(", ")
+                ));
+            };
+            Ok(search_parts(&read_banks(path), "machine", |data, p, t| {
+                (data.parts.unsaved.0[p].audio_track_machine_types[t] as usize == machine_type)
+                    .then(|| MACHINE_TYPES[machine_type].to_string())
+            }))
+        }
+        SearchQuery::Fx { fx, slot } => {
+            if slot.is_some_and(|s| s != 1 && s != 2) {
+                return Err("FX slot must be 1 or 2".to_string());
+            }
+            let wanted = normalize(fx);
+            let Some(&(fx_type, _)) = FX_TYPES.iter().find(|(_, name)| normalize(name) == wanted)
+            else {
+                return Err(format!("Unknown effect: {}", fx));
+            };
+            Ok(search_parts(&read_banks(path), "fx", |data, p, t| {
+                let part = &data.parts.unsaved.0[p];
+                let fx1 = slot != &Some(2) && part.audio_track_fx1[t] == fx_type;
+                let fx2 = slot != &Some(1) && part.audio_track_fx2[t] == fx_type;
+                match (fx1, fx2) {
+                    (true, true) => Some(format!("FX1+FX2 {}", fx_type_name(fx_type))),
+                    (true, false) => Some(format!("FX1 {}", fx_type_name(fx_type))),
+                    (false, true) => Some(format!("FX2 {}", fx_type_name(fx_type))),
+                    (false, false) => None,
+                }
+            }))
+        }
+        SearchQuery::Condition { condition } => Ok(search_conditions(&read_banks(path), condition)),
+        SearchQuery::Sample { text } => search_samples(path, text),
+    }
+}
+
+#[tauri::command]
+pub async fn search_project(path: String, query: SearchQuery) -> Result<Vec<SearchHit>, String> {
+    tauri::async_runtime::spawn_blocking(move || run_search(&path, &query))
+        .await
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn project_with_bank(modify: impl FnOnce(&mut BankFile)) -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let mut project = ProjectFile::default();
+        project.slots.flex_slots[2] = Some(
+            ot_tools_io::projects::SlotAttributes::new(
+                ot_tools_io::settings::SlotType::Flex,
+                3,
+                Some(std::path::PathBuf::from("../AUDIO/Big Kick 01.wav")),
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap(),
+        );
+        project
+            .to_data_file(&dir.path().join("project.work"))
+            .unwrap();
+        let mut bank = BankFile::default();
+        modify(&mut bank);
+        bank.to_data_file(&dir.path().join("bank01.work")).unwrap();
+        dir
+    }
+
+    fn search(dir: &TempDir, query: SearchQuery) -> Vec<SearchHit> {
+        run_search(&dir.path().to_string_lossy(), &query).unwrap()
+    }
+
+    #[test]
+    fn test_search_machines_and_fx() {
+        let dir = project_with_bank(|bank| {
+            bank.parts.unsaved.0[1].audio_track_machine_types[6] = 1;
+            bank.parts.unsaved.0[3].audio_track_fx2[2] = 28;
+        });
+
+        let hits = search(
+            &dir,
+            SearchQuery::Machine {
+                machine: "FLEX".to_string(),
+            },
+        );
+        assert!(hits
+            .iter()
+            .any(|h| h.bank == Some(0) && h.part == Some(1) && h.track == Some(6)));
+        assert!(hits.iter().all(|h| h.label == "Flex"));
+
+        let hits = search(
+            &dir,
+            SearchQuery::Fx {
+                fx: "lofi".to_string(),
+                slot: None,
+            },
+        );
+        assert_eq!(hits.len(), 1);
+        assert_eq!((hits[0].part, hits[0].track), (Some(3), Some(2)));
+        assert_eq!(hits[0].label, "FX2 Lo-Fi");
+        let hits = search(
+            &dir,
+            SearchQuery::Fx {
+                fx: "lo-fi".to_string(),
+                slot: Some(1),
+            },
+        );
+        assert!(hits.is_empty());
+
+        assert!(run_search(
+            &dir.path().to_string_lossy(),
+            &SearchQuery::Machine {
+                machine: "sampler".to_string()
+            }
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_search_conditions_and_samples() {
+        let dir = project_with_bank(|bank| {
+            let pattern = &mut bank.patterns.0[4];
+            pattern.scale.master_len = 16;
+            let track = &mut pattern.audio_track_trigs.0[1];
+            track.trig_masks.trigger = [0, 0, 0, 0, 0, 0, 0, 0b0000_1001];
+            track.trig_offsets_repeats_conditions[0][1] = 1; // Fill
+            track.trig_offsets_repeats_conditions[3][1] = 0x80 | 1; // Fill + micro-timing
+            track.trig_offsets_repeats_conditions[9][1] = 1; // no trig on step 10
+        });
+
+        let hits = search(
+            &dir,
+            SearchQuery::Condition {
+                condition: "fill".to_string(),
+            },
+        );
+        let steps: Vec<_> = hits.iter().map(|h| h.step.unwrap()).collect();
+        assert_eq!(steps, vec![0, 3]);
+        assert_eq!(hits[0].pattern, Some(4));
+        assert_eq!(hits[0].track, Some(1));
+
+        let hits = search(
+            &dir,
+            SearchQuery::Sample {
+                text: "KICK".to_string(),
+            },
+        );
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].slot_type.as_deref(), Some("Flex"));
+        assert_eq!(hits[0].slot_id, Some(3));
+    }
+}
@@ -0,0 +1,116 @@
+// Watches the open project's directory for files rewritten behind the app's
+// back (the device saving over USB, another tool, a sync client) and emits a
+// "project-changed" event per changed file, so the frontend can reload what
+// it shows instead of silently displaying stale values.
+//
+// Only one project is watched at a time; watching another replaces it. Bursts
+// of filesystem events (a save touches a file several times) are coalesced
+// by dir_watcher.rs. The app's own writes are reported too: reloading
+// after them is harmless.
+
+use crate::dir_watcher::{self, WatchSlot};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+
+static WATCHER: WatchSlot = WatchSlot::new(None);
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProjectChangedEvent {
+    pub project_path: String,
+    pub file: String,
+    pub kind: String,            // "project", "bank", "markers" or "arrangement"
+    pub bank: Option<u8>,        // 0-based, for bank files
+    pub bank_id: Option<String>, // "A".."P", for bank files
+}
+
+/// What a changed file in the project directory is, or None for files the
+/// app doesn't read (samples, backups, temp files).
+fn classify(project_path: &str, file: &Path) -> Option<ProjectChangedEvent> {
+    let name = file.file_name()?.to_str()?.to_ascii_lowercase();
+    let (stem, ext) = name.rsplit_once('.')?;
+    if ext != "work" && ext != "strd" {
+        return None;
+    }
+    let (kind, bank) = match stem {
+        "project" => ("project", None),
+        "markers" => ("markers", None),
+        _ if stem.starts_with("arr") => ("arrangement", None),
+        _ => {
+            let number: u8 = stem.strip_prefix("bank")?.parse().ok()?;
+            if !(1..=16).contains(&number) {
+                return None;
+            }
+            ("bank", Some(number - 1))
+        }
+    };
+    Some(ProjectChangedEvent {
+        project_path: project_path.to_string(),
+        file: name.clone(),
+        kind: kind.to_string(),
+        bank,
+        bank_id: bank.map(|b| crate::project_reader::BANK_LETTERS[b as usize].to_string()),
+    })
+}
+
+/// Start watching `project_path`, replacing any previously watched project.
+pub fn watch(app: AppHandle, project_path: &str) -> Result<(), String> {
+    let dir = PathBuf::from(project_path);
+    if !dir.is_dir() {
+        return Err(format!("Project directory not found: {}", project_path));
+    }
+
+    let watched = project_path.to_string();
+    dir_watcher::watch(&WATCHER, &dir, move |changed| {
+        let mut emitted = BTreeSet::new();
+        for event in changed.iter().filter_map(|p| classify(&watched, p)) {
+            if emitted.insert(event.file.clone()) {
+                let _ = app.emit("project-changed", event);
+            }
+        }
+    })
+}
+
+/// Stop watching. Returns the project that was being watched, if any.
+pub fn unwatch() -> Option<String> {
+    dir_watcher::unwatch(&WATCHER)
+}
+
+#[tauri::command]
+pub fn watch_project(app: AppHandle, path: String) -> Result<(), String> {
+    watch(app, &path)
+}
+
+#[tauri::command]
+pub fn unwatch_project() -> Option<String> {
+    unwatch()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_project_files() {
+        let dir = Path::new("/set/PROJ");
+        let bank = classify("/set/PROJ", &dir.join("bank03.work")).unwrap();
+        assert_eq!(bank.kind, "bank");
+        assert_eq!(bank.bank, Some(2));
+        assert_eq!(bank.bank_id.as_deref(), Some("C"));
+
+        assert_eq!(
+            classify("/set/PROJ", &dir.join("project.strd"))
+                .unwrap()
+                .kind,
+            "project"
+        );
+        assert_eq!(
+            classify("/set/PROJ", &dir.join("arr01.work")).unwrap().kind,
+            "arrangement"
+        );
+        assert!(classify("/set/PROJ", &dir.join("bank17.work")).is_none());
+        assert!(classify("/set/PROJ", &dir.join("kick.wav")).is_none());
+        assert!(classify("/set/PROJ", &dir.join("bank01.work.tmp")).is_none());
+    }
+}
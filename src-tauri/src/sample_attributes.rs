@@ -0,0 +1,638 @@
+// .ot sample attribute files: the Audio Editor settings (trim, loop, slices,
+// gain, tempo, timestretch) the Octatrack saves next to a sample.
+
+use ot_tools_io::settings::{LoopMode, TimeStretchMode, TrigQuantizationMode};
+use ot_tools_io::types::SlotMarkers;
+use ot_tools_io::{HasChecksumField, OctatrackFileIO, SampleSettingsFile};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// A slice's loop point is unset when it holds this value.
+const NO_LOOP_POINT: u32 = 0xFFFF_FFFF;
+pub(crate) const MAX_SLICES: usize = 64;
+/// Gain is stored with a +48 offset in 0.5 dB steps: 0..=96 is -24..=+24 dB.
+const MAX_GAIN: u8 = 96;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OtSlice {
+    pub start: u32, // sample frames
+    pub end: u32,
+    pub loop_point: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OtSampleAttributes {
+    pub bpm: f32,
+    pub gain: u8, // 48 = 0 dB, like SampleSlot::gain
+    pub loop_mode: String,
+    pub timestretch_mode: String,
+    pub trig_quantization: String,
+    pub trim_start: u32, // sample frames
+    pub trim_end: u32,
+    pub loop_point: u32,
+    pub slices: Vec<OtSlice>,
+}
+
+/// The `.ot` file belonging to `sample_path` (same name, `.ot` extension).
+pub fn ot_path_for(sample_path: &Path) -> PathBuf {
+    sample_path.with_extension("ot")
+}
+
+fn attributes_from(ot: &SampleSettingsFile) -> OtSampleAttributes {
+    let slice_count = (ot.slices_len as usize).min(ot.slices.len());
+    OtSampleAttributes {
+        bpm: ot.tempo as f32 / 24.0,
+        gain: ot.gain as u8,
+        loop_mode: format!("{:?}", LoopMode::try_from(ot.loop_mode).unwrap_or_default()),
+        timestretch_mode: format!(
+            "{:?}",
+            TimeStretchMode::try_from(ot.stretch).unwrap_or_default()
+        ),
+        trig_quantization: format!(
+            "{:?}",
+            TrigQuantizationMode::try_from(ot.quantization as u32).unwrap_or_default()
+        ),
+        trim_start: ot.trim_start,
+        trim_end: ot.trim_end,
+        loop_point: ot.loop_start,
+        slices: ot.slices[..slice_count]
+            .iter()
+            .map(|s| OtSlice {
+                start: s.trim_start,
+                end: s.trim_end,
+                loop_point: Some(s.loop_start).filter(|&p| p != NO_LOOP_POINT),
+            })
+            .collect(),
+    }
+}
+
+/// Parse an `.ot` file.
+pub fn read_ot_attributes(ot_path: &Path) -> Result<OtSampleAttributes, String> {
+    let ot = SampleSettingsFile::from_data_file(ot_path)
+        .map_err(|e| format!("Failed to read {}: {:?}", ot_path.display(), e))?;
+    Ok(attributes_from(&ot))
+}
+
+/// Attributes from the `.ot` next to `sample_path`, if there is a readable one.
+pub fn sidecar_attributes(sample_path: &Path) -> Option<OtSampleAttributes> {
+    let ot_path = ot_path_for(sample_path);
+    if !ot_path.is_file() {
+        return None;
+    }
+    read_ot_attributes(&ot_path).ok()
+}
+
+/// Changes to apply to a sample's .ot file. `None` leaves a field as is.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OtAttributesUpdate {
+    pub bpm: Option<f32>,
+    pub gain: Option<u8>,
+    pub trim_start: Option<u32>,
+    pub trim_end: Option<u32>,
+    pub loop_point: Option<u32>,
+    pub slices: Option<Vec<OtSlice>>,
+}
+
+/// Check the edited attributes against the device limits and, when known,
+/// the sample length in frames.
+fn validate(ot: &SampleSettingsFile, frames: Option<u64>) -> Result<(), String> {
+    if ot.trim_start > ot.trim_end {
+        return Err("Trim start must not be after trim end".to_string());
+    }
+    if let Some(frames) = frames {
+        if ot.trim_end as u64 > frames {
+            return Err(format!(
+                "Trim end {} is beyond the sample length ({} frames)",
+                ot.trim_end, frames
+            ));
+        }
+    }
+    let slice_count = (ot.slices_len as usize).min(ot.slices.len());
+    for (i, slice) in ot.slices[..slice_count].iter().enumerate() {
+        if slice.trim_start >= slice.trim_end {
+            return Err(format!("Slice {}: start must be before end", i + 1));
+        }
+        if frames.is_some_and(|f| slice.trim_end as u64 > f) {
+            return Err(format!("Slice {} ends beyond the sample", i + 1));
+        }
+        if slice.loop_start != NO_LOOP_POINT
+            && !(slice.trim_start..slice.trim_end).contains(&slice.loop_start)
+        {
+            return Err(format!("Slice {}: loop point is outside the slice", i + 1));
+        }
+    }
+    Ok(())
+}
+
+/// Create or update the `.ot` next to `sample_path`. A new file starts from
+/// the device defaults with the trim covering the whole sample.
+pub fn write_ot_attributes(
+    sample_path: &Path,
+    update: &OtAttributesUpdate,
+) -> Result<OtSampleAttributes, String> {
+    if !sample_path.is_file() {
+        return Err(format!("Sample not found: {}", sample_path.display()));
+    }
+    let frames =
+        crate::project_reader::audio_frames_and_rate(sample_path).map(|(frames, _)| frames);
+    let ot_path = ot_path_for(sample_path);
+    let mut ot = if ot_path.is_file() {
+        SampleSettingsFile::from_data_file(&ot_path)
+            .map_err(|e| format!("Failed to read {}: {:?}", ot_path.display(), e))?
+    } else {
+        let mut markers = SlotMarkers::default();
+        markers.trim_end = frames.unwrap_or(0).min(u32::MAX as u64) as u32;
+        SampleSettingsFile::new(markers, None, None, None, None, None, None, None)
+            .map_err(|e| format!("Failed to create .ot data: {:?}", e))?
+    };
+
+    if let Some(bpm) = update.bpm {
+        if !(30.0..=300.0).contains(&bpm) {
+            return Err(format!("BPM {} is out of range (30-300)", bpm));
+        }
+        ot.tempo = ((bpm * 24.0).round() as u32).into();
+    }
+    if let Some(gain) = update.gain {
+        if gain > MAX_GAIN {
+            return Err(format!("Gain {} is out of range (0-{})", gain, MAX_GAIN));
+        }
+        ot.gain = gain.into();
+    }
+    if let Some(trim_start) = update.trim_start {
+        ot.trim_start = trim_start;
+    }
+    if let Some(trim_end) = update.trim_end {
+        ot.trim_end = trim_end;
+    }
+    if let Some(loop_point) = update.loop_point {
+        ot.loop_start = loop_point;
+    }
+    if let Some(slices) = &update.slices {
+        if slices.len() > MAX_SLICES {
+            return Err(format!(
+                "Too many slices: {} (max {})",
+                slices.len(),
+                MAX_SLICES
+            ));
+        }
+        for (i, target) in ot.slices.iter_mut().enumerate() {
+            let slice = slices.get(i);
+            target.trim_start = slice.map(|s| s.start).unwrap_or(0);
+            target.trim_end = slice.map(|s| s.end).unwrap_or(0);
+            target.loop_start = slice.and_then(|s| s.loop_point).unwrap_or(NO_LOOP_POINT);
+        }
+        ot.slices_len = slices.len() as u32;
+    }
+    validate(&ot, frames)?;
+
+    ot.checksum = ot
+        .calculate_checksum()
+        .map_err(|e| format!("Failed to calculate checksum: {:?}", e))?;
+    crate::atomic_write::write_data_file(&ot, &ot_path)
+        .map_err(|e| format!("Failed to write {}: {}", ot_path.display(), e))?;
+    Ok(attributes_from(&ot))
+}
+
+/// Replace the slices of `sample_path`'s .ot with `count` equal slices
+/// spanning the whole sample (16/32/64 in the UI; any 1-64 is accepted).
+pub fn equal_slice_grid(sample_path: &Path, count: usize) -> Result<OtSampleAttributes, String> {
+    if !(1..=MAX_SLICES).contains(&count) {
+        return Err(format!("Slice count must be between 1 and {}", MAX_SLICES));
+    }
+    let (frames, _) = crate::project_reader::audio_frames_and_rate(sample_path)
+        .ok_or_else(|| format!("Could not read sample length: {}", sample_path.display()))?;
+    if frames < count as u64 {
+        return Err(format!(
+            "Sample is too short for {} slices ({} frames)",
+            count, frames
+        ));
+    }
+    let boundary = |i: usize| (frames * i as u64 / count as u64) as u32;
+    let slices = (0..count)
+        .map(|i| OtSlice {
+            start: boundary(i),
+            end: boundary(i + 1),
+            loop_point: None,
+        })
+        .collect();
+    let update = OtAttributesUpdate {
+        slices: Some(slices),
+        ..Default::default()
+    };
+    write_ot_attributes(sample_path, &update)
+}
+
+/// Options for slicing a sample at its transients.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TransientSliceOptions {
+    pub sensitivity: Option<f32>, // 0-1, default 0.5; higher finds quieter hits
+    pub max_slices: Option<usize>, // default 64
+    pub min_slice_ms: Option<u32>, // default 50
+}
+
+/// Replace the slices of `sample_path`'s .ot with one slice per transient.
+/// The first slice always starts at the beginning of the sample; when there
+/// are more transients than `max_slices`, the strongest are kept.
+pub fn transient_slice_grid(
+    sample_path: &Path,
+    options: &TransientSliceOptions,
+) -> Result<OtSampleAttributes, String> {
+    let max_slices = options.max_slices.unwrap_or(MAX_SLICES);
+    if !(1..=MAX_SLICES).contains(&max_slices) {
+        return Err(format!("Slice count must be between 1 and {}", MAX_SLICES));
+    }
+    let sensitivity = options.sensitivity.unwrap_or(0.5);
+    if !(0.0..=1.0).contains(&sensitivity) {
+        return Err("Sensitivity must be between 0 and 1".to_string());
+    }
+    let decoded = crate::audio_pool::decode_audio_file(sample_path)?;
+    let mono = crate::audio_analysis::mono_mix(&decoded.channels);
+    if mono.is_empty() {
+        return Err(format!("No audio in {}", sample_path.display()));
+    }
+    let min_gap =
+        (decoded.sample_rate as u64 * options.min_slice_ms.unwrap_or(50) as u64 / 1000) as usize;
+
+    let mut onsets: Vec<_> = crate::audio_analysis::detect_onsets(&mono, sensitivity, min_gap)
+        .into_iter()
+        .filter(|o| o.position >= min_gap.max(1) && mono.len() - o.position >= min_gap.max(1))
+        .collect();
+    onsets.sort_by(|a, b| b.strength.total_cmp(&a.strength));
+    let mut starts: Vec<usize> = onsets
+        .iter()
+        .take(max_slices - 1)
+        .map(|o| o.position)
+        .collect();
+    starts.push(0);
+    starts.sort_unstable();
+
+    let slices = starts
+        .iter()
+        .enumerate()
+        .map(|(i, &start)| OtSlice {
+            start: start as u32,
+            end: starts.get(i + 1).copied().unwrap_or(mono.len()) as u32,
+            loop_point: None,
+        })
+        .collect();
+    let update = OtAttributesUpdate {
+        slices: Some(slices),
+        ..Default::default()
+    };
+    write_ot_attributes(sample_path, &update)
+}
+
+/// Sample extensions an .ot file can belong to.
+const SAMPLE_EXTENSIONS: [&str; 3] = ["wav", "aif", "aiff"];
+
+/// True when `path` is a sample type that can have an .ot file.
+pub(crate) fn has_sample_extension(path: &Path) -> bool {
+    path.extension().is_some_and(|e| {
+        SAMPLE_EXTENSIONS
+            .iter()
+            .any(|ext| e.eq_ignore_ascii_case(ext))
+    })
+}
+
+/// True when `path` is an .ot file sitting next to its sample, so it is
+/// carried along with the sample rather than copied on its own.
+pub(crate) fn is_sidecar_with_sample(path: &Path) -> bool {
+    let is_ot = path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("ot"));
+    is_ot
+        && SAMPLE_EXTENSIONS.iter().any(|ext| {
+            path.with_extension(ext).is_file() || path.with_extension(ext.to_uppercase()).is_file()
+        })
+}
+
+/// Scale a sample position from `from` frames to `to` frames.
+fn rescale(position: u32, from: u64, to: u64) -> u32 {
+    ((position as u64 * to + from / 2) / from).min(to) as u32
+}
+
+/// Give `dest` a copy of `source`'s .ot, if it has one, replacing any .ot
+/// already there. When the sample was resampled on the way, trim, loop and
+/// slice positions are scaled to the new length so they still mark the same
+/// audio.
+pub fn copy_sidecar(source: &Path, dest: &Path) -> Result<(), String> {
+    let source_ot = ot_path_for(source);
+    let dest_ot = ot_path_for(dest);
+    if !has_sample_extension(source) || !source_ot.is_file() || source_ot == dest_ot {
+        return Ok(());
+    }
+    std::fs::copy(&source_ot, &dest_ot)
+        .map_err(|e| format!("Failed to copy {}: {}", source_ot.display(), e))?;
+
+    let frames = |path: &Path| crate::project_reader::audio_frames_and_rate(path).map(|(f, _)| f);
+    let (Some(from), Some(to)) = (frames(source), frames(dest)) else {
+        return Ok(());
+    };
+    if from == to || from == 0 {
+        return Ok(());
+    }
+    let attributes = read_ot_attributes(&dest_ot)?;
+    let update = OtAttributesUpdate {
+        trim_start: Some(rescale(attributes.trim_start, from, to)),
+        trim_end: Some(rescale(attributes.trim_end, from, to)),
+        loop_point: Some(attributes.loop_point)
+            .filter(|&p| p != NO_LOOP_POINT)
+            .map(|p| rescale(p, from, to)),
+        slices: Some(
+            attributes
+                .slices
+                .iter()
+                .map(|s| OtSlice {
+                    start: rescale(s.start, from, to),
+                    end: rescale(s.end, from, to),
+                    loop_point: s.loop_point.map(|p| rescale(p, from, to)),
+                })
+                .collect(),
+        ),
+        ..Default::default()
+    };
+    write_ot_attributes(dest, &update)?;
+    Ok(())
+}
+
+/// Move `old`'s .ot to go with the sample now at `new`, if there is one.
+/// Fails without moving anything when `new` already has an .ot.
+pub fn move_sidecar(old: &Path, new: &Path) -> Result<(), String> {
+    let old_ot = ot_path_for(old);
+    let new_ot = ot_path_for(new);
+    if !has_sample_extension(old) || !old_ot.is_file() || old_ot == new_ot {
+        return Ok(());
+    }
+    if new_ot.exists() {
+        return Err(format!("File already exists: {}", new_ot.display()));
+    }
+    std::fs::rename(&old_ot, &new_ot)
+        .map_err(|e| format!("Failed to move {}: {}", old_ot.display(), e))
+}
+
+#[tauri::command]
+pub fn get_sample_attributes(path: String) -> Option<OtSampleAttributes> {
+    sidecar_attributes(Path::new(&path))
+}
+
+#[tauri::command]
+pub async fn set_sample_attributes(
+    path: String,
+    update: OtAttributesUpdate,
+) -> Result<OtSampleAttributes, String> {
+    crate::fs_scope::ensure_allowed(&path)?;
+    tauri::async_runtime::spawn_blocking(move || write_ot_attributes(Path::new(&path), &update))
+        .await
+        .unwrap()
+}
+
+#[tauri::command]
+pub async fn generate_slices(path: String, count: usize) -> Result<OtSampleAttributes, String> {
+    crate::fs_scope::ensure_allowed(&path)?;
+    tauri::async_runtime::spawn_blocking(move || equal_slice_grid(Path::new(&path), count))
+        .await
+        .unwrap()
+}
+
+#[tauri::command]
+pub async fn generate_transient_slices(
+    path: String,
+    options: Option<TransientSliceOptions>,
+) -> Result<OtSampleAttributes, String> {
+    crate::fs_scope::ensure_allowed(&path)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        transient_slice_grid(Path::new(&path), &options.unwrap_or_default())
+    })
+    .await
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::write_wav;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_no_sidecar() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("kick.wav"), b"RIFF").unwrap();
+        assert!(sidecar_attributes(&dir.path().join("kick.wav")).is_none());
+    }
+
+    #[test]
+    fn test_sidecar_trim_and_slices() {
+        let dir = TempDir::new().unwrap();
+        let mut markers = SlotMarkers::default();
+        markers.trim_offset = 100;
+        markers.trim_end = 40000;
+        markers.slice_count = 2;
+        markers.slices[0].trim_start = 0;
+        markers.slices[0].trim_end = 20000;
+        markers.slices[0].loop_start = NO_LOOP_POINT;
+        markers.slices[1].trim_start = 20000;
+        markers.slices[1].trim_end = 40000;
+        markers.slices[1].loop_start = 30000;
+        SampleSettingsFile::new(markers, None, None, None, None, None, None, None)
+            .unwrap()
+            .to_data_file(&dir.path().join("loop.ot"))
+            .unwrap();
+
+        let attributes = sidecar_attributes(&dir.path().join("loop.wav")).unwrap();
+
+        assert_eq!(attributes.trim_start, 100);
+        assert_eq!(attributes.trim_end, 40000);
+        assert_eq!(
+            attributes.slices,
+            vec![
+                OtSlice {
+                    start: 0,
+                    end: 20000,
+                    loop_point: None
+                },
+                OtSlice {
+                    start: 20000,
+                    end: 40000,
+                    loop_point: Some(30000)
+                },
+            ]
+        );
+    }
+
+    /// Mono 16-bit WAV with `frames` silent frames.
+    #[test]
+    fn test_write_creates_ot_covering_whole_sample() {
+        let dir = TempDir::new().unwrap();
+        let wav = dir.path().join("loop.wav");
+        write_wav(&wav, 1, 44100, &[0; 1000]);
+
+        let update = OtAttributesUpdate {
+            bpm: Some(120.0),
+            gain: Some(60),
+            ..Default::default()
+        };
+        let written = write_ot_attributes(&wav, &update).unwrap();
+
+        assert_eq!(written.trim_end, 1000);
+        let read_back = sidecar_attributes(&wav).unwrap();
+        assert_eq!(read_back, written);
+        assert_eq!(read_back.bpm, 120.0);
+        assert_eq!(read_back.gain, 60);
+    }
+
+    #[test]
+    fn test_write_slices_and_keep_other_fields() {
+        let dir = TempDir::new().unwrap();
+        let wav = dir.path().join("loop.wav");
+        write_wav(&wav, 1, 44100, &[0; 1000]);
+        write_ot_attributes(
+            &wav,
+            &OtAttributesUpdate {
+                bpm: Some(90.0),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let slices = vec![
+            OtSlice {
+                start: 0,
+                end: 500,
+                loop_point: None,
+            },
+            OtSlice {
+                start: 500,
+                end: 1000,
+                loop_point: Some(750),
+            },
+        ];
+        let update = OtAttributesUpdate {
+            slices: Some(slices.clone()),
+            ..Default::default()
+        };
+        let written = write_ot_attributes(&wav, &update).unwrap();
+
+        assert_eq!(written.slices, slices);
+        assert_eq!(written.bpm, 90.0);
+    }
+
+    #[test]
+    fn test_write_rejects_invalid_values() {
+        let dir = TempDir::new().unwrap();
+        let wav = dir.path().join("loop.wav");
+        write_wav(&wav, 1, 44100, &[0; 1000]);
+
+        let beyond_end = OtAttributesUpdate {
+            trim_end: Some(2000),
+            ..Default::default()
+        };
+        assert!(write_ot_attributes(&wav, &beyond_end).is_err());
+        let loud = OtAttributesUpdate {
+            gain: Some(97),
+            ..Default::default()
+        };
+        assert!(write_ot_attributes(&wav, &loud).is_err());
+        // Nothing written on failure
+        assert!(!ot_path_for(&wav).exists());
+    }
+
+    #[test]
+    fn test_equal_slice_grid() {
+        let dir = TempDir::new().unwrap();
+        let wav = dir.path().join("break.wav");
+        write_wav(&wav, 1, 44100, &[0; 1000]);
+
+        let attributes = equal_slice_grid(&wav, 16).unwrap();
+
+        assert_eq!(attributes.slices.len(), 16);
+        assert_eq!(attributes.slices[0].start, 0);
+        assert_eq!(attributes.slices[0].end, 62);
+        assert_eq!(attributes.slices[15].end, 1000);
+        assert!(attributes.slices.windows(2).all(|w| w[0].end == w[1].start));
+        assert!(equal_slice_grid(&wav, 65).is_err());
+        assert!(equal_slice_grid(&wav, 0).is_err());
+    }
+
+    #[test]
+    fn test_transient_slice_grid() {
+        let dir = TempDir::new().unwrap();
+        let wav = dir.path().join("break.wav");
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 44100,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&wav, spec).unwrap();
+        for i in 0..30000u32 {
+            // Decaying bursts at 0, 10000 and 20000, silence in between
+            let t = i % 10000;
+            let sample = if t < 2000 {
+                let sign = if (t * 7919) % 3 == 0 { 1.0 } else { -1.0 };
+                sign * 20000.0 * (1.0 - t as f32 / 2000.0)
+            } else {
+                0.0
+            };
+            writer.write_sample(sample as i16).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let attributes = transient_slice_grid(&wav, &TransientSliceOptions::default()).unwrap();
+        let starts: Vec<u32> = attributes.slices.iter().map(|s| s.start).collect();
+        assert_eq!(starts, vec![0, 10000, 20000]);
+        assert_eq!(attributes.slices[2].end, 30000);
+
+        let two = TransientSliceOptions {
+            max_slices: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(transient_slice_grid(&wav, &two).unwrap().slices.len(), 2);
+        let invalid = TransientSliceOptions {
+            sensitivity: Some(1.5),
+            ..Default::default()
+        };
+        assert!(transient_slice_grid(&wav, &invalid).is_err());
+    }
+
+    #[test]
+    fn test_sidecar_follows_resampled_copy_and_move() {
+        let dir = TempDir::new().unwrap();
+        let source = dir.path().join("loop.wav");
+        write_wav(&source, 1, 44100, &[0; 4800]);
+        let slices = vec![
+            OtSlice {
+                start: 0,
+                end: 2400,
+                loop_point: None,
+            },
+            OtSlice {
+                start: 2400,
+                end: 4800,
+                loop_point: Some(3600),
+            },
+        ];
+        let update = OtAttributesUpdate {
+            slices: Some(slices),
+            ..Default::default()
+        };
+        write_ot_attributes(&source, &update).unwrap();
+        assert!(is_sidecar_with_sample(&dir.path().join("loop.ot")));
+
+        // The copy is shorter, as if converted from 48kHz
+        fs::create_dir(dir.path().join("out")).unwrap();
+        let dest = dir.path().join("out").join("loop.wav");
+        write_wav(&dest, 1, 44100, &[0; 4410]);
+        copy_sidecar(&source, &dest).unwrap();
+        let copied = sidecar_attributes(&dest).unwrap();
+        assert_eq!(copied.trim_end, 4410);
+        assert_eq!((copied.slices[1].start, copied.slices[1].end), (2205, 4410));
+        assert_eq!(copied.slices[1].loop_point, Some(3308));
+
+        let renamed = dir.path().join("out").join("break.wav");
+        fs::rename(&dest, &renamed).unwrap();
+        move_sidecar(&dest, &renamed).unwrap();
+        assert!(!ot_path_for(&dest).exists());
+        assert_eq!(sidecar_attributes(&renamed).unwrap(), copied);
+    }
+}
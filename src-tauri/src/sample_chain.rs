@@ -0,0 +1,243 @@
+// Sample chains: several samples joined end to end into one 44.1kHz WAV, with
+// an .ot file holding one slice per source so the chain can be played slice
+// by slice from a single Flex or Static slot.
+
+use crate::audio_pool::{
+    decode_audio_file, resample_audio_with_progress, write_wav_file_with_progress, Dither,
+    OCTATRACK_SAMPLE_RATE,
+};
+use crate::project_reader::OT_TOTAL_RAM_BYTES;
+use crate::sample_attributes::{
+    ot_path_for, write_ot_attributes, OtAttributesUpdate, OtSlice, MAX_SLICES,
+};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChainOptions {
+    /// Pad every source to the longest one, so the slices form an even grid
+    #[serde(default)]
+    pub equal_length: bool,
+    #[serde(default)]
+    pub bit_depth: Option<u16>, // 16 (default) or 24
+    #[serde(default)]
+    pub overwrite: bool,
+    /// Dither used when the chain is written at 16-bit
+    #[serde(default)]
+    pub dither: Dither,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SampleChainReport {
+    pub path: String,
+    pub ot_path: String,
+    pub slices: Vec<OtSlice>,
+    pub channels: u16,
+    pub total_frames: u64,
+    pub duration_seconds: f64,
+    pub flex_ram_bytes: u64, // as loaded into Flex RAM
+    pub flex_ram_limit_bytes: u64,
+    pub fits_flex: bool,
+    pub warnings: Vec<String>,
+}
+
+/// Decode `path` to 44.1kHz, keeping its channels.
+fn load_source(path: &Path) -> Result<Vec<Vec<f32>>, String> {
+    let decoded = decode_audio_file(path)?;
+    let mut samples = decoded.channels;
+    if samples.is_empty() || samples[0].is_empty() {
+        return Err(format!("No audio in {}", path.display()));
+    }
+    if decoded.sample_rate != OCTATRACK_SAMPLE_RATE {
+        samples = resample_audio_with_progress(
+            &samples,
+            decoded.sample_rate,
+            OCTATRACK_SAMPLE_RATE,
+            &None,
+            |_| {},
+        )?;
+    }
+    Ok(samples)
+}
+
+/// Join `sources` into a chain at `dest` and write its .ot slices.
+pub fn build_chain(
+    sources: &[PathBuf],
+    dest: &Path,
+    options: &ChainOptions,
+) -> Result<SampleChainReport, String> {
+    if sources.is_empty() {
+        return Err("Select at least one sample".to_string());
+    }
+    if sources.len() > MAX_SLICES {
+        return Err(format!(
+            "Too many samples: {} (a chain holds at most {} slices)",
+            sources.len(),
+            MAX_SLICES
+        ));
+    }
+    let bit_depth = options.bit_depth.unwrap_or(16);
+    if bit_depth != 16 && bit_depth != 24 {
+        return Err(format!("Unsupported bit depth: {}", bit_depth));
+    }
+    if dest.exists() && !options.overwrite {
+        return Err(format!("File already exists: {}", dest.display()));
+    }
+
+    let mut parts = sources
+        .iter()
+        .map(|p| load_source(p))
+        .collect::<Result<Vec<_>, _>>()?;
+    // Stereo if any source is; mono sources are duplicated to both sides
+    let channels = parts.iter().map(|p| p.len()).max().unwrap_or(1).min(2);
+    for part in &mut parts {
+        part.truncate(channels);
+        while part.len() < channels {
+            part.push(part[0].clone());
+        }
+    }
+
+    let longest = parts.iter().map(|p| p[0].len()).max().unwrap_or(0);
+    let mut chain: Vec<Vec<f32>> = vec![Vec::new(); channels];
+    let mut slices = Vec::with_capacity(parts.len());
+    for part in &parts {
+        let start = chain[0].len();
+        let len = if options.equal_length {
+            longest
+        } else {
+            part[0].len()
+        };
+        for (out, channel) in chain.iter_mut().zip(part) {
+            out.extend_from_slice(channel);
+            out.resize(start + len, 0.0);
+        }
+        slices.push(OtSlice {
+            start: start as u32,
+            end: (start + len) as u32,
+            loop_point: None,
+        });
+    }
+
+    let total_frames = chain[0].len() as u64;
+    if total_frames > u32::MAX as u64 {
+        return Err("Chain is too long for an .ot file".to_string());
+    }
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    write_wav_file_with_progress(
+        dest,
+        &chain,
+        OCTATRACK_SAMPLE_RATE,
+        bit_depth,
+        options.dither,
+        &None,
+        |_| {},
+    )?;
+    // A stale .ot from an earlier chain would keep its old trim
+    let ot_path = ot_path_for(dest);
+    if ot_path.exists() {
+        fs::remove_file(&ot_path)
+            .map_err(|e| format!("Failed to replace {}: {}", ot_path.display(), e))?;
+    }
+    let update = OtAttributesUpdate {
+        slices: Some(slices),
+        ..Default::default()
+    };
+    let attributes = write_ot_attributes(dest, &update).inspect_err(|_| {
+        let _ = fs::remove_file(dest);
+    })?;
+
+    // Flex RAM holds 16-bit unless "load 24-bit flex" is on; count the worst case
+    let bytes_per_sample = (bit_depth / 8) as u64;
+    let flex_ram_bytes = total_frames * channels as u64 * bytes_per_sample;
+    let fits_flex = flex_ram_bytes <= OT_TOTAL_RAM_BYTES;
+    let mut warnings = Vec::new();
+    if !fits_flex {
+        warnings.push(format!(
+            "Chain needs {:.1} MiB of Flex RAM, more than the {:.1} MiB available; use it as a Static sample",
+            flex_ram_bytes as f64 / (1024.0 * 1024.0),
+            OT_TOTAL_RAM_BYTES as f64 / (1024.0 * 1024.0)
+        ));
+    }
+
+    Ok(SampleChainReport {
+        path: dest.to_string_lossy().to_string(),
+        ot_path: ot_path.to_string_lossy().to_string(),
+        slices: attributes.slices,
+        channels: channels as u16,
+        total_frames,
+        duration_seconds: total_frames as f64 / OCTATRACK_SAMPLE_RATE as f64,
+        flex_ram_bytes,
+        flex_ram_limit_bytes: OT_TOTAL_RAM_BYTES,
+        fits_flex,
+        warnings,
+    })
+}
+
+#[tauri::command]
+pub async fn build_sample_chain(
+    paths: Vec<String>,
+    dest: String,
+    options: Option<ChainOptions>,
+) -> Result<SampleChainReport, String> {
+    crate::fs_scope::ensure_allowed(&dest)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let sources: Vec<PathBuf> = paths.iter().map(PathBuf::from).collect();
+        build_chain(&sources, Path::new(&dest), &options.unwrap_or_default())
+    })
+    .await
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sample_attributes::sidecar_attributes;
+    use crate::test_support::write_wav;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_build_chain_slices_each_source() {
+        let dir = TempDir::new().unwrap();
+        let kick = dir.path().join("kick.wav");
+        let hat = dir.path().join("hat.wav");
+        write_wav(&kick, 1, 44100, &[1000; 300]);
+        write_wav(&hat, 2, 44100, &[1000; 200]);
+        let dest = dir.path().join("chain.wav");
+
+        let report = build_chain(&[kick.clone(), hat.clone()], &dest, &Default::default()).unwrap();
+
+        assert_eq!(report.channels, 2);
+        assert_eq!(report.total_frames, 400);
+        assert_eq!(
+            report
+                .slices
+                .iter()
+                .map(|s| (s.start, s.end))
+                .collect::<Vec<_>>(),
+            vec![(0, 300), (300, 400)]
+        );
+        assert!(report.fits_flex);
+        assert_eq!(sidecar_attributes(&dest).unwrap().slices, report.slices);
+        let reader = hound::WavReader::open(&dest).unwrap();
+        assert_eq!(reader.spec().channels, 2);
+        assert_eq!(reader.duration(), 400);
+
+        // Equal-length chains pad every slice to the longest source
+        let padded = ChainOptions {
+            equal_length: true,
+            overwrite: true,
+            ..Default::default()
+        };
+        let report = build_chain(&[kick.clone(), hat.clone()], &dest, &padded).unwrap();
+        assert_eq!(report.total_frames, 600);
+        assert_eq!(report.slices[1].start, 300);
+        assert_eq!(report.slices[1].end, 600);
+        assert_eq!(sidecar_attributes(&dest).unwrap().trim_end, 600);
+
+        assert!(build_chain(&[kick], &dest, &Default::default()).is_err());
+    }
+}
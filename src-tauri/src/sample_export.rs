@@ -0,0 +1,395 @@
+// Export of pool samples to formats for use on a computer: FLAC (lossless,
+// about half the size of WAV), MP3 and Ogg Vorbis (for sharing). Sources are
+// decoded with symphonia like any import; each file is written as
+// `<stem>.<ext>` in the destination folder. .ot files are not exported.
+
+use crate::audio_pool::{
+    decode_audio_file, expand_audio_paths, extract_audio_metadata, is_cancelled,
+    register_cancellation_token, remove_cancellation_token,
+};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::num::{NonZeroU32, NonZeroU8};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::Instant;
+use tauri::{AppHandle, Emitter};
+
+/// Frames handed to the MP3 encoder at a time.
+const MP3_CHUNK_FRAMES: usize = 1152 * 32;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Flac,
+    Mp3,
+    Ogg,
+}
+
+impl ExportFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Flac => "flac",
+            ExportFormat::Mp3 => "mp3",
+            ExportFormat::Ogg => "ogg",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExportOptions {
+    #[serde(default)]
+    pub format: ExportFormat,
+    #[serde(default)]
+    pub mp3_bitrate_kbps: Option<u32>, // 128, 192, 256 or 320 (default)
+    #[serde(default)]
+    pub ogg_quality: Option<f32>, // -0.1 to 1.0, default 0.6 (about 192 kbps)
+    #[serde(default)]
+    pub overwrite: bool, // replace files already present in dest (otherwise skipped)
+    #[serde(default)]
+    pub transfer_id: Option<String>, // cancellable via cancel_audio_transfer
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportProgress {
+    pub transfer_id: Option<String>,
+    pub files_done: usize,
+    pub files_total: usize,
+    pub current_file: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ExportFileResult {
+    pub source: String,
+    pub dest: Option<String>,
+    pub status: String, // "exported", "skipped", "failed", "cancelled"
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportReport {
+    pub dest: String,
+    pub format: ExportFormat,
+    pub files: Vec<ExportFileResult>,
+    pub exported: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    pub cancelled: bool,
+    pub source_bytes: u64, // of the exported files
+    pub exported_bytes: u64,
+    pub elapsed_seconds: f64,
+}
+
+/// Interleaved integer samples at `bits` resolution.
+fn interleave_int(channels: &[Vec<f32>], bits: u32) -> Vec<i32> {
+    // Same scale the decoder used, so integer sources come back unchanged
+    let scale = (1i64 << (bits - 1)) as f32;
+    let frames = channels.first().map_or(0, |c| c.len());
+    let mut out = Vec::with_capacity(frames * channels.len());
+    for i in 0..frames {
+        for channel in channels {
+            out.push((channel[i] * scale).round().clamp(-scale, scale - 1.0) as i32);
+        }
+    }
+    out
+}
+
+fn encode_flac(channels: &[Vec<f32>], rate: u32, bits: u32) -> Result<Vec<u8>, String> {
+    use flacenc::component::BitRepr;
+    use flacenc::error::Verify;
+
+    let config = flacenc::config::Encoder::default()
+        .into_verified()
+        .map_err(|e| format!("Invalid FLAC settings: {:?}", e))?;
+    let samples = interleave_int(channels, bits);
+    let source = flacenc::source::MemSource::from_samples(
+        &samples,
+        channels.len(),
+        bits as usize,
+        rate as usize,
+    );
+    let stream = flacenc::encode_with_fixed_block_size(&config, source, config.block_size)
+        .map_err(|e| format!("FLAC encoding failed: {:?}", e))?;
+    let mut sink = flacenc::bitsink::ByteSink::new();
+    stream
+        .write(&mut sink)
+        .map_err(|e| format!("FLAC encoding failed: {:?}", e))?;
+    Ok(sink.as_slice().to_vec())
+}
+
+fn mp3_bitrate(kbps: u32) -> Result<mp3lame_encoder::Bitrate, String> {
+    use mp3lame_encoder::Bitrate;
+    match kbps {
+        128 => Ok(Bitrate::Kbps128),
+        192 => Ok(Bitrate::Kbps192),
+        256 => Ok(Bitrate::Kbps256),
+        320 => Ok(Bitrate::Kbps320),
+        other => Err(format!("Unsupported MP3 bitrate: {} kbps", other)),
+    }
+}
+
+fn encode_mp3(channels: &[Vec<f32>], rate: u32, kbps: u32) -> Result<Vec<u8>, String> {
+    use mp3lame_encoder::{Builder, FlushNoGap, InterleavedPcm, MonoPcm, Quality};
+
+    let fail = |e: &dyn std::fmt::Debug| format!("MP3 encoding failed: {:?}", e);
+    let mut builder =
+        Builder::new().ok_or_else(|| "Could not start the MP3 encoder".to_string())?;
+    builder
+        .set_num_channels(channels.len() as u8)
+        .map_err(|e| fail(&e))?;
+    builder.set_sample_rate(rate).map_err(|e| fail(&e))?;
+    builder
+        .set_brate(mp3_bitrate(kbps)?)
+        .map_err(|e| fail(&e))?;
+    builder.set_quality(Quality::Best).map_err(|e| fail(&e))?;
+    let mut encoder = builder.build().map_err(|e| fail(&e))?;
+
+    let samples: Vec<i16> = interleave_int(channels, 16)
+        .into_iter()
+        .map(|s| s as i16)
+        .collect();
+    let mut out = Vec::new();
+    for chunk in samples.chunks(MP3_CHUNK_FRAMES * channels.len()) {
+        out.reserve(mp3lame_encoder::max_required_buffer_size(chunk.len()));
+        let encoded = if channels.len() == 1 {
+            encoder.encode_to_vec(MonoPcm(chunk), &mut out)
+        } else {
+            encoder.encode_to_vec(InterleavedPcm(chunk), &mut out)
+        };
+        encoded.map_err(|e| fail(&e))?;
+    }
+    out.reserve(7200);
+    encoder
+        .flush_to_vec::<FlushNoGap>(&mut out)
+        .map_err(|e| fail(&e))?;
+    Ok(out)
+}
+
+fn encode_ogg(channels: &[Vec<f32>], rate: u32, quality: f32) -> Result<Vec<u8>, String> {
+    use vorbis_rs::{VorbisBitrateManagementStrategy, VorbisEncoderBuilder};
+
+    let fail = |e: vorbis_rs::VorbisError| format!("Ogg encoding failed: {}", e);
+    let rate = NonZeroU32::new(rate).ok_or_else(|| "Invalid sample rate".to_string())?;
+    let count = NonZeroU8::new(channels.len() as u8).ok_or_else(|| "No audio".to_string())?;
+    let mut out = Vec::new();
+    let mut builder = VorbisEncoderBuilder::new(rate, count, &mut out).map_err(fail)?;
+    builder.bitrate_management_strategy(VorbisBitrateManagementStrategy::QualityVbr {
+        target_quality: quality.clamp(-0.1, 1.0),
+    });
+    let mut encoder = builder.build().map_err(fail)?;
+    let frames = channels[0].len();
+    let mut start = 0;
+    while start < frames {
+        let end = (start + 4096).min(frames);
+        let block: Vec<&[f32]> = channels.iter().map(|c| &c[start..end]).collect();
+        encoder.encode_audio_block(&block).map_err(fail)?;
+        start = end;
+    }
+    encoder.finish().map_err(fail)?;
+    Ok(out)
+}
+
+/// Encode `source` as `options.format` into `dest`.
+fn export_file(source: &Path, dest: &Path, options: &ExportOptions) -> Result<(), String> {
+    let decoded = decode_audio_file(source)?;
+    let mut channels = decoded.channels;
+    if channels.is_empty() || channels[0].is_empty() {
+        return Err(format!("No audio in {}", source.display()));
+    }
+    let data = match options.format {
+        ExportFormat::Flac => {
+            let (_, bit_depth, _) = extract_audio_metadata(&source.to_path_buf());
+            let bits = if bit_depth.unwrap_or(16) > 16 { 24 } else { 16 };
+            encode_flac(&channels, decoded.sample_rate, bits)?
+        }
+        ExportFormat::Mp3 => {
+            channels.truncate(2); // MP3 is at most stereo
+            encode_mp3(
+                &channels,
+                decoded.sample_rate,
+                options.mp3_bitrate_kbps.unwrap_or(320),
+            )?
+        }
+        ExportFormat::Ogg => encode_ogg(
+            &channels,
+            decoded.sample_rate,
+            options.ogg_quality.unwrap_or(0.6),
+        )?,
+    };
+    fs::write(dest, data).map_err(|e| format!("Failed to write {}: {}", dest.display(), e))
+}
+
+/// Export the audio files in `paths` (folders are walked) into `dest`.
+pub fn export_samples_sync(
+    paths: &[String],
+    dest: &Path,
+    options: &ExportOptions,
+    cancel_token: Option<Arc<AtomicBool>>,
+    on_progress: &dyn Fn(&ExportProgress),
+) -> Result<ExportReport, String> {
+    if options.format == ExportFormat::Mp3 {
+        mp3_bitrate(options.mp3_bitrate_kbps.unwrap_or(320))?;
+    }
+    let sources = expand_audio_paths(paths)?;
+    fs::create_dir_all(dest).map_err(|e| format!("Failed to create {}: {}", dest.display(), e))?;
+    let started = Instant::now();
+    let files_total = sources.len();
+    let progress = |files_done: usize, current: Option<&str>| {
+        on_progress(&ExportProgress {
+            transfer_id: options.transfer_id.clone(),
+            files_done,
+            files_total,
+            current_file: current.map(str::to_string),
+        });
+    };
+
+    let mut files = Vec::with_capacity(files_total);
+    let (mut source_bytes, mut exported_bytes) = (0u64, 0u64);
+    let mut cancelled = false;
+    for (index, source) in sources.iter().enumerate() {
+        if cancelled || cancel_token.as_ref().is_some_and(is_cancelled) {
+            cancelled = true;
+            files.push(ExportFileResult {
+                source: source.clone(),
+                dest: None,
+                status: "cancelled".to_string(),
+                error: None,
+            });
+            continue;
+        }
+        progress(index, Some(source));
+        let source_path = Path::new(source);
+        let stem = source_path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy();
+        let target: PathBuf = dest.join(format!("{}.{}", stem, options.format.extension()));
+        let target_str = target.to_string_lossy().to_string();
+
+        let result = if target.exists() && !options.overwrite {
+            ExportFileResult {
+                source: source.clone(),
+                dest: Some(target_str),
+                status: "skipped".to_string(),
+                error: Some("File already exists".to_string()),
+            }
+        } else {
+            match export_file(source_path, &target, options) {
+                Ok(()) => {
+                    source_bytes += fs::metadata(source_path).map(|m| m.len()).unwrap_or(0);
+                    exported_bytes += fs::metadata(&target).map(|m| m.len()).unwrap_or(0);
+                    ExportFileResult {
+                        source: source.clone(),
+                        dest: Some(target_str),
+                        status: "exported".to_string(),
+                        error: None,
+                    }
+                }
+                Err(e) => ExportFileResult {
+                    source: source.clone(),
+                    dest: None,
+                    status: "failed".to_string(),
+                    error: Some(e),
+                },
+            }
+        };
+        files.push(result);
+    }
+    progress(files_total, None);
+
+    let count = |status: &str| files.iter().filter(|f| f.status == status).count();
+    Ok(ExportReport {
+        dest: dest.to_string_lossy().to_string(),
+        format: options.format,
+        exported: count("exported"),
+        skipped: count("skipped"),
+        failed: count("failed"),
+        cancelled,
+        source_bytes,
+        exported_bytes,
+        elapsed_seconds: started.elapsed().as_secs_f64(),
+        files,
+    })
+}
+
+/// Export pool samples as FLAC, MP3 or Ogg. Emits "sample-export-progress"
+/// events; cancellable via cancel_audio_transfer when a transfer_id is given.
+#[tauri::command]
+pub async fn export_samples(
+    app: AppHandle,
+    paths: Vec<String>,
+    dest: String,
+    options: Option<ExportOptions>,
+) -> Result<ExportReport, String> {
+    crate::fs_scope::ensure_allowed(&dest)?;
+    let options = options.unwrap_or_default();
+    let cancel_token = options
+        .transfer_id
+        .as_deref()
+        .map(register_cancellation_token);
+    let transfer_id = options.transfer_id.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        export_samples_sync(
+            &paths,
+            Path::new(&dest),
+            &options,
+            cancel_token,
+            &|progress| {
+                let _ = app.emit("sample-export-progress", progress);
+            },
+        )
+    })
+    .await
+    .unwrap();
+    if let Some(id) = transfer_id {
+        remove_cancellation_token(&id);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::write_wav;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_export_formats_decode_back() {
+        let tmp = TempDir::new().unwrap();
+        let source = tmp.path().join("stem.wav");
+        let sine: Vec<i16> = (0..44100)
+            .flat_map(|i| {
+                let s = ((i as f32 * 0.05).sin() * 8000.0) as i16;
+                [s, s]
+            })
+            .collect();
+        write_wav(&source, 2, 44100, &sine);
+        let paths = vec![source.to_string_lossy().to_string()];
+        let out = tmp.path().join("export");
+
+        for format in [ExportFormat::Flac, ExportFormat::Mp3, ExportFormat::Ogg] {
+            let options = ExportOptions {
+                format,
+                ..Default::default()
+            };
+            let report = export_samples_sync(&paths, &out, &options, None, &|_| {}).unwrap();
+            assert_eq!(report.exported, 1, "{:?}: {:?}", format, report.files);
+            let exported = out.join(format!("stem.{}", format.extension()));
+            let decoded = decode_audio_file(&exported).unwrap();
+            assert_eq!(decoded.channels.len(), 2);
+            assert_eq!(decoded.sample_rate, 44100);
+            assert!(report.exported_bytes < report.source_bytes);
+
+            // Exporting again leaves the file alone
+            let again = export_samples_sync(&paths, &out, &options, None, &|_| {}).unwrap();
+            assert_eq!(again.skipped, 1);
+        }
+
+        // Lossless round trip
+        let flac = decode_audio_file(&out.join("stem.flac")).unwrap();
+        let wav = decode_audio_file(&source).unwrap();
+        assert_eq!(flac.channels[0], wav.channels[0]);
+    }
+}
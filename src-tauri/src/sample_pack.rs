@@ -0,0 +1,319 @@
+// Sample pack publishing: bundle selected samples/chains with their .ot files
+// and a manifest into a zip ready to share.
+//
+// Inside the zip everything sits in one folder named after the pack, so
+// unzipping it into an Audio Pool gives a tidy sub-folder. File names are
+// normalized to the Octatrack charset.
+
+use crate::project_manager::{FS_FORBIDDEN, MAX_NAME_LEN, OT_CHARSET};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// Per-file details the user entered for the manifest.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PackFileInfo {
+    pub bpm: Option<f32>,
+    pub key: Option<String>,
+}
+
+/// What the user describes when publishing. `files` is keyed by source path.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackManifest {
+    pub name: String,
+    pub author: String,
+    pub license: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub files: HashMap<String, PackFileInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackManifestEntry {
+    pub file: String,
+    pub original_name: String,
+    pub ot_file: Option<String>,
+    pub bpm: Option<f32>,
+    pub key: Option<String>,
+}
+
+/// manifest.json as written into the pack.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublishedManifest {
+    pub name: String,
+    pub author: String,
+    pub license: String,
+    pub description: Option<String>,
+    pub created_at: String, // RFC 3339, local time
+    pub files: Vec<PackManifestEntry>,
+}
+
+/// Replace characters the Octatrack (or a host filesystem) cannot show with `_`
+/// and cap the length. Never returns an empty name.
+pub fn normalize_pack_name(name: &str) -> String {
+    let normalized: String = name
+        .trim()
+        .chars()
+        .map(|c| {
+            if OT_CHARSET.contains(c) && !FS_FORBIDDEN.contains(&c) {
+                c
+            } else {
+                '_'
+            }
+        })
+        .take(MAX_NAME_LEN)
+        .collect();
+    let normalized = normalized.trim().to_string();
+    if normalized.is_empty() {
+        "_".to_string()
+    } else {
+        normalized
+    }
+}
+
+/// Normalized `stem.ext` for `path`, unique among `taken` (compared
+/// case-insensitively, like FAT32 on the CF card).
+fn unique_file_name(path: &Path, taken: &mut HashSet<String>) -> String {
+    let stem = normalize_pack_name(&path.file_stem().unwrap_or_default().to_string_lossy());
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let mut candidate = stem.clone();
+    let mut n = 2;
+    while taken.contains(&candidate.to_lowercase()) {
+        candidate = format!("{}_{}", stem, n);
+        n += 1;
+    }
+    taken.insert(candidate.to_lowercase());
+    if ext.is_empty() {
+        candidate
+    } else {
+        format!("{}.{}", candidate, ext)
+    }
+}
+
+fn add_file(
+    zip: &mut ZipWriter<File>,
+    options: SimpleFileOptions,
+    name: &str,
+    source: &Path,
+) -> Result<(), String> {
+    zip.start_file(name, options)
+        .map_err(|e| format!("Failed to add {} to pack: {}", name, e))?;
+    let mut input =
+        File::open(source).map_err(|e| format!("Failed to read {}: {}", source.display(), e))?;
+    std::io::copy(&mut input, zip).map_err(|e| format!("Failed to add {} to pack: {}", name, e))?;
+    Ok(())
+}
+
+/// Assemble `paths` (plus their `.ot` files) into a zip at `dest`. When `dest`
+/// is a directory, the zip is named after the pack. Returns the zip path.
+pub fn build_pack(paths: &[String], manifest: &PackManifest, dest: &str) -> Result<String, String> {
+    if paths.is_empty() {
+        return Err("No files selected for the pack".to_string());
+    }
+    let pack_name = normalize_pack_name(&manifest.name);
+    let dest = Path::new(dest);
+    let zip_path = if dest.is_dir() {
+        dest.join(format!("{}.zip", pack_name))
+    } else {
+        dest.to_path_buf()
+    };
+
+    let mut sources: Vec<PathBuf> = Vec::new();
+    for p in paths {
+        let path = PathBuf::from(p);
+        if !path.is_file() {
+            return Err(format!("File not found: {}", p));
+        }
+        sources.push(path);
+    }
+    let sidecars: Vec<Option<PathBuf>> = sources
+        .iter()
+        .map(|p| Some(p.with_extension("ot")).filter(|ot| ot.is_file()))
+        .collect();
+    let all_files: Vec<PathBuf> = sources
+        .iter()
+        .cloned()
+        .chain(sidecars.iter().flatten().cloned())
+        .collect();
+    crate::disk_space::ensure_free_space(&zip_path, crate::disk_space::files_size(&all_files))?;
+
+    // Write next to the destination and rename at the end: a failed publish
+    // never leaves a truncated zip behind.
+    let tmp_path = zip_path.with_extension("zip.tmp");
+    let result = (|| {
+        let file = File::create(&tmp_path)
+            .map_err(|e| format!("Failed to create {}: {}", tmp_path.display(), e))?;
+        let mut zip = ZipWriter::new(file);
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        let mut taken = HashSet::new();
+        let mut entries = Vec::new();
+
+        for (source, sidecar) in sources.iter().zip(&sidecars) {
+            let file_name = unique_file_name(source, &mut taken);
+            add_file(
+                &mut zip,
+                options,
+                &format!("{}/{}", pack_name, file_name),
+                source,
+            )?;
+            let ot_file = match sidecar {
+                Some(ot) => {
+                    let ot_name = Path::new(&file_name)
+                        .with_extension("ot")
+                        .to_string_lossy()
+                        .to_string();
+                    add_file(&mut zip, options, &format!("{}/{}", pack_name, ot_name), ot)?;
+                    Some(ot_name)
+                }
+                None => None,
+            };
+            let info = manifest
+                .files
+                .get(&source.to_string_lossy().to_string())
+                .cloned()
+                .unwrap_or_default();
+            entries.push(PackManifestEntry {
+                file: file_name,
+                original_name: source
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                ot_file,
+                bpm: info.bpm,
+                key: info.key,
+            });
+        }
+
+        let published = PublishedManifest {
+            name: manifest.name.clone(),
+            author: manifest.author.clone(),
+            license: manifest.license.clone(),
+            description: manifest.description.clone(),
+            created_at: chrono::Local::now().to_rfc3339(),
+            files: entries,
+        };
+        let json = serde_json::to_string_pretty(&published)
+            .map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+        zip.start_file(format!("{}/manifest.json", pack_name), options)
+            .map_err(|e| format!("Failed to add manifest to pack: {}", e))?;
+        zip.write_all(json.as_bytes())
+            .map_err(|e| format!("Failed to add manifest to pack: {}", e))?;
+        zip.finish()
+            .map_err(|e| format!("Failed to finish pack: {}", e))?;
+        fs::rename(&tmp_path, &zip_path)
+            .map_err(|e| format!("Failed to write {}: {}", zip_path.display(), e))
+    })();
+
+    if let Err(e) = result {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(e);
+    }
+    Ok(zip_path.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+pub async fn publish_pack(
+    paths: Vec<String>,
+    manifest: PackManifest,
+    dest: String,
+) -> Result<String, String> {
+    crate::fs_scope::ensure_allowed(&dest)?;
+    tauri::async_runtime::spawn_blocking(move || build_pack(&paths, &manifest, &dest))
+        .await
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use tempfile::TempDir;
+
+    fn manifest(name: &str) -> PackManifest {
+        PackManifest {
+            name: name.to_string(),
+            author: "someone".to_string(),
+            license: "CC0".to_string(),
+            description: None,
+            files: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_normalize_pack_name() {
+        assert_eq!(normalize_pack_name("Kick: 909/hard"), "Kick_ 909_hard");
+        assert_eq!(normalize_pack_name("Basse élan"), "Basse _lan");
+        assert_eq!(normalize_pack_name("   "), "_");
+        assert_eq!(normalize_pack_name(&"x".repeat(40)).len(), MAX_NAME_LEN);
+    }
+
+    #[test]
+    fn test_publish_pack_bundles_samples_ot_files_and_manifest() {
+        let dir = TempDir::new().unwrap();
+        let a = dir.path().join("a");
+        let b = dir.path().join("b");
+        fs::create_dir_all(&a).unwrap();
+        fs::create_dir_all(&b).unwrap();
+        fs::write(a.join("kick.wav"), b"RIFF-a").unwrap();
+        fs::write(a.join("kick.ot"), b"FORM-ot").unwrap();
+        fs::write(b.join("Kick.WAV"), b"RIFF-b").unwrap();
+        let paths = vec![
+            a.join("kick.wav").to_string_lossy().to_string(),
+            b.join("Kick.WAV").to_string_lossy().to_string(),
+        ];
+        let mut m = manifest("My Pack");
+        m.files.insert(
+            paths[0].clone(),
+            PackFileInfo {
+                bpm: Some(120.0),
+                key: Some("Am".to_string()),
+            },
+        );
+
+        let zip_path = build_pack(&paths, &m, &dir.path().to_string_lossy()).unwrap();
+        assert!(zip_path.ends_with("My Pack.zip"));
+
+        let mut archive = zip::ZipArchive::new(File::open(&zip_path).unwrap()).unwrap();
+        let mut names: Vec<String> = archive.file_names().map(str::to_string).collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                "My Pack/Kick_2.wav",
+                "My Pack/kick.ot",
+                "My Pack/kick.wav",
+                "My Pack/manifest.json"
+            ]
+        );
+
+        let mut json = String::new();
+        archive
+            .by_name("My Pack/manifest.json")
+            .unwrap()
+            .read_to_string(&mut json)
+            .unwrap();
+        let published: PublishedManifest = serde_json::from_str(&json).unwrap();
+        assert_eq!(published.license, "CC0");
+        assert_eq!(published.files[0].ot_file.as_deref(), Some("kick.ot"));
+        assert_eq!(published.files[0].bpm, Some(120.0));
+        assert_eq!(published.files[1].original_name, "Kick.WAV");
+        assert!(!Path::new(&format!("{}.tmp", zip_path)).exists());
+    }
+
+    #[test]
+    fn test_publish_pack_rejects_missing_file() {
+        let dir = TempDir::new().unwrap();
+        let missing = dir.path().join("gone.wav").to_string_lossy().to_string();
+        let dest = dir.path().join("pack.zip");
+        assert!(build_pack(&[missing], &manifest("p"), &dest.to_string_lossy()).is_err());
+        assert!(!dest.exists());
+    }
+}
@@ -0,0 +1,569 @@
+// Sandboxed "what-if" editing: a project is cloned into a temporary working
+// copy, every edit is made there, and nothing reaches the card until the user
+// applies the sandbox (or it is thrown away with discard).
+//
+// Layout: <temp>/octatrack-manager-sandboxes/<id>/
+//   sandbox.json       - original path and a snapshot of the original files
+//   <PROJECT>/         - the working copy
+//   AUDIO/...          - copies of the Set files the project's slots load, so
+//                        "../AUDIO/..." sample paths keep resolving
+//
+// Nothing in the sandbox points back at the card: edits that write into the
+// Audio Pool land in the copies, and applying writes new or changed pool files
+// back next to the project. Slots loading files outside the Set are not
+// copied and show as missing in the sandbox.
+
+use crate::project_lock::LOCK_FILE;
+use crate::project_reader::normalize_path_lexically;
+use crate::sample_attributes::ot_path_for;
+use crate::write_backup::BACKUP_DIR;
+use ot_tools_io::{OctatrackFileIO, ProjectFile};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use walkdir::WalkDir;
+
+const MANIFEST_FILE: &str = "sandbox.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct FileStamp {
+    size: u64,
+    modified: u64, // seconds since epoch
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SandboxManifest {
+    original_path: String,
+    created_at: String,
+    files: BTreeMap<String, FileStamp>, // relative path ('/' separated) -> stamp
+    #[serde(default)]
+    set_files: BTreeMap<String, FileStamp>, // copied Set files, relative to the Set
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SandboxInfo {
+    pub sandbox_path: String, // project path to use for every edit
+    pub original_path: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SandboxApplyResult {
+    pub written: Vec<String>, // relative paths copied to the original project
+    pub removed: Vec<String>, // relative paths deleted from the original project
+    pub pool_written: Vec<String>, // Set files written, relative to the Set
+}
+
+pub(crate) fn sandbox_root() -> PathBuf {
+    std::env::temp_dir().join("octatrack-manager-sandboxes")
+}
+
+fn stamp(path: &Path) -> Option<FileStamp> {
+    let meta = fs::metadata(path).ok()?;
+    let modified = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    Some(FileStamp {
+        size: meta.len(),
+        modified,
+    })
+}
+
+fn relative_key(path: &Path, base: &Path) -> Option<String> {
+    Some(
+        path.strip_prefix(base)
+            .ok()?
+            .to_string_lossy()
+            .replace('\\', "/"),
+    )
+}
+
+/// Entries of a project folder that belong to the app, not the project.
+const APP_ENTRIES: [&str; 3] = ["backups", BACKUP_DIR, LOCK_FILE];
+
+/// Files of a project worth tracking: everything except the backups folders
+/// and the lock file.
+fn project_files(project: &Path) -> BTreeMap<String, FileStamp> {
+    WalkDir::new(project)
+        .into_iter()
+        .filter_entry(|e| e.depth() != 1 || !APP_ENTRIES.iter().any(|name| e.file_name() == *name))
+        .flatten()
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| Some((relative_key(e.path(), project)?, stamp(e.path())?)))
+        .collect()
+}
+
+/// Sample paths of every static and flex slot of `project`; empty when the
+/// project file can't be read.
+fn slot_paths(project: &Path) -> Vec<String> {
+    let file = ["project.work", "project.strd"]
+        .iter()
+        .map(|name| project.join(name))
+        .find(|file| file.exists());
+    let Some(Ok(data)) = file.map(|file| ProjectFile::from_data_file(&file)) else {
+        return Vec::new();
+    };
+    data.slots
+        .static_slots
+        .iter()
+        .chain(data.slots.flex_slots.iter())
+        .filter_map(|slot| slot.as_ref()?.path.as_ref())
+        .map(|path| path.to_string_lossy().replace('\\', "/"))
+        .filter(|path| !path.is_empty())
+        .collect()
+}
+
+/// Files of the Set around `project` that `slot_paths` load, with their .ot
+/// sidecars, keyed relative to the Set.
+fn set_files_for(project: &Path, slot_paths: &[String]) -> BTreeMap<String, FileStamp> {
+    let project = normalize_path_lexically(project);
+    let Some(set_dir) = project.parent() else {
+        return BTreeMap::new();
+    };
+    let mut files = BTreeMap::new();
+    for slot in slot_paths {
+        let sample = normalize_path_lexically(&project.join(slot));
+        if !sample.starts_with(set_dir) || sample.starts_with(&project) {
+            continue;
+        }
+        for path in [sample.clone(), ot_path_for(&sample)] {
+            if let (Some(rel), Some(stamp)) = (relative_key(&path, set_dir), stamp(&path)) {
+                files.insert(rel, stamp);
+            }
+        }
+    }
+    files
+}
+
+/// Files of the sandbox `dir` outside the working copy: the Set copies, and
+/// anything edits added next to them. Keyed relative to the Set.
+fn sandbox_set_files(dir: &Path, project_name: &OsStr) -> BTreeMap<String, FileStamp> {
+    WalkDir::new(dir)
+        .min_depth(1)
+        .into_iter()
+        .filter_entry(|e| {
+            e.depth() != 1 || (e.file_name() != project_name && e.file_name() != MANIFEST_FILE)
+        })
+        .flatten()
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| Some((relative_key(e.path(), dir)?, stamp(e.path())?)))
+        .collect()
+}
+
+/// Set files the sandbox added or changed, relative to the Set.
+fn changed_set_files(dir: &Path, original: &Path) -> Vec<String> {
+    let (Some(name), Some(set_dir)) = (original.file_name(), original.parent()) else {
+        return Vec::new();
+    };
+    sandbox_set_files(dir, name)
+        .into_keys()
+        .filter(|rel| fs::read(dir.join(rel)).ok() != fs::read(set_dir.join(rel)).ok())
+        .collect()
+}
+
+/// Sandbox directory (the `<id>` level) holding `sandbox_path`, after checking
+/// it really is a sandbox.
+fn sandbox_dir(sandbox_path: &str) -> Result<(PathBuf, SandboxManifest), String> {
+    let dir = Path::new(sandbox_path)
+        .parent()
+        .ok_or_else(|| format!("Not a sandbox: {}", sandbox_path))?
+        .to_path_buf();
+    let data = fs::read_to_string(dir.join(MANIFEST_FILE))
+        .map_err(|_| format!("Not a sandbox: {}", sandbox_path))?;
+    let manifest: SandboxManifest = serde_json::from_str(&data)
+        .map_err(|e| format!("Failed to read sandbox manifest: {}", e))?;
+    Ok((dir, manifest))
+}
+
+/// Clone `project_path` into a new sandbox under `root`.
+fn open_sandbox_in(root: &Path, project_path: &str) -> Result<SandboxInfo, String> {
+    let original = Path::new(project_path);
+    if !original.join("project.work").exists() && !original.join("project.strd").exists() {
+        return Err(format!("Not a project: {}", project_path));
+    }
+    let name = original
+        .file_name()
+        .ok_or_else(|| format!("Invalid project path: {}", project_path))?;
+    let files = project_files(original);
+    let set_files = set_files_for(original, &slot_paths(original));
+    let required: u64 = files
+        .values()
+        .chain(set_files.values())
+        .map(|f| f.size)
+        .sum();
+
+    let now = chrono::Local::now();
+    let mut id = now.format("%Y%m%d-%H%M%S-%3f").to_string();
+    let mut n = 2;
+    while root.join(&id).exists() {
+        id = format!("{}-{}", now.format("%Y%m%d-%H%M%S-%3f"), n);
+        n += 1;
+    }
+    let dir = root.join(&id);
+    let sandbox = dir.join(name);
+    crate::disk_space::ensure_free_space(root, required)?;
+    fs::create_dir_all(&sandbox).map_err(|e| format!("Failed to create sandbox: {}", e))?;
+
+    let result = (|| {
+        let set_dir = original.parent().unwrap_or(original);
+        let copies = files
+            .keys()
+            .map(|rel| (original.join(rel), sandbox.join(rel)))
+            .chain(
+                set_files
+                    .keys()
+                    .map(|rel| (set_dir.join(rel), dir.join(rel))),
+            );
+        for (source, dest) in copies {
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+            }
+            fs::copy(&source, &dest)
+                .map_err(|e| format!("Failed to copy {}: {}", source.display(), e))?;
+        }
+
+        let manifest = SandboxManifest {
+            original_path: project_path.to_string(),
+            created_at: now.to_rfc3339(),
+            files,
+            set_files,
+        };
+        let json = serde_json::to_string_pretty(&manifest)
+            .map_err(|e| format!("Failed to serialize sandbox manifest: {}", e))?;
+        fs::write(dir.join(MANIFEST_FILE), json)
+            .map_err(|e| format!("Failed to write sandbox manifest: {}", e))?;
+        Ok(SandboxInfo {
+            sandbox_path: sandbox.to_string_lossy().to_string(),
+            original_path: manifest.original_path,
+            created_at: manifest.created_at,
+        })
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_dir_all(&dir);
+    }
+    result
+}
+
+/// Clone a project into a temporary working copy. Edit the returned
+/// `sandbox_path` instead of the project; the original stays untouched.
+pub fn open_sandbox_sync(project_path: &str) -> Result<SandboxInfo, String> {
+    let info = open_sandbox_in(&sandbox_root(), project_path)?;
+    // The sandbox lives in the temp directory, outside the approved roots
+    let dir = Path::new(&info.sandbox_path)
+        .parent()
+        .unwrap_or(Path::new(""));
+    crate::fs_scope::approve_root(dir)?;
+    Ok(info)
+}
+
+/// Copy what changed in the sandbox back to the original project (files
+/// overwritten or deleted are backed up first), then remove the sandbox.
+/// Refuses when the original was modified since the sandbox was opened or is
+/// open in another instance.
+pub fn apply_sandbox_sync(sandbox_path: &str) -> Result<SandboxApplyResult, String> {
+    let (dir, manifest) = sandbox_dir(sandbox_path)?;
+    let original = Path::new(&manifest.original_path);
+    let sandbox = Path::new(sandbox_path);
+
+    // Every bank lock of the original, taken in bank order, is held from the
+    // change check to the last write so no bank edit lands in between.
+    let bank_locks: Vec<_> = (1..=16)
+        .map(|n| {
+            crate::project_reader::bank_file_lock(&original.join(format!("bank{:02}.work", n)))
+        })
+        .collect();
+    let _bank_guards: Vec<_> = bank_locks
+        .iter()
+        .map(|l| l.lock().unwrap_or_else(|e| e.into_inner()))
+        .collect();
+    crate::project_lock::ensure_unlocked(original)?;
+
+    let set_dir = original.parent().unwrap_or(original);
+    let set_unchanged = manifest
+        .set_files
+        .iter()
+        .all(|(rel, expected)| stamp(&set_dir.join(rel)).as_ref() == Some(expected));
+    if project_files(original) != manifest.files || !set_unchanged {
+        return Err(format!(
+            "{} changed since the sandbox was opened: discard the sandbox and start over",
+            manifest.original_path
+        ));
+    }
+
+    let current = project_files(sandbox);
+    let written: Vec<String> = current
+        .keys()
+        .filter(|rel| {
+            !manifest.files.contains_key(*rel)
+                || fs::read(sandbox.join(rel)).ok() != fs::read(original.join(rel)).ok()
+        })
+        .cloned()
+        .collect();
+    let removed: Vec<String> = manifest
+        .files
+        .keys()
+        .filter(|rel| !current.contains_key(*rel))
+        .cloned()
+        .collect();
+
+    let to_backup: Vec<String> = written
+        .iter()
+        .filter(|rel| manifest.files.contains_key(*rel))
+        .chain(&removed)
+        .cloned()
+        .collect();
+    if !to_backup.is_empty() {
+        crate::backup_project_files_impl(&manifest.original_path, &to_backup, "sandbox")?;
+    }
+
+    let pool_written = changed_set_files(&dir, original);
+    let required: u64 = written
+        .iter()
+        .map(|rel| sandbox.join(rel))
+        .chain(pool_written.iter().map(|rel| dir.join(rel)))
+        .filter_map(|path| stamp(&path))
+        .map(|f| f.size)
+        .sum();
+    crate::disk_space::ensure_free_space(original, required)?;
+    let copies = written
+        .iter()
+        .map(|rel| (sandbox.join(rel), original.join(rel)))
+        .chain(
+            pool_written
+                .iter()
+                .map(|rel| (dir.join(rel), set_dir.join(rel))),
+        );
+    for (source, dest) in copies {
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let data =
+            fs::read(&source).map_err(|e| format!("Failed to read {}: {}", source.display(), e))?;
+        crate::atomic_write::write_atomic(&dest, &data)
+            .map_err(|e| format!("Failed to write {}: {}", dest.display(), e))?;
+    }
+    for rel in &removed {
+        fs::remove_file(original.join(rel))
+            .map_err(|e| format!("Failed to remove {}: {}", rel, e))?;
+    }
+
+    let _ = fs::remove_dir_all(&dir);
+    Ok(SandboxApplyResult {
+        written,
+        removed,
+        pool_written,
+    })
+}
+
+/// Throw a sandbox away without touching the original project.
+pub fn discard_sandbox_sync(sandbox_path: &str) -> Result<(), String> {
+    let (dir, _) = sandbox_dir(sandbox_path)?;
+    fs::remove_dir_all(&dir).map_err(|e| format!("Failed to remove sandbox: {}", e))
+}
+
+#[tauri::command]
+pub async fn open_sandbox(path: String) -> Result<SandboxInfo, String> {
+    tauri::async_runtime::spawn_blocking(move || open_sandbox_sync(&path))
+        .await
+        .unwrap()
+}
+
+#[tauri::command]
+pub async fn apply_sandbox(sandbox_path: String) -> Result<SandboxApplyResult, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let (dir, manifest) = sandbox_dir(&sandbox_path)?;
+        crate::fs_scope::ensure_allowed(&sandbox_path)?;
+        crate::fs_scope::ensure_allowed(&manifest.original_path)?;
+        let original = Path::new(&manifest.original_path);
+        if !changed_set_files(&dir, original).is_empty() {
+            let set_dir = original.parent().unwrap_or(original);
+            crate::fs_scope::ensure_allowed(&set_dir.to_string_lossy())?;
+        }
+        // Everything apply may write or remove that the journal covers
+        let files: Vec<String> = manifest
+            .files
+            .into_keys()
+            .chain(project_files(Path::new(&sandbox_path)).into_keys())
+            .filter(|name| crate::edit_journal::is_journaled_file(name))
+            .collect::<std::collections::BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        crate::edit_journal::record_edit(&manifest.original_path, "apply_sandbox", &files, || {
+            apply_sandbox_sync(&sandbox_path)
+        })
+    })
+    .await
+    .unwrap()
+}
+
+#[tauri::command]
+pub async fn discard_sandbox(sandbox_path: String) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::fs_scope::ensure_allowed(&sandbox_path)?;
+        discard_sandbox_sync(&sandbox_path)
+    })
+    .await
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, PathBuf, PathBuf) {
+        let temp = TempDir::new().unwrap();
+        let set = temp.path().join("SET");
+        let project = set.join("PROJ");
+        fs::create_dir_all(set.join("AUDIO")).unwrap();
+        fs::create_dir_all(project.join("backups")).unwrap();
+        fs::write(project.join("project.work"), b"project").unwrap();
+        fs::write(project.join("bank01.work"), b"bank-1").unwrap();
+        fs::write(project.join("bank02.work"), b"bank-2").unwrap();
+        fs::write(project.join("backups").join("old.work"), b"old").unwrap();
+        fs::create_dir_all(project.join(BACKUP_DIR)).unwrap();
+        fs::write(project.join(BACKUP_DIR).join("bank01.work"), b"bak").unwrap();
+        fs::write(project.join(LOCK_FILE), b"{}").unwrap();
+        fs::write(set.join("AUDIO").join("kick.wav"), b"kick").unwrap();
+        let root = temp.path().join("sandboxes");
+        (temp, project, root)
+    }
+
+    #[test]
+    fn test_open_copies_project_without_linking_siblings() {
+        let (_temp, project, root) = setup();
+        let info = open_sandbox_in(&root, &project.to_string_lossy()).unwrap();
+        let sandbox = Path::new(&info.sandbox_path);
+
+        assert_eq!(fs::read(sandbox.join("bank01.work")).unwrap(), b"bank-1");
+        assert!(!sandbox.join("backups").exists());
+        assert!(!sandbox.join(BACKUP_DIR).exists());
+        assert!(!sandbox.join(LOCK_FILE).exists());
+        // Nothing in the sandbox leads back to the card
+        assert!(!sandbox.join("../AUDIO").exists());
+    }
+
+    #[test]
+    fn test_set_files_are_the_samples_slots_load() {
+        let (_temp, project, _) = setup();
+        let set = project.parent().unwrap();
+        fs::write(set.join("AUDIO/kick.ot"), b"ot").unwrap();
+        fs::write(project.join("local.wav"), b"local").unwrap();
+        let slots = [
+            "../AUDIO/kick.wav",
+            "local.wav",
+            "../AUDIO/missing.wav",
+            "../../elsewhere.wav",
+        ]
+        .map(String::from);
+
+        let files = set_files_for(&project, &slots);
+        let names: Vec<_> = files.keys().map(String::as_str).collect();
+        assert_eq!(names, vec!["AUDIO/kick.ot", "AUDIO/kick.wav"]);
+    }
+
+    #[test]
+    fn test_apply_writes_pool_files_added_in_sandbox() {
+        let (_temp, project, root) = setup();
+        let info = open_sandbox_in(&root, &project.to_string_lossy()).unwrap();
+        let dir = Path::new(&info.sandbox_path).parent().unwrap();
+        fs::create_dir_all(dir.join("AUDIO")).unwrap();
+        fs::write(dir.join("AUDIO/snare.wav"), b"snare").unwrap();
+
+        let set = project.parent().unwrap();
+        assert!(!set.join("AUDIO/snare.wav").exists());
+        let result = apply_sandbox_sync(&info.sandbox_path).unwrap();
+        assert_eq!(result.pool_written, vec!["AUDIO/snare.wav"]);
+        assert_eq!(fs::read(set.join("AUDIO/snare.wav")).unwrap(), b"snare");
+    }
+
+    #[test]
+    fn test_apply_writes_changes_back_with_backup() {
+        let (_temp, project, root) = setup();
+        let info = open_sandbox_in(&root, &project.to_string_lossy()).unwrap();
+        let sandbox = PathBuf::from(&info.sandbox_path);
+        fs::write(sandbox.join("bank01.work"), b"bank-1-edited").unwrap();
+        fs::remove_file(sandbox.join("bank02.work")).unwrap();
+        fs::write(sandbox.join("new.wav"), b"new").unwrap();
+
+        // Nothing reached the original yet
+        assert_eq!(fs::read(project.join("bank01.work")).unwrap(), b"bank-1");
+
+        let result = apply_sandbox_sync(&info.sandbox_path).unwrap();
+        assert_eq!(result.written, vec!["bank01.work", "new.wav"]);
+        assert_eq!(result.removed, vec!["bank02.work"]);
+        assert_eq!(
+            fs::read(project.join("bank01.work")).unwrap(),
+            b"bank-1-edited"
+        );
+        assert!(!project.join("bank02.work").exists());
+        assert!(!sandbox.exists(), "sandbox removed after apply");
+
+        let backup = fs::read_dir(project.join("backups"))
+            .unwrap()
+            .flatten()
+            .find(|e| e.file_name().to_string_lossy().contains("sandbox"))
+            .unwrap()
+            .path();
+        assert_eq!(fs::read(backup.join("bank01.work")).unwrap(), b"bank-1");
+        assert_eq!(fs::read(backup.join("bank02.work")).unwrap(), b"bank-2");
+    }
+
+    #[test]
+    fn test_apply_refuses_when_original_is_locked_elsewhere() {
+        let (_temp, project, root) = setup();
+        let info = open_sandbox_in(&root, &project.to_string_lossy()).unwrap();
+        let sandbox = PathBuf::from(&info.sandbox_path);
+        fs::write(sandbox.join("bank01.work"), b"bank-1-edited").unwrap();
+        let lock = crate::project_lock::ProjectLock {
+            pid: 1,
+            host: "some-other-host.invalid".to_string(),
+            created_at: "2026-10-15 10:00:00".to_string(),
+        };
+        fs::write(
+            project.join(LOCK_FILE),
+            serde_json::to_string(&lock).unwrap(),
+        )
+        .unwrap();
+
+        let err = apply_sandbox_sync(&info.sandbox_path).unwrap_err();
+        assert!(err.contains("another instance"), "{}", err);
+        assert_eq!(fs::read(project.join("bank01.work")).unwrap(), b"bank-1");
+        assert!(sandbox.exists());
+    }
+
+    #[test]
+    fn test_apply_refuses_when_original_changed() {
+        let (_temp, project, root) = setup();
+        let info = open_sandbox_in(&root, &project.to_string_lossy()).unwrap();
+        fs::write(project.join("bank01.work"), b"edited on the device").unwrap();
+
+        assert!(apply_sandbox_sync(&info.sandbox_path).is_err());
+        assert_eq!(
+            fs::read(project.join("bank01.work")).unwrap(),
+            b"edited on the device"
+        );
+    }
+
+    #[test]
+    fn test_discard_leaves_original_untouched() {
+        let (_temp, project, root) = setup();
+        let info = open_sandbox_in(&root, &project.to_string_lossy()).unwrap();
+        fs::write(Path::new(&info.sandbox_path).join("bank01.work"), b"x").unwrap();
+
+        discard_sandbox_sync(&info.sandbox_path).unwrap();
+        assert!(!Path::new(&info.sandbox_path).exists());
+        assert_eq!(fs::read(project.join("bank01.work")).unwrap(), b"bank-1");
+        assert!(project.parent().unwrap().join("AUDIO/kick.wav").exists());
+        assert!(discard_sandbox_sync(&project.to_string_lossy()).is_err());
+    }
+}
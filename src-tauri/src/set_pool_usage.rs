@@ -0,0 +1,258 @@
+// Set-wide Audio Pool usage.
+//
+// Walks every project of a Set and records which Audio Pool files each one
+// loads into a sample slot, from both the working (`project.work`) and saved
+// (`project.strd`) state, since either can be reloaded on the device. A pool
+// file no project loads is safe to delete; one that is loaded but never
+// played (see `compute_pool_usage`) is flagged separately so it isn't removed
+// by mistake.
+
+use crate::audio_pool::is_audio_file;
+use crate::project_reader::{
+    compute_pool_usage, normalize_path_lexically, pool_usage_key, read_raw_sample_fields,
+    set_project_files,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// Slots one project loads a pool file into.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProjectPoolReference {
+    pub project: String,
+    pub slots: Vec<String>, // e.g. "Flex 3", "Static 12"
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PoolFileUsage {
+    pub path: String,     // absolute
+    pub relative: String, // relative to the Audio Pool, forward slashes
+    pub size: u64,
+    pub projects: Vec<ProjectPoolReference>, // empty: referenced by no project
+    pub played: bool,                        // triggered by at least one pattern or part
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetPoolUsage {
+    pub pool_path: String,
+    pub files: Vec<PoolFileUsage>,
+    pub unreferenced: Vec<String>, // absolute paths of files no project loads
+    pub unreferenced_bytes: u64,
+    pub skipped_projects: Vec<String>, // project files that couldn't be read
+}
+
+/// Scan the Set at `set_path` (the directory holding `AUDIO` and the projects).
+pub fn analyze_set_pool_usage(set_path: &str) -> Result<SetPoolUsage, String> {
+    let set_dir = normalize_path_lexically(Path::new(set_path));
+    let pool_dir = set_dir.join("AUDIO");
+    if !pool_dir.is_dir() {
+        return Err(format!("No Audio Pool (AUDIO) in {}", set_dir.display()));
+    }
+
+    // pool key -> project -> slot labels
+    let mut references: BTreeMap<String, BTreeMap<String, BTreeSet<(u8, u16)>>> = BTreeMap::new();
+    let mut skipped_projects = Vec::new();
+    for (project_dir, _) in set_project_files(&set_dir, Some(&pool_dir))? {
+        let project = crate::project_reader::file_name_of(&project_dir);
+        for file in ["project.work", "project.strd"] {
+            let file = project_dir.join(file);
+            if !file.exists() {
+                continue;
+            }
+            let raw_fields = match read_raw_sample_fields(&file) {
+                Ok(f) => f,
+                Err(_) => {
+                    skipped_projects.push(project.clone());
+                    continue;
+                }
+            };
+            for ((slot_type, slot), fields) in &raw_fields {
+                let Some(path) = fields.get("PATH").filter(|p| !p.is_empty()) else {
+                    continue;
+                };
+                let key = pool_usage_key(&normalize_path_lexically(
+                    &project_dir.join(path.replace('\\', "/")),
+                ));
+                // Static before Flex, as the device lists them.
+                let pool = u8::from(slot_type.eq_ignore_ascii_case("FLEX"));
+                references
+                    .entry(key)
+                    .or_default()
+                    .entry(project.clone())
+                    .or_default()
+                    .insert((pool, *slot));
+            }
+        }
+    }
+    skipped_projects.dedup();
+
+    let played = compute_pool_usage(&pool_dir.to_string_lossy()).unwrap_or_default();
+
+    let mut files = Vec::new();
+    let mut unreferenced = Vec::new();
+    let mut unreferenced_bytes = 0;
+    for entry in WalkDir::new(&pool_dir).into_iter().filter_map(|e| e.ok()) {
+        if !entry.file_type().is_file() || !is_audio_file(&entry.file_name().to_string_lossy()) {
+            continue;
+        }
+        let path = entry.path();
+        let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+        let key = pool_usage_key(&normalize_path_lexically(path));
+        let projects: Vec<ProjectPoolReference> = references
+            .get(&key)
+            .map(|by_project| {
+                by_project
+                    .iter()
+                    .map(|(project, slots)| ProjectPoolReference {
+                        project: project.clone(),
+                        slots: slots
+                            .iter()
+                            .map(|&(pool, slot)| {
+                                format!("{} {}", if pool == 0 { "Static" } else { "Flex" }, slot)
+                            })
+                            .collect(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        if projects.is_empty() {
+            unreferenced.push(path.to_string_lossy().to_string());
+            unreferenced_bytes += size;
+        }
+        files.push(PoolFileUsage {
+            path: path.to_string_lossy().to_string(),
+            relative: path
+                .strip_prefix(&pool_dir)
+                .unwrap_or(path)
+                .to_string_lossy()
+                .replace('\\', "/"),
+            size,
+            projects,
+            played: played.contains_key(&key),
+        });
+    }
+    files.sort_by(|a, b| a.relative.cmp(&b.relative));
+    unreferenced.sort();
+
+    Ok(SetPoolUsage {
+        pool_path: pool_dir.to_string_lossy().to_string(),
+        files,
+        unreferenced,
+        unreferenced_bytes,
+        skipped_projects,
+    })
+}
+
+#[tauri::command]
+pub async fn get_set_pool_usage(set_path: String) -> Result<SetPoolUsage, String> {
+    tauri::async_runtime::spawn_blocking(move || analyze_set_pool_usage(&set_path))
+        .await
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ot_tools_io::projects::SlotAttributes;
+    use ot_tools_io::settings::SlotType;
+    use ot_tools_io::{OctatrackFileIO, ProjectFile};
+    use std::fs;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    fn write_project(dir: &Path, file: &str, flex: bool, slot: u8, path: &str) {
+        fs::create_dir_all(dir).unwrap();
+        let mut project = ProjectFile::default();
+        let slot_type = if flex {
+            SlotType::Flex
+        } else {
+            SlotType::Static
+        };
+        let attributes = Some(
+            SlotAttributes::new(
+                slot_type,
+                slot,
+                Some(PathBuf::from(path)),
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap(),
+        );
+        if flex {
+            project.slots.flex_slots[slot as usize - 1] = attributes;
+        } else {
+            project.slots.static_slots[slot as usize - 1] = attributes;
+        }
+        project.to_data_file(&dir.join(file)).unwrap();
+    }
+
+    #[test]
+    fn test_set_pool_usage_maps_files_to_projects() {
+        let set = TempDir::new().unwrap();
+        let pool = set.path().join("AUDIO");
+        fs::create_dir_all(pool.join("drums")).unwrap();
+        fs::write(pool.join("drums/kick.wav"), b"RIFF kick").unwrap();
+        fs::write(pool.join("pad.wav"), b"RIFF pad").unwrap();
+        fs::write(pool.join("orphan.wav"), b"RIFF orphan").unwrap();
+        fs::write(pool.join("notes.txt"), b"not audio").unwrap();
+
+        write_project(
+            &set.path().join("ONE"),
+            "project.work",
+            false,
+            3,
+            "../AUDIO/drums/kick.wav",
+        );
+        write_project(
+            &set.path().join("TWO"),
+            "project.work",
+            true,
+            1,
+            "../AUDIO/drums/kick.wav",
+        );
+        // Only the saved state still loads the pad.
+        write_project(
+            &set.path().join("TWO"),
+            "project.strd",
+            true,
+            2,
+            "../AUDIO/pad.wav",
+        );
+
+        let usage = analyze_set_pool_usage(&set.path().to_string_lossy()).unwrap();
+
+        assert_eq!(usage.files.len(), 3);
+        let kick = usage
+            .files
+            .iter()
+            .find(|f| f.relative == "drums/kick.wav")
+            .unwrap();
+        assert_eq!(
+            kick.projects,
+            vec![
+                ProjectPoolReference {
+                    project: "ONE".to_string(),
+                    slots: vec!["Static 3".to_string()],
+                },
+                ProjectPoolReference {
+                    project: "TWO".to_string(),
+                    slots: vec!["Flex 1".to_string()],
+                },
+            ]
+        );
+        assert_eq!(usage.unreferenced.len(), 1);
+        assert!(usage.unreferenced[0].ends_with("orphan.wav"));
+        assert_eq!(usage.unreferenced_bytes, 11);
+        assert!(usage.skipped_projects.is_empty());
+    }
+
+    #[test]
+    fn test_set_pool_usage_requires_audio_pool() {
+        let set = TempDir::new().unwrap();
+        assert!(analyze_set_pool_usage(&set.path().to_string_lossy()).is_err());
+    }
+}
@@ -0,0 +1,563 @@
+// Setlist builder: assembles a new project for a gig from patterns chosen in
+// several source projects.
+//
+// Each chosen pattern brings the part it plays with. Patterns are laid out in
+// setlist order from bank A on; a bank takes up to 16 patterns and the 4
+// parts they need, so patterns sharing a source part share the copied part.
+// Every sample slot those parts and patterns use is copied into the new
+// project's pools and the copied parts and sample locks are renumbered to the
+// new slots. The same file used by several projects takes one slot; different
+// files that sat in the same slot number get separate slots. The returned
+// report maps every source pattern, part and slot to where it ended up.
+
+use crate::project_reader::{
+    copy_slot_assignments, normalize_path_lexically, SlotCopyRequest, BANK_LETTERS,
+};
+use ot_tools_io::{BankFile, HasChecksumField, OctatrackFileIO, ProjectFile};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Usable slots per pool (flex slots 129-136 are the recorder buffers).
+const POOL_SLOTS: usize = 128;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SetlistEntry {
+    pub source_project: String,
+    pub bank: u8,    // 0-15
+    pub pattern: u8, // 0-15
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SetlistPlacement {
+    pub source_project: String,
+    pub source_bank: u8,
+    pub source_pattern: u8,
+    pub source_part: u8,
+    pub dest_bank: u8,
+    pub dest_pattern: u8,
+    pub dest_part: u8,
+    pub label: String, // destination pattern as shown on the device, e.g. "B03"
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SetlistSlot {
+    pub source_project: String,
+    pub slot_type: String, // "static" or "flex"
+    pub source_slot: u8,   // 1-128
+    pub dest_slot: u8,     // 1-128
+    pub path: Option<String>,
+    pub action: String, // from copy_slot_assignments: "linked", "copied", "converted", "missing"
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetlistReport {
+    pub project_path: String,
+    pub placements: Vec<SetlistPlacement>,
+    pub slots: Vec<SetlistSlot>,
+    pub warnings: Vec<String>,
+}
+
+/// Where a setlist entry goes, before anything is written.
+#[derive(Debug, Clone, PartialEq)]
+struct Placement {
+    entry: usize,
+    source_part: u8,
+    dest_bank: u8,
+    dest_pattern: u8,
+    dest_part: u8,
+}
+
+/// Lay entries out in order. `part_of(i)` is the part entry `i` plays with;
+/// `source_key(i)` identifies its source bank so equal parts can be shared.
+fn plan_layout(
+    count: usize,
+    source_key: impl Fn(usize) -> (String, u8),
+    part_of: impl Fn(usize) -> u8,
+) -> Result<Vec<Placement>, String> {
+    let mut placements = Vec::new();
+    let mut bank = 0u8;
+    let mut patterns_in_bank = 0u8;
+    let mut parts_in_bank: Vec<(String, u8, u8)> = Vec::new(); // (project, bank, part)
+    for i in 0..count {
+        let (project, source_bank) = source_key(i);
+        let part = part_of(i);
+        let key = (project, source_bank, part);
+        let mut dest_part = parts_in_bank.iter().position(|k| *k == key);
+        if patterns_in_bank == 16 || (dest_part.is_none() && parts_in_bank.len() == 4) {
+            bank += 1;
+            patterns_in_bank = 0;
+            parts_in_bank.clear();
+            dest_part = None;
+        }
+        if bank >= 16 {
+            return Err(format!(
+                "The setlist needs more than 16 banks (stopped at entry {})",
+                i + 1
+            ));
+        }
+        let dest_part = dest_part.unwrap_or_else(|| {
+            parts_in_bank.push(key);
+            parts_in_bank.len() - 1
+        });
+        placements.push(Placement {
+            entry: i,
+            source_part: part,
+            dest_bank: bank,
+            dest_pattern: patterns_in_bank,
+            dest_part: dest_part as u8,
+        });
+        patterns_in_bank += 1;
+    }
+    Ok(placements)
+}
+
+fn find_file(dir: &Path, stem: &str) -> Option<PathBuf> {
+    ["work", "strd"]
+        .iter()
+        .map(|ext| dir.join(format!("{}.{}", stem, ext)))
+        .find(|p| p.exists())
+}
+
+/// Source slots (0-based) a part and its patterns use, per pool.
+fn used_slots(
+    bank: &BankFile,
+    part: usize,
+    patterns: &[usize],
+    used: &mut BTreeSet<(bool, u8)>, // (is_static, slot)
+) {
+    let unsaved = &bank.parts.unsaved.0[part];
+    for t in 0..8 {
+        let slots = &unsaved.audio_track_machine_slots[t];
+        match unsaved.audio_track_machine_types[t] {
+            0 => {
+                used.insert((true, slots.static_slot_id));
+            }
+            1 => {
+                used.insert((false, slots.flex_slot_id));
+            }
+            _ => {}
+        }
+    }
+    for &p in patterns {
+        for (t, track) in bank.patterns.0[p].audio_track_trigs.0.iter().enumerate() {
+            let is_static = match unsaved.audio_track_machine_types[t] {
+                0 => true,
+                1 => false,
+                _ => continue,
+            };
+            // Sample locks live in the flex_slot_id byte for both pools.
+            for plock in track.plocks.0.iter().filter(|l| l.flex_slot_id != 255) {
+                used.insert((is_static, plock.flex_slot_id));
+            }
+        }
+    }
+}
+
+/// Point a copied part and its patterns at the new slots.
+fn remap_slots(
+    dest: &mut BankFile,
+    part: usize,
+    patterns: &[usize],
+    map: &HashMap<(bool, u8), u8>,
+) {
+    let machine_types = dest.parts.unsaved.0[part].audio_track_machine_types;
+    for copy in [
+        &mut dest.parts.unsaved.0[part],
+        &mut dest.parts.saved.0[part],
+    ] {
+        for t in 0..8 {
+            let slots = &mut copy.audio_track_machine_slots[t];
+            if let Some(&s) = map.get(&(true, slots.static_slot_id)) {
+                slots.static_slot_id = s;
+            }
+            if let Some(&s) = map.get(&(false, slots.flex_slot_id)) {
+                slots.flex_slot_id = s;
+            }
+        }
+    }
+    for &p in patterns {
+        for (t, track) in dest.patterns.0[p]
+            .audio_track_trigs
+            .0
+            .iter_mut()
+            .enumerate()
+        {
+            let is_static = match machine_types[t] {
+                0 => true,
+                1 => false,
+                _ => continue,
+            };
+            for plock in track.plocks.0.iter_mut().filter(|l| l.flex_slot_id != 255) {
+                if let Some(&s) = map.get(&(is_static, plock.flex_slot_id)) {
+                    plock.flex_slot_id = s;
+                }
+            }
+        }
+    }
+}
+
+fn build_into(
+    dest_dir: &Path,
+    entries: &[SetlistEntry],
+) -> Result<(Vec<SetlistPlacement>, Vec<SetlistSlot>, Vec<String>), String> {
+    let mut warnings = Vec::new();
+
+    // Source banks and project files, read once each.
+    let mut banks: HashMap<(String, u8), BankFile> = HashMap::new();
+    let mut projects: HashMap<String, ProjectFile> = HashMap::new();
+    for entry in entries {
+        if entry.bank > 15 || entry.pattern > 15 {
+            return Err("Bank and pattern indices must be between 0 and 15".to_string());
+        }
+        let dir = Path::new(&entry.source_project);
+        if !projects.contains_key(&entry.source_project) {
+            let file = find_file(dir, "project")
+                .ok_or_else(|| format!("Project file not found in {}", entry.source_project))?;
+            let project = ProjectFile::from_data_file(&file)
+                .map_err(|e| format!("Failed to read {}: {:?}", file.display(), e))?;
+            projects.insert(entry.source_project.clone(), project);
+        }
+        let key = (entry.source_project.clone(), entry.bank);
+        if !banks.contains_key(&key) {
+            let file = find_file(dir, &format!("bank{:02}", entry.bank + 1)).ok_or_else(|| {
+                format!(
+                    "Bank {} not found in {}",
+                    BANK_LETTERS[entry.bank as usize], entry.source_project
+                )
+            })?;
+            banks.insert(key, crate::os_compat::read_bank_file(&file)?);
+        }
+    }
+
+    let part_of = |i: usize| {
+        let e = &entries[i];
+        banks[&(e.source_project.clone(), e.bank)].patterns.0[e.pattern as usize]
+            .part_assignment
+            .min(3)
+    };
+    let layout = plan_layout(
+        entries.len(),
+        |i| (entries[i].source_project.clone(), entries[i].bank),
+        part_of,
+    )?;
+
+    // Copied parts: (dest bank, dest part) -> (source key, source part, dest patterns)
+    let mut parts: BTreeMap<(u8, u8), ((String, u8), u8, Vec<usize>, Vec<usize>)> = BTreeMap::new();
+    for p in &layout {
+        let e = &entries[p.entry];
+        let part = parts.entry((p.dest_bank, p.dest_part)).or_insert_with(|| {
+            (
+                (e.source_project.clone(), e.bank),
+                p.source_part,
+                Vec::new(),
+                Vec::new(),
+            )
+        });
+        part.2.push(e.pattern as usize);
+        part.3.push(p.dest_pattern as usize);
+    }
+
+    // Allocate new slots: one per distinct file per pool, in order of use.
+    let mut next_slot = [0usize; 2]; // [static, flex]
+    let mut by_file: HashMap<(bool, PathBuf), u8> = HashMap::new();
+    let mut slot_maps: HashMap<String, HashMap<(bool, u8), u8>> = HashMap::new();
+    let mut requests: BTreeMap<String, Vec<SlotCopyRequest>> = BTreeMap::new();
+    for ((source_project, bank_index), source_part, patterns, _) in parts.values() {
+        let bank = &banks[&(source_project.clone(), *bank_index)];
+        let mut used = BTreeSet::new();
+        used_slots(bank, *source_part as usize, patterns, &mut used);
+        let project = &projects[source_project];
+        let map = slot_maps.entry(source_project.clone()).or_default();
+        for (is_static, slot) in used {
+            if map.contains_key(&(is_static, slot)) {
+                continue;
+            }
+            let pool = if is_static {
+                &project.slots.static_slots
+            } else {
+                &project.slots.flex_slots
+            };
+            let Some(rel) = pool
+                .get(slot as usize)
+                .and_then(|s| s.as_ref())
+                .and_then(|s| s.path.as_ref())
+                .filter(|p| !p.as_os_str().is_empty())
+            else {
+                continue; // empty slot: nothing to carry over
+            };
+            let file = normalize_path_lexically(&Path::new(source_project).join(rel));
+            let dest_slot = match by_file.get(&(is_static, file.clone())) {
+                Some(&s) => s,
+                None => {
+                    let counter = &mut next_slot[usize::from(!is_static)];
+                    if *counter >= POOL_SLOTS {
+                        return Err(format!(
+                            "The setlist uses more than {} {} samples",
+                            POOL_SLOTS,
+                            if is_static { "static" } else { "flex" }
+                        ));
+                    }
+                    let s = *counter as u8;
+                    *counter += 1;
+                    by_file.insert((is_static, file), s);
+                    requests
+                        .entry(source_project.clone())
+                        .or_default()
+                        .push(SlotCopyRequest {
+                            slot_type: if is_static { "static" } else { "flex" }.to_string(),
+                            source_slot: slot + 1,
+                            dest_slot: s + 1,
+                        });
+                    s
+                }
+            };
+            map.insert((is_static, slot), dest_slot);
+        }
+    }
+
+    let dest_project = dest_dir.to_string_lossy().to_string();
+    let mut slots = Vec::new();
+    for (source_project, requests) in &requests {
+        for outcome in copy_slot_assignments(source_project, &dest_project, requests)? {
+            slots.push(SetlistSlot {
+                source_project: source_project.clone(),
+                slot_type: outcome.slot_type,
+                source_slot: outcome.source_slot,
+                dest_slot: outcome.dest_slot,
+                path: outcome.path,
+                action: outcome.action,
+            });
+        }
+    }
+    for slot in slots.iter().filter(|s| s.action == "missing") {
+        warnings.push(format!(
+            "{} slot {} of {} points at a missing file",
+            slot.slot_type, slot.source_slot, slot.source_project
+        ));
+    }
+
+    // Write the banks.
+    let mut dest_banks: BTreeMap<u8, BankFile> = BTreeMap::new();
+    for (&(dest_bank, dest_part), (source_key, source_part, src_patterns, dest_patterns)) in &parts
+    {
+        let source = &banks[source_key];
+        let dest = match dest_banks.entry(dest_bank) {
+            std::collections::btree_map::Entry::Occupied(e) => e.into_mut(),
+            std::collections::btree_map::Entry::Vacant(e) => {
+                let file = dest_dir.join(format!("bank{:02}.work", dest_bank + 1));
+                e.insert(crate::os_compat::read_bank_file(&file)?)
+            }
+        };
+        let (sp, dp) = (*source_part as usize, dest_part as usize);
+        dest.parts.unsaved.0[dp] = source.parts.unsaved.0[sp];
+        dest.parts.saved.0[dp] = source.parts.saved.0[sp];
+        dest.part_names[dp] = source.part_names[sp];
+        dest.parts_saved_state[dp] = source.parts_saved_state[sp];
+        if source.parts_edited_bitmask & (1 << sp) != 0 {
+            dest.parts_edited_bitmask |= 1 << dp;
+        }
+        for (&src, &dst) in src_patterns.iter().zip(dest_patterns) {
+            dest.patterns.0[dst] = source.patterns.0[src].clone();
+            dest.patterns.0[dst].part_assignment = dest_part;
+        }
+        remap_slots(dest, dp, dest_patterns, &slot_maps[&source_key.0]);
+    }
+    for (dest_bank, bank) in dest_banks.iter_mut() {
+        bank.checksum = bank
+            .calculate_checksum()
+            .map_err(|e| format!("Failed to calculate checksum: {:?}", e))?;
+        let file = dest_dir.join(format!("bank{:02}.work", dest_bank + 1));
+        crate::atomic_write::write_data_file(bank, &file)
+            .map_err(|e| format!("Failed to write bank {}: {}", dest_bank + 1, e))?;
+    }
+
+    let tempos: BTreeSet<String> = projects
+        .values()
+        .map(|p| format!("{}", p.settings.tempo.tempo))
+        .collect();
+    if tempos.len() > 1 {
+        warnings.push(format!(
+            "Source projects use different tempos ({}); patterns without their own tempo \
+             play at the new project's tempo",
+            tempos.into_iter().collect::<Vec<_>>().join(", ")
+        ));
+    }
+
+    let placements = layout
+        .iter()
+        .map(|p| {
+            let e = &entries[p.entry];
+            SetlistPlacement {
+                source_project: e.source_project.clone(),
+                source_bank: e.bank,
+                source_pattern: e.pattern,
+                source_part: p.source_part,
+                dest_bank: p.dest_bank,
+                dest_pattern: p.dest_pattern,
+                dest_part: p.dest_part,
+                label: format!(
+                    "{}{:02}",
+                    BANK_LETTERS[p.dest_bank as usize],
+                    p.dest_pattern + 1
+                ),
+            }
+        })
+        .collect();
+    Ok((placements, slots, warnings))
+}
+
+/// Create project `name` in `set_path` holding `entries` in order. The new
+/// project is removed again when building it fails.
+pub fn build_setlist(
+    set_path: &str,
+    name: &str,
+    entries: &[SetlistEntry],
+) -> Result<SetlistReport, String> {
+    if entries.is_empty() {
+        return Err("The setlist is empty".to_string());
+    }
+    let project_path = crate::project_manager::create_project_sync(Path::new(set_path), name)?;
+    let dest_dir = PathBuf::from(&project_path);
+    match build_into(&dest_dir, entries) {
+        Ok((placements, slots, warnings)) => Ok(SetlistReport {
+            project_path,
+            placements,
+            slots,
+            warnings,
+        }),
+        Err(e) => {
+            let _ = std::fs::remove_dir_all(&dest_dir);
+            Err(e)
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn build_setlist_project(
+    set_path: String,
+    name: String,
+    entries: Vec<SetlistEntry>,
+) -> Result<SetlistReport, String> {
+    crate::fs_scope::ensure_allowed(&set_path)?;
+    tauri::async_runtime::spawn_blocking(move || build_setlist(&set_path, &name, &entries))
+        .await
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project_reader::encode_trig_masks;
+    use crate::test_support::write_wav;
+    use ot_tools_io::projects::SlotAttributes;
+    use ot_tools_io::settings::SlotType;
+    use tempfile::TempDir;
+
+    /// A project whose pattern 1 plays `sample` from static slot 1 on part 2.
+    fn source_project(set: &Path, name: &str, sample: &str, value: i16) -> String {
+        let dir = set.join(name);
+        std::fs::create_dir_all(&dir).unwrap();
+        write_wav(&dir.join(sample), 1, 44100, &[value; 32]);
+        let mut project = ProjectFile::default();
+        project.slots.static_slots[0] = Some(
+            SlotAttributes::new(
+                SlotType::Static,
+                1,
+                Some(PathBuf::from(sample)),
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap(),
+        );
+        project.to_data_file(&dir.join("project.work")).unwrap();
+
+        let mut bank = BankFile::default();
+        let pattern = &mut bank.patterns.0[0];
+        pattern.part_assignment = 1;
+        pattern.audio_track_trigs.0[0].trig_masks.trigger =
+            encode_trig_masks(&std::array::from_fn(|s| s % 4 == 0));
+        let part = &mut bank.parts.unsaved.0[1];
+        for t in 0..8 {
+            part.audio_track_machine_types[t] = 2; // Thru: no sample
+        }
+        part.audio_track_machine_types[0] = 0;
+        part.audio_track_machine_slots[0].static_slot_id = 0;
+        bank.to_data_file(&dir.join("bank01.work")).unwrap();
+        dir.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn test_plan_layout_shares_parts_and_fills_banks() {
+        // Entries 0-2 play the same source part, entries 3-6 four other parts.
+        let parts = [0u8, 0, 0, 1, 2, 3, 0];
+        let layout = plan_layout(
+            7,
+            |i| (if i < 6 { "A" } else { "B" }.to_string(), 0),
+            |i| parts[i],
+        )
+        .unwrap();
+        assert!(layout[..3]
+            .iter()
+            .all(|p| p.dest_part == 0 && p.dest_bank == 0));
+        assert_eq!(layout[5].dest_part, 3);
+        // Fifth distinct part: next bank, first pattern and part.
+        assert_eq!(
+            (
+                layout[6].dest_bank,
+                layout[6].dest_pattern,
+                layout[6].dest_part
+            ),
+            (1, 0, 0)
+        );
+
+        let many = plan_layout(17, |_| ("A".to_string(), 0), |_| 0).unwrap();
+        assert_eq!((many[16].dest_bank, many[16].dest_pattern), (1, 0));
+    }
+
+    #[test]
+    fn test_build_setlist_copies_patterns_parts_and_renumbers_slots() {
+        let set = TempDir::new().unwrap();
+        let one = source_project(set.path(), "ONE", "kick.wav", 1000);
+        let two = source_project(set.path(), "TWO", "snare.wav", -1000);
+        let entries = vec![
+            SetlistEntry {
+                source_project: one,
+                bank: 0,
+                pattern: 0,
+            },
+            SetlistEntry {
+                source_project: two,
+                bank: 0,
+                pattern: 0,
+            },
+        ];
+
+        let report = build_setlist(&set.path().to_string_lossy(), "GIG", &entries).unwrap();
+        assert_eq!(report.placements[0].label, "A01");
+        assert_eq!(report.placements[1].label, "A02");
+        assert_eq!(report.placements[1].dest_part, 1);
+        // Both sources used static slot 1 for different files.
+        let dest_slots: Vec<u8> = report.slots.iter().map(|s| s.dest_slot).collect();
+        assert_eq!(dest_slots, vec![1, 2]);
+
+        let bank = BankFile::from_data_file(&set.path().join("GIG/bank01.work")).unwrap();
+        assert_eq!(bank.patterns.0[1].part_assignment, 1);
+        assert_eq!(
+            bank.parts.unsaved.0[1].audio_track_machine_slots[0].static_slot_id,
+            1
+        );
+        let project = ProjectFile::from_data_file(&set.path().join("GIG/project.work")).unwrap();
+        let path = |i: usize| {
+            project.slots.static_slots[i]
+                .as_ref()
+                .and_then(|s| s.path.clone())
+                .unwrap()
+        };
+        assert!(path(0).ends_with("kick.wav"));
+        assert!(path(1).ends_with("snare.wav"));
+    }
+}
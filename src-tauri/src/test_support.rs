@@ -0,0 +1,39 @@
+// Fixtures shared by the unit tests of several modules.
+
+use ot_tools_io::{BankFile, OctatrackFileIO, ProjectFile};
+use std::path::Path;
+use tempfile::TempDir;
+
+/// Write a 16-bit PCM WAV holding `samples`, interleaved when `channels` > 1.
+pub(crate) fn write_wav(path: &Path, channels: u16, sample_rate: u32, samples: &[i16]) {
+    let spec = hound::WavSpec {
+        channels,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(path, spec).unwrap();
+    for s in samples {
+        writer.write_sample(*s).unwrap();
+    }
+    writer.finalize().unwrap();
+}
+
+/// `frames` of a repeating ramp: audible content that isn't silence.
+pub(crate) fn ramp(frames: usize) -> Vec<i16> {
+    (0..frames).map(|i| (i % 1000) as i16).collect()
+}
+
+/// A project folder holding a default project file and 16 default banks.
+pub(crate) fn blank_project() -> TempDir {
+    let dir = TempDir::new().unwrap();
+    ProjectFile::default()
+        .to_data_file(&dir.path().join("project.work"))
+        .unwrap();
+    for bank in 1..=16 {
+        BankFile::default()
+            .to_data_file(&dir.path().join(format!("bank{:02}.work", bank)))
+            .unwrap();
+    }
+    dir
+}
@@ -0,0 +1,799 @@
+// Multi-file transfers run from a queue on the Rust side instead of one
+// copy_audio_file_with_progress call per file from the frontend. Up to
+// `concurrency` files are copied/converted at once; the queue can be paused
+// (running files finish, no new ones start), reordered and cancelled per
+// file, and reports through one "transfer-queue-progress" event carrying a
+// snapshot of the whole queue.
+//
+// Every status change is appended to a log in the app data directory as one
+// compact JSON line (the changed jobs only), and the log is rewritten from
+// the current state once it grows past a few times the queue size. A big
+// import interrupted by quitting or a crash is restored at launch: files
+// that were being written are deleted and queued again, finished jobs are
+// dropped, and nothing starts until the user chooses to resume or discard.
+
+use crate::audio_pool::{
+    cancel_transfer, copy_single_file_with_options, dest_filename_with_options, is_cancelled,
+    register_cancellation_token, remove_cancellation_token, ConversionOptions,
+};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+/// Minimum interval between progress snapshots; status changes are always sent.
+const EMIT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The log is compacted once it holds more than this many records, or four
+/// per job in the queue, whichever is larger.
+const MIN_COMPACT_RECORDS: usize = 64;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QueueJob {
+    pub id: String, // also the transfer id, so cancel_audio_transfer works on it
+    pub source: String,
+    pub dest_dir: String,
+    pub overwrite: bool,
+    pub conversion: ConversionOptions,
+    pub status: String, // "queued", "running", "done", "failed", "cancelled"
+    pub stage: Option<String>,
+    pub progress: f32, // 0.0 to 1.0 for this file
+    pub dest: Option<String>,
+    pub error: Option<String>,
+    /// Output being written by the running job, deleted if the app stops
+    /// mid-file. None when the file existed before and must be kept.
+    #[serde(default)]
+    pub partial: Option<String>,
+}
+
+impl QueueJob {
+    fn is_finished(&self) -> bool {
+        matches!(self.status.as_str(), "done" | "failed" | "cancelled")
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferQueueSnapshot {
+    pub jobs: Vec<QueueJob>,
+    pub paused: bool,
+    /// Restored from the previous session and held until the user resumes
+    /// or discards it.
+    pub awaiting_resume: bool,
+    pub concurrency: usize,
+    pub queued: usize,
+    pub running: usize,
+    pub finished: usize,
+    pub progress: f32, // whole queue, finished files counting as complete
+}
+
+type Notifier = Arc<dyn Fn(&TransferQueueSnapshot) + Send + Sync>;
+
+struct QueueState {
+    jobs: Vec<QueueJob>,
+    paused: bool,
+    awaiting_resume: bool,
+    concurrency: usize,
+    next_id: u64,
+    last_emit: Option<Instant>,
+    records: usize, // lines in the log since it was last compacted
+}
+
+/// One line of the queue log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum QueueRecord {
+    /// A job was added or its status changed.
+    Job(QueueJob),
+    Removed {
+        ids: Vec<String>,
+    },
+    /// The whole queue order, by id.
+    Order {
+        ids: Vec<String>,
+    },
+    Paused {
+        paused: bool,
+    },
+}
+
+pub struct TransferQueue {
+    state: Mutex<QueueState>,
+    notifier: Mutex<Option<Notifier>>,
+    record_imports: bool,   // add finished files to the library index
+    store: Option<PathBuf>, // where the queue is saved, if anywhere
+    save_lock: Mutex<()>,   // keeps saves in the order of the changes they record
+}
+
+static QUEUE: Lazy<Arc<TransferQueue>> = Lazy::new(|| {
+    let concurrency = crate::get_system_resources().recommended_concurrency;
+    let store = default_queue_path()
+        .inspect_err(|e| eprintln!("[TRANSFER] Queue will not be saved: {}", e))
+        .ok();
+    Arc::new(TransferQueue::new(concurrency, true, store))
+});
+
+pub fn default_queue_path() -> Result<PathBuf, String> {
+    crate::app_data::data_path("transfer_queue.jsonl")
+}
+
+/// Replay a queue log. A torn last line (the app stopped mid-append) is
+/// skipped.
+fn replay(data: &str) -> (Vec<QueueJob>, bool) {
+    let mut jobs: Vec<QueueJob> = Vec::new();
+    let mut paused = false;
+    for line in data.lines().filter(|l| !l.trim().is_empty()) {
+        let record = match serde_json::from_str::<QueueRecord>(line) {
+            Ok(record) => record,
+            Err(e) => {
+                eprintln!("[TRANSFER] Skipping unreadable queue record: {}", e);
+                continue;
+            }
+        };
+        match record {
+            QueueRecord::Job(job) => match jobs.iter_mut().find(|j| j.id == job.id) {
+                Some(existing) => *existing = job,
+                None => jobs.push(job),
+            },
+            QueueRecord::Removed { ids } => jobs.retain(|j| !ids.contains(&j.id)),
+            QueueRecord::Order { ids } => {
+                jobs.sort_by_key(|j| ids.iter().position(|id| *id == j.id).unwrap_or(ids.len()))
+            }
+            QueueRecord::Paused { paused: p } => paused = p,
+        }
+    }
+    (jobs, paused)
+}
+
+/// Load a saved queue: finished jobs are dropped, jobs cut off mid-file are
+/// queued again.
+fn load_saved(path: &Path) -> (Vec<QueueJob>, bool) {
+    let (mut jobs, paused) = match fs::read_to_string(path) {
+        Ok(data) => replay(&data),
+        Err(_) => (Vec::new(), false),
+    };
+    jobs.retain(|j| !j.is_finished());
+    for job in jobs.iter_mut().filter(|j| j.status == "running") {
+        if let Some(partial) = job.partial.take() {
+            let _ = fs::remove_file(partial);
+        }
+        job.status = "queued".to_string();
+        job.stage = None;
+        job.progress = 0.0;
+    }
+    (jobs, paused)
+}
+
+/// One compact JSON line per record.
+fn encode_records(records: &[QueueRecord]) -> Vec<u8> {
+    let mut data = Vec::new();
+    for record in records {
+        if let Ok(line) = serde_json::to_vec(record) {
+            data.extend_from_slice(&line);
+            data.push(b'\n');
+        }
+    }
+    data
+}
+
+/// Sequence number of a "queue-N" id.
+fn id_number(id: &str) -> u64 {
+    id.strip_prefix("queue-")
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(0)
+}
+
+/// Records for the current state of the given jobs.
+fn job_records(state: &QueueState, ids: &[String]) -> Vec<QueueRecord> {
+    state
+        .jobs
+        .iter()
+        .filter(|j| ids.contains(&j.id))
+        .map(|j| QueueRecord::Job(j.clone()))
+        .collect()
+}
+
+impl TransferQueue {
+    /// A queue restored from `store` when given, empty otherwise.
+    fn new(concurrency: usize, record_imports: bool, store: Option<PathBuf>) -> Self {
+        let (jobs, paused) = store.as_deref().map(load_saved).unwrap_or_default();
+        // An interrupted queue waits for the user; an empty one is never
+        // left paused, since nothing would show it
+        let awaiting_resume = !jobs.is_empty();
+        let next_id = jobs.iter().map(|j| id_number(&j.id)).max().unwrap_or(0) + 1;
+        let queue = TransferQueue {
+            state: Mutex::new(QueueState {
+                jobs,
+                paused: paused && awaiting_resume,
+                awaiting_resume,
+                concurrency: concurrency.max(1),
+                next_id,
+                last_emit: None,
+                records: 0,
+            }),
+            notifier: Mutex::new(None),
+            record_imports,
+            store,
+            save_lock: Mutex::new(()),
+        };
+        let mut state = queue.state.lock().unwrap();
+        queue.compact(&mut state);
+        drop(state);
+        queue
+    }
+
+    /// Rewrite the log as the current state, one record per job.
+    fn compact(&self, state: &mut QueueState) {
+        let Some(store) = &self.store else {
+            return;
+        };
+        let records: Vec<QueueRecord> = std::iter::once(QueueRecord::Paused {
+            paused: state.paused,
+        })
+        .chain(state.jobs.iter().cloned().map(QueueRecord::Job))
+        .collect();
+        let data = encode_records(&records);
+        let result = match store.parent() {
+            Some(parent) => fs::create_dir_all(parent).map_err(|e| e.to_string()),
+            None => Ok(()),
+        }
+        .and_then(|_| crate::atomic_write::write_atomic(store, &data));
+        match result {
+            Ok(()) => state.records = state.jobs.len() + 1,
+            Err(e) => eprintln!("[TRANSFER] Failed to save queue: {}", e),
+        }
+    }
+
+    /// Append records to the log, compacting it when it has grown too long.
+    fn append(&self, state: &mut QueueState, records: Vec<QueueRecord>) {
+        let Some(store) = &self.store else {
+            return;
+        };
+        if records.is_empty() {
+            return;
+        }
+        if state.records + records.len() > MIN_COMPACT_RECORDS.max(4 * state.jobs.len()) {
+            self.compact(state);
+            return;
+        }
+        let data = encode_records(&records);
+        let result = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(store)
+            .and_then(|mut file| file.write_all(&data));
+        match result {
+            Ok(()) => state.records += records.len(),
+            Err(e) => eprintln!("[TRANSFER] Failed to save queue: {}", e),
+        }
+    }
+
+    fn set_notifier(&self, notifier: Notifier) {
+        *self.notifier.lock().unwrap() = Some(notifier);
+    }
+
+    fn snapshot_of(state: &QueueState) -> TransferQueueSnapshot {
+        let count = |status: &str| state.jobs.iter().filter(|j| j.status == status).count();
+        let finished = state.jobs.iter().filter(|j| j.is_finished()).count();
+        let done: f32 = state
+            .jobs
+            .iter()
+            .map(|j| if j.is_finished() { 1.0 } else { j.progress })
+            .sum();
+        TransferQueueSnapshot {
+            jobs: state.jobs.clone(),
+            paused: state.paused,
+            awaiting_resume: state.awaiting_resume,
+            concurrency: state.concurrency,
+            queued: count("queued"),
+            running: count("running"),
+            finished,
+            progress: if state.jobs.is_empty() {
+                1.0
+            } else {
+                done / state.jobs.len() as f32
+            },
+        }
+    }
+
+    pub fn snapshot(&self) -> TransferQueueSnapshot {
+        Self::snapshot_of(&self.state.lock().unwrap())
+    }
+
+    /// Send a snapshot to the notifier; `force` bypasses the rate limit.
+    fn notify(&self, force: bool) {
+        let snapshot = {
+            let mut state = self.state.lock().unwrap();
+            if !force && state.last_emit.is_some_and(|t| t.elapsed() < EMIT_INTERVAL) {
+                return;
+            }
+            state.last_emit = Some(Instant::now());
+            Self::snapshot_of(&state)
+        };
+        let notifier = self.notifier.lock().unwrap().clone();
+        if let Some(notifier) = notifier {
+            notifier(&snapshot);
+        }
+    }
+
+    /// Record a status change: log the records `change` derives from the
+    /// current state and send a snapshot. Records are built and written under
+    /// one lock so the log keeps the order of the changes.
+    fn changed(&self, change: impl FnOnce(&QueueState) -> Vec<QueueRecord>) {
+        if self.store.is_some() {
+            let _order = self.save_lock.lock().unwrap();
+            let mut state = self.state.lock().unwrap();
+            let records = change(&state);
+            self.append(&mut state, records);
+        }
+        self.notify(true);
+    }
+
+    /// Add files to the end of the queue. Returns the new job ids.
+    pub fn enqueue(
+        self: &Arc<Self>,
+        sources: Vec<String>,
+        dest_dir: &str,
+        overwrite: bool,
+        conversion: &ConversionOptions,
+    ) -> Vec<String> {
+        let ids = {
+            let mut state = self.state.lock().unwrap();
+            let mut ids = Vec::with_capacity(sources.len());
+            for source in sources {
+                let id = format!("queue-{}", state.next_id);
+                state.next_id += 1;
+                state.jobs.push(QueueJob {
+                    id: id.clone(),
+                    source,
+                    dest_dir: dest_dir.to_string(),
+                    overwrite,
+                    conversion: conversion.clone(),
+                    status: "queued".to_string(),
+                    stage: None,
+                    progress: 0.0,
+                    dest: None,
+                    error: None,
+                    partial: None,
+                });
+                ids.push(id);
+            }
+            ids
+        };
+        self.changed(|state| job_records(state, &ids));
+        self.pump();
+        ids
+    }
+
+    /// Start queued jobs, in queue order, until `concurrency` are running.
+    fn pump(self: &Arc<Self>) {
+        let to_start: Vec<QueueJob> = {
+            let mut state = self.state.lock().unwrap();
+            if state.paused || state.awaiting_resume {
+                return;
+            }
+            let running = state.jobs.iter().filter(|j| j.status == "running").count();
+            let free = state.concurrency.saturating_sub(running);
+            state
+                .jobs
+                .iter_mut()
+                .filter(|j| j.status == "queued")
+                .take(free)
+                .map(|j| {
+                    j.status = "running".to_string();
+                    let dest = Path::new(&j.dest_dir).join(dest_filename_with_options(
+                        Path::new(&j.source),
+                        &j.conversion,
+                    ));
+                    if j.overwrite || !dest.exists() {
+                        j.partial = Some(dest.to_string_lossy().to_string());
+                    }
+                    j.clone()
+                })
+                .collect()
+        };
+        if to_start.is_empty() {
+            return;
+        }
+        let ids: Vec<String> = to_start.iter().map(|j| j.id.clone()).collect();
+        self.changed(|state| job_records(state, &ids));
+        for job in to_start {
+            let queue = Arc::clone(self);
+            std::thread::spawn(move || queue.run_job(job));
+        }
+    }
+
+    fn update(&self, id: &str, change: impl FnOnce(&mut QueueJob)) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(job) = state.jobs.iter_mut().find(|j| j.id == id) {
+            change(job);
+        }
+    }
+
+    fn run_job(self: Arc<Self>, job: QueueJob) {
+        let token = register_cancellation_token(&job.id);
+        let queue = Arc::clone(&self);
+        let id = job.id.clone();
+        let result = copy_single_file_with_options(
+            &job.source,
+            &job.dest_dir,
+            job.overwrite,
+            &job.conversion,
+            move |stage: &str, progress: f32| {
+                queue.update(&id, |j| {
+                    j.stage = Some(stage.to_string());
+                    j.progress = progress;
+                });
+                queue.notify(false);
+            },
+            Some(token.clone()),
+        );
+        remove_cancellation_token(&job.id);
+
+        if let Ok(dest) = &result {
+            if self.record_imports {
+                crate::library_index::record_import(Path::new(&job.source), Path::new(dest));
+            }
+        }
+        let cancelled = is_cancelled(&token);
+        self.update(&job.id, |j| {
+            j.partial = None;
+            match result {
+                Ok(dest) => {
+                    j.status = "done".to_string();
+                    j.progress = 1.0;
+                    j.dest = Some(dest);
+                }
+                Err(e) => {
+                    j.status = if cancelled { "cancelled" } else { "failed" }.to_string();
+                    j.error = Some(e);
+                }
+            }
+        });
+        self.changed(|state| job_records(state, std::slice::from_ref(&job.id)));
+        self.pump();
+    }
+
+    pub fn pause(&self) {
+        self.state.lock().unwrap().paused = true;
+        self.changed(|_| vec![QueueRecord::Paused { paused: true }]);
+    }
+
+    /// Resume a paused queue, including one restored at launch.
+    pub fn resume(self: &Arc<Self>) {
+        {
+            let mut state = self.state.lock().unwrap();
+            state.paused = false;
+            state.awaiting_resume = false;
+        }
+        self.changed(|_| vec![QueueRecord::Paused { paused: false }]);
+        self.pump();
+    }
+
+    /// Drop the jobs still waiting to run, for a restored queue the user
+    /// does not want to continue. Returns how many were removed.
+    pub fn discard_queued(self: &Arc<Self>) -> usize {
+        let ids: Vec<String> = {
+            let mut state = self.state.lock().unwrap();
+            let ids = state
+                .jobs
+                .iter()
+                .filter(|j| j.status == "queued")
+                .map(|j| j.id.clone())
+                .collect::<Vec<_>>();
+            state.jobs.retain(|j| j.status != "queued");
+            state.paused = false;
+            state.awaiting_resume = false;
+            ids
+        };
+        let removed = ids.len();
+        self.changed(|_| {
+            vec![
+                QueueRecord::Removed { ids },
+                QueueRecord::Paused { paused: false },
+            ]
+        });
+        removed
+    }
+
+    pub fn set_concurrency(self: &Arc<Self>, concurrency: usize) {
+        self.state.lock().unwrap().concurrency = concurrency.max(1);
+        self.changed(|_| Vec::new());
+        self.pump();
+    }
+
+    /// Put the listed queued jobs first, in the given order. Jobs not listed
+    /// keep their relative order after them; started jobs are not moved.
+    pub fn reorder(&self, order: &[String]) {
+        {
+            let mut state = self.state.lock().unwrap();
+            let jobs = std::mem::take(&mut state.jobs);
+            let (mut pending, started): (Vec<_>, Vec<_>) =
+                jobs.into_iter().partition(|j| j.status == "queued");
+            let rank = |job: &QueueJob| {
+                order
+                    .iter()
+                    .position(|id| *id == job.id)
+                    .unwrap_or(order.len())
+            };
+            pending.sort_by_key(rank); // stable: unlisted jobs keep their order
+            state.jobs = started.into_iter().chain(pending).collect();
+        }
+        self.changed(|state| {
+            vec![QueueRecord::Order {
+                ids: state.jobs.iter().map(|j| j.id.clone()).collect(),
+            }]
+        });
+    }
+
+    /// Cancel a job: queued jobs are dropped from the run, running ones are
+    /// interrupted. Returns false if the job is unknown or already finished.
+    pub fn cancel(&self, id: &str) -> bool {
+        let cancelled = {
+            let mut state = self.state.lock().unwrap();
+            match state.jobs.iter_mut().find(|j| j.id == id) {
+                Some(job) if job.status == "queued" => {
+                    job.status = "cancelled".to_string();
+                    true
+                }
+                Some(job) if job.status == "running" => cancel_transfer(id),
+                _ => false,
+            }
+        };
+        if cancelled {
+            self.changed(|state| job_records(state, &[id.to_string()]));
+        }
+        cancelled
+    }
+
+    /// Drop finished jobs from the queue. Returns how many were removed.
+    pub fn clear_finished(&self) -> usize {
+        let ids: Vec<String> = {
+            let mut state = self.state.lock().unwrap();
+            let ids = state
+                .jobs
+                .iter()
+                .filter(|j| j.is_finished())
+                .map(|j| j.id.clone())
+                .collect::<Vec<_>>();
+            state.jobs.retain(|j| !j.is_finished());
+            ids
+        };
+        let removed = ids.len();
+        self.changed(|_| vec![QueueRecord::Removed { ids }]);
+        removed
+    }
+}
+
+/// Called at launch: restore a queue the previous session left unfinished.
+/// It stays on hold until the frontend resumes or discards it.
+pub fn restore_saved_queue(app: AppHandle) {
+    // Off the main thread: the first use of the queue probes system resources
+    std::thread::spawn(move || {
+        global_queue(app);
+    });
+}
+
+fn global_queue(app: AppHandle) -> Arc<TransferQueue> {
+    let queue = Arc::clone(&QUEUE);
+    queue.set_notifier(Arc::new(move |snapshot| {
+        let _ = app.emit("transfer-queue-progress", snapshot);
+    }));
+    queue
+}
+
+#[tauri::command]
+pub fn enqueue_transfers(
+    app: AppHandle,
+    source_paths: Vec<String>,
+    destination_dir: String,
+    overwrite: Option<bool>,
+    conversion: Option<ConversionOptions>,
+) -> Result<Vec<String>, String> {
+    crate::fs_scope::ensure_allowed(&destination_dir)?;
+    Ok(global_queue(app).enqueue(
+        source_paths,
+        &destination_dir,
+        overwrite.unwrap_or(false),
+        &conversion.unwrap_or_default(),
+    ))
+}
+
+#[tauri::command]
+pub fn get_transfer_queue() -> TransferQueueSnapshot {
+    QUEUE.snapshot()
+}
+
+#[tauri::command]
+pub fn pause_transfer_queue(app: AppHandle) {
+    global_queue(app).pause()
+}
+
+#[tauri::command]
+pub fn resume_transfer_queue(app: AppHandle) {
+    global_queue(app).resume()
+}
+
+#[tauri::command]
+pub fn discard_transfer_queue(app: AppHandle) -> usize {
+    global_queue(app).discard_queued()
+}
+
+#[tauri::command]
+pub fn set_transfer_concurrency(app: AppHandle, concurrency: usize) {
+    global_queue(app).set_concurrency(concurrency)
+}
+
+#[tauri::command]
+pub fn reorder_transfer_queue(app: AppHandle, order: Vec<String>) {
+    global_queue(app).reorder(&order)
+}
+
+#[tauri::command]
+pub fn cancel_queued_transfer(app: AppHandle, id: String) -> bool {
+    global_queue(app).cancel(&id)
+}
+
+#[tauri::command]
+pub fn clear_finished_transfers(app: AppHandle) -> usize {
+    global_queue(app).clear_finished()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{ramp, write_wav};
+    use tempfile::TempDir;
+
+    fn wait_until_idle(queue: &TransferQueue) -> TransferQueueSnapshot {
+        let started = Instant::now();
+        loop {
+            let snapshot = queue.snapshot();
+            if snapshot.running == 0 && (snapshot.queued == 0 || snapshot.paused) {
+                return snapshot;
+            }
+            assert!(started.elapsed() < Duration::from_secs(30), "queue stuck");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn test_saved_queue_waits_for_resume_and_drops_finished_jobs() {
+        let tmp = TempDir::new().unwrap();
+        let store = tmp.path().join("queue.jsonl");
+        let source = tmp.path().join("a.wav");
+        write_wav(&source, 1, 44100, &ramp(4800));
+        let dest = tmp.path().join("out");
+        std::fs::create_dir_all(&dest).unwrap();
+
+        let queue = Arc::new(TransferQueue::new(1, false, Some(store.clone())));
+        queue.pause();
+        let ids = queue.enqueue(
+            vec![source.to_string_lossy().to_string(); 3],
+            &dest.to_string_lossy(),
+            false,
+            &ConversionOptions::default(),
+        );
+        // Simulate quitting while the first file was half written, after the
+        // third had finished
+        let partial = dest.join("a.wav");
+        std::fs::write(&partial, b"half a file").unwrap();
+        queue.update(&ids[0], |j| {
+            j.status = "running".to_string();
+            j.progress = 0.5;
+            j.partial = Some(partial.to_string_lossy().to_string());
+        });
+        queue.update(&ids[2], |j| j.status = "done".to_string());
+        queue.changed(|state| job_records(state, &[ids[0].clone(), ids[2].clone()]));
+        drop(queue);
+
+        let restored = Arc::new(TransferQueue::new(1, false, Some(store.clone())));
+        let snapshot = restored.snapshot();
+        assert!(snapshot.awaiting_resume);
+        assert_eq!(snapshot.jobs.len(), 2);
+        assert_eq!(snapshot.queued, 2);
+        assert_eq!(snapshot.jobs[0].progress, 0.0);
+        assert!(snapshot.jobs[0].partial.is_none());
+        assert!(!partial.exists());
+        // The log was compacted to the restored state on load
+        let lines = std::fs::read_to_string(&store).unwrap().lines().count();
+        assert_eq!(lines, 3);
+
+        // Nothing runs until the user decides; new ids follow the kept ones
+        let more = restored.enqueue(
+            vec![source.to_string_lossy().to_string()],
+            &dest.to_string_lossy(),
+            false,
+            &Default::default(),
+        );
+        assert_eq!(more, vec!["queue-3".to_string()]);
+        assert_eq!(restored.snapshot().running, 0);
+
+        assert_eq!(restored.discard_queued(), 3);
+        let snapshot = restored.snapshot();
+        assert!(!snapshot.awaiting_resume && !snapshot.paused);
+        assert!(snapshot.jobs.is_empty());
+        drop(restored);
+        let reopened = TransferQueue::new(1, false, Some(store));
+        assert!(!reopened.snapshot().awaiting_resume);
+    }
+
+    #[test]
+    fn test_queue_log_is_compacted() {
+        let tmp = TempDir::new().unwrap();
+        let store = tmp.path().join("queue.jsonl");
+        let queue = Arc::new(TransferQueue::new(1, false, Some(store.clone())));
+        queue.pause();
+        let ids = queue.enqueue(
+            vec!["a.wav".to_string(), "b.wav".to_string()],
+            &tmp.path().to_string_lossy(),
+            false,
+            &ConversionOptions::default(),
+        );
+        for i in 0..500 {
+            queue.reorder(&[ids[i % 2].clone()]);
+        }
+        let lines = std::fs::read_to_string(&store).unwrap().lines().count();
+        assert!(lines <= MIN_COMPACT_RECORDS, "{} lines", lines);
+
+        let restored = TransferQueue::new(1, false, Some(store));
+        let order: Vec<String> = restored.snapshot().jobs.into_iter().map(|j| j.id).collect();
+        assert_eq!(order, vec![ids[1].clone(), ids[0].clone()]);
+    }
+
+    #[test]
+    fn test_queue_pause_reorder_cancel_and_run() {
+        let tmp = TempDir::new().unwrap();
+        let sources: Vec<String> = ["a", "b", "c"]
+            .iter()
+            .map(|name| {
+                let path = tmp.path().join(format!("{}.wav", name));
+                write_wav(&path, 1, 48000, &ramp(4800));
+                path.to_string_lossy().to_string()
+            })
+            .collect();
+        let dest = tmp.path().join("out");
+        std::fs::create_dir_all(&dest).unwrap();
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let queue = Arc::new(TransferQueue::new(1, false, None));
+        let sink = Arc::clone(&events);
+        queue.set_notifier(Arc::new(move |s: &TransferQueueSnapshot| {
+            sink.lock().unwrap().push(s.clone())
+        }));
+
+        queue.pause();
+        let ids = queue.enqueue(
+            sources,
+            &dest.to_string_lossy(),
+            false,
+            &ConversionOptions::default(),
+        );
+        assert_eq!(queue.snapshot().queued, 3);
+
+        queue.reorder(&[ids[2].clone()]);
+        let order: Vec<String> = queue.snapshot().jobs.into_iter().map(|j| j.id).collect();
+        assert_eq!(order, vec![ids[2].clone(), ids[0].clone(), ids[1].clone()]);
+
+        assert!(queue.cancel(&ids[1]));
+        queue.resume();
+        let done = wait_until_idle(&queue);
+
+        let status: Vec<&str> = done.jobs.iter().map(|j| j.status.as_str()).collect();
+        assert_eq!(status, vec!["done", "done", "cancelled"]);
+        assert_eq!(done.progress, 1.0);
+        assert!(dest.join("a.wav").exists());
+        assert!(!dest.join("b.wav").exists());
+        let converted = hound::WavReader::open(dest.join("c.wav")).unwrap();
+        assert_eq!(converted.spec().sample_rate, 44100);
+        // Never more than `concurrency` files in flight
+        assert!(events.lock().unwrap().iter().all(|s| s.running <= 1));
+
+        assert!(!queue.cancel(&ids[0]));
+        assert_eq!(queue.clear_finished(), 3);
+        assert!(queue.snapshot().jobs.is_empty());
+    }
+}
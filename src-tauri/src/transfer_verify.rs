@@ -0,0 +1,126 @@
+// Verification of copied and converted files, turned on with the `verify`
+// conversion option. Card readers can corrupt writes without reporting an
+// error, so the written file is read back once the transfer is done:
+//
+// - plain copies must hash the same as their source;
+// - converted files must parse as a WAV at the target sample rate whose data
+//   chunk can be read to the end, with the source's duration when the source
+//   is WAV or AIFF.
+//
+// A file that fails is deleted, so the transfer ends as failed rather than
+// leaving a bad sample on the card.
+
+use crate::audio_pool::{file_content_hash, SampleRateTarget};
+use crate::project_reader::audio_frames_and_rate;
+use std::fs;
+use std::path::Path;
+
+/// Longest accepted difference in duration after conversion, in seconds,
+/// on top of 0.5% for resampler rounding.
+const DURATION_TOLERANCE: f64 = 0.01;
+
+fn check_copy(source: &Path, dest: &Path) -> Result<(), String> {
+    if file_content_hash(source)? != file_content_hash(dest)? {
+        return Err("content differs from the source".to_string());
+    }
+    Ok(())
+}
+
+fn check_converted(source: &Path, dest: &Path, target: SampleRateTarget) -> Result<(), String> {
+    let reader =
+        hound::WavReader::open(dest).map_err(|e| format!("not a readable WAV file ({})", e))?;
+    let spec = reader.spec();
+    let source_info = audio_frames_and_rate(source).filter(|(_, rate)| *rate > 0);
+    // The source's own rate is only known when it can be read
+    let expected_rate = match (target, source_info) {
+        (SampleRateTarget::Source, None) => spec.sample_rate,
+        (target, info) => target.rate_for(info.map_or(0, |(_, rate)| rate)),
+    };
+    if spec.sample_rate != expected_rate {
+        return Err(format!(
+            "sample rate is {} Hz instead of {} Hz",
+            spec.sample_rate, expected_rate
+        ));
+    }
+    let expected = reader.len() as u64;
+    let mut read = 0u64;
+    for sample in reader.into_samples::<i32>() {
+        sample.map_err(|e| format!("audio data is damaged after {} samples ({})", read, e))?;
+        read += 1;
+    }
+    if read != expected {
+        return Err(format!("audio data holds {} of {} samples", read, expected));
+    }
+
+    let channels = (spec.channels as u64).max(1);
+    let dest_seconds = (read / channels) as f64 / spec.sample_rate as f64;
+    if let Some((frames, rate)) = source_info {
+        let source_seconds = frames as f64 / rate as f64;
+        if (dest_seconds - source_seconds).abs() > DURATION_TOLERANCE + source_seconds * 0.005 {
+            return Err(format!(
+                "length is {:.3}s instead of {:.3}s",
+                dest_seconds, source_seconds
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Read `dest` back and check it against `source`; `conversion` is the sample
+/// rate it was converted to, or None for a byte copy. On failure `dest` is removed.
+pub(crate) fn verify_transfer(
+    source: &Path,
+    dest: &Path,
+    conversion: Option<SampleRateTarget>,
+) -> Result<(), String> {
+    let result = match conversion {
+        Some(target) => check_converted(source, dest, target),
+        None => check_copy(source, dest),
+    };
+    result.map_err(|problem| {
+        let _ = fs::remove_file(dest);
+        format!("Verification failed for {}: {}", dest.display(), problem)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{ramp, write_wav};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_verify_transfer_detects_damage() {
+        let tmp = TempDir::new().unwrap();
+        let source = tmp.path().join("source.wav");
+        write_wav(&source, 1, 44100, &ramp(4410));
+
+        let copy = tmp.path().join("copy.wav");
+        fs::copy(&source, &copy).unwrap();
+        assert!(verify_transfer(&source, &copy, None).is_ok());
+        assert!(verify_transfer(&source, &copy, Some(SampleRateTarget::Octatrack)).is_ok());
+
+        // One flipped byte in the data
+        let mut bytes = fs::read(&copy).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xFF;
+        fs::write(&copy, &bytes).unwrap();
+        let err = verify_transfer(&source, &copy, None).unwrap_err();
+        assert!(err.contains("differs"), "{}", err);
+        assert!(!copy.exists());
+
+        // A converted file cut short by the card
+        let converted = tmp.path().join("converted.wav");
+        fs::copy(&source, &converted).unwrap();
+        let bytes = fs::read(&converted).unwrap();
+        fs::write(&converted, &bytes[..bytes.len() - 2000]).unwrap();
+        assert!(verify_transfer(&source, &converted, Some(SampleRateTarget::Octatrack)).is_err());
+        assert!(!converted.exists());
+
+        // Right format, wrong length
+        let short = tmp.path().join("short.wav");
+        write_wav(&short, 1, 44100, &ramp(2205));
+        let err = verify_transfer(&source, &short, Some(SampleRateTarget::Octatrack)).unwrap_err();
+        assert!(err.contains("length"), "{}", err);
+    }
+}
@@ -0,0 +1,224 @@
+// Rotating backups taken automatically before the app overwrites a bank file.
+//
+// Copies go to `<project>/.otm-backups/<YYYY-MM-DD_HH-MM-SS-mmm>_<file>`, e.g.
+// `2026-10-15_18-22-09-123_bank03.work`. Only the newest `write_backup_keep`
+// copies (maintenance settings) of each file are kept. Unlike the labelled
+// `backups/` folders made before bulk operations, these need no user action
+// and make any single bad write recoverable.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const BACKUP_DIR: &str = ".otm-backups";
+
+/// Length of the timestamp prefix ("YYYY-MM-DD_HH-MM-SS-mmm").
+const STAMP_LEN: usize = 23;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WriteBackup {
+    pub name: String,       // backup file name inside .otm-backups/
+    pub file: String,       // original file name, e.g. "bank03.work"
+    pub created_at: String, // "YYYY-MM-DD HH:MM:SS"
+    pub size: u64,
+}
+
+/// Split a backup file name into (timestamp, original file name).
+fn parse_backup_name(name: &str) -> Option<(&str, &str)> {
+    let stamp = name.get(..STAMP_LEN)?;
+    let file = name.get(STAMP_LEN..)?.strip_prefix('_')?;
+    let valid = stamp.bytes().enumerate().all(|(i, b)| match i {
+        4 | 7 | 13 | 16 | 19 => b == b'-',
+        10 => b == b'_',
+        _ => b.is_ascii_digit(),
+    });
+    (valid && !file.is_empty()).then_some((stamp, file))
+}
+
+fn display_time(stamp: &str) -> String {
+    // "2026-10-15_18-22-09-123" -> "2026-10-15 18:22:09"
+    format!("{} {}", &stamp[..10], stamp[11..19].replace('-', ":"))
+}
+
+/// Backups of `file_name` in `backup_dir`, newest first.
+fn backups_of(backup_dir: &Path, file_name: Option<&str>) -> Vec<WriteBackup> {
+    let Ok(entries) = fs::read_dir(backup_dir) else {
+        return Vec::new();
+    };
+    let mut backups: Vec<WriteBackup> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_file())
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            let (stamp, file) = parse_backup_name(&name)?;
+            if file_name.is_some_and(|f| f != file) {
+                return None;
+            }
+            Some(WriteBackup {
+                created_at: display_time(stamp),
+                file: file.to_string(),
+                size: e.metadata().map(|m| m.len()).unwrap_or(0),
+                name,
+            })
+        })
+        .collect();
+    // The timestamp prefix sorts chronologically.
+    backups.sort_by(|a, b| b.name.cmp(&a.name));
+    backups
+}
+
+/// Copy `file` into its project's `.otm-backups/` and drop copies of the same
+/// file beyond `keep`. No-op when `keep` is 0 or the file does not exist yet.
+pub fn backup_file(file: &Path, keep: u32) -> Result<Option<PathBuf>, String> {
+    if keep == 0 || !file.is_file() {
+        return Ok(None);
+    }
+    let file_name = file
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or_else(|| format!("Invalid file path: {}", file.display()))?;
+    let backup_dir = file.parent().unwrap_or(Path::new(".")).join(BACKUP_DIR);
+
+    let size = fs::metadata(file).map(|m| m.len()).unwrap_or(0);
+    crate::disk_space::ensure_free_space(&backup_dir, size)?;
+    fs::create_dir_all(&backup_dir)
+        .map_err(|e| format!("Failed to create backup directory: {}", e))?;
+
+    let stamp = chrono::Local::now()
+        .format("%Y-%m-%d_%H-%M-%S-%3f")
+        .to_string();
+    let dest = backup_dir.join(format!("{}_{}", stamp, file_name));
+    fs::copy(file, &dest).map_err(|e| format!("Failed to back up {}: {}", file_name, e))?;
+
+    for old in backups_of(&backup_dir, Some(&file_name))
+        .into_iter()
+        .skip(keep as usize)
+    {
+        let _ = fs::remove_file(backup_dir.join(&old.name));
+    }
+    Ok(Some(dest))
+}
+
+/// [`backup_file`] with the configured rotation count. Called by every bank
+/// write right before the file is replaced; a failed backup aborts the write.
+/// Returns the copy taken, if any.
+pub(crate) fn backup_before_write(file: &Path) -> Result<Option<PathBuf>, String> {
+    backup_file(
+        file,
+        crate::maintenance::current_settings().write_backup_keep,
+    )
+}
+
+/// All automatic backups of a project, newest first.
+pub fn list_backups(project_path: &str) -> Result<Vec<WriteBackup>, String> {
+    let project_dir = Path::new(project_path);
+    if !project_dir.is_dir() {
+        return Err(format!("Project path does not exist: {}", project_path));
+    }
+    Ok(backups_of(&project_dir.join(BACKUP_DIR), None))
+}
+
+/// Put a backup back in place of its original file. The current file is
+/// backed up first, so a restore can itself be undone.
+pub fn restore_backup(project_path: &str, backup_name: &str, keep: u32) -> Result<(), String> {
+    let (_, file_name) = parse_backup_name(backup_name)
+        .filter(|_| !backup_name.contains(['/', '\\']))
+        .ok_or_else(|| format!("Invalid backup name: {}", backup_name))?;
+    let project_dir = Path::new(project_path);
+    let source = project_dir.join(BACKUP_DIR).join(backup_name);
+    if !source.is_file() {
+        return Err(format!("Backup not found: {}", backup_name));
+    }
+    let target = project_dir.join(file_name);
+
+    let lock = crate::project_reader::bank_file_lock(&target);
+    let _guard = lock.lock().unwrap_or_else(|e| e.into_inner());
+    crate::project_lock::ensure_unlocked(project_dir)?;
+    // Keep at least one slot so the pre-restore copy is not rotated out at once.
+    backup_file(&target, keep.max(1))?;
+    let data =
+        fs::read(&source).map_err(|e| format!("Failed to read backup {}: {}", backup_name, e))?;
+    crate::atomic_write::write_atomic(&target, &data)
+        .map_err(|e| format!("Failed to restore {}: {}", file_name, e))?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn list_write_backups(project_path: String) -> Result<Vec<WriteBackup>, String> {
+    crate::fs_scope::ensure_allowed(&project_path)?;
+    tauri::async_runtime::spawn_blocking(move || list_backups(&project_path))
+        .await
+        .unwrap()
+}
+
+#[tauri::command]
+pub async fn restore_write_backup(project_path: String, backup_name: String) -> Result<(), String> {
+    crate::fs_scope::ensure_allowed(&project_path)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let keep = crate::maintenance::current_settings().write_backup_keep;
+        restore_backup(&project_path, &backup_name, keep)
+    })
+    .await
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_backups_rotate_per_file() {
+        let dir = TempDir::new().unwrap();
+        let bank = dir.path().join("bank01.work");
+        let other = dir.path().join("bank02.work");
+        fs::write(&other, b"other").unwrap();
+        backup_file(&other, 2).unwrap();
+        for i in 0..4u8 {
+            fs::write(&bank, [i]).unwrap();
+            backup_file(&bank, 2).unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        }
+
+        let backups = list_backups(&dir.path().to_string_lossy()).unwrap();
+        let bank_backups: Vec<_> = backups.iter().filter(|b| b.file == "bank01.work").collect();
+        assert_eq!(bank_backups.len(), 2);
+        assert_eq!(
+            fs::read(dir.path().join(BACKUP_DIR).join(&bank_backups[0].name)).unwrap(),
+            vec![3]
+        );
+        assert!(backups.iter().any(|b| b.file == "bank02.work"));
+    }
+
+    #[test]
+    fn test_restore_replaces_file_and_keeps_current_copy() {
+        let dir = TempDir::new().unwrap();
+        let bank = dir.path().join("bank01.work");
+        fs::write(&bank, b"hardware work").unwrap();
+        let backup = backup_file(&bank, 5).unwrap().unwrap();
+        fs::write(&bank, b"bad write").unwrap();
+
+        let name = backup.file_name().unwrap().to_string_lossy().to_string();
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        restore_backup(&dir.path().to_string_lossy(), &name, 5).unwrap();
+
+        assert_eq!(fs::read(&bank).unwrap(), b"hardware work");
+        let backups = list_backups(&dir.path().to_string_lossy()).unwrap();
+        assert_eq!(backups.len(), 2);
+        assert_eq!(
+            fs::read(dir.path().join(BACKUP_DIR).join(&backups[0].name)).unwrap(),
+            b"bad write"
+        );
+    }
+
+    #[test]
+    fn test_disabled_backups_and_invalid_names() {
+        let dir = TempDir::new().unwrap();
+        let bank = dir.path().join("bank01.work");
+        fs::write(&bank, b"x").unwrap();
+        assert!(backup_file(&bank, 0).unwrap().is_none());
+        assert!(!dir.path().join(BACKUP_DIR).exists());
+        assert!(restore_backup(&dir.path().to_string_lossy(), "../project.work", 5).is_err());
+        assert!(parse_backup_name("2026-10-15_18-22-09-123_bank01.work").is_some());
+    }
+}
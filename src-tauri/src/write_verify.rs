@@ -0,0 +1,314 @@
+// Post-write verification for part edits.
+//
+// After a part write (save, commit, reload) the bank file is read back and the
+// fields the write changed are compared with what was meant to be written,
+// and the checksum of what was read back is computed afresh and compared with
+// the one written. When the file doesn't match, the copy taken
+// by `write_backup` just before the write is put back, so a bad write never
+// stays on the card. Each write says whether to verify; the `verify_writes`
+// maintenance setting is the default when the caller leaves it open.
+
+use ot_tools_io::{BankFile, OctatrackFileIO};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct VerificationReport {
+    pub file: String,
+    pub checked: bool, // false when verification is turned off
+    pub expected_checksum: u16,
+    pub found_checksum: Option<u16>, // computed from the re-read file; None when unreadable
+    pub fields: Vec<String>,         // fields compared
+    pub mismatches: Vec<String>,     // fields that differ after re-read
+    pub rolled_back: bool,
+}
+
+impl VerificationReport {
+    pub fn passed(&self) -> bool {
+        !self.checked
+            || (self.found_checksum == Some(self.expected_checksum) && self.mismatches.is_empty())
+    }
+
+    fn failure_message(&self) -> String {
+        let mut problems = Vec::new();
+        match self.found_checksum {
+            None => problems.push("file could not be read back".to_string()),
+            Some(found) if found != self.expected_checksum => problems.push(format!(
+                "checksum {} instead of {}",
+                found, self.expected_checksum
+            )),
+            Some(_) => {}
+        }
+        if !self.mismatches.is_empty() {
+            problems.push(format!("{} differ", self.mismatches.join(", ")));
+        }
+        let outcome = if self.rolled_back {
+            "the previous file was restored"
+        } else {
+            "no backup was available to restore"
+        };
+        format!(
+            "Write verification failed for {}: {}; {}",
+            self.file,
+            problems.join(", "),
+            outcome
+        )
+    }
+}
+
+/// A write that failed, with the verification report when it got that far
+/// (None when the backup or the write itself failed).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct VerificationFailure {
+    pub message: String,
+    pub report: Option<VerificationReport>,
+}
+
+impl VerificationFailure {
+    fn before_check(message: String) -> Self {
+        VerificationFailure {
+            message,
+            report: None,
+        }
+    }
+}
+
+impl From<String> for VerificationFailure {
+    fn from(message: String) -> Self {
+        VerificationFailure::before_check(message)
+    }
+}
+
+impl From<VerificationFailure> for String {
+    fn from(failure: VerificationFailure) -> String {
+        failure.message
+    }
+}
+
+/// A part of the bank a write changed, compared between the bank that was
+/// written and the bank read back.
+pub(crate) struct BankField {
+    name: String,
+    same: Box<dyn Fn(&BankFile, &BankFile) -> bool>,
+}
+
+pub(crate) fn field(
+    name: impl Into<String>,
+    same: impl Fn(&BankFile, &BankFile) -> bool + 'static,
+) -> BankField {
+    BankField {
+        name: name.into(),
+        same: Box::new(same),
+    }
+}
+
+/// Fields touched by part writes: both copies of each part in `parts`
+/// (0-based) and the edited/saved flags.
+pub(crate) fn part_fields(parts: &[usize]) -> Vec<BankField> {
+    let mut fields = Vec::new();
+    for &part in parts {
+        fields.push(field(
+            format!("part {} (unsaved)", part + 1),
+            move |a, b| a.parts.unsaved.0[part] == b.parts.unsaved.0[part],
+        ));
+        fields.push(field(format!("part {} (saved)", part + 1), move |a, b| {
+            a.parts.saved.0[part] == b.parts.saved.0[part]
+        }));
+    }
+    fields.push(field("parts edited flags", |a, b| {
+        a.parts_edited_bitmask == b.parts_edited_bitmask
+    }));
+    fields.push(field("parts saved state", |a, b| {
+        a.parts_saved_state == b.parts_saved_state
+    }));
+    fields
+}
+
+/// Compare the bank at `path` with `written`. Pure check, no rollback.
+fn check(path: &Path, written: &BankFile, fields: &[BankField]) -> VerificationReport {
+    let mut report = VerificationReport {
+        file: crate::project_reader::file_name_of(path),
+        checked: true,
+        expected_checksum: written.checksum,
+        found_checksum: None,
+        fields: fields.iter().map(|f| f.name.clone()).collect(),
+        mismatches: Vec::new(),
+        rolled_back: false,
+    };
+    if let Ok(reread) = BankFile::from_data_file(path) {
+        // The stored field would match even if the data around it was damaged
+        report.found_checksum = reread.calculate_checksum().ok();
+        report.mismatches = fields
+            .iter()
+            .filter(|f| !(f.same)(written, &reread))
+            .map(|f| f.name.clone())
+            .collect();
+    }
+    report
+}
+
+/// Whether a write verifies when its caller didn't say: the maintenance
+/// setting.
+pub fn verify_by_default() -> bool {
+    crate::maintenance::current_settings().verify_writes
+}
+
+/// Back up, write `bank` to `path` and, when `verify`, check it. On a
+/// mismatch the backup is restored and the report comes back with the error.
+pub(crate) fn write_bank_verified(
+    path: &Path,
+    bank: &BankFile,
+    fields: Vec<BankField>,
+    verify: bool,
+) -> Result<VerificationReport, VerificationFailure> {
+    write_bank_verified_with(path, bank, fields, verify, |p, b| {
+        crate::atomic_write::write_data_file(b, p)
+    })
+}
+
+fn write_bank_verified_with(
+    path: &Path,
+    bank: &BankFile,
+    fields: Vec<BankField>,
+    verify: bool,
+    write: impl FnOnce(&Path, &BankFile) -> Result<(), String>,
+) -> Result<VerificationReport, VerificationFailure> {
+    let backup = crate::write_backup::backup_before_write(path)
+        .map_err(VerificationFailure::before_check)?;
+    write(path, bank).map_err(|e| {
+        VerificationFailure::before_check(format!("Failed to write bank file: {}", e))
+    })?;
+
+    if !verify {
+        return Ok(VerificationReport {
+            file: crate::project_reader::file_name_of(path),
+            checked: false,
+            expected_checksum: bank.checksum,
+            found_checksum: None,
+            fields: Vec::new(),
+            mismatches: Vec::new(),
+            rolled_back: false,
+        });
+    }
+
+    let mut report = check(path, bank, &fields);
+    if report.passed() {
+        return Ok(report);
+    }
+    let restored = match backup {
+        Some(backup) => std::fs::read(&backup)
+            .map_err(|e| format!("reading the backup failed: {}", e))
+            .and_then(|data| {
+                crate::atomic_write::write_atomic(path, &data)
+                    .map_err(|e| format!("restoring failed: {}", e))
+            })
+            .map(|_| true),
+        None => Ok(false),
+    };
+    let message = match restored {
+        Ok(rolled_back) => {
+            report.rolled_back = rolled_back;
+            report.failure_message()
+        }
+        Err(e) => format!("{}; {}", report.failure_message(), e),
+    };
+    Err(VerificationFailure {
+        message,
+        report: Some(report),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ot_tools_io::HasChecksumField;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn bank_with_checksum() -> BankFile {
+        let mut bank = BankFile::default();
+        bank.checksum = bank.calculate_checksum().unwrap();
+        bank
+    }
+
+    #[test]
+    fn test_verified_write_passes() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("bank01.work");
+        let mut bank = bank_with_checksum();
+        bank.parts_edited_bitmask = 1;
+        bank.checksum = bank.calculate_checksum().unwrap();
+
+        let report = write_bank_verified(&path, &bank, part_fields(&[0]), true).unwrap();
+        assert!(report.passed());
+        assert!(report.mismatches.is_empty());
+        assert_eq!(report.found_checksum, Some(bank.checksum));
+    }
+
+    #[test]
+    fn test_failed_verification_restores_backup() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("bank01.work");
+        let original = bank_with_checksum();
+        original.to_data_file(&path).unwrap();
+        let before = fs::read(&path).unwrap();
+
+        let mut intended = bank_with_checksum();
+        intended.parts_edited_bitmask = 0b0101;
+        intended.checksum = intended.calculate_checksum().unwrap();
+        // Simulate a write that silently loses the flags.
+        let failure =
+            write_bank_verified_with(&path, &intended, part_fields(&[0]), true, |p, b| {
+                let mut lossy = bank_with_checksum();
+                lossy.checksum = b.checksum;
+                lossy.to_data_file(p).map_err(|e| format!("{:?}", e))
+            })
+            .unwrap_err();
+
+        let err = &failure.message;
+        assert!(err.contains("parts edited flags"), "{}", err);
+        assert!(err.contains("restored"), "{}", err);
+        assert_eq!(fs::read(&path).unwrap(), before);
+        let report = failure.report.unwrap();
+        assert!(report.rolled_back);
+        assert_eq!(report.mismatches, vec!["parts edited flags"]);
+    }
+
+    #[test]
+    fn test_checksum_is_recomputed_from_the_reread_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("bank01.work");
+        let mut intended = bank_with_checksum();
+        intended.parts_edited_bitmask = 1;
+        intended.checksum = intended.calculate_checksum().unwrap();
+        // The stored checksum survives but the data it covers does not
+        let failure = write_bank_verified_with(&path, &intended, Vec::new(), true, |p, b| {
+            let mut damaged = b.clone();
+            damaged.part_names[0][0] = b'X';
+            damaged.to_data_file(p).map_err(|e| format!("{:?}", e))
+        })
+        .unwrap_err();
+
+        let report = failure.report.unwrap();
+        assert!(report.mismatches.is_empty());
+        assert_ne!(report.found_checksum, Some(intended.checksum));
+        assert!(!report.passed(), "{}", failure.message);
+    }
+
+    #[test]
+    fn test_unverified_write_skips_the_check() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("bank01.work");
+        let intended = bank_with_checksum();
+        // Would fail verification, but this write didn't ask for it
+        let report =
+            write_bank_verified_with(&path, &intended, part_fields(&[0]), false, |p, _| {
+                bank_with_checksum()
+                    .to_data_file(p)
+                    .map_err(|e| format!("{:?}", e))
+            })
+            .unwrap();
+        assert!(!report.checked);
+        assert!(report.passed());
+    }
+}
@@ -1,6 +1,5 @@
 import { useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import { open } from "@tauri-apps/plugin-dialog";
 import "./App.css";
 
 interface OctatrackProject {
@@ -59,11 +58,7 @@ function App() {
 
   async function browseDirectory() {
     try {
-      const selected = await open({
-        directory: true,
-        multiple: false,
-        title: "Select Octatrack Directory"
-      });
+      const selected = await invoke<string | null>("pick_library_root", { title: "Select Octatrack Directory" });
 
       if (selected) {
         setIsScanning(true);
@@ -3,7 +3,7 @@ import { invoke } from '@tauri-apps/api/core';
 import { PartData, PartsDataResponse } from '../context/ProjectsContext';
 import { TrackBadge } from './TrackBadge';
 import { ALL_MIDI_TRACKS } from './TrackSelector';
-import { WriteStatus, writeStatus } from '../types/writeStatus';
+import { WriteStatus, writeStatus, describeWriteError } from '../types/writeStatus';
 import { RotaryKnob } from './RotaryKnob';
 import './PartsPanel.css';
 
@@ -161,7 +161,7 @@ export default function PartsPanel({
       setTimeout(() => onWriteStatusChange?.(writeStatus.idle()), 2000);
     } catch (err) {
       console.error('Failed to commit part:', err);
-      setError(`Failed to save: ${err}`);
+      setError(`Failed to save: ${describeWriteError(err)}`);
       onWriteStatusChange?.(writeStatus.error(`Failed to save part ${partName}`));
       setTimeout(() => onWriteStatusChange?.(writeStatus.idle()), 3000);
     } finally {
@@ -193,7 +193,7 @@ export default function PartsPanel({
       setTimeout(() => onWriteStatusChange?.(writeStatus.idle()), 2000);
     } catch (err) {
       console.error('Failed to commit all parts:', err);
-      setError(`Failed to save all: ${err}`);
+      setError(`Failed to save all: ${describeWriteError(err)}`);
       onWriteStatusChange?.(writeStatus.error('Failed to save all'));
       setTimeout(() => onWriteStatusChange?.(writeStatus.idle()), 3000);
     } finally {
@@ -236,7 +236,7 @@ export default function PartsPanel({
       setTimeout(() => onWriteStatusChange?.(writeStatus.idle()), 2000);
     } catch (err) {
       console.error('Failed to reload part:', err);
-      setError(`Failed to reload: ${err}`);
+      setError(`Failed to reload: ${describeWriteError(err)}`);
       onWriteStatusChange?.(writeStatus.error(`Failed to reload part ${partName}`));
       setTimeout(() => onWriteStatusChange?.(writeStatus.idle()), 3000);
     } finally {
@@ -323,7 +323,7 @@ export default function PartsPanel({
           setTimeout(() => onWriteStatusChange?.(writeStatus.idle()), 2000);
         }).catch(err => {
           console.error('Failed to auto-save part:', err);
-          onWriteStatusChange?.(writeStatus.error('Auto-save failed'));
+          onWriteStatusChange?.(writeStatus.error(`Auto-save failed: ${describeWriteError(err)}`));
           setTimeout(() => onWriteStatusChange?.(writeStatus.idle()), 3000);
         });
 
@@ -385,7 +385,7 @@ export default function PartsPanel({
       setTimeout(() => onWriteStatusChange?.(writeStatus.idle()), 2000);
     }).catch(err => {
       console.error('Failed to save LFO design:', err);
-      onWriteStatusChange?.(writeStatus.error('Save failed'));
+      onWriteStatusChange?.(writeStatus.error(`Save failed: ${describeWriteError(err)}`));
       setTimeout(() => onWriteStatusChange?.(writeStatus.idle()), 3000);
     });
   }, [projectPath, bankId, partsData, onWriteStatusChange]);
@@ -452,7 +452,7 @@ export default function PartsPanel({
       setTimeout(() => onWriteStatusChange?.(writeStatus.idle()), 2000);
     }).catch(err => {
       console.error('Failed to save part:', err);
-      onWriteStatusChange?.(writeStatus.error('Save failed'));
+      onWriteStatusChange?.(writeStatus.error(`Save failed: ${describeWriteError(err)}`));
       setTimeout(() => onWriteStatusChange?.(writeStatus.idle()), 3000);
     });
   }, [projectPath, bankId, partNames, onWriteStatusChange]);
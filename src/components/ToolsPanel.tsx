@@ -1,6 +1,5 @@
 import { useState, useEffect, useMemo } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import { open } from "@tauri-apps/plugin-dialog";
 import { useProjects } from "../context/ProjectsContext";
 import type { Bank, SampleSlotUsage, SlotUsageEntry } from "../context/ProjectsContext";
 import type { PoolUsageEntry } from "../types/audioFile";
@@ -320,9 +319,7 @@ export function ToolsPanel({ projectPath, projectName, banks, loadedBankIndices,
   // recursively and every project found under it is offered for selection
   async function handleBrowse() {
     try {
-      const selected = await open({
-        directory: true,
-        multiple: false,
+      const selected = await invoke<string | null>("pick_library_root", {
         title: "Select a Folder to Scan for Octatrack Projects",
       });
       if (selected && typeof selected === 'string') {
@@ -101,6 +101,17 @@ interface SampleSlots {
   flex_slots: SampleSlot[];
 }
 
+export interface ReadWarning {
+  file: string;
+  kind: "parse_error" | "io_error" | "format_version";
+  message: string;
+}
+
+export interface ReadResult<T> {
+  data: T;
+  warnings: ReadWarning[];
+}
+
 export interface ProjectMetadata {
   name: string;
   tempo: number;
@@ -235,6 +246,8 @@ interface TrackInfo {
   steps: TrigStep[];
   default_note: number | null;
   assigned_sample_slot: number | null;
+  assigned_slot_type: string | null;
+  assigned_sample_name: string | null;
   slice_count: number | null;
 }
 
@@ -1,7 +1,7 @@
 import { useState, useEffect, useRef } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { listen, UnlistenFn } from "@tauri-apps/api/event";
-import type { CopyProgressEvent, TransferItem } from "../types/transfer";
+import type { QueueJob, TransferItem, TransferQueueSnapshot } from "../types/transfer";
 
 export interface OverwriteModalState {
   isOpen: boolean;
@@ -29,16 +29,24 @@ function baseName(p: string): string {
   return p.split('/').pop() || p.split('\\').pop() || p;
 }
 
-async function getConcurrency(): Promise<number> {
-  try {
-    const resources = await invoke<{ recommended_concurrency: number }>("get_system_resources");
-    return resources.recommended_concurrency;
-  } catch {
-    return 2; // default fallback
-  }
+// What to do with files that already exist at the destination, once the user chose for all of them.
+type ConflictPolicy = "ask" | "overwrite" | "skip";
+
+// One copyFilesToPool call, tracked against the Rust-side transfer queue.
+interface Batch {
+  destinationPath: string;
+  fileSizes?: Map<string, number>;
+  jobIds: Set<string>;           // queue jobs of this batch, replaced ids included
+  settled: Set<string>;          // jobs whose outcome was handled
+  conflicts: QueueJob[];         // refused as "already exists", waiting for a decision
+  requeueing: number;            // overwrites being queued, whose new ids are not known yet
+  policy: ConflictPolicy;
+  finished: boolean;
 }
 
-type CopyOutcome = "ok" | "cancelled" | "conflict" | "failed";
+function isConflict(job: QueueJob): boolean {
+  return job.status === "failed" && !job.overwrite && (job.error ?? "").includes("already exists");
+}
 
 export function useAudioPoolTransfer(options?: { onComplete?: (destinationPath: string) => void }) {
   const onComplete = options?.onComplete;
@@ -47,85 +55,142 @@ export function useAudioPoolTransfer(options?: { onComplete?: (destinationPath:
   const [isTransferQueueOpen, setIsTransferQueueOpen] = useState(false);
   const [overwriteModal, setOverwriteModal] = useState<OverwriteModalState>(INITIAL_OVERWRITE_MODAL);
 
+  const batchRef = useRef<Batch | null>(null);
   // Per-batch collection of successfully-copied destination paths, plus an optional
   // callback fired when the batch settles (used by slot imports to assign afterwards).
   const copiedDestPathsRef = useRef<string[]>([]);
   const onCopiedRef = useRef<((destPaths: string[]) => void) | undefined>(undefined);
+  const onCompleteRef = useRef(onComplete);
+  onCompleteRef.current = onComplete;
+  const overwriteModalOpenRef = useRef(false);
+  overwriteModalOpenRef.current = overwriteModal.isOpen;
 
   // Fire the hook-level refresh + the per-batch onCopied callback once a batch finishes.
-  async function finishBatch(destinationPath: string) {
-    await onComplete?.(destinationPath);
+  async function finishBatch(batch: Batch) {
+    if (batch.finished) return;
+    batch.finished = true;
+    await onCompleteRef.current?.(batch.destinationPath);
     const cb = onCopiedRef.current;
     if (cb) cb([...copiedDestPathsRef.current]);
   }
 
-  // Patch a transfer, but never resurrect one the user already cancelled.
-  const updateTransfer = (id: string, patch: Partial<TransferItem>) =>
-    setTransfers(prev => prev.map(t =>
-      t.id === id && t.status !== "cancelled" ? { ...t, ...patch } : t));
-
-  const markCompleted = (id: string) =>
-    setTransfers(prev => prev.map(t =>
-      t.id === id && t.status !== "cancelled"
-        ? { ...t, status: "completed", bytesTransferred: t.fileSize || 1, progress: 1.0, stage: "complete" }
-        : t));
-
   const markCancelled = (id: string, error?: string) =>
     setTransfers(prev => prev.map(t => (t.id === id ? { ...t, status: "cancelled", error } : t)));
 
-  const markFailed = (id: string, error: string) =>
-    setTransfers(prev => prev.map(t =>
-      t.id === id && t.status !== "cancelled" ? { ...t, status: "failed", error } : t));
+  // Open the modal for the next conflict, apply the chosen policy, or finish the batch.
+  function resolveConflicts(batch: Batch) {
+    if (batch.conflicts.length > 0) {
+      if (batch.policy === "overwrite") {
+        const jobs = batch.conflicts.splice(0);
+        jobs.forEach(job => requeueWithOverwrite(batch, job));
+        return;
+      }
+      if (batch.policy === "skip") {
+        batch.conflicts.splice(0).forEach(job => markCancelled(job.id, 'Skipped (file exists)'));
+      } else {
+        const job = batch.conflicts[0];
+        setOverwriteModal({
+          isOpen: true,
+          fileName: baseName(job.source),
+          sourcePath: job.source,
+          transferId: job.id,
+          pendingFiles: batch.conflicts.map(j => j.source),
+          currentIndex: 0,
+          fileSizes: batch.fileSizes,
+          transferIds: batch.conflicts.map(j => j.id),
+          destinationPath: batch.destinationPath,
+        });
+        return;
+      }
+    }
+    if (batch.requeueing === 0 && batch.settled.size === batch.jobIds.size) {
+      finishBatch(batch);
+    }
+  }
 
-  // Single copy invoke + result handling. Returns the outcome so callers can sequence/queue.
-  async function runCopy(
-    sourcePath: string,
-    transferId: string,
-    destinationPath: string,
-    overwrite: boolean
-  ): Promise<CopyOutcome> {
-    try {
-      const destPath = await invoke<string>("copy_audio_file_with_progress", {
-        sourcePath,
-        destinationDir: destinationPath,
-        transferId,
-        overwrite,
-      });
-      markCompleted(transferId);
-      if (destPath) copiedDestPathsRef.current.push(destPath);
-      return "ok";
-    } catch (error) {
-      const errorStr = String(error);
-      if (errorStr.includes("cancelled")) {
-        markCancelled(transferId);
-        return "cancelled";
+  // Reflect a queue snapshot onto this batch's transfer items.
+  function applySnapshot(snapshot: TransferQueueSnapshot) {
+    const batch = batchRef.current;
+    if (!batch || batch.finished) return;
+    const jobs = snapshot.jobs.filter(j => batch.jobIds.has(j.id));
+
+    for (const job of jobs) {
+      const finished = job.status === "done" || job.status === "failed" || job.status === "cancelled";
+      if (!finished || batch.settled.has(job.id)) continue;
+      batch.settled.add(job.id);
+      if (job.status === "done" && job.dest) {
+        copiedDestPathsRef.current.push(job.dest);
+      } else if (isConflict(job)) {
+        batch.conflicts.push(job);
+      } else if (job.status === "failed") {
+        console.error(`Error copying ${baseName(job.source)}:`, job.error);
       }
-      if (errorStr.includes("already exists") && !overwrite) {
-        return "conflict";
+    }
+
+    const byId = new Map(jobs.map(j => [j.id, j]));
+    setTransfers(prev => prev.map(t => {
+      const job = byId.get(t.id);
+      if (!job || t.status === "cancelled") return t;
+      switch (job.status) {
+        case "queued":
+          return { ...t, status: "pending" };
+        case "running":
+          return {
+            ...t,
+            status: "copying",
+            stage: job.stage ?? undefined,
+            progress: job.progress,
+            bytesTransferred: job.progress * (t.fileSize || 1),
+          };
+        case "done":
+          return { ...t, status: "completed", bytesTransferred: t.fileSize || 1, progress: 1.0, stage: "complete" };
+        case "cancelled":
+          return { ...t, status: "cancelled", error: job.error ?? undefined };
+        default:
+          // Conflicts wait for the overwrite decision
+          return isConflict(job) ? { ...t, status: "pending" } : { ...t, status: "failed", error: job.error ?? undefined };
       }
-      console.error(`Error copying ${baseName(sourcePath)}:`, error);
-      markFailed(transferId, errorStr);
-      return "failed";
+    }));
+
+    // Conflicts found while the modal is open wait for its answer
+    if (!overwriteModalOpenRef.current) resolveConflicts(batch);
+  }
+
+  async function enqueue(sourcePaths: string[], destinationDir: string, overwrite: boolean): Promise<string[]> {
+    const ids = await invoke<string[]>("enqueue_transfers", {
+      sourcePaths,
+      destinationDir,
+      overwrite,
+    });
+    const batch = batchRef.current;
+    ids.forEach(id => batch?.jobIds.add(id));
+    return ids;
+  }
+
+  // Queue a refused file again with overwrite on; its transfer item follows the new job.
+  async function requeueWithOverwrite(batch: Batch, job: QueueJob) {
+    batch.requeueing += 1;
+    try {
+      const [id] = await enqueue([job.source], batch.destinationPath, true);
+      setTransfers(prev => prev.map(t =>
+        t.id === job.id ? { ...t, id, status: "pending", startTime: Date.now() } : t));
+    } catch (error) {
+      setTransfers(prev => prev.map(t =>
+        t.id === job.id ? { ...t, status: "failed", error: String(error) } : t));
+    } finally {
+      batch.requeueing -= 1;
     }
+    // Jobs can finish before the ids come back; catch up from the current queue
+    applySnapshot(await invoke<TransferQueueSnapshot>("get_transfer_queue"));
   }
 
-  // Listen for copy progress events from Rust backend
+  // Listen for queue progress from the Rust backend
   useEffect(() => {
     let unlisten: UnlistenFn | null = null;
     let cancelled = false;
 
-    listen<CopyProgressEvent>("copy-progress", (event) => {
-      const { file_path, transfer_id, stage, progress } = event.payload;
-
-      setTransfers(prev => prev.map(t => {
-        // Match by transfer_id if available, otherwise fall back to sourcePath.
-        // Only update transfers still copying (not cancelled/finished).
-        const matches = transfer_id ? t.id === transfer_id : t.sourcePath === file_path;
-        if (matches && t.status === "copying") {
-          return { ...t, stage, progress, bytesTransferred: progress * (t.fileSize || 1) };
-        }
-        return t;
-      }));
+    listen<TransferQueueSnapshot>("transfer-queue-progress", (event) => {
+      applySnapshot(event.payload);
     }).then(fn => {
       if (cancelled) {
         fn();
@@ -138,6 +203,7 @@ export function useAudioPoolTransfer(options?: { onComplete?: (destinationPath:
       cancelled = true;
       unlisten?.();
     };
+  // eslint-disable-next-line react-hooks/exhaustive-deps
   }, []);
 
   // Auto-close transfers pane when all transfers complete successfully
@@ -154,144 +220,8 @@ export function useAudioPoolTransfer(options?: { onComplete?: (destinationPath:
     }
   }, [transfers]);
 
-  // Process files in parallel with a concurrency limit, until a conflict is detected.
-  async function processFilesInParallel(
-    sourcePaths: string[],
-    transferIds: string[],
-    destinationPath: string,
-    fileSizes?: Map<string, number>,
-    concurrency: number = 2,
-    forceOverwrite: boolean = false
-  ) {
-    let conflictDetected = false;
-    let conflictIndex = -1;
-    const completedIndices = new Set<number>();
-    const activePromises: Map<number, Promise<void>> = new Map();
-    let queueIndex = 0;
-
-    // Process a single file; returns true if it hit an overwrite conflict.
-    const processFile = async (sourcePath: string, index: number): Promise<boolean> => {
-      const transferId = transferIds[index];
-      updateTransfer(transferId, { status: "copying", startTime: Date.now() });
-
-      const outcome = await runCopy(sourcePath, transferId, destinationPath, forceOverwrite);
-      if (outcome === "conflict") {
-        updateTransfer(transferId, { status: "pending" }); // reset for modal handling
-        return true;
-      }
-      // ok / failed are terminal here; cancelled is left for potential sequential retry (preserved behavior)
-      if (outcome === "ok" || outcome === "failed") {
-        completedIndices.add(index);
-      }
-      return false;
-    };
-
-    while (queueIndex < sourcePaths.length && !conflictDetected) {
-      while (queueIndex < sourcePaths.length && activePromises.size < concurrency && !conflictDetected) {
-        const currentIndex = queueIndex++;
-        const sourcePath = sourcePaths[currentIndex];
-
-        const promise = processFile(sourcePath, currentIndex).then((hasConflict) => {
-          activePromises.delete(currentIndex);
-          if (hasConflict && !conflictDetected) {
-            conflictDetected = true;
-            conflictIndex = currentIndex;
-          }
-        });
-        activePromises.set(currentIndex, promise);
-      }
-
-      if (activePromises.size > 0 && !conflictDetected) {
-        await Promise.race(Array.from(activePromises.values()));
-      }
-    }
-
-    // Let in-flight copies settle before handing off to the sequential/modal path.
-    if (activePromises.size > 0) {
-      await Promise.all(Array.from(activePromises.values()));
-    }
-
-    if (conflictDetected && conflictIndex >= 0) {
-      const remainingPaths: string[] = [];
-      const remainingIds: string[] = [];
-      for (let i = conflictIndex; i < sourcePaths.length; i++) {
-        if (!completedIndices.has(i)) {
-          remainingPaths.push(sourcePaths[i]);
-          remainingIds.push(transferIds[i]);
-        }
-      }
-
-      if (remainingPaths.length > 0) {
-        // Sequential processing so the overwrite modal can pause between files.
-        await processCopyQueue(remainingPaths, 0, destinationPath, false, fileSizes, false, remainingIds);
-        return; // processCopyQueue handles onComplete
-      }
-    }
-
-    await finishBatch(destinationPath);
-  }
-
-  // Sequential copy queue with overwrite-modal handling.
-  async function processCopyQueue(
-    sourcePaths: string[],
-    startIndex: number,
-    destinationPath: string,
-    forceOverwrite: boolean = false,
-    fileSizes?: Map<string, number>,
-    forceSkip: boolean = false,
-    transferIds?: string[]
-  ) {
-    for (let i = startIndex; i < sourcePaths.length; i++) {
-      const sourcePath = sourcePaths[i];
-      const fileName = baseName(sourcePath);
-      const transferId = transferIds?.[i] || `${Date.now()}-${fileName}`;
-
-      if (transferIds?.[i]) {
-        updateTransfer(transferId, { status: "copying", startTime: Date.now() });
-      } else {
-        setTransfers(prev => [...prev, {
-          id: transferId,
-          fileName,
-          fileSize: fileSizes?.get(sourcePath) || 0,
-          bytesTransferred: 0,
-          status: "copying",
-          startTime: Date.now(),
-          sourcePath,
-        }]);
-      }
-
-      const outcome = await runCopy(sourcePath, transferId, destinationPath, forceOverwrite);
-
-      if (outcome === "cancelled") {
-        return; // Stop processing the rest of the queue
-      }
-
-      if (outcome === "conflict") {
-        if (forceSkip) {
-          markCancelled(transferId, 'Skipped (file exists)');
-          continue;
-        }
-        // Show modal and pause until the user decides.
-        setOverwriteModal({
-          isOpen: true,
-          fileName,
-          sourcePath,
-          transferId,
-          pendingFiles: sourcePaths,
-          currentIndex: i,
-          fileSizes,
-          transferIds,
-          destinationPath,
-        });
-        return;
-      }
-      // ok / failed: keep going
-    }
-
-    await finishBatch(destinationPath);
-  }
-
-  // Entry point: copy files to the pool, asking about conflicts only when the backend reports them.
+  // Entry point: hand the files to the Rust transfer queue, which copies/converts
+  // them with its own concurrency limit and reports through "transfer-queue-progress".
   async function copyFilesToPool(
     sourcePaths: string[],
     destinationPath: string,
@@ -302,85 +232,120 @@ export function useAudioPoolTransfer(options?: { onComplete?: (destinationPath:
     // Start a fresh batch: reset collected dest paths and the per-batch callback.
     copiedDestPathsRef.current = [];
     onCopiedRef.current = onCopied;
+    const batch: Batch = {
+      destinationPath,
+      fileSizes,
+      jobIds: new Set(),
+      settled: new Set(),
+      conflicts: [],
+      requeueing: 0,
+      policy: "ask",
+      finished: false,
+    };
+    batchRef.current = batch;
 
-    const baseTimestamp = Date.now();
-    const transferIds: string[] = [];
-    const newTransfers: TransferItem[] = sourcePaths.map((sourcePath, index) => {
-      const transferId = `${baseTimestamp}-${index}-${baseName(sourcePath)}`;
-      transferIds.push(transferId);
-      return {
-        id: transferId,
+    let ids: string[];
+    try {
+      ids = await enqueue(sourcePaths, destinationPath, false);
+    } catch (error) {
+      console.error("Failed to queue transfers:", error);
+      const now = Date.now();
+      setTransfers(prev => [...prev, ...sourcePaths.map((sourcePath, index) => ({
+        id: `${now}-${index}-${baseName(sourcePath)}`,
         fileName: baseName(sourcePath),
         fileSize: fileSizes?.get(sourcePath) || 0,
         bytesTransferred: 0,
-        status: "pending",
-        startTime: baseTimestamp,
+        status: "failed" as const,
+        error: String(error),
+        startTime: now,
         sourcePath,
-      };
-    });
-
-    setTransfers(prev => [...prev, ...newTransfers]);
+      }))]);
+      await finishBatch(batch);
+      return;
+    }
 
-    const concurrency = await getConcurrency();
-    await processFilesInParallel(sourcePaths, transferIds, destinationPath, fileSizes, concurrency, false);
+    const startTime = Date.now();
+    setTransfers(prev => [...prev, ...sourcePaths.map((sourcePath, index) => ({
+      id: ids[index],
+      fileName: baseName(sourcePath),
+      fileSize: fileSizes?.get(sourcePath) || 0,
+      bytesTransferred: 0,
+      status: "pending" as const,
+      startTime,
+      sourcePath,
+    }))]);
+    if (ids.length === 0) {
+      await finishBatch(batch);
+      return;
+    }
+    // Jobs can finish before the ids come back; catch up from the current queue
+    applySnapshot(await invoke<TransferQueueSnapshot>("get_transfer_queue"));
   }
 
   // --- Overwrite modal actions ---
 
-  async function handleOverwrite() {
-    const { sourcePath, transferId, pendingFiles, currentIndex, fileSizes, transferIds, destinationPath } = overwriteModal;
+  function closeModal() {
+    overwriteModalOpenRef.current = false;
     setOverwriteModal(prev => ({ ...prev, isOpen: false }));
-
-    updateTransfer(transferId, { status: "copying", startTime: Date.now() });
-
-    // Copy this file in the background so the queue can keep moving.
-    const copyPromise = runCopy(sourcePath, transferId, destinationPath, true);
-
-    await processCopyQueue(pendingFiles, currentIndex + 1, destinationPath, false, fileSizes, false, transferIds);
-    await copyPromise;
   }
 
-  async function handleOverwriteAll() {
-    const { pendingFiles, fileSizes, transferIds, destinationPath } = overwriteModal;
-    setOverwriteModal(prev => ({ ...prev, isOpen: false }));
+  function handleOverwrite() {
+    const batch = batchRef.current;
+    closeModal();
+    if (!batch) return;
+    const job = batch.conflicts.shift();
+    if (job) requeueWithOverwrite(batch, job);
+    resolveConflicts(batch);
+  }
 
-    const concurrency = await getConcurrency();
-    await processFilesInParallel(pendingFiles, transferIds!, destinationPath, fileSizes, concurrency, true);
+  function handleOverwriteAll() {
+    const batch = batchRef.current;
+    closeModal();
+    if (!batch) return;
+    batch.policy = "overwrite";
+    resolveConflicts(batch);
   }
 
   function handleSkip() {
-    const { transferId, pendingFiles, currentIndex, fileSizes, transferIds, destinationPath } = overwriteModal;
-    setOverwriteModal(prev => ({ ...prev, isOpen: false }));
-
-    markCancelled(transferId, 'Skipped (file exists)');
-    processCopyQueue(pendingFiles, currentIndex + 1, destinationPath, false, fileSizes, false, transferIds);
+    const batch = batchRef.current;
+    closeModal();
+    if (!batch) return;
+    const job = batch.conflicts.shift();
+    if (job) markCancelled(job.id, 'Skipped (file exists)');
+    resolveConflicts(batch);
   }
 
-  async function handleSkipAll() {
-    const { transferId, pendingFiles, currentIndex, fileSizes, transferIds, destinationPath } = overwriteModal;
-    setOverwriteModal(prev => ({ ...prev, isOpen: false }));
-
-    markCancelled(transferId, 'Skipped (file exists)');
-    await processCopyQueue(pendingFiles, currentIndex + 1, destinationPath, false, fileSizes, true, transferIds);
+  function handleSkipAll() {
+    const batch = batchRef.current;
+    closeModal();
+    if (!batch) return;
+    batch.policy = "skip";
+    resolveConflicts(batch);
   }
 
   function handleCancelImport() {
-    const { transferId, transferIds, currentIndex, destinationPath } = overwriteModal;
-    setOverwriteModal(prev => ({ ...prev, isOpen: false }));
-
-    // Cancel the current file plus everything still queued behind it.
-    const remainingIds = transferIds ? new Set(transferIds.slice(currentIndex)) : new Set([transferId]);
+    const batch = batchRef.current;
+    closeModal();
+    if (!batch) return;
+
+    // Drop the refused files plus everything still queued or running in this batch.
+    const refused = new Set(batch.conflicts.splice(0).map(j => j.id));
+    for (const id of batch.jobIds) {
+      if (!batch.settled.has(id)) {
+        invoke("cancel_queued_transfer", { id }).catch(e => console.error("Failed to cancel transfer:", e));
+      }
+    }
     setTransfers(prev => prev.map(t =>
-      remainingIds.has(t.id) && (t.status === "pending" || t.status === "copying")
+      (refused.has(t.id) || batch.jobIds.has(t.id)) && (t.status === "pending" || t.status === "copying")
         ? { ...t, status: "cancelled", error: 'Import cancelled' }
         : t));
 
-    finishBatch(destinationPath);
+    finishBatch(batch);
   }
 
   async function cancelTransfer(transferId: string) {
     try {
-      await invoke("cancel_audio_transfer", { transferId });
+      await invoke<boolean>("cancel_queued_transfer", { id: transferId });
     } catch (e) {
       console.error("Failed to cancel transfer:", e);
     }
@@ -38,6 +38,15 @@ function PoolDropZone({ osOver, children }: { osOver: boolean; children: React.R
 }
 
 
+// Payload of the "pool-directory-changed" event (pool_watcher.rs)
+interface PoolDirectoryChangedEvent {
+  directory: string;
+  path: string;
+  name: string;
+  kind: "added" | "removed" | "modified";
+  is_directory: boolean;
+}
+
 // Import dropdown component
 interface ImportDropdownProps {
   onImportFiles: () => void;
@@ -497,6 +506,37 @@ export function AudioPoolPage() {
     }
   }, [destinationPath]);
 
+  // Reload the destination listing when files change behind the app's back
+  // (the device over USB, another tool). The backend reports each file of a
+  // burst separately, so the reloads are coalesced here.
+  useEffect(() => {
+    if (!destinationPath) return;
+    let unlisten: (() => void) | undefined;
+    let cancelled = false;
+    let reload: ReturnType<typeof setTimeout> | undefined;
+
+    invoke("watch_pool_directory", { path: destinationPath })
+      .catch((err) => console.error("Failed to watch the pool directory:", err));
+    listen<PoolDirectoryChangedEvent>("pool-directory-changed", (event) => {
+      if (event.payload.directory !== destinationPath) return;
+      clearTimeout(reload);
+      reload = setTimeout(() => loadDestinationFiles(destinationPath), 100);
+    }).then(fn => {
+      if (cancelled) {
+        fn();
+      } else {
+        unlisten = fn;
+      }
+    }).catch(() => { /* events unavailable */ });
+
+    return () => {
+      cancelled = true;
+      clearTimeout(reload);
+      unlisten?.();
+      invoke("unwatch_pool_directory").catch(() => { /* nothing watched */ });
+    };
+  }, [destinationPath]);
+
   // Load source files when path changes
   useEffect(() => {
     if (sourcePath) {
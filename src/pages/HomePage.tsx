@@ -1,6 +1,5 @@
 import { useState, useTransition, useEffect, useRef } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import { open } from "@tauri-apps/plugin-dialog";
 import { useNavigate } from "react-router-dom";
 import {
   DndContext,
@@ -29,6 +28,7 @@ import type {
   OctatrackProject,
   OctatrackSet,
 } from "../types/projectManagement";
+import type { TransferQueueSnapshot } from "../types/transfer";
 import "../App.css";
 
 // Natural sort comparator: "Project_2" < "Project_10" (not lexicographic)
@@ -128,6 +128,27 @@ export function HomePage() {
   const [contextMenu, setContextMenu] = useState<ContextMenuState | null>(null);
   const [clipboard, setClipboard] = useState<ClipboardState | null>(null);
   const [renamingProject, setRenamingProject] = useState<{ project: OctatrackProject; setPath: string } | null>(null);
+  // Transfers left unfinished by the previous session, waiting for the user's choice
+  const [interruptedTransfers, setInterruptedTransfers] = useState(0);
+
+  useEffect(() => {
+    invoke<TransferQueueSnapshot>("get_transfer_queue")
+      .then((snapshot) => {
+        if (snapshot.awaiting_resume) {
+          setInterruptedTransfers(snapshot.queued);
+        }
+      })
+      .catch((err) => console.error("Failed to read the transfer queue:", err));
+  }, []);
+
+  async function settleInterruptedTransfers(resume: boolean) {
+    setInterruptedTransfers(0);
+    try {
+      await invoke(resume ? "resume_transfer_queue" : "discard_transfer_queue");
+    } catch (err) {
+      console.error("Failed to settle interrupted transfers:", err);
+    }
+  }
   const [activeItem, setActiveItem] = useState<{ type: string; name: string } | null>(null);
   const [toast, setToast] = useState<{ message: string; icon: string; type?: 'warning' } | null>(null);
   const [copyProgress, setCopyProgress] = useState<{ transferId: string; label: string; command: string; commandArgs: Record<string, unknown>; setPath?: string; locationPath?: string; sourceSetPath?: string; isMove?: boolean } | null>(null);
@@ -324,11 +345,7 @@ export function HomePage() {
 
   async function browseDirectory() {
     try {
-      const selected = await open({
-        directory: true,
-        multiple: false,
-        title: "Select Octatrack Directory"
-      });
+      const selected = await invoke<string | null>("pick_library_root", { title: "Select Octatrack Directory" });
 
       if (selected) {
         setIsScanning(true);
@@ -1120,6 +1137,33 @@ export function HomePage() {
         />
       )}
 
+      {interruptedTransfers > 0 && (
+        <div className="modal-overlay">
+          <div className="modal-content warning-modal" onClick={(e) => e.stopPropagation()}>
+            <div className="modal-header">
+              <h3><i className="fas fa-file-audio"></i> Unfinished transfers</h3>
+            </div>
+            <div className="modal-body">
+              <p>
+                {interruptedTransfers} file{interruptedTransfers === 1 ? " was" : "s were"} still waiting to be
+                copied when Octatrack Manager last closed. Files that were being written have been removed
+                and will be copied again from the start.
+              </p>
+            </div>
+            <div className="modal-footer">
+              <div className="modal-buttons-row">
+                <button className="modal-button" onClick={() => settleInterruptedTransfers(false)}>
+                  Discard
+                </button>
+                <button className="modal-button" onClick={() => settleInterruptedTransfers(true)}>
+                  Resume
+                </button>
+              </div>
+            </div>
+          </div>
+        </div>
+      )}
+
       <ScrollToTop />
 
       {copyProgress && (
@@ -1,7 +1,8 @@
 import { useState, useEffect, useTransition, useCallback, useMemo, useRef, type ReactNode } from "react";
 import { useNavigate, useSearchParams } from "react-router-dom";
 import { invoke } from "@tauri-apps/api/core";
-import type { ProjectMetadata, Bank, PartsDataResponse, SampleSlotUsage } from "../context/ProjectsContext";
+import { listen } from "@tauri-apps/api/event";
+import type { ProjectMetadata, Bank, PartsDataResponse, ReadResult, SampleSlotUsage } from "../context/ProjectsContext";
 import { BankSelector, ALL_BANKS, formatBankName } from "../components/BankSelector";
 import { TrackSelector, ALL_AUDIO_TRACKS, ALL_MIDI_TRACKS } from "../components/TrackSelector";
 import { PatternSelector, ALL_PATTERNS } from "../components/PatternSelector";
@@ -91,6 +92,26 @@ interface TrigStep {
 
 // TrackInfo, Pattern, Part, and Bank interfaces are imported from ProjectsContext via Bank type
 
+interface ProjectLockHolder {
+  pid: number;
+  host: string;
+  created_at: string;
+}
+
+interface ProjectLockStatus {
+  acquired: boolean;
+  held_by: ProjectLockHolder | null; // the other holder when not acquired
+}
+
+// Payload of the "project-changed" event (project_watcher.rs)
+interface ProjectChangedEvent {
+  project_path: string;
+  file: string;
+  kind: "project" | "bank" | "markers" | "arrangement";
+  bank: number | null;
+  bank_id: string | null;
+}
+
 type TabType = "overview" | "parts" | "patterns" | "tracks" | "static-slots" | "flex-slots" | "tools";
 
 // Helper function to calculate the display denominator for length fraction
@@ -373,6 +394,8 @@ export function ProjectDetail() {
   const [isEditMode, setIsEditMode] = useState<boolean>(false); // Global edit mode toggle
   const [showBankWarning, setShowBankWarning] = useState<boolean>(false); // Show failed banks warning
   const [showBankWarningModal, setShowBankWarningModal] = useState<boolean>(false); // Show modal with details
+  const [lockHolder, setLockHolder] = useState<ProjectLockHolder | null>(null); // Other instance holding the project lock
+  const [showLockModal, setShowLockModal] = useState<boolean>(false); // Show lock details and takeover
   const [isTitleTruncated, setIsTitleTruncated] = useState<boolean>(false); // Track if project title is truncated
   const titleRef = useRef<HTMLHeadingElement>(null); // Ref for project title h1
   const [audioTrackMachineTypes, setAudioTrackMachineTypes] = useState<Record<number, string>>({}); // Track index (0-7) -> machine type
@@ -571,7 +594,8 @@ export function ProjectDetail() {
     if (!projectPath) return;
     try {
       // Reload metadata
-      const projectMetadata = await invoke<ProjectMetadata>("load_project_metadata", { path: projectPath });
+      const { data: projectMetadata, warnings } = await invoke<ReadResult<ProjectMetadata>>("load_project_metadata", { path: projectPath });
+      warnings.forEach((w) => console.warn(`${w.file}: ${w.message}`));
       setMetadata(projectMetadata);
       setUsageRefreshKey((k) => k + 1); // recompute usage in the background
 
@@ -624,6 +648,84 @@ export function ProjectDetail() {
     // eslint-disable-next-line react-hooks/exhaustive-deps
   }, [projectPath]);
 
+  // Hold the project lock while the project is open; the backend refuses
+  // edits while another instance holds it.
+  useEffect(() => {
+    if (!projectPath) return;
+    setLockHolder(null);
+    invoke<ProjectLockStatus>("acquire_project_lock", { path: projectPath })
+      .then((status) => {
+        if (status && !status.acquired && status.held_by) {
+          setLockHolder(status.held_by);
+          setShowLockModal(true);
+        }
+      })
+      .catch((err) => console.error("Failed to lock project:", err));
+    return () => {
+      invoke("release_project_lock", { path: projectPath }).catch(() => {});
+    };
+  }, [projectPath]);
+
+  // Reload what changed when project files are rewritten behind the app's
+  // back (the device saving over USB, another tool). Events of one burst are
+  // gathered so each bank is reloaded once.
+  useEffect(() => {
+    if (!projectPath) return;
+    let unlisten: (() => void) | undefined;
+    let cancelled = false;
+    let pending: ReturnType<typeof setTimeout> | undefined;
+    const banks = new Set<number>();
+    let projectChanged = false;
+
+    invoke("watch_project", { path: projectPath })
+      .catch((err) => console.error("Failed to watch project:", err));
+    listen<ProjectChangedEvent>("project-changed", (event) => {
+      if (event.payload.project_path !== projectPath) return;
+      if (event.payload.kind === "bank" && event.payload.bank !== null) {
+        banks.add(event.payload.bank);
+      } else {
+        projectChanged = true;
+      }
+      clearTimeout(pending);
+      pending = setTimeout(() => {
+        if (projectChanged) {
+          refreshProjectData();
+        } else {
+          banks.forEach((bank) => reloadBank(bank));
+        }
+        banks.clear();
+        projectChanged = false;
+      }, 100);
+    }).then((fn) => {
+      if (cancelled) {
+        fn();
+      } else {
+        unlisten = fn;
+      }
+    }).catch(() => { /* events unavailable */ });
+
+    return () => {
+      cancelled = true;
+      clearTimeout(pending);
+      unlisten?.();
+      invoke("unwatch_project").catch(() => {});
+    };
+  }, [projectPath, refreshProjectData, reloadBank]);
+
+  // Take the lock over from the other instance (its edits may then conflict)
+  const takeOverLock = useCallback(async () => {
+    if (!projectPath) return;
+    try {
+      const status = await invoke<ProjectLockStatus>("acquire_project_lock", { path: projectPath, force: true });
+      if (status.acquired) {
+        setLockHolder(null);
+        setShowLockModal(false);
+      }
+    } catch (err) {
+      setToast(`Failed to take over the project lock: ${err}`);
+    }
+  }, [projectPath]);
+
   // Show bank warning when failed banks are detected, auto-hide after 90 seconds
   useEffect(() => {
     if (failedBankIndices.size > 0) {
@@ -743,7 +845,8 @@ export function ProjectDetail() {
     try {
       // Step 1: Load metadata first (fast) - this enables Overview tab immediately
       setLoadingStatus("Reading project metadata...");
-      const projectMetadata = await invoke<ProjectMetadata>("load_project_metadata", { path: projectPath });
+      const { data: projectMetadata, warnings } = await invoke<ReadResult<ProjectMetadata>>("load_project_metadata", { path: projectPath });
+      warnings.forEach((w) => console.warn(`${w.file}: ${w.message}`));
 
       setMetadata(projectMetadata);
       const activeBankIndex = projectMetadata.current_state.bank;
@@ -947,6 +1050,15 @@ export function ProjectDetail() {
               {partsWriteStatus.state === 'idle' && lastStatusMessage}
             </span>
             {/* Failed banks warning indicator - only shown when save status is idle */}
+            {lockHolder && (
+              <span
+                className="bank-warning-indicator"
+                onClick={() => setShowLockModal(true)}
+                title="Click for details"
+              >
+                <i className="fas fa-lock"></i> Open in another instance
+              </span>
+            )}
             {showBankWarning && failedBankIndices.size > 0 && partsWriteStatus.state === 'idle' && (
               <span
                 className="bank-warning-indicator"
@@ -1052,6 +1164,38 @@ export function ProjectDetail() {
         </div>
       )}
 
+      {showLockModal && lockHolder && (
+        <div className="modal-overlay" onClick={() => setShowLockModal(false)}>
+          <div className="modal-content warning-modal" onClick={(e) => e.stopPropagation()}>
+            <div className="modal-header">
+              <h3><i className="fas fa-lock"></i> Project is open in another instance</h3>
+              <button className="modal-close" onClick={() => setShowLockModal(false)}>×</button>
+            </div>
+            <div className="modal-body">
+              <p>
+                This project is being edited by another Octatrack Manager (PID {lockHolder.pid} on{' '}
+                <strong>{lockHolder.host || "an unknown host"}</strong>, since {lockHolder.created_at}).
+                Edits are refused here until it is closed there.
+              </p>
+              <p>
+                If that instance is gone (for example, the card was moved to another computer), take over
+                the lock to edit here. Edits made in both places at once will overwrite each other.
+              </p>
+            </div>
+            <div className="modal-footer">
+              <div className="modal-buttons-row">
+                <button className="modal-button" onClick={() => setShowLockModal(false)}>
+                  View only
+                </button>
+                <button className="modal-button danger" onClick={takeOverLock}>
+                  Take over
+                </button>
+              </div>
+            </div>
+          </div>
+        </div>
+      )}
+
       {!isLoading && !error && metadata && (
         <div className="project-content">
           <div className="tab-content">
@@ -19,3 +19,28 @@ export interface TransferItem {
   stage?: string;  // "converting", "resampling", "writing", "copying", "complete"
   progress?: number;  // 0.0 to 1.0
 }
+
+// One file of the Rust-side transfer queue (transfer_queue.rs)
+export interface QueueJob {
+  id: string;
+  source: string;
+  dest_dir: string;
+  overwrite: boolean;
+  status: "queued" | "running" | "done" | "failed" | "cancelled";
+  stage: string | null;
+  progress: number;  // 0.0 to 1.0
+  dest: string | null;
+  error: string | null;
+}
+
+// Payload of the "transfer-queue-progress" event
+export interface TransferQueueSnapshot {
+  jobs: QueueJob[];
+  paused: boolean;
+  awaiting_resume: boolean;  // restored at launch, held until resumed or discarded
+  concurrency: number;
+  queued: number;
+  running: number;
+  finished: number;
+  progress: number;
+}
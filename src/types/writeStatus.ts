@@ -17,3 +17,32 @@ export const writeStatus = {
   success: (message?: string): WriteStatus => ({ state: 'success', message }),
   error: (message?: string): WriteStatus => ({ state: 'error', message }),
 };
+
+// Result of re-reading a bank file after a write (mirrors write_verify::VerificationReport)
+export interface VerificationReport {
+  file: string;
+  checked: boolean;
+  expected_checksum: number;
+  found_checksum: number | null;
+  fields: string[];
+  mismatches: string[];
+  rolled_back: boolean;
+}
+
+// Error returned by verified writes; report is null when the write failed before the check
+export interface VerificationFailure {
+  message: string;
+  report: VerificationReport | null;
+}
+
+// Turn an error from a verified write into text, listing the fields that didn't read back
+export function describeWriteError(err: unknown): string {
+  const failure = err as Partial<VerificationFailure> | null;
+  if (failure && typeof failure === 'object' && typeof failure.message === 'string') {
+    const mismatches = failure.report?.mismatches ?? [];
+    return mismatches.length > 0
+      ? `${failure.message} (mismatched: ${mismatches.join(', ')})`
+      : failure.message;
+  }
+  return String(err);
+}